@@ -1,10 +1,14 @@
+use std::future::Future;
 use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
 
 use anyhow::Result;
 use futures_util::StreamExt;
+use rustyline::error::ReadlineError;
 use structopt::StructOpt;
-use tonic::Request;
-use yql_dataset::dataset::DataSet;
+use tonic::{Code, Request, Status};
+use yql_dataset::dataset::{DataFormat, DataSet, TimestampFormat};
 use yql_protocol::{execute_response, ExecuteRequest};
 
 #[derive(Debug, StructOpt)]
@@ -13,16 +17,340 @@ struct Options {
     #[structopt(default_value = "http://localhost:33001")]
     /// YQL Server url
     url: String,
+
+    #[structopt(long, default_value = "table")]
+    /// Output format, one of 'table', 'csv' and 'json'
+    format: OutputFormat,
+
+    #[structopt(long, conflicts_with = "timing")]
+    /// Don't print the metrics of the queries
+    no_metrics: bool,
+
+    #[structopt(long)]
+    /// Print the metrics of the queries, the default for the 'table' format
+    timing: bool,
+}
+
+impl Options {
+    fn show_metrics(&self) -> bool {
+        if self.no_metrics {
+            false
+        } else {
+            self.timing || self.format == OutputFormat::Table
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum OutputFormat {
+    Table,
+    Data(DataFormat),
+}
+
+impl FromStr for OutputFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "table" => Ok(OutputFormat::Table),
+            "csv" => Ok(OutputFormat::Data(DataFormat::Csv {
+                delimiter: b',',
+                header: true,
+            })),
+            "json" => Ok(OutputFormat::Data(DataFormat::Json)),
+            _ => anyhow::bail!("unknown output format '{}'", s),
+        }
+    }
+}
+
+/// The delay before the second attempt to connect, doubled after each failed attempt.
+const RECONNECT_BACKOFF: Duration = Duration::from_millis(200);
+const MAX_RECONNECT_BACKOFF: Duration = Duration::from_secs(5);
+const MAX_CONNECT_ATTEMPTS: usize = 6;
+
+/// Returns `true` if the request failed because the connection to the server is lost.
+fn is_connection_error(status: &Status) -> bool {
+    match status.code() {
+        Code::Unavailable => true,
+        Code::Unknown => status.message().starts_with("transport error"),
+        _ => false,
+    }
+}
+
+/// A client that is connected when it is needed, and connected again after the connection is
+/// lost, e.g. because the server restarted.
+struct Reconnect<T, F> {
+    connect: F,
+    client: Option<T>,
+    backoff: Duration,
+    max_attempts: usize,
+}
+
+impl<T, F, Fut> Reconnect<T, F>
+where
+    T: Clone,
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    fn new(connect: F) -> Self {
+        Self {
+            connect,
+            client: None,
+            backoff: RECONNECT_BACKOFF,
+            max_attempts: MAX_CONNECT_ATTEMPTS,
+        }
+    }
+
+    /// Returns the connected client, or connects with backoff if there is none.
+    async fn client(&mut self) -> Result<T> {
+        if let Some(client) = &self.client {
+            return Ok(client.clone());
+        }
+
+        let mut backoff = self.backoff;
+        let mut attempt = 1;
+        loop {
+            match (self.connect)().await {
+                Ok(client) => {
+                    self.client = Some(client.clone());
+                    return Ok(client);
+                }
+                Err(err) if attempt >= self.max_attempts => return Err(err),
+                Err(_) => {
+                    tokio::time::sleep(backoff).await;
+                    backoff = (backoff * 2).min(MAX_RECONNECT_BACKOFF);
+                    attempt += 1;
+                }
+            }
+        }
+    }
+
+    /// Forgets the client, the next request connects again.
+    fn reset(&mut self) {
+        self.client = None;
+    }
+
+    /// Sends a request with the client. If it failed because the connection was lost, the client
+    /// is forgotten so that the next request connects again.
+    ///
+    /// The request is not sent again, since the server may have executed it before the connection
+    /// was lost, e.g. an `INSERT INTO` or a `CREATE`.
+    async fn call<R, G, GFut>(&mut self, f: G) -> Result<R>
+    where
+        G: FnOnce(T) -> GFut,
+        GFut: Future<Output = Result<R, Status>>,
+    {
+        let res = f(self.client().await?).await;
+        if let Err(status) = &res {
+            if is_connection_error(status) {
+                self.reset();
+            }
+        }
+        Ok(res?)
+    }
+}
+
+/// Accumulates the lines of the input until the statements are complete, i.e. the last line ends
+/// with a semicolon or is blank. The `--` comments are removed, the server doesn't parse them.
+#[derive(Default)]
+struct InputBuffer {
+    sql: String,
+    /// The quote of the string that is still open at the end of the last line.
+    quote: Option<char>,
+}
+
+impl InputBuffer {
+    fn is_empty(&self) -> bool {
+        self.sql.is_empty()
+    }
+
+    fn clear(&mut self) {
+        self.sql.clear();
+        self.quote = None;
+    }
+
+    /// Appends a line, and returns the statements if they are complete.
+    fn push(&mut self, line: &str) -> Option<String> {
+        let in_string = self.quote.is_some();
+        if !in_string && line.trim().is_empty() {
+            return if self.is_empty() {
+                None
+            } else {
+                Some(self.take())
+            };
+        }
+
+        let mut line = self.strip_comment(line);
+        if self.quote.is_none() {
+            line = line.trim_end();
+        }
+        if in_string || self.quote.is_some() || !line.trim().is_empty() {
+            if !self.sql.is_empty() {
+                self.sql.push('\n');
+            }
+            self.sql.push_str(line);
+        }
+
+        if self.quote.is_none() && self.sql.trim_end().ends_with(';') {
+            Some(self.take())
+        } else {
+            None
+        }
+    }
+
+    fn take(&mut self) -> String {
+        let sql = self.sql.trim().to_string();
+        self.clear();
+        sql
+    }
+
+    /// Returns the line without the comment, and updates the quote of the open string.
+    fn strip_comment<'a>(&mut self, line: &'a str) -> &'a str {
+        let mut chars = line.char_indices().peekable();
+        while let Some((idx, c)) = chars.next() {
+            match self.quote {
+                Some(quote) => {
+                    if c == '\\' {
+                        chars.next();
+                    } else if c == quote {
+                        self.quote = None;
+                    }
+                }
+                None => match c {
+                    '\'' | '"' => self.quote = Some(c),
+                    '-' if matches!(chars.peek(), Some((_, '-'))) => return &line[..idx],
+                    _ => {}
+                },
+            }
+        }
+        line
+    }
+}
+
+/// A backslash command of the REPL, like the ones of psql.
+#[derive(Debug, PartialEq)]
+enum MetaCommand {
+    /// Executes the statements one by one.
+    Execute(Vec<String>),
+    /// Turns the metrics of the queries on or off.
+    ToggleTiming,
+}
+
+impl MetaCommand {
+    /// Parses a command without the leading backslash.
+    fn parse(command: &str) -> Result<Self> {
+        let mut args = command.split_whitespace();
+        let name = args.next().unwrap_or_default();
+        let arg = args.next();
+        anyhow::ensure!(args.next().is_none(), "too many arguments for '\\{}'", name);
+
+        match (name, arg) {
+            ("d", None) => Ok(MetaCommand::Execute(vec![
+                "show sources".to_string(),
+                "show streams".to_string(),
+                "show sinks".to_string(),
+            ])),
+            ("d", Some(name)) => Ok(MetaCommand::Execute(vec![format!("describe {}", name)])),
+            ("df", None) => Ok(MetaCommand::Execute(vec!["show functions".to_string()])),
+            ("timing", None) => Ok(MetaCommand::ToggleTiming),
+            _ => anyhow::bail!("invalid command '\\{}'", command.trim()),
+        }
+    }
 }
 
 fn history_path() -> Option<PathBuf> {
     dirs::document_dir().map(|path| path.join(".yql-cli-history"))
 }
 
+/// Renders the datasets of a response, only the first of them with the header.
+struct ResultDisplay {
+    format: OutputFormat,
+    show_metrics: bool,
+    num_datasets: usize,
+    num_rows: usize,
+    finished: bool,
+}
+
+impl ResultDisplay {
+    fn new(options: &Options) -> Self {
+        Self {
+            format: options.format,
+            show_metrics: options.show_metrics(),
+            num_datasets: 0,
+            num_rows: 0,
+            finished: false,
+        }
+    }
+
+    /// Returns the lines to print for the dataset, or `None` if there are none.
+    fn dataset(&mut self, dataset: &DataSet) -> Result<Option<String>> {
+        let first = self.num_datasets == 0;
+        self.num_datasets += 1;
+        self.num_rows += dataset.len();
+        match self.format {
+            OutputFormat::Table if first => Ok(Some(dataset.display().to_string())),
+            OutputFormat::Table => Ok(Some(dataset.display_no_header().to_string())),
+            OutputFormat::Data(format) => {
+                let mut data = Vec::new();
+                if first {
+                    data.extend(format.serialize_header(&dataset.schema())?);
+                }
+                data.extend(format.serialize_lines(dataset, TimestampFormat::Rfc3339)?);
+                // only the line break of the last line is removed, the values may end with spaces
+                let data = String::from_utf8_lossy(&data);
+                let data = data.trim_end_matches('\n');
+                if data.is_empty() {
+                    Ok(None)
+                } else {
+                    Ok(Some(data.to_string()))
+                }
+            }
+        }
+    }
+
+    /// Returns the footer of an empty table, so that it doesn't look like nothing happened.
+    fn finish(&mut self) -> Option<&'static str> {
+        if self.finished {
+            return None;
+        }
+        self.finished = true;
+        if self.format == OutputFormat::Table && self.num_datasets > 0 && self.num_rows == 0 {
+            Some("(0 rows)")
+        } else {
+            None
+        }
+    }
+
+    fn metrics(&self, metrics: &execute_response::Metrics) -> Option<String> {
+        if !self.show_metrics {
+            return None;
+        }
+        if metrics.running {
+            return Some(format!(
+                "Running, input {} rows, output {} rows, {:.3} seconds elapsed.",
+                metrics.num_input_rows,
+                metrics.num_output_rows,
+                (metrics.end_time - metrics.start_time) as f64 / 1000.0
+            ));
+        }
+        Some(format!(
+            "Input {} rows, output {} rows, done in {:.3} seconds.",
+            metrics.num_input_rows,
+            metrics.num_output_rows,
+            (metrics.end_time - metrics.start_time) as f64 / 1000.0
+        ))
+    }
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
-    let options: Options = Options::from_args();
-    let mut client = yql_protocol::yql_client::YqlClient::connect(options.url).await?;
+    let mut options: Options = Options::from_args();
+    let url = options.url.clone();
+    let mut connection = Reconnect::new(move || {
+        let url = url.clone();
+        async move { Ok(yql_protocol::yql_client::YqlClient::connect(url).await?) }
+    });
+    connection.client().await?;
 
     let mut rl = rustyline::Editor::<()>::new();
 
@@ -30,84 +358,124 @@ async fn main() -> Result<()> {
         let _ = rl.history_mut().load(&path);
     }
 
+    let mut input = InputBuffer::default();
+
     loop {
-        let readline = rl.readline(">> ");
+        let readline = rl.readline(if input.is_empty() { ">> " } else { ".. " });
         match readline {
             Ok(line) => {
-                let line = line.trim();
-                if line.is_empty() {
-                    continue;
-                }
-                rl.history_mut().add(line);
-
-                match client
-                    .execute(Request::new(ExecuteRequest {
-                        sql: line.to_string(),
-                    }))
-                    .await
-                {
-                    Ok(resp) => {
-                        let stream = resp.into_inner().take_until(tokio::signal::ctrl_c());
-                        tokio::pin!(stream);
-
-                        let mut first = true;
-
-                        while let Some(res) = stream.next().await {
-                            let resp = match res {
-                                Ok(resp) => resp,
-                                Err(err) => {
-                                    println!("Error: {}", err);
-                                    break;
-                                }
-                            };
+                let statements = match line.trim().strip_prefix('\\') {
+                    Some(command) if input.is_empty() => {
+                        rl.history_mut().add(line.trim());
+                        match MetaCommand::parse(command) {
+                            Ok(MetaCommand::Execute(statements)) => statements,
+                            Ok(MetaCommand::ToggleTiming) => {
+                                let timing = !options.show_metrics();
+                                options.timing = timing;
+                                options.no_metrics = !timing;
+                                println!("Timing is {}.", if timing { "on" } else { "off" });
+                                continue;
+                            }
+                            Err(err) => {
+                                println!("Error: {}", err);
+                                continue;
+                            }
+                        }
+                    }
+                    _ => match input.push(&line) {
+                        Some(sql) => {
+                            // the lines of a statement are kept as one entry of the history
+                            let entry = sql.lines().map(str::trim).collect::<Vec<_>>().join(" ");
+                            rl.history_mut().add(entry);
+                            vec![sql]
+                        }
+                        None => continue,
+                    },
+                };
+
+                // the statements of a command are executed one by one, each with its own header
+                for sql in statements {
+                    match connection
+                        .call(|mut client| {
+                            let request = Request::new(ExecuteRequest { sql: sql.clone() });
+                            async move { client.execute(request).await }
+                        })
+                        .await
+                    {
+                        Ok(resp) => {
+                            let stream = resp.into_inner().take_until(tokio::signal::ctrl_c());
+                            tokio::pin!(stream);
+
+                            let mut display = ResultDisplay::new(&options);
 
-                            match resp.item {
-                                Some(execute_response::Item::Dataset(dataset)) => {
-                                    let dataset: DataSet =
-                                        match bincode::deserialize(&dataset.dataset) {
-                                            Ok(dataset) => dataset,
+                            while let Some(res) = stream.next().await {
+                                let resp = match res {
+                                    Ok(resp) => resp,
+                                    Err(err) => {
+                                        if is_connection_error(&err) {
+                                            connection.reset();
+                                        }
+                                        println!("Error: {}", err);
+                                        break;
+                                    }
+                                };
+
+                                match resp.item {
+                                    Some(execute_response::Item::Dataset(dataset)) => {
+                                        let dataset: DataSet =
+                                            match bincode::deserialize(&dataset.dataset) {
+                                                Ok(dataset) => dataset,
+                                                Err(err) => {
+                                                    println!("Error: {}", err);
+                                                    break;
+                                                }
+                                            };
+                                        match display.dataset(&dataset) {
+                                            Ok(Some(output)) => println!("{}", output),
+                                            Ok(None) => {}
                                             Err(err) => {
                                                 println!("Error: {}", err);
                                                 break;
                                             }
-                                        };
-                                    if first {
-                                        println!("{}", dataset.display());
-                                        first = false;
-                                    } else {
-                                        println!("{}", dataset.display_no_header());
+                                        }
                                     }
+                                    Some(execute_response::Item::Metrics(metrics))
+                                        if metrics.running =>
+                                    {
+                                        // the progress goes to stderr, so it doesn't mix with the data
+                                        if let Some(progress) = display.metrics(&metrics) {
+                                            eprintln!("{}", progress);
+                                        }
+                                    }
+                                    Some(execute_response::Item::Metrics(metrics)) => {
+                                        if let Some(footer) = display.finish() {
+                                            println!("{}", footer);
+                                        }
+                                        if let Some(metrics) = display.metrics(&metrics) {
+                                            println!("{}", metrics);
+                                        }
+                                    }
+                                    Some(execute_response::Item::Error(
+                                        execute_response::Error { error },
+                                    )) => {
+                                        println!("Error: {}", error);
+                                        break;
+                                    }
+                                    None => break,
                                 }
-                                Some(execute_response::Item::Metrics(
-                                    execute_response::Metrics {
-                                        start_time,
-                                        end_time,
-                                        num_input_rows,
-                                        num_output_rows,
-                                    },
-                                )) => {
-                                    println!(
-                                        "Input {} rows, output {} rows, done in {:.3} seconds.",
-                                        num_input_rows,
-                                        num_output_rows,
-                                        (end_time - start_time) as f64 / 1000.0
-                                    )
-                                }
-                                Some(execute_response::Item::Error(execute_response::Error {
-                                    error,
-                                })) => {
-                                    println!("Error: {}", error);
-                                    break;
-                                }
-                                None => break,
+                            }
+
+                            if let Some(footer) = display.finish() {
+                                println!("{}", footer);
                             }
                         }
-                    }
-                    Err(err) => {
-                        println!("Error: {}", err);
+                        Err(err) => {
+                            println!("Error: {}", err);
+                        }
                     }
                 }
             }
+            Err(ReadlineError::Interrupted) if !input.is_empty() => input.clear(),
             Err(_) => break,
         }
     }
@@ -118,3 +486,266 @@ async fn main() -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use yql_dataset::array::DataType;
+    use yql_dataset::dataset::{Field, Schema};
+
+    use super::*;
+
+    fn options(args: &[&str]) -> Options {
+        Options::from_iter_safe(std::iter::once("yql-cli").chain(args.iter().copied())).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_reconnect() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        // the server restarts after the first connection, and the first attempt to connect
+        // again fails
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let mut connection = Reconnect::new({
+            let attempts = attempts.clone();
+            move || {
+                let attempt = attempts.fetch_add(1, Ordering::SeqCst);
+                async move {
+                    match attempt {
+                        1 => anyhow::bail!("connection refused"),
+                        _ => Ok(attempt),
+                    }
+                }
+            }
+        });
+        connection.backoff = Duration::from_millis(1);
+
+        let execute = |connection: usize| async move {
+            if connection == 0 {
+                Err(Status::unavailable("connection reset"))
+            } else {
+                Ok(connection)
+            }
+        };
+
+        // the error is reported without sending the request again
+        assert!(connection.call(execute).await.is_err());
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+
+        // the next request connects again
+        assert_eq!(connection.call(execute).await.unwrap(), 2);
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+
+        // the next request reuses the connection
+        assert_eq!(connection.call(execute).await.unwrap(), 2);
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+
+        // other errors are not retried
+        assert!(connection
+            .call(|_| async { Err::<(), _>(Status::invalid_argument("bad sql")) })
+            .await
+            .is_err());
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_reconnect_gives_up() {
+        let mut connection =
+            Reconnect::new(|| async { Err::<usize, _>(anyhow::anyhow!("connection refused")) });
+        connection.backoff = Duration::from_millis(1);
+        connection.max_attempts = 3;
+        assert_eq!(
+            connection.client().await.unwrap_err().to_string(),
+            "connection refused"
+        );
+    }
+
+    #[test]
+    fn test_input_buffer() {
+        let mut input = InputBuffer::default();
+        assert_eq!(input.push(""), None);
+        assert_eq!(input.push("create stream s with -- the stream"), None);
+        assert_eq!(input.push("-- select a from t"), None);
+        assert_eq!(input.push("  select a, '--' as b"), None);
+        assert!(!input.is_empty());
+        assert_eq!(
+            input.push("  from t to k; -- done"),
+            Some("create stream s with\n  select a, '--' as b\n  from t to k;".to_string())
+        );
+        assert!(input.is_empty());
+
+        // a blank line completes the statements
+        assert_eq!(input.push("select a from t"), None);
+        assert_eq!(input.push("  "), Some("select a from t".to_string()));
+
+        // the semicolons and blank lines in a string don't complete the statements
+        assert_eq!(input.push("select 'a;"), None);
+        assert_eq!(input.push(""), None);
+        assert_eq!(input.push("\\' -- b;"), None);
+        assert_eq!(
+            input.push("' from t;"),
+            Some("select 'a;\n\n\\' -- b;\n' from t;".to_string())
+        );
+
+        assert_eq!(input.push("-- only a comment"), None);
+        assert!(input.is_empty());
+        assert_eq!(
+            input.push("select 1; select 2;"),
+            Some("select 1; select 2;".to_string())
+        );
+    }
+
+    #[test]
+    fn test_meta_commands() {
+        assert_eq!(
+            MetaCommand::parse("d").unwrap(),
+            MetaCommand::Execute(vec![
+                "show sources".to_string(),
+                "show streams".to_string(),
+                "show sinks".to_string(),
+            ])
+        );
+        assert_eq!(
+            MetaCommand::parse("d  btc_1m ").unwrap(),
+            MetaCommand::Execute(vec!["describe btc_1m".to_string()])
+        );
+        assert_eq!(
+            MetaCommand::parse("df").unwrap(),
+            MetaCommand::Execute(vec!["show functions".to_string()])
+        );
+        assert_eq!(
+            MetaCommand::parse("timing").unwrap(),
+            MetaCommand::ToggleTiming
+        );
+        assert!(MetaCommand::parse("x").is_err());
+        assert!(MetaCommand::parse("timing on").is_err());
+        assert!(MetaCommand::parse("d a b").is_err());
+    }
+
+    #[test]
+    fn test_metrics_flags() {
+        let metrics = execute_response::Metrics {
+            start_time: 1000,
+            end_time: 3500,
+            num_input_rows: 10,
+            num_output_rows: 2,
+            running: false,
+        };
+
+        for (args, show_metrics) in [
+            (&[][..], true),
+            (&["--no-metrics"][..], false),
+            (&["--timing"][..], true),
+            (&["--format", "csv"][..], false),
+            (&["--format", "json"][..], false),
+            (&["--format", "json", "--timing"][..], true),
+            (&["--format", "table", "--no-metrics"][..], false),
+        ] {
+            let display = ResultDisplay::new(&options(args));
+            assert_eq!(
+                display.metrics(&metrics),
+                if show_metrics {
+                    Some("Input 10 rows, output 2 rows, done in 2.500 seconds.".to_string())
+                } else {
+                    None
+                },
+                "{:?}",
+                args
+            );
+        }
+
+        let progress = execute_response::Metrics {
+            running: true,
+            ..metrics
+        };
+        assert_eq!(
+            ResultDisplay::new(&options(&[])).metrics(&progress),
+            Some("Running, input 10 rows, output 2 rows, 2.500 seconds elapsed.".to_string())
+        );
+        assert_eq!(
+            ResultDisplay::new(&options(&["--no-metrics"])).metrics(&progress),
+            None
+        );
+
+        assert!(Options::from_iter_safe(&["yql-cli", "--no-metrics", "--timing"]).is_err());
+        assert!(Options::from_iter_safe(&["yql-cli", "--format", "xml"]).is_err());
+    }
+
+    #[test]
+    fn test_csv_output() {
+        let schema = Arc::new(
+            Schema::try_new(vec![
+                Field::new("a", DataType::Int64),
+                Field::new("b", DataType::String),
+            ])
+            .unwrap(),
+        );
+        let dataset = DataSet::try_from_rows(schema, vec![vec![1i64.into(), "x".into()]]).unwrap();
+
+        let mut display = ResultDisplay::new(&options(&["--format", "csv"]));
+        assert_eq!(
+            display.dataset(&dataset).unwrap().as_deref(),
+            Some("a,b\n1,x")
+        );
+        assert_eq!(display.dataset(&dataset).unwrap().as_deref(), Some("1,x"));
+        assert_eq!(display.finish(), None);
+
+        // the trailing spaces of a value are kept
+        let dataset =
+            DataSet::try_from_rows(dataset.schema(), vec![vec![2i64.into(), "y  ".into()]])
+                .unwrap();
+        assert_eq!(display.dataset(&dataset).unwrap().as_deref(), Some("2,y  "));
+    }
+
+    #[test]
+    fn test_empty_data_output() {
+        let schema = Arc::new(Schema::try_new(vec![Field::new("a", DataType::Int64)]).unwrap());
+        let empty = DataSet::try_from_rows(schema.clone(), vec![]).unwrap();
+        let dataset = DataSet::try_from_rows(schema, vec![vec![1i64.into()]]).unwrap();
+
+        // nothing is printed for an empty dataset, except the header of the first one
+        let mut display = ResultDisplay::new(&options(&["--format", "json"]));
+        assert_eq!(display.dataset(&empty).unwrap(), None);
+        assert_eq!(
+            display.dataset(&dataset).unwrap().as_deref(),
+            Some("{\"a\":1}")
+        );
+        assert_eq!(display.dataset(&empty).unwrap(), None);
+
+        let mut display = ResultDisplay::new(&options(&["--format", "csv"]));
+        assert_eq!(display.dataset(&empty).unwrap().as_deref(), Some("a"));
+        assert_eq!(display.dataset(&empty).unwrap(), None);
+    }
+
+    #[test]
+    fn test_empty_result() {
+        // the result of a filter that excludes all the rows
+        let schema = Arc::new(
+            Schema::try_new(vec![
+                Field::new("name", DataType::String),
+                Field::new("value", DataType::Int64),
+            ])
+            .unwrap(),
+        );
+        let dataset = DataSet::try_from_rows(schema, vec![]).unwrap();
+
+        let mut display = ResultDisplay::new(&options(&[]));
+        let output = display.dataset(&dataset).unwrap().unwrap();
+        assert!(output.contains("name"));
+        assert!(output.contains("value"));
+        assert_eq!(display.finish(), Some("(0 rows)"));
+        assert_eq!(display.finish(), None);
+    }
+
+    #[test]
+    fn test_non_empty_result() {
+        let schema = Arc::new(Schema::try_new(vec![Field::new("a", DataType::Int64)]).unwrap());
+        let dataset = DataSet::try_from_rows(schema, vec![vec![1i64.into()]]).unwrap();
+
+        let mut display = ResultDisplay::new(&options(&[]));
+        assert!(display.dataset(&dataset).unwrap().unwrap().contains('a'));
+        assert!(!display.dataset(&dataset).unwrap().unwrap().contains('a'));
+        assert_eq!(display.finish(), None);
+    }
+}
@@ -1,4 +1,5 @@
 use std::pin::Pin;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use futures_util::{Stream, StreamExt};
 use tokio::sync::mpsc;
@@ -30,79 +31,113 @@ impl yql_protocol::yql_server::Yql for RpcYqlService {
         let (tx, rx) = mpsc::channel(8);
 
         tokio::spawn(async move {
-            match service.execute(&request.into_inner().sql).await {
-                Ok(ExecuteResult::DataSet(dataset)) => {
-                    let data = match bincode::serialize(&dataset) {
-                        Ok(data) => data,
-                        Err(err) => {
-                            tx.send(Err(Status::internal(err.to_string()))).await.ok();
-                            return;
-                        }
-                    };
-
-                    tx.send(Ok(ExecuteResponse {
-                        item: Some(execute_response::Item::Dataset(execute_response::DataSet {
-                            dataset: data,
-                        })),
-                    }))
-                    .await
-                    .ok();
+            let results = match service.execute(&request.into_inner().sql).await {
+                Ok(results) => results,
+                Err(err) => {
+                    tx.send(Err(Status::internal(err.to_string()))).await.ok();
+                    return;
                 }
-                Ok(ExecuteResult::ExecStream(mut stream)) => {
-                    let mut num_output_rows = 0;
-                    while let Some(res) = stream.next().await {
-                        let item = match res {
-                            Ok(item) => item,
+            };
+
+            // the results are sent in the order of the statements
+            for result in results {
+                match result {
+                    ExecuteResult::DataSet(dataset) => {
+                        let data = match bincode::serialize(&dataset) {
+                            Ok(data) => data,
                             Err(err) => {
                                 tx.send(Err(Status::internal(err.to_string()))).await.ok();
                                 return;
                             }
                         };
 
-                        match item {
-                            ExecuteStreamItem::DataSet(dataset) => {
-                                num_output_rows += dataset.len();
-                                let data = match bincode::serialize(&dataset) {
-                                    Ok(data) => data,
-                                    Err(err) => {
-                                        tx.send(Err(Status::internal(err.to_string()))).await.ok();
-                                        return;
-                                    }
-                                };
+                        tx.send(Ok(ExecuteResponse {
+                            item: Some(execute_response::Item::Dataset(
+                                execute_response::DataSet { dataset: data },
+                            )),
+                        }))
+                        .await
+                        .ok();
+                    }
+                    ExecuteResult::ExecStream(mut stream) => {
+                        while let Some(res) = stream.next().await {
+                            let item = match res {
+                                Ok(item) => item,
+                                Err(err) => {
+                                    tx.send(Err(Status::internal(err.to_string()))).await.ok();
+                                    return;
+                                }
+                            };
 
-                                if tx
-                                    .send(Ok(ExecuteResponse {
-                                        item: Some(execute_response::Item::Dataset(
-                                            execute_response::DataSet { dataset: data },
+                            match item {
+                                ExecuteStreamItem::DataSet(dataset) => {
+                                    let data = match bincode::serialize(&dataset) {
+                                        Ok(data) => data,
+                                        Err(err) => {
+                                            tx.send(Err(Status::internal(err.to_string())))
+                                                .await
+                                                .ok();
+                                            return;
+                                        }
+                                    };
+
+                                    if tx
+                                        .send(Ok(ExecuteResponse {
+                                            item: Some(execute_response::Item::Dataset(
+                                                execute_response::DataSet { dataset: data },
+                                            )),
+                                        }))
+                                        .await
+                                        .is_err()
+                                    {
+                                        return;
+                                    };
+                                }
+                                ExecuteStreamItem::Progress(metrics) => {
+                                    let now = SystemTime::now()
+                                        .duration_since(UNIX_EPOCH)
+                                        .map(|duration| duration.as_millis() as i64)
+                                        .unwrap_or_default();
+                                    if tx
+                                        .send(Ok(ExecuteResponse {
+                                            item: Some(execute_response::Item::Metrics(
+                                                execute_response::Metrics {
+                                                    start_time: metrics
+                                                        .start_time
+                                                        .unwrap_or_default(),
+                                                    end_time: now,
+                                                    num_input_rows: metrics.num_input_rows as i64,
+                                                    num_output_rows: metrics.num_output_rows as i64,
+                                                    running: true,
+                                                },
+                                            )),
+                                        }))
+                                        .await
+                                        .is_err()
+                                    {
+                                        return;
+                                    };
+                                }
+                                ExecuteStreamItem::Metrics(metrics) => {
+                                    tx.send(Ok(ExecuteResponse {
+                                        item: Some(execute_response::Item::Metrics(
+                                            execute_response::Metrics {
+                                                start_time: metrics.start_time.unwrap_or_default(),
+                                                end_time: metrics.end_time.unwrap_or_default(),
+                                                num_input_rows: metrics.num_input_rows as i64,
+                                                num_output_rows: metrics.num_output_rows as i64,
+                                                running: false,
+                                            },
                                         )),
                                     }))
                                     .await
-                                    .is_err()
-                                {
-                                    return;
-                                };
-                            }
-                            ExecuteStreamItem::Metrics(metrics) => {
-                                tx.send(Ok(ExecuteResponse {
-                                    item: Some(execute_response::Item::Metrics(
-                                        execute_response::Metrics {
-                                            start_time: metrics.start_time.unwrap_or_default(),
-                                            end_time: metrics.end_time.unwrap_or_default(),
-                                            num_input_rows: metrics.num_input_rows as i64,
-                                            num_output_rows: num_output_rows as i64,
-                                        },
-                                    )),
-                                }))
-                                .await
-                                .ok();
-                                break;
+                                    .ok();
+                                    break;
+                                }
                             }
                         }
                     }
                 }
-                Err(err) => {
-                    tx.send(Err(Status::internal(err.to_string()))).await.ok();
-                }
             }
         });
 
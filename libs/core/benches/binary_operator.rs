@@ -0,0 +1,41 @@
+use std::sync::Arc;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use yql_core::array::{ArrayRef, DataType, Int64Array};
+use yql_core::dataset::{DataSet, Field, Schema};
+use yql_core::dsl::*;
+
+fn create_dataset() -> DataSet {
+    let schema = Arc::new(
+        Schema::try_new(vec![
+            Field::new("a", DataType::Int64),
+            Field::new("b", DataType::Int64),
+        ])
+        .unwrap(),
+    );
+    let values = (0..1_000_000).collect::<Vec<i64>>();
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(Int64Array::from_vec(values.clone())),
+        Arc::new(Int64Array::from_vec(values)),
+    ];
+    DataSet::try_new(schema, columns).unwrap()
+}
+
+fn binary_operator(c: &mut Criterion) {
+    let dataset = create_dataset();
+
+    let mut expr = (col("a") * value(2))
+        .into_physical(dataset.schema())
+        .unwrap();
+    c.bench_function("array * scalar", |b| {
+        b.iter(|| expr.eval(&dataset).unwrap())
+    });
+
+    let mut expr = (col("a") * col("b"))
+        .into_physical(dataset.schema())
+        .unwrap();
+    c.bench_function("array * array", |b| b.iter(|| expr.eval(&dataset).unwrap()));
+}
+
+criterion_group!(benches, binary_operator);
+criterion_main!(benches);
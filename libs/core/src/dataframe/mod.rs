@@ -2,14 +2,17 @@ pub mod dsl;
 
 use anyhow::Result;
 
+use crate::dataset::SchemaRef;
 use crate::execution::stream::DataStream;
 use crate::expr::Expr;
 use crate::planner::logical_plan::{
-    LogicalAggregatePlan, LogicalFilterPlan, LogicalPlan, LogicalProjectionPlan, LogicalSourcePlan,
+    LogicalAggregatePlan, LogicalFilterPlan, LogicalJoinPlan, LogicalLimitPlan, LogicalLookupPlan,
+    LogicalPlan, LogicalProjectionPlan, LogicalSourcePlan, LogicalUnnestPlan,
 };
+use crate::planner::physical_plan::PhysicalPlan;
 use crate::sql::ast::Select;
 use crate::sql::SqlContext;
-use crate::{SourceProvider, Window};
+use crate::{Emit, JoinType, Pivot, SourceProvider, Window};
 
 #[derive(Clone)]
 pub struct DataFrame(LogicalPlan);
@@ -55,16 +58,147 @@ impl DataFrame {
         aggr_exprs: Vec<Expr>,
         window: Window,
         watermark_expr: Option<Expr>,
+        emit: Emit,
     ) -> Self {
         Self(LogicalPlan::Aggregate(LogicalAggregatePlan {
             group_exprs,
             aggr_exprs,
+            pivot: None,
             window,
+            emit,
+            order_by: Vec::new(),
+            having: None,
             watermark_expr,
             input: Box::new(self.0),
         }))
     }
 
+    /// Like `aggregate`, but also outputs a column for each value of the pivot.
+    pub fn pivot(
+        self,
+        group_exprs: Vec<Expr>,
+        aggr_exprs: Vec<Expr>,
+        pivot: Pivot,
+        window: Window,
+        watermark_expr: Option<Expr>,
+        emit: Emit,
+    ) -> Self {
+        Self(LogicalPlan::Aggregate(LogicalAggregatePlan {
+            group_exprs,
+            aggr_exprs,
+            pivot: Some(Box::new(pivot)),
+            window,
+            emit,
+            order_by: Vec::new(),
+            having: None,
+            watermark_expr,
+            input: Box::new(self.0),
+        }))
+    }
+
+    /// Sorts the rows emitted for each window of the aggregation by `exprs`, `true` means
+    /// ascending.
+    ///
+    /// A stream can't be sorted as a whole, so this must directly follow `aggregate` or `pivot`.
+    pub fn order_by(self, exprs: Vec<(Expr, bool)>) -> Result<Self> {
+        match self.0 {
+            LogicalPlan::Aggregate(mut aggregate) => {
+                aggregate.order_by = exprs;
+                Ok(Self(LogicalPlan::Aggregate(aggregate)))
+            }
+            _ => anyhow::bail!(
+                "ORDER BY is only supported directly on the results of an aggregation with a window."
+            ),
+        }
+    }
+
+    /// Filters the rows emitted for each window of the aggregation by `expr`, the aggregations in it
+    /// are computed for each group even if they are not selected.
+    ///
+    /// Like `order_by`, this must directly follow `aggregate` or `pivot`.
+    pub fn having(self, expr: Expr) -> Result<Self> {
+        match self.0 {
+            LogicalPlan::Aggregate(mut aggregate) => {
+                aggregate.having = Some(expr);
+                Ok(Self(LogicalPlan::Aggregate(aggregate)))
+            }
+            _ => anyhow::bail!("HAVING is only supported on the results of a window."),
+        }
+    }
+
+    /// Skips the first `offset` rows, and ends the stream after `limit` rows.
+    ///
+    /// If this follows `order_by`, the rows of each window are limited separately instead, and
+    /// with `Emit::Changes` the first rows of a window are emitted again whenever it is updated.
+    pub fn limit(self, limit: usize, offset: usize) -> Self {
+        Self(LogicalPlan::Limit(LogicalLimitPlan {
+            limit,
+            offset,
+            input: Box::new(self.0),
+        }))
+    }
+
+    /// Joins the rows with the rows of `right` for which the two expressions of `on` are equal.
+    ///
+    /// `right` is read to the end before any row is joined, so it must be bounded, e.g. a
+    /// dimension table read from a file.
+    ///
+    /// If `refresh` is set, `right` is read again from the beginning when it was read more than
+    /// `refresh` milliseconds ago, so that the changes of a reference table are picked up.
+    ///
+    /// The rows of `right` without a match of a right or full join are emitted when the table is
+    /// read again or when the rows of the left side end, so if the left side is unbounded,
+    /// `refresh` must be set.
+    pub fn join(
+        self,
+        right: DataFrame,
+        join_type: JoinType,
+        on: (Expr, Expr),
+        refresh: Option<i64>,
+    ) -> Self {
+        Self(LogicalPlan::Join(LogicalJoinPlan {
+            join_type,
+            left: Box::new(self.0),
+            right: Box::new(right.0),
+            on,
+            refresh,
+        }))
+    }
+
+    /// Appends a column with the value of `expr` evaluated on the first row of `right` for which
+    /// the two expressions of `on` are equal, or null if there is no such row.
+    ///
+    /// Like `join`, `right` is read to the end before any row is looked up, so it must be bounded.
+    pub fn lookup(self, right: DataFrame, on: (Expr, Expr), expr: Expr) -> Self {
+        Self(LogicalPlan::Lookup(LogicalLookupPlan {
+            left: Box::new(self.0),
+            right: Box::new(right.0),
+            on,
+            expr,
+        }))
+    }
+
+    /// Outputs a row for each element of the list returned by `expr`, with the columns of the input
+    /// repeated and the element appended. The rows with an empty or a null list are dropped.
+    pub fn unnest(self, expr: Expr) -> Self {
+        Self(LogicalPlan::Unnest(LogicalUnnestPlan {
+            input: Box::new(self.0),
+            expr,
+        }))
+    }
+
+    /// Plans the query without creating the stream, so that errors such as unknown columns or
+    /// mismatched types are reported without reading anything from the sources.
+    pub fn validate(&self) -> Result<()> {
+        PhysicalPlan::try_new(self.0.clone())?;
+        Ok(())
+    }
+
+    /// Plans the query and returns the schema of its output.
+    pub fn schema(&self) -> Result<SchemaRef> {
+        Ok(PhysicalPlan::try_new(self.0.clone())?.root.schema())
+    }
+
     pub fn into_stream(self, state: Option<Vec<u8>>) -> Result<DataStream> {
         DataStream::new(self.0, state)
     }
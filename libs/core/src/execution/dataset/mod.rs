@@ -1,3 +1,4 @@
+use std::convert::TryFrom;
 use std::hash::{Hash, Hasher};
 use std::sync::Arc;
 
@@ -10,9 +11,10 @@ use serde::{Deserialize, Serialize};
 use smallvec::SmallVec;
 
 use crate::array::{
-    Array, ArrayExt, ArrayRef, BooleanArray, BooleanType, DataType, Float32Type, Float64Type,
-    Int16Type, Int32Type, Int64Type, Int8Type, NullArray, PrimitiveArray, PrimitiveBuilder,
-    StringArray, StringBuilder, TimestampArray, TimestampType,
+    compute, Array, ArrayExt, ArrayRef, BooleanArray, BooleanType, DataType, Float32Type,
+    Float64Type, Int16Type, Int32Type, Int64Array, Int64Type, Int8Type, NullArray, PrimitiveArray,
+    PrimitiveBuilder, StringArray, StringBuilder, TimestampArray, TimestampType, UInt16Type,
+    UInt32Type, UInt64Array, UInt64Type, UInt8Type,
 };
 use crate::dataset::DataSet;
 use crate::expr::physical_expr::PhysicalExpr;
@@ -24,6 +26,9 @@ pub trait DataSetExt {
     fn group_by_exprs(&self, exprs: &mut [PhysicalExpr]) -> Result<GroupByExprsIter>;
 
     fn group_by_window(&self, time_idx: usize, window: &Window) -> Result<GroupByWindowIter>;
+
+    /// Returns the key of each row, or `None` if any of the expressions is null for that row.
+    fn row_keys(&self, exprs: &mut [PhysicalExpr]) -> Result<Vec<Option<GroupedKey>>>;
 }
 
 impl DataSetExt for DataSet {
@@ -38,6 +43,10 @@ impl DataSetExt for DataSet {
     fn group_by_window(&self, time_idx: usize, window: &Window) -> Result<GroupByWindowIter> {
         group_by_window(self, time_idx, window)
     }
+
+    fn row_keys(&self, exprs: &mut [PhysicalExpr]) -> Result<Vec<Option<GroupedKey>>> {
+        row_keys(self, exprs)
+    }
 }
 
 macro_rules! fill_integer_key {
@@ -73,16 +82,17 @@ macro_rules! copy_grouped_primitive_values {
     }};
 }
 
-#[derive(Debug, Hash, Eq, PartialEq, Clone, Serialize, Deserialize)]
+#[derive(Debug, Hash, Eq, PartialEq, Ord, PartialOrd, Clone, Serialize, Deserialize)]
 enum Key {
     Null,
     Boolean(bool),
     Int(i64),
     Float(OrderedFloat<f64>),
     String(String),
+    UInt(u64),
 }
 
-#[derive(Default, Eq, PartialEq, Hash, Clone, Serialize, Deserialize)]
+#[derive(Default, Eq, PartialEq, Ord, PartialOrd, Hash, Clone, Serialize, Deserialize)]
 pub struct GroupedKey(SmallVec<[Key; 4]>);
 
 fn create_record_keys(dataset: &DataSet, exprs: &mut [PhysicalExpr]) -> Result<Vec<Key>> {
@@ -108,6 +118,27 @@ fn create_record_keys(dataset: &DataSet, exprs: &mut [PhysicalExpr]) -> Result<V
             DataType::Int64 => {
                 fill_integer_key!(record_keys, array, Int64Type, num_group_exprs, column)
             }
+            DataType::UInt8 => {
+                fill_integer_key!(record_keys, array, UInt8Type, num_group_exprs, column)
+            }
+            DataType::UInt16 => {
+                fill_integer_key!(record_keys, array, UInt16Type, num_group_exprs, column)
+            }
+            DataType::UInt32 => {
+                fill_integer_key!(record_keys, array, UInt32Type, num_group_exprs, column)
+            }
+            DataType::UInt64 => {
+                // the values in the range of `Int64` have the same keys as the signed integers
+                let array = array.downcast_ref::<UInt64Array>();
+                for row in 0..array.len() {
+                    if let Some(value) = array.value_opt(row) {
+                        record_keys[row * num_group_exprs + column] = match i64::try_from(value) {
+                            Ok(value) => Key::Int(value),
+                            Err(_) => Key::UInt(value),
+                        };
+                    }
+                }
+            }
             DataType::Float32 => {
                 fill_float_key!(record_keys, array, Float32Type, num_group_exprs, column)
             }
@@ -134,11 +165,28 @@ fn create_record_keys(dataset: &DataSet, exprs: &mut [PhysicalExpr]) -> Result<V
                     }
                 }
             }
+            DataType::Decimal128 { .. } | DataType::List(_) | DataType::Struct(_) => {
+                anyhow::bail!("unsupported group key datatype: {}", array.data_type())
+            }
         }
     }
     Ok(record_keys)
 }
 
+fn row_keys(dataset: &DataSet, exprs: &mut [PhysicalExpr]) -> Result<Vec<Option<GroupedKey>>> {
+    let record_keys = create_record_keys(dataset, exprs)?;
+    Ok((0..dataset.len())
+        .map(|row| {
+            let keys = &record_keys[row * exprs.len()..(row + 1) * exprs.len()];
+            if keys.contains(&Key::Null) {
+                None
+            } else {
+                Some(GroupedKey(keys.iter().cloned().collect()))
+            }
+        })
+        .collect())
+}
+
 pub type HashGroupByExprsIter<'a> = Box<dyn Iterator<Item = Result<(u64, DataSet)>> + 'a>;
 
 fn hash_group_by_exprs<'a>(
@@ -200,6 +248,9 @@ fn group_by_window<'a>(
         _ => unreachable!(),
     };
     let times = times.downcast_ref::<TimestampArray>();
+    if let Window::Session { gap } = *window {
+        return group_by_session(dataset, times, gap);
+    }
     for (idx, timestamp) in times.iter().enumerate() {
         for (start, end) in window.windows(timestamp, tz) {
             let window = windows.entry(start).or_default();
@@ -215,6 +266,36 @@ fn group_by_window<'a>(
     )))
 }
 
+/// Splits the rows into sessions, the rows of a session are less than `gap` apart from each other
+/// when ordered by time, and the session ends at the time of its last row plus `gap`.
+fn group_by_session<'a>(
+    dataset: &'a DataSet,
+    times: &TimestampArray,
+    gap: i64,
+) -> Result<GroupByWindowIter<'a>> {
+    let mut rows = times.iter().enumerate().collect_vec();
+    rows.sort_by_key(|(_, timestamp)| *timestamp);
+
+    let mut sessions: Vec<(i64, i64, Vec<usize>)> = Vec::new();
+    for (idx, timestamp) in rows {
+        match sessions.last_mut() {
+            Some((_, end, indexes)) if timestamp < *end => {
+                *end = timestamp + gap;
+                indexes.push(idx);
+            }
+            _ => sessions.push((timestamp, timestamp + gap, vec![idx])),
+        }
+    }
+
+    Ok(Box::new(sessions.into_iter().map(
+        move |(start, end, mut indexes)| {
+            // the rows of a session keep their original order
+            indexes.sort_unstable();
+            create_dataset(dataset, &indexes).map(|dataset| (start, end, dataset))
+        },
+    )))
+}
+
 fn create_dataset(dataset: &DataSet, indexes: &[usize]) -> Result<DataSet> {
     let mut columns = Vec::with_capacity(dataset.schema().fields().len());
     for array in dataset.columns() {
@@ -224,6 +305,10 @@ fn create_dataset(dataset: &DataSet, indexes: &[usize]) -> Result<DataSet> {
             DataType::Int16 => copy_grouped_primitive_values!(array, indexes, Int16Type),
             DataType::Int32 => copy_grouped_primitive_values!(array, indexes, Int32Type),
             DataType::Int64 => copy_grouped_primitive_values!(array, indexes, Int64Type),
+            DataType::UInt8 => copy_grouped_primitive_values!(array, indexes, UInt8Type),
+            DataType::UInt16 => copy_grouped_primitive_values!(array, indexes, UInt16Type),
+            DataType::UInt32 => copy_grouped_primitive_values!(array, indexes, UInt32Type),
+            DataType::UInt64 => copy_grouped_primitive_values!(array, indexes, UInt64Type),
             DataType::Float32 => copy_grouped_primitive_values!(array, indexes, Float32Type),
             DataType::Float64 => copy_grouped_primitive_values!(array, indexes, Float64Type),
             DataType::Boolean => copy_grouped_primitive_values!(array, indexes, BooleanType),
@@ -238,8 +323,103 @@ fn create_dataset(dataset: &DataSet, indexes: &[usize]) -> Result<DataSet> {
                 }
                 Arc::new(builder.finish()) as ArrayRef
             }
+            DataType::Decimal128 { .. } | DataType::List(_) | DataType::Struct(_) => {
+                let indexes = indexes.iter().map(|index| *index as i64).collect();
+                compute::take(&**array, &Int64Array::from_vec(indexes))?
+            }
         };
         columns.push(new_array);
     }
     DataSet::try_new(dataset.schema(), columns)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dataset::{Field, Schema};
+
+    #[test]
+    fn test_group_by_sliding_window() {
+        let schema = Arc::new(
+            Schema::try_new(vec![
+                Field::new("a", DataType::Int64),
+                Field::new("@time", DataType::Timestamp(None)),
+            ])
+            .unwrap(),
+        );
+        let dataset = DataSet::try_new(
+            schema,
+            vec![
+                Arc::new(PrimitiveArray::<Int64Type>::from_vec(vec![1, 2, 3, 4])),
+                Arc::new(TimestampArray::from_vec(vec![0, 1000, 2500, 3000])),
+            ],
+        )
+        .unwrap();
+
+        let windows = dataset
+            .group_by_window(1, &Window::sliding(2000, 1000))
+            .unwrap()
+            .map(|item| {
+                let (start, end, dataset) = item.unwrap();
+                let values = dataset.columns()[0]
+                    .downcast_ref::<PrimitiveArray<Int64Type>>()
+                    .iter()
+                    .collect::<Vec<_>>();
+                (start, end, values)
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            windows,
+            vec![
+                (-1000, 1000, vec![1]),
+                (0, 2000, vec![1, 2]),
+                (1000, 3000, vec![2, 3]),
+                (2000, 4000, vec![3, 4]),
+                (3000, 5000, vec![4]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_group_by_session_window() {
+        let schema = Arc::new(
+            Schema::try_new(vec![
+                Field::new("a", DataType::Int64),
+                Field::new("@time", DataType::Timestamp(None)),
+            ])
+            .unwrap(),
+        );
+        // two clusters of events separated by one minute of inactivity
+        let dataset = DataSet::try_new(
+            schema,
+            vec![
+                Arc::new(PrimitiveArray::<Int64Type>::from_vec(vec![
+                    1, 2, 3, 4, 5, 6,
+                ])),
+                Arc::new(TimestampArray::from_vec(vec![
+                    0, 20000, 100000, 10000, 110000, 125000,
+                ])),
+            ],
+        )
+        .unwrap();
+
+        let windows = dataset
+            .group_by_window(1, &Window::session(30000))
+            .unwrap()
+            .map(|item| {
+                let (start, end, dataset) = item.unwrap();
+                let values = dataset.columns()[0]
+                    .downcast_ref::<PrimitiveArray<Int64Type>>()
+                    .iter()
+                    .collect::<Vec<_>>();
+                (start, end, values)
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            windows,
+            vec![(0, 50000, vec![1, 2, 4]), (100000, 155000, vec![3, 5, 6])]
+        );
+    }
+}
@@ -6,6 +6,7 @@ pub struct ExecutionMetrics {
     pub start_time: Option<i64>,
     pub end_time: Option<i64>,
     pub num_input_rows: usize,
+    pub num_output_rows: usize,
 }
 
 pub struct ExecutionContext {
@@ -23,6 +24,7 @@ impl ExecutionContext {
         f(&mut *self.metrics.lock());
     }
 
+    /// Returns a snapshot of the metrics.
     pub fn metrics(&self) -> ExecutionMetrics {
         self.metrics.lock().clone()
     }
@@ -67,6 +67,8 @@ impl DataStream {
         self.schema.clone()
     }
 
+    /// Returns the metrics of the rows read and produced so far, it is cheap enough to be called
+    /// while the stream is running.
     pub fn metrics(&self) -> ExecutionMetrics {
         self.ctx.metrics()
     }
@@ -83,7 +85,11 @@ impl Stream for DataStream {
         }
 
         match self.input.poll_next_unpin(cx) {
-            Poll::Ready(Some(Ok(dataset))) => Poll::Ready(Some(Ok(dataset))),
+            Poll::Ready(Some(Ok(dataset))) => {
+                self.ctx
+                    .update_metrics(|metrics| metrics.num_output_rows += dataset.len());
+                Poll::Ready(Some(Ok(dataset)))
+            }
             Poll::Ready(Some(Err(err))) => Poll::Ready(Some(Err(err))),
             Poll::Ready(None) => {
                 self.ctx.update_metrics(|metrics| {
@@ -100,13 +106,18 @@ impl Stream for DataStream {
 mod tests {
     use std::sync::Arc;
 
+    use anyhow::Result;
     use futures_util::StreamExt;
 
-    use crate::array::DataType;
+    use crate::array::{
+        ArrayExt, ArrayRef, DataType, Float64Array, Int64Array, Scalar, TimestampArray,
+    };
     use crate::dataset::{CsvOptions, DataSet, Field, Schema};
     use crate::dsl::*;
     use crate::sources::csv::{Options, Provider};
-    use crate::{DataFrame, SourceProviderWrapper, Window};
+    use crate::sql::{SqlContext, SqlSourceProvider};
+    use crate::testing::{collect_into_vec, collect_stream_into_vec, memory_source, MemoryContext};
+    use crate::{DataFrame, Emit, Pivot, SourceProvider, SourceProviderWrapper, Window};
 
     fn create_source_provider() -> Provider {
         let schema = Arc::new(
@@ -153,6 +164,8 @@ mod tests {
                 delimiter: b',',
                 has_header: false,
                 batch_size: 10,
+                tail: false,
+                poll_interval: 1000,
             },
             schema,
             data,
@@ -398,6 +411,34 @@ mod tests {
         assert!(stream.next().await.is_none());
     }
 
+    #[tokio::test]
+    async fn test_fused_filter_stream() {
+        let df = DataFrame::new(
+            Arc::new(SourceProviderWrapper(create_source_provider())),
+            None,
+            Some(col("time")),
+        );
+
+        let fused = df
+            .clone()
+            .filter(col("a").gt(value(3)))
+            .filter(col("a").lt(value(15)))
+            .into_stream(None)
+            .unwrap()
+            .map(Result::unwrap)
+            .collect::<Vec<_>>()
+            .await;
+        let expected = df
+            .filter(col("a").gt(value(3)).and(col("a").lt(value(15))))
+            .into_stream(None)
+            .unwrap()
+            .map(Result::unwrap)
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(fused, expected);
+        assert_eq!(fused.iter().map(DataSet::len).sum::<usize>(), 11);
+    }
+
     #[tokio::test]
     async fn test_aggregate_stream() {
         let provider = create_source_provider();
@@ -413,6 +454,7 @@ mod tests {
                 length: 1000 * 60 * 60,
             },
             None,
+            Emit::Final,
         );
         let output_schema = Arc::new(
             Schema::try_new(vec![
@@ -455,4 +497,2155 @@ d,141,1622512800000
 
         assert!(stream.next().await.is_none());
     }
+
+    #[tokio::test]
+    async fn test_aggregate_pivot() {
+        let provider = create_source_provider();
+        let df = DataFrame::new(
+            Arc::new(SourceProviderWrapper(provider)),
+            None,
+            Some(col("time")),
+        )
+        .pivot(
+            vec![],
+            vec![call("sum", vec![col("a")]).alias("total")],
+            Pivot {
+                aggr_expr: call("sum", vec![col("a")]),
+                pivot_expr: col("c"),
+                values: vec!["a".into(), "b".into(), "c".into()],
+            },
+            Window::Fixed {
+                length: 1000 * 60 * 60,
+            },
+            None,
+            Emit::Final,
+        );
+        let output_schema = Arc::new(
+            Schema::try_new(vec![
+                Field::new("total", DataType::Float64),
+                Field::new("a", DataType::Float64),
+                Field::new("b", DataType::Float64),
+                Field::new("c", DataType::Float64),
+                Field::new("@time", DataType::Timestamp(None)),
+            ])
+            .unwrap(),
+        );
+        let expected = |values: [Option<f64>; 4], time: i64| {
+            DataSet::try_new(
+                output_schema.clone(),
+                values
+                    .iter()
+                    .map(|value| Arc::new(Float64Array::from_opt_vec(vec![*value])) as ArrayRef)
+                    .chain(std::iter::once(
+                        Arc::new(TimestampArray::from_vec(vec![time])) as ArrayRef,
+                    ))
+                    .collect(),
+            )
+            .unwrap()
+        };
+
+        let mut stream = df.clone().into_stream(None).unwrap();
+        assert_eq!(stream.schema(), output_schema);
+        assert_eq!(
+            stream.next().await.unwrap().unwrap(),
+            expected([Some(66.0), Some(10.0), Some(56.0), None], 1622509200000)
+        );
+
+        let state = stream.save_state().unwrap();
+        let mut stream = df.into_stream(Some(state)).unwrap();
+        assert_eq!(
+            stream.next().await.unwrap().unwrap(),
+            expected([Some(285.0), None, Some(12.0), Some(132.0)], 1622512800000)
+        );
+        assert!(stream.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_aggregate_sum_boolean() {
+        let provider = create_source_provider();
+        let df = DataFrame::new(
+            Arc::new(SourceProviderWrapper(provider)),
+            None,
+            Some(col("time")),
+        )
+        .aggregate(
+            vec![col("c")],
+            vec![
+                col("c"),
+                call("sum", vec![(col("a") % value(2)).eq(value(0))]).alias("a"),
+            ],
+            Window::Fixed {
+                length: 1000 * 60 * 60,
+            },
+            None,
+            Emit::Final,
+        );
+        let output_schema = Arc::new(
+            Schema::try_new(vec![
+                Field::new("c", DataType::String),
+                Field::new("a", DataType::Float64),
+                Field::new("@time", DataType::Timestamp(None)),
+            ])
+            .unwrap(),
+        );
+
+        let mut stream = df.into_stream(None).unwrap();
+        assert_eq!(
+            stream.next().await.unwrap().unwrap(),
+            DataSet::from_csv_slice(
+                output_schema.clone(),
+                CsvOptions::default(),
+                br#"
+a,2,1622509200000
+b,3,1622509200000
+"#,
+            )
+            .unwrap()
+        );
+        assert_eq!(
+            stream.next().await.unwrap().unwrap(),
+            DataSet::from_csv_slice(
+                output_schema,
+                CsvOptions::default(),
+                br#"
+b,1,1622512800000
+c,4,1622512800000
+d,3,1622512800000
+"#,
+            )
+            .unwrap()
+        );
+        assert!(stream.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_aggregate_collect_list() {
+        let provider = create_source_provider();
+        let df = DataFrame::new(
+            Arc::new(SourceProviderWrapper(provider)),
+            None,
+            Some(col("time")),
+        )
+        .aggregate(
+            vec![col("c")],
+            vec![col("c"), call("collect_list", vec![col("a")]).alias("a")],
+            Window::Fixed {
+                length: 1000 * 60 * 60,
+            },
+            None,
+            Emit::Final,
+        );
+        let output_schema = Arc::new(
+            Schema::try_new(vec![
+                Field::new("c", DataType::String),
+                Field::new("a", DataType::List(Box::new(DataType::Int64))),
+                Field::new("@time", DataType::Timestamp(None)),
+            ])
+            .unwrap(),
+        );
+        let expected = |groups: Vec<(&str, std::ops::RangeInclusive<i64>)>, time: i64| {
+            let rows = groups
+                .into_iter()
+                .map(|(c, a)| {
+                    vec![
+                        c.into(),
+                        Scalar::List(a.map(Scalar::Int64).collect()),
+                        Scalar::Timestamp(time),
+                    ]
+                })
+                .collect();
+            DataSet::try_from_rows(output_schema.clone(), rows).unwrap()
+        };
+
+        let mut stream = df.clone().into_stream(None).unwrap();
+        assert_eq!(stream.schema(), output_schema);
+        assert_eq!(
+            stream.next().await.unwrap().unwrap(),
+            expected(vec![("a", 1..=4), ("b", 5..=11)], 1622509200000)
+        );
+
+        let state = stream.save_state().unwrap();
+        let mut stream = df.into_stream(Some(state)).unwrap();
+        assert_eq!(
+            stream.next().await.unwrap().unwrap(),
+            expected(
+                vec![("b", 12..=12), ("c", 13..=20), ("d", 21..=26)],
+                1622512800000
+            )
+        );
+        assert!(stream.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_aggregate_stream_emit_changes() {
+        let provider = create_source_provider();
+        let df = DataFrame::new(
+            Arc::new(SourceProviderWrapper(provider)),
+            None,
+            Some(col("time")),
+        )
+        .aggregate(
+            vec![col("c")],
+            vec![col("c"), call("sum", vec![col("a")]).alias("a")],
+            Window::Fixed {
+                length: 1000 * 60 * 60,
+            },
+            None,
+            Emit::Changes,
+        );
+        let output_schema = Arc::new(
+            Schema::try_new(vec![
+                Field::new("c", DataType::String),
+                Field::new("a", DataType::Float64),
+                Field::new("@time", DataType::Timestamp(None)),
+            ])
+            .unwrap(),
+        );
+
+        let mut stream = df.clone().into_stream(None).unwrap();
+        assert_eq!(
+            stream.next().await.unwrap().unwrap(),
+            DataSet::from_csv_slice(
+                output_schema.clone(),
+                CsvOptions::default(),
+                br#"
+a,10,1622509200000
+b,45,1622509200000
+"#,
+            )
+            .unwrap()
+        );
+
+        let state = stream.save_state().unwrap();
+        let mut stream = df.clone().into_stream(Some(state)).unwrap();
+        assert_eq!(
+            stream.next().await.unwrap().unwrap(),
+            DataSet::from_csv_slice(
+                output_schema.clone(),
+                CsvOptions::default(),
+                br#"
+b,56,1622509200000
+b,12,1622512800000
+c,132,1622512800000
+"#,
+            )
+            .unwrap()
+        );
+        assert_eq!(
+            stream.next().await.unwrap().unwrap(),
+            DataSet::from_csv_slice(
+                output_schema,
+                CsvOptions::default(),
+                br#"
+d,141,1622512800000
+"#,
+            )
+            .unwrap()
+        );
+
+        assert!(stream.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_aggregate_stream_sliding_window() {
+        let provider = create_source_provider();
+        let df = DataFrame::new(
+            Arc::new(SourceProviderWrapper(provider)),
+            None,
+            Some(col("time")),
+        )
+        .aggregate(
+            vec![],
+            vec![call("sum", vec![col("a")]).alias("a")],
+            Window::Sliding {
+                length: 1000 * 60 * 60 * 2,
+                interval: 1000 * 60 * 60,
+            },
+            None,
+            Emit::Final,
+        );
+        let output_schema = Arc::new(
+            Schema::try_new(vec![
+                Field::new("a", DataType::Float64),
+                Field::new("@time", DataType::Timestamp(None)),
+            ])
+            .unwrap(),
+        );
+
+        let mut stream = df.into_stream(None).unwrap();
+        assert_eq!(
+            stream.next().await.unwrap().unwrap(),
+            DataSet::from_csv_slice(
+                output_schema.clone(),
+                CsvOptions::default(),
+                br#"
+66,1622505600000
+"#,
+            )
+            .unwrap()
+        );
+        assert_eq!(
+            stream.next().await.unwrap().unwrap(),
+            DataSet::from_csv_slice(
+                output_schema.clone(),
+                CsvOptions::default(),
+                br#"
+351,1622509200000
+"#,
+            )
+            .unwrap()
+        );
+        assert_eq!(
+            stream.next().await.unwrap().unwrap(),
+            DataSet::from_csv_slice(
+                output_schema,
+                CsvOptions::default(),
+                br#"
+285,1622512800000
+"#,
+            )
+            .unwrap()
+        );
+        assert!(stream.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_aggregate_stream_overlapping_sliding_windows() {
+        let schema = Arc::new(
+            Schema::try_new(vec![
+                Field::new("time", DataType::Timestamp(None)),
+                Field::new("a", DataType::Int64),
+            ])
+            .unwrap(),
+        );
+        // 5-minute windows stepping every minute, each row is in five of them
+        let provider = Provider::new_from_memory(
+            Options {
+                delimiter: b',',
+                has_header: false,
+                batch_size: 1,
+                tail: false,
+                poll_interval: 1000,
+            },
+            schema,
+            "60000,1\n150000,2\n240000,4\n",
+        );
+        let df = DataFrame::new(
+            Arc::new(SourceProviderWrapper(provider)),
+            None,
+            Some(col("time")),
+        )
+        .aggregate(
+            vec![],
+            vec![call("sum", vec![col("a")]).alias("a")],
+            Window::sliding(1000 * 60 * 5, 1000 * 60),
+            None,
+            Emit::Final,
+        );
+
+        let output_schema = Arc::new(
+            Schema::try_new(vec![
+                Field::new("a", DataType::Float64),
+                Field::new("@time", DataType::Timestamp(None)),
+            ])
+            .unwrap(),
+        );
+
+        // the row at 150000 is counted once in each of the windows starting from -120000 to
+        // 120000, and the windows are emitted in the order of their end time
+        let mut stream = df.into_stream(None).unwrap();
+        for expected in [
+            "1,-180000",
+            "3,-120000",
+            "3,-60000",
+            "7,0",
+            "7,60000",
+            "6,120000",
+            "4,180000",
+            "4,240000",
+        ] {
+            assert_eq!(
+                stream.next().await.unwrap().unwrap(),
+                DataSet::from_csv_slice(
+                    output_schema.clone(),
+                    CsvOptions::default(),
+                    expected.as_bytes()
+                )
+                .unwrap()
+            );
+        }
+        assert!(stream.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_aggregate_stream_session_window() {
+        let schema = Arc::new(
+            Schema::try_new(vec![
+                Field::new("time", DataType::Timestamp(None)),
+                Field::new("a", DataType::Int64),
+            ])
+            .unwrap(),
+        );
+        // two clusters of events separated by one minute of inactivity
+        let provider = Provider::new_from_memory(
+            Options {
+                delimiter: b',',
+                has_header: false,
+                batch_size: 2,
+                tail: false,
+                poll_interval: 1000,
+            },
+            schema,
+            "0,1\n20000,2\n40000,3\n100000,4\n110000,5\n130000,6\n",
+        );
+        let df = DataFrame::new(
+            Arc::new(SourceProviderWrapper(provider)),
+            None,
+            Some(col("time")),
+        )
+        .aggregate(
+            vec![],
+            vec![call("sum", vec![col("a")]).alias("a")],
+            Window::session(30000),
+            None,
+            Emit::Final,
+        );
+        let output_schema = Arc::new(
+            Schema::try_new(vec![
+                Field::new("a", DataType::Float64),
+                Field::new("@time", DataType::Timestamp(None)),
+            ])
+            .unwrap(),
+        );
+
+        // the first session is completed when the watermark passes 40000 + 30000
+        let mut stream = df.clone().into_stream(None).unwrap();
+        assert_eq!(
+            stream.next().await.unwrap().unwrap(),
+            DataSet::from_csv_slice(output_schema.clone(), CsvOptions::default(), b"6,0\n")
+                .unwrap()
+        );
+
+        // the open session survives restoring from the saved state
+        let state = stream.save_state().unwrap();
+        let mut stream = df.into_stream(Some(state)).unwrap();
+        assert_eq!(
+            stream.next().await.unwrap().unwrap(),
+            DataSet::from_csv_slice(output_schema, CsvOptions::default(), b"15,100000\n").unwrap()
+        );
+        assert!(stream.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_aggregate_stream_session_bridge() {
+        let schema = Arc::new(
+            Schema::try_new(vec![
+                Field::new("time", DataType::Timestamp(None)),
+                Field::new("wm", DataType::Int64),
+                Field::new("g", DataType::String),
+                Field::new("a", DataType::Int64),
+            ])
+            .unwrap(),
+        );
+        // the late row of the second batch bridges the gap between the two sessions of the group
+        // `a` in the first batch, and the group `b` only has a session in the first batch
+        let provider = Provider::new_from_memory(
+            Options {
+                delimiter: b',',
+                has_header: false,
+                batch_size: 3,
+                tail: false,
+                poll_interval: 1000,
+            },
+            schema,
+            "0,0,a,1\n50000,0,a,2\n10000,0,b,4\n25000,0,a,3\n",
+        );
+        let df = DataFrame::new(
+            Arc::new(SourceProviderWrapper(provider)),
+            None,
+            Some(col("time")),
+        )
+        .aggregate(
+            vec![col("g")],
+            vec![
+                col("g"),
+                call("sum", vec![col("a")]).alias("a"),
+                call("count", vec![col("a")]).alias("n"),
+                call("first", vec![col("a")]).alias("f"),
+                call("last", vec![col("a")]).alias("l"),
+                call("collect_list", vec![col("a")]).alias("list"),
+            ],
+            Window::session(30000),
+            Some(col("wm")),
+            Emit::Final,
+        );
+        let output_schema = Arc::new(
+            Schema::try_new(vec![
+                Field::new("g", DataType::String),
+                Field::new("a", DataType::Float64),
+                Field::new("n", DataType::Int64),
+                Field::new("f", DataType::Int64),
+                Field::new("l", DataType::Int64),
+                Field::new("list", DataType::List(Box::new(DataType::Int64))),
+                Field::new("@time", DataType::Timestamp(None)),
+            ])
+            .unwrap(),
+        );
+        let row = |g: &str, a: f64, n: i64, f: i64, l: i64, list: Vec<i64>, time: i64| {
+            DataSet::try_from_rows(
+                output_schema.clone(),
+                vec![vec![
+                    Scalar::String(g.into()),
+                    Scalar::Float64(a),
+                    Scalar::Int64(n),
+                    Scalar::Int64(f),
+                    Scalar::Int64(l),
+                    Scalar::List(list.into_iter().map(Scalar::Int64).collect()),
+                    Scalar::Timestamp(time),
+                ]],
+            )
+            .unwrap()
+        };
+
+        // the sessions are emitted in the order of their end time
+        let mut stream = df.into_stream(None).unwrap();
+        assert_eq!(
+            stream.next().await.unwrap().unwrap(),
+            row("b", 4.0, 1, 4, 4, vec![4], 10000)
+        );
+        assert_eq!(
+            stream.next().await.unwrap().unwrap(),
+            row("a", 6.0, 3, 1, 3, vec![1, 2, 3], 0)
+        );
+        assert!(stream.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_aggregate_stream_session_per_group() {
+        let schema = Arc::new(
+            Schema::try_new(vec![
+                Field::new("time", DataType::Timestamp(None)),
+                Field::new("g", DataType::String),
+                Field::new("a", DataType::Int64),
+            ])
+            .unwrap(),
+        );
+        // the group `a` stays active, while the group `b` is quiet after its first row
+        let provider = Provider::new_from_memory(
+            Options {
+                delimiter: b',',
+                has_header: false,
+                batch_size: 2,
+                tail: false,
+                poll_interval: 1000,
+            },
+            schema,
+            "0,a,1\n0,b,10\n20000,a,2\n40000,a,3\n60000,a,4\n80000,a,5\n",
+        );
+        let df = DataFrame::new(
+            Arc::new(SourceProviderWrapper(provider)),
+            None,
+            Some(col("time")),
+        )
+        .aggregate(
+            vec![col("g")],
+            vec![
+                col("g"),
+                call("sum", vec![col("a")]).alias("a"),
+                col("@window_end"),
+            ],
+            Window::session(30000),
+            None,
+            Emit::Final,
+        );
+        let output_schema = Arc::new(
+            Schema::try_new(vec![
+                Field::new("g", DataType::String),
+                Field::new("a", DataType::Float64),
+                Field::new("@window_end", DataType::Timestamp(None)),
+                Field::new("@time", DataType::Timestamp(None)),
+            ])
+            .unwrap(),
+        );
+
+        // the session of `b` is completed when the watermark passes 0 + 30000, although the
+        // session of `a` is still open
+        let mut stream = df.into_stream(None).unwrap();
+        assert_eq!(
+            stream.next().await.unwrap().unwrap(),
+            DataSet::from_csv_slice(
+                output_schema.clone(),
+                CsvOptions::default(),
+                b"b,10,30000,0\n"
+            )
+            .unwrap()
+        );
+        assert_eq!(
+            stream.next().await.unwrap().unwrap(),
+            DataSet::from_csv_slice(output_schema, CsvOptions::default(), b"a,15,110000,0\n")
+                .unwrap()
+        );
+        assert!(stream.next().await.is_none());
+    }
+
+    #[test]
+    fn test_aggregate_session_window_without_merge() {
+        let df = DataFrame::new(
+            Arc::new(SourceProviderWrapper(create_source_provider())),
+            None,
+            Some(col("time")),
+        )
+        .aggregate(
+            vec![col("c")],
+            vec![
+                col("c"),
+                call(
+                    "count",
+                    vec![crate::expr::Expr::Distinct(Box::new(col("a")))],
+                )
+                .alias("n"),
+            ],
+            Window::session(30000),
+            None,
+            Emit::Final,
+        );
+
+        assert_eq!(
+            df.into_stream(None).err().unwrap().to_string(),
+            "the aggregation 'n' is not supported in a session window, because its state can't be merged."
+        );
+    }
+
+    #[tokio::test]
+    async fn test_aggregate_stream_over_window() {
+        let window = Window::Fixed {
+            length: 1000 * 60 * 60,
+        };
+        let df = DataFrame::new(
+            Arc::new(SourceProviderWrapper(create_source_provider())),
+            None,
+            Some(col("time")),
+        )
+        .aggregate(
+            vec![col("c")],
+            vec![
+                col("c"),
+                call("sum", vec![col("a")]).over(window).alias("a"),
+            ],
+            window,
+            None,
+            Emit::Final,
+        );
+        let output_schema = Arc::new(
+            Schema::try_new(vec![
+                Field::new("c", DataType::String),
+                Field::new("a", DataType::Float64),
+                Field::new("@time", DataType::Timestamp(None)),
+            ])
+            .unwrap(),
+        );
+
+        let mut stream = df.into_stream(None).unwrap();
+        assert_eq!(
+            stream.next().await.unwrap().unwrap(),
+            DataSet::from_csv_slice(
+                output_schema,
+                CsvOptions::default(),
+                br#"
+a,10,1622509200000
+b,56,1622509200000
+"#,
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_aggregate_multiple_windows() {
+        let df = DataFrame::new(
+            Arc::new(SourceProviderWrapper(create_source_provider())),
+            None,
+            Some(col("time")),
+        )
+        .aggregate(
+            vec![col("c")],
+            vec![
+                col("c"),
+                call("sum", vec![col("a")])
+                    .over(Window::Fixed {
+                        length: 1000 * 60 * 5,
+                    })
+                    .alias("a"),
+            ],
+            Window::Fixed {
+                length: 1000 * 60 * 60,
+            },
+            None,
+            Emit::Final,
+        );
+
+        assert_eq!(
+            df.into_stream(None).err().unwrap().to_string(),
+            "aggregations with different windows are not supported: 'a' uses window 'fixed(300000 milliseconds)', but the query uses window 'fixed(3600000 milliseconds)'"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_group_by_tumble() {
+        struct Context;
+
+        impl SqlContext for Context {
+            fn create_source_provider(&self, _name: &str) -> Result<Option<SqlSourceProvider>> {
+                Ok(Some(SqlSourceProvider {
+                    source_provider: Arc::new(SourceProviderWrapper(create_source_provider())),
+                    time_expr: Some(col("time")),
+                }))
+            }
+        }
+
+        let collect = |sql: &'static str| async move {
+            DataFrame::from_sql(&Context, sql)
+                .unwrap()
+                .into_stream(None)
+                .unwrap()
+                .map(Result::unwrap)
+                .collect::<Vec<_>>()
+                .await
+        };
+
+        let output = collect("select c, sum(a) from t group by c, tumble(@time, 60 minutes)").await;
+        assert_eq!(output.iter().map(DataSet::len).sum::<usize>(), 5);
+        assert_eq!(
+            output,
+            collect("select c, sum(a) from t group by c window fixed(60 minutes)").await
+        );
+    }
+
+    #[tokio::test]
+    async fn test_aggregate_watermark_jump() {
+        let schema = Arc::new(
+            Schema::try_new(vec![
+                Field::new("time", DataType::Timestamp(None)),
+                Field::new("wm", DataType::Timestamp(None)),
+                Field::new("a", DataType::Int64),
+            ])
+            .unwrap(),
+        );
+        // the watermark stays at the start until the last row, which closes three windows at once
+        let ctx = MemoryContext {
+            source_provider: memory_source(
+                schema,
+                r#"
+1622509200000,1622509200000,1
+1622509270000,1622509200000,2
+1622509340000,1622509200000,4
+1622509800000,1622509800000,8
+"#,
+                3,
+            ),
+            time_expr: Some(col("time")),
+        };
+        let df = DataFrame::from_sql(
+            &ctx,
+            "select sum(a) as s from t window fixed(1 minutes) watermark by wm",
+        )
+        .unwrap();
+        let output_schema = Arc::new(
+            Schema::try_new(vec![
+                Field::new("s", DataType::Float64),
+                Field::new("@time", DataType::Timestamp(None)),
+            ])
+            .unwrap(),
+        );
+
+        let mut stream = df.into_stream(None).unwrap();
+        let mut output = Vec::new();
+        for _ in 0..3 {
+            output.push(stream.next().await.unwrap().unwrap());
+        }
+        assert_eq!(
+            output,
+            vec![
+                DataSet::from_csv_slice(
+                    output_schema.clone(),
+                    CsvOptions::default(),
+                    b"1,1622509200000\n"
+                )
+                .unwrap(),
+                DataSet::from_csv_slice(
+                    output_schema.clone(),
+                    CsvOptions::default(),
+                    b"2,1622509260000\n"
+                )
+                .unwrap(),
+                DataSet::from_csv_slice(
+                    output_schema.clone(),
+                    CsvOptions::default(),
+                    b"4,1622509320000\n"
+                )
+                .unwrap(),
+            ]
+        );
+
+        // the last window is emitted at the end of the stream
+        assert_eq!(
+            collect_stream_into_vec(stream).await.unwrap(),
+            vec![DataSet::from_csv_slice(
+                output_schema,
+                CsvOptions::default(),
+                b"8,1622509800000\n"
+            )
+            .unwrap()]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_aggregate_distinct() {
+        struct Context;
+
+        impl SqlContext for Context {
+            fn create_source_provider(&self, _name: &str) -> Result<Option<SqlSourceProvider>> {
+                Ok(Some(SqlSourceProvider {
+                    source_provider: Arc::new(SourceProviderWrapper(create_source_provider())),
+                    time_expr: Some(col("time")),
+                }))
+            }
+        }
+
+        let df = DataFrame::from_sql(
+            &Context,
+            "select count(distinct c) as n, count(c) as m from t window fixed(60 minutes)",
+        )
+        .unwrap();
+        let output_schema = Arc::new(
+            Schema::try_new(vec![
+                Field::new("n", DataType::Int64),
+                Field::new("m", DataType::Int64),
+                Field::new("@time", DataType::Timestamp(None)),
+            ])
+            .unwrap(),
+        );
+        let output = df
+            .into_stream(None)
+            .unwrap()
+            .map(Result::unwrap)
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(
+            output,
+            vec![
+                DataSet::from_csv_slice(
+                    output_schema.clone(),
+                    CsvOptions::default(),
+                    b"2,11,1622509200000\n"
+                )
+                .unwrap(),
+                DataSet::from_csv_slice(
+                    output_schema,
+                    CsvOptions::default(),
+                    b"3,15,1622512800000\n"
+                )
+                .unwrap(),
+            ]
+        );
+
+        assert_eq!(
+            DataFrame::from_sql(&Context, "select concat(distinct c) from t")
+                .unwrap()
+                .into_stream(None)
+                .err()
+                .unwrap()
+                .to_string(),
+            "DISTINCT can only be used with aggregate functions, but 'concat' is not one"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_limit() {
+        struct Context;
+
+        impl SqlContext for Context {
+            fn create_source_provider(&self, _name: &str) -> Result<Option<SqlSourceProvider>> {
+                Ok(Some(SqlSourceProvider {
+                    source_provider: Arc::new(SourceProviderWrapper(create_source_provider())),
+                    time_expr: Some(col("time")),
+                }))
+            }
+        }
+
+        let output_schema =
+            Arc::new(Schema::try_new(vec![Field::new("a", DataType::Int64)]).unwrap());
+        let collect = |sql: &'static str| async move {
+            DataFrame::from_sql(&Context, sql)
+                .unwrap()
+                .into_stream(None)
+                .unwrap()
+                .map(Result::unwrap)
+                .collect::<Vec<_>>()
+                .await
+        };
+
+        // the stream ends as soon as the limit is reached
+        let output = collect("select a from t limit 10").await;
+        assert_eq!(output.len(), 1);
+        assert_eq!(output[0].len(), 10);
+
+        // the limit spans multiple datasets
+        let output = collect("select a from t limit 7 offset 8").await;
+        assert_eq!(
+            output,
+            vec![
+                DataSet::from_csv_slice(output_schema.clone(), CsvOptions::default(), b"9\n10\n")
+                    .unwrap(),
+                DataSet::from_csv_slice(
+                    output_schema.clone(),
+                    CsvOptions::default(),
+                    b"11\n12\n13\n14\n15\n"
+                )
+                .unwrap(),
+            ]
+        );
+
+        assert!(collect("select a from t limit 0").await.is_empty());
+        assert!(collect("select a from t limit 10 offset 30")
+            .await
+            .is_empty());
+
+        // the counters are restored from the saved state
+        let df = DataFrame::from_sql(&Context, "select a from t limit 12 offset 5").unwrap();
+        let mut stream = df.clone().into_stream(None).unwrap();
+        assert_eq!(stream.next().await.unwrap().unwrap().len(), 5);
+        let state = stream.save_state().unwrap();
+        let mut stream = df.into_stream(Some(state)).unwrap();
+        assert_eq!(
+            stream.next().await.unwrap().unwrap(),
+            DataSet::from_csv_slice(
+                output_schema,
+                CsvOptions::default(),
+                b"11\n12\n13\n14\n15\n16\n17\n"
+            )
+            .unwrap()
+        );
+        assert!(stream.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_order_by() {
+        struct Context;
+
+        impl SqlContext for Context {
+            fn create_source_provider(&self, _name: &str) -> Result<Option<SqlSourceProvider>> {
+                Ok(Some(SqlSourceProvider {
+                    source_provider: Arc::new(SourceProviderWrapper(create_source_provider())),
+                    time_expr: Some(col("time")),
+                }))
+            }
+        }
+
+        let output_schema = Arc::new(
+            Schema::try_new(vec![
+                Field::new("c", DataType::String),
+                Field::new("s", DataType::Float64),
+                Field::new("@time", DataType::Timestamp(None)),
+            ])
+            .unwrap(),
+        );
+        let collect = |sql: &'static str| async move {
+            DataFrame::from_sql(&Context, sql)
+                .unwrap()
+                .into_stream(None)
+                .unwrap()
+                .map(Result::unwrap)
+                .collect::<Vec<_>>()
+                .await
+        };
+
+        // the rows of each window are sorted separately
+        let output = collect(
+            "select c, sum(a) as s from t group by c window fixed(60 minutes) order by s desc",
+        )
+        .await;
+        assert_eq!(
+            output,
+            vec![
+                DataSet::from_csv_slice(
+                    output_schema.clone(),
+                    CsvOptions::default(),
+                    b"b,56,1622509200000\na,10,1622509200000\n"
+                )
+                .unwrap(),
+                DataSet::from_csv_slice(
+                    output_schema.clone(),
+                    CsvOptions::default(),
+                    b"d,141,1622512800000\nc,132,1622512800000\nb,12,1622512800000\n"
+                )
+                .unwrap(),
+            ]
+        );
+
+        // an aggregation refers to its result
+        assert_eq!(
+            collect("select c, sum(a) as s from t group by c window fixed(60 minutes) order by sum(a) desc, c")
+                .await,
+            output
+        );
+
+        assert_eq!(
+            DataFrame::from_sql(&Context, "select a from t order by a")
+                .err()
+                .unwrap()
+                .to_string(),
+            "ORDER BY is only supported directly on the results of an aggregation with a window."
+        );
+
+        // the results of a window can't be sorted after they are projected
+        let df = DataFrame::from_sql(
+            &Context,
+            "select c, sum(a) as s from t group by c window fixed(60 minutes)",
+        )
+        .unwrap();
+        assert!(df
+            .select(vec![col("s")])
+            .order_by(vec![(col("s"), true)])
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_top_n() {
+        struct Context;
+
+        impl SqlContext for Context {
+            fn create_source_provider(&self, _name: &str) -> Result<Option<SqlSourceProvider>> {
+                Ok(Some(SqlSourceProvider {
+                    source_provider: Arc::new(SourceProviderWrapper(create_source_provider())),
+                    time_expr: Some(col("time")),
+                }))
+            }
+        }
+
+        let output_schema = Arc::new(
+            Schema::try_new(vec![
+                Field::new("c", DataType::String),
+                Field::new("s", DataType::Float64),
+                Field::new("@time", DataType::Timestamp(None)),
+            ])
+            .unwrap(),
+        );
+        let collect = |sql: &'static str| async move {
+            DataFrame::from_sql(&Context, sql)
+                .unwrap()
+                .into_stream(None)
+                .unwrap()
+                .map(Result::unwrap)
+                .collect::<Vec<_>>()
+                .await
+        };
+        let dataset = |data: &[u8]| {
+            DataSet::from_csv_slice(output_schema.clone(), CsvOptions::default(), data).unwrap()
+        };
+
+        // the limit applies to each window
+        assert_eq!(
+            collect(
+                "select c, sum(a) as s from t group by c window fixed(60 minutes) order by s desc limit 2"
+            )
+            .await,
+            vec![
+                dataset(b"b,56,1622509200000\na,10,1622509200000\n"),
+                dataset(b"d,141,1622512800000\nc,132,1622512800000\n"),
+            ]
+        );
+        assert_eq!(
+            collect(
+                "select c, sum(a) as s from t group by c window fixed(60 minutes) order by s desc limit 2 offset 1"
+            )
+            .await,
+            vec![
+                dataset(b"a,10,1622509200000\n"),
+                dataset(b"c,132,1622512800000\nb,12,1622512800000\n"),
+            ]
+        );
+
+        // a window without any row after the offset is not emitted
+        assert_eq!(
+            collect(
+                "select c, sum(a) as s from t group by c window fixed(60 minutes) order by c limit 1 offset 2"
+            )
+            .await,
+            vec![dataset(b"d,141,1622512800000\n")]
+        );
+
+        // the first rows of each updated window are emitted again
+        assert_eq!(
+            collect(
+                "select c, sum(a) as s from t group by c window fixed(60 minutes) emit changes order by s desc limit 1"
+            )
+            .await,
+            vec![
+                dataset(b"b,45,1622509200000\n"),
+                dataset(b"b,56,1622509200000\n"),
+                dataset(b"c,132,1622512800000\n"),
+                dataset(b"d,141,1622512800000\n"),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_top_n_many_groups() {
+        let schema = Arc::new(
+            Schema::try_new(vec![
+                Field::new("time", DataType::Timestamp(None)),
+                Field::new("a", DataType::Int64),
+            ])
+            .unwrap(),
+        );
+        let data = (0..3000).map(|a| format!("0,{}\n", a)).collect::<String>();
+        let ctx = MemoryContext {
+            source_provider: memory_source(schema, &data, 500),
+            time_expr: Some(col("time")),
+        };
+        let df = DataFrame::from_sql(
+            &ctx,
+            "select a from t group by a window fixed(1 minutes) order by a desc limit 2 offset 1",
+        )
+        .unwrap();
+        let output_schema = Arc::new(
+            Schema::try_new(vec![
+                Field::new("a", DataType::Int64),
+                Field::new("@time", DataType::Timestamp(None)),
+            ])
+            .unwrap(),
+        );
+        assert_eq!(
+            collect_into_vec(df).await.unwrap(),
+            vec![DataSet::from_csv_slice(
+                output_schema,
+                CsvOptions::default(),
+                b"2998,0\n2997,0\n"
+            )
+            .unwrap()]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_having() {
+        struct Context;
+
+        impl SqlContext for Context {
+            fn create_source_provider(&self, _name: &str) -> Result<Option<SqlSourceProvider>> {
+                Ok(Some(SqlSourceProvider {
+                    source_provider: Arc::new(SourceProviderWrapper(create_source_provider())),
+                    time_expr: Some(col("time")),
+                }))
+            }
+        }
+
+        let output_schema = Arc::new(
+            Schema::try_new(vec![
+                Field::new("c", DataType::String),
+                Field::new("s", DataType::Float64),
+                Field::new("@time", DataType::Timestamp(None)),
+            ])
+            .unwrap(),
+        );
+        let collect = |sql: &'static str| async move {
+            DataFrame::from_sql(&Context, sql)
+                .unwrap()
+                .into_stream(None)
+                .unwrap()
+                .map(Result::unwrap)
+                .collect::<Vec<_>>()
+                .await
+        };
+        let dataset = |data: &[u8]| {
+            DataSet::from_csv_slice(output_schema.clone(), CsvOptions::default(), data).unwrap()
+        };
+
+        // the aggregation that is not selected is still computed for each group
+        assert_eq!(
+            collect(
+                "select c, sum(a) as s from t group by c having count(b) > 5 window fixed(60 minutes)"
+            )
+            .await,
+            vec![
+                dataset(b"b,56,1622509200000\n"),
+                dataset(b"c,132,1622512800000\nd,141,1622512800000\n"),
+            ]
+        );
+
+        // the aliases and the selected aggregations refer to the results, and a window without
+        // any matching row is not emitted
+        for sql in &[
+            "select c, sum(a) as s from t group by c having s > 100 window fixed(60 minutes) order by s desc",
+            "select c, sum(a) as s from t group by c having sum(a) > 100 and c <> 'b' window fixed(60 minutes) order by s desc",
+        ] {
+            assert_eq!(
+                collect(sql).await,
+                vec![dataset(b"d,141,1622512800000\nc,132,1622512800000\n")]
+            );
+        }
+
+        assert_eq!(
+            DataFrame::from_sql(&Context, "select a from t having a > 1")
+                .err()
+                .unwrap()
+                .to_string(),
+            "HAVING is only supported on the results of a window."
+        );
+    }
+
+    #[tokio::test]
+    async fn test_source_alias() {
+        struct Context;
+
+        impl SqlContext for Context {
+            fn create_source_provider(&self, name: &str) -> Result<Option<SqlSourceProvider>> {
+                assert_eq!(name, "t");
+                Ok(Some(SqlSourceProvider {
+                    source_provider: Arc::new(SourceProviderWrapper(create_source_provider())),
+                    time_expr: Some(col("time")),
+                }))
+            }
+        }
+
+        let df = DataFrame::from_sql(&Context, "select x.a, x.b as b from t as x").unwrap();
+        let output_schema = Arc::new(
+            Schema::try_new(vec![
+                Field {
+                    qualifier: Some("x".to_string()),
+                    name: "a".to_string(),
+                    data_type: DataType::Int64,
+                },
+                Field::new("b", DataType::String),
+            ])
+            .unwrap(),
+        );
+
+        let mut stream = df.into_stream(None).unwrap();
+        assert_eq!(stream.schema(), output_schema);
+        assert_eq!(
+            stream.next().await.unwrap().unwrap().slice(0, 2),
+            DataSet::from_csv_slice(
+                output_schema,
+                CsvOptions::default(),
+                br#"
+1,a
+2,b
+"#,
+            )
+            .unwrap()
+        );
+
+        assert_eq!(
+            DataFrame::from_sql(&Context, "select y.a from t as x")
+                .unwrap()
+                .into_stream(None)
+                .err()
+                .unwrap()
+                .to_string(),
+            "not such column: 'y.a'"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_filter_time_column() {
+        struct Context;
+
+        impl SqlContext for Context {
+            fn create_source_provider(&self, _name: &str) -> Result<Option<SqlSourceProvider>> {
+                Ok(Some(SqlSourceProvider {
+                    source_provider: Arc::new(SourceProviderWrapper(create_source_provider())),
+                    time_expr: Some(col("time")),
+                }))
+            }
+        }
+
+        let df = DataFrame::from_sql(
+            &Context,
+            "select a, @time from t where @time > timestamp '2021-06-01 02:09:00'",
+        )
+        .unwrap();
+        let output_schema = Arc::new(
+            Schema::try_new(vec![
+                Field::new("a", DataType::Int64),
+                Field::new("@time", DataType::Timestamp(None)),
+            ])
+            .unwrap(),
+        );
+
+        let mut stream = df.into_stream(None).unwrap();
+        assert_eq!(
+            stream.next().await.unwrap().unwrap(),
+            DataSet::from_csv_slice(
+                output_schema,
+                CsvOptions::default(),
+                br#"
+22,1622513400000
+23,1622513460000
+24,1622513520000
+25,1622513580000
+26,1622513640000
+"#,
+            )
+            .unwrap()
+        );
+        assert!(stream.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_join() {
+        struct Context;
+
+        impl SqlContext for Context {
+            fn create_source_provider(&self, name: &str) -> Result<Option<SqlSourceProvider>> {
+                let dim_schema = Arc::new(
+                    Schema::try_new(vec![
+                        Field::new("c", DataType::String),
+                        Field::new("label", DataType::String),
+                    ])
+                    .unwrap(),
+                );
+                let dim_provider = |tail| {
+                    Provider::new_from_memory(
+                        Options {
+                            delimiter: b',',
+                            has_header: false,
+                            batch_size: 2,
+                            tail,
+                            poll_interval: 1000,
+                        },
+                        dim_schema.clone(),
+                        "a,first\nb,second\nc,third\n",
+                    )
+                };
+                Ok(Some(match name {
+                    "t" => SqlSourceProvider {
+                        source_provider: Arc::new(SourceProviderWrapper(create_source_provider())),
+                        time_expr: Some(col("time")),
+                    },
+                    "dim" => SqlSourceProvider {
+                        source_provider: Arc::new(SourceProviderWrapper(dim_provider(false))),
+                        time_expr: None,
+                    },
+                    _ => SqlSourceProvider {
+                        source_provider: Arc::new(SourceProviderWrapper(dim_provider(true))),
+                        time_expr: None,
+                    },
+                }))
+            }
+        }
+
+        let output_schema = Arc::new(
+            Schema::try_new(vec![
+                Field::new("a", DataType::Int64).with_qualifier("t"),
+                Field::new("label", DataType::String).with_qualifier("dim"),
+            ])
+            .unwrap(),
+        );
+        let df = DataFrame::from_sql(
+            &Context,
+            "select t.a, label from t join dim on dim.c = t.c where t.a % 4 = 0",
+        )
+        .unwrap();
+
+        // the rows of 'd' have no match, so the last dataset of the left side is dropped
+        let output = df
+            .clone()
+            .into_stream(None)
+            .unwrap()
+            .map(Result::unwrap)
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(
+            output,
+            vec![
+                DataSet::from_csv_slice(
+                    output_schema.clone(),
+                    CsvOptions::default(),
+                    b"4,first\n8,second\n"
+                )
+                .unwrap(),
+                DataSet::from_csv_slice(
+                    output_schema.clone(),
+                    CsvOptions::default(),
+                    b"12,second\n16,third\n20,third\n"
+                )
+                .unwrap(),
+            ]
+        );
+
+        // the right side is read again when the stream is restored
+        let mut stream = df.clone().into_stream(None).unwrap();
+        stream.next().await.unwrap().unwrap();
+        let state = stream.save_state().unwrap();
+        let mut stream = df.into_stream(Some(state)).unwrap();
+        assert_eq!(stream.next().await.unwrap().unwrap(), output[1]);
+        assert!(stream.next().await.is_none());
+
+        // the joined rows can be aggregated by the time of the left side
+        let output = DataFrame::from_sql(
+            &Context,
+            "select label, count(t.a) from t join dim on t.c = dim.c group by label window fixed(60 minutes)",
+        )
+        .unwrap()
+        .into_stream(None)
+        .unwrap()
+        .map(Result::unwrap)
+        .collect::<Vec<_>>()
+        .await;
+        assert_eq!(output.iter().map(DataSet::len).sum::<usize>(), 4);
+
+        let error = |sql: &str| {
+            DataFrame::from_sql(&Context, sql)
+                .and_then(|df| df.into_stream(None))
+                .err()
+                .unwrap()
+                .to_string()
+        };
+        assert_eq!(
+            error("select * from t join tail on t.c = tail.c"),
+            "the right side of a join must be bounded, because it is read to the end before the left side."
+        );
+        assert_eq!(
+            error("select * from tail full join dim on tail.c = dim.c"),
+            "the left side of a right or full join must be bounded or the join must have a refresh interval, because the rows of the right side without a match are emitted when the left side ends or the right side is read again."
+        );
+        assert_eq!(
+            error("select * from t join dim on t.a = dim.c"),
+            "the join keys have incompatible types: int64 and string"
+        );
+        assert_eq!(
+            error("select * from t join dim on t.a > 1"),
+            "the join condition must be an equality, but got '(t.a > 1)'."
+        );
+        assert_eq!(
+            error("select * from t join dim on t.c = t.b"),
+            "the join condition 't.c = t.b' must compare an expression of the left side with an expression of the right side."
+        );
+    }
+
+    #[tokio::test]
+    async fn test_time_expr_type() {
+        let schema = Arc::new(
+            Schema::try_new(vec![
+                Field::new("ts", DataType::Int64),
+                Field::new("user", DataType::String),
+                Field::new("a", DataType::Int64),
+            ])
+            .unwrap(),
+        );
+        let data = "1622509200000,a,1\n1622509260000,b,2\n";
+        let create_ctx = |time_expr| MemoryContext {
+            source_provider: memory_source(schema.clone(), data, 2),
+            time_expr: Some(time_expr),
+        };
+
+        // the integers are milliseconds since the unix epoch
+        let df = DataFrame::from_sql(
+            &create_ctx(col("ts")),
+            "select sum(a) as s from t window fixed(1 minutes) watermark by ts",
+        )
+        .unwrap();
+        assert_eq!(
+            collect_into_vec(df).await.unwrap(),
+            vec![
+                DataSet::from_csv_slice(
+                    Arc::new(
+                        Schema::try_new(vec![
+                            Field::new("s", DataType::Float64),
+                            Field::new("@time", DataType::Timestamp(None)),
+                        ])
+                        .unwrap()
+                    ),
+                    CsvOptions::default(),
+                    b"1,1622509200000\n"
+                )
+                .unwrap(),
+                DataSet::from_csv_slice(
+                    Arc::new(
+                        Schema::try_new(vec![
+                            Field::new("s", DataType::Float64),
+                            Field::new("@time", DataType::Timestamp(None)),
+                        ])
+                        .unwrap()
+                    ),
+                    CsvOptions::default(),
+                    b"2,1622509260000\n"
+                )
+                .unwrap(),
+            ]
+        );
+
+        let df = DataFrame::from_sql(&create_ctx(col("user")), "select a from t").unwrap();
+        assert_eq!(
+            df.into_stream(None).err().unwrap().to_string(),
+            "the time expression 'user' must return a timestamp or an integer, but it returns string."
+        );
+
+        let df = DataFrame::from_sql(
+            &create_ctx(col("ts")),
+            "select sum(a) from t window fixed(1 minutes) watermark by user",
+        )
+        .unwrap();
+        assert_eq!(
+            df.into_stream(None).err().unwrap().to_string(),
+            "the watermark expression 'user' must return a timestamp or an integer, but it returns string."
+        );
+    }
+
+    #[tokio::test]
+    async fn test_cast_string_column() {
+        let schema = Arc::new(
+            Schema::try_new(vec![
+                Field::new("time", DataType::Timestamp(None)),
+                Field::new("price", DataType::String),
+            ])
+            .unwrap(),
+        );
+        let ctx = MemoryContext {
+            source_provider: memory_source(
+                schema,
+                "1622509200000,1.5\n1622509260000,abc\n1622509320000,20\n",
+                10,
+            ),
+            time_expr: Some(col("time")),
+        };
+        let df = DataFrame::from_sql(
+            &ctx,
+            "select cast(price as float64) as price from t where cast(price as float64) > 1",
+        )
+        .unwrap();
+        assert_eq!(
+            collect_into_vec(df).await.unwrap(),
+            vec![DataSet::from_csv_slice(
+                Arc::new(Schema::try_new(vec![Field::new("price", DataType::Float64)]).unwrap()),
+                CsvOptions::default(),
+                b"1.5\n20\n"
+            )
+            .unwrap()]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_cross_join_unnest() {
+        let schema = Arc::new(
+            Schema::try_new(vec![
+                Field::new("a", DataType::Int64),
+                Field::new("tags", DataType::String),
+            ])
+            .unwrap(),
+        );
+        let ctx = MemoryContext {
+            source_provider: memory_source(schema, "1,x;y;z\n2,u;v;w\n", 10),
+            time_expr: None,
+        };
+        let df = DataFrame::from_sql(
+            &ctx,
+            "select a, tag from t cross join unnest(string_to_array(tags, ';')) as tag where a > 1",
+        )
+        .unwrap();
+        assert_eq!(
+            collect_into_vec(df).await.unwrap(),
+            vec![DataSet::from_csv_slice(
+                Arc::new(
+                    Schema::try_new(vec![
+                        Field::new("a", DataType::Int64).with_qualifier("t"),
+                        Field::new("tag", DataType::String),
+                    ])
+                    .unwrap()
+                ),
+                CsvOptions::default(),
+                b"2,u\n2,v\n2,w\n"
+            )
+            .unwrap()]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_between() {
+        let schema = Arc::new(
+            Schema::try_new(vec![
+                Field::new("time", DataType::Timestamp(None)),
+                Field::new("v", DataType::Int64),
+            ])
+            .unwrap(),
+        );
+        let data = "1622509200000,5\n1622509260000,10\n1622509320000,15\n1622509380000,20\n1622509440000,25\n";
+        let ctx = MemoryContext {
+            source_provider: memory_source(schema, data, 10),
+            time_expr: Some(col("time")),
+        };
+        let query = |sql: &str| {
+            let df = DataFrame::from_sql(&ctx, sql).unwrap();
+            async move { collect_into_vec(df).await.unwrap() }
+        };
+
+        let between = query("select v from t where v between 10 and 20").await;
+        assert_eq!(
+            between,
+            query("select v from t where v >= 10 and v <= 20").await
+        );
+        assert_eq!(
+            between,
+            vec![DataSet::from_csv_slice(
+                Arc::new(Schema::try_new(vec![Field::new("v", DataType::Int64)]).unwrap()),
+                CsvOptions::default(),
+                b"10\n15\n20\n"
+            )
+            .unwrap()]
+        );
+        assert_eq!(
+            query("select v from t where v not between 10 and 20").await,
+            query("select v from t where v < 10 or v > 20").await
+        );
+    }
+
+    #[tokio::test]
+    async fn test_null_column() {
+        let schema = Arc::new(
+            Schema::try_new(vec![
+                Field::new("time", DataType::Timestamp(None)),
+                Field::new("a", DataType::Int64),
+            ])
+            .unwrap(),
+        );
+        let ctx = MemoryContext {
+            source_provider: memory_source(schema, "1622509200000,1\n1622509260000,2\n", 10),
+            time_expr: Some(col("time")),
+        };
+
+        // the cast always fails, so the column is a single null instead of a null for each row
+        let df = DataFrame::from_sql(&ctx, "select a, cast('x' as int64) as n from t").unwrap();
+        let output = collect_into_vec(df).await.unwrap();
+        assert_eq!(output.len(), 1);
+        let column = output[0].column(1).unwrap();
+        assert_eq!(column.len(), 2);
+        assert_eq!(column.downcast_ref::<Int64Array>().to_scalar(), Some(None));
+
+        let df = DataFrame::from_sql(
+            &ctx,
+            "select a + n as b, n * 2 as c from (select a, cast('x' as int64) as n from t)",
+        )
+        .unwrap();
+        assert_eq!(
+            collect_into_vec(df).await.unwrap(),
+            vec![DataSet::try_from_rows(
+                Arc::new(
+                    Schema::try_new(vec![
+                        Field::new("b", DataType::Int64),
+                        Field::new("c", DataType::Int64),
+                    ])
+                    .unwrap()
+                ),
+                vec![
+                    vec![Scalar::Null, Scalar::Null],
+                    vec![Scalar::Null, Scalar::Null]
+                ],
+            )
+            .unwrap()]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_window_bounds() {
+        let schema = Arc::new(
+            Schema::try_new(vec![
+                Field::new("time", DataType::Timestamp(None)),
+                Field::new("a", DataType::Int64),
+            ])
+            .unwrap(),
+        );
+        let data = "1622509200000,1\n1622509230000,2\n1622509270000,3\n";
+        let ctx = MemoryContext {
+            source_provider: memory_source(schema, data, 10),
+            time_expr: Some(col("time")),
+        };
+        let collect = |sql: &'static str| {
+            let df = DataFrame::from_sql(&ctx, sql).unwrap();
+            async move {
+                collect_into_vec(df)
+                    .await
+                    .unwrap()
+                    .iter()
+                    .map(|dataset| dataset.display_csv().unwrap())
+                    .collect::<Vec<_>>()
+            }
+        };
+
+        assert_eq!(
+            collect("select @window_start, @window_end, count(a) as n from t window fixed(1m)")
+                .await,
+            vec![
+                "@window_start,@window_end,n,@time\n2021-06-01T01:00:00+00:00,2021-06-01T01:01:00+00:00,2,2021-06-01T01:00:00+00:00\n",
+                "@window_start,@window_end,n,@time\n2021-06-01T01:01:00+00:00,2021-06-01T01:02:00+00:00,1,2021-06-01T01:01:00+00:00\n",
+            ]
+        );
+        assert_eq!(
+            collect("select @window_end, sum(a) as s from t window session(45s)").await,
+            vec!["@window_end,s,@time\n2021-06-01T01:01:55+00:00,6.0,2021-06-01T01:00:00+00:00\n"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_outer_join() {
+        struct Context;
+
+        impl SqlContext for Context {
+            fn create_source_provider(&self, name: &str) -> Result<Option<SqlSourceProvider>> {
+                Ok(Some(match name {
+                    "t" => SqlSourceProvider {
+                        source_provider: memory_source(
+                            Arc::new(Schema::try_new(vec![
+                                Field::new("time", DataType::Timestamp(None)),
+                                Field::new("c", DataType::String),
+                                Field::new("a", DataType::Int64),
+                            ])?),
+                            "1622509200000,a,1\n1622509260000,b,2\n1622509320000,x,3\n",
+                            10,
+                        ),
+                        time_expr: Some(col("time")),
+                    },
+                    _ => SqlSourceProvider {
+                        source_provider: memory_source(
+                            Arc::new(Schema::try_new(vec![
+                                Field::new("c", DataType::String),
+                                Field::new("label", DataType::String),
+                            ])?),
+                            "a,first\nb,second\nc,third\n",
+                            10,
+                        ),
+                        time_expr: None,
+                    },
+                }))
+            }
+        }
+
+        let output_schema = Arc::new(
+            Schema::try_new(vec![
+                Field::new("a", DataType::Int64).with_qualifier("t"),
+                Field::new("label", DataType::String).with_qualifier("dim"),
+            ])
+            .unwrap(),
+        );
+        let run = |join_type: &str| {
+            let df = DataFrame::from_sql(
+                &Context,
+                &format!(
+                    "select t.a, label from t {} join dim on t.c = dim.c",
+                    join_type
+                ),
+            )
+            .unwrap();
+            async move { collect_into_vec(df).await.unwrap() }
+        };
+        let rows = |rows: Vec<(Scalar, Scalar)>| {
+            DataSet::try_from_rows(
+                output_schema.clone(),
+                rows.into_iter().map(|(a, label)| vec![a, label]).collect(),
+            )
+            .unwrap()
+        };
+        let matched = || {
+            vec![
+                (1i64.into(), "first".into()),
+                (2i64.into(), "second".into()),
+            ]
+        };
+        let unmatched_left = (3i64.into(), Scalar::Null);
+        let unmatched_right = (Scalar::Null, "third".into());
+
+        assert_eq!(run("inner").await, vec![rows(matched())]);
+        assert_eq!(
+            run("left").await,
+            vec![rows(vec![
+                matched()[0].clone(),
+                matched()[1].clone(),
+                unmatched_left.clone()
+            ])]
+        );
+
+        // the rows of the right side without a match are emitted when the left side ends
+        assert_eq!(
+            run("right outer").await,
+            vec![rows(matched()), rows(vec![unmatched_right.clone()])]
+        );
+        assert_eq!(
+            run("full").await,
+            vec![
+                rows(vec![
+                    matched()[0].clone(),
+                    matched()[1].clone(),
+                    unmatched_left
+                ]),
+                rows(vec![unmatched_right])
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_join_refresh() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        use futures_util::stream::BoxStream;
+
+        use crate::dataset::SchemaRef;
+        use crate::{GenericSourceDataSet, GenericSourceProvider};
+
+        /// A reference table that has a new label each time it is read.
+        struct Versioned(AtomicUsize);
+
+        impl GenericSourceProvider for Versioned {
+            type State = ();
+
+            fn provider_name(&self) -> &'static str {
+                "versioned"
+            }
+
+            fn schema(&self) -> Result<SchemaRef> {
+                Ok(Arc::new(Schema::try_new(vec![
+                    Field::new("c", DataType::String),
+                    Field::new("label", DataType::String),
+                ])?))
+            }
+
+            fn is_bounded(&self) -> bool {
+                true
+            }
+
+            fn create_stream(
+                &self,
+                _state: Option<Self::State>,
+            ) -> Result<BoxStream<'static, Result<GenericSourceDataSet<Self::State>>>> {
+                let version = self.0.fetch_add(1, Ordering::SeqCst);
+                let dataset = DataSet::try_from_rows(
+                    self.schema()?,
+                    vec![vec!["a".into(), format!("v{}", version).into()]],
+                )?;
+                Ok(Box::pin(futures_util::stream::iter(vec![Ok(
+                    GenericSourceDataSet { state: (), dataset },
+                )])))
+            }
+        }
+
+        struct Context(SourceProvider);
+
+        impl SqlContext for Context {
+            fn create_source_provider(&self, name: &str) -> Result<Option<SqlSourceProvider>> {
+                let schema = Arc::new(Schema::try_new(vec![
+                    Field::new("time", DataType::Timestamp(None)),
+                    Field::new("c", DataType::String),
+                ])?);
+                Ok(Some(match name {
+                    "t" => SqlSourceProvider {
+                        source_provider: memory_source(
+                            schema,
+                            "1622509200000,a\n1622509260000,a\n1622509320000,a\n",
+                            1,
+                        ),
+                        time_expr: Some(col("time")),
+                    },
+                    _ => SqlSourceProvider {
+                        source_provider: self.0.clone(),
+                        time_expr: None,
+                    },
+                }))
+            }
+        }
+
+        let output_schema = Arc::new(
+            Schema::try_new(vec![
+                Field::new("label", DataType::String).with_qualifier("dim")
+            ])
+            .unwrap(),
+        );
+        let run = |sql: &str| {
+            let ctx = Context(Arc::new(SourceProviderWrapper(Versioned(
+                AtomicUsize::new(0),
+            ))));
+            let df = DataFrame::from_sql(&ctx, sql).unwrap();
+            async move { collect_into_vec(df).await.unwrap() }
+        };
+        let labels = |labels: &[u8]| {
+            DataSet::from_csv_slice(output_schema.clone(), CsvOptions::default(), labels).unwrap()
+        };
+
+        // without a refresh interval, the right side is read once
+        assert_eq!(
+            run("select label from t join dim on t.c = dim.c").await,
+            vec![labels(b"v0\n"), labels(b"v0\n"), labels(b"v0\n")]
+        );
+
+        // the table is older than the interval before each dataset of the left side
+        assert_eq!(
+            run("select label from t join dim on t.c = dim.c refresh 0ms").await,
+            vec![labels(b"v0\n"), labels(b"v1\n"), labels(b"v2\n")]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_outer_join_unbounded() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        use futures_util::stream::BoxStream;
+
+        use crate::dataset::SchemaRef;
+        use crate::{GenericSourceDataSet, GenericSourceProvider};
+
+        /// A stream of rows that never ends.
+        struct Unbounded;
+
+        impl GenericSourceProvider for Unbounded {
+            type State = ();
+
+            fn provider_name(&self) -> &'static str {
+                "unbounded"
+            }
+
+            fn schema(&self) -> Result<SchemaRef> {
+                Ok(Arc::new(Schema::try_new(vec![
+                    Field::new("time", DataType::Timestamp(None)),
+                    Field::new("c", DataType::String),
+                    Field::new("a", DataType::Int64),
+                ])?))
+            }
+
+            fn is_bounded(&self) -> bool {
+                false
+            }
+
+            fn create_stream(
+                &self,
+                _state: Option<Self::State>,
+            ) -> Result<BoxStream<'static, Result<GenericSourceDataSet<Self::State>>>> {
+                let datasets = [
+                    "1622509200000,a,1\n",
+                    "1622509260000,x,2\n",
+                    "1622509320000,b,3\n",
+                ]
+                .iter()
+                .map(|data| {
+                    let dataset = DataSet::from_csv_slice(
+                        self.schema()?,
+                        CsvOptions::default(),
+                        data.as_bytes(),
+                    )?;
+                    Ok(GenericSourceDataSet { state: (), dataset })
+                })
+                .collect::<Vec<_>>();
+                Ok(Box::pin(
+                    futures_util::stream::iter(datasets).chain(futures_util::stream::pending()),
+                ))
+            }
+        }
+
+        /// A reference table that has new labels each time it is read.
+        struct Versioned(AtomicUsize);
+
+        impl GenericSourceProvider for Versioned {
+            type State = ();
+
+            fn provider_name(&self) -> &'static str {
+                "versioned"
+            }
+
+            fn schema(&self) -> Result<SchemaRef> {
+                Ok(Arc::new(Schema::try_new(vec![
+                    Field::new("c", DataType::String),
+                    Field::new("label", DataType::String),
+                ])?))
+            }
+
+            fn is_bounded(&self) -> bool {
+                true
+            }
+
+            fn create_stream(
+                &self,
+                _state: Option<Self::State>,
+            ) -> Result<BoxStream<'static, Result<GenericSourceDataSet<Self::State>>>> {
+                let version = self.0.fetch_add(1, Ordering::SeqCst);
+                let dataset = DataSet::try_from_rows(
+                    self.schema()?,
+                    vec![
+                        vec!["a".into(), format!("a{}", version).into()],
+                        vec!["b".into(), format!("b{}", version).into()],
+                    ],
+                )?;
+                Ok(Box::pin(futures_util::stream::iter(vec![Ok(
+                    GenericSourceDataSet { state: (), dataset },
+                )])))
+            }
+        }
+
+        struct Context(SourceProvider);
+
+        impl SqlContext for Context {
+            fn create_source_provider(&self, name: &str) -> Result<Option<SqlSourceProvider>> {
+                Ok(Some(match name {
+                    "t" => SqlSourceProvider {
+                        source_provider: Arc::new(SourceProviderWrapper(Unbounded)),
+                        time_expr: Some(col("time")),
+                    },
+                    _ => SqlSourceProvider {
+                        source_provider: self.0.clone(),
+                        time_expr: None,
+                    },
+                }))
+            }
+        }
+
+        let output_schema = Arc::new(
+            Schema::try_new(vec![
+                Field::new("a", DataType::Int64).with_qualifier("t"),
+                Field::new("label", DataType::String).with_qualifier("dim"),
+            ])
+            .unwrap(),
+        );
+        let run = |join_type: &str, count: usize| {
+            let ctx = Context(Arc::new(SourceProviderWrapper(Versioned(
+                AtomicUsize::new(0),
+            ))));
+            let df = DataFrame::from_sql(
+                &ctx,
+                &format!(
+                    "select t.a, label from t {} join dim on t.c = dim.c refresh 0ms",
+                    join_type
+                ),
+            )
+            .unwrap();
+            async move {
+                df.into_stream(None)
+                    .unwrap()
+                    .map(Result::unwrap)
+                    .take(count)
+                    .collect::<Vec<_>>()
+                    .await
+            }
+        };
+        let rows = |rows: &[(Option<i64>, Option<&str>)]| {
+            DataSet::try_from_rows(
+                output_schema.clone(),
+                rows.iter()
+                    .map(|(a, label)| {
+                        vec![
+                            a.map(Scalar::from).unwrap_or(Scalar::Null),
+                            label.map(Scalar::from).unwrap_or(Scalar::Null),
+                        ]
+                    })
+                    .collect(),
+            )
+            .unwrap()
+        };
+
+        // each table is used for a dataset of the left side, then the rows of it without a match
+        // are emitted when it is replaced by the next one
+        assert_eq!(
+            run("right", 4).await,
+            vec![
+                rows(&[(Some(1), Some("a0"))]),
+                rows(&[(None, Some("b0"))]),
+                rows(&[(Some(3), Some("b1"))]),
+                rows(&[(None, Some("a1"))])
+            ]
+        );
+        assert_eq!(
+            run("full", 5).await,
+            vec![
+                rows(&[(Some(1), Some("a0"))]),
+                rows(&[(None, Some("b0"))]),
+                rows(&[(Some(2), None)]),
+                rows(&[(None, Some("a1")), (None, Some("b1"))]),
+                rows(&[(Some(3), Some("b2"))])
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sub_query() {
+        struct Context;
+
+        impl SqlContext for Context {
+            fn create_source_provider(&self, name: &str) -> Result<Option<SqlSourceProvider>> {
+                let dim_schema = Arc::new(
+                    Schema::try_new(vec![
+                        Field::new("c", DataType::String),
+                        Field::new("label", DataType::String),
+                    ])
+                    .unwrap(),
+                );
+                let dim_provider = |tail| {
+                    Provider::new_from_memory(
+                        Options {
+                            delimiter: b',',
+                            has_header: false,
+                            batch_size: 2,
+                            tail,
+                            poll_interval: 1000,
+                        },
+                        dim_schema.clone(),
+                        "a,first\nb,second\nc,third\nc,fourth\n",
+                    )
+                };
+                Ok(Some(match name {
+                    "t" => SqlSourceProvider {
+                        source_provider: Arc::new(SourceProviderWrapper(create_source_provider())),
+                        time_expr: Some(col("time")),
+                    },
+                    "dim" => SqlSourceProvider {
+                        source_provider: Arc::new(SourceProviderWrapper(dim_provider(false))),
+                        time_expr: None,
+                    },
+                    _ => SqlSourceProvider {
+                        source_provider: Arc::new(SourceProviderWrapper(dim_provider(true))),
+                        time_expr: None,
+                    },
+                }))
+            }
+        }
+
+        // the rows of 'd' have no match, and the first row of 'c' wins
+        let df = DataFrame::from_sql(
+            &Context,
+            "select a, (select label from dim where dim.c = c) as label, (select ucase(label) from dim where c = dim.c) from t where a % 5 = 0",
+        )
+        .unwrap();
+        let output = collect_into_vec(df)
+            .await
+            .unwrap()
+            .iter()
+            .map(|dataset| dataset.display_csv().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            output,
+            vec![
+                "a,label,ucase(label)\n5,second,SECOND\n10,second,SECOND\n",
+                "a,label,ucase(label)\n15,third,THIRD\n20,third,THIRD\n",
+                "a,label,ucase(label)\n25,,\n",
+            ]
+        );
+
+        let error = |sql| {
+            DataFrame::from_sql(&Context, sql)
+                .and_then(|df| df.into_stream(None))
+                .err()
+                .unwrap()
+                .to_string()
+        };
+        assert_eq!(
+            error("select (select label from tail where tail.c = c) from t"),
+            "the source of a subquery must be bounded, because it is read to the end before the outer query."
+        );
+        assert_eq!(
+            error("select (select label from dim where dim.c > c) from t"),
+            "the where clause of a subquery must be an equality between the subquery and the outer query."
+        );
+        assert_eq!(
+            error("select (select c, label from dim where dim.c = c) from t"),
+            "a subquery must select exactly one field."
+        );
+        assert_eq!(
+            error("select a from t where (select label from dim where dim.c = c) = 'first'"),
+            "a subquery can only be a field of the projection"
+        );
+    }
 }
@@ -1,32 +1,39 @@
-use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
 
 use anyhow::Result;
 use futures_util::{Stream, StreamExt};
-use indexmap::IndexMap;
+use indexmap::{IndexMap, IndexSet};
 use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 
+use crate::array::compute::{self, SortOptions};
 use crate::array::{
-    ArrayExt, ArrayRef, BooleanBuilder, BooleanType, DataType, Float32Type, Float64Type, Int16Type,
-    Int32Type, Int64Type, Int8Type, NullArray, PrimitiveBuilder, Scalar, StringBuilder,
-    TimestampArray, TimestampType,
+    ArrayExt, ArrayRef, BooleanArray, BooleanBuilder, BooleanType, DataType, Float32Type,
+    Float64Type, Int16Type, Int32Type, Int64Type, Int8Type, NullArray, PrimitiveBuilder, Scalar,
+    StringBuilder, TimestampArray, TimestampBuilder, TimestampType, UInt16Type, UInt32Type,
+    UInt64Type, UInt8Type,
 };
-use crate::dataset::{DataSet, SchemaRef};
+use crate::dataset::{DataSet, Schema, SchemaRef};
 use crate::execution::dataset::{DataSetExt, GroupedKey};
 use crate::execution::stream::{BoxDataSetStream, CreateStreamContext, DataSetStream};
-use crate::execution::streams::create_stream;
+use crate::execution::streams::{create_stream, to_timestamps};
 use crate::expr::physical_expr::PhysicalExpr;
 use crate::expr::ExprState;
+use crate::planner::emit::Emit;
 use crate::planner::physical_plan::PhysicalAggregateNode;
 use crate::planner::window::Window;
 
+/// The number of groups of which the results are created at a time, when only the first rows of
+/// each window are emitted.
+const LIMIT_CHUNK_SIZE: usize = 1024;
+
 macro_rules! append_primitive_value {
     ($columns:expr, $aggregate_states:expr, $index:expr, $ty:ty, $scalar_ty:ident) => {{
         let mut builder = PrimitiveBuilder::<$ty>::with_capacity($aggregate_states.len());
-        for state in $aggregate_states.values() {
+        for state in $aggregate_states {
             builder.append_opt(if let Scalar::$scalar_ty(value) = &state.values[$index] {
                 Some(*value)
             } else {
@@ -44,9 +51,18 @@ pub fn create_aggregate_stream(
     let PhysicalAggregateNode {
         id,
         schema,
+        results_schema,
+        window_schema,
         group_exprs,
         aggr_exprs,
+        pivot_filters,
+        num_hidden_exprs,
         window,
+        emit,
+        order_by,
+        limit,
+        offset,
+        having,
         time_idx,
         watermark_expr,
         input,
@@ -55,9 +71,18 @@ pub fn create_aggregate_stream(
     let mut stream = AggregateStream {
         id,
         schema,
+        results_schema,
+        window_schema,
         group_exprs,
         aggr_exprs,
+        pivot_filters,
+        num_hidden_exprs,
         window,
+        emit,
+        order_by,
+        limit,
+        offset,
+        having,
         time_idx,
         windows: Default::default(),
         new_datasets: Default::default(),
@@ -75,6 +100,11 @@ pub fn create_aggregate_stream(
 
 type SavedWindow = (i64, i64, Vec<(GroupedKey, Vec<ExprState>, Vec<Scalar>)>);
 
+/// Identifies an open window by a group key and its start time, the key is only set for the
+/// sessions since each group has its own sessions, and it is empty for the other windows, which
+/// are shared by all the groups.
+type WindowKey = (GroupedKey, i64);
+
 #[derive(Serialize, Deserialize)]
 struct SavedState {
     current_watermark: Option<i64>,
@@ -88,6 +118,29 @@ struct AggregateState {
     values: Vec<Scalar>,
 }
 
+impl AggregateState {
+    /// Merges the state of a later session of the same group, `null_row` is a row of the input in
+    /// which every value is null.
+    fn merge(&mut self, other: AggregateState, null_row: &DataSet) -> Result<()> {
+        for ((expr, value), (other_expr, other_value)) in self
+            .aggr_exprs
+            .iter_mut()
+            .zip(self.values.iter_mut())
+            .zip(other.aggr_exprs.iter().zip(other.values))
+        {
+            if !expr.is_stateful() {
+                // the columns outside of the aggregate functions have the values of the last row
+                *value = other_value;
+            } else if *value != Scalar::Null || other_value != Scalar::Null {
+                // a pivot column without matching rows in both sessions stays null
+                expr.merge(other_expr)?;
+                *value = expr.eval_current(null_row)?.scalar_value(0);
+            }
+        }
+        Ok(())
+    }
+}
+
 #[derive(Default)]
 struct WindowState {
     start_time: i64,
@@ -98,11 +151,20 @@ struct WindowState {
 struct AggregateStream {
     id: usize,
     schema: SchemaRef,
+    results_schema: SchemaRef,
+    window_schema: SchemaRef,
     group_exprs: Vec<PhysicalExpr>,
     aggr_exprs: Vec<PhysicalExpr>,
+    pivot_filters: Vec<PhysicalExpr>,
+    num_hidden_exprs: usize,
     window: Window,
+    emit: Emit,
+    order_by: Vec<(PhysicalExpr, SortOptions)>,
+    limit: Option<usize>,
+    offset: usize,
+    having: Option<PhysicalExpr>,
     time_idx: usize,
-    windows: BTreeMap<i64, WindowState>,
+    windows: BTreeMap<WindowKey, WindowState>,
     new_datasets: VecDeque<DataSet>,
     watermark_expr: Option<PhysicalExpr>,
     current_watermark: Option<i64>,
@@ -122,12 +184,17 @@ impl AggregateStream {
             expr.load_state(data)?;
         }
 
+        let is_session = matches!(self.window, Window::Session { .. });
         for (start, end, groups) in saved_state.windows {
-            let mut window_state = WindowState {
+            let new_window = || WindowState {
                 start_time: start,
                 end_time: end,
                 children: Default::default(),
             };
+            if !is_session {
+                self.windows
+                    .insert((GroupedKey::default(), start), new_window());
+            }
             for (key, expr_state, scalars) in groups {
                 let mut aggregate_state = AggregateState {
                     aggr_exprs: self.aggr_exprs.clone(),
@@ -136,25 +203,46 @@ impl AggregateStream {
                 for (expr, data) in aggregate_state.aggr_exprs.iter_mut().zip(expr_state) {
                     expr.load_state(data)?;
                 }
-                window_state.children.insert(key, aggregate_state);
+                // every group has its own sessions, so a saved session shared by several groups
+                // is split into one for each of them
+                let window_key = if is_session {
+                    (key.clone(), start)
+                } else {
+                    (GroupedKey::default(), start)
+                };
+                self.windows
+                    .entry(window_key)
+                    .or_insert_with(new_window)
+                    .children
+                    .insert(key, aggregate_state);
             }
-            self.windows.insert(start, window_state);
         }
         Ok(())
     }
 
     fn process_dataset(
         &mut self,
-        start: i64,
+        window_key: WindowKey,
         end: i64,
         grouped_key: GroupedKey,
         dataset: &DataSet,
     ) -> Result<()> {
-        let window_state = self.windows.entry(start).or_insert_with(|| WindowState {
-            start_time: start,
-            end_time: end,
-            children: Default::default(),
-        });
+        let mut pivot_datasets = Vec::with_capacity(self.pivot_filters.len());
+        for filter in &mut self.pivot_filters {
+            let flags = filter.eval(dataset)?;
+            pivot_datasets.push(dataset.filter(flags.downcast_ref::<BooleanArray>())?);
+        }
+        let pivot_offset = self.aggr_exprs.len() - self.num_hidden_exprs - pivot_datasets.len();
+
+        let start = window_key.1;
+        let window_state = self
+            .windows
+            .entry(window_key)
+            .or_insert_with(|| WindowState {
+                start_time: start,
+                end_time: end,
+                children: Default::default(),
+            });
 
         let aggregate_state = match window_state.children.get_mut(&grouped_key) {
             Some(aggregate_state) => aggregate_state,
@@ -166,11 +254,23 @@ impl AggregateStream {
                     values: vec![Scalar::Null; self.aggr_exprs.len()],
                 }),
         };
-        for (expr, scalar) in aggregate_state
+        for (idx, (expr, scalar)) in aggregate_state
             .aggr_exprs
             .iter_mut()
             .zip(aggregate_state.values.iter_mut())
+            .enumerate()
         {
+            let dataset = match idx
+                .checked_sub(pivot_offset)
+                .and_then(|pivot_idx| pivot_datasets.get(pivot_idx))
+            {
+                Some(pivot_dataset) => pivot_dataset,
+                None => dataset,
+            };
+            // a pivot column keeps its value until there are rows matching it
+            if dataset.is_empty() {
+                continue;
+            }
             let array = expr.eval(dataset)?;
             *scalar = array.scalar_value(array.len() - 1);
         }
@@ -178,11 +278,97 @@ impl AggregateStream {
         Ok(())
     }
 
+    /// Adds the session of new rows of a group, and returns the start time of the session of the
+    /// group that contains it.
+    ///
+    /// The new rows may bridge the gaps between several open sessions of the group, e.g. when they
+    /// arrive out of order, so all the sessions that they overlap are merged into one.
+    fn extend_session(&mut self, grouped_key: &GroupedKey, start: i64, end: i64) -> Result<i64> {
+        let overlapped = self
+            .windows
+            .range((grouped_key.clone(), i64::MIN)..=(grouped_key.clone(), i64::MAX))
+            .filter(|(_, window)| start < window.end_time && window.start_time < end)
+            .map(|(window_key, _)| window_key.clone())
+            .collect_vec();
+        let null_row = if overlapped.len() > 1 {
+            let num_fields = self.window_schema.fields().len();
+            Some(DataSet::try_from_rows(
+                self.window_schema.clone(),
+                vec![vec![Scalar::Null; num_fields]],
+            )?)
+        } else {
+            None
+        };
+
+        let mut window_state = WindowState {
+            start_time: start,
+            end_time: end,
+            children: Default::default(),
+        };
+        // the sessions are merged in the order of their start time
+        for window_key in overlapped {
+            let other = match self.windows.remove(&window_key) {
+                Some(other) => other,
+                None => continue,
+            };
+            window_state.start_time = window_state.start_time.min(other.start_time);
+            window_state.end_time = window_state.end_time.max(other.end_time);
+            for (grouped_key, aggregate_state) in other.children {
+                match window_state.children.get_mut(&grouped_key) {
+                    Some(merged_state) => merged_state.merge(
+                        aggregate_state,
+                        null_row.as_ref().expect("several sessions are merged"),
+                    )?,
+                    None => {
+                        window_state.children.insert(grouped_key, aggregate_state);
+                    }
+                }
+            }
+        }
+        let start = window_state.start_time;
+        self.windows
+            .insert((grouped_key.clone(), start), window_state);
+        Ok(start)
+    }
+
+    /// Aggregates the rows in the sessions of their groups.
+    ///
+    /// Every group has its own sessions, so the rows are grouped before they are split into
+    /// sessions, and the bounds of the windows are null when the group keys are evaluated.
+    fn aggregate_sessions(
+        &mut self,
+        dataset: &DataSet,
+        updated: &mut IndexSet<(WindowKey, GroupedKey)>,
+    ) -> Result<()> {
+        let schema = dataset.schema();
+        let num_fields = schema.fields().len();
+        let dataset = self.append_window_bounds(dataset.clone(), None, None)?;
+
+        for item in dataset.group_by_exprs(&mut self.group_exprs)? {
+            let (grouped_key, dataset) = item?;
+            let dataset =
+                DataSet::try_new(schema.clone(), dataset.columns()[..num_fields].to_vec())?;
+
+            for item in dataset.group_by_window(self.time_idx, &self.window)? {
+                let (start, end, dataset) = item?;
+                let start = self.extend_session(&grouped_key, start, end)?;
+                let window_key = (grouped_key.clone(), start);
+                let end = self.windows[&window_key].end_time;
+                let dataset = self.append_window_bounds(dataset, Some(start), Some(end))?;
+                if self.emit == Emit::Changes {
+                    updated.insert((window_key.clone(), grouped_key.clone()));
+                }
+                self.process_dataset(window_key, end, grouped_key.clone(), &dataset)?;
+            }
+        }
+        Ok(())
+    }
+
     fn process_watermark(&mut self, dataset: &DataSet) -> Result<DataSet> {
         let times_array = &dataset.columns()[self.time_idx];
         let times = times_array.downcast_ref::<TimestampArray>();
         let watermarks_array = match &mut self.watermark_expr {
-            Some(watermark_expr) => watermark_expr.eval(dataset)?,
+            Some(watermark_expr) => to_timestamps(watermark_expr.eval(dataset)?)?,
             None => times_array.clone(),
         };
         let watermarks = watermarks_array.downcast_ref::<TimestampArray>();
@@ -216,88 +402,267 @@ impl AggregateStream {
         dataset.filter(&flags.finish())
     }
 
+    /// Appends the bounds of the window to its rows, so they can be selected as `@window_start`
+    /// and `@window_end`.
+    fn append_window_bounds(
+        &self,
+        dataset: DataSet,
+        start: Option<i64>,
+        end: Option<i64>,
+    ) -> Result<DataSet> {
+        let mut columns = dataset.columns().to_vec();
+        columns.push(Arc::new(TimestampArray::new_scalar(dataset.len(), start)));
+        columns.push(Arc::new(TimestampArray::new_scalar(dataset.len(), end)));
+        DataSet::try_new(self.window_schema.clone(), columns)
+    }
+
     fn aggregate(&mut self, dataset: &DataSet) -> Result<Vec<DataSet>> {
         let mut datasets = Vec::new();
         let dataset = self.process_watermark(dataset)?;
+        let mut updated = IndexSet::new();
+
+        if let Window::Session { .. } = self.window {
+            self.aggregate_sessions(&dataset, &mut updated)?;
+        } else {
+            for item in dataset.group_by_window(self.time_idx, &self.window)? {
+                let (start, end, dataset) = item?;
+                let dataset = self.append_window_bounds(dataset, Some(start), Some(end))?;
+
+                for item in dataset.group_by_exprs(&mut self.group_exprs)? {
+                    let (grouped_key, dataset) = item?;
+                    let window_key = (GroupedKey::default(), start);
+                    if self.emit == Emit::Changes {
+                        updated.insert((window_key.clone(), grouped_key.clone()));
+                    }
+                    self.process_dataset(window_key, end, grouped_key, &dataset)?;
+                }
+            }
+        }
 
-        for item in dataset.group_by_window(self.time_idx, &self.window)? {
-            let (start, end, dataset) = item?;
-
-            for item in dataset.group_by_exprs(&mut self.group_exprs)? {
-                let (grouped_key, dataset) = item?;
-                self.process_dataset(start, end, grouped_key, &dataset)?;
+        if !updated.is_empty() {
+            if self.limit.is_some() {
+                // the first rows of a window may change with any of its groups, so they are emitted
+                // again for each updated window
+                let window_keys = updated
+                    .iter()
+                    .map(|(window_key, _)| window_key.clone())
+                    .collect::<BTreeSet<_>>();
+                for window_key in window_keys {
+                    if let Some(window) = self.windows.remove(&window_key) {
+                        let dataset = self.take_window_results(&window);
+                        self.windows.insert(window_key, window);
+                        datasets.push(dataset?);
+                    }
+                }
+            } else {
+                let dataset = self.take_changes(updated)?;
+                let dataset = self.finish_results(dataset)?;
+                datasets.push(self.sort(dataset, None)?);
             }
         }
 
+        // the windows are ordered by the start time, and a window that starts later never ends
+        // earlier, so the completed windows are emitted in the order of their end time
         let mut completed_windows = Vec::new();
         if let Some(current_watermark) = self.current_watermark {
-            while let Some((start, window)) = self.windows.iter().next() {
-                if current_watermark > window.end_time {
-                    let start = *start;
-                    if let Some(window) = self.windows.remove(&start) {
-                        completed_windows.push(window);
+            if let Window::Session { .. } = self.window {
+                // the sessions of different groups end in any order
+                let window_keys = self
+                    .windows
+                    .iter()
+                    .filter(|(_, window)| current_watermark >= window.end_time)
+                    .map(|(window_key, _)| window_key.clone())
+                    .collect_vec();
+                for window_key in window_keys {
+                    completed_windows.extend(self.windows.remove(&window_key));
+                }
+                completed_windows.sort_by_key(|window| (window.end_time, window.start_time));
+            } else {
+                while let Some((window_key, window)) = self.windows.iter().next() {
+                    if current_watermark >= window.end_time {
+                        let window_key = window_key.clone();
+                        if let Some(window) = self.windows.remove(&window_key) {
+                            completed_windows.push(window);
+                        }
+                    } else {
+                        break;
                     }
-                } else {
-                    break;
                 }
             }
         }
 
-        for window in completed_windows {
-            datasets.push(self.take_window_results(window)?);
+        // the results of completed windows have already been emitted as changes
+        if self.emit == Emit::Final {
+            for window in completed_windows {
+                datasets.push(self.take_window_results(&window)?);
+            }
         }
 
+        // all the rows may be filtered out by the having clause
+        datasets.retain(|dataset| !dataset.is_empty());
         Ok(datasets)
     }
 
     fn finish(&mut self) -> Result<Vec<DataSet>> {
-        std::mem::take(&mut self.windows)
-            .into_iter()
-            .map(|(_, window)| self.take_window_results(window))
-            .try_collect()
+        let windows = std::mem::take(&mut self.windows);
+        if self.emit == Emit::Changes {
+            return Ok(Vec::new());
+        }
+
+        let mut windows = windows.into_values().collect_vec();
+        windows.sort_by_key(|window| (window.end_time, window.start_time));
+        let mut datasets = Vec::with_capacity(windows.len());
+        for window in windows {
+            let dataset = self.take_window_results(&window)?;
+            if !dataset.is_empty() {
+                datasets.push(dataset);
+            }
+        }
+        Ok(datasets)
+    }
+
+    /// Filters the rows of an emitted dataset, and removes the hidden columns.
+    fn finish_results(&mut self, mut dataset: DataSet) -> Result<DataSet> {
+        if let Some(having) = &mut self.having {
+            let flags = having.eval(&dataset)?;
+            dataset = dataset.filter(flags.downcast_ref::<BooleanArray>())?;
+        }
+        if self.num_hidden_exprs > 0 {
+            let columns = dataset.columns();
+            let num_columns = columns.len() - self.num_hidden_exprs - 1;
+            dataset = DataSet::try_new(
+                self.schema.clone(),
+                columns[..num_columns]
+                    .iter()
+                    .chain(columns.last())
+                    .cloned()
+                    .collect(),
+            )?;
+        }
+        Ok(dataset)
+    }
+
+    /// Sorts the rows of an emitted dataset by the keys of the `ORDER BY` clause, and only keeps
+    /// the first `num_rows` rows if it is not `None`.
+    fn sort(&mut self, dataset: DataSet, num_rows: Option<usize>) -> Result<DataSet> {
+        if self.order_by.is_empty() {
+            return Ok(dataset);
+        }
+
+        let keys = self
+            .order_by
+            .iter_mut()
+            .map(|(expr, options)| Ok((expr.eval(&dataset)?, *options)))
+            .collect::<Result<Vec<_>>>()?;
+        let keys = keys
+            .iter()
+            .map(|(array, options)| (&**array, *options))
+            .collect_vec();
+        let indices = match num_rows {
+            Some(num_rows) => compute::lexsort_to_indices_limit(&keys, num_rows)?,
+            None => compute::lexsort_to_indices(&keys)?,
+        };
+        dataset.take(&indices)
     }
 
-    fn take_window_results(&self, window: WindowState) -> Result<DataSet> {
+    fn take_changes(&self, updated: IndexSet<(WindowKey, GroupedKey)>) -> Result<DataSet> {
+        let mut states = Vec::with_capacity(updated.len());
+        let mut times = TimestampBuilder::with_capacity(updated.len());
+
+        for (window_key, grouped_key) in &updated {
+            if let Some(window) = self.windows.get(window_key) {
+                if let Some(state) = window.children.get(grouped_key) {
+                    states.push(state);
+                    times.append(window.start_time);
+                }
+            }
+        }
+
+        self.create_dataset(&states, Arc::new(times.finish()))
+    }
+
+    /// Returns the sorted results of all the groups of a window.
+    ///
+    /// With a limit, the results are created for a chunk of groups at a time and only the first
+    /// rows are kept between the chunks, so the results of the whole window are never created at
+    /// once.
+    fn take_window_results(&mut self, window: &WindowState) -> Result<DataSet> {
+        let states = window.children.values().collect_vec();
+        let times = |len| Arc::new(TimestampArray::new_scalar(len, Some(window.start_time)));
+
+        let limit = match self.limit {
+            Some(limit) => limit,
+            None => {
+                let dataset = self.create_dataset(&states, times(states.len()))?;
+                let dataset = self.finish_results(dataset)?;
+                return self.sort(dataset, None);
+            }
+        };
+
+        let num_rows = self.offset.saturating_add(limit);
+        let mut top = self.finish_results(self.create_dataset(&[], times(0))?)?;
+        for chunk in states.chunks(LIMIT_CHUNK_SIZE) {
+            let dataset = self.create_dataset(chunk, times(chunk.len()))?;
+            let dataset = self.finish_results(dataset)?;
+            let columns = top
+                .columns()
+                .iter()
+                .zip(dataset.columns())
+                .map(|(a, b)| compute::concat(&[&**a, &**b]))
+                .try_collect()?;
+            top = self.sort(DataSet::try_new(top.schema(), columns)?, Some(num_rows))?;
+        }
+        let offset = self.offset.min(top.len());
+        Ok(top.slice(offset, top.len() - offset))
+    }
+
+    fn create_dataset(&self, states: &[&AggregateState], times: ArrayRef) -> Result<DataSet> {
         let mut columns = Vec::with_capacity(self.aggr_exprs.len());
 
         for index in 0..self.aggr_exprs.len() {
-            let field = &self.schema.fields()[index];
+            let field = &self.results_schema.fields()[index];
 
             match field.data_type {
-                DataType::Null => {
-                    columns.push(Arc::new(NullArray::new(window.children.len())) as ArrayRef)
-                }
+                DataType::Null => columns.push(Arc::new(NullArray::new(states.len())) as ArrayRef),
                 DataType::Int8 => {
-                    append_primitive_value!(columns, window.children, index, Int8Type, Int8)
+                    append_primitive_value!(columns, states, index, Int8Type, Int8)
                 }
                 DataType::Int16 => {
-                    append_primitive_value!(columns, window.children, index, Int16Type, Int16)
+                    append_primitive_value!(columns, states, index, Int16Type, Int16)
                 }
                 DataType::Int32 => {
-                    append_primitive_value!(columns, window.children, index, Int32Type, Int32)
+                    append_primitive_value!(columns, states, index, Int32Type, Int32)
                 }
                 DataType::Int64 => {
-                    append_primitive_value!(columns, window.children, index, Int64Type, Int64)
+                    append_primitive_value!(columns, states, index, Int64Type, Int64)
+                }
+                DataType::UInt8 => {
+                    append_primitive_value!(columns, states, index, UInt8Type, UInt8)
+                }
+                DataType::UInt16 => {
+                    append_primitive_value!(columns, states, index, UInt16Type, UInt16)
+                }
+                DataType::UInt32 => {
+                    append_primitive_value!(columns, states, index, UInt32Type, UInt32)
+                }
+                DataType::UInt64 => {
+                    append_primitive_value!(columns, states, index, UInt64Type, UInt64)
                 }
                 DataType::Float32 => {
-                    append_primitive_value!(columns, window.children, index, Float32Type, Float32)
+                    append_primitive_value!(columns, states, index, Float32Type, Float32)
                 }
                 DataType::Float64 => {
-                    append_primitive_value!(columns, window.children, index, Float64Type, Float64)
+                    append_primitive_value!(columns, states, index, Float64Type, Float64)
                 }
                 DataType::Boolean => {
-                    append_primitive_value!(columns, window.children, index, BooleanType, Boolean)
+                    append_primitive_value!(columns, states, index, BooleanType, Boolean)
+                }
+                DataType::Timestamp(_) => {
+                    append_primitive_value!(columns, states, index, TimestampType, Timestamp)
                 }
-                DataType::Timestamp(_) => append_primitive_value!(
-                    columns,
-                    window.children,
-                    index,
-                    TimestampType,
-                    Timestamp
-                ),
                 DataType::String => {
-                    let mut builder = StringBuilder::with_capacity(window.children.len());
-                    for state in window.children.values() {
+                    let mut builder = StringBuilder::with_capacity(states.len());
+                    for state in states {
                         builder.append_opt(if let Scalar::String(value) = &state.values[index] {
                             Some(value)
                         } else {
@@ -306,14 +671,19 @@ impl AggregateStream {
                     }
                     columns.push(Arc::new(builder.finish()));
                 }
+                DataType::Decimal128 { .. } | DataType::List(_) | DataType::Struct(_) => {
+                    let schema = Arc::new(Schema::try_new(vec![field.clone()])?);
+                    let rows = states
+                        .iter()
+                        .map(|state| vec![state.values[index].clone()])
+                        .collect();
+                    columns.push(DataSet::try_from_rows(schema, rows)?.columns()[0].clone());
+                }
             }
         }
 
-        columns.push(Arc::new(TimestampArray::new_scalar(
-            window.children.len(),
-            Some(window.start_time),
-        )));
-        DataSet::try_new(self.schema.clone(), columns)
+        columns.push(times);
+        DataSet::try_new(self.results_schema.clone(), columns)
     }
 }
 
@@ -332,7 +702,7 @@ impl DataSetStream for AggregateStream {
             .try_collect()?;
 
         let mut windows = Vec::new();
-        for (start, window) in &self.windows {
+        for window in self.windows.values() {
             let mut groups = Vec::new();
             for (grouped_key, aggregate_state) in &window.children {
                 groups.push((
@@ -345,7 +715,7 @@ impl DataSetStream for AggregateStream {
                     aggregate_state.values.clone(),
                 ));
             }
-            windows.push((*start, window.end_time, groups));
+            windows.push((window.start_time, window.end_time, groups));
         }
 
         let saved_state = SavedState {
@@ -18,12 +18,15 @@ pub fn create_filter_stream(
     node: PhysicalFilterNode,
 ) -> Result<BoxDataSetStream> {
     let PhysicalFilterNode {
-        id, expr, input, ..
+        id,
+        predicates,
+        input,
+        ..
     } = node;
 
     let mut stream = FilterStream {
         id,
-        expr,
+        predicates,
         input: create_stream(create_ctx, *input)?,
     };
     if let Some(data) = create_ctx.prev_state.remove(&id) {
@@ -35,18 +38,26 @@ pub fn create_filter_stream(
 
 struct FilterStream {
     id: usize,
-    expr: PhysicalExpr,
+    /// Only the first predicate can be stateful, its state is the state of the stream.
+    predicates: Vec<PhysicalExpr>,
     input: BoxDataSetStream,
 }
 
 impl FilterStream {
     fn load_state(&mut self, data: Vec<u8>) -> Result<()> {
-        self.expr.load_state(data)
+        self.predicates[0].load_state(data)
     }
 
     fn process_dataset(&mut self, dataset: &DataSet) -> Result<DataSet> {
-        let array = self.expr.eval(&dataset)?;
-        dataset.filter(array.downcast_ref::<BooleanArray>())
+        let mut dataset = dataset.clone();
+        for predicate in &mut self.predicates {
+            let array = predicate.eval(&dataset)?;
+            dataset = dataset.filter(array.downcast_ref::<BooleanArray>())?;
+            if dataset.is_empty() {
+                break;
+            }
+        }
+        Ok(dataset)
     }
 }
 
@@ -54,7 +65,7 @@ impl DataSetStream for FilterStream {
     fn save_state(&self, state: &mut HashMap<usize, Vec<u8>>) -> Result<()> {
         self.input.save_state(state)?;
 
-        state.insert(self.id, self.expr.save_state()?);
+        state.insert(self.id, self.predicates[0].save_state()?);
         Ok(())
     }
 }
@@ -79,3 +90,84 @@ impl Stream for FilterStream {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use futures_util::TryStreamExt;
+
+    use super::*;
+    use crate::array::{ArrayRef, DataType};
+    use crate::dataset::{Field, Schema};
+    use crate::dsl::*;
+    use crate::execution::execution_context::ExecutionContext;
+    use crate::expr::physical_expr::{PhysicalFunction, PhysicalNode};
+    use crate::expr::BinaryOperator;
+    use crate::planner::logical_plan::{LogicalFilterPlan, LogicalPlan, LogicalSourcePlan};
+    use crate::planner::physical_plan::{self, PhysicalPlan};
+    use crate::testing::memory_source;
+
+    static COUNTED_ROWS: AtomicUsize = AtomicUsize::new(0);
+
+    fn count_rows(args: &[ArrayRef]) -> Result<ArrayRef> {
+        COUNTED_ROWS.fetch_add(args[0].len(), Ordering::SeqCst);
+        Ok(Arc::new(BooleanArray::new_scalar(
+            args[0].len(),
+            Some(true),
+        )))
+    }
+
+    #[tokio::test]
+    async fn test_predicate_order() {
+        let schema = Arc::new(Schema::try_new(vec![Field::new("a", DataType::Int64)]).unwrap());
+        let plan = LogicalPlan::Filter(LogicalFilterPlan {
+            input: Box::new(LogicalPlan::Source(LogicalSourcePlan {
+                qualifier: None,
+                source_provider: memory_source(schema, "1\n2\n3\n2\n5\n12\n", 10),
+                time_expr: None,
+            })),
+            expr: col("a")
+                .cast(DataType::String)
+                .like(value("%2"))
+                .and(col("a").eq(value(2))),
+        });
+
+        let mut node = match PhysicalPlan::try_new(plan).unwrap().root {
+            physical_plan::PhysicalNode::Filter(node) => node,
+            _ => panic!("expected a filter"),
+        };
+        assert_eq!(node.predicates.len(), 2);
+        assert!(matches!(
+            node.predicates[0].root,
+            PhysicalNode::Binary {
+                op: BinaryOperator::Eq,
+                ..
+            }
+        ));
+
+        // count the rows that the expensive predicate is evaluated on
+        node.predicates[1] = PhysicalExpr {
+            root: PhysicalNode::Call {
+                input_data_types: vec![DataType::Int64],
+                func: PhysicalFunction::Stateless(count_rows),
+                args: vec![PhysicalNode::Column { index: 0 }],
+            },
+            data_type: DataType::Boolean,
+            stateful_funcs: Vec::new(),
+        };
+        let mut create_ctx = CreateStreamContext {
+            ctx: Arc::new(ExecutionContext::new()),
+            prev_state: HashMap::new(),
+        };
+        let datasets = create_filter_stream(&mut create_ctx, node)
+            .unwrap()
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert_eq!(datasets.len(), 1);
+        assert_eq!(datasets[0].len(), 2);
+        assert_eq!(COUNTED_ROWS.load(Ordering::SeqCst), 2);
+    }
+}
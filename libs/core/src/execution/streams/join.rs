@@ -0,0 +1,356 @@
+use std::collections::HashMap;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use ahash::AHashMap;
+use anyhow::Result;
+use futures_util::Stream;
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+
+use crate::array::Scalar;
+use crate::array::{compute, ArrayExt, Int64Array};
+use crate::dataset::{DataSet, SchemaRef};
+use crate::execution::dataset::{DataSetExt, GroupedKey};
+use crate::execution::execution_context::ExecutionContext;
+use crate::execution::stream::{BoxDataSetStream, CreateStreamContext, DataSetStream};
+use crate::execution::streams::create_stream;
+use crate::expr::physical_expr::PhysicalExpr;
+use crate::expr::ExprState;
+use crate::planner::physical_plan::{PhysicalJoinNode, PhysicalNode, FIELD_TIME};
+use crate::JoinType;
+
+pub fn create_join_stream(
+    create_ctx: &mut CreateStreamContext,
+    node: PhysicalJoinNode,
+) -> Result<BoxDataSetStream> {
+    let PhysicalJoinNode {
+        id,
+        schema,
+        join_type,
+        left_key,
+        right_key,
+        left,
+        right,
+        refresh,
+    } = node;
+
+    // the time column of the right side is not part of the output
+    let right_schema = right.schema();
+    let right_columns = right_schema
+        .fields()
+        .iter()
+        .enumerate()
+        .filter(|(_, field)| field.name != FIELD_TIME)
+        .map(|(index, _)| index)
+        .collect();
+
+    let left_time = left
+        .schema()
+        .field(None, FIELD_TIME)
+        .map(|(index, _)| index)
+        .expect("the left side has a time column");
+    let right_time = right_schema
+        .field(None, FIELD_TIME)
+        .map(|(index, _)| index)
+        .expect("the right side has a time column");
+
+    let mut stream = JoinStream {
+        id,
+        schema,
+        join_type,
+        left_time,
+        right_time,
+        left_key,
+        right_key,
+        right_schema,
+        right_columns,
+        left: create_stream(create_ctx, *left)?,
+        right: Some(create_stream(create_ctx, (*right).clone())?),
+        right_datasets: Vec::new(),
+        table: None,
+        refresh: refresh.map(|refresh| (create_ctx.ctx.clone(), *right, refresh)),
+        restored_matched: None,
+        finished: false,
+    };
+    if let Some(data) = create_ctx.prev_state.remove(&id) {
+        stream.load_state(data)?;
+    }
+
+    Ok(Box::pin(stream))
+}
+
+#[derive(Serialize, Deserialize)]
+struct SavedState {
+    left_key: ExprState,
+    right_key: ExprState,
+    matched: Vec<bool>,
+}
+
+/// The rows of the right side followed by a row of nulls, and the indexes of the rows of each
+/// key.
+struct HashTable {
+    dataset: DataSet,
+    rows: AHashMap<GroupedKey, Vec<usize>>,
+    /// Whether each row of the right side has been joined with a row of the left side.
+    matched: Vec<bool>,
+    created_at: Instant,
+    /// Whether a dataset of the left side has been joined with the table, the table is only
+    /// refreshed after that.
+    probed: bool,
+}
+
+impl HashTable {
+    /// The index of the row of nulls, that is joined with the rows of the left side without a
+    /// match.
+    fn null_row(&self) -> usize {
+        self.matched.len()
+    }
+}
+
+/// Reads the right side to the end into a hash table, then probes it with the rows of the left
+/// side.
+///
+/// The state of the right side is never saved, so it is read again from the beginning when the
+/// stream is restored, or when the table is older than the refresh interval.
+///
+/// The rows of the right side without a match are emitted when the left side ends, the time of
+/// these rows is the time of the right side. When the table is refreshed, the rows of the old
+/// table without a match are emitted, so with an unbounded left side each table is a window of
+/// the refresh interval that is closed by the next one.
+struct JoinStream {
+    id: usize,
+    schema: SchemaRef,
+    join_type: JoinType,
+    left_time: usize,
+    right_time: usize,
+    left_key: PhysicalExpr,
+    right_key: PhysicalExpr,
+    right_schema: SchemaRef,
+    right_columns: Vec<usize>,
+    left: BoxDataSetStream,
+    right: Option<BoxDataSetStream>,
+    right_datasets: Vec<DataSet>,
+    table: Option<HashTable>,
+    refresh: Option<(Arc<ExecutionContext>, PhysicalNode, i64)>,
+    restored_matched: Option<Vec<bool>>,
+    finished: bool,
+}
+
+impl JoinStream {
+    fn load_state(&mut self, data: Vec<u8>) -> Result<()> {
+        let saved_state: SavedState = bincode::deserialize(&data)?;
+        self.left_key.load_state(saved_state.left_key)?;
+        self.right_key.load_state(saved_state.right_key)?;
+        self.restored_matched = Some(saved_state.matched);
+        Ok(())
+    }
+
+    /// Builds the table from the rows of the right side, and returns the rows without a match of
+    /// the table it replaces, if any.
+    fn build_table(&mut self) -> Result<Option<DataSet>> {
+        let unmatched = match (&self.table, self.join_type) {
+            (Some(_), JoinType::Right | JoinType::Full) => Some(self.unmatched_right_rows()?),
+            _ => None,
+        };
+        let datasets = std::mem::take(&mut self.right_datasets);
+        let mut rows: Vec<Vec<Scalar>> = datasets
+            .iter()
+            .flat_map(|dataset| {
+                (0..dataset.len()).map(move |row| {
+                    dataset
+                        .columns()
+                        .iter()
+                        .map(|column| column.scalar_value(row))
+                        .collect()
+                })
+            })
+            .collect();
+        let num_rows = rows.len();
+        rows.push(vec![Scalar::Null; self.right_schema.fields().len()]);
+        let dataset = DataSet::try_from_rows(self.right_schema.clone(), rows)?;
+
+        // the rows that were joined before the stream was saved are the same if the right side
+        // hasn't changed
+        let matched = match self.restored_matched.take() {
+            Some(matched) if matched.len() == num_rows => matched,
+            _ => vec![false; num_rows],
+        };
+
+        let mut rows: AHashMap<_, Vec<usize>> = AHashMap::new();
+        let keys = dataset.row_keys(std::slice::from_mut(&mut self.right_key))?;
+        for (row, key) in keys.into_iter().enumerate() {
+            if let Some(key) = key {
+                rows.entry(key).or_default().push(row);
+            }
+        }
+        self.table = Some(HashTable {
+            dataset,
+            rows,
+            matched,
+            created_at: Instant::now(),
+            probed: false,
+        });
+        Ok(unmatched.filter(|dataset| !dataset.is_empty()))
+    }
+
+    /// Starts reading the right side again if the table is older than the refresh interval, the
+    /// old table is used until the new one is built.
+    fn refresh_table(&mut self) -> Result<()> {
+        if let (Some((ctx, right, refresh)), Some(table), None) =
+            (&self.refresh, &self.table, &self.right)
+        {
+            if table.probed && table.created_at.elapsed() >= Duration::from_millis(*refresh as u64)
+            {
+                let mut create_ctx = CreateStreamContext {
+                    ctx: ctx.clone(),
+                    prev_state: HashMap::new(),
+                };
+                self.right = Some(create_stream(&mut create_ctx, right.clone())?);
+            }
+        }
+        Ok(())
+    }
+
+    fn process_dataset(&mut self, dataset: &DataSet) -> Result<DataSet> {
+        let table = self.table.as_mut().expect("the right side has been read");
+        table.probed = true;
+        let keys = dataset.row_keys(std::slice::from_mut(&mut self.left_key))?;
+        let keep_left = matches!(self.join_type, JoinType::Left | JoinType::Full);
+        let mut left_indices = Vec::new();
+        let mut right_indices = Vec::new();
+        for (row, key) in keys.iter().enumerate() {
+            let rows = match key {
+                Some(key) => table.rows.get(key),
+                None => None,
+            };
+            match rows {
+                Some(rows) => {
+                    for right_row in rows {
+                        left_indices.push(row as i64);
+                        right_indices.push(*right_row as i64);
+                        table.matched[*right_row] = true;
+                    }
+                }
+                None if keep_left => {
+                    left_indices.push(row as i64);
+                    right_indices.push(table.null_row() as i64);
+                }
+                None => {}
+            }
+        }
+
+        let left_indices = Int64Array::from_vec(left_indices);
+        let right_indices = Int64Array::from_vec(right_indices);
+        DataSet::try_new(
+            self.schema.clone(),
+            dataset
+                .columns()
+                .iter()
+                .map(|column| compute::take(&**column, &left_indices))
+                .chain(
+                    self.right_columns.iter().map(|index| {
+                        compute::take(&*table.dataset.columns()[*index], &right_indices)
+                    }),
+                )
+                .collect::<Result<_>>()?,
+        )
+    }
+
+    /// Returns the rows of the right side without a match, the columns of the left side are
+    /// null except the time.
+    fn unmatched_right_rows(&self) -> Result<DataSet> {
+        let table = self.table.as_ref().expect("the right side has been read");
+        let num_left_columns = self.schema.fields().len() - self.right_columns.len();
+        let columns = table.dataset.columns();
+        let rows = (0..table.null_row())
+            .filter(|row| !table.matched[*row])
+            .map(|row| {
+                let mut values = vec![Scalar::Null; num_left_columns];
+                values[self.left_time] = columns[self.right_time].scalar_value(row);
+                values.extend(
+                    self.right_columns
+                        .iter()
+                        .map(|index| columns[*index].scalar_value(row)),
+                );
+                values
+            })
+            .collect();
+        DataSet::try_from_rows(self.schema.clone(), rows)
+    }
+}
+
+impl DataSetStream for JoinStream {
+    fn save_state(&self, state: &mut HashMap<usize, Vec<u8>>) -> Result<()> {
+        self.left.save_state(state)?;
+
+        let data = bincode::serialize(&SavedState {
+            left_key: self.left_key.save_state()?,
+            right_key: self.right_key.save_state()?,
+            matched: self
+                .table
+                .as_ref()
+                .map(|table| table.matched.clone())
+                .unwrap_or_default(),
+        })?;
+        state.insert(self.id, data);
+        Ok(())
+    }
+}
+
+impl Stream for JoinStream {
+    type Item = Result<DataSet>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if let Err(err) = self.refresh_table() {
+            return Poll::Ready(Some(Err(err)));
+        }
+
+        while let Some(right) = &mut self.right {
+            match right.poll_next_unpin(cx) {
+                Poll::Ready(Some(Ok(dataset))) => self.right_datasets.push(dataset),
+                Poll::Ready(Some(Err(err))) => return Poll::Ready(Some(Err(err))),
+                Poll::Ready(None) => {
+                    self.right = None;
+                    match self.build_table() {
+                        Ok(Some(unmatched)) => return Poll::Ready(Some(Ok(unmatched))),
+                        Ok(None) => {}
+                        Err(err) => return Poll::Ready(Some(Err(err))),
+                    }
+                }
+                Poll::Pending if self.table.is_some() => break,
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+
+        loop {
+            match self.left.poll_next_unpin(cx) {
+                Poll::Ready(Some(Ok(dataset))) => match self.process_dataset(&dataset) {
+                    Ok(new_dataset) if !new_dataset.is_empty() => {
+                        return Poll::Ready(Some(Ok(new_dataset)));
+                    }
+                    Ok(_) => {}
+                    Err(err) => return Poll::Ready(Some(Err(err))),
+                },
+                Poll::Ready(Some(Err(err))) => return Poll::Ready(Some(Err(err))),
+                Poll::Ready(None)
+                    if !self.finished
+                        && matches!(self.join_type, JoinType::Right | JoinType::Full) =>
+                {
+                    self.finished = true;
+                    match self.unmatched_right_rows() {
+                        Ok(dataset) if !dataset.is_empty() => {
+                            return Poll::Ready(Some(Ok(dataset)));
+                        }
+                        Ok(_) => return Poll::Ready(None),
+                        Err(err) => return Poll::Ready(Some(Err(err))),
+                    }
+                }
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
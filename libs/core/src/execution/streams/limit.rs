@@ -0,0 +1,114 @@
+use std::collections::HashMap;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use anyhow::Result;
+use futures_util::{Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+
+use crate::dataset::DataSet;
+use crate::execution::stream::{BoxDataSetStream, CreateStreamContext, DataSetStream};
+use crate::execution::streams::create_stream;
+use crate::planner::physical_plan::PhysicalLimitNode;
+
+pub fn create_limit_stream(
+    create_ctx: &mut CreateStreamContext,
+    node: PhysicalLimitNode,
+) -> Result<BoxDataSetStream> {
+    let PhysicalLimitNode {
+        id,
+        limit,
+        offset,
+        input,
+        ..
+    } = node;
+
+    let mut stream = LimitStream {
+        id,
+        limit,
+        offset,
+        skipped: 0,
+        emitted: 0,
+        input: create_stream(create_ctx, *input)?,
+    };
+    if let Some(data) = create_ctx.prev_state.remove(&id) {
+        stream.load_state(data)?;
+    }
+
+    Ok(Box::pin(stream))
+}
+
+#[derive(Serialize, Deserialize)]
+struct SavedState {
+    skipped: usize,
+    emitted: usize,
+}
+
+struct LimitStream {
+    id: usize,
+    limit: usize,
+    offset: usize,
+    skipped: usize,
+    emitted: usize,
+    input: BoxDataSetStream,
+}
+
+impl LimitStream {
+    fn load_state(&mut self, data: Vec<u8>) -> Result<()> {
+        let saved_state: SavedState = bincode::deserialize(&data)?;
+        self.skipped = saved_state.skipped;
+        self.emitted = saved_state.emitted;
+        Ok(())
+    }
+
+    /// Skips the rows before the offset, and truncates the rows after the limit.
+    fn process_dataset(&mut self, dataset: DataSet) -> DataSet {
+        let skip = (self.offset - self.skipped).min(dataset.len());
+        self.skipped += skip;
+        let take = (self.limit - self.emitted).min(dataset.len() - skip);
+        self.emitted += take;
+        if skip == 0 && take == dataset.len() {
+            dataset
+        } else {
+            dataset.slice(skip, take)
+        }
+    }
+}
+
+impl DataSetStream for LimitStream {
+    fn save_state(&self, state: &mut HashMap<usize, Vec<u8>>) -> Result<()> {
+        self.input.save_state(state)?;
+
+        let saved_state = SavedState {
+            skipped: self.skipped,
+            emitted: self.emitted,
+        };
+        state.insert(self.id, bincode::serialize(&saved_state)?);
+        Ok(())
+    }
+}
+
+impl Stream for LimitStream {
+    type Item = Result<DataSet>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            // the input is not polled anymore once the limit is reached
+            if self.emitted >= self.limit {
+                return Poll::Ready(None);
+            }
+
+            match self.input.poll_next_unpin(cx) {
+                Poll::Ready(Some(Ok(dataset))) => {
+                    let new_dataset = self.process_dataset(dataset);
+                    if !new_dataset.is_empty() {
+                        return Poll::Ready(Some(Ok(new_dataset)));
+                    }
+                }
+                Poll::Ready(Some(Err(err))) => return Poll::Ready(Some(Err(err))),
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
@@ -0,0 +1,162 @@
+use std::collections::HashMap;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use ahash::AHashMap;
+use anyhow::Result;
+use futures_util::Stream;
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+
+use crate::array::{ArrayExt, Scalar};
+use crate::dataset::{DataSet, Schema, SchemaRef};
+use crate::execution::dataset::{DataSetExt, GroupedKey};
+use crate::execution::stream::{BoxDataSetStream, CreateStreamContext, DataSetStream};
+use crate::execution::streams::create_stream;
+use crate::expr::physical_expr::PhysicalExpr;
+use crate::expr::ExprState;
+use crate::planner::physical_plan::PhysicalLookupNode;
+
+pub fn create_lookup_stream(
+    create_ctx: &mut CreateStreamContext,
+    node: PhysicalLookupNode,
+) -> Result<BoxDataSetStream> {
+    let PhysicalLookupNode {
+        id,
+        schema,
+        left_key,
+        right_key,
+        value,
+        left,
+        right,
+    } = node;
+
+    // the schema of the appended column
+    let value_schema = Arc::new(Schema::try_new(vec![schema
+        .fields()
+        .last()
+        .expect("the looked up column")
+        .clone()])?);
+
+    let mut stream = LookupStream {
+        id,
+        schema,
+        value_schema,
+        left_key,
+        right_key,
+        value,
+        left: create_stream(create_ctx, *left)?,
+        right: Some(create_stream(create_ctx, *right)?),
+        table: AHashMap::new(),
+    };
+    if let Some(data) = create_ctx.prev_state.remove(&id) {
+        stream.load_state(data)?;
+    }
+
+    Ok(Box::pin(stream))
+}
+
+#[derive(Serialize, Deserialize)]
+struct SavedState {
+    left_key: ExprState,
+    right_key: ExprState,
+}
+
+/// Reads the right side to the end into a map from the keys to the values, then looks up the
+/// value for each row of the left side.
+///
+/// The state of the right side is never saved, so it is read again from the beginning when the
+/// stream is restored.
+struct LookupStream {
+    id: usize,
+    schema: SchemaRef,
+    value_schema: SchemaRef,
+    left_key: PhysicalExpr,
+    right_key: PhysicalExpr,
+    value: PhysicalExpr,
+    left: BoxDataSetStream,
+    right: Option<BoxDataSetStream>,
+    table: AHashMap<GroupedKey, Scalar>,
+}
+
+impl LookupStream {
+    fn load_state(&mut self, data: Vec<u8>) -> Result<()> {
+        let saved_state: SavedState = bincode::deserialize(&data)?;
+        self.left_key.load_state(saved_state.left_key)?;
+        self.right_key.load_state(saved_state.right_key)?;
+        Ok(())
+    }
+
+    /// Adds the values of the rows of the right side, the first row of each key wins.
+    fn process_right_dataset(&mut self, dataset: &DataSet) -> Result<()> {
+        let keys = dataset.row_keys(std::slice::from_mut(&mut self.right_key))?;
+        let values = self.value.eval(dataset)?;
+        for (row, key) in keys.into_iter().enumerate() {
+            if let Some(key) = key {
+                self.table
+                    .entry(key)
+                    .or_insert_with(|| values.scalar_value(row));
+            }
+        }
+        Ok(())
+    }
+
+    fn process_dataset(&mut self, dataset: &DataSet) -> Result<DataSet> {
+        let keys = dataset.row_keys(std::slice::from_mut(&mut self.left_key))?;
+        let rows = keys
+            .iter()
+            .map(|key| {
+                vec![key
+                    .as_ref()
+                    .and_then(|key| self.table.get(key))
+                    .cloned()
+                    .unwrap_or(Scalar::Null)]
+            })
+            .collect();
+        let values = DataSet::try_from_rows(self.value_schema.clone(), rows)?;
+
+        let mut columns = dataset.columns().to_vec();
+        columns.push(values.columns()[0].clone());
+        DataSet::try_new(self.schema.clone(), columns)
+    }
+}
+
+impl DataSetStream for LookupStream {
+    fn save_state(&self, state: &mut HashMap<usize, Vec<u8>>) -> Result<()> {
+        self.left.save_state(state)?;
+
+        let data = bincode::serialize(&SavedState {
+            left_key: self.left_key.save_state()?,
+            right_key: self.right_key.save_state()?,
+        })?;
+        state.insert(self.id, data);
+        Ok(())
+    }
+}
+
+impl Stream for LookupStream {
+    type Item = Result<DataSet>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        while let Some(right) = &mut self.right {
+            match right.poll_next_unpin(cx) {
+                Poll::Ready(Some(Ok(dataset))) => {
+                    if let Err(err) = self.process_right_dataset(&dataset) {
+                        return Poll::Ready(Some(Err(err)));
+                    }
+                }
+                Poll::Ready(Some(Err(err))) => return Poll::Ready(Some(Err(err))),
+                Poll::Ready(None) => self.right = None,
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+
+        match self.left.poll_next_unpin(cx) {
+            Poll::Ready(Some(Ok(dataset))) => Poll::Ready(Some(self.process_dataset(&dataset))),
+            Poll::Ready(Some(Err(err))) => Poll::Ready(Some(Err(err))),
+            Poll::Ready(None) => Poll::Ready(None),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
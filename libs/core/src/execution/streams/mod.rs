@@ -1,10 +1,15 @@
 mod aggregate;
 mod filter;
+mod join;
+mod limit;
+mod lookup;
 mod projection;
 mod source;
+mod unnest;
 
 use anyhow::Result;
 
+use crate::array::{compute, ArrayRef, DataType};
 use crate::execution::stream::{BoxDataSetStream, CreateStreamContext};
 use crate::planner::physical_plan::PhysicalNode;
 
@@ -21,5 +26,21 @@ pub fn create_stream(
         PhysicalNode::Aggregate(aggregate) => {
             aggregate::create_aggregate_stream(create_ctx, aggregate)
         }
+        PhysicalNode::Limit(limit) => limit::create_limit_stream(create_ctx, limit),
+        PhysicalNode::Join(join) => join::create_join_stream(create_ctx, join),
+        PhysicalNode::Lookup(lookup) => lookup::create_lookup_stream(create_ctx, lookup),
+        PhysicalNode::Unnest(unnest) => unnest::create_unnest_stream(create_ctx, unnest),
     }
 }
+
+/// Converts the result of a time or a watermark expression to timestamps, the integers are
+/// milliseconds since the unix epoch.
+fn to_timestamps(array: ArrayRef) -> Result<ArrayRef> {
+    if array.data_type().is_timestamp() {
+        return Ok(array);
+    }
+    compute::cast(
+        compute::cast(array, DataType::Int64)?,
+        DataType::Timestamp(None),
+    )
+}
@@ -6,6 +6,7 @@ use anyhow::Result;
 use futures_util::{Stream, StreamExt};
 use itertools::Itertools;
 
+use crate::array::compute;
 use crate::dataset::{DataSet, SchemaRef};
 use crate::execution::stream::{BoxDataSetStream, CreateStreamContext, DataSetStream};
 use crate::execution::streams::create_stream;
@@ -55,7 +56,9 @@ impl ProjectionStream {
     fn process_dataset(&mut self, dataset: &DataSet) -> Result<DataSet> {
         let mut columns = Vec::with_capacity(self.exprs.len());
         for expr in &mut self.exprs {
-            columns.push(expr.eval(&dataset)?);
+            // a column in which every value is null, e.g. the result of a cast that always fails,
+            // doesn't need to store the values
+            columns.push(compute::compact_nulls(expr.eval(&dataset)?));
         }
         DataSet::try_new(self.schema.clone(), columns)
     }
@@ -13,6 +13,7 @@ use crate::array::TimestampArray;
 use crate::dataset::{DataSet, SchemaRef};
 use crate::execution::execution_context::ExecutionContext;
 use crate::execution::stream::{BoxDataSetStream, CreateStreamContext, DataSetStream};
+use crate::execution::streams::to_timestamps;
 use crate::expr::physical_expr::PhysicalExpr;
 use crate::expr::ExprState;
 use crate::planner::physical_plan::PhysicalSourceNode;
@@ -69,7 +70,7 @@ struct SourceStream {
 impl SourceStream {
     fn process_dataset(&mut self, dataset: &DataSet) -> Result<DataSet> {
         let times_array = match &mut self.time_expr {
-            Some(expr) => expr.eval(dataset)?,
+            Some(expr) => to_timestamps(expr.eval(dataset)?)?,
             None => {
                 let now = SystemTime::now()
                     .duration_since(UNIX_EPOCH)
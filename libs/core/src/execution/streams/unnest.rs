@@ -0,0 +1,153 @@
+use std::collections::HashMap;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use anyhow::Result;
+use futures_util::{Stream, StreamExt};
+
+use crate::array::{compute, ArrayExt, Int64Array, ListArray};
+use crate::dataset::{DataSet, SchemaRef};
+use crate::execution::stream::{BoxDataSetStream, CreateStreamContext, DataSetStream};
+use crate::execution::streams::create_stream;
+use crate::expr::physical_expr::PhysicalExpr;
+use crate::planner::physical_plan::PhysicalUnnestNode;
+
+pub fn create_unnest_stream(
+    create_ctx: &mut CreateStreamContext,
+    node: PhysicalUnnestNode,
+) -> Result<BoxDataSetStream> {
+    let PhysicalUnnestNode {
+        id,
+        schema,
+        expr,
+        input,
+    } = node;
+
+    let mut stream = UnnestStream {
+        id,
+        schema,
+        expr,
+        input: create_stream(create_ctx, *input)?,
+    };
+    if let Some(data) = create_ctx.prev_state.remove(&id) {
+        stream.expr.load_state(data)?;
+    }
+
+    Ok(Box::pin(stream))
+}
+
+struct UnnestStream {
+    id: usize,
+    schema: SchemaRef,
+    expr: PhysicalExpr,
+    input: BoxDataSetStream,
+}
+
+impl UnnestStream {
+    fn process_dataset(&mut self, dataset: &DataSet) -> Result<DataSet> {
+        let array = self.expr.eval(dataset)?;
+        let lists = array.downcast_ref::<ListArray>();
+
+        // the index of the input row of each element
+        let mut indices = Vec::new();
+        let mut elements = Vec::new();
+        for (index, list) in lists.iter_opt().enumerate() {
+            if let Some(list) = list {
+                indices.resize(indices.len() + list.len(), index as i64);
+                elements.push(list);
+            }
+        }
+        let elements = if elements.is_empty() {
+            lists.values().slice(0, 0)
+        } else {
+            compute::concat(&elements.iter().map(|array| &**array).collect::<Vec<_>>())?
+        };
+
+        let indices = Int64Array::from_vec(indices);
+        let mut columns = dataset
+            .columns()
+            .iter()
+            .map(|column| compute::take(&**column, &indices))
+            .collect::<Result<Vec<_>>>()?;
+        columns.push(elements);
+        DataSet::try_new(self.schema.clone(), columns)
+    }
+}
+
+impl DataSetStream for UnnestStream {
+    fn save_state(&self, state: &mut HashMap<usize, Vec<u8>>) -> Result<()> {
+        self.input.save_state(state)?;
+        state.insert(self.id, self.expr.save_state()?);
+        Ok(())
+    }
+}
+
+impl Stream for UnnestStream {
+    type Item = Result<DataSet>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            match self.input.poll_next_unpin(cx) {
+                Poll::Ready(Some(Ok(dataset))) => match self.process_dataset(&dataset) {
+                    Ok(new_dataset) if new_dataset.is_empty() => {}
+                    Ok(new_dataset) => return Poll::Ready(Some(Ok(new_dataset))),
+                    Err(err) => return Poll::Ready(Some(Err(err))),
+                },
+                Poll::Ready(Some(Err(err))) => return Poll::Ready(Some(Err(err))),
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::array::DataType;
+    use crate::dataframe::DataFrame;
+    use crate::dataset::{CsvOptions, DataSet, Field, Schema};
+    use crate::dsl::*;
+    use crate::testing::{collect_into_vec, memory_source};
+
+    #[tokio::test]
+    async fn test_unnest() {
+        let schema = Arc::new(
+            Schema::try_new(vec![
+                Field::new("a", DataType::Int64),
+                Field::new("b", DataType::String),
+                Field::new("tags", DataType::String),
+            ])
+            .unwrap(),
+        );
+        let source = memory_source(schema, "1,x,p;q;r\n2,y,s;t;u\n", 10);
+        let df = DataFrame::new(source, None, None)
+            .unnest(call("string_to_array", vec![col("tags"), value(";")]).alias("tag"))
+            .select(vec![col("a"), col("b"), col("tag")]);
+
+        assert_eq!(
+            collect_into_vec(df).await.unwrap(),
+            vec![DataSet::from_csv_slice(
+                Arc::new(
+                    Schema::try_new(vec![
+                        Field::new("a", DataType::Int64),
+                        Field::new("b", DataType::String),
+                        Field::new("tag", DataType::String),
+                    ])
+                    .unwrap()
+                ),
+                CsvOptions::default(),
+                b"1,x,p\n1,x,q\n1,x,r\n2,y,s\n2,y,t\n2,y,u\n",
+            )
+            .unwrap()]
+        );
+    }
+
+    #[test]
+    fn test_unnest_not_list() {
+        let schema = Arc::new(Schema::try_new(vec![Field::new("a", DataType::Int64)]).unwrap());
+        let df = DataFrame::new(memory_source(schema, "1\n", 10), None, None).unnest(col("a"));
+        assert!(df.validate().is_err());
+    }
+}
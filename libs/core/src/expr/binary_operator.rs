@@ -5,10 +5,12 @@ use derive_more::Display;
 use serde::{Deserialize, Serialize};
 
 use crate::array::{
-    Array, ArrayExt, ArrayRef, BooleanArray, BooleanBuilder, BooleanType, DataType, Float32Type,
-    Float64Type, Int16Type, Int32Type, Int64Type, Int8Type, PrimitiveArray, PrimitiveBuilder,
-    PrimitiveType, StringArray,
+    compute, rescale_decimal, Array, ArrayExt, ArrayRef, BooleanArray, BooleanBuilder, BooleanType,
+    DataType, Decimal128Array, Decimal128Type, Float32Type, Float64Type, Int16Type, Int32Type,
+    Int64Type, Int8Type, PrimitiveArray, PrimitiveBuilder, PrimitiveType, StringArray,
+    TimestampType, UInt16Type, UInt32Type, UInt64Type, UInt8Type, DECIMAL128_MAX_PRECISION,
 };
+use crate::expr::like::like;
 
 macro_rules! check_overflow {
     ($expr:expr) => {
@@ -208,6 +210,12 @@ macro_rules! binary_arithmetic_array {
                 })
             }
 
+            (DataType::UInt64, DataType::UInt64) => {
+                math_op::<UInt64Type, UInt64Type, UInt64Type, _>($lhs, $rhs, |a, b| {
+                    check_overflow!(a.$op(b))
+                })
+            }
+
             _ => Err(binary_error($opcode, $lhs.data_type(), $rhs.data_type())),
         }
     };
@@ -300,6 +308,12 @@ macro_rules! binary_rem_array {
                 })
             }
 
+            (DataType::UInt64, DataType::UInt64) => {
+                math_op::<UInt64Type, UInt64Type, UInt64Type, _>($lhs, $rhs, |a, b| {
+                    check_overflow!(a.checked_rem(b))
+                })
+            }
+
             _ => Err(binary_error($opcode, $lhs.data_type(), $rhs.data_type())),
         }
     };
@@ -330,6 +344,8 @@ macro_rules! binary_equal_array {
 
             (DataType::Boolean, DataType::Boolean) => math_op::<BooleanType, BooleanType, BooleanType, _>($lhs, $rhs, |a, b| Ok(a $op b)),
 
+            (DataType::Timestamp(_), DataType::Timestamp(_)) => math_op::<TimestampType, TimestampType, BooleanType, _>($lhs, $rhs, |a, b| Ok(a $op b)),
+
             (DataType::String, DataType::String) => {
                 let a = $lhs.downcast_ref::<StringArray>();
                 let b = $rhs.downcast_ref::<StringArray>();
@@ -349,6 +365,8 @@ macro_rules! binary_equal_array {
                 Ok(Arc::new(builder.finish()))
             },
 
+            (DataType::UInt64, DataType::UInt64) => math_op::<UInt64Type, UInt64Type, BooleanType, _>($lhs, $rhs, |a, b| Ok(a $op b)),
+
             _ => Err(binary_error($opcode, $lhs.data_type(), $rhs.data_type())),
         }
     };
@@ -412,6 +430,10 @@ macro_rules! binary_order_array {
             (DataType::Float64, DataType::Float32) => math_op::<Float64Type, Float32Type, BooleanType, _>($lhs, $rhs, |a, b| Ok(a $op (b as f64))),
             (DataType::Float64, DataType::Float64) => math_op::<Float64Type, Float64Type, BooleanType, _>($lhs, $rhs, |a, b| Ok(a $op b)),
 
+            (DataType::Timestamp(_), DataType::Timestamp(_)) => math_op::<TimestampType, TimestampType, BooleanType, _>($lhs, $rhs, |a, b| Ok(a $op b)),
+
+            (DataType::UInt64, DataType::UInt64) => math_op::<UInt64Type, UInt64Type, BooleanType, _>($lhs, $rhs, |a, b| Ok(a $op b)),
+
             _ => Err(binary_error($opcode, $lhs.data_type(), $rhs.data_type())),
         }
     };
@@ -484,6 +506,12 @@ pub enum BinaryOperator {
 
     #[display(fmt = "%")]
     Rem,
+
+    #[display(fmt = "like")]
+    Like,
+
+    #[display(fmt = "not like")]
+    NotLike,
 }
 
 impl BinaryOperator {
@@ -491,9 +519,18 @@ impl BinaryOperator {
         use BinaryOperator::*;
         use DataType::*;
 
+        if left.is_decimal() || right.is_decimal() {
+            return decimal_data_type(*self, left, right);
+        }
+        if let Some(operand_type) = unsigned_operand_type(&left, &right) {
+            return self
+                .data_type(operand_type.clone(), operand_type)
+                .map_err(|_| binary_error(*self, left, right));
+        }
+
         match self {
             And | Or => {
-                if let (Boolean, Boolean) = (left, right) {
+                if let (Boolean, Boolean) = (&left, &right) {
                     Ok(Boolean)
                 } else {
                     Err(binary_error(*self, left, right))
@@ -502,6 +539,7 @@ impl BinaryOperator {
             Eq | NotEq => {
                 if (left.is_string() && right.is_string())
                     || (left.is_integer() && right.is_integer())
+                    || (left.is_timestamp() && right.is_timestamp())
                 {
                     Ok(Boolean)
                 } else {
@@ -511,6 +549,7 @@ impl BinaryOperator {
             Lt | LtEq | Gt | GtEq => {
                 if (left.is_numeric() && right.is_numeric())
                     || (left.is_string() && right.is_string())
+                    || (left.is_timestamp() && right.is_timestamp())
                 {
                     Ok(Boolean)
                 } else {
@@ -522,6 +561,8 @@ impl BinaryOperator {
                     || (left.is_numeric() && right.is_float())
                 {
                     Ok(DataType::Float64)
+                } else if let (UInt64, UInt64) = (&left, &right) {
+                    Ok(UInt64)
                 } else if left.is_integer() && right.is_integer() {
                     Ok(DataType::Int64)
                 } else {
@@ -529,12 +570,21 @@ impl BinaryOperator {
                 }
             }
             Rem => {
-                if left.is_integer() && right.is_integer() {
+                if let (UInt64, UInt64) = (&left, &right) {
+                    Ok(UInt64)
+                } else if left.is_integer() && right.is_integer() {
                     Ok(DataType::Int64)
                 } else {
                     Err(binary_error(*self, left, right))
                 }
             }
+            Like | NotLike => {
+                if left.is_string() && right.is_string() {
+                    Ok(Boolean)
+                } else {
+                    Err(binary_error(*self, left, right))
+                }
+            }
         }
     }
 
@@ -544,6 +594,15 @@ impl BinaryOperator {
             "cannot perform math operation on arrays of different length"
         );
 
+        if lhs.data_type().is_decimal() || rhs.data_type().is_decimal() {
+            return eval_decimal_array(*self, lhs, rhs);
+        }
+        if let Some(operand_type) = unsigned_operand_type(&lhs.data_type(), &rhs.data_type()) {
+            let lhs = compute::cast(lhs.slice(0, lhs.len()), operand_type.clone())?;
+            let rhs = compute::cast(rhs.slice(0, rhs.len()), operand_type)?;
+            return self.eval_array(&*lhs, &*rhs);
+        }
+
         match self {
             BinaryOperator::And => binary_logic_array!(*self, lhs, rhs, &&),
             BinaryOperator::Or => binary_logic_array!(*self, lhs, rhs, ||),
@@ -558,8 +617,32 @@ impl BinaryOperator {
             BinaryOperator::Multiply => binary_arithmetic_array!(*self, lhs, rhs, checked_mul, *),
             BinaryOperator::Divide => binary_arithmetic_array!(*self, lhs, rhs, checked_div, /),
             BinaryOperator::Rem => binary_rem_array!(*self, lhs, rhs),
+            BinaryOperator::Like => binary_like_array(*self, lhs, rhs, false),
+            BinaryOperator::NotLike => binary_like_array(*self, lhs, rhs, true),
+        }
+    }
+}
+
+fn binary_like_array(
+    op: BinaryOperator,
+    lhs: &dyn Array,
+    rhs: &dyn Array,
+    negated: bool,
+) -> Result<ArrayRef> {
+    if !lhs.data_type().is_string() || !rhs.data_type().is_string() {
+        return Err(binary_error(op, lhs.data_type(), rhs.data_type()));
+    }
+
+    let strings = lhs.downcast_ref::<StringArray>();
+    let patterns = rhs.downcast_ref::<StringArray>();
+    let mut builder = BooleanBuilder::with_capacity(strings.len());
+    for (string, pattern) in strings.iter_opt().zip(patterns.iter_opt()) {
+        match (string, pattern) {
+            (Some(string), Some(pattern)) => builder.append(like(string, pattern) != negated),
+            _ => builder.append_null(),
         }
     }
+    Ok(Arc::new(builder.finish()))
 }
 
 fn binary_error(op: BinaryOperator, left: DataType, right: DataType) -> Error {
@@ -571,6 +654,159 @@ fn binary_error(op: BinaryOperator, left: DataType, right: DataType) -> Error {
     )
 }
 
+/// Returns the type that both operands are cast to when an unsigned integer is mixed with another
+/// numeric type, or `None` if the operands are used as they are.
+///
+/// The unsigned integers narrower than 64 bits always fit in `Int64`. `UInt64` is only kept when
+/// both operands are unsigned, mixed with a signed integer both operands become decimals with
+/// scale 0, which holds every value of both types.
+fn unsigned_operand_type(left: &DataType, right: &DataType) -> Option<DataType> {
+    use DataType::*;
+
+    if !(left.is_unsigned_integer() || right.is_unsigned_integer())
+        || !left.is_numeric()
+        || !right.is_numeric()
+    {
+        return None;
+    }
+    match (left, right) {
+        (UInt64, UInt64) => None,
+        _ if left.is_float() || right.is_float() => Some(Float64),
+        (UInt64, other) | (other, UInt64) if other.is_unsigned_integer() => Some(UInt64),
+        (UInt64, _) | (_, UInt64) => Some(Decimal128 {
+            precision: DECIMAL128_MAX_PRECISION,
+            scale: 0,
+        }),
+        _ => Some(Int64),
+    }
+}
+
+/// Returns the scale of a decimal operand, integers are treated as decimals with scale 0.
+fn decimal_scale(data_type: &DataType) -> Option<u8> {
+    match data_type {
+        DataType::Decimal128 { scale, .. } => Some(*scale),
+        _ if data_type.is_integer() => Some(0),
+        _ => None,
+    }
+}
+
+/// Decimals can only be mixed with decimals and integers, a float must be cast explicitly so that
+/// the result is never silently rounded.
+fn decimal_data_type(op: BinaryOperator, left: DataType, right: DataType) -> Result<DataType> {
+    use BinaryOperator::*;
+
+    let (left_scale, right_scale) = match (decimal_scale(&left), decimal_scale(&right)) {
+        (Some(left_scale), Some(right_scale)) => (left_scale, right_scale),
+        _ => return Err(binary_error(op, left, right)),
+    };
+    let scale = match op {
+        Eq | NotEq | Lt | LtEq | Gt | GtEq => return Ok(DataType::Boolean),
+        Plus | Minus | Divide => left_scale.max(right_scale),
+        Multiply if left_scale + right_scale <= DECIMAL128_MAX_PRECISION => {
+            left_scale + right_scale
+        }
+        _ => return Err(binary_error(op, left, right)),
+    };
+    Ok(DataType::Decimal128 {
+        precision: DECIMAL128_MAX_PRECISION,
+        scale,
+    })
+}
+
+fn integer_to_decimal<T>(array: &dyn Array) -> PrimitiveArray<Decimal128Type>
+where
+    T: PrimitiveType,
+    T::Native: Into<i128>,
+{
+    let array = array.downcast_ref::<PrimitiveArray<T>>();
+    match array.to_scalar() {
+        Some(value) => PrimitiveArray::new_scalar(array.len(), value.map(Into::into)),
+        None => PrimitiveArray::from_opt_vec(
+            array
+                .iter_opt()
+                .map(|value| value.map(Into::into))
+                .collect(),
+        ),
+    }
+}
+
+fn decimal_operand(array: &dyn Array) -> PrimitiveArray<Decimal128Type> {
+    match array.data_type() {
+        DataType::Decimal128 { .. } => {
+            let values = array.downcast_ref::<Decimal128Array>().values();
+            values.slice_values(0, values.len())
+        }
+        DataType::Int8 => integer_to_decimal::<Int8Type>(array),
+        DataType::Int16 => integer_to_decimal::<Int16Type>(array),
+        DataType::Int32 => integer_to_decimal::<Int32Type>(array),
+        DataType::Int64 => integer_to_decimal::<Int64Type>(array),
+        DataType::UInt8 => integer_to_decimal::<UInt8Type>(array),
+        DataType::UInt16 => integer_to_decimal::<UInt16Type>(array),
+        DataType::UInt32 => integer_to_decimal::<UInt32Type>(array),
+        DataType::UInt64 => integer_to_decimal::<UInt64Type>(array),
+        _ => unreachable!(),
+    }
+}
+
+fn eval_decimal_array(op: BinaryOperator, lhs: &dyn Array, rhs: &dyn Array) -> Result<ArrayRef> {
+    use BinaryOperator::*;
+
+    let data_type = op.data_type(lhs.data_type(), rhs.data_type())?;
+    let a_scale = decimal_scale(&lhs.data_type()).unwrap();
+    let b_scale = decimal_scale(&rhs.data_type()).unwrap();
+    let scale = a_scale.max(b_scale);
+    let a = decimal_operand(lhs);
+    let b = decimal_operand(rhs);
+    let max_value = 10i128.pow(DECIMAL128_MAX_PRECISION as u32) - 1;
+    let rescale =
+        |value, from_scale, to_scale| check_overflow!(rescale_decimal(value, from_scale, to_scale));
+    let check_precision =
+        |value: Option<i128>| check_overflow!(value.filter(|value| value.abs() <= max_value));
+
+    macro_rules! compare {
+        ($op:tt) => {
+            math_op::<Decimal128Type, Decimal128Type, BooleanType, _>(&a, &b, |a, b| {
+                Ok(rescale(a, a_scale, scale)? $op rescale(b, b_scale, scale)?)
+            })
+        };
+    }
+
+    let values = match op {
+        Eq => return compare!(==),
+        NotEq => return compare!(!=),
+        Lt => return compare!(<),
+        LtEq => return compare!(<=),
+        Gt => return compare!(>),
+        GtEq => return compare!(>=),
+        Plus => math_op::<Decimal128Type, Decimal128Type, Decimal128Type, _>(&a, &b, |a, b| {
+            check_precision(rescale(a, a_scale, scale)?.checked_add(rescale(b, b_scale, scale)?))
+        })?,
+        Minus => math_op::<Decimal128Type, Decimal128Type, Decimal128Type, _>(&a, &b, |a, b| {
+            check_precision(rescale(a, a_scale, scale)?.checked_sub(rescale(b, b_scale, scale)?))
+        })?,
+        Multiply => {
+            math_op::<Decimal128Type, Decimal128Type, Decimal128Type, _>(&a, &b, |a, b| {
+                check_precision(a.checked_mul(b))
+            })?
+        }
+        // the dividend is scaled up so that the quotient has the result scale
+        Divide => math_op::<Decimal128Type, Decimal128Type, Decimal128Type, _>(&a, &b, |a, b| {
+            check_precision(rescale(a, a_scale, scale + b_scale)?.checked_div(b))
+        })?,
+        And | Or | Rem | Like | NotLike => unreachable!(),
+    };
+
+    let values = values.downcast_ref::<PrimitiveArray<Decimal128Type>>();
+    match data_type {
+        DataType::Decimal128 { precision, scale } => Ok(Arc::new(Decimal128Array::new(
+            precision,
+            scale,
+            values.slice_values(0, values.len()),
+        ))),
+        _ => unreachable!(),
+    }
+}
+
 #[inline]
 fn math_op<A, B, R, F>(a: &dyn Array, b: &dyn Array, f: F) -> Result<ArrayRef>
 where
@@ -581,15 +817,25 @@ where
 {
     let a = a.downcast_ref::<PrimitiveArray<A>>();
     let b = b.downcast_ref::<PrimitiveArray<B>>();
-    if let (Some(a_scalar), Some(b_scalar)) = (a.to_scalar(), b.to_scalar()) {
-        return match (a_scalar, b_scalar) {
-            (Some(a_scalar), Some(b_scalar)) => Ok(Arc::new(PrimitiveArray::<R>::new_scalar(
+    match (a.to_scalar(), b.to_scalar()) {
+        (Some(Some(a_scalar)), Some(Some(b_scalar))) => {
+            return Ok(Arc::new(PrimitiveArray::<R>::new_scalar(
                 a.len(),
                 Some(f(a_scalar, b_scalar)?),
-            ))),
-            _ => Ok(Arc::new(PrimitiveArray::<R>::new_scalar(a.len(), None))),
-        };
+            )));
+        }
+        (Some(None), _) | (_, Some(None)) => {
+            return Ok(Arc::new(PrimitiveArray::<R>::new_scalar(a.len(), None)));
+        }
+        (Some(Some(a_scalar)), None) => {
+            return map_array::<B, R, _>(b, |b| f(a_scalar, b));
+        }
+        (None, Some(Some(b_scalar))) => {
+            return map_array::<A, R, _>(a, |a| f(a, b_scalar));
+        }
+        (None, None) => {}
     }
+
     let mut builder = PrimitiveBuilder::<R>::with_capacity(a.len());
     for (a, b) in a.iter_opt().zip(b.iter_opt()) {
         match (a, b) {
@@ -599,3 +845,300 @@ where
     }
     Ok(Arc::new(builder.finish()))
 }
+
+/// Applies `f` to each value of the array, used when the other operand is a scalar.
+#[inline]
+fn map_array<A, R, F>(array: &PrimitiveArray<A>, f: F) -> Result<ArrayRef>
+where
+    A: PrimitiveType,
+    R: PrimitiveType,
+    F: Fn(A::Native) -> Result<R::Native>,
+{
+    let mut builder = PrimitiveBuilder::<R>::with_capacity(array.len());
+    for value in array.iter_opt() {
+        match value {
+            Some(value) => builder.append(f(value)?),
+            None => builder.append_null(),
+        }
+    }
+    Ok(Arc::new(builder.finish()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::array::{Float64Array, Int32Array, Int64Array, Scalar, UInt32Array, UInt64Array};
+
+    #[test]
+    fn test_like() {
+        let array = StringArray::from_opt_vec(vec![
+            Some("error_1"),
+            Some("warning"),
+            None,
+            Some("error%"),
+            Some("errors"),
+        ]);
+        let eval = |op: BinaryOperator, pattern: &str| {
+            op.eval_array(&array, &StringArray::new_scalar(5, Some(pattern)))
+                .unwrap()
+                .downcast_ref::<BooleanArray>()
+                .iter_opt()
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            eval(BinaryOperator::Like, "error_%"),
+            vec![Some(true), Some(false), None, Some(true), Some(true)]
+        );
+        assert_eq!(
+            eval(BinaryOperator::NotLike, "%rr%"),
+            vec![Some(false), Some(true), None, Some(false), Some(false)]
+        );
+        assert_eq!(
+            eval(BinaryOperator::Like, "error\\%"),
+            vec![Some(false), Some(false), None, Some(true), Some(false)]
+        );
+
+        assert_eq!(
+            BinaryOperator::Like
+                .data_type(DataType::String, DataType::String)
+                .unwrap(),
+            DataType::Boolean
+        );
+        assert_eq!(
+            BinaryOperator::NotLike
+                .data_type(DataType::String, DataType::Int64)
+                .unwrap_err()
+                .to_string(),
+            "cannot perform 'not like' operator on 'string' and 'int64' types"
+        );
+    }
+
+    fn eval_both(op: BinaryOperator, lhs: &dyn Array, rhs: &dyn Array) -> Result<ArrayRef> {
+        let res = op.eval_array(lhs, rhs)?;
+        let res_general = op.eval_array(&*materialize(lhs), &*materialize(rhs))?;
+        assert_eq!(res.len(), res_general.len());
+        for idx in 0..res.len() {
+            assert_eq!(res.scalar_value(idx), res_general.scalar_value(idx));
+        }
+        Ok(res)
+    }
+
+    fn materialize(array: &dyn Array) -> ArrayRef {
+        match array.data_type() {
+            DataType::Int32 => Arc::new(Int32Array::from_opt_vec(
+                array.downcast_ref::<Int32Array>().iter_opt().collect(),
+            )),
+            DataType::Int64 => Arc::new(Int64Array::from_opt_vec(
+                array.downcast_ref::<Int64Array>().iter_opt().collect(),
+            )),
+            DataType::Float64 => Arc::new(Float64Array::from_opt_vec(
+                array.downcast_ref::<Float64Array>().iter_opt().collect(),
+            )),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_array_op_scalar() {
+        let array = Int32Array::from_opt_vec(vec![Some(1), None, Some(3), Some(-4)]);
+        let ops = [
+            BinaryOperator::Plus,
+            BinaryOperator::Minus,
+            BinaryOperator::Multiply,
+            BinaryOperator::Divide,
+            BinaryOperator::Rem,
+            BinaryOperator::Eq,
+            BinaryOperator::Lt,
+            BinaryOperator::GtEq,
+        ];
+
+        for op in ops.iter() {
+            let scalar = Int64Array::new_scalar(4, Some(2));
+            eval_both(*op, &array, &scalar).unwrap();
+            eval_both(*op, &scalar, &array).unwrap();
+
+            let null_scalar = Int64Array::new_scalar(4, None);
+            let res = eval_both(*op, &array, &null_scalar).unwrap();
+            assert!((0..4).all(|idx| res.is_null(idx)));
+        }
+
+        let res = eval_both(
+            BinaryOperator::Multiply,
+            &array,
+            &Float64Array::new_scalar(4, Some(1.5)),
+        )
+        .unwrap();
+        assert_eq!(
+            res.downcast_ref::<Float64Array>()
+                .iter_opt()
+                .collect::<Vec<_>>(),
+            vec![Some(1.5), None, Some(4.5), Some(-6.0)]
+        );
+
+        assert_eq!(
+            BinaryOperator::Divide
+                .eval_array(&array, &Int64Array::new_scalar(4, Some(0)))
+                .unwrap_err()
+                .to_string(),
+            "arithmetic overflowed"
+        );
+        assert_eq!(
+            BinaryOperator::Plus
+                .eval_array(
+                    &Int64Array::new_scalar(1, Some(i64::MAX)),
+                    &Int64Array::from_vec(vec![1])
+                )
+                .unwrap_err()
+                .to_string(),
+            "arithmetic overflowed"
+        );
+    }
+
+    #[test]
+    fn test_decimal() {
+        let array = Decimal128Array::from_str_vec(10, 2, vec!["1.50", "-2.25", "100"]).unwrap();
+        let eval = |op: BinaryOperator, rhs: &dyn Array| {
+            let res = op.eval_array(&array, rhs).unwrap();
+            (0..res.len())
+                .map(|idx| res.scalar_value(idx).to_string())
+                .collect::<Vec<_>>()
+        };
+
+        let other = Decimal128Array::from_str_vec(5, 3, vec!["0.125", "1", "-0.001"]).unwrap();
+        assert_eq!(
+            eval(BinaryOperator::Plus, &other),
+            vec!["1.625", "-1.250", "99.999"]
+        );
+        assert_eq!(
+            eval(BinaryOperator::Multiply, &other),
+            vec!["0.18750", "-2.25000", "-0.10000"]
+        );
+        assert_eq!(
+            eval(BinaryOperator::Divide, &other),
+            vec!["12.000", "-2.250", "-100000.000"]
+        );
+        assert_eq!(
+            eval(BinaryOperator::Gt, &other),
+            vec!["true", "false", "true"]
+        );
+
+        let int = Int64Array::new_scalar(3, Some(2));
+        assert_eq!(
+            BinaryOperator::Plus
+                .data_type(array.data_type(), DataType::Int64)
+                .unwrap(),
+            DataType::Decimal128 {
+                precision: 38,
+                scale: 2
+            }
+        );
+        assert_eq!(
+            eval(BinaryOperator::Minus, &int),
+            vec!["-0.50", "-4.25", "98.00"]
+        );
+        assert_eq!(
+            BinaryOperator::Eq
+                .eval_array(&int, &Decimal128Array::new_scalar(5, 1, 3, Some(20)))
+                .unwrap()
+                .scalar_value(0),
+            Scalar::Boolean(true)
+        );
+
+        assert_eq!(
+            BinaryOperator::Plus
+                .data_type(array.data_type(), DataType::Float64)
+                .unwrap_err()
+                .to_string(),
+            "cannot perform '+' operator on 'decimal128(10, 2)' and 'float64' types"
+        );
+        assert!(BinaryOperator::Plus
+            .eval_array(&array, &Float64Array::new_scalar(3, Some(1.0)))
+            .is_err());
+        assert_eq!(
+            BinaryOperator::Divide
+                .eval_array(&array, &Int64Array::new_scalar(3, Some(0)))
+                .unwrap_err()
+                .to_string(),
+            "arithmetic overflowed"
+        );
+    }
+
+    #[test]
+    fn test_unsigned() {
+        let eval = |op: BinaryOperator, lhs: &dyn Array, rhs: &dyn Array| {
+            let data_type = op.data_type(lhs.data_type(), rhs.data_type()).unwrap();
+            let res = op.eval_array(lhs, rhs).unwrap();
+            assert_eq!(res.data_type(), data_type);
+            (0..res.len())
+                .map(|idx| res.scalar_value(idx))
+                .collect::<Vec<_>>()
+        };
+
+        let big = UInt64Array::from_vec(vec![u64::MAX - 1, 1]);
+        assert_eq!(
+            eval(
+                BinaryOperator::Plus,
+                &big,
+                &UInt64Array::new_scalar(2, Some(1))
+            ),
+            vec![Scalar::UInt64(u64::MAX), Scalar::UInt64(2)]
+        );
+        assert_eq!(
+            BinaryOperator::Plus
+                .eval_array(&big, &UInt64Array::new_scalar(2, Some(2)))
+                .unwrap_err()
+                .to_string(),
+            "arithmetic overflowed"
+        );
+
+        // the narrow unsigned integers are promoted to `Int64`
+        let small = UInt32Array::from_vec(vec![u32::MAX, 0]);
+        assert_eq!(
+            eval(
+                BinaryOperator::Minus,
+                &small,
+                &Int32Array::new_scalar(2, Some(1))
+            ),
+            vec![Scalar::Int64(u32::MAX as i64 - 1), Scalar::Int64(-1)]
+        );
+
+        // mixed with a signed integer, `UInt64` is compared without wrapping around
+        let signed = Int64Array::from_vec(vec![-2, 1]);
+        assert_eq!(
+            eval(BinaryOperator::Eq, &big, &signed),
+            vec![Scalar::Boolean(false), Scalar::Boolean(true)]
+        );
+        assert_eq!(
+            eval(BinaryOperator::Gt, &big, &signed),
+            vec![Scalar::Boolean(true), Scalar::Boolean(false)]
+        );
+        assert_eq!(
+            BinaryOperator::Minus
+                .data_type(DataType::UInt64, DataType::Int64)
+                .unwrap(),
+            DataType::Decimal128 {
+                precision: DECIMAL128_MAX_PRECISION,
+                scale: 0
+            }
+        );
+        assert_eq!(
+            eval(
+                BinaryOperator::Multiply,
+                &big,
+                &Float64Array::new_scalar(2, Some(0.5))
+            ),
+            vec![
+                Scalar::Float64((u64::MAX - 1) as f64 * 0.5),
+                Scalar::Float64(0.5)
+            ]
+        );
+        assert_eq!(
+            BinaryOperator::Rem
+                .data_type(DataType::UInt64, DataType::Int8)
+                .unwrap_err()
+                .to_string(),
+            "cannot perform '%' operator on 'uint64' and 'int8' types"
+        );
+    }
+}
@@ -34,6 +34,58 @@ impl Display for Expr {
             Expr::Alias(expr, name) => {
                 write!(f, "{} as {}", expr, name)
             }
+            Expr::Over { expr, window } => write!(f, "{} over window {}", expr, window),
+            Expr::Case {
+                operand,
+                when_then,
+                else_expr,
+            } => {
+                f.write_str("case")?;
+                if let Some(operand) = operand {
+                    write!(f, " {}", operand)?;
+                }
+                for (when, then) in when_then {
+                    write!(f, " when {} then {}", when, then)?;
+                }
+                if let Some(else_expr) = else_expr {
+                    write!(f, " else {}", else_expr)?;
+                }
+                f.write_str(" end")
+            }
+            Expr::Cast { expr, data_type } => write!(f, "cast({} as {})", expr, data_type),
+            Expr::NamedArgument { name, expr } => write!(f, "{} => {}", name, expr),
+            Expr::Distinct(expr) => write!(f, "distinct {}", expr),
+            Expr::InList {
+                expr,
+                list,
+                negated,
+            } => {
+                write!(f, "{} ", expr)?;
+                if *negated {
+                    f.write_str("not ")?;
+                }
+                f.write_str("in (")?;
+                for (idx, element) in list.iter().enumerate() {
+                    if idx > 0 {
+                        f.write_str(",")?;
+                    }
+                    write!(f, "{}", element)?;
+                }
+                f.write_char(')')
+            }
+            Expr::Between {
+                expr,
+                low,
+                high,
+                negated,
+            } => {
+                write!(f, "{} ", expr)?;
+                if *negated {
+                    f.write_str("not ")?;
+                }
+                write!(f, "between {} and {}", low, high)
+            }
+            Expr::SubQuery(_) => f.write_str("(subquery)"),
         }
     }
 }
@@ -2,7 +2,12 @@ use std::ops::{Add, Div, Mul, Neg, Not, Rem, Sub};
 
 use serde::{Deserialize, Serialize};
 
+use crate::array::DataType;
+use crate::expr::func::FunctionType;
+use crate::expr::funcs::find_function;
 use crate::expr::{BinaryOperator, Literal, UnaryOperator};
+use crate::sql::ast::Select;
+use crate::Window;
 
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub enum Expr {
@@ -29,6 +34,46 @@ pub enum Expr {
         args: Vec<Expr>,
     },
     Alias(Box<Expr>, String),
+    Over {
+        expr: Box<Expr>,
+        window: Window,
+    },
+    /// `CASE [operand] WHEN .. THEN .. [ELSE ..] END`, the `WHEN` expressions are conditions
+    /// without the operand, or compared with the operand for equality.
+    Case {
+        operand: Option<Box<Expr>>,
+        when_then: Vec<(Expr, Expr)>,
+        else_expr: Option<Box<Expr>>,
+    },
+    /// `CAST(expr AS type)`, or the shorthand `expr::type`.
+    Cast {
+        expr: Box<Expr>,
+        data_type: DataType,
+    },
+    /// `name => expr`, an argument of a function call passed by the name of the parameter.
+    NamedArgument {
+        name: String,
+        expr: Box<Expr>,
+    },
+    /// `DISTINCT expr`, the argument of an aggregate function that only folds each value once.
+    Distinct(Box<Expr>),
+    /// `expr [NOT] IN (list)`, null if `expr` is null, or if no element of the list is equal to it
+    /// but some element is null.
+    InList {
+        expr: Box<Expr>,
+        list: Vec<Expr>,
+        negated: bool,
+    },
+    /// `expr [NOT] BETWEEN low AND high`, evaluated as `expr >= low AND expr <= high`.
+    Between {
+        expr: Box<Expr>,
+        low: Box<Expr>,
+        high: Box<Expr>,
+        negated: bool,
+    },
+    /// `(SELECT expr FROM source WHERE a = b)`, a scalar subquery in the projection that looks up
+    /// the value for each row in a bounded source.
+    SubQuery(Box<Select>),
 }
 
 impl Expr {
@@ -36,6 +81,13 @@ impl Expr {
         Expr::Alias(Box::new(self), alias.into())
     }
 
+    pub fn over(self, window: Window) -> Expr {
+        Expr::Over {
+            expr: Box::new(self),
+            window,
+        }
+    }
+
     pub fn eq(self, rhs: Expr) -> Expr {
         Expr::Binary {
             op: BinaryOperator::Eq,
@@ -100,10 +152,82 @@ impl Expr {
         }
     }
 
+    pub fn like(self, pattern: Expr) -> Expr {
+        Expr::Binary {
+            op: BinaryOperator::Like,
+            lhs: Box::new(self),
+            rhs: Box::new(pattern),
+        }
+    }
+
+    pub fn not_like(self, pattern: Expr) -> Expr {
+        Expr::Binary {
+            op: BinaryOperator::NotLike,
+            lhs: Box::new(self),
+            rhs: Box::new(pattern),
+        }
+    }
+
+    pub fn in_list(self, list: Vec<Expr>, negated: bool) -> Expr {
+        Expr::InList {
+            expr: Box::new(self),
+            list,
+            negated,
+        }
+    }
+
+    pub fn between(self, low: Expr, high: Expr, negated: bool) -> Expr {
+        Expr::Between {
+            expr: Box::new(self),
+            low: Box::new(low),
+            high: Box::new(high),
+            negated,
+        }
+    }
+
+    pub fn cast(self, data_type: DataType) -> Expr {
+        Expr::Cast {
+            expr: Box::new(self),
+            data_type,
+        }
+    }
+
+    /// Returns the window specified for this projection field.
+    pub(crate) fn window(&self) -> Option<Window> {
+        match self {
+            Expr::Over { window, .. } => Some(*window),
+            Expr::Alias(expr, _) => expr.window(),
+            _ => None,
+        }
+    }
+
+    /// Removes the window specified for this projection field.
+    pub(crate) fn without_window(self) -> Expr {
+        match self {
+            Expr::Over { expr, .. } => *expr,
+            Expr::Alias(expr, name) => Expr::Alias(Box::new(expr.without_window()), name),
+            _ => self,
+        }
+    }
+
+    /// Returns `true` if this is a call of a stateful function, e.g. an aggregate function.
+    pub(crate) fn is_stateful_call(&self) -> bool {
+        match self {
+            Expr::Call {
+                namespace, name, ..
+            } => matches!(
+                find_function(namespace.as_deref(), name).map(|func| &func.function_type),
+                Some(FunctionType::Stateful(_))
+            ),
+            _ => false,
+        }
+    }
+
     pub fn create_name(&self) -> String {
         match self {
             Expr::Column { name, .. } => name.clone(),
             Expr::Alias(_, name) => name.clone(),
+            Expr::Over { expr, .. } => expr.create_name(),
             _ => self.to_string(),
         }
     }
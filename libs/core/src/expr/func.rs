@@ -1,8 +1,12 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
 use anyhow::Result;
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 
-use crate::array::{ArrayRef, DataType};
+use crate::array::{ArrayExt, ArrayRef, DataType, Scalar};
+use crate::dataset::{DataSet, Field, Schema};
 use crate::expr::signature::Signature;
 
 pub trait GenericStatefulFunction: dyn_clone::DynClone + Sync + Send + 'static {
@@ -11,6 +15,19 @@ pub trait GenericStatefulFunction: dyn_clone::DynClone + Sync + Send + 'static {
     fn save_state(&self) -> Result<Vec<u8>>;
 
     fn load_state(&mut self, state: Vec<u8>) -> Result<()>;
+
+    /// Merges the state saved by another instance of the function, e.g. one that processed
+    /// another partition of the data.
+    fn merge_state(&mut self, state: Vec<u8>) -> Result<()>;
+
+    /// Returns `true` if `merge_state` is supported.
+    fn can_merge(&self) -> bool;
+
+    /// Merges the state of another instance of the function, so that the partial results of
+    /// multiple partitions can be combined.
+    fn merge(&mut self, other: &dyn GenericStatefulFunction) -> Result<()> {
+        self.merge_state(other.save_state()?)
+    }
 }
 
 dyn_clone::clone_trait_object!(GenericStatefulFunction);
@@ -22,6 +39,7 @@ where
 {
     state: T,
     f: fn(&mut T, &[ArrayRef]) -> Result<ArrayRef>,
+    merge: Option<fn(&mut T, T) -> Result<()>>,
 }
 
 impl<T> StatefulFunction<T>
@@ -32,6 +50,15 @@ where
         Self {
             state: T::default(),
             f,
+            merge: None,
+        }
+    }
+
+    /// Allows the states of two instances of the function to be merged.
+    pub fn with_merge(self, merge: fn(&mut T, T) -> Result<()>) -> Self {
+        Self {
+            merge: Some(merge),
+            ..self
         }
     }
 }
@@ -55,6 +82,90 @@ where
         self.state = state;
         Ok(())
     }
+
+    fn merge_state(&mut self, state: Vec<u8>) -> Result<()> {
+        let merge = self
+            .merge
+            .ok_or_else(|| anyhow::anyhow!("the function state can't be merged"))?;
+        let state = bincode::deserialize(&state)
+            .map_err(|err| anyhow::anyhow!("failed to deserialize function state: {}", err))?;
+        merge(&mut self.state, state)
+    }
+
+    fn can_merge(&self) -> bool {
+        self.merge.is_some()
+    }
+}
+
+/// Wraps an aggregate function of one argument, so that it only folds the values that it has not
+/// seen before, e.g. `count(distinct a)`.
+///
+/// Every distinct value is kept in the state, so the memory and the size of the saved state grow
+/// with the number of distinct values of the group.
+#[derive(Clone)]
+pub struct DistinctFunction {
+    func: Box<dyn GenericStatefulFunction>,
+    /// The serialized values that have been folded.
+    seen: HashSet<Vec<u8>>,
+}
+
+impl DistinctFunction {
+    pub fn new(func: Box<dyn GenericStatefulFunction>) -> Self {
+        Self {
+            func,
+            seen: HashSet::new(),
+        }
+    }
+}
+
+impl GenericStatefulFunction for DistinctFunction {
+    fn call(&mut self, args: &[ArrayRef]) -> Result<ArrayRef> {
+        let array = &args[0];
+        let mut rows = Vec::with_capacity(array.len());
+        let mut has_repeated = false;
+        for index in 0..array.len() {
+            let value = array.scalar_value(index);
+            // a repeated value is replaced with null, which the aggregate functions skip
+            if array.is_valid(index) && !self.seen.insert(bincode::serialize(&value)?) {
+                has_repeated = true;
+                rows.push(vec![Scalar::Null]);
+            } else {
+                rows.push(vec![value]);
+            }
+        }
+        if !has_repeated {
+            return self.func.call(args);
+        }
+
+        let schema = Arc::new(Schema::try_new(vec![Field::new(
+            "distinct",
+            array.data_type(),
+        )])?);
+        let array = DataSet::try_from_rows(schema, rows)?.columns()[0].clone();
+        self.func.call(&[array])
+    }
+
+    fn save_state(&self) -> Result<Vec<u8>> {
+        bincode::serialize(&(self.func.save_state()?, &self.seen))
+            .map_err(|err| anyhow::anyhow!("failed to serialize function state: {}", err))
+    }
+
+    fn load_state(&mut self, state: Vec<u8>) -> Result<()> {
+        let (func_state, seen) = bincode::deserialize(&state)
+            .map_err(|err| anyhow::anyhow!("failed to deserialize function state: {}", err))?;
+        self.func.load_state(func_state)?;
+        self.seen = seen;
+        Ok(())
+    }
+
+    fn merge_state(&mut self, _state: Vec<u8>) -> Result<()> {
+        // the values seen by both instances would be folded twice
+        anyhow::bail!("the state of an aggregate function with DISTINCT can't be merged")
+    }
+
+    fn can_merge(&self) -> bool {
+        false
+    }
 }
 
 #[derive(Clone)]
@@ -84,6 +195,9 @@ impl FunctionType {
 pub struct Function {
     pub namespace: Option<&'static str>,
     pub name: &'static str,
+    /// The names of the parameters, so that the arguments can also be passed by name, empty if
+    /// the arguments can only be passed by position.
+    pub params: &'static [&'static str],
     pub signature: &'static Signature,
     pub return_type: fn(&[DataType]) -> DataType,
     pub function_type: FunctionType,
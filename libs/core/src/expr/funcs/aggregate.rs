@@ -1,71 +1,242 @@
+use std::cmp::Ordering;
 use std::sync::Arc;
 
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
 use crate::array::{
-    Array, ArrayExt, BooleanType, DataType, Float32Type, Float64Array, Float64Builder, Float64Type,
-    Int16Type, Int32Type, Int64Builder, Int64Type, Int8Type, NullArray, PrimitiveArray,
-    PrimitiveBuilder, Scalar, StringArray, StringBuilder, TimestampType,
+    Array, ArrayExt, ArrayRef, BooleanType, DataType, Decimal128Array, Decimal128Builder,
+    Float32Type, Float64Array, Float64Builder, Float64Type, Int16Type, Int32Type, Int64Builder,
+    Int64Type, Int8Type, ListArray, NullArray, PrimitiveArray, PrimitiveBuilder, Scalar,
+    StringArray, StringBuilder, TimestampType, UInt16Type, UInt32Type, UInt64Type, UInt8Type,
+    DECIMAL128_MAX_PRECISION,
 };
+use crate::dataset::{DataSet, Field, Schema};
 use crate::expr::func::{Function, FunctionType, StatefulFunction};
 use crate::expr::signature::Signature;
 
 pub const AVG: Function = Function {
     namespace: None,
     name: "avg",
+    params: &[],
     signature: &Signature::Exact(&[DataType::Float64]),
     return_type: |_| DataType::Float64,
     function_type: FunctionType::Stateful(|| {
-        Box::new(StatefulFunction::<(f64, f64)>::new(|state, args| {
-            let array = args[0].downcast_ref::<Float64Array>();
-            let mut builder = Float64Builder::with_capacity(array.len());
-            for value in array.iter_opt() {
-                if let Some(value) = value {
-                    state.0 += value;
-                    state.1 += 1.0;
+        Box::new(
+            StatefulFunction::<(f64, f64)>::new(|state, args| {
+                let array = args[0].downcast_ref::<Float64Array>();
+                let mut builder = Float64Builder::with_capacity(array.len());
+                for value in array.iter_opt() {
+                    if let Some(value) = value {
+                        state.0 += value;
+                        state.1 += 1.0;
+                    }
+                    builder.append(state.0 / state.1);
                 }
-                builder.append(state.0 / state.1);
-            }
-            Ok(Arc::new(builder.finish()))
-        }))
+                Ok(Arc::new(builder.finish()))
+            })
+            .with_merge(|state, other| {
+                state.0 += other.0;
+                state.1 += other.1;
+                Ok(())
+            }),
+        )
     }),
 };
 
 pub const SUM: Function = Function {
     namespace: None,
     name: "sum",
-    signature: &Signature::Exact(&[DataType::Float64]),
-    return_type: |_| DataType::Float64,
+    params: &[],
+    signature: &Signature::OneOf(&[
+        Signature::AnyDecimal(1),
+        Signature::Exact(&[DataType::Float64]),
+    ]),
+    return_type: |args| match args[0] {
+        DataType::Decimal128 { scale, .. } => DataType::Decimal128 {
+            precision: DECIMAL128_MAX_PRECISION,
+            scale,
+        },
+        _ => DataType::Float64,
+    },
     function_type: FunctionType::Stateful(|| {
-        Box::new(StatefulFunction::<f64>::new(|state, args| {
-            let array = args[0].downcast_ref::<Float64Array>();
-            let mut builder = Float64Builder::with_capacity(array.len());
-            for value in array.iter_opt() {
-                if let Some(value) = value {
-                    *state += value;
+        Box::new(
+            StatefulFunction::<Scalar>::new(|state, args| match args[0].data_type() {
+                DataType::Decimal128 { scale, .. } => {
+                    let array = args[0].downcast_ref::<Decimal128Array>();
+                    let mut sum = match state {
+                        Scalar::Decimal128 { value, .. } => *value,
+                        _ => 0,
+                    };
+                    let mut builder = Decimal128Builder::with_capacity(
+                        DECIMAL128_MAX_PRECISION,
+                        scale,
+                        array.len(),
+                    );
+                    for value in array.iter_opt() {
+                        if let Some(value) = value {
+                            sum = sum
+                                .checked_add(value)
+                                .ok_or_else(|| anyhow::anyhow!("decimal overflow in sum"))?;
+                        }
+                        builder.append(sum);
+                    }
+                    *state = Scalar::Decimal128 {
+                        value: sum,
+                        precision: DECIMAL128_MAX_PRECISION,
+                        scale,
+                    };
+                    Ok(Arc::new(builder.finish()))
                 }
-                builder.append(*state);
-            }
-            Ok(Arc::new(builder.finish()))
-        }))
+                _ => {
+                    let array = args[0].downcast_ref::<Float64Array>();
+                    let mut sum = match state {
+                        Scalar::Float64(value) => *value,
+                        _ => 0.0,
+                    };
+                    let mut builder = Float64Builder::with_capacity(array.len());
+                    for value in array.iter_opt() {
+                        if let Some(value) = value {
+                            sum += value;
+                        }
+                        builder.append(sum);
+                    }
+                    *state = Scalar::Float64(sum);
+                    Ok(Arc::new(builder.finish()))
+                }
+            })
+            .with_merge(|state, other| {
+                *state = match (&*state, other) {
+                    (_, Scalar::Null) => return Ok(()),
+                    (Scalar::Null, other) => other,
+                    (Scalar::Float64(a), Scalar::Float64(b)) => Scalar::Float64(a + b),
+                    (
+                        Scalar::Decimal128 {
+                            value: a,
+                            precision,
+                            scale,
+                        },
+                        Scalar::Decimal128 { value: b, .. },
+                    ) => Scalar::Decimal128 {
+                        value: a
+                            .checked_add(b)
+                            .ok_or_else(|| anyhow::anyhow!("decimal overflow in sum"))?,
+                        precision: *precision,
+                        scale: *scale,
+                    },
+                    _ => unreachable!(),
+                };
+                Ok(())
+            }),
+        )
     }),
 };
 
+/// The number of the non-null values, it returns `Int64` which is the type of the array it
+/// computes.
 pub const COUNT: Function = Function {
     namespace: None,
     name: "count",
+    params: &[],
     signature: &Signature::Any(1),
+    return_type: |_| DataType::Int64,
+    function_type: FunctionType::Stateful(|| {
+        Box::new(
+            StatefulFunction::<i64>::new(|state, args| {
+                let array = &args[0];
+                let mut builder = Int64Builder::with_capacity(array.len());
+                for i in 0..args[0].len() {
+                    if args[0].is_valid(i) {
+                        *state += 1;
+                    }
+                    builder.append(*state);
+                }
+                Ok(Arc::new(builder.finish()))
+            })
+            .with_merge(|state, other| {
+                *state += other;
+                Ok(())
+            }),
+        )
+    }),
+};
+
+#[derive(Default, Clone, Serialize, Deserialize)]
+struct CorrState {
+    count: f64,
+    mean_x: f64,
+    mean_y: f64,
+    m2_x: f64,
+    m2_y: f64,
+    c_xy: f64,
+}
+
+impl CorrState {
+    fn update(&mut self, x: f64, y: f64) {
+        self.count += 1.0;
+        let dx = x - self.mean_x;
+        self.mean_x += dx / self.count;
+        let dy = y - self.mean_y;
+        self.mean_y += dy / self.count;
+        self.m2_x += dx * (x - self.mean_x);
+        self.m2_y += dy * (y - self.mean_y);
+        self.c_xy += dx * (y - self.mean_y);
+    }
+
+    /// Combines the moments of two sets of rows with the pairwise formulas of Chan et al.
+    fn merge(&mut self, other: CorrState) {
+        if other.count == 0.0 {
+            return;
+        }
+        if self.count == 0.0 {
+            *self = other;
+            return;
+        }
+        let count = self.count + other.count;
+        let dx = other.mean_x - self.mean_x;
+        let dy = other.mean_y - self.mean_y;
+        let factor = self.count * other.count / count;
+        self.mean_x += dx * other.count / count;
+        self.mean_y += dy * other.count / count;
+        self.m2_x += other.m2_x + dx * dx * factor;
+        self.m2_y += other.m2_y + dy * dy * factor;
+        self.c_xy += other.c_xy + dx * dy * factor;
+        self.count = count;
+    }
+
+    fn value(&self) -> Option<f64> {
+        if self.count < 2.0 || self.m2_x == 0.0 || self.m2_y == 0.0 {
+            return None;
+        }
+        Some((self.c_xy / (self.m2_x * self.m2_y).sqrt()).clamp(-1.0, 1.0))
+    }
+}
+
+pub const CORR: Function = Function {
+    namespace: None,
+    name: "corr",
+    params: &[],
+    signature: &Signature::Exact(&[DataType::Float64, DataType::Float64]),
     return_type: |_| DataType::Float64,
     function_type: FunctionType::Stateful(|| {
-        Box::new(StatefulFunction::<i64>::new(|state, args| {
-            let array = &args[0];
-            let mut builder = Int64Builder::with_capacity(array.len());
-            for i in 0..args[0].len() {
-                if args[0].is_valid(i) {
-                    *state += 1;
+        Box::new(
+            StatefulFunction::<CorrState>::new(|state, args| {
+                let x = args[0].downcast_ref::<Float64Array>();
+                let y = args[1].downcast_ref::<Float64Array>();
+                let mut builder = Float64Builder::with_capacity(x.len());
+                for (x, y) in x.iter_opt().zip(y.iter_opt()) {
+                    if let (Some(x), Some(y)) = (x, y) {
+                        state.update(x, y);
+                    }
+                    builder.append_opt(state.value());
                 }
-                builder.append(*state);
-            }
-            Ok(Arc::new(builder.finish()))
-        }))
+                Ok(Arc::new(builder.finish()))
+            })
+            .with_merge(|state, other| {
+                state.merge(other);
+                Ok(())
+            }),
+        )
     }),
 };
 
@@ -97,11 +268,33 @@ macro_rules! max_min {
     }};
 }
 
+macro_rules! merge_max_min {
+    ($state:expr, $other:expr, $func:ident) => {{
+        *$state = match (&*$state, $other) {
+            (_, Scalar::Null) => return Ok(()),
+            (Scalar::Null, other) => other,
+            (Scalar::Float64(a), Scalar::Float64(b)) => Scalar::Float64((*a).$func(b)),
+            (Scalar::Float32(a), Scalar::Float32(b)) => Scalar::Float32((*a).$func(b)),
+            (Scalar::Int64(a), Scalar::Int64(b)) => Scalar::Int64((*a).$func(b)),
+            (Scalar::Int32(a), Scalar::Int32(b)) => Scalar::Int32((*a).$func(b)),
+            (Scalar::Int16(a), Scalar::Int16(b)) => Scalar::Int16((*a).$func(b)),
+            (Scalar::Int8(a), Scalar::Int8(b)) => Scalar::Int8((*a).$func(b)),
+            (Scalar::UInt64(a), Scalar::UInt64(b)) => Scalar::UInt64((*a).$func(b)),
+            (Scalar::UInt32(a), Scalar::UInt32(b)) => Scalar::UInt32((*a).$func(b)),
+            (Scalar::UInt16(a), Scalar::UInt16(b)) => Scalar::UInt16((*a).$func(b)),
+            (Scalar::UInt8(a), Scalar::UInt8(b)) => Scalar::UInt8((*a).$func(b)),
+            _ => unreachable!(),
+        };
+        Ok(())
+    }};
+}
+
 macro_rules! make_max_min_func {
     ($ident:ident, $name:literal, $func:ident) => {
         pub const $ident: Function = Function {
             namespace: None,
             name: $name,
+            params: &[],
             signature: &Signature::Uniform(
                 1,
                 &[
@@ -111,34 +304,53 @@ macro_rules! make_max_min_func {
                     DataType::Int32,
                     DataType::Int16,
                     DataType::Int8,
+                    DataType::UInt64,
+                    DataType::UInt32,
+                    DataType::UInt16,
+                    DataType::UInt8,
                 ],
             ),
-            return_type: |args| args[0],
+            return_type: |args| args[0].clone(),
             function_type: FunctionType::Stateful(|| {
-                Box::new(StatefulFunction::<Scalar>::new(|state, args| {
-                    let array = &args[0];
-                    match array.data_type() {
-                        DataType::Float64 => {
-                            max_min!(array, state, Float64Type, Float64, $func)
-                        }
-                        DataType::Float32 => {
-                            max_min!(array, state, Float32Type, Float32, $func)
-                        }
-                        DataType::Int64 => {
-                            max_min!(array, state, Int64Type, Int64, $func)
-                        }
-                        DataType::Int32 => {
-                            max_min!(array, state, Int32Type, Int32, $func)
-                        }
-                        DataType::Int16 => {
-                            max_min!(array, state, Int16Type, Int16, $func)
-                        }
-                        DataType::Int8 => {
-                            max_min!(array, state, Int8Type, Int8, $func)
+                Box::new(
+                    StatefulFunction::<Scalar>::new(|state, args| {
+                        let array = &args[0];
+                        match array.data_type() {
+                            DataType::Float64 => {
+                                max_min!(array, state, Float64Type, Float64, $func)
+                            }
+                            DataType::Float32 => {
+                                max_min!(array, state, Float32Type, Float32, $func)
+                            }
+                            DataType::Int64 => {
+                                max_min!(array, state, Int64Type, Int64, $func)
+                            }
+                            DataType::Int32 => {
+                                max_min!(array, state, Int32Type, Int32, $func)
+                            }
+                            DataType::Int16 => {
+                                max_min!(array, state, Int16Type, Int16, $func)
+                            }
+                            DataType::Int8 => {
+                                max_min!(array, state, Int8Type, Int8, $func)
+                            }
+                            DataType::UInt64 => {
+                                max_min!(array, state, UInt64Type, UInt64, $func)
+                            }
+                            DataType::UInt32 => {
+                                max_min!(array, state, UInt32Type, UInt32, $func)
+                            }
+                            DataType::UInt16 => {
+                                max_min!(array, state, UInt16Type, UInt16, $func)
+                            }
+                            DataType::UInt8 => {
+                                max_min!(array, state, UInt8Type, UInt8, $func)
+                            }
+                            _ => unreachable!(),
                         }
-                        _ => unreachable!(),
-                    }
-                }))
+                    })
+                    .with_merge(|state, other| merge_max_min!(state, other, $func)),
+                )
             }),
         };
     };
@@ -178,48 +390,65 @@ macro_rules! first_value {
 pub const FIRST: Function = Function {
     namespace: None,
     name: "first",
+    params: &[],
     signature: &Signature::Any(1),
-    return_type: |args| args[0],
+    return_type: |args| args[0].clone(),
     function_type: FunctionType::Stateful(|| {
-        Box::new(StatefulFunction::<Scalar>::new(|state, args| {
-            let array = &args[0];
-            match array.data_type() {
-                DataType::Null => Ok(Arc::new(NullArray::new(array.len()))),
-                DataType::Int8 => first_value!(array, state, Int8Type, Int8),
-                DataType::Int16 => first_value!(array, state, Int16Type, Int16),
-                DataType::Int32 => first_value!(array, state, Int32Type, Int32),
-                DataType::Int64 => first_value!(array, state, Int64Type, Int64),
-                DataType::Float32 => first_value!(array, state, Float32Type, Float32),
-                DataType::Float64 => first_value!(array, state, Float64Type, Float64),
-                DataType::Boolean => first_value!(array, state, BooleanType, Boolean),
-                DataType::Timestamp(_) => first_value!(array, state, TimestampType, Timestamp),
-                DataType::String => {
-                    let array = array.downcast_ref::<StringArray>();
-                    let mut builder = StringBuilder::with_capacity(array.len());
-                    for value in array.iter_opt() {
-                        match value {
-                            Some(value) => match state {
-                                Scalar::String(first_value) => {
-                                    builder.append(first_value);
-                                }
-                                _ => {
-                                    *state = Scalar::String(value.into());
-                                    builder.append(value);
-                                }
-                            },
-                            None => {
-                                if let Scalar::String(first_value) = state {
-                                    builder.append(first_value);
-                                } else {
-                                    builder.append_null();
+        Box::new(
+            StatefulFunction::<Scalar>::new(|state, args| {
+                let array = &args[0];
+                match array.data_type() {
+                    DataType::Null => Ok(Arc::new(NullArray::new(array.len()))),
+                    DataType::Int8 => first_value!(array, state, Int8Type, Int8),
+                    DataType::Int16 => first_value!(array, state, Int16Type, Int16),
+                    DataType::Int32 => first_value!(array, state, Int32Type, Int32),
+                    DataType::Int64 => first_value!(array, state, Int64Type, Int64),
+                    DataType::UInt8 => first_value!(array, state, UInt8Type, UInt8),
+                    DataType::UInt16 => first_value!(array, state, UInt16Type, UInt16),
+                    DataType::UInt32 => first_value!(array, state, UInt32Type, UInt32),
+                    DataType::UInt64 => first_value!(array, state, UInt64Type, UInt64),
+                    DataType::Float32 => first_value!(array, state, Float32Type, Float32),
+                    DataType::Float64 => first_value!(array, state, Float64Type, Float64),
+                    DataType::Boolean => first_value!(array, state, BooleanType, Boolean),
+                    DataType::Timestamp(_) => first_value!(array, state, TimestampType, Timestamp),
+                    DataType::String => {
+                        let array = array.downcast_ref::<StringArray>();
+                        let mut builder = StringBuilder::with_capacity(array.len());
+                        for value in array.iter_opt() {
+                            match value {
+                                Some(value) => match state {
+                                    Scalar::String(first_value) => {
+                                        builder.append(first_value);
+                                    }
+                                    _ => {
+                                        *state = Scalar::String(value.into());
+                                        builder.append(value);
+                                    }
+                                },
+                                None => {
+                                    if let Scalar::String(first_value) = state {
+                                        builder.append(first_value);
+                                    } else {
+                                        builder.append_null();
+                                    }
                                 }
                             }
                         }
+                        Ok(Arc::new(builder.finish()))
+                    }
+                    DataType::Decimal128 { .. } | DataType::List(_) | DataType::Struct(_) => {
+                        anyhow::bail!("unsupported datatype: {}", array.data_type())
                     }
-                    Ok(Arc::new(builder.finish()))
                 }
-            }
-        }))
+            })
+            // the other state is of the later rows
+            .with_merge(|state, other| {
+                if *state == Scalar::Null {
+                    *state = other;
+                }
+                Ok(())
+            }),
+        )
     }),
 };
 
@@ -249,49 +478,505 @@ macro_rules! last_value {
 pub const LAST: Function = Function {
     namespace: None,
     name: "last",
+    params: &[],
     signature: &Signature::Any(1),
-    return_type: |args| args[0],
+    return_type: |args| args[0].clone(),
     function_type: FunctionType::Stateful(|| {
-        Box::new(StatefulFunction::<Scalar>::new(|state, args| {
-            let array = &args[0];
-            match array.data_type() {
-                DataType::Null => Ok(Arc::new(NullArray::new(array.len()))),
-                DataType::Int8 => last_value!(array, state, Int8Type, Int8),
-                DataType::Int16 => last_value!(array, state, Int16Type, Int16),
-                DataType::Int32 => last_value!(array, state, Int32Type, Int32),
-                DataType::Int64 => last_value!(array, state, Int64Type, Int64),
-                DataType::Float32 => last_value!(array, state, Float32Type, Float32),
-                DataType::Float64 => last_value!(array, state, Float64Type, Float64),
-                DataType::Boolean => last_value!(array, state, BooleanType, Boolean),
-                DataType::Timestamp(_) => last_value!(array, state, TimestampType, Timestamp),
-                DataType::String => {
-                    let array = array.downcast_ref::<StringArray>();
-                    let mut builder = StringBuilder::with_capacity(array.len());
-                    for value in array.iter_opt() {
-                        match value {
-                            Some(value) => {
-                                *state = Scalar::String(value.into());
-                                builder.append(value);
-                            }
-                            None => {
-                                if let Scalar::String(last_value) = state {
-                                    builder.append(last_value);
-                                } else {
-                                    builder.append_null();
+        Box::new(
+            StatefulFunction::<Scalar>::new(|state, args| {
+                let array = &args[0];
+                match array.data_type() {
+                    DataType::Null => Ok(Arc::new(NullArray::new(array.len()))),
+                    DataType::Int8 => last_value!(array, state, Int8Type, Int8),
+                    DataType::Int16 => last_value!(array, state, Int16Type, Int16),
+                    DataType::Int32 => last_value!(array, state, Int32Type, Int32),
+                    DataType::Int64 => last_value!(array, state, Int64Type, Int64),
+                    DataType::UInt8 => last_value!(array, state, UInt8Type, UInt8),
+                    DataType::UInt16 => last_value!(array, state, UInt16Type, UInt16),
+                    DataType::UInt32 => last_value!(array, state, UInt32Type, UInt32),
+                    DataType::UInt64 => last_value!(array, state, UInt64Type, UInt64),
+                    DataType::Float32 => last_value!(array, state, Float32Type, Float32),
+                    DataType::Float64 => last_value!(array, state, Float64Type, Float64),
+                    DataType::Boolean => last_value!(array, state, BooleanType, Boolean),
+                    DataType::Timestamp(_) => last_value!(array, state, TimestampType, Timestamp),
+                    DataType::String => {
+                        let array = array.downcast_ref::<StringArray>();
+                        let mut builder = StringBuilder::with_capacity(array.len());
+                        for value in array.iter_opt() {
+                            match value {
+                                Some(value) => {
+                                    *state = Scalar::String(value.into());
+                                    builder.append(value);
+                                }
+                                None => {
+                                    if let Scalar::String(last_value) = state {
+                                        builder.append(last_value);
+                                    } else {
+                                        builder.append_null();
+                                    }
                                 }
                             }
                         }
+                        Ok(Arc::new(builder.finish()))
                     }
-                    Ok(Arc::new(builder.finish()))
+                    DataType::Decimal128 { .. } | DataType::List(_) | DataType::Struct(_) => {
+                        anyhow::bail!("unsupported datatype: {}", array.data_type())
+                    }
+                }
+            })
+            // the other state is of the later rows
+            .with_merge(|state, other| {
+                if other != Scalar::Null {
+                    *state = other;
                 }
+                Ok(())
+            }),
+        )
+    }),
+};
+
+/// The state of `FIRST_BY` and `LAST_BY`, the value of the row with the extreme ordering key.
+#[derive(Default, Clone, Serialize, Deserialize)]
+struct ValueByKey {
+    key: Scalar,
+    value: Scalar,
+}
+
+impl ValueByKey {
+    /// Replaces the value if the state is empty or `replace` accepts the ordering of the new key
+    /// compared to the current key.
+    fn update(&mut self, key: Scalar, value: Scalar, replace: fn(Ordering) -> bool) {
+        if self.key == Scalar::Null || replace(compare_keys(&key, &self.key)) {
+            *self = ValueByKey { key, value };
+        }
+    }
+}
+
+/// Compares two non-null ordering keys of the same type.
+fn compare_keys(a: &Scalar, b: &Scalar) -> Ordering {
+    match (a, b) {
+        (Scalar::Int8(a), Scalar::Int8(b)) => a.cmp(b),
+        (Scalar::Int16(a), Scalar::Int16(b)) => a.cmp(b),
+        (Scalar::Int32(a), Scalar::Int32(b)) => a.cmp(b),
+        (Scalar::Int64(a), Scalar::Int64(b)) => a.cmp(b),
+        (Scalar::Float32(a), Scalar::Float32(b)) => a
+            .partial_cmp(b)
+            .unwrap_or_else(|| a.is_nan().cmp(&b.is_nan())),
+        (Scalar::Float64(a), Scalar::Float64(b)) => a
+            .partial_cmp(b)
+            .unwrap_or_else(|| a.is_nan().cmp(&b.is_nan())),
+        (Scalar::Boolean(a), Scalar::Boolean(b)) => a.cmp(b),
+        (Scalar::Timestamp(a), Scalar::Timestamp(b)) => a.cmp(b),
+        (Scalar::Decimal128 { value: a, .. }, Scalar::Decimal128 { value: b, .. }) => a.cmp(b),
+        (Scalar::String(a), Scalar::String(b)) => a.cmp(b),
+        _ => unreachable!(),
+    }
+}
+
+fn value_by_key(
+    state: &mut ValueByKey,
+    args: &[ArrayRef],
+    replace: fn(Ordering) -> bool,
+) -> Result<ArrayRef> {
+    let (values, keys) = (&args[0], &args[1]);
+    if let DataType::List(_) | DataType::Struct(_) = keys.data_type() {
+        anyhow::bail!("cannot order by the values of type '{}'", keys.data_type());
+    }
+
+    let mut rows = Vec::with_capacity(values.len());
+    for row in 0..values.len() {
+        // like `FIRST` and `LAST`, the rows whose value is null are ignored
+        if values.is_valid(row) && keys.is_valid(row) {
+            state.update(keys.scalar_value(row), values.scalar_value(row), replace);
+        }
+        rows.push(vec![state.value.clone()]);
+    }
+
+    let schema = Arc::new(Schema::try_new(vec![Field::new(
+        "value",
+        values.data_type(),
+    )])?);
+    Ok(DataSet::try_from_rows(schema, rows)?.columns()[0].clone())
+}
+
+/// Returns the value of the row with the smallest ordering key, so the result doesn't depend on
+/// the order in which the rows arrive, the earliest row wins a tie.
+///
+/// `FIRST(value ORDER BY key)` is parsed to `FIRST_BY(value, key)`.
+pub const FIRST_BY: Function = Function {
+    namespace: None,
+    name: "first_by",
+    params: &[],
+    signature: &Signature::Any(2),
+    return_type: |args| args[0].clone(),
+    function_type: FunctionType::Stateful(|| {
+        Box::new(
+            StatefulFunction::<ValueByKey>::new(|state, args| {
+                value_by_key(state, args, |ordering| ordering == Ordering::Less)
+            })
+            .with_merge(|state, other| {
+                if other.key != Scalar::Null {
+                    state.update(other.key, other.value, |ordering| {
+                        ordering == Ordering::Less
+                    });
+                }
+                Ok(())
+            }),
+        )
+    }),
+};
+
+/// Returns the value of the row with the largest ordering key, so the result doesn't depend on
+/// the order in which the rows arrive, the latest row wins a tie.
+///
+/// `LAST(value ORDER BY key)` is parsed to `LAST_BY(value, key)`.
+pub const LAST_BY: Function = Function {
+    namespace: None,
+    name: "last_by",
+    params: &[],
+    signature: &Signature::Any(2),
+    return_type: |args| args[0].clone(),
+    function_type: FunctionType::Stateful(|| {
+        Box::new(
+            StatefulFunction::<ValueByKey>::new(|state, args| {
+                value_by_key(state, args, |ordering| ordering != Ordering::Less)
+            })
+            .with_merge(|state, other| {
+                if other.key != Scalar::Null {
+                    state.update(other.key, other.value, |ordering| {
+                        ordering == Ordering::Greater
+                    });
+                }
+                Ok(())
+            }),
+        )
+    }),
+};
+
+/// The compression of the t-digest, a digest keeps at most about `2 * TDIGEST_COMPRESSION`
+/// centroids, and a larger value is more accurate.
+const TDIGEST_COMPRESSION: f64 = 100.0;
+
+/// A t-digest that summarizes a distribution with bounded memory, the centroids are
+/// `(mean, weight)` sorted by the mean.
+///
+/// The centroids near the tails are kept small, so the extreme percentiles are more accurate.
+#[derive(Default, Clone, Serialize, Deserialize)]
+struct TDigest {
+    centroids: Vec<(f64, f64)>,
+    count: f64,
+    min: f64,
+    max: f64,
+}
+
+impl TDigest {
+    fn add(&mut self, value: f64) {
+        if self.count == 0.0 {
+            self.min = value;
+            self.max = value;
+        } else {
+            self.min = self.min.min(value);
+            self.max = self.max.max(value);
+        }
+        self.count += 1.0;
+
+        let idx = self.centroids.partition_point(|(mean, _)| *mean <= value);
+        self.centroids.insert(idx, (value, 1.0));
+        if self.centroids.len() as f64 > 2.0 * TDIGEST_COMPRESSION {
+            self.compress();
+        }
+    }
+
+    fn merge(&mut self, other: TDigest) -> Result<()> {
+        if other.count == 0.0 {
+            return Ok(());
+        }
+        if self.count == 0.0 {
+            *self = other;
+            return Ok(());
+        }
+
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+        self.count += other.count;
+        self.centroids.extend(other.centroids);
+        // the means are never NaN, because NaN values are not added to the digest
+        self.centroids
+            .sort_by(|(a, _), (b, _)| a.partial_cmp(b).unwrap());
+        self.compress();
+        Ok(())
+    }
+
+    /// The scale function `k1`, the centroids are merged as long as each of them spans at most
+    /// one unit of `k`.
+    fn k(q: f64) -> f64 {
+        TDIGEST_COMPRESSION / (2.0 * std::f64::consts::PI) * (2.0 * q - 1.0).asin()
+    }
+
+    fn compress(&mut self) {
+        let mut centroids: Vec<(f64, f64)> = Vec::with_capacity(self.centroids.len());
+        let mut cumulative = 0.0;
+        let mut k_lower = Self::k(0.0);
+        for (mean, weight) in self.centroids.drain(..) {
+            match centroids.last_mut() {
+                Some(last)
+                    if Self::k(((cumulative + weight) / self.count).min(1.0)) - k_lower <= 1.0 =>
+                {
+                    last.1 += weight;
+                    last.0 += (mean - last.0) * weight / last.1;
+                }
+                _ => {
+                    k_lower = Self::k(cumulative / self.count);
+                    centroids.push((mean, weight));
+                }
+            }
+            cumulative += weight;
+        }
+        self.centroids = centroids;
+    }
+
+    /// Returns the estimated value at the percentile `p` in `[0, 1]`.
+    fn quantile(&self, p: f64) -> Option<f64> {
+        let (first, last) = match (self.centroids.first(), self.centroids.last()) {
+            (Some(first), Some(last)) => (*first, *last),
+            _ => return None,
+        };
+        if self.centroids.len() == 1 {
+            return Some(first.0);
+        }
+
+        // the value at a centroid's center is its mean, and the values between the centers are
+        // interpolated linearly
+        let target = p * self.count;
+        if target <= first.1 / 2.0 {
+            return Some(self.min + (first.0 - self.min) * target / (first.1 / 2.0));
+        }
+        let mut center = first.1 / 2.0;
+        for window in self.centroids.windows(2) {
+            let ((mean_a, weight_a), (mean_b, weight_b)) = (window[0], window[1]);
+            let next_center = center + (weight_a + weight_b) / 2.0;
+            if target <= next_center {
+                return Some(
+                    mean_a + (mean_b - mean_a) * (target - center) / (next_center - center),
+                );
+            }
+            center = next_center;
+        }
+        let rest = (target - center) / (last.1 / 2.0);
+        Some(last.0 + (self.max - last.0) * rest.min(1.0))
+    }
+}
+
+pub const APPROX_PERCENTILE: Function = Function {
+    namespace: None,
+    name: "approx_percentile",
+    params: &["value", "percentile"],
+    signature: &Signature::Exact(&[DataType::Float64, DataType::Float64]),
+    return_type: |_| DataType::Float64,
+    function_type: FunctionType::Stateful(|| {
+        Box::new(
+            StatefulFunction::<TDigest>::new(|state, args| {
+                let array = args[0].downcast_ref::<Float64Array>();
+                let percentile = args[1].downcast_ref::<Float64Array>();
+                let mut builder = Float64Builder::with_capacity(array.len());
+                for (value, p) in array.iter_opt().zip(percentile.iter_opt()) {
+                    if let Some(value) = value.filter(|value| !value.is_nan()) {
+                        state.add(value);
+                    }
+                    match p {
+                        Some(p) => {
+                            anyhow::ensure!(
+                                (0.0..=1.0).contains(&p),
+                                "the percentile must be between 0 and 1, actual value is {}",
+                                p
+                            );
+                            builder.append_opt(state.quantile(p));
+                        }
+                        None => builder.append_null(),
+                    }
+                }
+                Ok(Arc::new(builder.finish()))
+            })
+            .with_merge(TDigest::merge),
+        )
+    }),
+};
+
+/// The number of bits of the hash used to select a register of the HyperLogLog, the standard error
+/// of the estimate is about `1.04 / sqrt(2^HYPERLOGLOG_PRECISION)`, i.e. 1.6%.
+const HYPERLOGLOG_PRECISION: u32 = 12;
+
+/// A HyperLogLog sketch that estimates the number of distinct values with bounded memory.
+///
+/// The registers are allocated on the first value, and two sketches are merged by taking the
+/// maximum of each register.
+#[derive(Default, Clone, Serialize, Deserialize)]
+pub(crate) struct HyperLogLog {
+    registers: Vec<u8>,
+    /// The sum of `2^-rank` of all the registers, it is updated incrementally so that the
+    /// estimate doesn't scan the registers for every row.
+    sum: f64,
+    zeros: usize,
+}
+
+impl HyperLogLog {
+    /// Hashes the serialized value with FNV-1a and the finalizer of splitmix64, the hash must be
+    /// stable across processes because the registers are saved in the checkpoints.
+    fn hash(data: &[u8]) -> u64 {
+        let mut hash = data.iter().fold(0xcbf29ce484222325u64, |hash, b| {
+            (hash ^ *b as u64).wrapping_mul(0x100000001b3)
+        });
+        hash = (hash ^ (hash >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        hash = (hash ^ (hash >> 27)).wrapping_mul(0x94d049bb133111eb);
+        hash ^ (hash >> 31)
+    }
+
+    pub(crate) fn add(&mut self, data: &[u8]) {
+        if self.registers.is_empty() {
+            self.registers = vec![0; 1 << HYPERLOGLOG_PRECISION];
+            self.update_summary();
+        }
+        let hash = Self::hash(data);
+        let idx = (hash >> (64 - HYPERLOGLOG_PRECISION)) as usize;
+        let rank = ((hash << HYPERLOGLOG_PRECISION) | (1 << (HYPERLOGLOG_PRECISION - 1)))
+            .leading_zeros() as u8
+            + 1;
+        let current = self.registers[idx];
+        if rank > current {
+            self.sum += 2f64.powi(-(rank as i32)) - 2f64.powi(-(current as i32));
+            if current == 0 {
+                self.zeros -= 1;
+            }
+            self.registers[idx] = rank;
+        }
+    }
+
+    fn merge(&mut self, other: HyperLogLog) -> Result<()> {
+        if self.registers.is_empty() {
+            *self = other;
+        } else if !other.registers.is_empty() {
+            for (a, b) in self.registers.iter_mut().zip(other.registers) {
+                *a = (*a).max(b);
             }
-        }))
+            self.update_summary();
+        }
+        Ok(())
+    }
+
+    fn update_summary(&mut self) {
+        self.sum = self
+            .registers
+            .iter()
+            .map(|rank| 2f64.powi(-(*rank as i32)))
+            .sum();
+        self.zeros = self.registers.iter().filter(|rank| **rank == 0).count();
+    }
+
+    pub(crate) fn estimate(&self) -> i64 {
+        if self.registers.is_empty() {
+            return 0;
+        }
+
+        let m = self.registers.len() as f64;
+        let alpha = 0.7213 / (1.0 + 1.079 / m);
+        let estimate = alpha * m * m / self.sum;
+
+        // linear counting is more accurate for the small cardinalities
+        if estimate <= 2.5 * m && self.zeros > 0 {
+            (m * (m / self.zeros as f64).ln()).round() as i64
+        } else {
+            estimate.round() as i64
+        }
+    }
+}
+
+pub const APPROX_COUNT_DISTINCT: Function = Function {
+    namespace: None,
+    name: "approx_count_distinct",
+    params: &[],
+    signature: &Signature::Any(1),
+    return_type: |_| DataType::Int64,
+    function_type: FunctionType::Stateful(|| {
+        Box::new(
+            StatefulFunction::<HyperLogLog>::new(|state, args| {
+                let array = &args[0];
+                let mut builder = Int64Builder::with_capacity(array.len());
+                for index in 0..array.len() {
+                    if array.is_valid(index) {
+                        state.add(&bincode::serialize(&array.scalar_value(index))?);
+                    }
+                    builder.append(state.estimate());
+                }
+                Ok(Arc::new(builder.finish()))
+            })
+            .with_merge(HyperLogLog::merge),
+        )
+    }),
+};
+
+/// The maximum number of values that `COLLECT_LIST` collects for a group, it keeps the state that
+/// is saved in the checkpoints bounded, the values after it are dropped.
+const MAX_COLLECT_LIST_SIZE: usize = 10000;
+
+/// Collects the non-null values of a group into a list.
+///
+/// At most the first 10000 values of a group are collected, so the list of a larger group is
+/// truncated and the later values are dropped without an error.
+///
+/// Only the last row of each call carries the collected list and the others are null, since the
+/// aggregation uses the last value of each group, so that the values aren't copied for every row.
+pub const COLLECT_LIST: Function = Function {
+    namespace: None,
+    name: "collect_list",
+    params: &[],
+    signature: &Signature::Any(1),
+    return_type: |args| DataType::List(Box::new(args[0].clone())),
+    function_type: FunctionType::Stateful(|| {
+        Box::new(
+            StatefulFunction::<Vec<Scalar>>::new(|state, args| {
+                let array = &args[0];
+                for index in 0..array.len() {
+                    if state.len() == MAX_COLLECT_LIST_SIZE {
+                        break;
+                    }
+                    if array.is_valid(index) {
+                        state.push(array.scalar_value(index));
+                    }
+                }
+
+                let schema = Arc::new(Schema::try_new(vec![Field::new(
+                    "collect_list",
+                    array.data_type(),
+                )])?);
+                let values = DataSet::try_from_rows(
+                    schema,
+                    state.iter().map(|value| vec![value.clone()]).collect(),
+                )?
+                .columns()[0]
+                    .clone();
+                let mut lengths = vec![None; array.len()];
+                match lengths.last_mut() {
+                    Some(last) => *last = Some(values.len()),
+                    None => return Ok(Arc::new(ListArray::from_lengths(&[], values.slice(0, 0)))),
+                }
+                Ok(Arc::new(ListArray::from_lengths(&lengths, values)))
+            })
+            // the other state is of the later rows
+            .with_merge(|state, other| {
+                let len = MAX_COLLECT_LIST_SIZE.saturating_sub(state.len());
+                state.extend(other.into_iter().take(len));
+                Ok(())
+            }),
+        )
     }),
 };
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::array::{Int32Array, Int64Array, TimestampArray};
+    use crate::dsl::{call, col};
+    use crate::expr::func::{DistinctFunction, GenericStatefulFunction};
 
     fn test_unary_func(func: &Function, first: (Vec<f64>, Vec<f64>), second: (Vec<f64>, Vec<f64>)) {
         let mut f = func.function_type.create_stateful_fun();
@@ -331,6 +1016,58 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_distinct() {
+        let values = |f: &mut Box<dyn GenericStatefulFunction>, values| {
+            let array = f.call(&[Arc::new(Float64Array::from_vec(values))]).unwrap();
+            array
+                .downcast_ref::<Float64Array>()
+                .iter()
+                .collect::<Vec<_>>()
+        };
+
+        let mut f: Box<dyn GenericStatefulFunction> = Box::new(DistinctFunction::new(
+            AVG.function_type.create_stateful_fun(),
+        ));
+        assert_eq!(values(&mut f, vec![1.0, 1.0, 2.0]), vec![1.0, 1.0, 1.5]);
+
+        // the seen values are restored from the saved state
+        let state = f.save_state().unwrap();
+        let mut f: Box<dyn GenericStatefulFunction> = Box::new(DistinctFunction::new(
+            AVG.function_type.create_stateful_fun(),
+        ));
+        f.load_state(state).unwrap();
+        assert_eq!(
+            values(&mut f, vec![2.0, 4.0, 1.0]),
+            vec![1.5, 7.0 / 3.0, 7.0 / 3.0]
+        );
+
+        let other = f.clone();
+        assert!(!f.can_merge());
+        assert!(f.merge(&*other).is_err());
+    }
+
+    #[test]
+    fn test_count() {
+        let schema = Arc::new(Schema::try_new(vec![Field::new("a", DataType::String)]).unwrap());
+        let dataset = DataSet::try_new(
+            schema.clone(),
+            vec![Arc::new(StringArray::from_opt_vec(vec![
+                Some("x"),
+                None,
+                Some("y"),
+            ]))],
+        )
+        .unwrap();
+
+        let mut expr = call("count", vec![col("a")]).into_physical(schema).unwrap();
+        assert_eq!(expr.data_type(), DataType::Int64);
+        assert_eq!(
+            expr.eval(&dataset).unwrap().downcast_ref::<Int64Array>(),
+            &Int64Array::from_vec(vec![1, 1, 2])
+        );
+    }
+
     #[test]
     fn test_sum() {
         test_unary_func(
@@ -340,6 +1077,44 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_sum_decimal() {
+        let data_type = DataType::Decimal128 {
+            precision: 10,
+            scale: 2,
+        };
+        let input_types = SUM.signature.data_types(&[data_type]).unwrap();
+        assert_eq!(
+            (SUM.return_type)(&input_types),
+            DataType::Decimal128 {
+                precision: 38,
+                scale: 2
+            }
+        );
+
+        let sum_values = |f: &mut Box<dyn crate::expr::func::GenericStatefulFunction>, values| {
+            let array = f
+                .call(&[Arc::new(
+                    Decimal128Array::from_str_vec(10, 2, values).unwrap(),
+                )])
+                .unwrap();
+            (0..array.len())
+                .map(|idx| array.scalar_value(idx).to_string())
+                .collect::<Vec<_>>()
+        };
+
+        let mut f = SUM.function_type.create_stateful_fun();
+        assert_eq!(
+            sum_values(&mut f, vec!["0.10", "0.20", "123.45"]),
+            vec!["0.10", "0.30", "123.75"]
+        );
+
+        let state = f.save_state().unwrap();
+        let mut f = SUM.function_type.create_stateful_fun();
+        f.load_state(state).unwrap();
+        assert_eq!(sum_values(&mut f, vec!["-0.75"]), vec!["123.00"]);
+    }
+
     #[test]
     fn test_max() {
         test_unary_func(
@@ -375,4 +1150,441 @@ mod tests {
             (vec![7.0, 3.0, 35.0], vec![7.0, 3.0, 35.0]),
         );
     }
+
+    fn value_by_key(
+        f: &mut Box<dyn GenericStatefulFunction>,
+        values: Vec<Option<i64>>,
+        keys: Vec<i64>,
+    ) -> Vec<Option<i64>> {
+        let array = f
+            .call(&[
+                Arc::new(Int64Array::from_opt_vec(values)),
+                Arc::new(TimestampArray::from_vec(keys)),
+            ])
+            .unwrap();
+        array.downcast_ref::<Int64Array>().iter_opt().collect()
+    }
+
+    #[test]
+    fn test_last_by() {
+        // the rows arrive out of order, the value of the largest key is kept
+        let mut f = LAST_BY.function_type.create_stateful_fun();
+        assert_eq!(
+            value_by_key(
+                &mut f,
+                vec![Some(1), Some(3), Some(2), None],
+                vec![1000, 3000, 2000, 4000]
+            ),
+            vec![Some(1), Some(3), Some(3), Some(3)]
+        );
+
+        let state = f.save_state().unwrap();
+        let mut f = LAST_BY.function_type.create_stateful_fun();
+        f.load_state(state).unwrap();
+        assert_eq!(
+            value_by_key(&mut f, vec![Some(0), Some(5)], vec![500, 5000]),
+            vec![Some(3), Some(5)]
+        );
+
+        // the partitions are merged by the ordering key too
+        let mut a = LAST_BY.function_type.create_stateful_fun();
+        value_by_key(&mut a, vec![Some(6)], vec![6000]);
+        let mut b = LAST_BY.function_type.create_stateful_fun();
+        value_by_key(&mut b, vec![Some(4)], vec![4000]);
+        a.merge(&*b).unwrap();
+        assert_eq!(value_by_key(&mut a, vec![None], vec![0]), vec![Some(6)]);
+    }
+
+    #[test]
+    fn test_first_by() {
+        let mut f = FIRST_BY.function_type.create_stateful_fun();
+        assert_eq!(
+            value_by_key(
+                &mut f,
+                vec![Some(2), Some(1), Some(3)],
+                vec![2000, 1000, 3000]
+            ),
+            vec![Some(2), Some(1), Some(1)]
+        );
+    }
+
+    /// Aggregates the rows in two partitions and merges the partial states, then aggregates the
+    /// last row, the result must be the same as aggregating all the rows in a single partition.
+    fn aggregate_partitions(func: &Function, args: Vec<ArrayRef>, mid: usize) -> (Scalar, Scalar) {
+        let len = args[0].len();
+        let rows = |start: usize, end: usize| {
+            args.iter()
+                .map(|array| array.slice(start, end - start))
+                .collect::<Vec<_>>()
+        };
+        let last_value = |f: &mut Box<dyn GenericStatefulFunction>, args: &[ArrayRef]| {
+            let array = f.call(args).unwrap();
+            array.scalar_value(array.len() - 1)
+        };
+
+        let mut all = func.function_type.create_stateful_fun();
+        let expected = last_value(&mut all, &rows(0, len));
+
+        let mut a = func.function_type.create_stateful_fun();
+        a.call(&rows(0, mid)).unwrap();
+        let mut b = func.function_type.create_stateful_fun();
+        b.call(&rows(mid, len - 1)).unwrap();
+        a.merge(&*b).unwrap();
+        (last_value(&mut a, &rows(len - 1, len)), expected)
+    }
+
+    #[test]
+    fn test_merge_partitions() {
+        let floats: ArrayRef = Arc::new(Float64Array::from_opt_vec(vec![
+            Some(3.0),
+            None,
+            Some(-1.0),
+            Some(8.0),
+            Some(2.0),
+            None,
+        ]));
+        for func in [&AVG, &SUM, &COUNT, &MAX, &MIN, &FIRST, &LAST]
+            .iter()
+            .copied()
+        {
+            for mid in 0..floats.len() {
+                let (merged, expected) = aggregate_partitions(func, vec![floats.clone()], mid);
+                assert_eq!(merged, expected, "func={}, mid={}", func.name, mid);
+            }
+        }
+
+        let ints: ArrayRef = Arc::new(Int32Array::from_opt_vec(vec![
+            None,
+            Some(7),
+            Some(-3),
+            None,
+            Some(5),
+        ]));
+        for func in [&MAX, &MIN, &FIRST, &LAST, &COLLECT_LIST].iter().copied() {
+            for mid in 0..ints.len() {
+                let (merged, expected) = aggregate_partitions(func, vec![ints.clone()], mid);
+                assert_eq!(merged, expected, "func={}, mid={}", func.name, mid);
+            }
+        }
+
+        let decimals: ArrayRef =
+            Arc::new(Decimal128Array::from_str_vec(10, 2, vec!["0.10", "0.20", "123.45"]).unwrap());
+        assert_eq!(
+            aggregate_partitions(&SUM, vec![decimals], 1).0.to_string(),
+            "123.75"
+        );
+
+        let values: ArrayRef = Arc::new(Int64Array::from_vec(
+            (0..50000).map(|i| i % 20000).collect(),
+        ));
+        let (merged, expected) = aggregate_partitions(&APPROX_COUNT_DISTINCT, vec![values], 30000);
+        assert_eq!(merged, expected);
+
+        let x: ArrayRef = Arc::new(Float64Array::from_vec(vec![1.0, 2.0, 3.0, 4.0, 5.0]));
+        let y: ArrayRef = Arc::new(Float64Array::from_vec(vec![2.0, 1.0, 4.0, 3.0, 6.0]));
+        for mid in 0..x.len() {
+            match aggregate_partitions(&CORR, vec![x.clone(), y.clone()], mid) {
+                (Scalar::Float64(merged), Scalar::Float64(expected)) => {
+                    assert!((merged - expected).abs() < 1e-9, "mid={}", mid)
+                }
+                values => panic!("unexpected values: {:?}", values),
+            }
+        }
+    }
+
+    #[test]
+    fn test_approx_percentile_merge() {
+        // a permutation of 1..=100000
+        let values: ArrayRef = Arc::new(Float64Array::from_vec(
+            (0..100000)
+                .map(|i| (i * 7919 % 100000 + 1) as f64)
+                .collect(),
+        ));
+        for p in [0.01, 0.5, 0.99].iter().copied() {
+            let percentile: ArrayRef = Arc::new(Float64Array::new_scalar(100000, Some(p)));
+            let (merged, _) =
+                aggregate_partitions(&APPROX_PERCENTILE, vec![values.clone(), percentile], 40000);
+            let estimate = match merged {
+                Scalar::Float64(value) => value,
+                _ => unreachable!(),
+            };
+            let exact = p * 100000.0;
+            assert!(
+                (estimate - exact).abs() / 100000.0 < 0.005,
+                "p={}, estimate={}, exact={}",
+                p,
+                estimate,
+                exact
+            );
+        }
+    }
+
+    fn corr_values(
+        f: &mut Box<dyn crate::expr::func::GenericStatefulFunction>,
+        x: Vec<f64>,
+        y: Vec<f64>,
+    ) -> Vec<Option<f64>> {
+        let array = f
+            .call(&[
+                Arc::new(Float64Array::from_vec(x)),
+                Arc::new(Float64Array::from_vec(y)),
+            ])
+            .unwrap();
+        array
+            .downcast_ref::<Float64Array>()
+            .iter_opt()
+            .map(|value| value.map(|value| (value * 1e6).round() / 1e6))
+            .collect()
+    }
+
+    #[test]
+    fn test_corr() {
+        let mut f = CORR.function_type.create_stateful_fun();
+        assert_eq!(
+            corr_values(&mut f, vec![1.0, 2.0, 3.0], vec![2.0, 1.0, 4.0]),
+            vec![None, Some(-1.0), Some(0.654654)]
+        );
+
+        let state = f.save_state().unwrap();
+        let mut f = CORR.function_type.create_stateful_fun();
+        f.load_state(state).unwrap();
+        assert_eq!(
+            corr_values(&mut f, vec![4.0, 5.0], vec![3.0, 6.0]),
+            vec![Some(0.6), Some(0.821995)]
+        );
+    }
+
+    #[test]
+    fn test_corr_zero_variance() {
+        let mut f = CORR.function_type.create_stateful_fun();
+        assert_eq!(
+            corr_values(&mut f, vec![1.0, 2.0, 3.0], vec![5.0, 5.0, 5.0]),
+            vec![None, None, None]
+        );
+    }
+
+    fn percentile_values(
+        f: &mut Box<dyn crate::expr::func::GenericStatefulFunction>,
+        values: Vec<f64>,
+        p: f64,
+    ) -> Vec<Option<f64>> {
+        let len = values.len();
+        let array = f
+            .call(&[
+                Arc::new(Float64Array::from_vec(values)),
+                Arc::new(Float64Array::new_scalar(len, Some(p))),
+            ])
+            .unwrap();
+        array.downcast_ref::<Float64Array>().iter_opt().collect()
+    }
+
+    #[test]
+    fn test_approx_percentile() {
+        let mut f = APPROX_PERCENTILE.function_type.create_stateful_fun();
+        assert_eq!(
+            percentile_values(&mut f, vec![3.0, 1.0, 2.0], 0.5),
+            vec![Some(3.0), Some(2.0), Some(2.0)]
+        );
+
+        // a permutation of 1..=100000
+        let values = (0..100000)
+            .map(|i| (i * 7919 % 100000 + 1) as f64)
+            .collect::<Vec<_>>();
+        for p in [0.01, 0.25, 0.5, 0.9, 0.99].iter().copied() {
+            let mut f = APPROX_PERCENTILE.function_type.create_stateful_fun();
+            let estimate = percentile_values(&mut f, values.clone(), p)
+                .pop()
+                .unwrap()
+                .unwrap();
+            let exact = p * 100000.0;
+            assert!(
+                (estimate - exact).abs() / 100000.0 < 0.005,
+                "p={}, estimate={}, exact={}",
+                p,
+                estimate,
+                exact
+            );
+        }
+    }
+
+    #[test]
+    fn test_approx_percentile_state() {
+        let values = (0..10000)
+            .map(|i| ((i * 7919 % 10000) as f64).sqrt())
+            .collect::<Vec<_>>();
+
+        let mut f = APPROX_PERCENTILE.function_type.create_stateful_fun();
+        let expected = percentile_values(&mut f, values.clone(), 0.9);
+
+        let mut f = APPROX_PERCENTILE.function_type.create_stateful_fun();
+        let mut output = percentile_values(&mut f, values[..6000].to_vec(), 0.9);
+        let state = f.save_state().unwrap();
+        let mut f = APPROX_PERCENTILE.function_type.create_stateful_fun();
+        f.load_state(state).unwrap();
+        output.extend(percentile_values(&mut f, values[6000..].to_vec(), 0.9));
+        assert_eq!(output, expected);
+
+        let mut f = APPROX_PERCENTILE.function_type.create_stateful_fun();
+        assert!(f
+            .call(&[
+                Arc::new(Float64Array::from_vec(vec![1.0])),
+                Arc::new(Float64Array::from_vec(vec![1.5])),
+            ])
+            .is_err());
+    }
+
+    fn count_distinct_values(
+        f: &mut Box<dyn crate::expr::func::GenericStatefulFunction>,
+        values: impl Iterator<Item = i64>,
+    ) -> Vec<i64> {
+        let array = f
+            .call(&[Arc::new(Int64Array::from_vec(values.collect()))])
+            .unwrap();
+        array.downcast_ref::<Int64Array>().iter().collect()
+    }
+
+    #[test]
+    fn test_approx_count_distinct() {
+        let mut f = APPROX_COUNT_DISTINCT.function_type.create_stateful_fun();
+        let array = f
+            .call(&[Arc::new(StringArray::from_opt_vec(vec![
+                None,
+                Some("a"),
+                Some("b"),
+                Some("a"),
+            ]))])
+            .unwrap();
+        assert_eq!(
+            array
+                .downcast_ref::<Int64Array>()
+                .iter()
+                .collect::<Vec<_>>(),
+            vec![0, 1, 2, 2]
+        );
+
+        // every value appears twice
+        let mut f = APPROX_COUNT_DISTINCT.function_type.create_stateful_fun();
+        let estimate = *count_distinct_values(&mut f, (0..200000).map(|i| i % 100000))
+            .last()
+            .unwrap();
+        assert!(
+            (estimate - 100000).abs() < 5000,
+            "estimate={}, exact=100000",
+            estimate
+        );
+
+        let state = f.save_state().unwrap();
+        let mut f = APPROX_COUNT_DISTINCT.function_type.create_stateful_fun();
+        f.load_state(state).unwrap();
+        assert_eq!(count_distinct_values(&mut f, 0..10), vec![estimate; 10]);
+    }
+
+    #[test]
+    fn test_approx_count_distinct_merge() {
+        let mut all = APPROX_COUNT_DISTINCT.function_type.create_stateful_fun();
+        let expected = *count_distinct_values(&mut all, 0..100000).last().unwrap();
+
+        // the partitions overlap
+        let mut a = APPROX_COUNT_DISTINCT.function_type.create_stateful_fun();
+        count_distinct_values(&mut a, 0..60000);
+        let mut b = APPROX_COUNT_DISTINCT.function_type.create_stateful_fun();
+        count_distinct_values(&mut b, 40000..100000);
+        a.merge_state(b.save_state().unwrap()).unwrap();
+        assert_eq!(count_distinct_values(&mut a, 0..1), vec![expected]);
+
+        // merging into an empty sketch
+        let mut c = APPROX_COUNT_DISTINCT.function_type.create_stateful_fun();
+        c.merge_state(a.save_state().unwrap()).unwrap();
+        assert_eq!(count_distinct_values(&mut c, 0..1), vec![expected]);
+
+        // a function without a merge function
+        let mut f = StatefulFunction::<i64>::new(|_, args| Ok(args[0].clone()));
+        assert!(!f.can_merge());
+        assert_eq!(
+            f.merge(&StatefulFunction::<i64>::new(|_, args| Ok(args[0].clone())))
+                .unwrap_err()
+                .to_string(),
+            "the function state can't be merged"
+        );
+    }
+
+    fn collect_list_values(
+        f: &mut Box<dyn crate::expr::func::GenericStatefulFunction>,
+        values: Vec<Option<i32>>,
+    ) -> Vec<Scalar> {
+        let array = f
+            .call(&[Arc::new(Int32Array::from_opt_vec(values))])
+            .unwrap();
+        assert_eq!(array.data_type(), DataType::List(Box::new(DataType::Int32)));
+        (0..array.len())
+            .map(|index| array.scalar_value(index))
+            .collect()
+    }
+
+    #[test]
+    fn test_collect_list() {
+        let mut f = COLLECT_LIST.function_type.create_stateful_fun();
+        assert_eq!(
+            collect_list_values(&mut f, vec![None, Some(3), None, Some(1)]),
+            vec![
+                Scalar::Null,
+                Scalar::Null,
+                Scalar::Null,
+                Scalar::List(vec![Scalar::Int32(3), Scalar::Int32(1)]),
+            ]
+        );
+        assert_eq!(
+            collect_list_values(&mut f, vec![None]),
+            vec![Scalar::List(vec![Scalar::Int32(3), Scalar::Int32(1)])]
+        );
+        assert_eq!(collect_list_values(&mut f, vec![]), vec![]);
+
+        let state = f.save_state().unwrap();
+        let mut f = COLLECT_LIST.function_type.create_stateful_fun();
+        f.load_state(state).unwrap();
+        assert_eq!(
+            collect_list_values(&mut f, vec![Some(2)]),
+            vec![Scalar::List(vec![
+                Scalar::Int32(3),
+                Scalar::Int32(1),
+                Scalar::Int32(2)
+            ])]
+        );
+    }
+
+    #[test]
+    fn test_collect_list_merge_max_size() {
+        let mut f = COLLECT_LIST.function_type.create_stateful_fun();
+        f.load_state(
+            bincode::serialize(&vec![Scalar::Int32(1); MAX_COLLECT_LIST_SIZE - 1]).unwrap(),
+        )
+        .unwrap();
+        let mut other = COLLECT_LIST.function_type.create_stateful_fun();
+        other
+            .load_state(bincode::serialize(&vec![Scalar::Int32(2), Scalar::Int32(3)]).unwrap())
+            .unwrap();
+        f.merge(&*other).unwrap();
+        match &collect_list_values(&mut f, vec![None])[0] {
+            Scalar::List(list) => {
+                assert_eq!(list.len(), MAX_COLLECT_LIST_SIZE);
+                assert_eq!(list.last(), Some(&Scalar::Int32(2)));
+            }
+            value => panic!("unexpected value: {:?}", value),
+        }
+    }
+
+    #[test]
+    fn test_collect_list_max_size() {
+        let state = bincode::serialize(&vec![Scalar::Int32(1); MAX_COLLECT_LIST_SIZE - 1]).unwrap();
+        let mut f = COLLECT_LIST.function_type.create_stateful_fun();
+        f.load_state(state).unwrap();
+        let values = collect_list_values(&mut f, vec![Some(2), None, Some(3)]);
+        match &values[2] {
+            Scalar::List(list) => {
+                assert_eq!(list.len(), MAX_COLLECT_LIST_SIZE);
+                assert_eq!(list.last(), Some(&Scalar::Int32(2)));
+            }
+            value => panic!("unexpected value: {:?}", value),
+        }
+    }
 }
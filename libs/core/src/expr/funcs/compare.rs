@@ -0,0 +1,151 @@
+use std::cmp::Ordering;
+use std::sync::Arc;
+
+use crate::array::{
+    ArrayExt, DataType, Float32Type, Float64Type, Int16Type, Int32Type, Int64Type, Int8Type,
+    PrimitiveArray, PrimitiveBuilder, UInt16Type, UInt32Type, UInt64Type, UInt8Type,
+};
+use crate::expr::func::{Function, FunctionType};
+use crate::expr::signature::Signature;
+
+/// The arguments are cast to the first of these types that all of them can be cast to.
+const NUMERIC_TYPES: &[DataType] = &[
+    DataType::Int8,
+    DataType::UInt8,
+    DataType::Int16,
+    DataType::UInt16,
+    DataType::Int32,
+    DataType::UInt32,
+    DataType::Int64,
+    DataType::UInt64,
+    DataType::Float32,
+    DataType::Float64,
+];
+
+macro_rules! select_value {
+    ($args:expr, $ty:ty, $ordering:expr) => {{
+        let len = $args[0].len();
+        let arrays = $args
+            .iter()
+            .map(|array| array.downcast_ref::<PrimitiveArray<$ty>>())
+            .collect::<Vec<_>>();
+        let mut builder = PrimitiveBuilder::<$ty>::with_capacity(len);
+
+        for row in 0..len {
+            let mut value = None;
+            for array in &arrays {
+                if let Some(v) = array.value_opt(row) {
+                    match value {
+                        Some(current) if v.partial_cmp(&current) != Some($ordering) => {}
+                        _ => value = Some(v),
+                    }
+                }
+            }
+            builder.append_opt(value);
+        }
+
+        Ok(Arc::new(builder.finish()))
+    }};
+}
+
+macro_rules! make_select_func {
+    ($ident:ident, $name:literal, $ordering:expr) => {
+        pub const $ident: Function = Function {
+            namespace: None,
+            name: $name,
+            params: &[],
+            signature: &Signature::Variadic(NUMERIC_TYPES),
+            return_type: |args| args[0].clone(),
+            function_type: FunctionType::Stateless(|args| match args[0].data_type() {
+                DataType::Int8 => select_value!(args, Int8Type, $ordering),
+                DataType::Int16 => select_value!(args, Int16Type, $ordering),
+                DataType::Int32 => select_value!(args, Int32Type, $ordering),
+                DataType::Int64 => select_value!(args, Int64Type, $ordering),
+                DataType::UInt8 => select_value!(args, UInt8Type, $ordering),
+                DataType::UInt16 => select_value!(args, UInt16Type, $ordering),
+                DataType::UInt32 => select_value!(args, UInt32Type, $ordering),
+                DataType::UInt64 => select_value!(args, UInt64Type, $ordering),
+                DataType::Float32 => select_value!(args, Float32Type, $ordering),
+                DataType::Float64 => select_value!(args, Float64Type, $ordering),
+                _ => unreachable!(),
+            }),
+        };
+    };
+}
+
+make_select_func!(GREATEST, "greatest", Ordering::Greater);
+make_select_func!(LEAST, "least", Ordering::Less);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::array::{Array, Float64Array, Int32Array, Int64Array, UInt8Array};
+    use crate::dataset::{DataSet, Field, Schema};
+    use crate::dsl::*;
+
+    #[test]
+    fn test_greatest_least() {
+        let args = [
+            Arc::new(Int64Array::from_opt_vec(vec![Some(1), None, Some(5), None])) as _,
+            Arc::new(Int64Array::from_opt_vec(vec![Some(3), Some(2), None, None])) as _,
+            Arc::new(Int64Array::from_opt_vec(vec![
+                Some(2),
+                Some(-1),
+                Some(4),
+                None,
+            ])) as _,
+        ];
+
+        assert_eq!(
+            &*GREATEST.function_type.call_stateless_fun(&args).unwrap(),
+            &Int64Array::from_opt_vec(vec![Some(3), Some(2), Some(5), None]) as &dyn Array
+        );
+        assert_eq!(
+            &*LEAST.function_type.call_stateless_fun(&args).unwrap(),
+            &Int64Array::from_opt_vec(vec![Some(1), Some(-1), Some(4), None]) as &dyn Array
+        );
+    }
+
+    #[test]
+    fn test_type_promotion() {
+        let schema = Arc::new(
+            Schema::try_new(vec![
+                Field::new("a", DataType::Int32),
+                Field::new("b", DataType::Float64),
+                Field::new("c", DataType::UInt8),
+            ])
+            .unwrap(),
+        );
+        let dataset = DataSet::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int32Array::from_vec(vec![1, 5])),
+                Arc::new(Float64Array::from_opt_vec(vec![Some(1.5), None])),
+                Arc::new(UInt8Array::from_vec(vec![200, 3])),
+            ],
+        )
+        .unwrap();
+
+        let mut expr = call("greatest", vec![col("a"), col("b")])
+            .into_physical(schema.clone())
+            .unwrap();
+        assert_eq!(expr.data_type(), DataType::Float64);
+        assert_eq!(
+            expr.eval(&dataset).unwrap().downcast_ref::<Float64Array>(),
+            &Float64Array::from_vec(vec![1.5, 5.0])
+        );
+
+        let mut expr = call("least", vec![col("a"), col("c")])
+            .into_physical(schema.clone())
+            .unwrap();
+        assert_eq!(expr.data_type(), DataType::Int32);
+        assert_eq!(
+            expr.eval(&dataset).unwrap().downcast_ref::<Int32Array>(),
+            &Int32Array::from_vec(vec![1, 3])
+        );
+
+        assert!(call("greatest", vec![col("a"), value("x")])
+            .into_physical(schema)
+            .is_err());
+    }
+}
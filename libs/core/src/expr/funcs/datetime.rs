@@ -0,0 +1,372 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use chrono::{DateTime, Datelike, Offset, TimeZone, Timelike};
+use chrono_tz::Tz;
+
+use crate::array::{
+    Array, ArrayExt, ArrayRef, DataType, Int64Builder, StringArray, TimestampArray,
+    TimestampBuilder,
+};
+use crate::expr::func::{Function, FunctionType};
+use crate::expr::signature::Signature;
+use crate::Period;
+
+use super::time::timezone_array;
+
+#[derive(Debug, Copy, Clone)]
+enum TruncUnit {
+    Second,
+    Minute,
+    Hour,
+    Period(Period),
+}
+
+impl TruncUnit {
+    fn parse(unit: &str) -> Result<Self> {
+        Ok(match unit.to_ascii_lowercase().as_str() {
+            "second" => TruncUnit::Second,
+            "minute" => TruncUnit::Minute,
+            "hour" => TruncUnit::Hour,
+            "day" => TruncUnit::Period(Period::Day),
+            "week" => TruncUnit::Period(Period::Week),
+            "month" => TruncUnit::Period(Period::Month),
+            "year" => TruncUnit::Period(Period::Year),
+            _ => anyhow::bail!("invalid unit of date_trunc: '{}'", unit),
+        })
+    }
+
+    fn truncate(self, datetime: DateTime<Tz>) -> i64 {
+        let length = match self {
+            TruncUnit::Second => 1000,
+            TruncUnit::Minute => 1000 * 60,
+            TruncUnit::Hour => 1000 * 60 * 60,
+            TruncUnit::Period(period) => return period.window(datetime).0.timestamp_millis(),
+        };
+        // truncates the local time, the offsets of some timezones are not whole hours
+        let offset = datetime.offset().fix().local_minus_utc() as i64 * 1000;
+        (datetime.timestamp_millis() + offset).div_euclid(length) * length - offset
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+enum DatePart {
+    Year,
+    Month,
+    Day,
+    Hour,
+    Minute,
+    Second,
+}
+
+impl DatePart {
+    fn parse(part: &str) -> Result<Self> {
+        Ok(match part.to_ascii_lowercase().as_str() {
+            "year" => DatePart::Year,
+            "month" => DatePart::Month,
+            "day" => DatePart::Day,
+            "hour" => DatePart::Hour,
+            "minute" => DatePart::Minute,
+            "second" => DatePart::Second,
+            _ => anyhow::bail!("invalid part of extract: '{}'", part),
+        })
+    }
+
+    fn extract(self, datetime: DateTime<Tz>) -> i64 {
+        match self {
+            DatePart::Year => datetime.year() as i64,
+            DatePart::Month => datetime.month() as i64,
+            DatePart::Day => datetime.day() as i64,
+            DatePart::Hour => datetime.hour() as i64,
+            DatePart::Minute => datetime.minute() as i64,
+            DatePart::Second => datetime.second() as i64,
+        }
+    }
+}
+
+/// Extracts a part of each timestamp, in the timezone given by `tz_array` or UTC.
+fn extract_parts(
+    array: &ArrayRef,
+    mut parts: impl Iterator<Item = Option<Result<DatePart>>>,
+    tz_array: Option<&ArrayRef>,
+) -> Result<ArrayRef> {
+    let array = array.downcast_ref::<TimestampArray>();
+    let tz_array = timezone_array(tz_array);
+    let mut builder = Int64Builder::with_capacity(array.len());
+
+    for (value, tz_res) in array.iter_opt().zip(tz_array) {
+        let tz = tz_res.map_err(|err| anyhow::anyhow!("{}", err))?;
+
+        match (value, parts.next().flatten().transpose()?) {
+            (Some(value), Some(part)) => builder.append(part.extract(tz.timestamp_millis(value))),
+            _ => builder.append_null(),
+        }
+    }
+
+    Ok(Arc::new(builder.finish()))
+}
+
+pub const DATE_TRUNC: Function = Function {
+    namespace: None,
+    name: "date_trunc",
+    params: &["unit", "value", "timezone"],
+    signature: &Signature::OneOf(&[
+        Signature::Exact(&[
+            DataType::String,
+            DataType::Timestamp(None),
+            DataType::String,
+        ]),
+        Signature::Exact(&[DataType::String, DataType::Timestamp(None)]),
+    ]),
+    return_type: |args| args[1].clone(),
+    function_type: FunctionType::Stateless(|args| {
+        let unit = args[0].downcast_ref::<StringArray>();
+        let array = args[1].downcast_ref::<TimestampArray>();
+        let tz_array = timezone_array(args.get(2));
+        let mut builder = TimestampBuilder::with_capacity(args[1].len());
+
+        for ((unit, value), tz_res) in unit.iter_opt().zip(array.iter_opt()).zip(tz_array) {
+            let tz = tz_res.map_err(|err| anyhow::anyhow!("{}", err))?;
+
+            if let (Some(unit), Some(value)) = (unit, value) {
+                builder.append(TruncUnit::parse(unit)?.truncate(tz.timestamp_millis(value)));
+            } else {
+                builder.append_null();
+            }
+        }
+
+        Ok(Arc::new(builder.finish()))
+    }),
+};
+
+pub const EXTRACT: Function = Function {
+    namespace: None,
+    name: "extract",
+    params: &["part", "value", "timezone"],
+    signature: &Signature::OneOf(&[
+        Signature::Exact(&[
+            DataType::String,
+            DataType::Timestamp(None),
+            DataType::String,
+        ]),
+        Signature::Exact(&[DataType::String, DataType::Timestamp(None)]),
+    ]),
+    return_type: |_| DataType::Int64,
+    function_type: FunctionType::Stateless(|args| {
+        let parts = args[0].downcast_ref::<StringArray>();
+        extract_parts(
+            &args[1],
+            parts.iter_opt().map(|part| part.map(DatePart::parse)),
+            args.get(2),
+        )
+    }),
+};
+
+macro_rules! make_extract_func {
+    ($ident:ident, $name:literal, $part:expr) => {
+        pub const $ident: Function = Function {
+            namespace: None,
+            name: $name,
+            params: &["value", "timezone"],
+            signature: &Signature::OneOf(&[
+                Signature::Exact(&[DataType::Timestamp(None), DataType::String]),
+                Signature::Exact(&[DataType::Timestamp(None)]),
+            ]),
+            return_type: |_| DataType::Int64,
+            function_type: FunctionType::Stateless(|args| {
+                extract_parts(
+                    &args[0],
+                    std::iter::repeat_with(|| Some(Ok($part))),
+                    args.get(1),
+                )
+            }),
+        };
+    };
+}
+
+make_extract_func!(YEAR, "year", DatePart::Year);
+make_extract_func!(MONTH, "month", DatePart::Month);
+make_extract_func!(DAY, "day", DatePart::Day);
+make_extract_func!(HOUR, "hour", DatePart::Hour);
+make_extract_func!(MINUTE, "minute", DatePart::Minute);
+make_extract_func!(SECOND, "second", DatePart::Second);
+
+/// The functions whose omitted `timezone` defaults to the timezone of the timestamp argument.
+const TIMEZONE_OF_ARGUMENT_FUNCS: &[&str] = &[
+    "date_trunc",
+    "extract",
+    "year",
+    "month",
+    "day",
+    "hour",
+    "minute",
+    "second",
+];
+
+pub(crate) fn is_timezone_of_argument_func(func: &Function) -> bool {
+    func.namespace.is_none() && TIMEZONE_OF_ARGUMENT_FUNCS.contains(&func.name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::array::{Int64Array, Scalar};
+    use crate::dataset::{DataSet, Field, Schema};
+    use crate::dsl::*;
+
+    // 2021-08-31 23:45:30.500 UTC, which is 2021-09-01 07:45:30.500 in Asia/Shanghai
+    const TIMESTAMP: i64 = 1630453530500;
+
+    fn date_trunc(unit: &str, timezone: &str) -> ArrayRef {
+        DATE_TRUNC
+            .function_type
+            .call_stateless_fun(&[
+                Arc::new(StringArray::new_scalar(1, Some(unit))),
+                Arc::new(TimestampArray::new_scalar(1, Some(TIMESTAMP))),
+                Arc::new(StringArray::new_scalar(1, Some(timezone))),
+            ])
+            .unwrap()
+    }
+
+    fn parse(s: &str, timezone: &str) -> ArrayRef {
+        let tz: Tz = timezone.parse().unwrap();
+        let mut builder = TimestampBuilder::default();
+        builder.append_str_with_tz(s, Some(tz));
+        Arc::new(builder.finish())
+    }
+
+    #[test]
+    fn test_date_trunc() {
+        for (unit, timezone, expected) in [
+            ("second", "UTC", "2021-08-31 23:45:30"),
+            ("minute", "UTC", "2021-08-31 23:45:00"),
+            ("hour", "UTC", "2021-08-31 23:00:00"),
+            ("day", "UTC", "2021-08-31 00:00:00"),
+            ("week", "UTC", "2021-08-30 00:00:00"),
+            ("month", "UTC", "2021-08-01 00:00:00"),
+            ("hour", "Asia/Shanghai", "2021-09-01 07:00:00"),
+            ("day", "Asia/Shanghai", "2021-09-01 00:00:00"),
+            ("month", "Asia/Shanghai", "2021-09-01 00:00:00"),
+            ("YEAR", "Asia/Shanghai", "2021-01-01 00:00:00"),
+            // the offset of Asia/Kolkata is 5:30
+            ("hour", "Asia/Kolkata", "2021-09-01 05:00:00"),
+        ] {
+            assert_eq!(
+                &*date_trunc(unit, timezone),
+                &*parse(expected, timezone),
+                "{} {}",
+                unit,
+                timezone
+            );
+        }
+
+        assert!(DATE_TRUNC
+            .function_type
+            .call_stateless_fun(&[
+                Arc::new(StringArray::new_scalar(1, Some("fortnight"))),
+                Arc::new(TimestampArray::new_scalar(1, Some(TIMESTAMP))),
+            ])
+            .is_err());
+    }
+
+    #[test]
+    fn test_extract() {
+        let array: ArrayRef = Arc::new(TimestampArray::from_opt_vec(vec![Some(TIMESTAMP), None]));
+
+        assert_eq!(
+            &*EXTRACT
+                .function_type
+                .call_stateless_fun(&[
+                    Arc::new(StringArray::from_vec(vec!["day", "day"])),
+                    array.clone(),
+                ])
+                .unwrap(),
+            &Int64Array::from_opt_vec(vec![Some(31), None]) as &dyn Array
+        );
+        assert_eq!(
+            &*EXTRACT
+                .function_type
+                .call_stateless_fun(&[
+                    Arc::new(StringArray::new_scalar(2, Some("month"))),
+                    array.clone(),
+                    Arc::new(StringArray::new_scalar(2, Some("Asia/Shanghai"))),
+                ])
+                .unwrap(),
+            &Int64Array::from_opt_vec(vec![Some(9), None]) as &dyn Array
+        );
+
+        for (func, expected) in [
+            (YEAR, 2021),
+            (MONTH, 8),
+            (DAY, 31),
+            (HOUR, 23),
+            (MINUTE, 45),
+            (SECOND, 30),
+        ] {
+            assert_eq!(
+                &*func
+                    .function_type
+                    .call_stateless_fun(std::slice::from_ref(&array))
+                    .unwrap(),
+                &Int64Array::from_opt_vec(vec![Some(expected), None]) as &dyn Array,
+                "{}",
+                func.name
+            );
+        }
+    }
+
+    #[test]
+    fn test_timezone_of_column() {
+        let tz = chrono_tz::Asia::Shanghai;
+        let schema = Arc::new(
+            Schema::try_new(vec![Field::new("t", DataType::Timestamp(Some(tz)))]).unwrap(),
+        );
+        let dataset =
+            DataSet::try_from_rows(schema.clone(), vec![vec![Scalar::Timestamp(TIMESTAMP)]])
+                .unwrap();
+
+        let mut expr = call("date_trunc", vec![value("day"), col("t")])
+            .into_physical(schema.clone())
+            .unwrap();
+        assert_eq!(expr.data_type(), DataType::Timestamp(Some(tz)));
+        assert_eq!(
+            &*expr.eval(&dataset).unwrap(),
+            &*parse("2021-09-01 00:00:00", "Asia/Shanghai")
+        );
+
+        let mut expr = call("hour", vec![col("t")])
+            .into_physical(schema.clone())
+            .unwrap();
+        assert_eq!(
+            expr.eval(&dataset)
+                .unwrap()
+                .downcast_ref::<Int64Array>()
+                .value(0),
+            7
+        );
+
+        // an explicit timezone overrides the one of the column
+        let mut expr = call("hour", vec![col("t"), value("UTC")])
+            .into_physical(schema.clone())
+            .unwrap();
+        assert_eq!(
+            expr.eval(&dataset)
+                .unwrap()
+                .downcast_ref::<Int64Array>()
+                .value(0),
+            23
+        );
+
+        // other functions keep their UTC default
+        let mut expr = call("format_timestamp", vec![col("t"), value("%H")])
+            .into_physical(schema)
+            .unwrap();
+        assert_eq!(
+            expr.eval(&dataset)
+                .unwrap()
+                .downcast_ref::<StringArray>()
+                .value(0),
+            "23"
+        );
+    }
+}
@@ -4,11 +4,13 @@ use serde::{Deserialize, Serialize};
 
 use crate::array::{Array, ArrayExt, BooleanBuilder, DataType, Float64Array, Int64Array};
 use crate::expr::func::{Function, FunctionType, StatefulFunction};
+use crate::expr::funcs::utils::check_window_size;
 use crate::expr::signature::Signature;
 
 pub const F_BETWEEN: Function = Function {
     namespace: Some("f"),
     name: "between",
+    params: &[],
     signature: &Signature::Exact(&[DataType::Float64, DataType::Float64, DataType::Float64]),
     return_type: |_| DataType::Boolean,
     function_type: FunctionType::Stateless(|args| {
@@ -37,6 +39,7 @@ struct CrossState {
 pub const F_CROSS: Function = Function {
     namespace: Some("f"),
     name: "cross",
+    params: &[],
     signature: &Signature::Exact(&[DataType::Float64, DataType::Float64]),
     return_type: |_| DataType::Boolean,
     function_type: FunctionType::Stateful(|| {
@@ -69,7 +72,8 @@ struct LongCrossState {
 pub const F_LONGCROSS: Function = Function {
     namespace: Some("f"),
     name: "longcross",
-    signature: &Signature::Exact(&[DataType::Float64, DataType::Float64]),
+    params: &[],
+    signature: &Signature::Exact(&[DataType::Float64, DataType::Float64, DataType::Int64]),
     return_type: |_| DataType::Boolean,
     function_type: FunctionType::Stateful(|| {
         Box::new(StatefulFunction::<LongCrossState>::new(|state, args| {
@@ -79,6 +83,7 @@ pub const F_LONGCROSS: Function = Function {
             let mut builder = BooleanBuilder::with_capacity(a.len());
 
             for ((a, b), n) in a.iter().zip(b.iter()).zip(n.iter()) {
+                check_window_size(n)?;
                 if n > 0 {
                     let res = if a < b {
                         state.count += 1;
@@ -105,3 +110,49 @@ pub const F_LONGCROSS: Function = Function {
         }))
     }),
 };
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::array::BooleanArray;
+    use crate::dataset::{DataSet, Field, Schema};
+    use crate::dsl::*;
+
+    #[test]
+    fn test_longcross() {
+        let schema = Arc::new(
+            Schema::try_new(vec![
+                Field::new("a", DataType::Float64),
+                Field::new("b", DataType::Float64),
+            ])
+            .unwrap(),
+        );
+        let dataset = DataSet::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Float64Array::from_vec(vec![
+                    1.0, 1.0, 1.0, 3.0, 0.0, 0.0, 3.0,
+                ])),
+                Arc::new(Float64Array::new_scalar(7, Some(2.0))),
+            ],
+        )
+        .unwrap();
+
+        // the number of periods is the third argument
+        let mut expr = call_with_namespace("f", "longcross", vec![col("a"), col("b"), value(2)])
+            .into_physical(schema)
+            .unwrap();
+        assert_eq!(
+            expr.eval(&dataset).unwrap().downcast_ref::<BooleanArray>(),
+            &BooleanArray::from_opt_vec(vec![
+                None,
+                None,
+                Some(false),
+                Some(true),
+                Some(false),
+                Some(false),
+                Some(true)
+            ])
+        );
+    }
+}
@@ -8,9 +8,10 @@ use crate::array::{
     ArrayExt, BooleanArray, BooleanBuilder, BooleanType, DataType, Float32Type, Float64Array,
     Float64Builder, Float64Type, Int16Type, Int32Type, Int64Array, Int64Builder, Int64Type,
     Int8Type, PrimitiveArray, PrimitiveBuilder, Scalar, StringArray, StringBuilder, TimestampType,
+    UInt16Type, UInt32Type, UInt64Type, UInt8Type,
 };
 use crate::expr::func::{Function, FunctionType, StatefulFunction};
-use crate::expr::funcs::utils::VecDequeExt;
+use crate::expr::funcs::utils::{check_window_size, VecDequeExt};
 use crate::expr::signature::Signature;
 
 #[derive(Default, Clone, Serialize, Deserialize)]
@@ -23,6 +24,7 @@ struct AllState {
 pub const F_ALL: Function = Function {
     namespace: Some("f"),
     name: "all",
+    params: &[],
     signature: &Signature::Exact(&[DataType::Boolean, DataType::Int64]),
     return_type: |_| DataType::Boolean,
     function_type: FunctionType::Stateful(|| {
@@ -32,6 +34,7 @@ pub const F_ALL: Function = Function {
             let mut builder = BooleanBuilder::default();
 
             for (x, n) in array.iter().zip(n.iter()) {
+                check_window_size(n)?;
                 if n > 0 {
                     if x {
                         state.success_count += 1;
@@ -80,6 +83,7 @@ struct AnyState {
 pub const F_ANY: Function = Function {
     namespace: Some("f"),
     name: "any",
+    params: &[],
     signature: &Signature::Exact(&[DataType::Boolean, DataType::Int64]),
     return_type: |_| DataType::Boolean,
     function_type: FunctionType::Stateful(|| {
@@ -89,6 +93,7 @@ pub const F_ANY: Function = Function {
             let mut builder = BooleanBuilder::default();
 
             for (x, n) in array.iter().zip(n.iter()) {
+                check_window_size(n)?;
                 if n > 0 {
                     if x {
                         state.success_count += 1;
@@ -136,6 +141,7 @@ struct BarsLastState {
 pub const F_BARSLAST: Function = Function {
     namespace: Some("f"),
     name: "barslast",
+    params: &[],
     signature: &Signature::Exact(&[DataType::Boolean]),
     return_type: |_| DataType::Int64,
     function_type: FunctionType::Stateful(|| {
@@ -169,6 +175,7 @@ struct BarsSinceState {
 pub const F_BARSSINCE: Function = Function {
     namespace: Some("f"),
     name: "barssince",
+    params: &[],
     signature: &Signature::Exact(&[DataType::Boolean]),
     return_type: |_| DataType::Int64,
     function_type: FunctionType::Stateful(|| {
@@ -202,6 +209,7 @@ struct CountState {
 pub const F_COUNT: Function = Function {
     namespace: Some("f"),
     name: "count",
+    params: &[],
     signature: &Signature::Exact(&[DataType::Boolean, DataType::Int64]),
     return_type: |_| DataType::Int64,
     function_type: FunctionType::Stateful(|| {
@@ -211,6 +219,7 @@ pub const F_COUNT: Function = Function {
             let mut builder = Int64Builder::default();
 
             for (x, n) in array.iter().zip(n.iter()) {
+                check_window_size(n)?;
                 if n > 0 {
                     if x {
                         state.count += 1;
@@ -247,6 +256,7 @@ struct DmaState {
 pub const F_DMA: Function = Function {
     namespace: Some("f"),
     name: "dma",
+    params: &[],
     signature: &Signature::Exact(&[DataType::Float64, DataType::Float64]),
     return_type: |_| DataType::Float64,
     function_type: FunctionType::Stateful(|| {
@@ -284,6 +294,7 @@ struct EmaState {
 pub const F_EMA: Function = Function {
     namespace: Some("f"),
     name: "ema",
+    params: &[],
     signature: &Signature::Exact(&[DataType::Float64, DataType::Int64]),
     return_type: |_| DataType::Float64,
     function_type: FunctionType::Stateful(|| {
@@ -293,6 +304,7 @@ pub const F_EMA: Function = Function {
             let mut builder = Float64Builder::default();
 
             for (x, n) in array.iter().zip(n.iter()) {
+                check_window_size(n)?;
                 let nx = match state.x {
                     Some(px) if n == 0 => px,
                     Some(px) => (x * 2.0 + (n - 1) as f64 * px) / (n + 1) as f64,
@@ -316,6 +328,7 @@ struct FilterState {
 pub const F_FILTER: Function = Function {
     namespace: Some("f"),
     name: "filter",
+    params: &[],
     signature: &Signature::Exact(&[DataType::Boolean, DataType::Int64]),
     return_type: |_| DataType::Boolean,
     function_type: FunctionType::Stateful(|| {
@@ -325,6 +338,7 @@ pub const F_FILTER: Function = Function {
             let mut builder = BooleanBuilder::default();
 
             for (x, n) in array.iter().zip(n.iter()) {
+                check_window_size(n)?;
                 if n > 0 {
                     if let Some(count) = &mut state.count {
                         *count -= 1;
@@ -362,6 +376,7 @@ struct HhvState {
 pub const F_HHV: Function = Function {
     namespace: Some("f"),
     name: "hhv",
+    params: &[],
     signature: &Signature::Exact(&[DataType::Float64, DataType::Int64]),
     return_type: |_| DataType::Float64,
     function_type: FunctionType::Stateful(|| {
@@ -371,6 +386,7 @@ pub const F_HHV: Function = Function {
             let mut builder = Float64Builder::default();
 
             for (x, n) in array.iter().zip(n.iter()) {
+                check_window_size(n)?;
                 if n > 0 {
                     state.values.push_back_limit(x, n as usize);
                     builder.append(
@@ -414,6 +430,7 @@ struct LlvState {
 pub const F_LLV: Function = Function {
     namespace: Some("f"),
     name: "llv",
+    params: &[],
     signature: &Signature::Exact(&[DataType::Float64, DataType::Int64]),
     return_type: |_| DataType::Float64,
     function_type: FunctionType::Stateful(|| {
@@ -423,6 +440,7 @@ pub const F_LLV: Function = Function {
             let mut builder = Float64Builder::default();
 
             for (x, n) in array.iter().zip(n.iter()) {
+                check_window_size(n)?;
                 if n > 0 {
                     state.values.push_back_limit(x, n as usize);
                     builder.append(
@@ -464,18 +482,35 @@ struct HhvBarsState {
     values: VecDeque<f64>,
 }
 
+/// Returns the number of bars since the highest value in the window.
+///
+/// When several bars share the highest value the earliest one is used, pass `true` as the
+/// optional third argument to use the latest one instead.
 pub const F_HHVBARS: Function = Function {
     namespace: Some("f"),
     name: "hhvbars",
-    signature: &Signature::Exact(&[DataType::Float64, DataType::Int64]),
+    params: &[],
+    signature: &Signature::OneOf(&[
+        Signature::Exact(&[DataType::Float64, DataType::Int64]),
+        Signature::Exact(&[DataType::Float64, DataType::Int64, DataType::Boolean]),
+    ]),
     return_type: |_| DataType::Int64,
     function_type: FunctionType::Stateful(|| {
         Box::new(StatefulFunction::<HhvBarsState>::new(|state, args| {
             let array = args[0].downcast_ref::<Float64Array>();
             let n = args[1].downcast_ref::<Int64Array>();
+            let latest = args
+                .get(2)
+                .map(|latest| latest.downcast_ref::<BooleanArray>());
             let mut builder = Int64Builder::default();
 
-            for (x, n) in array.iter().zip(n.iter()) {
+            for (idx, (x, n)) in array.iter().zip(n.iter()).enumerate() {
+                check_window_size(n)?;
+                let latest = latest
+                    .and_then(|latest| latest.value_opt(idx))
+                    .unwrap_or_default();
+                let replace = |x: f64, max_value: f64| x > max_value || (latest && x == max_value);
+
                 if n > 0 {
                     state.values.push_back_limit(x, n as usize);
                     let max_idx = state
@@ -484,7 +519,7 @@ pub const F_HHVBARS: Function = Function {
                         .cloned()
                         .enumerate()
                         .fold(None, |acc, (idx, x)| match acc {
-                            Some((max_value, _)) if x > max_value => Some((x, idx)),
+                            Some((max_value, _)) if replace(x, max_value) => Some((x, idx)),
                             Some(_) => acc,
                             None => Some((x, idx)),
                         })
@@ -493,7 +528,7 @@ pub const F_HHVBARS: Function = Function {
                     builder.append((state.values.len() - max_idx - 1) as i64);
                 } else {
                     match state.max {
-                        Some((max_value, _)) if x > max_value => {
+                        Some((max_value, _)) if replace(x, max_value) => {
                             state.max = Some((x, state.index));
                             builder.append(0);
                         }
@@ -522,18 +557,35 @@ struct LlvBarsState {
     values: VecDeque<f64>,
 }
 
+/// Returns the number of bars since the lowest value in the window.
+///
+/// When several bars share the lowest value the earliest one is used, pass `true` as the
+/// optional third argument to use the latest one instead.
 pub const F_LLVBARS: Function = Function {
     namespace: Some("f"),
     name: "llvbars",
-    signature: &Signature::Exact(&[DataType::Float64, DataType::Int64]),
+    params: &[],
+    signature: &Signature::OneOf(&[
+        Signature::Exact(&[DataType::Float64, DataType::Int64]),
+        Signature::Exact(&[DataType::Float64, DataType::Int64, DataType::Boolean]),
+    ]),
     return_type: |_| DataType::Int64,
     function_type: FunctionType::Stateful(|| {
         Box::new(StatefulFunction::<LlvBarsState>::new(|state, args| {
             let array = args[0].downcast_ref::<Float64Array>();
             let n = args[1].downcast_ref::<Int64Array>();
+            let latest = args
+                .get(2)
+                .map(|latest| latest.downcast_ref::<BooleanArray>());
             let mut builder = Int64Builder::default();
 
-            for (x, n) in array.iter().zip(n.iter()) {
+            for (idx, (x, n)) in array.iter().zip(n.iter()).enumerate() {
+                check_window_size(n)?;
+                let latest = latest
+                    .and_then(|latest| latest.value_opt(idx))
+                    .unwrap_or_default();
+                let replace = |x: f64, min_value: f64| x < min_value || (latest && x == min_value);
+
                 if n > 0 {
                     state.values.push_back_limit(x, n as usize);
                     let min_idx = state
@@ -542,7 +594,7 @@ pub const F_LLVBARS: Function = Function {
                         .cloned()
                         .enumerate()
                         .fold(None, |acc, (idx, x)| match acc {
-                            Some((min_value, _)) if x < min_value => Some((x, idx)),
+                            Some((min_value, _)) if replace(x, min_value) => Some((x, idx)),
                             Some(_) => acc,
                             None => Some((x, idx)),
                         })
@@ -551,7 +603,7 @@ pub const F_LLVBARS: Function = Function {
                     builder.append((state.values.len() - min_idx - 1) as i64);
                 } else {
                     match state.min {
-                        Some((min_value, _)) if x < min_value => {
+                        Some((min_value, _)) if replace(x, min_value) => {
                             state.min = Some((x, state.index));
                             builder.append(0);
                         }
@@ -581,6 +633,7 @@ struct LastState {
 pub const F_LAST: Function = Function {
     namespace: Some("f"),
     name: "last",
+    params: &[],
     signature: &Signature::Exact(&[DataType::Boolean]),
     return_type: |_| DataType::Int64,
     function_type: FunctionType::Stateful(|| {
@@ -612,6 +665,7 @@ struct MaState {
 pub const F_MA: Function = Function {
     namespace: Some("f"),
     name: "ma",
+    params: &[],
     signature: &Signature::Exact(&[DataType::Float64, DataType::Int64]),
     return_type: |_| DataType::Float64,
     function_type: FunctionType::Stateful(|| {
@@ -621,6 +675,7 @@ pub const F_MA: Function = Function {
             let mut builder = Float64Builder::default();
 
             for (x, n) in array.iter().zip(n.iter()) {
+                check_window_size(n)?;
                 if n > 0 {
                     state.values.push_back_limit(x, n as usize);
                     if state.values.len() == n as usize {
@@ -648,6 +703,7 @@ struct MemaState {
 pub const F_MEMA: Function = Function {
     namespace: Some("f"),
     name: "mema",
+    params: &[],
     signature: &Signature::Exact(&[DataType::Float64, DataType::Int64]),
     return_type: |_| DataType::Float64,
     function_type: FunctionType::Stateful(|| {
@@ -657,6 +713,7 @@ pub const F_MEMA: Function = Function {
             let mut builder = Float64Builder::default();
 
             for (x, n) in array.iter().zip(n.iter()) {
+                check_window_size(n)?;
                 match state.y {
                     Some(py) => {
                         let new_py = if n >= 1 {
@@ -685,13 +742,16 @@ struct RefState {
 }
 
 macro_rules! ref_values {
-    ($state:expr, $array:expr, $n:expr, $ty:ty, $scalar_ty:ident) => {{
+    ($state:expr, $array:expr, $n:expr, $default:expr, $ty:ty, $scalar_ty:ident) => {{
+        let default = $default.map(|array| array.downcast_ref::<PrimitiveArray<$ty>>());
         let mut builder = PrimitiveBuilder::<$ty>::with_capacity($array.len());
-        for (x, n) in $array
+        for (idx, (x, n)) in $array
             .downcast_ref::<PrimitiveArray<$ty>>()
             .iter_opt()
             .zip($n.iter())
+            .enumerate()
         {
+            check_window_size(n)?;
             $state.values.push_back_limit(
                 match x {
                     Some(x) => Scalar::$scalar_ty(x),
@@ -705,7 +765,7 @@ macro_rules! ref_values {
                     _ => builder.append_null(),
                 }
             } else {
-                builder.append_null();
+                builder.append_opt(default.and_then(|default| default.value_opt(idx)));
             }
         }
         Ok(Arc::new(builder.finish()))
@@ -715,6 +775,7 @@ macro_rules! ref_values {
 pub const F_REF: Function = Function {
     namespace: Some("f"),
     name: "ref",
+    params: &[],
     signature: &Signature::OneOf(&[
         Signature::Exact(&[DataType::Float64, DataType::Int64]),
         Signature::Exact(&[DataType::Float32, DataType::Int64]),
@@ -725,26 +786,53 @@ pub const F_REF: Function = Function {
         Signature::Exact(&[DataType::Boolean, DataType::Int64]),
         Signature::Exact(&[DataType::Timestamp(None), DataType::Int64]),
         Signature::Exact(&[DataType::String, DataType::Int64]),
+        Signature::Exact(&[DataType::Float64, DataType::Int64, DataType::Float64]),
+        Signature::Exact(&[DataType::Float32, DataType::Int64, DataType::Float32]),
+        Signature::Exact(&[DataType::Int64, DataType::Int64, DataType::Int64]),
+        Signature::Exact(&[DataType::Int32, DataType::Int64, DataType::Int32]),
+        Signature::Exact(&[DataType::Int16, DataType::Int64, DataType::Int16]),
+        Signature::Exact(&[DataType::Int8, DataType::Int64, DataType::Int8]),
+        Signature::Exact(&[DataType::Boolean, DataType::Int64, DataType::Boolean]),
+        Signature::Exact(&[
+            DataType::Timestamp(None),
+            DataType::Int64,
+            DataType::Timestamp(None),
+        ]),
+        Signature::Exact(&[DataType::String, DataType::Int64, DataType::String]),
     ]),
-    return_type: |args| args[0],
+    return_type: |args| args[0].clone(),
     function_type: FunctionType::Stateful(|| {
         Box::new(StatefulFunction::<RefState>::new(|state, args| {
             let array = &args[0];
             let n = args[1].downcast_ref::<Int64Array>();
+            let default = args.get(2);
 
             match array.data_type() {
                 DataType::Null => unreachable!(),
-                DataType::Int8 => ref_values!(state, array, n, Int8Type, Int8),
-                DataType::Int16 => ref_values!(state, array, n, Int16Type, Int16),
-                DataType::Int32 => ref_values!(state, array, n, Int32Type, Int32),
-                DataType::Int64 => ref_values!(state, array, n, Int64Type, Int64),
-                DataType::Float32 => ref_values!(state, array, n, Float32Type, Float32),
-                DataType::Float64 => ref_values!(state, array, n, Float64Type, Float64),
-                DataType::Boolean => ref_values!(state, array, n, BooleanType, Boolean),
-                DataType::Timestamp(_) => ref_values!(state, array, n, TimestampType, Timestamp),
+                DataType::Int8 => ref_values!(state, array, n, default, Int8Type, Int8),
+                DataType::Int16 => ref_values!(state, array, n, default, Int16Type, Int16),
+                DataType::Int32 => ref_values!(state, array, n, default, Int32Type, Int32),
+                DataType::Int64 => ref_values!(state, array, n, default, Int64Type, Int64),
+                DataType::UInt8 => ref_values!(state, array, n, default, UInt8Type, UInt8),
+                DataType::UInt16 => ref_values!(state, array, n, default, UInt16Type, UInt16),
+                DataType::UInt32 => ref_values!(state, array, n, default, UInt32Type, UInt32),
+                DataType::UInt64 => ref_values!(state, array, n, default, UInt64Type, UInt64),
+                DataType::Float32 => ref_values!(state, array, n, default, Float32Type, Float32),
+                DataType::Float64 => ref_values!(state, array, n, default, Float64Type, Float64),
+                DataType::Boolean => ref_values!(state, array, n, default, BooleanType, Boolean),
+                DataType::Timestamp(_) => {
+                    ref_values!(state, array, n, default, TimestampType, Timestamp)
+                }
                 DataType::String => {
+                    let default = default.map(|array| array.downcast_ref::<StringArray>());
                     let mut builder = StringBuilder::with_capacity(array.len());
-                    for (x, n) in array.downcast_ref::<StringArray>().iter_opt().zip(n.iter()) {
+                    for (idx, (x, n)) in array
+                        .downcast_ref::<StringArray>()
+                        .iter_opt()
+                        .zip(n.iter())
+                        .enumerate()
+                    {
+                        check_window_size(n)?;
                         state.values.push_back_limit(
                             match x {
                                 Some(x) => Scalar::String(x.into()),
@@ -758,11 +846,14 @@ pub const F_REF: Function = Function {
                                 _ => builder.append_null(),
                             }
                         } else {
-                            builder.append_null();
+                            builder.append_opt(default.and_then(|default| default.value_opt(idx)));
                         }
                     }
                     Ok(Arc::new(builder.finish()))
                 }
+                DataType::Decimal128 { .. } | DataType::List(_) | DataType::Struct(_) => {
+                    unreachable!()
+                }
             }
         }))
     }),
@@ -776,16 +867,18 @@ struct SmaState {
 pub const F_SMA: Function = Function {
     namespace: Some("f"),
     name: "sma",
+    params: &[],
     signature: &Signature::Exact(&[DataType::Float64, DataType::Int64, DataType::Float64]),
     return_type: |_| DataType::Float64,
     function_type: FunctionType::Stateful(|| {
         Box::new(StatefulFunction::<SmaState>::new(|state, args| {
             let array = args[0].downcast_ref::<Float64Array>();
             let n = args[1].downcast_ref::<Int64Array>();
-            let m = args[1].downcast_ref::<Float64Array>();
+            let m = args[2].downcast_ref::<Float64Array>();
             let mut builder = Float64Builder::default();
 
             for ((x, n), m) in array.iter().zip(n.iter()).zip(m.iter()) {
+                check_window_size(n)?;
                 match state.y {
                     Some(py) => {
                         let new_py = if n > 0 {
@@ -817,6 +910,7 @@ struct SumState {
 pub const F_SUM: Function = Function {
     namespace: Some("f"),
     name: "sum",
+    params: &[],
     signature: &Signature::Exact(&[DataType::Float64, DataType::Int64]),
     return_type: |_| DataType::Float64,
     function_type: FunctionType::Stateful(|| {
@@ -826,6 +920,7 @@ pub const F_SUM: Function = Function {
             let mut builder = Float64Builder::default();
 
             for (x, n) in array.iter().zip(n.iter()) {
+                check_window_size(n)?;
                 if n > 0 {
                     state.values.push_back_limit(x, n as usize);
                     if state.values.len() == n as usize {
@@ -855,6 +950,7 @@ struct WmaState {
 pub const F_WMA: Function = Function {
     namespace: Some("f"),
     name: "wma",
+    params: &[],
     signature: &Signature::Exact(&[DataType::Float64, DataType::Int64]),
     return_type: |_| DataType::Float64,
     function_type: FunctionType::Stateful(|| {
@@ -864,6 +960,7 @@ pub const F_WMA: Function = Function {
             let mut builder = Float64Builder::default();
 
             for (x, n) in array.iter().zip(n.iter()) {
+                check_window_size(n)?;
                 if n > 0 {
                     state.values.push_back_limit(x, n as usize);
                     if state.values.len() == n as usize {
@@ -892,3 +989,182 @@ pub const F_WMA: Function = Function {
         }))
     }),
 };
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::array::ArrayRef;
+
+    #[test]
+    fn test_ref_default() {
+        let mut f = F_REF.function_type.create_stateful_fun();
+        let array = f
+            .call(&[
+                Arc::new(Int64Array::from_vec(vec![1, 2, 3])),
+                Arc::new(Int64Array::new_scalar(3, Some(2))),
+                Arc::new(Int64Array::new_scalar(3, Some(-1))),
+            ])
+            .unwrap();
+        assert_eq!(
+            array
+                .downcast_ref::<Int64Array>()
+                .iter()
+                .collect::<Vec<_>>(),
+            vec![-1, -1, 1]
+        );
+
+        let state = f.save_state().unwrap();
+        let mut f = F_REF.function_type.create_stateful_fun();
+        f.load_state(state).unwrap();
+
+        let array = f
+            .call(&[
+                Arc::new(Int64Array::from_vec(vec![4, 5])),
+                Arc::new(Int64Array::new_scalar(2, Some(2))),
+                Arc::new(Int64Array::new_scalar(2, Some(-1))),
+            ])
+            .unwrap();
+        assert_eq!(
+            array
+                .downcast_ref::<Int64Array>()
+                .iter()
+                .collect::<Vec<_>>(),
+            vec![2, 3]
+        );
+    }
+
+    #[test]
+    fn test_ref_string_default() {
+        let mut f = F_REF.function_type.create_stateful_fun();
+        let array = f
+            .call(&[
+                Arc::new(StringArray::from_vec(vec!["a", "b", "c"])),
+                Arc::new(Int64Array::new_scalar(3, Some(1))),
+                Arc::new(StringArray::new_scalar(3, Some("-"))),
+            ])
+            .unwrap();
+        assert_eq!(
+            array
+                .downcast_ref::<StringArray>()
+                .iter_opt()
+                .collect::<Vec<_>>(),
+            vec![Some("-"), Some("a"), Some("b")]
+        );
+    }
+
+    fn call_bars(func: &Function, values: Vec<f64>, n: i64, latest: Option<bool>) -> Vec<i64> {
+        let mut f = func.function_type.create_stateful_fun();
+        let len = values.len();
+        let mut args: Vec<ArrayRef> = vec![
+            Arc::new(Float64Array::from_vec(values)),
+            Arc::new(Int64Array::new_scalar(len, Some(n))),
+        ];
+        if let Some(latest) = latest {
+            args.push(Arc::new(BooleanArray::new_scalar(len, Some(latest))));
+        }
+        f.call(&args)
+            .unwrap()
+            .downcast_ref::<Int64Array>()
+            .iter()
+            .collect()
+    }
+
+    #[test]
+    fn test_hhvbars_ties() {
+        let values = vec![1.0, 3.0, 3.0, 2.0, 3.0, 1.0];
+
+        for latest in [None, Some(false)].iter().cloned() {
+            assert_eq!(
+                call_bars(&F_HHVBARS, values.clone(), 0, latest),
+                vec![0, 0, 1, 2, 3, 4]
+            );
+            assert_eq!(
+                call_bars(&F_HHVBARS, values.clone(), 3, latest),
+                vec![0, 0, 1, 2, 2, 1]
+            );
+        }
+
+        assert_eq!(
+            call_bars(&F_HHVBARS, values.clone(), 0, Some(true)),
+            vec![0, 0, 0, 1, 0, 1]
+        );
+        assert_eq!(
+            call_bars(&F_HHVBARS, values, 3, Some(true)),
+            vec![0, 0, 0, 1, 0, 1]
+        );
+    }
+
+    #[test]
+    fn test_llvbars_ties() {
+        let values = vec![-1.0, -3.0, -3.0, -2.0, -3.0, -1.0];
+
+        for latest in [None, Some(false)].iter().cloned() {
+            assert_eq!(
+                call_bars(&F_LLVBARS, values.clone(), 0, latest),
+                vec![0, 0, 1, 2, 3, 4]
+            );
+            assert_eq!(
+                call_bars(&F_LLVBARS, values.clone(), 3, latest),
+                vec![0, 0, 1, 2, 2, 1]
+            );
+        }
+
+        assert_eq!(
+            call_bars(&F_LLVBARS, values.clone(), 0, Some(true)),
+            vec![0, 0, 0, 1, 0, 1]
+        );
+        assert_eq!(
+            call_bars(&F_LLVBARS, values, 3, Some(true)),
+            vec![0, 0, 0, 1, 0, 1]
+        );
+    }
+
+    #[test]
+    fn test_sma() {
+        // the weight is the third argument
+        let mut f = F_SMA.function_type.create_stateful_fun();
+        let array = f
+            .call(&[
+                Arc::new(Float64Array::from_vec(vec![1.0, 3.0, 5.0])),
+                Arc::new(Int64Array::new_scalar(3, Some(2))),
+                Arc::new(Float64Array::new_scalar(3, Some(1.0))),
+            ])
+            .unwrap();
+        assert_eq!(
+            array
+                .downcast_ref::<Float64Array>()
+                .iter()
+                .collect::<Vec<_>>(),
+            vec![0.0, 2.0, 3.5]
+        );
+    }
+
+    #[test]
+    fn test_negative_window_size() {
+        for func in &[
+            F_EMA, F_HHV, F_LLV, F_HHVBARS, F_LLVBARS, F_MA, F_MEMA, F_REF, F_SUM, F_WMA,
+        ] {
+            let mut f = func.function_type.create_stateful_fun();
+            let err = f
+                .call(&[
+                    Arc::new(Float64Array::from_vec(vec![1.0, 2.0, 3.0])),
+                    Arc::new(Int64Array::new_scalar(3, Some(-1))),
+                ])
+                .unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                "window size must not be negative, but got -1"
+            );
+        }
+
+        for func in &[F_ALL, F_ANY, F_COUNT, F_FILTER] {
+            let mut f = func.function_type.create_stateful_fun();
+            assert!(f
+                .call(&[
+                    Arc::new(BooleanArray::from_vec(vec![true, false, true])),
+                    Arc::new(Int64Array::new_scalar(3, Some(-1))),
+                ])
+                .is_err());
+        }
+    }
+}
@@ -1,13 +1,14 @@
 #![allow(clippy::suspicious_operation_groupings)]
 
-use std::collections::VecDeque;
+use std::collections::{BTreeMap, VecDeque};
 use std::sync::Arc;
 
+use ordered_float::OrderedFloat;
 use serde::{Deserialize, Serialize};
 
 use crate::array::{ArrayExt, DataType, Float64Array, Float64Builder, Int64Array};
 use crate::expr::func::{Function, FunctionType, StatefulFunction};
-use crate::expr::funcs::utils::VecDequeExt;
+use crate::expr::funcs::utils::{check_window_size, VecDequeExt};
 use crate::expr::signature::Signature;
 
 #[derive(Default, Clone, Serialize, Deserialize)]
@@ -18,6 +19,7 @@ struct AveDevState {
 pub const F_AVEDEV: Function = Function {
     namespace: Some("f"),
     name: "avedev",
+    params: &[],
     signature: &Signature::Exact(&[DataType::Float64, DataType::Int64]),
     return_type: |_| DataType::Float64,
     function_type: FunctionType::Stateful(|| {
@@ -27,6 +29,7 @@ pub const F_AVEDEV: Function = Function {
             let mut builder = Float64Builder::default();
 
             for (x, n) in array.iter().zip(n.iter()) {
+                check_window_size(n)?;
                 if n < 2 {
                     builder.append_null();
                     continue;
@@ -51,6 +54,61 @@ pub const F_AVEDEV: Function = Function {
     }),
 };
 
+#[derive(Default, Clone, Serialize, Deserialize)]
+struct CorrState {
+    values: VecDeque<(f64, f64)>,
+}
+
+pub const F_CORR: Function = Function {
+    namespace: Some("f"),
+    name: "corr",
+    params: &[],
+    signature: &Signature::Exact(&[DataType::Float64, DataType::Float64, DataType::Int64]),
+    return_type: |_| DataType::Float64,
+    function_type: FunctionType::Stateful(|| {
+        Box::new(StatefulFunction::<CorrState>::new(|state, args| {
+            let a = args[0].downcast_ref::<Float64Array>();
+            let b = args[1].downcast_ref::<Float64Array>();
+            let n = args[2].downcast_ref::<Int64Array>();
+            let mut builder = Float64Builder::default();
+
+            for ((x, y), n) in a.iter().zip(b.iter()).zip(n.iter()) {
+                check_window_size(n)?;
+                if n < 2 {
+                    builder.append_null();
+                    continue;
+                }
+
+                state.values.push_back_limit((x, y), n as usize);
+                if state.values.len() == n as usize {
+                    let count = state.values.len() as f64;
+                    let mx = state.values.iter().map(|(x, _)| *x).sum::<f64>() / count;
+                    let my = state.values.iter().map(|(_, y)| *y).sum::<f64>() / count;
+                    let mut sxy = 0.0;
+                    let mut sxx = 0.0;
+                    let mut syy = 0.0;
+
+                    for (x, y) in state.values.iter().copied() {
+                        sxy += (x - mx) * (y - my);
+                        sxx += (x - mx) * (x - mx);
+                        syy += (y - my) * (y - my);
+                    }
+
+                    if sxx == 0.0 || syy == 0.0 {
+                        builder.append_null();
+                    } else {
+                        builder.append((sxy / (sxx * syy).sqrt()).clamp(-1.0, 1.0));
+                    }
+                } else {
+                    builder.append_null();
+                }
+            }
+
+            Ok(Arc::new(builder.finish()))
+        }))
+    }),
+};
+
 #[derive(Default, Clone, Serialize, Deserialize)]
 struct DevSqState {
     values: VecDeque<f64>,
@@ -59,6 +117,7 @@ struct DevSqState {
 pub const F_DEVSQ: Function = Function {
     namespace: Some("f"),
     name: "devsq",
+    params: &[],
     signature: &Signature::Exact(&[DataType::Float64, DataType::Int64]),
     return_type: |_| DataType::Float64,
     function_type: FunctionType::Stateful(|| {
@@ -68,6 +127,7 @@ pub const F_DEVSQ: Function = Function {
             let mut builder = Float64Builder::default();
 
             for (x, n) in array.iter().zip(n.iter()) {
+                check_window_size(n)?;
                 if n < 2 {
                     builder.append_null();
                     continue;
@@ -126,6 +186,7 @@ fn lsm(values: &VecDeque<f64>) -> Option<(f64, f64)> {
 pub const F_FORCAST: Function = Function {
     namespace: Some("f"),
     name: "forcast",
+    params: &[],
     signature: &Signature::Exact(&[DataType::Float64, DataType::Int64]),
     return_type: |_| DataType::Float64,
     function_type: FunctionType::Stateful(|| {
@@ -135,6 +196,7 @@ pub const F_FORCAST: Function = Function {
             let mut builder = Float64Builder::default();
 
             for (x, n) in array.iter().zip(n.iter()) {
+                check_window_size(n)?;
                 if n < 2 {
                     builder.append_null();
                     continue;
@@ -165,6 +227,7 @@ struct SlopeState {
 pub const F_SLOPE: Function = Function {
     namespace: Some("f"),
     name: "slope",
+    params: &[],
     signature: &Signature::Exact(&[DataType::Float64, DataType::Int64]),
     return_type: |_| DataType::Float64,
     function_type: FunctionType::Stateful(|| {
@@ -174,6 +237,7 @@ pub const F_SLOPE: Function = Function {
             let mut builder = Float64Builder::default();
 
             for (x, n) in array.iter().zip(n.iter()) {
+                check_window_size(n)?;
                 if n < 2 {
                     builder.append_null();
                     continue;
@@ -216,6 +280,7 @@ struct StdState {
 pub const F_STD: Function = Function {
     namespace: Some("f"),
     name: "std",
+    params: &[],
     signature: &Signature::Exact(&[DataType::Float64, DataType::Int64]),
     return_type: |_| DataType::Float64,
     function_type: FunctionType::Stateful(|| {
@@ -225,6 +290,7 @@ pub const F_STD: Function = Function {
             let mut builder = Float64Builder::default();
 
             for (x, n) in array.iter().zip(n.iter()) {
+                check_window_size(n)?;
                 if n < 2 {
                     builder.append_null();
                     continue;
@@ -261,6 +327,7 @@ struct StdDevState {
 pub const F_STDDEV: Function = Function {
     namespace: Some("f"),
     name: "stddev",
+    params: &[],
     signature: &Signature::Exact(&[DataType::Float64, DataType::Int64]),
     return_type: |_| DataType::Float64,
     function_type: FunctionType::Stateful(|| {
@@ -270,6 +337,7 @@ pub const F_STDDEV: Function = Function {
             let mut builder = Float64Builder::default();
 
             for (x, n) in array.iter().zip(n.iter()) {
+                check_window_size(n)?;
                 if n < 2 {
                     builder.append_null();
                     continue;
@@ -303,6 +371,7 @@ struct StdpState {
 pub const F_STDP: Function = Function {
     namespace: Some("f"),
     name: "stdp",
+    params: &[],
     signature: &Signature::Exact(&[DataType::Float64, DataType::Int64]),
     return_type: |_| DataType::Float64,
     function_type: FunctionType::Stateful(|| {
@@ -312,6 +381,7 @@ pub const F_STDP: Function = Function {
             let mut builder = Float64Builder::default();
 
             for (x, n) in array.iter().zip(n.iter()) {
+                check_window_size(n)?;
                 if n < 2 {
                     builder.append_null();
                     continue;
@@ -347,6 +417,7 @@ struct VarState {
 pub const F_VAR: Function = Function {
     namespace: Some("f"),
     name: "var",
+    params: &[],
     signature: &Signature::Exact(&[DataType::Float64, DataType::Int64]),
     return_type: |_| DataType::Float64,
     function_type: FunctionType::Stateful(|| {
@@ -356,6 +427,7 @@ pub const F_VAR: Function = Function {
             let mut builder = Float64Builder::default();
 
             for (x, n) in array.iter().zip(n.iter()) {
+                check_window_size(n)?;
                 if n < 2 {
                     builder.append_null();
                     continue;
@@ -391,6 +463,7 @@ struct VarpState {
 pub const F_VARP: Function = Function {
     namespace: Some("f"),
     name: "varp",
+    params: &[],
     signature: &Signature::Exact(&[DataType::Float64, DataType::Int64]),
     return_type: |_| DataType::Float64,
     function_type: FunctionType::Stateful(|| {
@@ -400,6 +473,7 @@ pub const F_VARP: Function = Function {
             let mut builder = Float64Builder::default();
 
             for (x, n) in array.iter().zip(n.iter()) {
+                check_window_size(n)?;
                 if n < 2 {
                     builder.append_null();
                     continue;
@@ -426,3 +500,279 @@ pub const F_VARP: Function = Function {
         }))
     }),
 };
+
+/// The values of a window split into two halves, so that the median is at the boundary of them.
+///
+/// The lower half has the same number of values as the upper half, or one more.
+#[derive(Default, Clone, Serialize, Deserialize)]
+struct MedianState {
+    values: VecDeque<f64>,
+    lower: BTreeMap<OrderedFloat<f64>, usize>,
+    lower_len: usize,
+    upper: BTreeMap<OrderedFloat<f64>, usize>,
+    upper_len: usize,
+}
+
+fn multiset_insert(set: &mut BTreeMap<OrderedFloat<f64>, usize>, x: OrderedFloat<f64>) {
+    *set.entry(x).or_default() += 1;
+}
+
+fn multiset_remove(set: &mut BTreeMap<OrderedFloat<f64>, usize>, x: OrderedFloat<f64>) {
+    if let Some(count) = set.get_mut(&x) {
+        *count -= 1;
+        if *count == 0 {
+            set.remove(&x);
+        }
+    }
+}
+
+impl MedianState {
+    fn lower_max(&self) -> Option<OrderedFloat<f64>> {
+        self.lower.keys().next_back().copied()
+    }
+
+    fn upper_min(&self) -> Option<OrderedFloat<f64>> {
+        self.upper.keys().next().copied()
+    }
+
+    fn insert(&mut self, x: f64) {
+        let x = OrderedFloat(x);
+        if self.lower_max().map(|max| x <= max).unwrap_or(true) {
+            multiset_insert(&mut self.lower, x);
+            self.lower_len += 1;
+        } else {
+            multiset_insert(&mut self.upper, x);
+            self.upper_len += 1;
+        }
+        self.rebalance();
+    }
+
+    fn remove(&mut self, x: f64) {
+        let x = OrderedFloat(x);
+        if self.lower.contains_key(&x) {
+            multiset_remove(&mut self.lower, x);
+            self.lower_len -= 1;
+        } else {
+            multiset_remove(&mut self.upper, x);
+            self.upper_len -= 1;
+        }
+        self.rebalance();
+    }
+
+    fn rebalance(&mut self) {
+        while self.lower_len > self.upper_len + 1 {
+            let x = self.lower_max().expect("the lower half is not empty");
+            multiset_remove(&mut self.lower, x);
+            multiset_insert(&mut self.upper, x);
+            self.lower_len -= 1;
+            self.upper_len += 1;
+        }
+        while self.upper_len > self.lower_len {
+            let x = self.upper_min().expect("the upper half is not empty");
+            multiset_remove(&mut self.upper, x);
+            multiset_insert(&mut self.lower, x);
+            self.upper_len -= 1;
+            self.lower_len += 1;
+        }
+    }
+
+    fn median(&self) -> Option<f64> {
+        let lower_max = self.lower_max()?.0;
+        if self.lower_len > self.upper_len {
+            Some(lower_max)
+        } else {
+            Some((lower_max + self.upper_min()?.0) / 2.0)
+        }
+    }
+}
+
+pub const F_MEDIAN_OVER: Function = Function {
+    namespace: Some("f"),
+    name: "median_over",
+    params: &[],
+    signature: &Signature::Exact(&[DataType::Float64, DataType::Int64]),
+    return_type: |_| DataType::Float64,
+    function_type: FunctionType::Stateful(|| {
+        Box::new(StatefulFunction::<MedianState>::new(|state, args| {
+            let array = args[0].downcast_ref::<Float64Array>();
+            let n = args[1].downcast_ref::<Int64Array>();
+            let mut builder = Float64Builder::default();
+
+            for (x, n) in array.iter().zip(n.iter()) {
+                check_window_size(n)?;
+                state.insert(x);
+                if n > 0 {
+                    state.values.push_back(x);
+                    while state.values.len() > n as usize {
+                        if let Some(value) = state.values.pop_front() {
+                            state.remove(value);
+                        }
+                    }
+                    if state.values.len() == n as usize {
+                        builder.append_opt(state.median());
+                    } else {
+                        builder.append_null();
+                    }
+                } else {
+                    builder.append_opt(state.median());
+                }
+            }
+
+            Ok(Arc::new(builder.finish()))
+        }))
+    }),
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn corr_values(
+        f: &mut Box<dyn crate::expr::func::GenericStatefulFunction>,
+        x: Vec<f64>,
+        y: Vec<f64>,
+        n: i64,
+    ) -> Vec<Option<f64>> {
+        let len = x.len();
+        let array = f
+            .call(&[
+                Arc::new(Float64Array::from_vec(x)),
+                Arc::new(Float64Array::from_vec(y)),
+                Arc::new(Int64Array::new_scalar(len, Some(n))),
+            ])
+            .unwrap();
+        array
+            .downcast_ref::<Float64Array>()
+            .iter_opt()
+            .map(|value| value.map(|value| (value * 1e6).round() / 1e6))
+            .collect()
+    }
+
+    #[test]
+    fn test_corr() {
+        let mut f = F_CORR.function_type.create_stateful_fun();
+        assert_eq!(
+            corr_values(&mut f, vec![1.0, 2.0, 3.0], vec![2.0, 1.0, 4.0], 3),
+            vec![None, None, Some(0.654654)]
+        );
+
+        let state = f.save_state().unwrap();
+        let mut f = F_CORR.function_type.create_stateful_fun();
+        f.load_state(state).unwrap();
+        assert_eq!(
+            corr_values(&mut f, vec![4.0, 5.0], vec![3.0, 6.0], 3),
+            vec![Some(0.654654), Some(0.654654)]
+        );
+    }
+
+    #[test]
+    fn test_corr_zero_variance() {
+        let mut f = F_CORR.function_type.create_stateful_fun();
+        assert_eq!(
+            corr_values(&mut f, vec![1.0, 2.0, 3.0], vec![5.0, 5.0, 5.0], 2),
+            vec![None, None, None]
+        );
+    }
+
+    fn median_values(
+        f: &mut Box<dyn crate::expr::func::GenericStatefulFunction>,
+        x: &[f64],
+        n: i64,
+    ) -> Vec<Option<f64>> {
+        let array = f
+            .call(&[
+                Arc::new(Float64Array::from_vec(x.to_vec())),
+                Arc::new(Int64Array::new_scalar(x.len(), Some(n))),
+            ])
+            .unwrap();
+        array.downcast_ref::<Float64Array>().iter_opt().collect()
+    }
+
+    fn naive_median(values: &[f64]) -> f64 {
+        let mut values = values.to_vec();
+        values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let mid = values.len() / 2;
+        if values.len() % 2 == 1 {
+            values[mid]
+        } else {
+            (values[mid - 1] + values[mid]) / 2.0
+        }
+    }
+
+    #[test]
+    fn test_median_over() {
+        // a long series with many repeated values
+        let series = (0..1000)
+            .map(|i| ((i * 7919) % 101) as f64 - 50.0)
+            .collect::<Vec<_>>();
+
+        for n in [1, 2, 5, 10, 33] {
+            let mut f = F_MEDIAN_OVER.function_type.create_stateful_fun();
+            let expected = (0..series.len())
+                .map(|i| {
+                    if i + 1 >= n {
+                        Some(naive_median(&series[i + 1 - n..=i]))
+                    } else {
+                        None
+                    }
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(median_values(&mut f, &series, n as i64), expected, "{}", n);
+        }
+
+        let mut f = F_MEDIAN_OVER.function_type.create_stateful_fun();
+        assert_eq!(
+            median_values(&mut f, &[3.0, 1.0, 2.0, 10.0], 0),
+            vec![Some(3.0), Some(2.0), Some(2.0), Some(2.5)]
+        );
+    }
+
+    #[test]
+    fn test_median_over_save_state() {
+        let series = (0..200).map(|i| ((i * 31) % 17) as f64).collect::<Vec<_>>();
+
+        let mut f = F_MEDIAN_OVER.function_type.create_stateful_fun();
+        let expected = median_values(&mut f, &series, 10);
+
+        let mut f = F_MEDIAN_OVER.function_type.create_stateful_fun();
+        let mut values = median_values(&mut f, &series[..95], 10);
+        let state = f.save_state().unwrap();
+        let mut f = F_MEDIAN_OVER.function_type.create_stateful_fun();
+        f.load_state(state).unwrap();
+        values.extend(median_values(&mut f, &series[95..], 10));
+        assert_eq!(values, expected);
+    }
+
+    #[test]
+    fn test_negative_window_size() {
+        for func in &[
+            F_AVEDEV,
+            F_DEVSQ,
+            F_FORCAST,
+            F_SLOPE,
+            F_STD,
+            F_STDDEV,
+            F_STDP,
+            F_VAR,
+            F_VARP,
+            F_MEDIAN_OVER,
+        ] {
+            let mut f = func.function_type.create_stateful_fun();
+            assert!(f
+                .call(&[
+                    Arc::new(Float64Array::from_vec(vec![1.0, 2.0, 3.0])),
+                    Arc::new(Int64Array::new_scalar(3, Some(-1))),
+                ])
+                .is_err());
+        }
+
+        let mut f = F_CORR.function_type.create_stateful_fun();
+        assert!(f
+            .call(&[
+                Arc::new(Float64Array::from_vec(vec![1.0, 2.0, 3.0])),
+                Arc::new(Float64Array::from_vec(vec![1.0, 2.0, 3.0])),
+                Arc::new(Int64Array::new_scalar(3, Some(-1))),
+            ])
+            .is_err());
+    }
+}
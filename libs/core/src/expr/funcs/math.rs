@@ -2,53 +2,108 @@ use std::sync::Arc;
 
 use crate::array::{
     Array, ArrayExt, DataType, Float32Array, Float32Builder, Float64Array, Float64Builder,
+    Int16Type, Int32Type, Int64Array, Int64Type, Int8Type, PrimitiveArray, PrimitiveBuilder,
+    PrimitiveType,
 };
 use crate::expr::func::{Function, FunctionType};
 use crate::expr::signature::Signature;
 
+macro_rules! float_math_array {
+    ($array:expr, $array_ty:ty, $builder_ty:ty, $func:ident) => {{
+        let array = $array.downcast_ref::<$array_ty>();
+        if let Some(scalar) = array.to_scalar() {
+            return Ok(Arc::new(<$array_ty>::new_scalar(
+                array.len(),
+                scalar.map(|x| x.$func()),
+            )));
+        }
+        let mut builder = <$builder_ty>::with_capacity(array.len());
+        for value in array.iter_opt() {
+            match value {
+                Some(value) => builder.append(value.$func()),
+                None => builder.append_null(),
+            }
+        }
+        Ok(Arc::new(builder.finish()))
+    }};
+}
+
+macro_rules! integer_math_array {
+    ($array:expr, $ty:ty, $x:ident, $func:expr) => {{
+        let f = |$x: <$ty as PrimitiveType>::Native| {
+            $func.ok_or_else(|| anyhow::anyhow!("arithmetic overflowed"))
+        };
+        let array = $array.downcast_ref::<PrimitiveArray<$ty>>();
+        if let Some(scalar) = array.to_scalar() {
+            return Ok(Arc::new(PrimitiveArray::<$ty>::new_scalar(
+                array.len(),
+                scalar.map(f).transpose()?,
+            )));
+        }
+        let mut builder = PrimitiveBuilder::<$ty>::with_capacity(array.len());
+        for value in array.iter_opt() {
+            builder.append_opt(value.map(f).transpose()?);
+        }
+        Ok(Arc::new(builder.finish()))
+    }};
+}
+
 macro_rules! make_math_func {
     ($ident:ident, $name:literal, $func:ident) => {
         pub const $ident: Function = Function {
             namespace: None,
             name: $name,
+            params: &[],
             signature: &Signature::Uniform(1, &[DataType::Float64, DataType::Float32]),
-            return_type: |args| args[0],
+            return_type: |args| args[0].clone(),
             function_type: FunctionType::Stateless(|args| {
                 let array = &args[0];
                 match array.data_type() {
                     DataType::Float32 => {
-                        let array = array.downcast_ref::<Float32Array>();
-                        if let Some(scalar) = array.to_scalar() {
-                            return Ok(Arc::new(Float32Array::new_scalar(
-                                array.len(),
-                                scalar.map(|x| x.$func()),
-                            )));
-                        }
-                        let mut builder = Float32Builder::with_capacity(array.len());
-                        for value in array.iter_opt() {
-                            match value {
-                                Some(value) => builder.append(value.$func()),
-                                None => builder.append_null(),
-                            }
-                        }
-                        Ok(Arc::new(builder.finish()))
+                        float_math_array!(array, Float32Array, Float32Builder, $func)
                     }
                     DataType::Float64 => {
-                        let array = array.downcast_ref::<Float64Array>();
-                        if let Some(scalar) = array.to_scalar() {
-                            return Ok(Arc::new(Float64Array::new_scalar(
-                                array.len(),
-                                scalar.map(|x| x.$func()),
-                            )));
-                        }
-                        let mut builder = Float64Builder::with_capacity(array.len());
-                        for value in array.iter_opt() {
-                            match value {
-                                Some(value) => builder.append(value.$func()),
-                                None => builder.append_null(),
-                            }
-                        }
-                        Ok(Arc::new(builder.finish()))
+                        float_math_array!(array, Float64Array, Float64Builder, $func)
+                    }
+                    _ => unreachable!(),
+                }
+            }),
+        };
+    };
+}
+
+/// Like `make_math_func`, but integers keep their type instead of being coerced to `Float64`.
+macro_rules! make_integer_math_func {
+    ($ident:ident, $name:literal, $func:ident, $x:ident => $integer_func:expr) => {
+        pub const $ident: Function = Function {
+            namespace: None,
+            name: $name,
+            params: &[],
+            signature: &Signature::OneOf(&[
+                Signature::Uniform(1, &[DataType::Float64, DataType::Float32]),
+                Signature::Uniform(
+                    1,
+                    &[
+                        DataType::Int8,
+                        DataType::Int16,
+                        DataType::Int32,
+                        DataType::Int64,
+                    ],
+                ),
+            ]),
+            return_type: |args| args[0].clone(),
+            function_type: FunctionType::Stateless(|args| {
+                let array = &args[0];
+                match array.data_type() {
+                    DataType::Int8 => integer_math_array!(array, Int8Type, $x, $integer_func),
+                    DataType::Int16 => integer_math_array!(array, Int16Type, $x, $integer_func),
+                    DataType::Int32 => integer_math_array!(array, Int32Type, $x, $integer_func),
+                    DataType::Int64 => integer_math_array!(array, Int64Type, $x, $integer_func),
+                    DataType::Float32 => {
+                        float_math_array!(array, Float32Array, Float32Builder, $func)
+                    }
+                    DataType::Float64 => {
+                        float_math_array!(array, Float64Array, Float64Builder, $func)
                     }
                     _ => unreachable!(),
                 }
@@ -66,11 +121,207 @@ make_math_func!(ACOS, "acos", acos);
 make_math_func!(ATAN, "atan", atan);
 make_math_func!(FLOOR, "floor", floor);
 make_math_func!(CEIL, "ceil", ceil);
-make_math_func!(ROUND, "round", round);
 make_math_func!(TRUNC, "trunc", trunc);
-make_math_func!(ABS, "abs", abs);
-make_math_func!(SIGNUM, "signum", signum);
+make_integer_math_func!(ABS, "abs", abs, x => x.checked_abs());
+make_integer_math_func!(SIGNUM, "signum", signum, x => Some(x.signum()));
 make_math_func!(EXP, "exp", exp);
 make_math_func!(LN, "ln", ln);
 make_math_func!(LOG2, "log2", log2);
 make_math_func!(LOG10, "log10", log10);
+
+macro_rules! round_digits_array {
+    ($array:expr, $digits:expr, $array_ty:ty, $builder_ty:ty, $native_ty:ty) => {{
+        let array = $array.downcast_ref::<$array_ty>();
+        let digits = $digits.downcast_ref::<Int64Array>();
+        let mut builder = <$builder_ty>::with_capacity(array.len());
+        for (value, digits) in array.iter_opt().zip(digits.iter_opt()) {
+            match (value, digits) {
+                (Some(value), Some(digits)) => {
+                    builder.append(round_digits(value as f64, digits) as $native_ty)
+                }
+                _ => builder.append_null(),
+            }
+        }
+        Ok(Arc::new(builder.finish()))
+    }};
+}
+
+/// Rounds to the given number of decimal places, a negative number rounds to the left of the
+/// decimal point.
+fn round_digits(value: f64, digits: i64) -> f64 {
+    // 10^308 is the largest power of ten that fits in a f64
+    let digits = digits.clamp(-308, 308) as i32;
+    if digits >= 0 {
+        let factor = 10f64.powi(digits);
+        let res = (value * factor).round() / factor;
+        if res.is_finite() {
+            res
+        } else {
+            value
+        }
+    } else {
+        let factor = 10f64.powi(-digits);
+        (value / factor).round() * factor
+    }
+}
+
+pub const ROUND: Function = Function {
+    namespace: None,
+    name: "round",
+    params: &["value", "digits"],
+    signature: &Signature::OneOf(&[
+        Signature::Uniform(1, &[DataType::Float64, DataType::Float32]),
+        Signature::Exact(&[DataType::Float64, DataType::Int64]),
+        Signature::Exact(&[DataType::Float32, DataType::Int64]),
+    ]),
+    return_type: |args| args[0].clone(),
+    function_type: FunctionType::Stateless(|args| {
+        let array = &args[0];
+        match (array.data_type(), args.get(1)) {
+            (DataType::Float32, None) => {
+                float_math_array!(array, Float32Array, Float32Builder, round)
+            }
+            (DataType::Float64, None) => {
+                float_math_array!(array, Float64Array, Float64Builder, round)
+            }
+            (DataType::Float32, Some(digits)) => {
+                round_digits_array!(array, digits, Float32Array, Float32Builder, f32)
+            }
+            (DataType::Float64, Some(digits)) => {
+                round_digits_array!(array, digits, Float64Array, Float64Builder, f64)
+            }
+            _ => unreachable!(),
+        }
+    }),
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::array::{ArrayRef, Float64Array, Int32Array, Int64Array};
+    use crate::dataset::{DataSet, Field, Schema};
+    use crate::dsl::*;
+
+    #[test]
+    fn test_abs_signum_integer() {
+        assert_eq!(
+            ABS.signature.data_types(&[DataType::Int64]).unwrap(),
+            vec![DataType::Int64]
+        );
+        assert_eq!((ABS.return_type)(&[DataType::Int64]), DataType::Int64);
+        assert_eq!(
+            SIGNUM.signature.data_types(&[DataType::Int8]).unwrap(),
+            vec![DataType::Int8]
+        );
+
+        let array: ArrayRef = Arc::new(Int64Array::from_opt_vec(vec![Some(-5), None, Some(3)]));
+        let res = ABS
+            .function_type
+            .call_stateless_fun(std::slice::from_ref(&array))
+            .unwrap();
+        assert_eq!(res.data_type(), DataType::Int64);
+        assert_eq!(
+            res.downcast_ref::<Int64Array>(),
+            &Int64Array::from_opt_vec(vec![Some(5), None, Some(3)])
+        );
+
+        let res = SIGNUM.function_type.call_stateless_fun(&[array]).unwrap();
+        assert_eq!(
+            res.downcast_ref::<Int64Array>(),
+            &Int64Array::from_opt_vec(vec![Some(-1), None, Some(1)])
+        );
+
+        let res = ABS
+            .function_type
+            .call_stateless_fun(&[Arc::new(Int32Array::new_scalar(2, Some(-5)))])
+            .unwrap();
+        assert_eq!(res.downcast_ref::<Int32Array>().to_scalar(), Some(Some(5)));
+
+        assert!(ABS
+            .function_type
+            .call_stateless_fun(&[Arc::new(Int64Array::from_vec(vec![i64::MIN]))])
+            .is_err());
+    }
+
+    #[test]
+    fn test_abs_signum_float() {
+        assert_eq!(
+            ABS.signature.data_types(&[DataType::Float32]).unwrap(),
+            vec![DataType::Float32]
+        );
+
+        let array: ArrayRef = Arc::new(Float64Array::from_vec(vec![-1.5, 2.0]));
+        let res = ABS
+            .function_type
+            .call_stateless_fun(std::slice::from_ref(&array))
+            .unwrap();
+        assert_eq!(
+            res.downcast_ref::<Float64Array>(),
+            &Float64Array::from_vec(vec![1.5, 2.0])
+        );
+
+        let res = SIGNUM.function_type.call_stateless_fun(&[array]).unwrap();
+        assert_eq!(
+            res.downcast_ref::<Float64Array>(),
+            &Float64Array::from_vec(vec![-1.0, 1.0])
+        );
+    }
+
+    #[test]
+    fn test_round_digits() {
+        let schema = Arc::new(
+            Schema::try_new(vec![
+                Field::new("a", DataType::Float64),
+                Field::new("b", DataType::Int64),
+            ])
+            .unwrap(),
+        );
+        let dataset = DataSet::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Float64Array::from_opt_vec(vec![
+                    Some(12.34567),
+                    None,
+                    Some(2.5),
+                ])),
+                Arc::new(Int64Array::from_opt_vec(vec![Some(1234), Some(1), None])),
+            ],
+        )
+        .unwrap();
+
+        let mut expr = call("round", vec![col("a"), value(2)])
+            .into_physical(schema.clone())
+            .unwrap();
+        assert_eq!(expr.data_type(), DataType::Float64);
+        assert_eq!(
+            expr.eval(&dataset).unwrap().downcast_ref::<Float64Array>(),
+            &Float64Array::from_opt_vec(vec![Some(12.35), None, Some(2.5)])
+        );
+
+        let mut expr = call("round", vec![col("b"), value(-2)])
+            .into_physical(schema.clone())
+            .unwrap();
+        assert_eq!(
+            expr.eval(&dataset).unwrap().downcast_ref::<Float64Array>(),
+            &Float64Array::from_opt_vec(vec![Some(1200.0), Some(0.0), None])
+        );
+
+        let mut expr = call("round", vec![col("a"), col("b")])
+            .into_physical(schema.clone())
+            .unwrap();
+        assert_eq!(
+            expr.eval(&dataset).unwrap().downcast_ref::<Float64Array>(),
+            &Float64Array::from_opt_vec(vec![Some(12.34567), None, None])
+        );
+
+        let mut expr = call("round", vec![col("a")]).into_physical(schema).unwrap();
+        assert_eq!(
+            expr.eval(&dataset).unwrap().downcast_ref::<Float64Array>(),
+            &Float64Array::from_opt_vec(vec![Some(12.0), None, Some(3.0)])
+        );
+
+        assert_eq!(round_digits(1.5, 400), 1.5);
+        assert_eq!(round_digits(1.5e300, 100), 1.5e300);
+        assert_eq!(round_digits(1.5, -400), 0.0);
+    }
+}
@@ -1,4 +1,6 @@
 mod aggregate;
+mod compare;
+mod datetime;
 mod f_logic;
 mod f_ref;
 mod f_stat;
@@ -6,9 +8,12 @@ mod math;
 mod nulls;
 mod string;
 mod time;
+mod types;
 mod utils;
 
 use aggregate::*;
+use compare::*;
+use datetime::*;
 use f_logic::*;
 use f_ref::*;
 use f_stat::*;
@@ -16,6 +21,11 @@ use math::*;
 use nulls::*;
 use string::*;
 use time::*;
+use types::*;
+
+pub(crate) use aggregate::HyperLogLog;
+pub(crate) use datetime::is_timezone_of_argument_func;
+pub(crate) use types::is_typeof_func;
 
 use crate::expr::func::Function;
 
@@ -25,16 +35,25 @@ const FUNCS: &[Function] = &[
     SQRT, SIN, COS, TAN, ASIN, ACOS, ATAN, FLOOR, CEIL, ROUND, TRUNC, ABS, SIGNUM, EXP, LN, LOG2, LOG10,
     
     // aggregate
-    AVG, SUM, COUNT, MIN, MAX, FIRST, LAST,
+    AVG, SUM, COUNT, MIN, MAX, FIRST, LAST, FIRST_BY, LAST_BY, CORR, COLLECT_LIST,
+    APPROX_PERCENTILE, APPROX_COUNT_DISTINCT,
     
     // string
     CHR, CONCAT, CONCAT_WS, ENCODE, INSTR, LCASE, LEN, LPAD, REPLACE, RPAD, SUBSTRING, TRIM, UCASE,
+    REGEXP_MATCH, REGEXP_REPLACE, SPLIT_PART, STRING_TO_ARRAY,
     
     // nulls
-    COALESCE, IFNULL,
+    COALESCE, IFNULL, NULLIF,
+
+    // compare
+    GREATEST, LEAST,
 
     // time
     PARSE_TIMESTAMP, FORMAT_TIMESTAMP, TIMESTAMP_ADD, TIMESTAMP_SUB,
+    DATE_TRUNC, EXTRACT, YEAR, MONTH, DAY, HOUR, MINUTE, SECOND,
+
+    // types
+    TYPEOF,
     
     // f.ref
     F_ALL, F_ANY, F_BARSLAST, F_BARSSINCE, F_COUNT, F_DMA, F_EMA, F_FILTER, F_HHV, F_LLV, 
@@ -44,9 +63,18 @@ const FUNCS: &[Function] = &[
     F_BETWEEN, F_CROSS, F_LONGCROSS,
     
     // f.stat
-    F_AVEDEV, F_DEVSQ, F_FORCAST, F_SLOPE, F_STD, F_STDDEV, F_STDP, F_VAR, F_VARP,
+    F_AVEDEV, F_CORR, F_DEVSQ, F_FORCAST, F_SLOPE, F_STD, F_STDDEV, F_STDP, F_VAR, F_VARP,
+    F_MEDIAN_OVER,
 ];
 
+/// Returns the names of all the functions, qualified by the namespaces.
+pub fn function_names() -> impl Iterator<Item = String> {
+    FUNCS.iter().map(|func| match func.namespace {
+        Some(namespace) => format!("{}.{}", namespace, func.name),
+        None => func.name.to_string(),
+    })
+}
+
 pub fn find_function(namespace: Option<&str>, name: &str) -> Option<&'static Function> {
     FUNCS.iter().find(|func| match namespace {
         Some(namespace) => match func.namespace {
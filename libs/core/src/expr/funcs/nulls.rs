@@ -1,12 +1,16 @@
 use std::sync::Arc;
 
+use anyhow::Result;
+
 use crate::array::{
-    ArrayExt, BooleanType, DataType, Float32Type, Float64Type, Int16Type, Int32Type, Int64Type,
-    Int8Type, NullArray, PrimitiveArray, PrimitiveBuilder, StringArray, StringBuilder,
-    TimestampType,
+    compute, Array, ArrayExt, ArrayRef, BooleanArray, BooleanBuilder, BooleanType, DataType,
+    Decimal128Array, Decimal128Builder, Float32Type, Float64Array, Float64Type, Int16Type,
+    Int32Type, Int64Type, Int8Type, NullArray, PrimitiveArray, PrimitiveBuilder, StringArray,
+    StringBuilder, TimestampType, UInt16Type, UInt32Type, UInt64Type, UInt8Type,
 };
 use crate::expr::func::{Function, FunctionType};
 use crate::expr::signature::Signature;
+use crate::expr::BinaryOperator;
 
 macro_rules! coalesce {
     ($args:expr, $ty:ty) => {{
@@ -31,14 +35,19 @@ macro_rules! coalesce {
 pub const COALESCE: Function = Function {
     namespace: None,
     name: "coalesce",
+    params: &[],
     signature: &Signature::VariadicEqual,
-    return_type: |args| args[0],
+    return_type: |args| args[0].clone(),
     function_type: FunctionType::Stateless(|args| match args[0].data_type() {
         DataType::Null => Ok(Arc::new(NullArray::new(args[0].len()))),
         DataType::Int8 => coalesce!(args, Int8Type),
         DataType::Int16 => coalesce!(args, Int16Type),
         DataType::Int32 => coalesce!(args, Int32Type),
         DataType::Int64 => coalesce!(args, Int64Type),
+        DataType::UInt8 => coalesce!(args, UInt8Type),
+        DataType::UInt16 => coalesce!(args, UInt16Type),
+        DataType::UInt32 => coalesce!(args, UInt32Type),
+        DataType::UInt64 => coalesce!(args, UInt64Type),
         DataType::Float32 => coalesce!(args, Float32Type),
         DataType::Float64 => coalesce!(args, Float64Type),
         DataType::Boolean => coalesce!(args, BooleanType),
@@ -60,6 +69,9 @@ pub const COALESCE: Function = Function {
 
             Ok(Arc::new(builder.finish()))
         }
+        DataType::Decimal128 { .. } | DataType::List(_) | DataType::Struct(_) => {
+            anyhow::bail!("unsupported datatype: {}", args[0].data_type())
+        }
     }),
 };
 
@@ -80,25 +92,34 @@ macro_rules! ifnull {
 pub const IFNULL: Function = Function {
     namespace: None,
     name: "ifnull",
+    params: &[],
     signature: &Signature::OneOf(&[
         Signature::Exact(&[DataType::Null, DataType::Null]),
         Signature::Exact(&[DataType::Int8, DataType::Int8]),
         Signature::Exact(&[DataType::Int16, DataType::Int16]),
         Signature::Exact(&[DataType::Int32, DataType::Int32]),
         Signature::Exact(&[DataType::Int64, DataType::Int64]),
+        Signature::Exact(&[DataType::UInt8, DataType::UInt8]),
+        Signature::Exact(&[DataType::UInt16, DataType::UInt16]),
+        Signature::Exact(&[DataType::UInt32, DataType::UInt32]),
+        Signature::Exact(&[DataType::UInt64, DataType::UInt64]),
         Signature::Exact(&[DataType::Float32, DataType::Float32]),
         Signature::Exact(&[DataType::Float64, DataType::Float64]),
         Signature::Exact(&[DataType::Boolean, DataType::Boolean]),
         Signature::Exact(&[DataType::Timestamp(None), DataType::Timestamp(None)]),
         Signature::Exact(&[DataType::String, DataType::String]),
     ]),
-    return_type: |args| args[0],
+    return_type: |args| args[0].clone(),
     function_type: FunctionType::Stateless(|args| match args[0].data_type() {
         DataType::Null => Ok(Arc::new(NullArray::new(args[0].len()))),
         DataType::Int8 => ifnull!(args, Int8Type),
         DataType::Int16 => ifnull!(args, Int16Type),
         DataType::Int32 => ifnull!(args, Int32Type),
         DataType::Int64 => ifnull!(args, Int64Type),
+        DataType::UInt8 => ifnull!(args, UInt8Type),
+        DataType::UInt16 => ifnull!(args, UInt16Type),
+        DataType::UInt32 => ifnull!(args, UInt32Type),
+        DataType::UInt64 => ifnull!(args, UInt64Type),
         DataType::Float32 => ifnull!(args, Float32Type),
         DataType::Float64 => ifnull!(args, Float64Type),
         DataType::Boolean => ifnull!(args, BooleanType),
@@ -114,13 +135,119 @@ pub const IFNULL: Function = Function {
             }
             Ok(Arc::new(builder.finish()))
         }
+        DataType::Decimal128 { .. } | DataType::List(_) | DataType::Struct(_) => unreachable!(),
+    }),
+};
+
+/// Compares the values of the two arrays like the `=` operator, which also unifies the types of
+/// them, except that the floats can be compared too.
+fn equal_array(a: &ArrayRef, b: &ArrayRef) -> Result<ArrayRef> {
+    let (a_type, b_type) = (a.data_type(), b.data_type());
+    if !(a_type.is_numeric() && b_type.is_numeric() && (a_type.is_float() || b_type.is_float())) {
+        return BinaryOperator::Eq.eval_array(&**a, &**b);
+    }
+
+    let a = compute::cast(a.clone(), DataType::Float64)?;
+    let b = compute::cast(b.clone(), DataType::Float64)?;
+    let (a, b) = (
+        a.downcast_ref::<Float64Array>(),
+        b.downcast_ref::<Float64Array>(),
+    );
+    if let (Some(a_scalar), Some(b_scalar)) = (a.to_scalar(), b.to_scalar()) {
+        let equal = a_scalar.zip(b_scalar).map(|(a, b)| a == b);
+        return Ok(Arc::new(BooleanArray::new_scalar(a.len(), equal)));
+    }
+    let mut builder = BooleanBuilder::with_capacity(a.len());
+    for (a, b) in a.iter_opt().zip(b.iter_opt()) {
+        builder.append_opt(a.zip(b).map(|(a, b)| a == b));
+    }
+    Ok(Arc::new(builder.finish()))
+}
+
+macro_rules! nullif {
+    ($array:expr, $equal:expr, $ty:ty) => {{
+        let array = $array.downcast_ref::<PrimitiveArray<$ty>>();
+        let mut builder = PrimitiveBuilder::<$ty>::with_capacity(array.len());
+        for (value, equal) in array.iter_opt().zip($equal.iter_opt()) {
+            if equal == Some(true) {
+                builder.append_null();
+            } else {
+                builder.append_opt(value);
+            }
+        }
+        Ok(Arc::new(builder.finish()))
+    }};
+}
+
+pub const NULLIF: Function = Function {
+    namespace: None,
+    name: "nullif",
+    params: &[],
+    signature: &Signature::Any(2),
+    return_type: |args| args[0].clone(),
+    function_type: FunctionType::Stateless(|args| {
+        let equal = equal_array(&args[0], &args[1])?;
+        let equal = equal.downcast_ref::<BooleanArray>();
+        match equal.to_scalar() {
+            Some(Some(true)) => {
+                return Ok(compute::new_null_array(&args[0].data_type(), args[0].len())
+                    .unwrap_or_else(|| args[0].clone()))
+            }
+            Some(_) => return Ok(args[0].clone()),
+            None => {}
+        }
+
+        match args[0].data_type() {
+            DataType::Null => Ok(args[0].clone()),
+            DataType::Int8 => nullif!(args[0], equal, Int8Type),
+            DataType::Int16 => nullif!(args[0], equal, Int16Type),
+            DataType::Int32 => nullif!(args[0], equal, Int32Type),
+            DataType::Int64 => nullif!(args[0], equal, Int64Type),
+            DataType::UInt8 => nullif!(args[0], equal, UInt8Type),
+            DataType::UInt16 => nullif!(args[0], equal, UInt16Type),
+            DataType::UInt32 => nullif!(args[0], equal, UInt32Type),
+            DataType::UInt64 => nullif!(args[0], equal, UInt64Type),
+            DataType::Float32 => nullif!(args[0], equal, Float32Type),
+            DataType::Float64 => nullif!(args[0], equal, Float64Type),
+            DataType::Boolean => nullif!(args[0], equal, BooleanType),
+            DataType::Timestamp(_) => nullif!(args[0], equal, TimestampType),
+            DataType::Decimal128 { precision, scale } => {
+                let array = args[0].downcast_ref::<Decimal128Array>();
+                let mut builder = Decimal128Builder::with_capacity(precision, scale, array.len());
+                for (index, equal) in equal.iter_opt().enumerate() {
+                    if equal == Some(true) {
+                        builder.append_null();
+                    } else {
+                        builder.append_opt(array.value_opt(index));
+                    }
+                }
+                Ok(Arc::new(builder.finish()))
+            }
+            DataType::String => {
+                let array = args[0].downcast_ref::<StringArray>();
+                let mut builder = StringBuilder::with_capacity(array.len());
+                for (value, equal) in array.iter_opt().zip(equal.iter_opt()) {
+                    if equal == Some(true) {
+                        builder.append_null();
+                    } else {
+                        builder.append_opt(value);
+                    }
+                }
+                Ok(Arc::new(builder.finish()))
+            }
+            DataType::List(_) | DataType::Struct(_) => {
+                anyhow::bail!("unsupported datatype: {}", args[0].data_type())
+            }
+        }
     }),
 };
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::array::{Array, Int64Array};
+    use crate::array::{Int32Array, Int64Array};
+    use crate::dataset::{DataSet, Field, Schema};
+    use crate::dsl::*;
 
     #[test]
     fn test_coalesce() {
@@ -223,4 +350,83 @@ mod tests {
             &StringArray::new_scalar(1, Some("b")) as &dyn Array
         );
     }
+
+    #[test]
+    fn test_nullif() {
+        let array: ArrayRef = Arc::new(Int32Array::from_opt_vec(vec![Some(1), Some(0), None]));
+        assert_eq!(
+            &*NULLIF
+                .function_type
+                .call_stateless_fun(&[array.clone(), Arc::new(Int64Array::new_scalar(3, Some(0)))])
+                .unwrap(),
+            &Int32Array::from_opt_vec(vec![Some(1), None, None]) as &dyn Array
+        );
+        assert_eq!(
+            &*NULLIF
+                .function_type
+                .call_stateless_fun(&[
+                    array.clone(),
+                    Arc::new(Int64Array::from_opt_vec(vec![Some(1), None, Some(2)]))
+                ])
+                .unwrap(),
+            &Int32Array::from_opt_vec(vec![None, Some(0), None]) as &dyn Array
+        );
+
+        // the scalars
+        assert_eq!(
+            &*NULLIF
+                .function_type
+                .call_stateless_fun(&[
+                    Arc::new(StringArray::new_scalar(2, Some("a"))),
+                    Arc::new(StringArray::new_scalar(2, Some("a"))),
+                ])
+                .unwrap(),
+            &StringArray::new_scalar(2, None::<&str>) as &dyn Array
+        );
+        assert_eq!(
+            &*NULLIF
+                .function_type
+                .call_stateless_fun(&[
+                    Arc::new(StringArray::new_scalar(2, Some("a"))),
+                    Arc::new(StringArray::new_scalar(2, None::<&str>)),
+                ])
+                .unwrap(),
+            &StringArray::new_scalar(2, Some("a")) as &dyn Array
+        );
+
+        assert!(NULLIF
+            .function_type
+            .call_stateless_fun(&[
+                Arc::new(StringArray::new_scalar(1, Some("a"))),
+                Arc::new(Int64Array::new_scalar(1, Some(1))),
+            ])
+            .is_err());
+    }
+
+    #[test]
+    fn test_nullif_divide() {
+        let schema = Arc::new(
+            Schema::try_new(vec![
+                Field::new("num", DataType::Float64),
+                Field::new("denom", DataType::Float64),
+            ])
+            .unwrap(),
+        );
+        let dataset = DataSet::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Float64Array::from_vec(vec![1.0, 2.0, 3.0])),
+                Arc::new(Float64Array::from_vec(vec![2.0, 0.0, 0.5])),
+            ],
+        )
+        .unwrap();
+
+        let mut expr = (col("num") / call("nullif", vec![col("denom"), value(0)]))
+            .into_physical(schema)
+            .unwrap();
+        assert_eq!(
+            expr.eval(&dataset).unwrap().downcast_ref::<Float64Array>(),
+            &Float64Array::from_opt_vec(vec![Some(0.5), None, Some(6.0)])
+        );
+    }
 }
@@ -2,10 +2,13 @@ use std::borrow::Cow;
 use std::fmt::Write;
 use std::sync::Arc;
 
+use anyhow::Result;
 use itertools::Either;
+use regex::Regex;
 
 use crate::array::{
-    Array, ArrayExt, DataType, Int64Array, Int64Builder, StringArray, StringBuilder,
+    Array, ArrayExt, BooleanBuilder, DataType, Int64Array, Int64Builder, ListArray, StringArray,
+    StringBuilder,
 };
 use crate::expr::func::{Function, FunctionType};
 use crate::expr::signature::Signature;
@@ -13,6 +16,7 @@ use crate::expr::signature::Signature;
 pub const CHR: Function = Function {
     namespace: None,
     name: "chr",
+    params: &[],
     signature: &Signature::Exact(&[DataType::Int64]),
     return_type: |_| DataType::String,
     function_type: FunctionType::Stateless(|args| {
@@ -42,6 +46,7 @@ pub const CHR: Function = Function {
 pub const CONCAT: Function = Function {
     namespace: None,
     name: "concat",
+    params: &[],
     signature: &Signature::Variadic(&[DataType::String]),
     return_type: |_| DataType::String,
     function_type: FunctionType::Stateless(|args| {
@@ -74,6 +79,7 @@ pub const CONCAT: Function = Function {
 pub const CONCAT_WS: Function = Function {
     namespace: None,
     name: "concat_ws",
+    params: &[],
     signature: &Signature::Variadic(&[DataType::String]),
     return_type: |_| DataType::String,
     function_type: FunctionType::Stateless(|args| {
@@ -110,6 +116,7 @@ pub const CONCAT_WS: Function = Function {
 pub const ENCODE: Function = Function {
     namespace: None,
     name: "encode",
+    params: &["value", "from", "to"],
     signature: &Signature::Exact(&[DataType::String, DataType::String, DataType::String]),
     return_type: |_| DataType::String,
     function_type: FunctionType::Stateless(|args| {
@@ -157,6 +164,7 @@ pub const ENCODE: Function = Function {
 pub const INSTR: Function = Function {
     namespace: None,
     name: "instr",
+    params: &["value", "substring", "position", "occurrence"],
     signature: &Signature::OneOf(&[
         Signature::Exact(&[DataType::String, DataType::String]),
         Signature::Exact(&[DataType::String, DataType::String, DataType::Int64]),
@@ -273,6 +281,7 @@ pub const INSTR: Function = Function {
 pub const LCASE: Function = Function {
     namespace: None,
     name: "lcase",
+    params: &[],
     signature: &Signature::Exact(&[DataType::String]),
     return_type: |_| DataType::String,
     function_type: FunctionType::Stateless(|args| {
@@ -294,6 +303,7 @@ pub const LCASE: Function = Function {
 pub const LEN: Function = Function {
     namespace: None,
     name: "len",
+    params: &[],
     signature: &Signature::Exact(&[DataType::String]),
     return_type: |_| DataType::Int64,
     function_type: FunctionType::Stateless(|args| {
@@ -315,6 +325,7 @@ pub const LEN: Function = Function {
 pub const LPAD: Function = Function {
     namespace: None,
     name: "lpad",
+    params: &["value", "length", "padding"],
     signature: &Signature::Exact(&[DataType::String, DataType::Int64, DataType::String]),
     return_type: |_| DataType::String,
     function_type: FunctionType::Stateless(|args| {
@@ -355,9 +366,90 @@ pub const LPAD: Function = Function {
     }),
 };
 
+/// The patterns of a regular expression function, a scalar pattern is compiled only once.
+enum RegexPatterns<'a> {
+    Scalar(Option<Regex>),
+    Array(&'a StringArray),
+}
+
+impl<'a> RegexPatterns<'a> {
+    fn new(array: &'a StringArray) -> Result<Self> {
+        Ok(match array.to_scalar() {
+            Some(pattern) => RegexPatterns::Scalar(pattern.map(compile_regex).transpose()?),
+            None => RegexPatterns::Array(array),
+        })
+    }
+
+    fn get(&self, index: usize) -> Result<Option<Cow<'_, Regex>>> {
+        Ok(match self {
+            RegexPatterns::Scalar(regex) => regex.as_ref().map(Cow::Borrowed),
+            RegexPatterns::Array(array) => array
+                .value_opt(index)
+                .map(compile_regex)
+                .transpose()?
+                .map(Cow::Owned),
+        })
+    }
+}
+
+fn compile_regex(pattern: &str) -> Result<Regex> {
+    Regex::new(pattern)
+        .map_err(|err| anyhow::anyhow!("invalid regular expression '{}': {}", pattern, err))
+}
+
+pub const REGEXP_MATCH: Function = Function {
+    namespace: None,
+    name: "regexp_match",
+    params: &["value", "pattern"],
+    signature: &Signature::Exact(&[DataType::String, DataType::String]),
+    return_type: |_| DataType::Boolean,
+    function_type: FunctionType::Stateless(|args| {
+        let array = args[0].downcast_ref::<StringArray>();
+        let patterns = RegexPatterns::new(args[1].downcast_ref::<StringArray>())?;
+        let mut builder = BooleanBuilder::with_capacity(array.len());
+
+        for (index, value) in array.iter_opt().enumerate() {
+            match (value, patterns.get(index)?) {
+                (Some(value), Some(regex)) => builder.append(regex.is_match(value)),
+                _ => builder.append_null(),
+            }
+        }
+
+        Ok(Arc::new(builder.finish()))
+    }),
+};
+
+pub const REGEXP_REPLACE: Function = Function {
+    namespace: None,
+    name: "regexp_replace",
+    params: &["value", "pattern", "replacement"],
+    signature: &Signature::Exact(&[DataType::String, DataType::String, DataType::String]),
+    return_type: |_| DataType::String,
+    function_type: FunctionType::Stateless(|args| {
+        let array = args[0].downcast_ref::<StringArray>();
+        let patterns = RegexPatterns::new(args[1].downcast_ref::<StringArray>())?;
+        let replacement = args[2].downcast_ref::<StringArray>();
+        let mut builder = StringBuilder::with_capacity(array.len());
+
+        for (index, (value, replacement)) in
+            array.iter_opt().zip(replacement.iter_opt()).enumerate()
+        {
+            match (value, patterns.get(index)?, replacement) {
+                (Some(value), Some(regex), Some(replacement)) => {
+                    builder.append(&regex.replace_all(value, replacement))
+                }
+                _ => builder.append_null(),
+            }
+        }
+
+        Ok(Arc::new(builder.finish()))
+    }),
+};
+
 pub const REPLACE: Function = Function {
     namespace: None,
     name: "replace",
+    params: &["value", "from", "to"],
     signature: &Signature::Exact(&[DataType::String, DataType::String, DataType::String]),
     return_type: |_| DataType::String,
     function_type: FunctionType::Stateless(|args| {
@@ -381,6 +473,7 @@ pub const REPLACE: Function = Function {
 pub const RPAD: Function = Function {
     namespace: None,
     name: "rpad",
+    params: &["value", "length", "padding"],
     signature: &Signature::Exact(&[DataType::String, DataType::Int64, DataType::String]),
     return_type: |_| DataType::String,
     function_type: FunctionType::Stateless(|args| {
@@ -421,9 +514,87 @@ pub const RPAD: Function = Function {
     }),
 };
 
+/// Splits the value into its fields, the value is a single field if the delimiter is empty.
+fn split_fields<'a>(value: &'a str, delimiter: &'a str) -> Vec<&'a str> {
+    if delimiter.is_empty() {
+        vec![value]
+    } else {
+        value.split(delimiter).collect()
+    }
+}
+
+pub const SPLIT_PART: Function = Function {
+    namespace: None,
+    name: "split_part",
+    params: &["value", "delimiter", "n"],
+    signature: &Signature::Exact(&[DataType::String, DataType::String, DataType::Int64]),
+    return_type: |_| DataType::String,
+    function_type: FunctionType::Stateless(|args| {
+        let array = args[0].downcast_ref::<StringArray>();
+        let delimiter = args[1].downcast_ref::<StringArray>();
+        let n = args[2].downcast_ref::<Int64Array>();
+        let mut builder = StringBuilder::with_capacity(array.len());
+
+        for ((value, delimiter), n) in array.iter_opt().zip(delimiter.iter_opt()).zip(n.iter_opt())
+        {
+            match (value, delimiter, n) {
+                (Some(value), Some(delimiter), Some(n)) => {
+                    let fields = split_fields(value, delimiter);
+                    // `n` is 1-based, and a negative `n` counts from the end
+                    let index = if n > 0 {
+                        Some(n as usize - 1)
+                    } else if n < 0 {
+                        n.checked_neg()
+                            .and_then(|n| fields.len().checked_sub(n as usize))
+                    } else {
+                        None
+                    };
+                    builder.append_opt(index.and_then(|index| fields.get(index).copied()));
+                }
+                _ => builder.append_null(),
+            }
+        }
+
+        Ok(Arc::new(builder.finish()))
+    }),
+};
+
+pub const STRING_TO_ARRAY: Function = Function {
+    namespace: None,
+    name: "string_to_array",
+    params: &["value", "delimiter"],
+    signature: &Signature::Exact(&[DataType::String, DataType::String]),
+    return_type: |_| DataType::List(Box::new(DataType::String)),
+    function_type: FunctionType::Stateless(|args| {
+        let array = args[0].downcast_ref::<StringArray>();
+        let delimiter = args[1].downcast_ref::<StringArray>();
+        let mut lengths = Vec::with_capacity(array.len());
+        let mut values = StringBuilder::default();
+
+        for (value, delimiter) in array.iter_opt().zip(delimiter.iter_opt()) {
+            match (value, delimiter) {
+                (Some(value), Some(delimiter)) => {
+                    let fields = split_fields(value, delimiter);
+                    lengths.push(Some(fields.len()));
+                    for field in fields {
+                        values.append(field);
+                    }
+                }
+                _ => lengths.push(None),
+            }
+        }
+
+        Ok(Arc::new(ListArray::from_lengths(
+            &lengths,
+            Arc::new(values.finish()),
+        )))
+    }),
+};
+
 pub const SUBSTRING: Function = Function {
     namespace: None,
     name: "substring",
+    params: &["value", "position", "length"],
     signature: &Signature::OneOf(&[
         Signature::Exact(&[DataType::String, DataType::Int64]),
         Signature::Exact(&[DataType::String, DataType::Int64, DataType::Int64]),
@@ -469,6 +640,7 @@ pub const SUBSTRING: Function = Function {
 pub const TRIM: Function = Function {
     namespace: None,
     name: "trim",
+    params: &[],
     signature: &Signature::Exact(&[DataType::String]),
     return_type: |_| DataType::String,
     function_type: FunctionType::Stateless(|args| {
@@ -490,6 +662,7 @@ pub const TRIM: Function = Function {
 pub const UCASE: Function = Function {
     namespace: None,
     name: "ucase",
+    params: &[],
     signature: &Signature::Exact(&[DataType::String]),
     return_type: |_| DataType::String,
     function_type: FunctionType::Stateless(|args| {
@@ -511,6 +684,7 @@ pub const UCASE: Function = Function {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::array::BooleanArray;
 
     #[test]
     fn test_chr() {
@@ -1037,6 +1211,95 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_regexp_match() {
+        assert_eq!(
+            &*REGEXP_MATCH
+                .function_type
+                .call_stateless_fun(&[
+                    Arc::new(StringArray::from_opt_vec(vec![
+                        Some("GET /index.html 200"),
+                        Some("GET /missing 404"),
+                        None,
+                    ])),
+                    Arc::new(StringArray::new_scalar(3, Some(r" 2\d{2}$"))),
+                ])
+                .unwrap(),
+            &BooleanArray::from_opt_vec(vec![Some(true), Some(false), None]) as &dyn Array
+        );
+
+        // a pattern per row
+        assert_eq!(
+            &*REGEXP_MATCH
+                .function_type
+                .call_stateless_fun(&[
+                    Arc::new(StringArray::from_vec(vec!["abc", "abc", "abc"])),
+                    Arc::new(StringArray::from_opt_vec(vec![
+                        Some("^a"),
+                        Some("^b"),
+                        None
+                    ])),
+                ])
+                .unwrap(),
+            &BooleanArray::from_opt_vec(vec![Some(true), Some(false), None]) as &dyn Array
+        );
+
+        assert_eq!(
+            REGEXP_MATCH
+                .function_type
+                .call_stateless_fun(&[
+                    Arc::new(StringArray::new_scalar(1, Some("abc"))),
+                    Arc::new(StringArray::new_scalar(1, Some("a("))),
+                ])
+                .unwrap_err()
+                .to_string()
+                .lines()
+                .next()
+                .unwrap(),
+            "invalid regular expression 'a(': regex parse error:"
+        );
+    }
+
+    #[test]
+    fn test_regexp_replace() {
+        assert_eq!(
+            &*REGEXP_REPLACE
+                .function_type
+                .call_stateless_fun(&[
+                    Arc::new(StringArray::from_opt_vec(vec![
+                        Some("127.0.0.1 GET /index.html 200 512"),
+                        Some("127.0.0.1 GET /missing 404 0"),
+                        None,
+                    ])),
+                    Arc::new(StringArray::new_scalar(3, Some(r".* (\d{3}) .*"))),
+                    Arc::new(StringArray::new_scalar(3, Some("$1"))),
+                ])
+                .unwrap(),
+            &StringArray::from_opt_vec(vec![Some("200"), Some("404"), None]) as &dyn Array
+        );
+
+        assert_eq!(
+            &*REGEXP_REPLACE
+                .function_type
+                .call_stateless_fun(&[
+                    Arc::new(StringArray::new_scalar(1, Some("a1b22c333"))),
+                    Arc::new(StringArray::new_scalar(1, Some(r"\d+"))),
+                    Arc::new(StringArray::new_scalar(1, Some("#"))),
+                ])
+                .unwrap(),
+            &StringArray::new_scalar(1, Some("a#b#c#")) as &dyn Array
+        );
+
+        assert!(REGEXP_REPLACE
+            .function_type
+            .call_stateless_fun(&[
+                Arc::new(StringArray::new_scalar(1, None::<&str>)),
+                Arc::new(StringArray::new_scalar(1, Some("["))),
+                Arc::new(StringArray::new_scalar(1, Some(""))),
+            ])
+            .is_err());
+    }
+
     #[test]
     fn test_rpad() {
         assert_eq!(
@@ -1100,6 +1363,82 @@ mod tests {
         );
     }
 
+    fn split_part(value: Option<&str>, delimiter: &str, n: i64) -> Option<String> {
+        let array = SPLIT_PART
+            .function_type
+            .call_stateless_fun(&[
+                Arc::new(StringArray::new_scalar(1, value)),
+                Arc::new(StringArray::new_scalar(1, Some(delimiter))),
+                Arc::new(Int64Array::new_scalar(1, Some(n))),
+            ])
+            .unwrap();
+        array
+            .downcast_ref::<StringArray>()
+            .value_opt(0)
+            .map(ToString::to_string)
+    }
+
+    #[test]
+    fn test_split_part() {
+        let path = Some("/var/log/yql/server.log");
+        assert_eq!(split_part(path, "/", -1).as_deref(), Some("server.log"));
+        assert_eq!(split_part(path, "/", 2).as_deref(), Some("var"));
+        assert_eq!(split_part(path, "/", -5).as_deref(), Some(""));
+        assert_eq!(split_part(path, "/", 6), None);
+        assert_eq!(split_part(path, "/", -6), None);
+        assert_eq!(split_part(path, "/", 0), None);
+
+        // empty fields
+        assert_eq!(split_part(Some("a,,c"), ",", 2).as_deref(), Some(""));
+        assert_eq!(split_part(Some("a,,c"), ",", 3).as_deref(), Some("c"));
+        assert_eq!(split_part(Some(""), ",", 1).as_deref(), Some(""));
+
+        // the delimiter is not present
+        assert_eq!(split_part(Some("abc"), ",", 1).as_deref(), Some("abc"));
+        assert_eq!(split_part(Some("abc"), ",", 2), None);
+        assert_eq!(split_part(Some("abc"), "", 1).as_deref(), Some("abc"));
+
+        // a delimiter of multiple characters
+        assert_eq!(split_part(Some("a::b::c"), "::", -2).as_deref(), Some("b"));
+
+        assert_eq!(split_part(None, ",", 1), None);
+    }
+
+    #[test]
+    fn test_string_to_array() {
+        let array = STRING_TO_ARRAY
+            .function_type
+            .call_stateless_fun(&[
+                Arc::new(StringArray::from_opt_vec(vec![
+                    Some("a,,c"),
+                    Some("abc"),
+                    None,
+                    Some(""),
+                ])),
+                Arc::new(StringArray::new_scalar(4, Some(","))),
+            ])
+            .unwrap();
+        assert_eq!(
+            array.data_type(),
+            DataType::List(Box::new(DataType::String))
+        );
+
+        let array = array.downcast_ref::<ListArray>();
+        assert_eq!(
+            array.value(0).downcast_ref::<StringArray>(),
+            &StringArray::from_vec(vec!["a", "", "c"])
+        );
+        assert_eq!(
+            array.value(1).downcast_ref::<StringArray>(),
+            &StringArray::from_vec(vec!["abc"])
+        );
+        assert!(array.is_null(2));
+        assert_eq!(
+            array.value(3).downcast_ref::<StringArray>(),
+            &StringArray::from_vec(vec![""])
+        );
+    }
+
     #[test]
     fn test_substring() {
         assert_eq!(
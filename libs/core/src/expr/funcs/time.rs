@@ -13,7 +13,7 @@ use crate::expr::func::{Function, FunctionType};
 use crate::expr::signature::Signature;
 
 #[allow(clippy::needless_lifetimes)]
-fn timezone_array<'a>(
+pub(super) fn timezone_array<'a>(
     array: Option<&'a ArrayRef>,
 ) -> impl Iterator<Item = Result<Tz, String>> + 'a {
     array
@@ -37,6 +37,7 @@ fn timezone_array<'a>(
 pub const PARSE_TIMESTAMP: Function = Function {
     namespace: None,
     name: "parse_timestamp",
+    params: &["value", "format", "timezone"],
     signature: &Signature::OneOf(&[
         Signature::Exact(&[DataType::String, DataType::String, DataType::String]),
         Signature::Exact(&[DataType::String, DataType::String]),
@@ -65,6 +66,7 @@ pub const PARSE_TIMESTAMP: Function = Function {
 pub const FORMAT_TIMESTAMP: Function = Function {
     namespace: None,
     name: "format_timestamp",
+    params: &["value", "format", "timezone"],
     signature: &Signature::OneOf(&[
         Signature::Exact(&[
             DataType::Timestamp(None),
@@ -97,8 +99,9 @@ pub const FORMAT_TIMESTAMP: Function = Function {
 pub const TIMESTAMP_ADD: Function = Function {
     namespace: None,
     name: "timestamp_add",
+    params: &["value", "milliseconds"],
     signature: &Signature::Exact(&[DataType::Timestamp(None), DataType::Int64]),
-    return_type: |args| args[0],
+    return_type: |args| args[0].clone(),
     function_type: FunctionType::Stateless(|args| {
         let array = args[0].downcast_ref::<TimestampArray>();
         let n = args[1].downcast_ref::<Int64Array>();
@@ -119,8 +122,9 @@ pub const TIMESTAMP_ADD: Function = Function {
 pub const TIMESTAMP_SUB: Function = Function {
     namespace: None,
     name: "timestamp_sub",
+    params: &["value", "milliseconds"],
     signature: &Signature::Exact(&[DataType::Timestamp(None), DataType::Int64]),
-    return_type: |args| args[0],
+    return_type: |args| args[0].clone(),
     function_type: FunctionType::Stateless(|args| {
         let array = args[0].downcast_ref::<TimestampArray>();
         let n = args[1].downcast_ref::<Int64Array>();
@@ -0,0 +1,100 @@
+use std::sync::Arc;
+
+use crate::array::{DataType, StringArray};
+use crate::expr::func::{Function, FunctionType};
+use crate::expr::signature::Signature;
+
+/// Returns the name of the type of the argument, e.g. `int64` or `timestamp(Asia/Shanghai)`.
+///
+/// The arrays don't carry the timezones of the timestamps, so a planned call is replaced by the
+/// name of the planned type of its argument.
+pub const TYPEOF: Function = Function {
+    namespace: None,
+    name: "typeof",
+    params: &[],
+    signature: &Signature::Any(1),
+    return_type: |_| DataType::String,
+    function_type: FunctionType::Stateless(|args| {
+        Ok(Arc::new(StringArray::new_scalar(
+            args[0].len(),
+            Some(args[0].data_type().to_string()),
+        )))
+    }),
+};
+
+pub(crate) fn is_typeof_func(func: &Function) -> bool {
+    func.namespace.is_none() && func.name == TYPEOF.name
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::array::{ArrayExt, ArrayRef, BooleanArray, Float32Array, Int8Array, NullArray};
+    use crate::dataset::{DataSet, Field, Schema};
+    use crate::dsl::*;
+
+    #[test]
+    fn test_typeof() {
+        let values: Vec<(ArrayRef, &str)> = vec![
+            (Arc::new(NullArray::new(2)), "null"),
+            (Arc::new(Int8Array::from_vec(vec![1, 2])), "int8"),
+            (Arc::new(Float32Array::from_vec(vec![1.0, 2.0])), "float32"),
+            (
+                Arc::new(BooleanArray::from_vec(vec![true, false])),
+                "boolean",
+            ),
+            (Arc::new(StringArray::from_vec(vec!["a", "b"])), "string"),
+        ];
+
+        for (array, name) in values {
+            let res = TYPEOF.function_type.call_stateless_fun(&[array]).unwrap();
+            assert_eq!(res.len(), 2);
+            assert_eq!(
+                res.downcast_ref::<StringArray>().to_scalar(),
+                Some(Some(name))
+            );
+        }
+    }
+
+    #[test]
+    fn test_typeof_columns() {
+        let schema = Arc::new(
+            Schema::try_new(vec![
+                Field::new("a", DataType::Int64),
+                Field::new("b", DataType::Float64),
+                Field::new("c", DataType::Timestamp(None)),
+                Field::new("d", DataType::Timestamp(Some(chrono_tz::Asia::Shanghai))),
+            ])
+            .unwrap(),
+        );
+        let dataset = DataSet::try_from_rows(
+            schema.clone(),
+            vec![vec![
+                1i64.into(),
+                1.5f64.into(),
+                crate::array::Scalar::Timestamp(1622512140000),
+                crate::array::Scalar::Timestamp(1622512140000),
+            ]],
+        )
+        .unwrap();
+
+        for (column, name) in &[
+            ("a", "int64"),
+            ("b", "float64"),
+            ("c", "timestamp"),
+            ("d", "timestamp(Asia/Shanghai)"),
+        ] {
+            let mut expr = call("typeof", vec![col(*column)])
+                .into_physical(schema.clone())
+                .unwrap();
+            assert_eq!(expr.data_type(), DataType::String);
+            assert_eq!(
+                expr.eval(&dataset)
+                    .unwrap()
+                    .downcast_ref::<StringArray>()
+                    .value(0),
+                *name
+            );
+        }
+    }
+}
@@ -1,5 +1,7 @@
 use std::collections::VecDeque;
 
+use anyhow::Result;
+
 pub trait VecDequeExt<T> {
     fn push_back_limit(&mut self, x: T, limit: usize) -> Option<T>;
 }
@@ -16,3 +18,12 @@ impl<T> VecDequeExt<T> for VecDeque<T> {
         }
     }
 }
+
+/// Checks the window size argument of a stateful function.
+///
+/// A window size of `0` means that the function is calculated over all the values seen so far,
+/// negative sizes are rejected.
+pub fn check_window_size(n: i64) -> Result<()> {
+    anyhow::ensure!(n >= 0, "window size must not be negative, but got {}", n);
+    Ok(())
+}
@@ -0,0 +1,87 @@
+use std::sync::Arc;
+
+use ahash::AHashSet;
+use anyhow::Result;
+
+use crate::array::{Array, ArrayExt, ArrayRef, BooleanArray, BooleanBuilder, DataType, Scalar};
+use crate::expr::{BinaryOperator, Literal};
+
+/// A value of an `IN` list that can be looked up by hashing, the integers of all widths are
+/// compared as `i128`.
+#[derive(Hash, Eq, PartialEq)]
+enum Key {
+    Int(i128),
+    String(Arc<str>),
+}
+
+impl Key {
+    fn from_scalar(scalar: Scalar) -> Option<Key> {
+        match scalar {
+            Scalar::Int8(n) => Some(Key::Int(n as i128)),
+            Scalar::Int16(n) => Some(Key::Int(n as i128)),
+            Scalar::Int32(n) => Some(Key::Int(n as i128)),
+            Scalar::Int64(n) | Scalar::Timestamp(n) => Some(Key::Int(n as i128)),
+            Scalar::UInt8(n) => Some(Key::Int(n as i128)),
+            Scalar::UInt16(n) => Some(Key::Int(n as i128)),
+            Scalar::UInt32(n) => Some(Key::Int(n as i128)),
+            Scalar::UInt64(n) => Some(Key::Int(n as i128)),
+            Scalar::String(s) => Some(Key::String(s)),
+            _ => None,
+        }
+    }
+}
+
+/// The constants of an `IN` list.
+#[derive(Default)]
+pub struct InListSet {
+    values: AHashSet<Key>,
+}
+
+impl InListSet {
+    /// Returns `None` if the values of `data_type` can't be looked up by hashing, the literals
+    /// have already been checked to be comparable with them.
+    pub fn try_new(data_type: &DataType, literals: &[Literal]) -> Option<Self> {
+        if !(data_type.is_integer() || data_type.is_string() || data_type.is_timestamp()) {
+            return None;
+        }
+        let values = literals
+            .iter()
+            .map(|literal| Key::from_scalar(literal.to_scalar()))
+            .collect::<Option<_>>()?;
+        Some(Self { values })
+    }
+
+    pub fn eval(&self, array: &dyn Array, negated: bool) -> ArrayRef {
+        let mut builder = BooleanBuilder::with_capacity(array.len());
+        for row in 0..array.len() {
+            match Key::from_scalar(array.scalar_value(row)) {
+                Some(key) => builder.append(self.values.contains(&key) != negated),
+                None => builder.append_null(),
+            }
+        }
+        Arc::new(builder.finish())
+    }
+}
+
+/// Compares the values with each element of the list, following the three-valued logic of SQL:
+/// the result is null if no element is equal but some comparison is null.
+pub fn eval_in_list(array: &dyn Array, list: &[ArrayRef], negated: bool) -> Result<ArrayRef> {
+    let mut results = vec![Some(false); array.len()];
+    for element in list {
+        let equal = BinaryOperator::Eq.eval_array(array, &**element)?;
+        let equal = equal.downcast_ref::<BooleanArray>();
+        for (row, result) in results.iter_mut().enumerate() {
+            *result = match (*result, equal.value_opt(row)) {
+                (Some(true), _) | (_, Some(true)) => Some(true),
+                (None, _) | (_, None) => None,
+                _ => Some(false),
+            };
+        }
+    }
+
+    let mut builder = BooleanBuilder::with_capacity(array.len());
+    for result in results {
+        builder.append_opt(result.map(|result| result != negated));
+    }
+    Ok(Arc::new(builder.finish()))
+}
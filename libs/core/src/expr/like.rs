@@ -0,0 +1,88 @@
+/// Returns `true` if the string matches the `LIKE` pattern.
+///
+/// `%` matches any sequence of characters (including the empty one) and `_` matches exactly one
+/// character, a backslash escapes the following character and a trailing backslash matches
+/// itself.
+pub fn like(s: &str, pattern: &str) -> bool {
+    let s = s.chars().collect::<Vec<_>>();
+    let pattern = pattern.chars().collect::<Vec<_>>();
+    let (mut s_idx, mut p_idx) = (0, 0);
+    let mut backtrack = None;
+
+    while s_idx < s.len() {
+        match pattern.get(p_idx) {
+            Some('%') => {
+                p_idx += 1;
+                backtrack = Some((s_idx, p_idx));
+                continue;
+            }
+            Some('_') => {
+                s_idx += 1;
+                p_idx += 1;
+                continue;
+            }
+            Some('\\') if pattern.get(p_idx + 1).unwrap_or(&'\\') == &s[s_idx] => {
+                s_idx += 1;
+                p_idx = (p_idx + 2).min(pattern.len());
+                continue;
+            }
+            Some(ch) if *ch != '\\' && *ch == s[s_idx] => {
+                s_idx += 1;
+                p_idx += 1;
+                continue;
+            }
+            _ => {}
+        }
+
+        match &mut backtrack {
+            Some((start, p_start)) => {
+                *start += 1;
+                s_idx = *start;
+                p_idx = *p_start;
+            }
+            None => return false,
+        }
+    }
+
+    pattern[p_idx..].iter().all(|ch| *ch == '%')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_like() {
+        assert!(like("abc", "abc"));
+        assert!(!like("abc", "ab"));
+        assert!(!like("ab", "abc"));
+        assert!(like("", ""));
+        assert!(like("", "%"));
+        assert!(!like("", "_"));
+
+        assert!(like("abc", "a%"));
+        assert!(like("abc", "%c"));
+        assert!(like("abc", "%b%"));
+        assert!(like("abc", "a%%c"));
+        assert!(!like("abc", "%d%"));
+        assert!(like("abcbc", "a%bc"));
+        assert!(like("aXbYc", "a%b%c"));
+
+        assert!(like("abc", "a_c"));
+        assert!(like("abc", "___"));
+        assert!(!like("abc", "__"));
+        assert!(like("你好吗", "你_吗"));
+
+        assert!(like("a%c", "a\\%c"));
+        assert!(!like("abc", "a\\%c"));
+        assert!(like("a_c", "a\\_c"));
+        assert!(!like("abc", "a\\_c"));
+        assert!(like("a\\c", "a\\\\c"));
+
+        assert!(like("a\\", "a\\"));
+        assert!(like("xa\\", "%a\\"));
+        assert!(like("\\", "\\"));
+        assert!(!like("ab", "a\\"));
+        assert!(!like("a", "a\\"));
+    }
+}
@@ -1,12 +1,10 @@
+use std::convert::TryFrom;
 use std::fmt::{self, Display, Formatter};
-use std::sync::Arc;
 
+use chrono::{TimeZone, Utc};
 use serde::{Deserialize, Serialize};
 
-use crate::array::{
-    ArrayRef, BooleanArray, DataType, Float32Array, Float64Array, Int16Array, Int32Array,
-    Int64Array, Int8Array, StringArray,
-};
+use crate::array::{ArrayRef, DataType, Scalar};
 
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub enum Literal {
@@ -14,6 +12,8 @@ pub enum Literal {
     Int(i64),
     Float(f64),
     String(String),
+    /// Milliseconds since the unix epoch.
+    Timestamp(i64),
 }
 
 impl Display for Literal {
@@ -23,6 +23,11 @@ impl Display for Literal {
             Literal::Int(n) => write!(f, "{}", n),
             Literal::Float(n) => write!(f, "{}", n),
             Literal::String(n) => write!(f, "\"{}\"", n),
+            Literal::Timestamp(n) => write!(
+                f,
+                "timestamp '{}'",
+                Utc.timestamp_millis(*n).format("%Y-%m-%d %H:%M:%S%.3f")
+            ),
         }
     }
 }
@@ -82,38 +87,69 @@ impl Literal {
                 }
             }
             Literal::String(_) => DataType::String,
+            Literal::Timestamp(_) => DataType::Timestamp(None),
         }
     }
 
-    pub fn to_array(&self, len: usize) -> ArrayRef {
+    /// Returns the value of the literal as a scalar of the type returned by `data_type`.
+    pub fn to_scalar(&self) -> Scalar {
         match self {
-            Literal::Boolean(n) => Arc::new(BooleanArray::new_scalar(len, Some(*n))),
-            Literal::Int(n) => {
-                if *n >= i8::MIN as i64 && *n <= i8::MAX as i64 {
-                    Arc::new(Int8Array::new_scalar(len, Some(*n as i8)))
-                } else if *n >= i16::MIN as i64 && *n <= i16::MAX as i64 {
-                    Arc::new(Int16Array::new_scalar(len, Some(*n as i16)))
-                } else if *n >= i32::MIN as i64 && *n <= i32::MAX as i64 {
-                    Arc::new(Int32Array::new_scalar(len, Some(*n as i32)))
-                } else {
-                    Arc::new(Int64Array::new_scalar(len, Some(*n)))
-                }
-            }
-            Literal::Float(n) => {
-                if *n >= f32::MIN as f64 && *n <= f32::MAX as f64 {
-                    Arc::new(Float32Array::new_scalar(len, Some(*n as f32)))
-                } else {
-                    Arc::new(Float64Array::new_scalar(len, Some(*n)))
-                }
-            }
-            Literal::String(s) => Arc::new(StringArray::new_scalar(len, Some(s.as_str()))),
+            Literal::Boolean(n) => Scalar::Boolean(*n),
+            Literal::Int(n) => match self.data_type() {
+                DataType::Int8 => Scalar::Int8(*n as i8),
+                DataType::Int16 => Scalar::Int16(*n as i16),
+                DataType::Int32 => Scalar::Int32(*n as i32),
+                _ => Scalar::Int64(*n),
+            },
+            Literal::Float(n) => match self.data_type() {
+                DataType::Float32 => Scalar::Float32(*n as f32),
+                _ => Scalar::Float64(*n),
+            },
+            Literal::String(s) => Scalar::String(s.as_str().into()),
+            Literal::Timestamp(n) => Scalar::Timestamp(*n),
         }
     }
+
+    pub fn to_array(&self, len: usize) -> ArrayRef {
+        self.to_scalar().to_array(len)
+    }
+}
+
+/// Converts a scalar back to a literal, `Scalar` is defined in the dataset crate, so the method
+/// is added by this trait.
+pub trait ScalarExt {
+    /// Returns the literal of a scalar value, or `None` if there is no literal for it, e.g. null,
+    /// a decimal or an unsigned integer greater than `i64::MAX`.
+    fn to_literal(&self) -> Option<Literal>;
+}
+
+impl ScalarExt for Scalar {
+    fn to_literal(&self) -> Option<Literal> {
+        Some(match self {
+            Scalar::Int8(n) => Literal::Int(*n as i64),
+            Scalar::Int16(n) => Literal::Int(*n as i64),
+            Scalar::Int32(n) => Literal::Int(*n as i64),
+            Scalar::Int64(n) => Literal::Int(*n),
+            Scalar::UInt8(n) => Literal::Int(*n as i64),
+            Scalar::UInt16(n) => Literal::Int(*n as i64),
+            Scalar::UInt32(n) => Literal::Int(*n as i64),
+            Scalar::UInt64(n) => Literal::Int(i64::try_from(*n).ok()?),
+            Scalar::Float32(n) => Literal::Float(*n as f64),
+            Scalar::Float64(n) => Literal::Float(*n),
+            Scalar::Boolean(n) => Literal::Boolean(*n),
+            Scalar::Timestamp(n) => Literal::Timestamp(*n),
+            Scalar::String(s) => Literal::String(s.to_string()),
+            Scalar::Null | Scalar::Decimal128 { .. } | Scalar::List(_) | Scalar::Struct(_) => {
+                return None
+            }
+        })
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::array::ArrayExt;
 
     #[test]
     fn test_data_type() {
@@ -148,5 +184,53 @@ mod tests {
             Literal::String("abc".to_string()).data_type(),
             DataType::String
         );
+
+        assert_eq!(
+            Literal::Timestamp(1622512140000).data_type(),
+            DataType::Timestamp(None)
+        );
+    }
+
+    #[test]
+    fn test_scalar() {
+        let literals = vec![
+            (Literal::Boolean(true), Scalar::Boolean(true)),
+            (Literal::Int(1), Scalar::Int8(1)),
+            (Literal::Int(1000), Scalar::Int16(1000)),
+            (Literal::Int(100000), Scalar::Int32(100000)),
+            (Literal::Int(i64::MAX), Scalar::Int64(i64::MAX)),
+            (Literal::Float(1.5), Scalar::Float32(1.5)),
+            (Literal::Float(f64::MAX), Scalar::Float64(f64::MAX)),
+            (
+                Literal::String("abc".to_string()),
+                Scalar::String("abc".into()),
+            ),
+            (
+                Literal::Timestamp(1622512140000),
+                Scalar::Timestamp(1622512140000),
+            ),
+        ];
+        for (literal, scalar) in literals {
+            assert_eq!(literal.to_scalar(), scalar);
+            assert_eq!(scalar.data_type(), literal.data_type());
+            assert_eq!(scalar.to_literal(), Some(literal.clone()));
+
+            let array = literal.to_array(2);
+            assert_eq!(array.data_type(), literal.data_type());
+            assert_eq!(array.scalar_value(1), scalar);
+        }
+
+        assert_eq!(Scalar::UInt8(1).to_literal(), Some(Literal::Int(1)));
+        assert_eq!(Scalar::UInt64(u64::MAX).to_literal(), None);
+        assert_eq!(Scalar::Null.to_literal(), None);
+        assert_eq!(
+            Scalar::Decimal128 {
+                value: 1,
+                precision: 10,
+                scale: 2
+            }
+            .to_literal(),
+            None
+        );
     }
 }
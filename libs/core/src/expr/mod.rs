@@ -1,10 +1,11 @@
 mod binary_operator;
-mod cast;
 mod display;
 #[allow(clippy::module_inception)]
 mod expr;
 mod func;
 mod funcs;
+mod in_list;
+mod like;
 mod literal;
 mod signature;
 mod to_physical;
@@ -14,6 +15,9 @@ pub mod physical_expr;
 
 pub use binary_operator::BinaryOperator;
 pub use expr::Expr;
-pub use literal::Literal;
+pub use funcs::function_names;
+pub(crate) use funcs::HyperLogLog;
+pub use like::like;
+pub use literal::{Literal, ScalarExt};
 pub use physical_expr::ExprState;
 pub use unary_operator::UnaryOperator;
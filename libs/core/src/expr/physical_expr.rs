@@ -1,11 +1,16 @@
 use std::collections::HashMap;
+use std::sync::Arc;
 
 use anyhow::{Context, Result};
 
-use crate::array::{ArrayRef, DataType};
+use crate::array::compute::CastMode;
+use crate::array::{
+    compute, Array, ArrayExt, ArrayRef, BooleanArray, DataType, Int64Array, NullArray, StructArray,
+};
 use crate::dataset::DataSet;
 use crate::expr::func::GenericStatefulFunction;
-use crate::expr::{cast, BinaryOperator, Literal, UnaryOperator};
+use crate::expr::in_list::{eval_in_list, InListSet};
+use crate::expr::{BinaryOperator, Literal, UnaryOperator};
 
 #[derive(Clone)]
 pub enum PhysicalFunction {
@@ -19,6 +24,10 @@ pub enum PhysicalNode {
     Column {
         index: usize,
     },
+    StructField {
+        expr: Box<PhysicalNode>,
+        index: usize,
+    },
     Binary {
         op: BinaryOperator,
         lhs: Box<PhysicalNode>,
@@ -33,6 +42,23 @@ pub enum PhysicalNode {
         func: PhysicalFunction,
         args: Vec<PhysicalNode>,
     },
+    Case {
+        operand: Option<Box<PhysicalNode>>,
+        when_then: Vec<(PhysicalNode, PhysicalNode)>,
+        else_expr: Option<Box<PhysicalNode>>,
+        data_type: DataType,
+    },
+    Cast {
+        expr: Box<PhysicalNode>,
+        data_type: DataType,
+    },
+    /// The list is looked up in `set` if all of its elements are constants.
+    InList {
+        expr: Box<PhysicalNode>,
+        list: Vec<PhysicalNode>,
+        set: Option<Arc<InListSet>>,
+        negated: bool,
+    },
 }
 
 pub type ExprState = Vec<u8>;
@@ -47,7 +73,13 @@ pub struct PhysicalExpr {
 impl PhysicalExpr {
     #[inline]
     pub fn data_type(&self) -> DataType {
-        self.data_type
+        self.data_type.clone()
+    }
+
+    /// Returns `true` if the expression calls a stateful function, so that its result depends on
+    /// the rows it was evaluated on before.
+    pub(crate) fn is_stateful(&self) -> bool {
+        !self.stateful_funcs.is_empty()
     }
 
     pub fn eval(&mut self, dataset: &DataSet) -> Result<ArrayRef> {
@@ -74,6 +106,84 @@ impl PhysicalExpr {
         }
         Ok(())
     }
+
+    /// Merges the states of the stateful functions of another instance of the expression, e.g. one
+    /// that aggregated another partition of the rows.
+    pub(crate) fn merge(&mut self, other: &PhysicalExpr) -> Result<()> {
+        for (func, other_func) in self.stateful_funcs.iter_mut().zip(&other.stateful_funcs) {
+            func.merge(&**other_func)?;
+        }
+        Ok(())
+    }
+
+    /// Returns `true` if the states of all the stateful functions can be merged.
+    pub(crate) fn can_merge(&self) -> bool {
+        self.stateful_funcs.iter().all(|func| func.can_merge())
+    }
+
+    /// Evaluates the expression without changing the states of the stateful functions, which are
+    /// called with null arguments instead, so that they return their current results.
+    pub(crate) fn eval_current(&self, dataset: &DataSet) -> Result<ArrayRef> {
+        let mut expr = PhysicalExpr {
+            root: self.root.clone(),
+            data_type: self.data_type.clone(),
+            stateful_funcs: self
+                .stateful_funcs
+                .iter()
+                .map(|func| {
+                    Box::new(NullArgsFunction(func.clone())) as Box<dyn GenericStatefulFunction>
+                })
+                .collect(),
+        };
+        expr.eval(dataset)
+    }
+}
+
+/// Calls a stateful function with null arguments, which the aggregate functions skip.
+#[derive(Clone)]
+struct NullArgsFunction(Box<dyn GenericStatefulFunction>);
+
+impl GenericStatefulFunction for NullArgsFunction {
+    fn call(&mut self, args: &[ArrayRef]) -> Result<ArrayRef> {
+        let len = args.first().map(|array| array.len()).unwrap_or_default();
+        let indices = Int64Array::from_opt_vec(vec![None; len]);
+        let args = args
+            .iter()
+            .map(|array| compute::take(&**array, &indices))
+            .collect::<Result<Vec<_>>>()?;
+        self.0.call(&args)
+    }
+
+    fn save_state(&self) -> Result<Vec<u8>> {
+        self.0.save_state()
+    }
+
+    fn load_state(&mut self, state: Vec<u8>) -> Result<()> {
+        self.0.load_state(state)
+    }
+
+    fn merge_state(&mut self, state: Vec<u8>) -> Result<()> {
+        self.0.merge_state(state)
+    }
+
+    fn can_merge(&self) -> bool {
+        self.0.can_merge()
+    }
+}
+
+/// Appends the values of a branch of a case expression cast to its type, and returns the offset
+/// of them, or `None` if they are all null.
+fn branch_offset(
+    branches: &mut Vec<ArrayRef>,
+    array: ArrayRef,
+    data_type: &DataType,
+) -> Result<Option<usize>> {
+    if array.data_type() == DataType::Null {
+        return Ok(None);
+    }
+    let offset = branches.iter().map(|array| array.len()).sum();
+    branches.push(compute::cast(array, data_type.clone())?);
+    Ok(Some(offset))
 }
 
 #[inline]
@@ -85,6 +195,10 @@ fn internal_eval(
     match op {
         PhysicalNode::Literal(literal) => Ok(literal.to_array(dataset.len())),
         PhysicalNode::Column { index } => Ok(dataset.column(*index).context("internal error")?),
+        PhysicalNode::StructField { expr, index } => {
+            let array = internal_eval(expr, stateful_funcs, dataset)?;
+            Ok(array.downcast_ref::<StructArray>().columns()[*index].clone())
+        }
         PhysicalNode::Binary { op, lhs, rhs } => {
             let left = internal_eval(lhs, stateful_funcs, dataset)?;
             let right = internal_eval(rhs, stateful_funcs, dataset)?;
@@ -94,6 +208,29 @@ fn internal_eval(
             let array = internal_eval(expr, stateful_funcs, dataset)?;
             op.eval_array(&*array)
         }
+        PhysicalNode::Cast { expr, data_type } => {
+            // like most SQL engines, an explicit cast truncates the fractional part of a float
+            let array = internal_eval(expr, stateful_funcs, dataset)?;
+            compute::cast_with_mode(array, data_type.clone(), CastMode::Truncate)
+        }
+        PhysicalNode::InList {
+            expr,
+            list,
+            set,
+            negated,
+        } => {
+            let array = internal_eval(expr, stateful_funcs, dataset)?;
+            match set {
+                Some(set) => Ok(set.eval(&*array, *negated)),
+                None => {
+                    let mut elements = Vec::with_capacity(list.len());
+                    for element in list.iter_mut() {
+                        elements.push(internal_eval(element, stateful_funcs, dataset)?);
+                    }
+                    eval_in_list(&*array, &elements, *negated)
+                }
+            }
+        }
         PhysicalNode::Call {
             input_data_types,
             func,
@@ -101,9 +238,9 @@ fn internal_eval(
         } => {
             let mut arg_values = Vec::with_capacity(args.len());
             for (expr, data_type) in args.iter_mut().zip(input_data_types) {
-                arg_values.push(cast::array_cast_to(
+                arg_values.push(compute::cast(
                     internal_eval(expr, stateful_funcs, dataset)?,
-                    *data_type,
+                    data_type.clone(),
                 )?);
             }
             match func {
@@ -114,5 +251,61 @@ fn internal_eval(
                 }
             }
         }
+        PhysicalNode::Case {
+            operand,
+            when_then,
+            else_expr,
+            data_type,
+        } => {
+            let operand = match operand {
+                Some(operand) => Some(internal_eval(operand, stateful_funcs, dataset)?),
+                None => None,
+            };
+
+            // the values of the branches are concatenated, and each row takes the value of the
+            // first branch whose condition is satisfied, a null condition is not satisfied
+            let len = dataset.len();
+            let mut branches = Vec::with_capacity(when_then.len() + 1);
+            let mut indices = vec![None; len];
+            let mut matched = vec![false; len];
+            for (when, then) in when_then.iter_mut() {
+                let mut condition = internal_eval(when, stateful_funcs, dataset)?;
+                if let Some(operand) = &operand {
+                    condition = BinaryOperator::Eq.eval_array(&**operand, &*condition)?;
+                }
+                let then = internal_eval(then, stateful_funcs, dataset)?;
+                if condition.data_type() == DataType::Boolean {
+                    let condition = condition.downcast_ref::<BooleanArray>();
+                    let offset = branch_offset(&mut branches, then, data_type)?;
+                    for (row, (index, matched)) in indices.iter_mut().zip(&mut matched).enumerate()
+                    {
+                        if !*matched && condition.value_opt(row) == Some(true) {
+                            *matched = true;
+                            *index = offset.map(|offset| (offset + row) as i64);
+                        }
+                    }
+                }
+            }
+            if let Some(else_expr) = else_expr {
+                let array = internal_eval(else_expr, stateful_funcs, dataset)?;
+                let offset = branch_offset(&mut branches, array, data_type)?;
+                for (row, (index, matched)) in indices.iter_mut().zip(&matched).enumerate() {
+                    if !*matched {
+                        *index = offset.map(|offset| (offset + row) as i64);
+                    }
+                }
+            }
+
+            if branches.is_empty() {
+                return Ok(Arc::new(NullArray::new(len)));
+            }
+            let values = compute::concat(
+                &branches
+                    .iter()
+                    .map(|array| &**array as &dyn Array)
+                    .collect::<Vec<_>>(),
+            )?;
+            compute::take(&*values, &Int64Array::from_opt_vec(indices))
+        }
     }
 }
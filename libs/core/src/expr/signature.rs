@@ -9,7 +9,14 @@ pub enum Signature {
     VariadicEqual,
     Uniform(usize, &'static [DataType]),
     Exact(&'static [DataType]),
+    /// Accepts the specified number of arguments of any type, which are passed through as they are.
     Any(usize),
+    /// Accepts the specified number of arguments of any numeric type, which are passed through as
+    /// they are.
+    AnyNumeric(usize),
+    /// Accepts the specified number of arguments of any decimal type, which are passed through as
+    /// they are.
+    AnyDecimal(usize),
     OneOf(&'static [Signature]),
 }
 
@@ -18,16 +25,17 @@ impl Signature {
         let valid_types: Vec<Vec<DataType>> = match self {
             Signature::Variadic(valid_types) => valid_types
                 .iter()
-                .copied()
-                .map(|valid_type| current_types.iter().map(|_| valid_type).collect())
+                .map(|valid_type| current_types.iter().map(|_| valid_type.clone()).collect())
                 .collect(),
             Signature::Uniform(number, valid_types) => valid_types
                 .iter()
-                .copied()
-                .map(|valid_type| (0..*number).map(|_| valid_type).collect())
+                .map(|valid_type| (0..*number).map(|_| valid_type.clone()).collect())
                 .collect(),
             Signature::VariadicEqual => {
-                vec![current_types.iter().map(|_| current_types[0]).collect()]
+                vec![current_types
+                    .iter()
+                    .map(|_| current_types[0].clone())
+                    .collect()]
             }
             Signature::Exact(valid_types) => vec![valid_types.to_vec()],
             Signature::Any(number) => {
@@ -37,7 +45,33 @@ impl Signature {
                     *number,
                     current_types.len()
                 );
-                vec![(0..*number).map(|i| current_types[i]).collect()]
+                vec![(0..*number).map(|i| current_types[i].clone()).collect()]
+            }
+            Signature::AnyNumeric(number) => {
+                anyhow::ensure!(
+                    current_types.len() == *number,
+                    "invalid arguments number expect: {} actual: {}",
+                    *number,
+                    current_types.len()
+                );
+                if current_types.iter().all(DataType::is_numeric) {
+                    vec![current_types.to_vec()]
+                } else {
+                    vec![]
+                }
+            }
+            Signature::AnyDecimal(number) => {
+                anyhow::ensure!(
+                    current_types.len() == *number,
+                    "invalid arguments number expect: {} actual: {}",
+                    *number,
+                    current_types.len()
+                );
+                if current_types.iter().all(DataType::is_decimal) {
+                    vec![current_types.to_vec()]
+                } else {
+                    vec![]
+                }
             }
             Signature::OneOf(types) => {
                 let mut r = Vec::new();
@@ -64,9 +98,9 @@ impl Signature {
             let current_type = &current_types[i];
 
             if current_type == valid_type {
-                new_type.push(*current_type)
-            } else if current_type.can_cast_to(*valid_type) {
-                new_type.push(*valid_type)
+                new_type.push(current_type.clone())
+            } else if current_type.can_cast_to(valid_type.clone()) {
+                new_type.push(valid_type.clone())
             } else {
                 return None;
             }
@@ -95,3 +129,60 @@ impl Signature {
         anyhow::bail!("can't coerce arguments")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expr::func::{Function, FunctionType};
+
+    const IDENTITY: Function = Function {
+        namespace: None,
+        name: "identity",
+        params: &[],
+        signature: &Signature::Any(1),
+        return_type: |args| args[0].clone(),
+        function_type: FunctionType::Stateless(|args| Ok(args[0].clone())),
+    };
+
+    #[test]
+    fn test_any() {
+        for data_type in &[
+            DataType::Null,
+            DataType::Int8,
+            DataType::Float64,
+            DataType::Boolean,
+            DataType::Timestamp(None),
+            DataType::String,
+        ] {
+            let input_types = IDENTITY
+                .signature
+                .data_types(std::slice::from_ref(data_type))
+                .unwrap();
+            assert_eq!(input_types, vec![data_type.clone()]);
+            assert_eq!((IDENTITY.return_type)(&input_types), data_type.clone());
+        }
+
+        assert!(IDENTITY
+            .signature
+            .data_types(&[DataType::Int8, DataType::Int8])
+            .is_err());
+    }
+
+    #[test]
+    fn test_any_numeric() {
+        let signature = Signature::AnyNumeric(2);
+        assert_eq!(
+            signature
+                .data_types(&[DataType::Int8, DataType::Float32])
+                .unwrap(),
+            vec![DataType::Int8, DataType::Float32]
+        );
+        assert!(signature
+            .data_types(&[DataType::Int8, DataType::String])
+            .is_err());
+        assert!(signature
+            .data_types(&[DataType::Boolean, DataType::Int64])
+            .is_err());
+        assert!(signature.data_types(&[DataType::Int8]).is_err());
+    }
+}
@@ -1,11 +1,14 @@
+use std::sync::Arc;
+
 use anyhow::Error;
 
-use crate::array::DataType;
+use crate::array::{compute, DataType};
 use crate::dataset::SchemaRef;
-use crate::expr::func::{FunctionType, GenericStatefulFunction};
-use crate::expr::funcs::find_function;
+use crate::expr::func::{DistinctFunction, Function, FunctionType, GenericStatefulFunction};
+use crate::expr::funcs::{find_function, is_timezone_of_argument_func, is_typeof_func};
+use crate::expr::in_list::InListSet;
 use crate::expr::physical_expr::{PhysicalExpr, PhysicalFunction, PhysicalNode};
-use crate::expr::Expr;
+use crate::expr::{BinaryOperator, Expr, Literal};
 
 pub type Result<T, E = Error> = std::result::Result<(T, DataType), E>;
 
@@ -14,6 +17,20 @@ struct Context {
     stateful_funcs: Vec<Box<dyn GenericStatefulFunction>>,
 }
 
+/// Finds the field `name` of the struct column `column`, returns the index of the column, the
+/// index of the field and its datatype.
+fn struct_field(schema: &SchemaRef, column: &str, name: &str) -> Option<(usize, usize, DataType)> {
+    let (index, field) = schema.field(None, column)?;
+    match &field.data_type {
+        DataType::Struct(fields) => fields
+            .iter()
+            .enumerate()
+            .find(|(_, field)| field.name.eq_ignore_ascii_case(name))
+            .map(|(field_index, field)| (index, field_index, field.data_type.clone())),
+        _ => None,
+    }
+}
+
 fn to_physical(ctx: &mut Context, expr: Expr) -> Result<PhysicalNode> {
     match expr {
         Expr::Literal(literal) => {
@@ -21,9 +38,19 @@ fn to_physical(ctx: &mut Context, expr: Expr) -> Result<PhysicalNode> {
             Ok((PhysicalNode::Literal(literal), data_type))
         }
         Expr::Column { qualifier, name } => match ctx.schema.field(qualifier.as_deref(), &name) {
-            Some((index, field)) => Ok((PhysicalNode::Column { index }, field.data_type)),
+            Some((index, field)) => Ok((PhysicalNode::Column { index }, field.data_type.clone())),
             None => match qualifier {
-                Some(qualifier) => anyhow::bail!("not such column: '{}.{}'", qualifier, name),
+                // `a.b` is the field `b` of the struct column `a` if there is no such table
+                Some(qualifier) => match struct_field(&ctx.schema, &qualifier, &name) {
+                    Some((index, field_index, data_type)) => Ok((
+                        PhysicalNode::StructField {
+                            expr: Box::new(PhysicalNode::Column { index }),
+                            index: field_index,
+                        },
+                        data_type,
+                    )),
+                    None => anyhow::bail!("not such column: '{}.{}'", qualifier, name),
+                },
                 None => anyhow::bail!("not such column: '{}'", name),
             },
         },
@@ -61,27 +88,72 @@ fn to_physical(ctx: &mut Context, expr: Expr) -> Result<PhysicalNode> {
                 None => anyhow::bail!("no such function: '{}'", name),
             };
 
+            // `DISTINCT` can only be applied to the only argument of an aggregate function
+            let distinct = matches!(arguments.as_slice(), [Expr::Distinct(_)]);
+            let arguments = if distinct {
+                anyhow::ensure!(
+                    matches!(func.function_type, FunctionType::Stateful(_)),
+                    "DISTINCT can only be used with aggregate functions, but '{}' is not one",
+                    func.name
+                );
+                arguments
+                    .into_iter()
+                    .map(|arg| match arg {
+                        Expr::Distinct(expr) => *expr,
+                        arg => arg,
+                    })
+                    .collect()
+            } else {
+                arguments
+            };
+
             let mut arg_exprs = Vec::new();
             let mut arg_data_types = Vec::new();
-            for arg in arguments {
+            for arg in resolve_arguments(func, arguments)? {
                 let (expr, data_type) = to_physical(ctx, arg)?;
                 arg_exprs.push(expr);
                 arg_data_types.push(data_type);
             }
 
+            // the timezone of a timestamp argument is the default of an omitted `timezone` of the
+            // datetime functions
+            if is_timezone_of_argument_func(func) && arg_exprs.len() + 1 == func.params.len() {
+                let tz = arg_data_types.iter().find_map(|data_type| match data_type {
+                    DataType::Timestamp(Some(tz)) => Some(*tz),
+                    _ => None,
+                });
+                if let Some(tz) = tz {
+                    arg_exprs.push(PhysicalNode::Literal(Literal::String(tz.name().to_string())));
+                    arg_data_types.push(DataType::String);
+                }
+            }
+
             let input_data_types = func
                 .signature
                 .data_types(&arg_data_types)
                 .map_err(|_| anyhow::anyhow!("misuse function: {}", func.name))?;
             let return_data_type = (func.return_type)(&input_data_types);
 
+            // the arrays don't carry the timezones of the timestamps, so the name of the type is
+            // taken from the plan
+            if is_typeof_func(func) {
+                return Ok((
+                    PhysicalNode::Literal(Literal::String(arg_data_types[0].to_string())),
+                    return_data_type,
+                ));
+            }
+
             let call = PhysicalNode::Call {
                 input_data_types,
                 func: match &func.function_type {
                     FunctionType::Stateless(f) => PhysicalFunction::Stateless(*f),
                     FunctionType::Stateful(f) => {
                         let id = ctx.stateful_funcs.len() as usize;
-                        ctx.stateful_funcs.push(f());
+                        ctx.stateful_funcs.push(if distinct {
+                            Box::new(DistinctFunction::new(f()))
+                        } else {
+                            f()
+                        });
                         PhysicalFunction::Stateful { id }
                     }
                 },
@@ -90,10 +162,227 @@ fn to_physical(ctx: &mut Context, expr: Expr) -> Result<PhysicalNode> {
             Ok((call, return_data_type))
         }
         Expr::Alias(expr, _) => to_physical(ctx, *expr),
+        Expr::NamedArgument { name, .. } => anyhow::bail!(
+            "the named argument '{}' can only be passed to a function",
+            name
+        ),
+        Expr::Distinct(expr) => anyhow::bail!(
+            "DISTINCT can only be applied to the only argument of an aggregate function, but got 'distinct {}'",
+            expr
+        ),
+        Expr::Cast { expr, data_type } => {
+            let (expr, from_data_type) = to_physical(ctx, *expr)?;
+            anyhow::ensure!(
+                compute::can_cast(&from_data_type, &data_type),
+                "cannot cast type from '{}' to '{}'",
+                from_data_type,
+                data_type
+            );
+            Ok((
+                PhysicalNode::Cast {
+                    expr: Box::new(expr),
+                    data_type: data_type.clone(),
+                },
+                data_type,
+            ))
+        }
         Expr::Wildcard { .. } => anyhow::bail!("invalid wildcard position"),
+        Expr::Over { .. } => anyhow::bail!("a window can only be specified for an aggregation"),
+        Expr::InList {
+            expr,
+            list,
+            negated,
+        } => {
+            let (expr, data_type) = to_physical(ctx, *expr)?;
+            let literals = list
+                .iter()
+                .map(|element| match element {
+                    Expr::Literal(literal) => Some(literal.clone()),
+                    _ => None,
+                })
+                .collect::<Option<Vec<_>>>();
+            let mut physical_list = Vec::with_capacity(list.len());
+            for element in list {
+                let display = element.to_string();
+                let (element, element_data_type) = to_physical(ctx, element)?;
+                anyhow::ensure!(
+                    BinaryOperator::Eq
+                        .data_type(data_type.clone(), element_data_type.clone())
+                        .is_ok(),
+                    "the element '{}' of the in list has type {}, which can't be compared with {}",
+                    display,
+                    element_data_type,
+                    data_type
+                );
+                physical_list.push(element);
+            }
+            let set = literals
+                .and_then(|literals| InListSet::try_new(&data_type, &literals))
+                .map(Arc::new);
+            Ok((
+                PhysicalNode::InList {
+                    expr: Box::new(expr),
+                    list: physical_list,
+                    set,
+                    negated,
+                },
+                DataType::Boolean,
+            ))
+        }
+        Expr::Between {
+            expr,
+            low,
+            high,
+            negated,
+        } => {
+            let expr = if negated {
+                expr.clone().lt(*low).or(expr.gt(*high))
+            } else {
+                expr.clone().gt_eq(*low).and(expr.lt_eq(*high))
+            };
+            to_physical(ctx, expr)
+        }
+        Expr::SubQuery(_) => {
+            anyhow::bail!("a subquery can only be a field of the projection")
+        }
+        Expr::Case {
+            operand,
+            when_then,
+            else_expr,
+        } => {
+            anyhow::ensure!(
+                !when_then.is_empty(),
+                "the case expression requires at least one when clause"
+            );
+            let operand = match operand {
+                Some(operand) => Some(to_physical(ctx, *operand)?),
+                None => None,
+            };
+
+            let mut branch_types = Vec::with_capacity(when_then.len() + 1);
+            let mut physical_when_then = Vec::with_capacity(when_then.len());
+            for (when, then) in when_then {
+                let (when, when_data_type) = to_physical(ctx, when)?;
+                match &operand {
+                    Some((_, operand_data_type)) => {
+                        BinaryOperator::Eq.data_type(operand_data_type.clone(), when_data_type)?;
+                    }
+                    None => anyhow::ensure!(
+                        matches!(when_data_type, DataType::Boolean | DataType::Null),
+                        "the when clause of the case expression must return a boolean type, but it returns {}",
+                        when_data_type
+                    ),
+                }
+                let (then, then_data_type) = to_physical(ctx, then)?;
+                branch_types.push(then_data_type);
+                physical_when_then.push((when, then));
+            }
+            let else_expr = match else_expr {
+                Some(else_expr) => {
+                    let (else_expr, else_data_type) = to_physical(ctx, *else_expr)?;
+                    branch_types.push(else_data_type);
+                    Some(Box::new(else_expr))
+                }
+                None => None,
+            };
+
+            let data_type = branch_types
+                .into_iter()
+                .try_fold(DataType::Null, unify_branch_types)?;
+            Ok((
+                PhysicalNode::Case {
+                    operand: operand.map(|(operand, _)| Box::new(operand)),
+                    when_then: physical_when_then,
+                    else_expr,
+                    data_type: data_type.clone(),
+                },
+                data_type,
+            ))
+        }
     }
 }
 
+/// Puts the arguments passed by name at the positions of the parameters of the function, they
+/// must come after the arguments passed by position.
+fn resolve_arguments(func: &Function, args: Vec<Expr>) -> anyhow::Result<Vec<Expr>> {
+    let mut resolved: Vec<Option<Expr>> = Vec::with_capacity(args.len());
+    let mut has_named = false;
+    for arg in args {
+        match arg {
+            Expr::NamedArgument { name, expr } => {
+                let index = func
+                    .params
+                    .iter()
+                    .position(|param| param.eq_ignore_ascii_case(&name))
+                    .ok_or_else(|| {
+                        anyhow::anyhow!(
+                            "function '{}' has no parameter named '{}'",
+                            func.name,
+                            name
+                        )
+                    })?;
+                if resolved.len() <= index {
+                    resolved.resize(index + 1, None);
+                }
+                anyhow::ensure!(
+                    resolved[index].is_none(),
+                    "the argument '{}' of function '{}' is specified more than once",
+                    func.params[index],
+                    func.name
+                );
+                resolved[index] = Some(*expr);
+                has_named = true;
+            }
+            expr => {
+                anyhow::ensure!(
+                    !has_named,
+                    "the positional arguments of function '{}' must come before the named arguments",
+                    func.name
+                );
+                resolved.push(Some(expr));
+            }
+        }
+    }
+
+    resolved
+        .into_iter()
+        .enumerate()
+        .map(|(index, arg)| {
+            arg.ok_or_else(|| {
+                anyhow::anyhow!(
+                    "missing the argument '{}' of function '{}'",
+                    func.params[index],
+                    func.name
+                )
+            })
+        })
+        .collect()
+}
+
+/// Returns the type that the values of two branches of a case expression are converted to.
+fn unify_branch_types(a: DataType, b: DataType) -> anyhow::Result<DataType> {
+    Ok(match (a, b) {
+        (a, b) if a == b => a,
+        (DataType::Null, other) | (other, DataType::Null) => other,
+        (DataType::UInt64, other) | (other, DataType::UInt64) if other.is_unsigned_integer() => {
+            DataType::UInt64
+        }
+        // a signed integer and a `UInt64` only have a float as the common type
+        (DataType::UInt64, other) | (other, DataType::UInt64) if other.is_integer() => {
+            DataType::Float64
+        }
+        (a, b) if a.is_integer() && b.is_integer() => DataType::Int64,
+        (a, b) if a.is_numeric() && b.is_numeric() && !a.is_decimal() && !b.is_decimal() => {
+            DataType::Float64
+        }
+        (a, b) => anyhow::bail!(
+            "the branches of the case expression have incompatible types: {} and {}",
+            a,
+            b
+        ),
+    })
+}
+
 impl Expr {
     pub fn into_physical(self, schema: SchemaRef) -> anyhow::Result<PhysicalExpr> {
         let mut ctx = Context {
@@ -108,3 +397,299 @@ impl Expr {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::array::{ArrayExt, Int64Array, Scalar, StringArray};
+    use crate::dataset::{DataSet, Field, Schema};
+    use crate::dsl::*;
+
+    #[test]
+    fn test_in_list() {
+        let schema = Arc::new(
+            Schema::try_new(vec![
+                Field::new("status", DataType::String),
+                Field::new("a", DataType::Int64),
+                Field::new("b", DataType::UInt8),
+            ])
+            .unwrap(),
+        );
+        let dataset = DataSet::try_from_rows(
+            schema.clone(),
+            vec![
+                vec!["open".into(), 1i64.into(), 1u8.into()],
+                vec!["closed".into(), 2i64.into(), Scalar::Null],
+                vec![Scalar::Null, Scalar::Null, 3u8.into()],
+                vec!["pending".into(), 300i64.into(), 4u8.into()],
+            ],
+        )
+        .unwrap();
+        let eval = |sql: &str| {
+            let mut expr = crate::sql::parser::expr(sql)
+                .unwrap()
+                .1
+                .into_physical(schema.clone())?;
+            let array = expr.eval(&dataset)?;
+            assert_eq!(array.data_type(), expr.data_type());
+            Ok::<_, anyhow::Error>(
+                (0..array.len())
+                    .map(|row| array.scalar_value(row))
+                    .collect::<Vec<_>>(),
+            )
+        };
+
+        assert_eq!(
+            eval("status in ('open', 'pending')").unwrap(),
+            vec![true.into(), false.into(), Scalar::Null, true.into()]
+        );
+        assert_eq!(
+            eval("status not in ('open', 'pending')").unwrap(),
+            vec![false.into(), true.into(), Scalar::Null, false.into()]
+        );
+        assert_eq!(
+            eval("a in (300, 2)").unwrap(),
+            vec![false.into(), true.into(), Scalar::Null, true.into()]
+        );
+
+        // the elements that aren't constants are compared one by one, no equal element but a
+        // null one makes the result null
+        assert_eq!(
+            eval("a in (b, 2)").unwrap(),
+            vec![true.into(), true.into(), Scalar::Null, false.into()]
+        );
+        assert_eq!(
+            eval("a not in (b, 300)").unwrap(),
+            vec![false.into(), Scalar::Null, Scalar::Null, false.into()]
+        );
+
+        assert_eq!(
+            eval("a in (1, 'x')").unwrap_err().to_string(),
+            "the element '\"x\"' of the in list has type string, which can't be compared with int64"
+        );
+    }
+
+    #[test]
+    fn test_case() {
+        let schema = Arc::new(Schema::try_new(vec![Field::new("a", DataType::Int64)]).unwrap());
+        let dataset = DataSet::try_from_rows(
+            schema.clone(),
+            vec![
+                vec![5i64.into()],
+                vec![20i64.into()],
+                vec![Scalar::Null],
+                vec![10i64.into()],
+            ],
+        )
+        .unwrap();
+        let eval = |sql: &str| {
+            let mut expr = crate::sql::parser::expr(sql)
+                .unwrap()
+                .1
+                .into_physical(schema.clone())?;
+            let array = expr.eval(&dataset)?;
+            assert_eq!(array.data_type(), expr.data_type());
+            Ok::<_, anyhow::Error>(
+                (0..array.len())
+                    .map(|row| array.scalar_value(row))
+                    .collect::<Vec<_>>(),
+            )
+        };
+
+        assert_eq!(
+            eval("case when a > 10 then 'hi' else 'lo' end").unwrap(),
+            vec!["lo".into(), "hi".into(), "lo".into(), "lo".into()]
+        );
+
+        // the first satisfied branch is selected, and the result is null without the else clause
+        assert_eq!(
+            eval("case when a > 10 then 1 when a > 1 then 2.5 end").unwrap(),
+            vec![2.5.into(), 1.0.into(), Scalar::Null, 2.5.into()]
+        );
+
+        assert_eq!(
+            eval("case a when 5 then 'five' when 10 then 'ten' end").unwrap(),
+            vec!["five".into(), Scalar::Null, Scalar::Null, "ten".into()]
+        );
+
+        // the values of the branches are taken from their own rows
+        assert_eq!(
+            eval("case when a > 10 then a when a > 1 then a * 2 else 0 end").unwrap(),
+            vec![10i64.into(), 20i64.into(), 0i64.into(), 20i64.into()]
+        );
+
+        assert_eq!(
+            eval("case when a > 10 then 'hi' else 1 end")
+                .unwrap_err()
+                .to_string(),
+            "the branches of the case expression have incompatible types: string and int8"
+        );
+        assert_eq!(
+            eval("case when a then 1 end").unwrap_err().to_string(),
+            "the when clause of the case expression must return a boolean type, but it returns int64"
+        );
+    }
+
+    #[test]
+    fn test_cast() {
+        let schema = Arc::new(Schema::try_new(vec![Field::new("a", DataType::Float64)]).unwrap());
+        let dataset = DataSet::try_from_rows(
+            schema.clone(),
+            vec![
+                vec![1.5f64.into()],
+                vec![Scalar::Null],
+                vec![300.0f64.into()],
+            ],
+        )
+        .unwrap();
+
+        let mut expr = crate::sql::parser::expr("a::int64 + 1")
+            .unwrap()
+            .1
+            .into_physical(schema.clone())
+            .unwrap();
+        assert_eq!(expr.data_type(), DataType::Int64);
+        assert_eq!(
+            expr.eval(&dataset).unwrap().downcast_ref::<Int64Array>(),
+            &Int64Array::from_opt_vec(vec![Some(2), None, Some(301)])
+        );
+
+        let null_schema = Arc::new(Schema::try_new(vec![Field::new("n", DataType::Null)]).unwrap());
+        let mut expr = col("n")
+            .cast(DataType::Int64)
+            .into_physical(null_schema.clone())
+            .unwrap();
+        assert_eq!(expr.data_type(), DataType::Int64);
+        assert_eq!(
+            expr.eval(&DataSet::try_from_rows(null_schema, vec![vec![Scalar::Null]]).unwrap())
+                .unwrap()
+                .downcast_ref::<Int64Array>(),
+            &Int64Array::from_opt_vec(vec![None])
+        );
+
+        assert_eq!(
+            col("a")
+                .cast(DataType::Timestamp(None))
+                .into_physical(schema)
+                .err()
+                .unwrap()
+                .to_string(),
+            "cannot cast type from 'float64' to 'timestamp'"
+        );
+    }
+
+    #[test]
+    fn test_named_arguments() {
+        let schema = Arc::new(Schema::try_new(vec![Field::new("a", DataType::Float64)]).unwrap());
+        let dataset = DataSet::try_from_rows(
+            schema.clone(),
+            vec![vec![1.25f64.into()], vec![2.5f64.into()]],
+        )
+        .unwrap();
+        let eval = |sql: &str| {
+            let mut expr = crate::sql::parser::expr(sql)
+                .unwrap()
+                .1
+                .into_physical(schema.clone())?;
+            let array = expr.eval(&dataset)?;
+            Ok::<_, anyhow::Error>(
+                (0..array.len())
+                    .map(|row| array.scalar_value(row))
+                    .collect::<Vec<_>>(),
+            )
+        };
+
+        let expected = eval("round(a, 1)").unwrap();
+        assert_eq!(eval("round(a, digits => 1)").unwrap(), expected);
+        assert_eq!(eval("round(DIGITS => 1, value => a)").unwrap(), expected);
+        assert_eq!(
+            eval("encode(typeof(a), from => 'utf8', to => 'hex')").unwrap(),
+            vec!["666c6f61743634".into(), "666c6f61743634".into()]
+        );
+
+        assert_eq!(
+            eval("round(a, precision => 1)").unwrap_err().to_string(),
+            "function 'round' has no parameter named 'precision'"
+        );
+        assert_eq!(
+            eval("sqrt(value => a)").unwrap_err().to_string(),
+            "function 'sqrt' has no parameter named 'value'"
+        );
+        assert_eq!(
+            eval("round(a, value => a)").unwrap_err().to_string(),
+            "the argument 'value' of function 'round' is specified more than once"
+        );
+        assert_eq!(
+            eval("round(digits => 1, a)").unwrap_err().to_string(),
+            "the positional arguments of function 'round' must come before the named arguments"
+        );
+        assert_eq!(
+            eval("encode(a, to => 'hex')").unwrap_err().to_string(),
+            "missing the argument 'from' of function 'encode'"
+        );
+        assert_eq!(
+            Expr::NamedArgument {
+                name: "digits".to_string(),
+                expr: Box::new(value(1i64)),
+            }
+            .into_physical(schema)
+            .err()
+            .unwrap()
+            .to_string(),
+            "the named argument 'digits' can only be passed to a function"
+        );
+    }
+
+    #[test]
+    fn test_struct_field() {
+        let schema = Arc::new(
+            Schema::try_new(vec![Field::new(
+                "s",
+                DataType::Struct(vec![
+                    Field::new("a", DataType::Int64),
+                    Field::new("b", DataType::String),
+                ]),
+            )])
+            .unwrap(),
+        );
+        let dataset = DataSet::try_from_rows(
+            schema.clone(),
+            vec![
+                vec![Scalar::Struct(vec![
+                    ("a".to_string(), 1i64.into()),
+                    ("b".to_string(), "x".into()),
+                ])],
+                vec![Scalar::Null],
+            ],
+        )
+        .unwrap();
+
+        let mut expr = qualified_col("s", "b")
+            .into_physical(schema.clone())
+            .unwrap();
+        assert_eq!(expr.data_type(), DataType::String);
+        assert_eq!(
+            expr.eval(&dataset).unwrap().downcast_ref::<StringArray>(),
+            &StringArray::from_opt_vec(vec![Some("x"), None])
+        );
+
+        let mut expr = (qualified_col("s", "A") + value(1i64))
+            .into_physical(schema.clone())
+            .unwrap();
+        assert_eq!(
+            expr.eval(&dataset).unwrap().downcast_ref::<Int64Array>(),
+            &Int64Array::from_opt_vec(vec![Some(2), None])
+        );
+
+        assert_eq!(
+            qualified_col("s", "c")
+                .into_physical(schema)
+                .err()
+                .unwrap()
+                .to_string(),
+            "not such column: 's.c'"
+        );
+    }
+}
@@ -8,13 +8,20 @@ mod execution;
 mod planner;
 mod sink_provider;
 mod source_provider;
+mod statistics;
+#[cfg(test)]
+mod testing;
 
 pub use dataframe::{dsl, DataFrame};
 pub use execution::execution_context::ExecutionMetrics;
 pub use execution::stream::DataStream;
+pub use planner::emit::Emit;
+pub use planner::join_type::JoinType;
+pub use planner::pivot::Pivot;
 pub use planner::window::{Period, Window};
 pub use sink_provider::{BoxSink, Sink, SinkProvider};
 pub use source_provider::{
     GenericSourceDataSet, GenericSourceProvider, SourceProvider, SourceProviderWrapper,
 };
+pub use statistics::{collect_statistics, ColumnStatistics};
 pub use yql_dataset::{array, dataset};
@@ -0,0 +1,17 @@
+use serde::{Deserialize, Serialize};
+
+/// Controls when an aggregation emits its results.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Emit {
+    /// Emits the results of a window once the window is closed.
+    Final,
+
+    /// Emits the current results of every updated group after each input.
+    Changes,
+}
+
+impl Default for Emit {
+    fn default() -> Self {
+        Emit::Final
+    }
+}
@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+
+/// Controls which rows without a match are kept by a join, the columns of the other side are
+/// null in these rows.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum JoinType {
+    /// Only keeps the rows that have a match.
+    #[default]
+    Inner,
+
+    /// Also keeps the rows of the left side without a match.
+    Left,
+
+    /// Also keeps the rows of the right side without a match, they are emitted when the left
+    /// side ends.
+    Right,
+
+    /// Keeps the rows of both sides without a match.
+    Full,
+}
@@ -1,12 +1,22 @@
 use crate::expr::Expr;
+use crate::planner::emit::Emit;
 use crate::planner::logical_plan::LogicalPlan;
+use crate::planner::pivot::Pivot;
 use crate::planner::window::Window;
 
 #[derive(Clone)]
 pub struct LogicalAggregatePlan {
     pub input: Box<LogicalPlan>,
     pub group_exprs: Vec<Expr>,
+    /// The aggregate expressions, each of them may carry its own window with `Expr::Over`.
     pub aggr_exprs: Vec<Expr>,
+    pub pivot: Option<Box<Pivot>>,
     pub watermark_expr: Option<Expr>,
     pub window: Window,
+    pub emit: Emit,
+    /// The sort keys of the rows emitted for each window, `true` means ascending.
+    pub order_by: Vec<(Expr, bool)>,
+    /// The condition of the rows emitted for each window, it can refer to the aggregations as well
+    /// as the columns of the results.
+    pub having: Option<Expr>,
 }
@@ -0,0 +1,16 @@
+use crate::expr::Expr;
+use crate::planner::logical_plan::LogicalPlan;
+use crate::JoinType;
+
+/// Joins the rows of the left input with the rows of the right input that have the same key.
+#[derive(Clone)]
+pub struct LogicalJoinPlan {
+    pub join_type: JoinType,
+    pub left: Box<LogicalPlan>,
+    pub right: Box<LogicalPlan>,
+    /// The two expressions that must be equal, one of them is evaluated on the left input and
+    /// the other one on the right input, in any order.
+    pub on: (Expr, Expr),
+    /// The interval in milliseconds after which the right input is read again.
+    pub refresh: Option<i64>,
+}
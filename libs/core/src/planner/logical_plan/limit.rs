@@ -0,0 +1,8 @@
+use crate::planner::logical_plan::LogicalPlan;
+
+#[derive(Clone)]
+pub struct LogicalLimitPlan {
+    pub input: Box<LogicalPlan>,
+    pub limit: usize,
+    pub offset: usize,
+}
@@ -0,0 +1,15 @@
+use crate::expr::Expr;
+use crate::planner::logical_plan::LogicalPlan;
+
+/// Appends the value of an expression evaluated on the first row of the right input that has
+/// the same key as each row of the left input, or null if there is no such row.
+#[derive(Clone)]
+pub struct LogicalLookupPlan {
+    pub left: Box<LogicalPlan>,
+    pub right: Box<LogicalPlan>,
+    /// The two expressions that must be equal, one of them is evaluated on the left input and
+    /// the other one on the right input, in any order.
+    pub on: (Expr, Expr),
+    /// The expression evaluated on the right input, its name is the name of the new column.
+    pub expr: Expr,
+}
@@ -1,12 +1,20 @@
 mod aggregate;
 mod filter;
+mod join;
+mod limit;
+mod lookup;
 mod projection;
 mod source;
+mod unnest;
 
 pub use aggregate::LogicalAggregatePlan;
 pub use filter::LogicalFilterPlan;
+pub use join::LogicalJoinPlan;
+pub use limit::LogicalLimitPlan;
+pub use lookup::LogicalLookupPlan;
 pub use projection::LogicalProjectionPlan;
 pub use source::LogicalSourcePlan;
+pub use unnest::LogicalUnnestPlan;
 
 #[derive(Clone)]
 pub enum LogicalPlan {
@@ -14,4 +22,8 @@ pub enum LogicalPlan {
     Projection(LogicalProjectionPlan),
     Filter(LogicalFilterPlan),
     Aggregate(LogicalAggregatePlan),
+    Limit(LogicalLimitPlan),
+    Join(LogicalJoinPlan),
+    Lookup(LogicalLookupPlan),
+    Unnest(LogicalUnnestPlan),
 }
@@ -0,0 +1,8 @@
+use crate::expr::Expr;
+use crate::planner::logical_plan::LogicalPlan;
+
+#[derive(Clone)]
+pub struct LogicalUnnestPlan {
+    pub input: Box<LogicalPlan>,
+    pub expr: Expr,
+}
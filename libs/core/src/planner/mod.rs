@@ -1,3 +1,7 @@
+pub mod emit;
+pub mod join_type;
 pub mod logical_plan;
+mod optimizer;
 pub mod physical_plan;
+pub mod pivot;
 pub mod window;
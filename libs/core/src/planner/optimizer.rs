@@ -0,0 +1,458 @@
+use std::collections::HashMap;
+
+use crate::expr::{BinaryOperator, Expr, UnaryOperator};
+use crate::planner::logical_plan::{LogicalFilterPlan, LogicalPlan, LogicalProjectionPlan};
+
+/// Fuses adjacent filters and projections so that a batch goes through fewer nodes, and orders the
+/// predicates of each filter by their estimated cost.
+pub(crate) fn optimize(plan: LogicalPlan) -> LogicalPlan {
+    match plan {
+        LogicalPlan::Source(_) => plan,
+        LogicalPlan::Projection(LogicalProjectionPlan { exprs, input }) => match optimize(*input) {
+            LogicalPlan::Projection(inner) => match fuse_projections(&exprs, &inner.exprs) {
+                Some(exprs) => LogicalPlan::Projection(LogicalProjectionPlan {
+                    exprs,
+                    input: inner.input,
+                }),
+                None => LogicalPlan::Projection(LogicalProjectionPlan {
+                    exprs,
+                    input: Box::new(LogicalPlan::Projection(inner)),
+                }),
+            },
+            input => LogicalPlan::Projection(LogicalProjectionPlan {
+                exprs,
+                input: Box::new(input),
+            }),
+        },
+        LogicalPlan::Filter(LogicalFilterPlan { expr, input }) => match optimize(*input) {
+            // the fused predicate may be evaluated on all rows, so the outer one must not fail on
+            // the rows that the inner one filters out
+            LogicalPlan::Filter(inner) if is_infallible(&expr) => {
+                LogicalPlan::Filter(LogicalFilterPlan {
+                    expr: reorder_predicates(inner.expr.and(expr)),
+                    input: inner.input,
+                })
+            }
+            input => LogicalPlan::Filter(LogicalFilterPlan {
+                expr: reorder_predicates(expr),
+                input: Box::new(input),
+            }),
+        },
+        LogicalPlan::Aggregate(mut aggregate) => {
+            aggregate.input = Box::new(optimize(*aggregate.input));
+            LogicalPlan::Aggregate(aggregate)
+        }
+        LogicalPlan::Limit(mut limit) => {
+            limit.input = Box::new(optimize(*limit.input));
+            LogicalPlan::Limit(limit)
+        }
+        LogicalPlan::Join(mut join) => {
+            join.left = Box::new(optimize(*join.left));
+            join.right = Box::new(optimize(*join.right));
+            LogicalPlan::Join(join)
+        }
+        LogicalPlan::Lookup(mut lookup) => {
+            lookup.left = Box::new(optimize(*lookup.left));
+            lookup.right = Box::new(optimize(*lookup.right));
+            LogicalPlan::Lookup(lookup)
+        }
+        LogicalPlan::Unnest(mut unnest) => {
+            unnest.input = Box::new(optimize(*unnest.input));
+            LogicalPlan::Unnest(unnest)
+        }
+    }
+}
+
+/// Splits the operands of the `AND`s at the top of the expression, in order.
+pub(crate) fn split_conjunction(expr: Expr) -> Vec<Expr> {
+    match expr {
+        Expr::Binary {
+            op: BinaryOperator::And,
+            lhs,
+            rhs,
+        } => {
+            let mut predicates = split_conjunction(*lhs);
+            predicates.extend(split_conjunction(*rhs));
+            predicates
+        }
+        _ => vec![expr],
+    }
+}
+
+/// Orders the predicates of the conjunction by their estimated cost, the filter evaluates each of
+/// them only on the rows kept by the previous ones.
+///
+/// The predicates with stateful calls are kept in order, because they are evaluated together on
+/// all the rows and their states are saved in that order.
+fn reorder_predicates(expr: Expr) -> Expr {
+    let mut predicates = split_conjunction(expr);
+    if !predicates.iter().any(contains_stateful_call) {
+        predicates.sort_by_key(estimate_cost);
+    }
+    let mut predicates = predicates.into_iter();
+    let first = predicates.next().expect("at least one predicate");
+    predicates.fold(first, Expr::and)
+}
+
+fn sub_exprs(expr: &Expr) -> Vec<&Expr> {
+    match expr {
+        Expr::Literal(_) | Expr::Column { .. } | Expr::Wildcard { .. } | Expr::SubQuery(_) => {
+            Vec::new()
+        }
+        Expr::Binary { lhs, rhs, .. } => vec![lhs, rhs],
+        Expr::Unary { expr, .. }
+        | Expr::Alias(expr, _)
+        | Expr::Over { expr, .. }
+        | Expr::Cast { expr, .. }
+        | Expr::NamedArgument { expr, .. }
+        | Expr::Distinct(expr) => vec![expr],
+        Expr::Call { args, .. } => args.iter().collect(),
+        Expr::InList { expr, list, .. } => std::iter::once(&**expr).chain(list).collect(),
+        Expr::Between {
+            expr, low, high, ..
+        } => vec![expr, low, high],
+        Expr::Case {
+            operand,
+            when_then,
+            else_expr,
+        } => operand
+            .as_deref()
+            .into_iter()
+            .chain(when_then.iter().flat_map(|(when, then)| vec![when, then]))
+            .chain(else_expr.as_deref())
+            .collect(),
+    }
+}
+
+fn contains_stateful_call(expr: &Expr) -> bool {
+    matches!(expr, Expr::Over { .. } | Expr::SubQuery(_))
+        || expr.is_stateful_call()
+        || sub_exprs(expr).into_iter().any(contains_stateful_call)
+}
+
+/// Estimates the relative cost of evaluating the expression.
+///
+/// An equality is ranked before the other comparisons, because it usually also keeps the fewest
+/// rows. Pattern matching and function calls are the most expensive.
+fn estimate_cost(expr: &Expr) -> usize {
+    let cost = match expr {
+        Expr::Literal(_) | Expr::Column { .. } | Expr::Wildcard { .. } => 0,
+        Expr::Binary { op, .. } => match op {
+            BinaryOperator::And | BinaryOperator::Or => 0,
+            BinaryOperator::Eq => 1,
+            BinaryOperator::Like | BinaryOperator::NotLike => 20,
+            _ => 2,
+        },
+        Expr::Unary { .. } | Expr::InList { .. } | Expr::Between { .. } | Expr::Case { .. } => 2,
+        Expr::Cast { .. } => 5,
+        Expr::Call { .. } => 10,
+        Expr::SubQuery(_) => 100,
+        Expr::Alias(..) | Expr::Over { .. } | Expr::NamedArgument { .. } | Expr::Distinct(_) => 0,
+    };
+    cost + sub_exprs(expr)
+        .into_iter()
+        .map(estimate_cost)
+        .sum::<usize>()
+}
+
+/// Returns `true` if evaluating the expression can't fail at runtime.
+fn is_infallible(expr: &Expr) -> bool {
+    match expr {
+        Expr::Literal(_) | Expr::Column { .. } => true,
+        Expr::Binary { op, lhs, rhs } => {
+            matches!(
+                op,
+                BinaryOperator::And
+                    | BinaryOperator::Or
+                    | BinaryOperator::Eq
+                    | BinaryOperator::NotEq
+                    | BinaryOperator::Lt
+                    | BinaryOperator::LtEq
+                    | BinaryOperator::Gt
+                    | BinaryOperator::GtEq
+            ) && is_infallible(lhs)
+                && is_infallible(rhs)
+        }
+        Expr::Unary {
+            op: UnaryOperator::Not,
+            expr,
+        } => is_infallible(expr),
+        Expr::Alias(expr, _) => is_infallible(expr),
+        _ => false,
+    }
+}
+
+/// Rewrites the outer projection to be evaluated directly on the input of the inner one.
+///
+/// Returns `None` if some column can't be resolved unambiguously, or if a computed field of the
+/// inner projection would have to be evaluated more than once.
+fn fuse_projections(outer: &[Expr], inner: &[Expr]) -> Option<Vec<Expr>> {
+    let mut fields = HashMap::new();
+    for expr in inner {
+        if let Expr::Wildcard { .. } = expr {
+            return None;
+        }
+        let name = expr.create_name().to_ascii_lowercase();
+        let expr = match expr {
+            Expr::Alias(expr, _) => expr.as_ref(),
+            _ => expr,
+        };
+        if fields.insert(name, (expr, 0)).is_some() {
+            return None;
+        }
+    }
+
+    let mut exprs = Vec::with_capacity(outer.len());
+    for expr in outer {
+        let name = expr.create_name();
+        let new_expr = replace_columns(expr, &mut fields)?;
+        exprs.push(match new_expr {
+            Expr::Alias(..) => new_expr,
+            _ if new_expr.create_name() == name => new_expr,
+            _ => new_expr.alias(name),
+        });
+    }
+
+    if fields
+        .values()
+        .any(|(expr, count)| *count > 1 && !matches!(expr, Expr::Column { .. } | Expr::Literal(_)))
+    {
+        return None;
+    }
+    Some(exprs)
+}
+
+fn replace_columns(expr: &Expr, fields: &mut HashMap<String, (&Expr, usize)>) -> Option<Expr> {
+    Some(match expr {
+        Expr::Literal(_) => expr.clone(),
+        Expr::Column {
+            qualifier: None,
+            name,
+        } => {
+            let (expr, count) = fields.get_mut(&name.to_ascii_lowercase())?;
+            *count += 1;
+            (*expr).clone()
+        }
+        Expr::Column { .. } | Expr::Wildcard { .. } | Expr::Over { .. } | Expr::SubQuery(_) => {
+            return None
+        }
+        Expr::Binary { op, lhs, rhs } => Expr::Binary {
+            op: *op,
+            lhs: Box::new(replace_columns(lhs, fields)?),
+            rhs: Box::new(replace_columns(rhs, fields)?),
+        },
+        Expr::Unary { op, expr } => Expr::Unary {
+            op: *op,
+            expr: Box::new(replace_columns(expr, fields)?),
+        },
+        Expr::Call {
+            namespace,
+            name,
+            args,
+        } => Expr::Call {
+            namespace: namespace.clone(),
+            name: name.clone(),
+            args: args
+                .iter()
+                .map(|arg| replace_columns(arg, fields))
+                .collect::<Option<_>>()?,
+        },
+        Expr::Alias(expr, name) => {
+            Expr::Alias(Box::new(replace_columns(expr, fields)?), name.clone())
+        }
+        Expr::Cast { expr, data_type } => Expr::Cast {
+            expr: Box::new(replace_columns(expr, fields)?),
+            data_type: data_type.clone(),
+        },
+        Expr::NamedArgument { name, expr } => Expr::NamedArgument {
+            name: name.clone(),
+            expr: Box::new(replace_columns(expr, fields)?),
+        },
+        Expr::Distinct(expr) => Expr::Distinct(Box::new(replace_columns(expr, fields)?)),
+        Expr::InList {
+            expr,
+            list,
+            negated,
+        } => Expr::InList {
+            expr: Box::new(replace_columns(expr, fields)?),
+            list: list
+                .iter()
+                .map(|element| replace_columns(element, fields))
+                .collect::<Option<_>>()?,
+            negated: *negated,
+        },
+        Expr::Between {
+            expr,
+            low,
+            high,
+            negated,
+        } => Expr::Between {
+            expr: Box::new(replace_columns(expr, fields)?),
+            low: Box::new(replace_columns(low, fields)?),
+            high: Box::new(replace_columns(high, fields)?),
+            negated: *negated,
+        },
+        Expr::Case {
+            operand,
+            when_then,
+            else_expr,
+        } => Expr::Case {
+            operand: match operand {
+                Some(operand) => Some(Box::new(replace_columns(operand, fields)?)),
+                None => None,
+            },
+            when_then: when_then
+                .iter()
+                .map(|(when, then)| {
+                    Some((
+                        replace_columns(when, fields)?,
+                        replace_columns(then, fields)?,
+                    ))
+                })
+                .collect::<Option<_>>()?,
+            else_expr: match else_expr {
+                Some(else_expr) => Some(Box::new(replace_columns(else_expr, fields)?)),
+                None => None,
+            },
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::array::DataType;
+    use crate::dataset::{Field, Schema};
+    use crate::dsl::*;
+    use crate::planner::logical_plan::LogicalSourcePlan;
+    use crate::planner::physical_plan::{PhysicalNode, PhysicalPlan};
+    use crate::sources::csv::{Options, Provider};
+    use crate::SourceProviderWrapper;
+
+    fn source() -> LogicalPlan {
+        let schema = Arc::new(
+            Schema::try_new(vec![
+                Field::new("time", DataType::Timestamp(None)),
+                Field::new("a", DataType::Int64),
+            ])
+            .unwrap(),
+        );
+        let provider = Provider::new_from_memory(
+            Options {
+                delimiter: b',',
+                has_header: false,
+                batch_size: 10,
+                tail: false,
+                poll_interval: 1000,
+            },
+            schema,
+            "1622512140000,1\n",
+        );
+        LogicalPlan::Source(LogicalSourcePlan {
+            qualifier: None,
+            source_provider: Arc::new(SourceProviderWrapper(provider)),
+            time_expr: Some(col("time")),
+        })
+    }
+
+    fn filter(input: LogicalPlan, expr: Expr) -> LogicalPlan {
+        LogicalPlan::Filter(LogicalFilterPlan {
+            input: Box::new(input),
+            expr,
+        })
+    }
+
+    fn projection(input: LogicalPlan, exprs: Vec<Expr>) -> LogicalPlan {
+        LogicalPlan::Projection(LogicalProjectionPlan {
+            input: Box::new(input),
+            exprs,
+        })
+    }
+
+    #[test]
+    fn test_fuse_filters() {
+        let plan = filter(
+            filter(
+                filter(source(), col("a").gt(value(1))),
+                col("a").lt(value(10)),
+            ),
+            col("a").not_eq(value(5)),
+        );
+        match optimize(plan) {
+            LogicalPlan::Filter(LogicalFilterPlan { expr, input }) => {
+                assert_eq!(
+                    expr,
+                    col("a")
+                        .gt(value(1))
+                        .and(col("a").lt(value(10)))
+                        .and(col("a").not_eq(value(5)))
+                );
+                assert!(matches!(*input, LogicalPlan::Source(_)));
+            }
+            _ => panic!("expected a filter"),
+        }
+
+        let plan = filter(
+            filter(source(), col("a").gt(value(1))),
+            col("a").lt(value(10)),
+        );
+        match PhysicalPlan::try_new(plan).unwrap().root {
+            PhysicalNode::Filter(filter) => {
+                assert!(matches!(*filter.input, PhysicalNode::Source(_)))
+            }
+            _ => panic!("expected a filter"),
+        }
+
+        // the outer predicate may fail on the rows filtered out by the inner one
+        let plan = filter(
+            filter(source(), col("a").not_eq(value(0))),
+            (value(10) / col("a")).gt(value(1)),
+        );
+        match optimize(plan) {
+            LogicalPlan::Filter(LogicalFilterPlan { input, .. }) => {
+                assert!(matches!(*input, LogicalPlan::Filter(_)))
+            }
+            _ => panic!("expected a filter"),
+        }
+    }
+
+    #[test]
+    fn test_fuse_projections() {
+        let plan = projection(
+            projection(
+                source(),
+                vec![col("time"), (col("a") + value(1)).alias("b")],
+            ),
+            vec![col("time").alias("t"), col("b") * value(2), col("time")],
+        );
+        match optimize(plan) {
+            LogicalPlan::Projection(LogicalProjectionPlan { exprs, input }) => {
+                assert!(matches!(*input, LogicalPlan::Source(_)));
+                assert_eq!(exprs.len(), 3);
+                assert_eq!(exprs[0], col("time").alias("t"));
+                assert_eq!(
+                    exprs[1],
+                    ((col("a") + value(1)) * value(2)).alias("(b * 2)")
+                );
+                assert_eq!(exprs[2], col("time"));
+            }
+            _ => panic!("expected a projection"),
+        }
+    }
+
+    #[test]
+    fn test_fuse_projections_computed_twice() {
+        let plan = projection(
+            projection(source(), vec![(col("a") + value(1)).alias("b")]),
+            vec![col("b"), col("b") * value(2)],
+        );
+        match optimize(plan) {
+            LogicalPlan::Projection(LogicalProjectionPlan { input, .. }) => {
+                assert!(matches!(*input, LogicalPlan::Projection(_)))
+            }
+            _ => panic!("expected a projection"),
+        }
+    }
+}
@@ -1,5 +1,7 @@
+use crate::array::compute::SortOptions;
 use crate::dataset::SchemaRef;
 use crate::expr::physical_expr::PhysicalExpr;
+use crate::planner::emit::Emit;
 use crate::planner::physical_plan::PhysicalNode;
 use crate::planner::window::Window;
 
@@ -7,9 +9,29 @@ use crate::planner::window::Window;
 pub struct PhysicalAggregateNode {
     pub id: usize,
     pub schema: SchemaRef,
+    /// The schema of the results before the columns only computed for `having` are removed.
+    pub results_schema: SchemaRef,
+    /// The schema of the input with the `@window_start` and `@window_end` columns appended, the
+    /// rows of each window are aggregated with it.
+    pub window_schema: SchemaRef,
     pub group_exprs: Vec<PhysicalExpr>,
     pub aggr_exprs: Vec<PhysicalExpr>,
+    /// The `pivot_filters.len()` aggregate expressions before the hidden ones are the columns of
+    /// the pivot, each of them is only evaluated on the rows matching the respective filter.
+    pub pivot_filters: Vec<PhysicalExpr>,
+    /// The number of the last aggregate expressions that are only computed for `having`, their
+    /// columns are removed from the results after filtering.
+    pub num_hidden_exprs: usize,
     pub window: Window,
+    pub emit: Emit,
+    /// The sort keys of the rows emitted for each window, evaluated on the results.
+    pub order_by: Vec<(PhysicalExpr, SortOptions)>,
+    /// The number of the sorted rows emitted for each window, `None` if all of them are emitted.
+    pub limit: Option<usize>,
+    /// The number of the sorted rows skipped for each window before the limit.
+    pub offset: usize,
+    /// The condition of the rows emitted for each window, evaluated on the results.
+    pub having: Option<PhysicalExpr>,
     pub time_idx: usize,
     pub watermark_expr: Option<PhysicalExpr>,
     pub input: Box<PhysicalNode>,
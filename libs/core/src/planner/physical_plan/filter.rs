@@ -6,6 +6,8 @@ use crate::planner::physical_plan::PhysicalNode;
 pub struct PhysicalFilterNode {
     pub id: usize,
     pub schema: SchemaRef,
-    pub expr: PhysicalExpr,
+    /// The predicates of the conjunction, each of them is evaluated on the rows kept by the
+    /// previous ones. There is only a single predicate if any of them is stateful.
+    pub predicates: Vec<PhysicalExpr>,
     pub input: Box<PhysicalNode>,
 }
@@ -0,0 +1,18 @@
+use crate::dataset::SchemaRef;
+use crate::expr::physical_expr::PhysicalExpr;
+use crate::planner::physical_plan::PhysicalNode;
+use crate::JoinType;
+
+#[derive(Clone)]
+pub struct PhysicalJoinNode {
+    pub id: usize,
+    pub schema: SchemaRef,
+    pub join_type: JoinType,
+    pub left_key: PhysicalExpr,
+    pub right_key: PhysicalExpr,
+    pub left: Box<PhysicalNode>,
+    /// The right input must be bounded, it is read to the end before the left input.
+    pub right: Box<PhysicalNode>,
+    /// The interval in milliseconds after which the right input is read again.
+    pub refresh: Option<i64>,
+}
@@ -0,0 +1,11 @@
+use crate::dataset::SchemaRef;
+use crate::planner::physical_plan::PhysicalNode;
+
+#[derive(Clone)]
+pub struct PhysicalLimitNode {
+    pub id: usize,
+    pub schema: SchemaRef,
+    pub limit: usize,
+    pub offset: usize,
+    pub input: Box<PhysicalNode>,
+}
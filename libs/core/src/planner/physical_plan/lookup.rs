@@ -0,0 +1,17 @@
+use crate::dataset::SchemaRef;
+use crate::expr::physical_expr::PhysicalExpr;
+use crate::planner::physical_plan::PhysicalNode;
+
+#[derive(Clone)]
+pub struct PhysicalLookupNode {
+    pub id: usize,
+    pub schema: SchemaRef,
+    pub left_key: PhysicalExpr,
+    pub right_key: PhysicalExpr,
+    /// Evaluated on the rows of the right input, the value of the first row of each key is
+    /// appended to the rows of the left input.
+    pub value: PhysicalExpr,
+    pub left: Box<PhysicalNode>,
+    /// The right input must be bounded, it is read to the end before the left input.
+    pub right: Box<PhysicalNode>,
+}
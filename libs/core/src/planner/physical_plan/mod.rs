@@ -1,17 +1,27 @@
 mod aggregate;
 mod filter;
+mod join;
+mod limit;
+mod lookup;
 mod projection;
 mod source;
 mod to_physical;
+mod unnest;
 
 use crate::dataset::SchemaRef;
 
 pub use aggregate::PhysicalAggregateNode;
 pub use filter::PhysicalFilterNode;
+pub use join::PhysicalJoinNode;
+pub use limit::PhysicalLimitNode;
+pub use lookup::PhysicalLookupNode;
 pub use projection::PhysicalProjectionNode;
 pub use source::PhysicalSourceNode;
+pub use unnest::PhysicalUnnestNode;
 
 pub const FIELD_TIME: &str = "@time";
+pub const FIELD_WINDOW_START: &str = "@window_start";
+pub const FIELD_WINDOW_END: &str = "@window_end";
 
 #[derive(Clone)]
 pub enum PhysicalNode {
@@ -19,6 +29,10 @@ pub enum PhysicalNode {
     Projection(PhysicalProjectionNode),
     Filter(PhysicalFilterNode),
     Aggregate(PhysicalAggregateNode),
+    Limit(PhysicalLimitNode),
+    Join(PhysicalJoinNode),
+    Lookup(PhysicalLookupNode),
+    Unnest(PhysicalUnnestNode),
 }
 
 impl PhysicalNode {
@@ -28,6 +42,24 @@ impl PhysicalNode {
             PhysicalNode::Projection(projection) => projection.schema.clone(),
             PhysicalNode::Filter(filter) => filter.schema.clone(),
             PhysicalNode::Aggregate(aggregate) => aggregate.schema.clone(),
+            PhysicalNode::Limit(limit) => limit.schema.clone(),
+            PhysicalNode::Join(join) => join.schema.clone(),
+            PhysicalNode::Lookup(lookup) => lookup.schema.clone(),
+            PhysicalNode::Unnest(unnest) => unnest.schema.clone(),
+        }
+    }
+
+    /// Returns `true` if the stream of this node ends.
+    pub fn is_bounded(&self) -> bool {
+        match self {
+            PhysicalNode::Source(source) => source.source_provider.is_bounded(),
+            PhysicalNode::Projection(projection) => projection.input.is_bounded(),
+            PhysicalNode::Filter(filter) => filter.input.is_bounded(),
+            PhysicalNode::Aggregate(aggregate) => aggregate.input.is_bounded(),
+            PhysicalNode::Limit(_) => true,
+            PhysicalNode::Join(join) => join.left.is_bounded(),
+            PhysicalNode::Lookup(lookup) => lookup.left.is_bounded(),
+            PhysicalNode::Unnest(unnest) => unnest.input.is_bounded(),
         }
     }
 }
@@ -3,17 +3,24 @@ use std::sync::Arc;
 use anyhow::Result;
 use itertools::Itertools;
 
+use crate::array::compute::SortOptions;
 use crate::array::DataType;
 use crate::dataset::{Field, Schema, SchemaRef};
 use crate::expr::physical_expr::PhysicalExpr;
 use crate::expr::Expr;
+use crate::planner::join_type::JoinType;
 use crate::planner::logical_plan::{
-    LogicalAggregatePlan, LogicalFilterPlan, LogicalPlan, LogicalProjectionPlan, LogicalSourcePlan,
+    LogicalAggregatePlan, LogicalFilterPlan, LogicalJoinPlan, LogicalLimitPlan, LogicalLookupPlan,
+    LogicalPlan, LogicalProjectionPlan, LogicalSourcePlan, LogicalUnnestPlan,
 };
+use crate::planner::optimizer;
 use crate::planner::physical_plan::{
-    PhysicalAggregateNode, PhysicalFilterNode, PhysicalNode, PhysicalPlan, PhysicalProjectionNode,
-    PhysicalSourceNode, FIELD_TIME,
+    PhysicalAggregateNode, PhysicalFilterNode, PhysicalJoinNode, PhysicalLimitNode,
+    PhysicalLookupNode, PhysicalNode, PhysicalPlan, PhysicalProjectionNode, PhysicalSourceNode,
+    PhysicalUnnestNode, FIELD_TIME, FIELD_WINDOW_END, FIELD_WINDOW_START,
 };
+use crate::planner::pivot::Pivot;
+use crate::planner::window::Window;
 
 struct Context {
     id: usize,
@@ -34,38 +41,55 @@ fn to_physical(ctx: &mut Context, plan: LogicalPlan) -> Result<PhysicalNode> {
         LogicalPlan::Projection(projection) => projection_to_physical(ctx, projection),
         LogicalPlan::Filter(filter) => filter_to_physical(ctx, filter),
         LogicalPlan::Aggregate(aggregate) => aggregate_to_physical(ctx, aggregate),
+        LogicalPlan::Limit(limit) => limit_to_physical(ctx, limit),
+        LogicalPlan::Join(join) => join_to_physical(ctx, join),
+        LogicalPlan::Lookup(lookup) => lookup_to_physical(ctx, lookup),
+        LogicalPlan::Unnest(unnest) => unnest_to_physical(ctx, unnest),
     }
 }
 
 fn source_to_physical(ctx: &mut Context, source: LogicalSourcePlan) -> Result<PhysicalNode> {
     let source_schema = source.source_provider.schema()?;
-    let schema = Arc::new(Schema::try_new(
+    let mut schema = Arc::new(Schema::try_new(
         source_schema
             .fields()
-            .to_vec()
-            .into_iter()
-            .chain(std::iter::once(Field {
-                qualifier: None,
-                name: FIELD_TIME.to_string(),
-                data_type: DataType::Timestamp(None),
-            }))
-            .map(|mut field| {
-                field.qualifier = source.qualifier.clone();
-                field
-            })
+            .iter()
+            .cloned()
+            .chain(std::iter::once(Field::new(
+                FIELD_TIME,
+                DataType::Timestamp(None),
+            )))
             .collect(),
     )?);
+    if let Some(qualifier) = &source.qualifier {
+        schema = schema.qualify(qualifier)?;
+    }
     Ok(PhysicalNode::Source(PhysicalSourceNode {
         id: ctx.take_id(),
         schema,
         source_provider: source.source_provider,
         time_expr: match source.time_expr {
-            Some(expr) => Some(expr.into_physical(source_schema)?),
+            Some(expr) => Some(time_to_physical("time", expr, source_schema)?),
             None => None,
         },
     }))
 }
 
+/// Converts the expression of the time or the watermark of the rows, which must return a timestamp
+/// or an integer of milliseconds since the unix epoch.
+fn time_to_physical(kind: &str, expr: Expr, schema: SchemaRef) -> Result<PhysicalExpr> {
+    let physical_expr = expr.clone().into_physical(schema)?;
+    let data_type = physical_expr.data_type();
+    anyhow::ensure!(
+        data_type.is_timestamp() || data_type.is_integer(),
+        "the {} expression '{}' must return a timestamp or an integer, but it returns {}.",
+        kind,
+        expr,
+        data_type
+    );
+    Ok(physical_expr)
+}
+
 fn projection_to_physical(
     ctx: &mut Context,
     projection: LogicalProjectionPlan,
@@ -82,16 +106,25 @@ fn projection_to_physical(
 
 fn filter_to_physical(ctx: &mut Context, filter: LogicalFilterPlan) -> Result<PhysicalNode> {
     let input = to_physical(ctx, *filter.input)?;
-    let expr = filter.expr.into_physical(input.schema())?;
+    let mut predicates = optimizer::split_conjunction(filter.expr.clone())
+        .into_iter()
+        .map(|expr| expr.into_physical(input.schema()))
+        .collect::<Result<Vec<_>>>()?;
+    // a stateful predicate must see all the rows, so the conjunction is evaluated as a whole
+    if predicates.len() > 1 && predicates.iter().any(PhysicalExpr::is_stateful) {
+        predicates = vec![filter.expr.into_physical(input.schema())?];
+    }
 
     anyhow::ensure!(
-        expr.data_type() == DataType::Boolean,
+        predicates
+            .iter()
+            .all(|expr| expr.data_type() == DataType::Boolean),
         "filter expression must return a boolean type."
     );
     Ok(PhysicalNode::Filter(PhysicalFilterNode {
         id: ctx.take_id(),
         schema: input.schema(),
-        expr,
+        predicates,
         input: Box::new(input),
     }))
 }
@@ -100,6 +133,43 @@ fn aggregate_to_physical(
     ctx: &mut Context,
     aggregate: LogicalAggregatePlan,
 ) -> Result<PhysicalNode> {
+    let window = aggregate.window;
+    window.validate()?;
+    let mut aggr_exprs = aggregate.aggr_exprs;
+    let mut pivot_filters = Vec::new();
+    if let Some(pivot) = aggregate.pivot {
+        for value in pivot.values {
+            aggr_exprs.push(pivot.aggr_expr.clone().alias(Pivot::column_name(&value)));
+            pivot_filters.push(pivot.pivot_expr.clone().eq(Expr::Literal(value)));
+        }
+    }
+    let mut aggr_exprs = aggr_exprs
+        .into_iter()
+        .map(|expr| match expr.window() {
+            Some(expr_window) if expr_window != window => Err(anyhow::anyhow!(
+                "aggregations with different windows are not supported: '{}' uses window '{}', but the query uses window '{}'",
+                expr.create_name(),
+                expr_window,
+                window
+            )),
+            _ => Ok(expr.without_window()),
+        })
+        .try_collect::<_, Vec<_>, _>()?;
+    // the aggregations in the having clause are replaced by the columns of the results, the ones
+    // that are not selected are appended as hidden columns
+    let mut hidden_exprs = Vec::new();
+    let having = aggregate.having.map(|expr| {
+        let fields = aggr_exprs
+            .iter()
+            .map(|expr| match expr {
+                Expr::Alias(expr, alias) => ((**expr).clone(), alias.clone()),
+                _ => (expr.clone(), expr.create_name()),
+            })
+            .collect_vec();
+        replace_aggregations(expr, &fields, &mut hidden_exprs)
+    });
+    let num_hidden_exprs = hidden_exprs.len();
+    aggr_exprs.extend(hidden_exprs);
     let input = to_physical(ctx, *aggregate.input)?;
     let (time_idx, timezone) = match input.schema().field(None, FIELD_TIME) {
         Some((idx, Field { data_type:DataType::Timestamp(timezone), .. })) => {
@@ -107,34 +177,427 @@ fn aggregate_to_physical(
         },
         _ => anyhow::bail!("A column whose name is '@time' and type is 'timestamp' is required to perform aggregation operations."),
     };
+    let window_schema = Arc::new(Schema::try_new(
+        input
+            .schema()
+            .fields()
+            .iter()
+            .cloned()
+            .chain(vec![
+                Field::new(FIELD_WINDOW_START, DataType::Timestamp(timezone)),
+                Field::new(FIELD_WINDOW_END, DataType::Timestamp(timezone)),
+            ])
+            .collect(),
+    )?);
 
     let group_exprs = aggregate
         .group_exprs
         .into_iter()
-        .map(|expr| expr.into_physical(input.schema()))
+        .map(|expr| expr.into_physical(window_schema.clone()))
         .try_collect()?;
-    let (aggr_exprs, schema) = select_expr(
-        aggregate.aggr_exprs,
-        input.schema(),
+    // the sort keys are evaluated on the results, so they refer to the aliases of the aggregations
+    let aliases = aggr_exprs
+        .iter()
+        .filter_map(|expr| match expr {
+            Expr::Alias(expr, alias) => Some(((**expr).clone(), alias.clone())),
+            _ => None,
+        })
+        .collect_vec();
+    let (aggr_exprs, results_schema) = select_expr(
+        aggr_exprs,
+        window_schema.clone(),
         vec![Field::new(FIELD_TIME, DataType::Timestamp(timezone))],
     )?;
+    // late rows may bridge two sessions of a group, and then the states of the sessions are merged
+    if let Window::Session { .. } = window {
+        for (expr, field) in aggr_exprs.iter().zip(results_schema.fields()) {
+            anyhow::ensure!(
+                expr.can_merge(),
+                "the aggregation '{}' is not supported in a session window, because its state can't be merged.",
+                field.name
+            );
+        }
+    }
+    let schema = if num_hidden_exprs > 0 {
+        let fields = results_schema.fields();
+        Arc::new(Schema::try_new(
+            fields[..fields.len() - num_hidden_exprs - 1]
+                .iter()
+                .chain(fields.last())
+                .cloned()
+                .collect(),
+        )?)
+    } else {
+        results_schema.clone()
+    };
+    let pivot_filters = pivot_filters
+        .into_iter()
+        .map(|expr| {
+            let physical_expr = expr.clone().into_physical(window_schema.clone())?;
+            // the filters are shared by all the groups, so they can't keep any state
+            anyhow::ensure!(
+                physical_expr.stateful_funcs.is_empty(),
+                "the pivot expression '{}' must not use stateful functions.",
+                expr
+            );
+            Ok(physical_expr)
+        })
+        .try_collect()?;
     let watermark_expr = match aggregate.watermark_expr {
-        Some(expr) => Some(expr.into_physical(input.schema())?),
+        Some(expr) => Some(time_to_physical("watermark", expr, input.schema())?),
+        None => None,
+    };
+    let order_by = aggregate
+        .order_by
+        .into_iter()
+        .map(|(expr, asc)| {
+            let name = match aliases.iter().find(|(aggr_expr, _)| *aggr_expr == expr) {
+                Some((_, alias)) => alias.clone(),
+                None => expr.create_name(),
+            };
+            let expr = match expr {
+                Expr::Column { .. } => expr,
+                _ if schema.field(None, &name).is_some() => Expr::Column {
+                    qualifier: None,
+                    name,
+                },
+                _ => expr,
+            };
+            let physical_expr = expr.clone().into_physical(schema.clone())?;
+            anyhow::ensure!(
+                physical_expr.stateful_funcs.is_empty(),
+                "the order by expression '{}' must not use stateful functions.",
+                expr
+            );
+            let options = if asc {
+                SortOptions::asc()
+            } else {
+                SortOptions::desc()
+            };
+            Ok((physical_expr, options))
+        })
+        .try_collect()?;
+    let having = match having {
+        Some(expr) => {
+            let physical_expr = expr.clone().into_physical(results_schema.clone())?;
+            anyhow::ensure!(
+                physical_expr.data_type() == DataType::Boolean,
+                "having expression must return a boolean type."
+            );
+            anyhow::ensure!(
+                physical_expr.stateful_funcs.is_empty(),
+                "the having expression '{}' must not use stateful functions.",
+                expr
+            );
+            Some(physical_expr)
+        }
         None => None,
     };
 
     Ok(PhysicalNode::Aggregate(PhysicalAggregateNode {
         id: ctx.take_id(),
         schema,
+        results_schema,
+        window_schema,
         group_exprs,
         aggr_exprs,
-        window: aggregate.window,
+        pivot_filters,
+        num_hidden_exprs,
+        window,
+        emit: aggregate.emit,
+        order_by,
+        limit: None,
+        offset: 0,
+        having,
         time_idx,
         watermark_expr,
         input: Box::new(input),
     }))
 }
 
+/// Replaces the aggregations in `expr` that are selected by the columns of the results, and the
+/// others by the columns of the hidden expressions appended to `hidden_exprs`.
+fn replace_aggregations(
+    expr: Expr,
+    fields: &[(Expr, String)],
+    hidden_exprs: &mut Vec<Expr>,
+) -> Expr {
+    if let Some((_, name)) = fields.iter().find(|(field, _)| *field == expr) {
+        return Expr::Column {
+            qualifier: None,
+            name: name.clone(),
+        };
+    }
+    if expr.is_stateful_call() {
+        let name = match hidden_exprs
+            .iter()
+            .find_map(|hidden_expr| match hidden_expr {
+                Expr::Alias(hidden_expr, name) if **hidden_expr == expr => Some(name.clone()),
+                _ => None,
+            }) {
+            Some(name) => name,
+            None => {
+                let name = format!("@having_{}", hidden_exprs.len());
+                hidden_exprs.push(expr.alias(name.clone()));
+                name
+            }
+        };
+        return Expr::Column {
+            qualifier: None,
+            name,
+        };
+    }
+
+    let mut replace = |expr: Box<Expr>| Box::new(replace_aggregations(*expr, fields, hidden_exprs));
+    match expr {
+        Expr::Binary { op, lhs, rhs } => Expr::Binary {
+            op,
+            lhs: replace(lhs),
+            rhs: replace(rhs),
+        },
+        Expr::Unary { op, expr } => Expr::Unary {
+            op,
+            expr: replace(expr),
+        },
+        Expr::Call {
+            namespace,
+            name,
+            args,
+        } => Expr::Call {
+            namespace,
+            name,
+            args: args
+                .into_iter()
+                .map(|arg| *replace(Box::new(arg)))
+                .collect(),
+        },
+        Expr::Cast { expr, data_type } => Expr::Cast {
+            expr: replace(expr),
+            data_type,
+        },
+        Expr::NamedArgument { name, expr } => Expr::NamedArgument {
+            name,
+            expr: replace(expr),
+        },
+        Expr::InList {
+            expr,
+            list,
+            negated,
+        } => Expr::InList {
+            expr: replace(expr),
+            list: list
+                .into_iter()
+                .map(|element| *replace(Box::new(element)))
+                .collect(),
+            negated,
+        },
+        Expr::Between {
+            expr,
+            low,
+            high,
+            negated,
+        } => Expr::Between {
+            expr: replace(expr),
+            low: replace(low),
+            high: replace(high),
+            negated,
+        },
+        Expr::Case {
+            operand,
+            when_then,
+            else_expr,
+        } => Expr::Case {
+            operand: operand.map(&mut replace),
+            when_then: when_then
+                .into_iter()
+                .map(|(when, then)| (*replace(Box::new(when)), *replace(Box::new(then))))
+                .collect(),
+            else_expr: else_expr.map(&mut replace),
+        },
+        _ => expr,
+    }
+}
+
+fn limit_to_physical(ctx: &mut Context, limit: LogicalLimitPlan) -> Result<PhysicalNode> {
+    let input = to_physical(ctx, *limit.input)?;
+
+    // the rows of each window are sorted separately, so the limit also applies to each window,
+    // and the aggregation only keeps the first rows of each window instead of sorting all of them
+    let input = match input {
+        PhysicalNode::Aggregate(mut aggregate)
+            if !aggregate.order_by.is_empty() && aggregate.limit.is_none() =>
+        {
+            aggregate.limit = Some(limit.limit);
+            aggregate.offset = limit.offset;
+            return Ok(PhysicalNode::Aggregate(aggregate));
+        }
+        input => input,
+    };
+    Ok(PhysicalNode::Limit(PhysicalLimitNode {
+        id: ctx.take_id(),
+        schema: input.schema(),
+        limit: limit.limit,
+        offset: limit.offset,
+        input: Box::new(input),
+    }))
+}
+
+fn join_to_physical(ctx: &mut Context, join: LogicalJoinPlan) -> Result<PhysicalNode> {
+    let left = to_physical(ctx, *join.left)?;
+    let right = to_physical(ctx, *join.right)?;
+    anyhow::ensure!(
+        right.is_bounded(),
+        "the right side of a join must be bounded, because it is read to the end before the left side."
+    );
+    anyhow::ensure!(
+        !matches!(join.join_type, JoinType::Right | JoinType::Full)
+            || left.is_bounded()
+            || join.refresh.is_some(),
+        "the left side of a right or full join must be bounded or the join must have a refresh interval, because the rows of the right side without a match are emitted when the left side ends or the right side is read again."
+    );
+    let (left_key, right_key) = join_keys(join.on, &left, &right)?;
+
+    // the time of a joined row is the time of the left row
+    let schema = Arc::new(Schema::try_new(
+        left.schema()
+            .fields()
+            .iter()
+            .chain(
+                right
+                    .schema()
+                    .fields()
+                    .iter()
+                    .filter(|field| field.name != FIELD_TIME),
+            )
+            .cloned()
+            .collect(),
+    )?);
+    Ok(PhysicalNode::Join(PhysicalJoinNode {
+        id: ctx.take_id(),
+        schema,
+        join_type: join.join_type,
+        left_key,
+        right_key,
+        left: Box::new(left),
+        right: Box::new(right),
+        refresh: join.refresh,
+    }))
+}
+
+fn lookup_to_physical(ctx: &mut Context, lookup: LogicalLookupPlan) -> Result<PhysicalNode> {
+    let left = to_physical(ctx, *lookup.left)?;
+    let right = to_physical(ctx, *lookup.right)?;
+    anyhow::ensure!(
+        right.is_bounded(),
+        "the source of a subquery must be bounded, because it is read to the end before the outer query."
+    );
+    let (left_key, right_key) = join_keys(lookup.on, &left, &right)?;
+
+    let name = lookup.expr.create_name();
+    let value = match lookup.expr {
+        Expr::Alias(expr, _) => *expr,
+        expr => expr,
+    }
+    .into_physical(right.schema())?;
+    let schema = Arc::new(Schema::try_new(
+        left.schema()
+            .fields()
+            .iter()
+            .cloned()
+            .chain(std::iter::once(Field::new(name, value.data_type())))
+            .collect(),
+    )?);
+    Ok(PhysicalNode::Lookup(PhysicalLookupNode {
+        id: ctx.take_id(),
+        schema,
+        left_key,
+        right_key,
+        value,
+        left: Box::new(left),
+        right: Box::new(right),
+    }))
+}
+
+fn unnest_to_physical(ctx: &mut Context, unnest: LogicalUnnestPlan) -> Result<PhysicalNode> {
+    let input = to_physical(ctx, *unnest.input)?;
+    let name = unnest.expr.create_name();
+    let expr = match unnest.expr {
+        Expr::Alias(expr, _) => *expr,
+        expr => expr,
+    };
+    let physical_expr = expr.clone().into_physical(input.schema())?;
+    let element_type = match physical_expr.data_type() {
+        DataType::List(element_type) => *element_type,
+        data_type => anyhow::bail!(
+            "the expression '{}' of UNNEST must return a list, but it returns {}.",
+            expr,
+            data_type
+        ),
+    };
+
+    // the element is appended after the columns of the input, which are repeated for each element
+    let schema = Arc::new(Schema::try_new(
+        input
+            .schema()
+            .fields()
+            .iter()
+            .cloned()
+            .chain(std::iter::once(Field::new(name, element_type)))
+            .collect(),
+    )?);
+    Ok(PhysicalNode::Unnest(PhysicalUnnestNode {
+        id: ctx.take_id(),
+        schema,
+        expr: physical_expr,
+        input: Box::new(input),
+    }))
+}
+
+/// Converts the condition of a join to the keys of the left and the right side.
+fn join_keys(
+    on: (Expr, Expr),
+    left: &PhysicalNode,
+    right: &PhysicalNode,
+) -> Result<(PhysicalExpr, PhysicalExpr)> {
+    // each expression of the condition may refer to either side
+    let (a, b) = on;
+    let (mut left_key, mut right_key, left_type, right_type) = match (
+        a.clone().into_physical(left.schema()),
+        b.clone().into_physical(right.schema()),
+    ) {
+        (Ok(left_expr), Ok(right_expr)) => (a, b, left_expr.data_type(), right_expr.data_type()),
+        _ => match (
+            b.clone().into_physical(left.schema()),
+            a.clone().into_physical(right.schema()),
+        ) {
+            (Ok(left_expr), Ok(right_expr)) => {
+                (b, a, left_expr.data_type(), right_expr.data_type())
+            }
+            _ => anyhow::bail!(
+                "the join condition '{} = {}' must compare an expression of the left side with an expression of the right side.",
+                a,
+                b
+            ),
+        },
+    };
+    // the integer keys are compared as 64-bit integers, so only a mix of integers and floats
+    // needs to be converted
+    if left_type != right_type && !(left_type.is_integer() && right_type.is_integer()) {
+        anyhow::ensure!(
+            left_type.is_numeric() && right_type.is_numeric(),
+            "the join keys have incompatible types: {} and {}",
+            left_type,
+            right_type
+        );
+        left_key = left_key.cast(DataType::Float64);
+        right_key = right_key.cast(DataType::Float64);
+    }
+    Ok((
+        left_key.into_physical(left.schema())?,
+        right_key.into_physical(right.schema())?,
+    ))
+}
+
 fn select_expr(
     exprs: Vec<Expr>,
     schema: SchemaRef,
@@ -167,9 +630,16 @@ fn select_expr(
             }
             _ => {
                 let field_name = expr.create_name();
+                // a plain column keeps the qualifier of the source it comes from
+                let qualifier = match &expr {
+                    Expr::Column { qualifier, name } => schema
+                        .field(qualifier.as_deref(), name)
+                        .and_then(|(_, field)| field.qualifier.clone()),
+                    _ => None,
+                };
                 let physical_expr = expr.into_physical(schema.clone())?;
                 fields.push(Field {
-                    qualifier: None,
+                    qualifier,
                     name: field_name,
                     data_type: physical_expr.data_type(),
                 });
@@ -186,7 +656,7 @@ fn select_expr(
 impl PhysicalPlan {
     pub fn try_new(plan: LogicalPlan) -> Result<PhysicalPlan> {
         let mut ctx = Context { id: 0 };
-        let root = to_physical(&mut ctx, plan)?;
+        let root = to_physical(&mut ctx, optimizer::optimize(plan))?;
         Ok(PhysicalPlan { root })
     }
 }
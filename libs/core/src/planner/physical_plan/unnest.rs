@@ -0,0 +1,12 @@
+use crate::dataset::SchemaRef;
+use crate::expr::physical_expr::PhysicalExpr;
+use crate::planner::physical_plan::PhysicalNode;
+
+#[derive(Clone)]
+pub struct PhysicalUnnestNode {
+    pub id: usize,
+    pub schema: SchemaRef,
+    /// The expression of the lists, each of their elements is output in its own row.
+    pub expr: PhysicalExpr,
+    pub input: Box<PhysicalNode>,
+}
@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+
+use crate::expr::{Expr, Literal};
+
+/// Turns the known values of an expression into columns of an aggregation.
+///
+/// Each value produces a column named after it, which holds the aggregate expression evaluated
+/// over the rows of the group where the pivot expression is equal to that value. The values must
+/// be known up front, since a stream can't discover them before emitting a window.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Pivot {
+    pub aggr_expr: Expr,
+    pub pivot_expr: Expr,
+    pub values: Vec<Literal>,
+}
+
+impl Pivot {
+    /// Returns the name of the column produced for a value.
+    pub(crate) fn column_name(value: &Literal) -> String {
+        match value {
+            Literal::String(value) => value.clone(),
+            _ => value.to_string(),
+        }
+    }
+}
@@ -1,3 +1,6 @@
+use std::fmt::{self, Display, Formatter};
+
+use anyhow::Result;
 use chrono::TimeZone;
 use chrono::{DateTime, Datelike, Duration};
 use chrono_tz::Tz;
@@ -48,11 +51,53 @@ impl Period {
     }
 }
 
+impl Display for Period {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Period::Day => f.write_str("day"),
+            Period::Week => f.write_str("week"),
+            Period::Month => f.write_str("month"),
+            Period::Year => f.write_str("year"),
+        }
+    }
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Window {
-    Fixed { length: i64 },
-    Sliding { length: i64, interval: i64 },
-    Period { period: Period },
+    Fixed {
+        length: i64,
+    },
+    Sliding {
+        length: i64,
+        interval: i64,
+    },
+    Period {
+        period: Period,
+    },
+    /// A session window closes after no rows of its group arrive for `gap` milliseconds.
+    ///
+    /// Every group has its own sessions, the rows of a group whose timestamps are less than `gap`
+    /// apart belong to the same session, and a session is completed when the watermark passes the
+    /// time of its last row plus `gap`. Late rows may bridge two sessions of a group, so only the
+    /// aggregations whose states can be merged are supported.
+    Session {
+        gap: i64,
+    },
+}
+
+impl Display for Window {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Window::Fixed { length } => write!(f, "fixed({} milliseconds)", length),
+            Window::Sliding { length, interval } => write!(
+                f,
+                "sliding({} milliseconds, {} milliseconds)",
+                length, interval
+            ),
+            Window::Period { period } => write!(f, "{}", period),
+            Window::Session { gap } => write!(f, "session({} milliseconds)", gap),
+        }
+    }
 }
 
 impl Window {
@@ -71,20 +116,62 @@ impl Window {
         Window::Period { period }
     }
 
+    #[inline]
+    pub fn session(gap: i64) -> Self {
+        Window::Session { gap }
+    }
+
+    /// Checks that the window lengths and intervals are valid.
+    pub fn validate(&self) -> Result<()> {
+        match self.invalid_reason() {
+            Some((reason, got)) => anyhow::bail!("{}, but got {}", reason, got),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns why the window is invalid and the invalid values, the reason is static so that the
+    /// parser can report it.
+    pub(crate) fn invalid_reason(&self) -> Option<(&'static str, String)> {
+        match *self {
+            Window::Fixed { length } if length <= 0 => Some((
+                "the length of a fixed window must be positive",
+                format!("{}ms", length),
+            )),
+            Window::Sliding { length, .. } if length <= 0 => Some((
+                "the length of a sliding window must be positive",
+                format!("{}ms", length),
+            )),
+            Window::Sliding { interval, .. } if interval <= 0 => Some((
+                "the interval of a sliding window must be positive",
+                format!("{}ms", interval),
+            )),
+            Window::Sliding { length, interval } if interval > length => Some((
+                "the interval of a sliding window must not be greater than its length",
+                format!("interval {}ms and length {}ms", interval, length),
+            )),
+            Window::Session { gap } if gap <= 0 => Some((
+                "the gap of a session window must be positive",
+                format!("{}ms", gap),
+            )),
+            _ => None,
+        }
+    }
+
     pub(crate) fn windows(self, timestamp: i64, tz: Tz) -> Vec<(i64, i64)> {
         match self {
             Window::Fixed { length } => {
-                let start = timestamp / length * length;
+                let start = timestamp.div_euclid(length) * length;
                 vec![(start, start + length)]
             }
             Window::Sliding { length, interval } => {
+                // the windows containing the timestamp start in the range `(timestamp - length, timestamp]`
+                let mut start = timestamp.div_euclid(interval) * interval;
                 let mut windows = Vec::new();
-                let mut time = timestamp / interval * interval;
-                let end_time = time + length;
-                while time < end_time {
-                    windows.push((time, time + length));
-                    time += interval;
+                while start > timestamp - length {
+                    windows.push((start, start + length));
+                    start -= interval;
                 }
+                windows.reverse();
                 windows
             }
             Window::Period { period } => {
@@ -92,6 +179,8 @@ impl Window {
                 let (start, end) = period.window(datetime);
                 vec![(start.timestamp_millis(), end.timestamp_millis())]
             }
+            // a single row opens a session, the sessions of close rows are merged by the caller
+            Window::Session { gap } => vec![(timestamp, timestamp + gap)],
         }
     }
 }
@@ -101,6 +190,69 @@ mod tests {
     use super::*;
     use chrono::NaiveDate;
 
+    #[test]
+    fn test_validate() {
+        assert!(Window::fixed(1000).validate().is_ok());
+        assert!(Window::sliding(5000, 1000).validate().is_ok());
+        assert!(Window::sliding(5000, 5000).validate().is_ok());
+        assert!(Window::period(Period::Day).validate().is_ok());
+        assert!(Window::session(30000).validate().is_ok());
+
+        assert_eq!(
+            Window::fixed(0).validate().unwrap_err().to_string(),
+            "the length of a fixed window must be positive, but got 0ms"
+        );
+        assert_eq!(
+            Window::sliding(1000, 5000).validate().unwrap_err().to_string(),
+            "the interval of a sliding window must not be greater than its length, but got interval 5000ms and length 1000ms"
+        );
+        assert!(Window::sliding(1000, 0).validate().is_err());
+        assert_eq!(
+            Window::session(0).validate().unwrap_err().to_string(),
+            "the gap of a session window must be positive, but got 0ms"
+        );
+    }
+
+    #[test]
+    fn test_fixed_windows() {
+        let tz = chrono_tz::UTC;
+
+        assert_eq!(Window::fixed(1000).windows(0, tz), vec![(0, 1000)]);
+        assert_eq!(Window::fixed(1000).windows(999, tz), vec![(0, 1000)]);
+        assert_eq!(Window::fixed(1000).windows(1000, tz), vec![(1000, 2000)]);
+        assert_eq!(Window::fixed(1000).windows(-500, tz), vec![(-1000, 0)]);
+    }
+
+    #[test]
+    fn test_sliding_windows() {
+        let tz = chrono_tz::UTC;
+
+        assert_eq!(
+            Window::sliding(3000, 1000).windows(5500, tz),
+            vec![(3000, 6000), (4000, 7000), (5000, 8000)]
+        );
+        assert_eq!(
+            Window::sliding(3000, 1000).windows(5000, tz),
+            vec![(3000, 6000), (4000, 7000), (5000, 8000)]
+        );
+        assert_eq!(
+            Window::sliding(3000, 1000).windows(6000, tz),
+            vec![(4000, 7000), (5000, 8000), (6000, 9000)]
+        );
+        assert_eq!(
+            Window::sliding(2500, 1000).windows(5500, tz),
+            vec![(4000, 6500), (5000, 7500)]
+        );
+        assert_eq!(
+            Window::sliding(1000, 1000).windows(5500, tz),
+            Window::fixed(1000).windows(5500, tz)
+        );
+        assert_eq!(
+            Window::sliding(2000, 1000).windows(-500, tz),
+            vec![(-2000, 0), (-1000, 1000)]
+        );
+    }
+
     #[test]
     fn test_period_day() {
         let tz = chrono_tz::Asia::Shanghai;
@@ -1,19 +1,55 @@
 use anyhow::Result;
 
-use crate::dataset::DataSet;
+use crate::dataset::{DataFormat, DataSet, TimestampFormat};
 use crate::{BoxSink, Sink, SinkProvider};
 
-struct ConsoleSink;
+struct ConsoleSink {
+    format: Option<DataFormat>,
+    /// Whether the header of CSV has been printed, it is only printed before the first rows.
+    header_printed: bool,
+}
+
+impl ConsoleSink {
+    /// Renders the dataset in the format of the sink, `None` if there is nothing to print.
+    fn render(&mut self, dataset: &DataSet) -> Result<Option<String>> {
+        let data = match self.format {
+            Some(format @ DataFormat::Csv { .. }) => {
+                let mut data = Vec::new();
+                if !self.header_printed {
+                    data = format.serialize_header(&dataset.schema())?;
+                    self.header_printed = true;
+                }
+                data.extend(format.serialize_lines(dataset, TimestampFormat::Rfc3339)?);
+                data
+            }
+            Some(format) => format.serialize(dataset)?,
+            None => return Ok(Some(dataset.display().to_string())),
+        };
+        let data = String::from_utf8(data)?;
+        let data = data.trim_end_matches('\n');
+        Ok(if data.is_empty() {
+            None
+        } else {
+            Some(data.to_string())
+        })
+    }
+}
 
 #[async_trait::async_trait]
 impl Sink for ConsoleSink {
     async fn send(&mut self, dataset: DataSet) -> Result<()> {
-        println!("{}", dataset.display());
+        if let Some(output) = self.render(&dataset)? {
+            println!("{}", output);
+        }
         Ok(())
     }
 }
 
-pub struct Console;
+/// Prints the datasets to the stdout, as tables if `format` is not set.
+#[derive(Default)]
+pub struct Console {
+    pub format: Option<DataFormat>,
+}
 
 impl SinkProvider for Console {
     fn provider_name(&self) -> &'static str {
@@ -21,6 +57,42 @@ impl SinkProvider for Console {
     }
 
     fn create(&self) -> Result<BoxSink> {
-        Ok(Box::new(ConsoleSink))
+        Ok(Box::new(ConsoleSink {
+            format: self.format,
+            header_printed: false,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::array::DataType;
+    use crate::dataset::{CsvOptions, Field, Schema};
+
+    #[test]
+    fn test_csv_header() {
+        let schema = Arc::new(Schema::try_new(vec![Field::new("a", DataType::Int64)]).unwrap());
+        let create_dataset =
+            |data: &[u8]| DataSet::from_csv_slice(schema.clone(), CsvOptions::default(), data);
+        let mut sink = ConsoleSink {
+            format: Some(DataFormat::Csv {
+                delimiter: b',',
+                header: true,
+            }),
+            header_printed: false,
+        };
+
+        assert_eq!(
+            sink.render(&create_dataset(b"1\n2\n").unwrap()).unwrap(),
+            Some("a\n1\n2".to_string())
+        );
+        assert_eq!(
+            sink.render(&create_dataset(b"3\n").unwrap()).unwrap(),
+            Some("3".to_string())
+        );
+        assert_eq!(sink.render(&create_dataset(b"").unwrap()).unwrap(), None);
     }
 }
@@ -0,0 +1,268 @@
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::dataset::{DataFormat, DataSet, TimestampFormat};
+use crate::{BoxSink, Sink, SinkProvider};
+
+const SEQ_PLACEHOLDER: &str = "{seq}";
+
+#[derive(Serialize, Deserialize)]
+pub struct Options {
+    #[serde(default)]
+    pub format: DataFormat,
+    #[serde(default)]
+    pub timestamp_format: TimestampFormat,
+    /// Starts a new file when the current file has at least this number of rows, 0 means no
+    /// limit.
+    #[serde(default)]
+    pub rows: usize,
+    /// Starts a new file when the current file has at least this number of bytes, 0 means no
+    /// limit.
+    #[serde(default)]
+    pub bytes: u64,
+    /// The name of the files, `{seq}` is replaced with the sequence number of the file.
+    #[serde(default = "default_filename")]
+    pub filename: String,
+}
+
+fn default_filename() -> String {
+    "out-{seq}.json".to_string()
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            format: DataFormat::default(),
+            timestamp_format: TimestampFormat::default(),
+            rows: 0,
+            bytes: 0,
+            filename: default_filename(),
+        }
+    }
+}
+
+/// Writes the datasets to a sequence of files under a directory.
+///
+/// A dataset is never split across files, so a file can exceed the limits of the options by the
+/// size of the last dataset written to it. The existing files are never overwritten, the sequence
+/// number of the first file is the first one whose file doesn't exist.
+pub struct Provider {
+    options: Options,
+    path: PathBuf,
+}
+
+impl Provider {
+    pub fn new(options: Options, path: impl AsRef<Path>) -> Self {
+        Self {
+            options,
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+}
+
+impl SinkProvider for Provider {
+    fn provider_name(&self) -> &'static str {
+        "file"
+    }
+
+    fn create(&self) -> Result<BoxSink> {
+        anyhow::ensure!(
+            self.options.filename.contains(SEQ_PLACEHOLDER),
+            "the filename template '{}' must contain '{}'",
+            self.options.filename,
+            SEQ_PLACEHOLDER
+        );
+        std::fs::create_dir_all(&self.path)
+            .with_context(|| format!("failed to create directory '{}'", self.path.display()))?;
+
+        Ok(Box::new(FileSink {
+            format: self.options.format,
+            timestamp_format: self.options.timestamp_format,
+            max_rows: self.options.rows,
+            max_bytes: self.options.bytes,
+            filename: self.options.filename.clone(),
+            path: self.path.clone(),
+            seq: 0,
+            current: None,
+        }))
+    }
+}
+
+struct CurrentFile {
+    file: File,
+    rows: usize,
+    bytes: u64,
+}
+
+struct FileSink {
+    format: DataFormat,
+    timestamp_format: TimestampFormat,
+    max_rows: usize,
+    max_bytes: u64,
+    filename: String,
+    path: PathBuf,
+    seq: usize,
+    current: Option<CurrentFile>,
+}
+
+impl FileSink {
+    fn is_full(&self, current: &CurrentFile) -> bool {
+        (self.max_rows > 0 && current.rows >= self.max_rows)
+            || (self.max_bytes > 0 && current.bytes >= self.max_bytes)
+    }
+
+    fn open_next_file(&mut self) -> Result<CurrentFile> {
+        loop {
+            let path = self.path.join(
+                self.filename
+                    .replace(SEQ_PLACEHOLDER, &self.seq.to_string()),
+            );
+            self.seq += 1;
+            if path.exists() {
+                continue;
+            }
+            let file = OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&path)
+                .with_context(|| format!("failed to create file '{}'", path.display()))?;
+            return Ok(CurrentFile {
+                file,
+                rows: 0,
+                bytes: 0,
+            });
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Sink for FileSink {
+    async fn send(&mut self, dataset: DataSet) -> Result<()> {
+        if dataset.is_empty() {
+            return Ok(());
+        }
+
+        let mut current = match self.current.take() {
+            Some(current) if !self.is_full(&current) => current,
+            _ => self.open_next_file()?,
+        };
+        let mut data = Vec::new();
+        if current.bytes == 0 {
+            data = self.format.serialize_header(&dataset.schema())?;
+        }
+        data.extend(
+            self.format
+                .serialize_lines(&dataset, self.timestamp_format)?,
+        );
+        // the data is synced to the disk before the next dataset is requested, so a crash loses
+        // at most the dataset being written
+        current.file.write_all(&data)?;
+        current.file.flush()?;
+        current.file.sync_data()?;
+        current.rows += dataset.len();
+        current.bytes += data.len() as u64;
+        self.current = Some(current);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::array::DataType;
+    use crate::dataset::{CsvOptions, Field, Schema};
+
+    fn create_dataset(data: &[u8]) -> DataSet {
+        let schema = Arc::new(
+            Schema::try_new(vec![
+                Field::new("a", DataType::Int64),
+                Field::new("b", DataType::String),
+            ])
+            .unwrap(),
+        );
+        DataSet::from_csv_slice(schema, CsvOptions::default(), data).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_rollover() {
+        let dir = tempfile::tempdir().unwrap();
+        let provider = Provider::new(
+            Options {
+                rows: 3,
+                ..Options::default()
+            },
+            dir.path(),
+        );
+
+        let mut sink = provider.create().unwrap();
+        sink.send(create_dataset(b"1,a\n2,b\n")).await.unwrap();
+        sink.send(create_dataset(b"3,c\n4,d\n")).await.unwrap();
+        sink.send(create_dataset(b"5,e\n")).await.unwrap();
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("out-0.json")).unwrap(),
+            "{\"a\":1,\"b\":\"a\"}\n{\"a\":2,\"b\":\"b\"}\n{\"a\":3,\"b\":\"c\"}\n{\"a\":4,\"b\":\"d\"}\n"
+        );
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("out-1.json")).unwrap(),
+            "{\"a\":5,\"b\":\"e\"}\n"
+        );
+
+        // a new sink doesn't overwrite the existing files
+        let mut sink = provider.create().unwrap();
+        sink.send(create_dataset(b"6,f\n")).await.unwrap();
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("out-2.json")).unwrap(),
+            "{\"a\":6,\"b\":\"f\"}\n"
+        );
+    }
+
+    #[test]
+    fn test_invalid_filename() {
+        let dir = tempfile::tempdir().unwrap();
+        let provider = Provider::new(
+            Options {
+                filename: "out.json".to_string(),
+                ..Options::default()
+            },
+            dir.path(),
+        );
+        assert!(provider.create().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_csv() {
+        let dir = tempfile::tempdir().unwrap();
+        let provider = Provider::new(
+            Options {
+                format: DataFormat::Csv {
+                    delimiter: b',',
+                    header: true,
+                },
+                rows: 2,
+                filename: "out-{seq}.csv".to_string(),
+                ..Options::default()
+            },
+            dir.path(),
+        );
+
+        // the header is written once at the start of each file
+        let mut sink = provider.create().unwrap();
+        sink.send(create_dataset(b"1,a\n")).await.unwrap();
+        sink.send(create_dataset(b"2,\"b,c\"\n")).await.unwrap();
+        sink.send(create_dataset(b"3,d\n")).await.unwrap();
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("out-0.csv")).unwrap(),
+            "a,b\n1,a\n2,\"b,c\"\n"
+        );
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("out-1.csv")).unwrap(),
+            "a,b\n3,d\n"
+        );
+    }
+}
@@ -1,3 +1,4 @@
 mod console;
+pub mod file;
 
 pub use console::Console;
@@ -22,6 +22,12 @@ pub trait GenericSourceProvider: Send + Sync + 'static {
 
     fn schema(&self) -> Result<SchemaRef>;
 
+    /// Returns `true` if the stream of this source ends, e.g. it reads a file once instead of
+    /// waiting for new data.
+    fn is_bounded(&self) -> bool {
+        false
+    }
+
     fn create_stream(
         &self,
         state: Option<Self::State>,
@@ -43,6 +49,10 @@ impl<T: GenericSourceProvider> GenericSourceProvider for SourceProviderWrapper<T
         self.0.schema()
     }
 
+    fn is_bounded(&self) -> bool {
+        self.0.is_bounded()
+    }
+
     fn create_stream(
         &self,
         state: Option<Self::State>,
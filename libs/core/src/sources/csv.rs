@@ -1,4 +1,7 @@
+use std::fs::File;
+use std::io::{Cursor, Read, Seek, SeekFrom};
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 use anyhow::Result;
 use futures_util::stream::BoxStream;
@@ -6,10 +9,9 @@ use serde::{Deserialize, Serialize};
 
 use crate::dataset::{CsvOptions, SchemaRef};
 use crate::{GenericSourceDataSet, GenericSourceProvider};
-use std::fs::File;
-use std::io::{Cursor, Read};
 
 const DEFAULT_BATCH_SIZE: usize = 1000000;
+const DEFAULT_POLL_INTERVAL: u64 = 1000;
 
 #[derive(Serialize, Deserialize)]
 pub struct Options {
@@ -19,6 +21,12 @@ pub struct Options {
     pub has_header: bool,
     #[serde(default = "default_batch_size")]
     pub batch_size: usize,
+    /// Wait for new rows appended to the file instead of ending the stream at EOF.
+    #[serde(default)]
+    pub tail: bool,
+    /// The interval in milliseconds to check the file for new rows when tailing.
+    #[serde(default = "default_poll_interval")]
+    pub poll_interval: u64,
 }
 
 fn default_delimiter() -> u8 {
@@ -29,6 +37,10 @@ fn default_batch_size() -> usize {
     DEFAULT_BATCH_SIZE
 }
 
+fn default_poll_interval() -> u64 {
+    DEFAULT_POLL_INTERVAL
+}
+
 enum DataFrom {
     Path(PathBuf),
     Data(Vec<u8>),
@@ -56,6 +68,72 @@ impl Provider {
             from: DataFrom::Data(data.into()),
         }
     }
+
+    fn create_tail_stream(
+        &self,
+        options: CsvOptions,
+        position: Option<usize>,
+    ) -> Result<BoxStream<'static, Result<GenericSourceDataSet<usize>>>> {
+        let path = match &self.from {
+            DataFrom::Path(path) => path.clone(),
+            DataFrom::Data(_) => anyhow::bail!("only the csv file can be tailed"),
+        };
+
+        let mut position = position.unwrap_or_default();
+        let (header, mut offset) = {
+            let mut reader = options.open_path(self.schema.clone(), &path)?;
+            let header_len = reader.byte_position()?;
+            reader.skip(position)?;
+            let mut header = vec![0; header_len as usize];
+            File::open(&path)?.read_exact(&mut header)?;
+            (header, reader.byte_position()?)
+        };
+
+        let schema = self.schema.clone();
+        let delimiter = self.options.delimiter;
+        let batch_size = self.options.batch_size;
+        let poll_interval = Duration::from_millis(self.options.poll_interval);
+        Ok(Box::pin(async_stream::try_stream! {
+            loop {
+                // Only the complete lines are read, the last line may still be being written.
+                // The header is prepended so that the columns are mapped by name.
+                let mut data = header.clone();
+                let mut file = File::open(&path)?;
+                file.seek(SeekFrom::Start(offset))?;
+                file.read_to_end(&mut data)?;
+                let len = data[header.len()..]
+                    .iter()
+                    .rposition(|b| *b == b'\n')
+                    .map(|idx| idx + 1)
+                    .unwrap_or_default();
+                if len == 0 {
+                    tokio::time::sleep(poll_interval).await;
+                    continue;
+                }
+                data.truncate(header.len() + len);
+                offset += len as u64;
+
+                let options = CsvOptions {
+                    delimiter,
+                    has_header: !header.is_empty(),
+                    ..CsvOptions::default()
+                };
+                let mut reader = options.open(schema.clone(), Cursor::new(data));
+                loop {
+                    let dataset = reader.read_batch(Some(batch_size))?;
+                    if dataset.is_empty() {
+                        break;
+                    }
+                    let count = dataset.len();
+                    yield GenericSourceDataSet {
+                        state: position + count,
+                        dataset,
+                    };
+                    position += count;
+                }
+            }
+        }))
+    }
 }
 
 impl GenericSourceProvider for Provider {
@@ -69,6 +147,10 @@ impl GenericSourceProvider for Provider {
         Ok(self.schema.clone())
     }
 
+    fn is_bounded(&self) -> bool {
+        !self.options.tail
+    }
+
     fn create_stream(
         &self,
         position: Option<Self::State>,
@@ -76,7 +158,12 @@ impl GenericSourceProvider for Provider {
         let options = CsvOptions {
             delimiter: self.options.delimiter,
             has_header: self.options.has_header,
+            ..CsvOptions::default()
         };
+        if self.options.tail {
+            return self.create_tail_stream(options, position);
+        }
+
         let mut reader = match &self.from {
             DataFrom::Path(path) => options.open(
                 self.schema.clone(),
@@ -111,3 +198,71 @@ impl GenericSourceProvider for Provider {
         }))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+    use std::sync::Arc;
+
+    use futures_util::StreamExt;
+
+    use super::*;
+    use crate::array::DataType;
+    use crate::dataset::{DataSet, Field, Schema};
+
+    #[tokio::test]
+    async fn test_tail() {
+        let schema = Arc::new(
+            Schema::try_new(vec![
+                Field::new("a", DataType::Int64),
+                Field::new("b", DataType::String),
+            ])
+            .unwrap(),
+        );
+        let dataset = |data: &[u8]| {
+            DataSet::from_csv_slice(schema.clone(), CsvOptions::default(), data).unwrap()
+        };
+
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        file.write_all(b"b,a\na,1\nb,2\n").unwrap();
+        let provider = Provider::new(
+            Options {
+                delimiter: b',',
+                has_header: true,
+                batch_size: 10,
+                tail: true,
+                poll_interval: 10,
+            },
+            schema.clone(),
+            file.path(),
+        );
+
+        let mut stream = provider.create_stream(None).unwrap();
+        let data = stream.next().await.unwrap().unwrap();
+        assert_eq!(data.state, 2);
+        assert_eq!(data.dataset, dataset(b"1,a\n2,b\n"));
+
+        // waits for the new rows at EOF
+        assert!(
+            tokio::time::timeout(Duration::from_millis(100), stream.next())
+                .await
+                .is_err()
+        );
+
+        // the incomplete line is not read
+        file.write_all(b"c,3\nd,").unwrap();
+        let data = stream.next().await.unwrap().unwrap();
+        assert_eq!(data.state, 3);
+        assert_eq!(data.dataset, dataset(b"3,c\n"));
+
+        file.write_all(b"4\n").unwrap();
+        let data = stream.next().await.unwrap().unwrap();
+        assert_eq!(data.state, 4);
+        assert_eq!(data.dataset, dataset(b"4,d\n"));
+
+        let mut stream = provider.create_stream(Some(3)).unwrap();
+        let data = stream.next().await.unwrap().unwrap();
+        assert_eq!(data.state, 4);
+        assert_eq!(data.dataset, dataset(b"4,d\n"));
+    }
+}
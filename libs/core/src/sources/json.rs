@@ -0,0 +1,309 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader, Cursor, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use futures_util::stream::BoxStream;
+use serde::{Deserialize, Serialize};
+
+use crate::dataset::{infer_json_lines_schema, DataFormat, SchemaRef};
+use crate::{GenericSourceDataSet, GenericSourceProvider};
+
+const DEFAULT_BATCH_SIZE: usize = 1000000;
+
+/// The number of lines sampled to infer the schema of a file.
+const INFER_SCHEMA_SAMPLE_SIZE: usize = 1000;
+
+#[derive(Serialize, Deserialize)]
+pub struct Options {
+    /// The maximum number of lines in a batch.
+    #[serde(default = "default_batch_size")]
+    pub batch_size: usize,
+}
+
+fn default_batch_size() -> usize {
+    DEFAULT_BATCH_SIZE
+}
+
+enum DataFrom {
+    Path(PathBuf),
+    Data(Vec<u8>),
+}
+
+/// The position of a json source.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum State {
+    /// The number of lines that have been read, they are read again and skipped when resuming.
+    Lines(usize),
+    /// The number of lines that have been read and the byte offset of the next line, resuming
+    /// seeks to the offset directly.
+    Offset { lines: usize, offset: u64 },
+}
+
+/// Reads the newline-delimited JSON objects.
+pub struct Provider {
+    options: Options,
+    schema: SchemaRef,
+    from: DataFrom,
+}
+
+impl Provider {
+    pub fn new(options: Options, schema: SchemaRef, path: impl AsRef<Path>) -> Self {
+        Self {
+            options,
+            schema,
+            from: DataFrom::Path(path.as_ref().to_path_buf()),
+        }
+    }
+
+    pub fn new_from_memory(options: Options, schema: SchemaRef, data: impl Into<Vec<u8>>) -> Self {
+        Self {
+            options,
+            schema,
+            from: DataFrom::Data(data.into()),
+        }
+    }
+
+    /// Infers the schema from the first lines of the file.
+    pub fn infer_schema(path: impl AsRef<Path>) -> Result<SchemaRef> {
+        let path = path.as_ref();
+        let file =
+            File::open(path).with_context(|| format!("failed to open file: {}", path.display()))?;
+        infer_json_lines_schema(BufReader::new(file), Some(INFER_SCHEMA_SAMPLE_SIZE))
+    }
+}
+
+/// Reads the next line without the line terminator, and adds the number of bytes read to
+/// `offset`.
+fn read_line(reader: &mut impl BufRead, offset: &mut u64) -> Result<Option<String>> {
+    let mut line = String::new();
+    let len = reader.read_line(&mut line)?;
+    if len == 0 {
+        return Ok(None);
+    }
+    *offset += len as u64;
+    if line.ends_with('\n') {
+        line.pop();
+        if line.ends_with('\r') {
+            line.pop();
+        }
+    }
+    Ok(Some(line))
+}
+
+impl GenericSourceProvider for Provider {
+    type State = State;
+
+    fn provider_name(&self) -> &'static str {
+        "json"
+    }
+
+    fn schema(&self) -> Result<SchemaRef> {
+        Ok(self.schema.clone())
+    }
+
+    fn is_bounded(&self) -> bool {
+        true
+    }
+
+    fn create_stream(
+        &self,
+        state: Option<Self::State>,
+    ) -> Result<BoxStream<'static, Result<GenericSourceDataSet<Self::State>>>> {
+        let (mut position, mut offset) = match state {
+            Some(State::Offset { lines, offset }) => (lines, offset),
+            _ => (0, 0),
+        };
+        let reader = match &self.from {
+            DataFrom::Path(path) => {
+                let mut file = File::open(path)?;
+                file.seek(SeekFrom::Start(offset))?;
+                Box::new(file) as Box<dyn Read + Send>
+            }
+            DataFrom::Data(data) => {
+                let mut cursor = Cursor::new(data.clone());
+                cursor.set_position(offset);
+                Box::new(cursor) as Box<dyn Read + Send>
+            }
+        };
+        let mut reader = BufReader::new(reader);
+        if let Some(State::Lines(lines)) = state {
+            while position < lines && read_line(&mut reader, &mut offset)?.is_some() {
+                position += 1;
+            }
+        }
+
+        let schema = self.schema.clone();
+        let batch_size = self.options.batch_size;
+        Ok(Box::pin(async_stream::try_stream! {
+            loop {
+                let mut batch = Vec::new();
+                while batch.len() < batch_size {
+                    match read_line(&mut reader, &mut offset)? {
+                        Some(line) => batch.push(line),
+                        None => break,
+                    }
+                }
+                if batch.is_empty() {
+                    break;
+                }
+
+                let dataset = DataFormat::Json.parse_lines(schema.clone(), &batch, position + 1)?;
+                position += batch.len();
+                // a batch of blank lines is skipped
+                if !dataset.is_empty() {
+                    yield GenericSourceDataSet {
+                        state: State::Offset {
+                            lines: position,
+                            offset,
+                        },
+                        dataset,
+                    };
+                }
+            }
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+    use std::sync::Arc;
+
+    use futures_util::StreamExt;
+
+    use super::*;
+    use crate::array::DataType;
+    use crate::dataset::{CsvOptions, DataSet, Field, Schema};
+
+    #[tokio::test]
+    async fn test_json_lines() {
+        let schema = Arc::new(
+            Schema::try_new(vec![
+                Field::new("a", DataType::Int64),
+                Field::new("b", DataType::String),
+            ])
+            .unwrap(),
+        );
+        let dataset = |data: &[u8]| {
+            DataSet::from_csv_slice(schema.clone(), CsvOptions::default(), data).unwrap()
+        };
+        let provider = Provider::new_from_memory(
+            Options { batch_size: 2 },
+            schema.clone(),
+            r#"{"a": 1, "b": "x"}
+{"a": 2, "b": "y"}
+
+{"a": 3}
+{"a": 4, "b": "z"}
+"#,
+        );
+
+        let mut stream = provider.create_stream(None).unwrap();
+        let data = stream.next().await.unwrap().unwrap();
+        assert_eq!(
+            data.state,
+            State::Offset {
+                lines: 2,
+                offset: 38
+            }
+        );
+        assert_eq!(data.dataset, dataset(b"1,x\n2,y\n"));
+        let data = stream.next().await.unwrap().unwrap();
+        assert_eq!(
+            data.state,
+            State::Offset {
+                lines: 4,
+                offset: 48
+            }
+        );
+        assert_eq!(
+            data.dataset,
+            DataFormat::Json
+                .parse(schema.clone(), br#"[{"a": 3}]"#)
+                .unwrap()
+        );
+        let data = stream.next().await.unwrap().unwrap();
+        let end_state = State::Offset {
+            lines: 5,
+            offset: 67,
+        };
+        assert_eq!(data.state, end_state);
+        assert_eq!(data.dataset, dataset(b"4,z\n"));
+        assert!(stream.next().await.is_none());
+
+        let mut stream = provider.create_stream(Some(State::Lines(4))).unwrap();
+        let data = stream.next().await.unwrap().unwrap();
+        assert_eq!(data.state, end_state);
+        assert_eq!(data.dataset, dataset(b"4,z\n"));
+        assert!(stream.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_malformed_line() {
+        let schema = Arc::new(Schema::try_new(vec![Field::new("a", DataType::Int64)]).unwrap());
+        let provider = Provider::new_from_memory(
+            Options { batch_size: 2 },
+            schema,
+            "{\"a\": 1}\n{\"a\": 2}\n{\"a\": 3}\n{\"a\" 4}\n",
+        );
+
+        let mut stream = provider.create_stream(None).unwrap();
+        assert_eq!(
+            stream.next().await.unwrap().unwrap().state,
+            State::Offset {
+                lines: 2,
+                offset: 18
+            }
+        );
+        assert_eq!(
+            stream.next().await.unwrap().err().unwrap().to_string(),
+            "invalid json at line 4: expected `:` at line 1 column 6"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_resume_from_offset() {
+        let schema = Arc::new(Schema::try_new(vec![Field::new("a", DataType::Int64)]).unwrap());
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        for i in 1..=5 {
+            writeln!(file, "{{\"a\": {}}}", i).unwrap();
+        }
+        let provider = Provider::new(Options { batch_size: 2 }, schema.clone(), file.path());
+
+        let mut stream = provider.create_stream(None).unwrap();
+        let state = stream.next().await.unwrap().unwrap().state;
+        assert_eq!(
+            state,
+            State::Offset {
+                lines: 2,
+                offset: 18
+            }
+        );
+        drop(stream);
+
+        // the lines before the offset are not read again, so changing them doesn't matter
+        file.as_file_mut().seek(SeekFrom::Start(0)).unwrap();
+        file.write_all(b"not json\nnot json\n").unwrap();
+
+        let mut stream = provider.create_stream(Some(state)).unwrap();
+        let data = stream.next().await.unwrap().unwrap();
+        assert_eq!(
+            data.state,
+            State::Offset {
+                lines: 4,
+                offset: 36
+            }
+        );
+        assert_eq!(
+            data.dataset,
+            DataSet::from_csv_slice(schema.clone(), CsvOptions::default(), b"3\n4\n").unwrap()
+        );
+        let data = stream.next().await.unwrap().unwrap();
+        assert_eq!(
+            data.dataset,
+            DataSet::from_csv_slice(schema, CsvOptions::default(), b"5\n").unwrap()
+        );
+        assert!(stream.next().await.is_none());
+    }
+}
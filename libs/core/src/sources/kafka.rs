@@ -1,23 +1,38 @@
+use std::collections::BTreeMap;
+use std::time::Duration;
+
 use anyhow::Result;
 use futures_util::stream::BoxStream;
 use futures_util::StreamExt;
-use itertools::Itertools;
 use rdkafka::consumer::{Consumer, StreamConsumer};
-use rdkafka::{ClientConfig, Message};
+use rdkafka::{ClientConfig, Message, Offset, TopicPartitionList};
 use serde::{Deserialize, Serialize};
 
 use crate::dataset::{DataFormat, SchemaRef};
 use crate::{GenericSourceDataSet, GenericSourceProvider};
 
+const FETCH_METADATA_TIMEOUT: Duration = Duration::from_secs(10);
+
 #[derive(Serialize, Deserialize)]
 pub struct Options {
-    servers: String,
-    group_id: String,
-    topics: Vec<String>,
+    /// The comma-separated list of the brokers.
+    #[serde(default)]
+    pub servers: String,
+    pub group_id: String,
     #[serde(default)]
-    format: DataFormat,
+    pub topics: Vec<String>,
+    #[serde(default)]
+    pub format: DataFormat,
 }
 
+/// The offsets of the last consumed messages, keyed by the topic and partition.
+pub type Offsets = BTreeMap<(String, i32), i64>;
+
+/// Consumes all the partitions of the topics, the state is the offsets of the consumed messages.
+///
+/// The offsets are never committed to Kafka, a stream restored from a checkpoint resumes from the
+/// offsets saved in the checkpoint, so the messages after the checkpoint are consumed again, but
+/// no message is counted twice in the state of the checkpoint.
 pub struct Provider {
     options: Options,
     schema: SchemaRef,
@@ -30,7 +45,7 @@ impl Provider {
 }
 
 impl GenericSourceProvider for Provider {
-    type State = ();
+    type State = Offsets;
 
     fn provider_name(&self) -> &'static str {
         "kafka"
@@ -42,25 +57,40 @@ impl GenericSourceProvider for Provider {
 
     fn create_stream(
         &self,
-        _: Option<Self::State>,
+        position: Option<Self::State>,
     ) -> Result<BoxStream<'static, Result<GenericSourceDataSet<Self::State>>>> {
+        anyhow::ensure!(
+            !self.options.topics.is_empty(),
+            "at least one kafka topic is required"
+        );
         let consumer: StreamConsumer = ClientConfig::new()
             .set("group.id", &self.options.group_id)
             .set("bootstrap.servers", &self.options.servers)
             .set("enable.auto.commit", "false")
             .create()?;
 
-        consumer.subscribe(&self.options.topics.iter().map(|x| x.as_str()).collect_vec())?;
-
+        let offsets = position.unwrap_or_default();
+        let topics = self.options.topics.clone();
         let schema = self.schema.clone();
         let format = self.options.format;
         Ok(Box::pin(async_stream::try_stream! {
+            // fetching the metadata blocks until the brokers respond, so it's not done on the
+            // threads of the runtime
+            let (consumer, mut offsets) = tokio::task::spawn_blocking(move || {
+                assign_partitions(consumer, &topics, offsets)
+            })
+            .await??;
+
             let mut input = consumer.stream();
             while let Some(message) = input.next().await.transpose()? {
+                offsets.insert(
+                    (message.topic().to_string(), message.partition()),
+                    message.offset(),
+                );
                 if let Some(payload) = message.payload() {
                     let dataset = format.parse(schema.clone(), payload)?;
                     yield GenericSourceDataSet {
-                        state: (),
+                        state: offsets.clone(),
                         dataset,
                     };
                 }
@@ -68,3 +98,27 @@ impl GenericSourceProvider for Provider {
         }))
     }
 }
+
+/// Assigns all the partitions of the topics to the consumer, a partition is consumed after its
+/// saved offset, or from the offset of the consumer group if it has not been consumed yet.
+fn assign_partitions(
+    consumer: StreamConsumer,
+    topics: &[String],
+    offsets: Offsets,
+) -> Result<(StreamConsumer, Offsets)> {
+    let mut assignment = TopicPartitionList::new();
+    for topic in topics {
+        let metadata = consumer.fetch_metadata(Some(topic), FETCH_METADATA_TIMEOUT)?;
+        for metadata_topic in metadata.topics() {
+            for partition in metadata_topic.partitions() {
+                let offset = match offsets.get(&(topic.clone(), partition.id())) {
+                    Some(offset) => Offset::Offset(offset + 1),
+                    None => Offset::Stored,
+                };
+                assignment.add_partition_offset(topic, partition.id(), offset)?;
+            }
+        }
+    }
+    consumer.assign(&assignment)?;
+    Ok((consumer, offsets))
+}
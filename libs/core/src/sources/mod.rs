@@ -1,2 +1,4 @@
 pub mod csv;
+pub mod json;
 pub mod kafka;
+pub mod stdin;
@@ -0,0 +1,198 @@
+use std::io::Cursor;
+
+use anyhow::Result;
+use futures_util::stream::BoxStream;
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+
+use crate::dataset::{DataFormat, SchemaRef};
+use crate::{GenericSourceDataSet, GenericSourceProvider};
+
+const DEFAULT_BATCH_SIZE: usize = 1000;
+
+#[derive(Serialize, Deserialize)]
+pub struct Options {
+    #[serde(default)]
+    pub format: DataFormat,
+    /// The maximum number of lines in a batch.
+    #[serde(default = "default_batch_size")]
+    pub batch_size: usize,
+}
+
+fn default_batch_size() -> usize {
+    DEFAULT_BATCH_SIZE
+}
+
+enum DataFrom {
+    Stdin,
+    Data(Vec<u8>),
+}
+
+/// Reads the rows from the standard input with one row per line, the stream ends at EOF, but it is
+/// not bounded since the input may never end.
+///
+/// The standard input cannot be read again, so the stream can't be resumed from a saved state.
+pub struct Provider {
+    options: Options,
+    schema: SchemaRef,
+    from: DataFrom,
+}
+
+impl Provider {
+    pub fn new(options: Options, schema: SchemaRef) -> Self {
+        Self {
+            options,
+            schema,
+            from: DataFrom::Stdin,
+        }
+    }
+
+    pub fn new_from_memory(options: Options, schema: SchemaRef, data: impl Into<Vec<u8>>) -> Self {
+        Self {
+            options,
+            schema,
+            from: DataFrom::Data(data.into()),
+        }
+    }
+}
+
+impl GenericSourceProvider for Provider {
+    /// The number of lines that have been read.
+    type State = usize;
+
+    fn provider_name(&self) -> &'static str {
+        "stdin"
+    }
+
+    fn schema(&self) -> Result<SchemaRef> {
+        Ok(self.schema.clone())
+    }
+
+    /// The standard input may never reach EOF, e.g. the output of `tail -f`, so it can't be read
+    /// to the end before the stream starts.
+    fn is_bounded(&self) -> bool {
+        matches!(self.from, DataFrom::Data(_))
+    }
+
+    fn create_stream(
+        &self,
+        state: Option<Self::State>,
+    ) -> Result<BoxStream<'static, Result<GenericSourceDataSet<Self::State>>>> {
+        if let Some(lines) = state {
+            anyhow::bail!(
+                "the stdin source cannot be resumed, {} lines have been read before.",
+                lines
+            );
+        }
+
+        let reader = match &self.from {
+            DataFrom::Stdin => Box::new(tokio::io::stdin()) as Box<dyn AsyncRead + Send + Unpin>,
+            DataFrom::Data(data) => {
+                Box::new(Cursor::new(data.clone())) as Box<dyn AsyncRead + Send + Unpin>
+            }
+        };
+        let mut reader = BufReader::new(reader);
+        let schema = self.schema.clone();
+        let format = self.options.format;
+        let batch_size = self.options.batch_size;
+        let mut position = 0;
+
+        Ok(Box::pin(async_stream::try_stream! {
+            let mut eof = false;
+            while !eof {
+                // waits for the first line of a batch, the following lines are only taken if
+                // they are already buffered, so that the rows are not held back by a slow writer
+                let mut batch = Vec::new();
+                while batch.len() < batch_size
+                    && (batch.is_empty() || reader.buffer().contains(&b'\n'))
+                {
+                    let mut line = String::new();
+                    if reader.read_line(&mut line).await? == 0 {
+                        eof = true;
+                        break;
+                    }
+                    if line.ends_with('\n') {
+                        line.pop();
+                        if line.ends_with('\r') {
+                            line.pop();
+                        }
+                    }
+                    batch.push(line);
+                }
+                if batch.is_empty() {
+                    break;
+                }
+
+                let dataset = format.parse_lines(schema.clone(), &batch, position + 1)?;
+                position += batch.len();
+                // a batch of blank lines is skipped
+                if !dataset.is_empty() {
+                    yield GenericSourceDataSet {
+                        state: position,
+                        dataset,
+                    };
+                }
+            }
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use futures_util::StreamExt;
+
+    use super::*;
+    use crate::array::DataType;
+    use crate::dataset::{CsvOptions, DataSet, Field, Schema};
+
+    #[tokio::test]
+    async fn test_stdin_lines() {
+        let schema = Arc::new(
+            Schema::try_new(vec![
+                Field::new("a", DataType::Int64),
+                Field::new("b", DataType::String),
+            ])
+            .unwrap(),
+        );
+        let provider = Provider::new_from_memory(
+            Options {
+                format: DataFormat::Json,
+                batch_size: 2,
+            },
+            schema.clone(),
+            "{\"a\": 1, \"b\": \"x\"}\r\n{\"a\": 2, \"b\": \"y\"}\n\n{\"a\": 3, \"b\": \"z\"}",
+        );
+
+        assert!(provider.is_bounded());
+        assert!(!Provider::new(
+            Options {
+                format: DataFormat::Json,
+                batch_size: 2,
+            },
+            schema.clone()
+        )
+        .is_bounded());
+
+        let mut stream = provider.create_stream(None).unwrap();
+        let data = stream.next().await.unwrap().unwrap();
+        assert_eq!(data.state, 2);
+        assert_eq!(
+            data.dataset,
+            DataSet::from_csv_slice(schema.clone(), CsvOptions::default(), b"1,x\n2,y\n").unwrap()
+        );
+        let data = stream.next().await.unwrap().unwrap();
+        assert_eq!(data.state, 4);
+        assert_eq!(
+            data.dataset,
+            DataSet::from_csv_slice(schema, CsvOptions::default(), b"3,z\n").unwrap()
+        );
+        assert!(stream.next().await.is_none());
+
+        assert_eq!(
+            provider.create_stream(Some(4)).err().unwrap().to_string(),
+            "the stdin source cannot be resumed, 4 lines have been read before."
+        );
+    }
+}
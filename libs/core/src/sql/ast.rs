@@ -1,32 +1,66 @@
 use serde::{Deserialize, Serialize};
 
 use crate::expr::Expr;
-use crate::Window;
+use crate::{Emit, JoinType, Pivot, Window};
 
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum SourceFrom {
     Named(String),
     SubQuery(Box<Select>),
+    Join(Box<Join>),
+    Unnest(Box<Unnest>),
 }
 
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// `left [INNER | LEFT | RIGHT | FULL] JOIN right ON condition [REFRESH duration]`, the
+/// condition must be an equality.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Join {
+    pub join_type: JoinType,
+    pub left: Source,
+    pub right: Source,
+    pub on: Expr,
+    /// Read the right side again when it is older than this many milliseconds.
+    pub refresh: Option<i64>,
+}
+
+/// `source CROSS JOIN UNNEST(expr) [AS alias]`, outputs a row for each element of the list
+/// returned by `expr`, the element is named by the alias or `unnest`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Unnest {
+    pub source: Source,
+    pub expr: Expr,
+    pub alias: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Source {
     pub from: SourceFrom,
     pub alias: Option<String>,
 }
 
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct GroupBy {
     pub exprs: Vec<Expr>,
 }
 
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Limit {
+    pub limit: usize,
+    pub offset: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Select {
     pub projection: Vec<Expr>,
     pub source: Source,
     pub where_clause: Option<Expr>,
     pub having_clause: Option<Expr>,
     pub group_clause: Option<GroupBy>,
+    pub pivot: Option<Pivot>,
     pub window: Option<Window>,
     pub watermark: Option<Expr>,
+    pub emit: Emit,
+    /// The sort keys of the rows emitted for each window, `true` means ascending.
+    pub order_by: Vec<(Expr, bool)>,
+    pub limit: Option<Limit>,
 }
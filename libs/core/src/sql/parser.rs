@@ -1,18 +1,34 @@
 use std::str::FromStr;
 
+use chrono::{DateTime, NaiveDateTime};
+use chrono_tz::Tz;
+use itertools::{Either, Itertools};
 use nom::branch::alt;
 use nom::bytes::complete::{is_not, tag, tag_no_case, take};
 use nom::character::complete::{alpha1, alphanumeric1, char, digit1, one_of};
-use nom::combinator::{cut, map, opt, recognize, value};
-use nom::error::context;
-use nom::multi::{fold_many0, many0, separated_list0, separated_list1};
-use nom::sequence::{delimited, pair, preceded, separated_pair, tuple};
-use nom::IResult;
+use nom::combinator::{
+    cut, map, map_opt, map_res, not as not_followed_by, opt, peek, recognize, value, verify,
+};
+use nom::error::{context, convert_error, VerboseError, VerboseErrorKind};
+use nom::multi::{fold_many0, many0, many1, separated_list0, separated_list1};
+use nom::sequence::{delimited, pair, preceded, separated_pair, terminated, tuple};
 
+use crate::array::{DataType, DECIMAL128_MAX_PRECISION};
 use crate::expr::{BinaryOperator, Expr, Literal, UnaryOperator};
+use crate::planner::physical_plan::FIELD_TIME;
 use crate::planner::window::Period;
-use crate::sql::ast::{GroupBy, Select, Source, SourceFrom};
-use crate::Window;
+use crate::sql::ast::{GroupBy, Join, Limit, Select, Source, SourceFrom, Unnest};
+use crate::{Emit, JoinType, Pivot, Window};
+
+pub type IResult<I, O> = nom::IResult<I, O, VerboseError<I>>;
+
+/// Formats a parse error with the line and column where parsing failed.
+pub fn format_error(input: &str, err: nom::Err<VerboseError<&str>>) -> String {
+    match err {
+        nom::Err::Error(err) | nom::Err::Failure(err) => convert_error(input, err),
+        nom::Err::Incomplete(_) => "incomplete input".to_string(),
+    }
+}
 
 pub fn sp(input: &str) -> IResult<&str, ()> {
     fold_many0(value((), one_of(" \t\n\r")), (), |_, _| ())(input)
@@ -91,6 +107,9 @@ fn raw_string_quoted(input: &str, is_single_quote: bool) -> IResult<&str, String
                 map(tag("\\t"), |_| "\t"),
                 map(tag("\\0"), |_| "\0"),
                 map(tag("\\Z"), |_| "\x1A"),
+                // like MySQL, the backslash is kept so that the wildcards are escaped in patterns
+                map(tag("\\%"), |_| "\\%"),
+                map(tag("\\_"), |_| "\\_"),
                 preceded(tag("\\"), take(1usize)),
             )),
             String::new(),
@@ -118,10 +137,27 @@ pub fn string(input: &str) -> IResult<&str, String> {
     )(input)
 }
 
+/// Parses `TIMESTAMP '2021-06-01 01:49:00'` as milliseconds since the unix epoch in UTC, RFC 3339
+/// strings are accepted as well.
+pub fn timestamp(input: &str) -> IResult<&str, i64> {
+    context(
+        "timestamp",
+        map_opt(preceded(pair(tag_no_case("timestamp"), sp), string), |s| {
+            NaiveDateTime::parse_from_str(&s, "%Y-%m-%d %H:%M:%S%.f")
+                .map(|datetime| datetime.timestamp_millis())
+                .or_else(|_| {
+                    DateTime::parse_from_rfc3339(&s).map(|datetime| datetime.timestamp_millis())
+                })
+                .ok()
+        }),
+    )(input)
+}
+
 pub fn literal(input: &str) -> IResult<&str, Literal> {
     context(
         "literal",
         alt((
+            map(timestamp, Literal::Timestamp),
             map(boolean, Literal::Boolean),
             map(float, Literal::Float),
             map(duration, Literal::Int),
@@ -131,6 +167,66 @@ pub fn literal(input: &str) -> IResult<&str, Literal> {
     )(input)
 }
 
+pub fn timezone(input: &str) -> IResult<&str, Tz> {
+    cut(map_res(string, |name| name.parse::<Tz>()))(input)
+}
+
+pub fn data_type(input: &str) -> IResult<&str, DataType> {
+    let timezone = map(
+        tuple((tag_no_case("timezone"), sp, timezone)),
+        |(_, _, tz)| tz,
+    );
+    let decimal = preceded(
+        tuple((tag_no_case("decimal128"), sp)),
+        cut(map(
+            verify(
+                tuple((
+                    char('('),
+                    sp,
+                    map_res(digit1, str::parse::<u8>),
+                    sp,
+                    char(','),
+                    sp,
+                    map_res(digit1, str::parse::<u8>),
+                    sp,
+                    char(')'),
+                )),
+                |(_, _, precision, _, _, _, scale, _, _)| {
+                    (1..=DECIMAL128_MAX_PRECISION).contains(precision) && scale <= precision
+                },
+            ),
+            |(_, _, precision, _, _, _, scale, _, _)| DataType::Decimal128 { precision, scale },
+        )),
+    );
+
+    context(
+        "data_type",
+        alt((
+            value(DataType::Int8, tag_no_case("int8")),
+            value(DataType::Int16, tag_no_case("int16")),
+            value(DataType::Int32, tag_no_case("int32")),
+            value(DataType::Int64, tag_no_case("int64")),
+            value(DataType::UInt8, tag_no_case("uint8")),
+            value(DataType::UInt16, tag_no_case("uint16")),
+            value(DataType::UInt32, tag_no_case("uint32")),
+            value(DataType::UInt64, tag_no_case("uint64")),
+            value(DataType::Float32, tag_no_case("float32")),
+            value(DataType::Float64, tag_no_case("float64")),
+            value(DataType::Boolean, tag_no_case("boolean")),
+            value(DataType::String, tag_no_case("string")),
+            decimal,
+            map(
+                tuple((
+                    tag_no_case("timestamp"),
+                    sp,
+                    opt(delimited(sp, timezone, sp)),
+                )),
+                |(_, _, tz)| DataType::Timestamp(tz),
+            ),
+        )),
+    )(input)
+}
+
 pub fn name(input: &str) -> IResult<&str, String> {
     context("name", alt((string, map(ident, ToString::to_string))))(input)
 }
@@ -172,81 +268,259 @@ fn expr_call(input: &str) -> IResult<&str, Expr> {
         }),
         map(ident, |name| (None, name)),
     ));
-    let arguments = separated_list0(char(','), delimited(sp, expr, sp));
+    let named_argument = map(tuple((ident, sp, tag("=>"), expr)), |(name, _, _, expr)| {
+        Expr::NamedArgument {
+            name: name.to_string(),
+            expr: Box::new(expr),
+        }
+    });
+    let distinct = map(
+        preceded(tuple((keyword("distinct"), sp)), cut(expr)),
+        |expr| Expr::Distinct(Box::new(expr)),
+    );
+    let arguments = separated_list0(
+        char(','),
+        delimited(sp, alt((named_argument, distinct, expr)), sp),
+    );
+    let order_by = preceded(tuple((keyword("order"), sp, keyword("by"), sp)), expr);
     context(
         "expr_call",
         map(
-            tuple((func_name, sp, char('('), sp, arguments, sp, char(')'))),
-            |((namespace, name), _, _, _, args, _, _)| Expr::Call {
-                namespace: namespace.map(ToString::to_string),
-                name: name.to_string(),
-                args,
+            verify(
+                tuple((
+                    func_name,
+                    sp,
+                    char('('),
+                    cut(tuple((
+                        sp,
+                        arguments,
+                        sp,
+                        opt(terminated(order_by, sp)),
+                        char(')'),
+                    ))),
+                )),
+                // only `FIRST` and `LAST` of a single argument can be ordered by a key
+                |((namespace, name), _, _, (_, args, _, order_by, _))| {
+                    order_by.is_none()
+                        || (namespace.is_none()
+                            && args.len() == 1
+                            && (name.eq_ignore_ascii_case("first")
+                                || name.eq_ignore_ascii_case("last")))
+                },
+            ),
+            |((namespace, name), _, _, (_, mut args, _, order_by, _))| match order_by {
+                Some(key) => {
+                    args.push(key);
+                    Expr::Call {
+                        namespace: None,
+                        name: format!("{}_by", name.to_lowercase()),
+                        args,
+                    }
+                }
+                None => Expr::Call {
+                    namespace: namespace.map(ToString::to_string),
+                    name: name.to_string(),
+                    args,
+                },
+            },
+        ),
+    )(input)
+}
+
+/// Parses a keyword that isn't the prefix of an identifier.
+fn keyword<'a>(name: &'static str) -> impl FnMut(&'a str) -> IResult<&'a str, &'a str> {
+    terminated(
+        tag_no_case(name),
+        not_followed_by(alt((alphanumeric1, tag("_")))),
+    )
+}
+
+fn expr_case(input: &str) -> IResult<&str, Expr> {
+    let operand = preceded(not_followed_by(keyword("when")), expr);
+    let when_then = map(
+        tuple((keyword("when"), expr, keyword("then"), expr)),
+        |(_, when, _, then)| (when, then),
+    );
+    let else_expr = preceded(keyword("else"), expr);
+    context(
+        "expr_case",
+        map(
+            tuple((
+                keyword("case"),
+                sp,
+                cut(tuple((
+                    opt(operand),
+                    many1(when_then),
+                    opt(else_expr),
+                    keyword("end"),
+                ))),
+            )),
+            |(_, _, (operand, when_then, else_expr, _))| Expr::Case {
+                operand: operand.map(Box::new),
+                when_then,
+                else_expr: else_expr.map(Box::new),
             },
         ),
     )(input)
 }
 
+fn expr_cast(input: &str) -> IResult<&str, Expr> {
+    context(
+        "expr_cast",
+        map(
+            tuple((
+                keyword("cast"),
+                sp,
+                char('('),
+                cut(tuple((expr, keyword("as"), sp, data_type, sp, char(')')))),
+            )),
+            |(_, _, _, (expr, _, _, data_type, _, _))| expr.cast(data_type),
+        ),
+    )(input)
+}
+
 fn expr_primitive(input: &str) -> IResult<&str, Expr> {
     let parens = map(
         tuple((char('('), sp, expr, sp, char(')'))),
         |(_, _, expr, _, _)| expr,
     );
+    let sub_query = map(
+        tuple((
+            char('('),
+            sp,
+            peek(keyword("select")),
+            select,
+            sp,
+            char(')'),
+        )),
+        |(_, _, _, select, _, _)| Expr::SubQuery(Box::new(select)),
+    );
     let p = alt((
+        sub_query,
         parens,
-        expr_unary,
+        expr_neg,
+        expr_case,
+        expr_cast,
         expr_call,
         map(literal, Expr::Literal),
         column,
     ));
-    context("expr_primitive", delimited(sp, p, sp))(input)
+    // `expr::type` binds tighter than any operator
+    let casts = many0(preceded(tuple((sp, tag("::"), sp)), cut(data_type)));
+    context(
+        "expr_primitive",
+        delimited(
+            sp,
+            map(pair(p, casts), |(expr, casts)| {
+                casts
+                    .into_iter()
+                    .fold(expr, |expr, data_type| expr.cast(data_type))
+            }),
+            sp,
+        ),
+    )(input)
 }
 
-fn expr_unary(input: &str) -> IResult<&str, Expr> {
-    let op = alt((
-        value(UnaryOperator::Not, tag_no_case("not")),
-        value(UnaryOperator::Neg, char('-')),
-    ));
-    map(separated_pair(op, sp, expr), |(op, expr)| Expr::Unary {
-        op,
+fn expr_neg(input: &str) -> IResult<&str, Expr> {
+    map(preceded(char('-'), expr_primitive), |expr| Expr::Unary {
+        op: UnaryOperator::Neg,
         expr: Box::new(expr),
     })(input)
 }
 
+/// `NOT` binds looser than the comparison operators, so `not a = b` means `not (a = b)`.
+fn expr_not(input: &str) -> IResult<&str, Expr> {
+    let not = terminated(
+        tag_no_case("not"),
+        not_followed_by(alt((alphanumeric1, tag("_")))),
+    );
+    alt((
+        map(preceded(tuple((sp, not, sp)), expr_not), |expr| {
+            Expr::Unary {
+                op: UnaryOperator::Not,
+                expr: Box::new(expr),
+            }
+        }),
+        expr_c,
+    ))(input)
+}
+
 fn expr_a(input: &str) -> IResult<&str, Expr> {
     let (input, lhs) = expr_b(input)?;
-    let (input, exprs) = many0(tuple((
-        value(BinaryOperator::Or, tag_no_case("or")),
-        expr_b,
-    )))(input)?;
+    let (input, exprs) = many0(tuple((value(BinaryOperator::Or, keyword("or")), expr_b)))(input)?;
     Ok((input, parse_expr(lhs, exprs)))
 }
 
 fn expr_b(input: &str) -> IResult<&str, Expr> {
-    let (input, lhs) = expr_c(input)?;
+    let (input, lhs) = expr_not(input)?;
     let (input, exprs) = many0(tuple((
-        value(BinaryOperator::Or, tag_no_case("and")),
-        expr_c,
+        value(BinaryOperator::And, keyword("and")),
+        expr_not,
     )))(input)?;
     Ok((input, parse_expr(lhs, exprs)))
 }
 
 fn expr_c(input: &str) -> IResult<&str, Expr> {
-    let (input, lhs) = expr_d(input)?;
+    let (input, lhs) = expr_in(input)?;
     let (input, exprs) = many0(tuple((
         alt((
             value(BinaryOperator::Eq, tag("=")),
             value(BinaryOperator::NotEq, tag("!=")),
             value(BinaryOperator::NotEq, tag("<>")),
+            value(BinaryOperator::LtEq, tag("<=")),
             value(BinaryOperator::Lt, tag("<")),
-            value(BinaryOperator::LtEq, tag("<")),
-            value(BinaryOperator::Gt, tag(">")),
             value(BinaryOperator::GtEq, tag(">=")),
+            value(BinaryOperator::Gt, tag(">")),
+            value(
+                BinaryOperator::NotLike,
+                tuple((keyword("not"), sp, keyword("like"))),
+            ),
+            value(BinaryOperator::Like, keyword("like")),
         )),
-        expr_d,
+        expr_in,
     )))(input)?;
     Ok((input, parse_expr(lhs, exprs)))
 }
 
+/// `expr [NOT] IN (list)` and `expr [NOT] BETWEEN low AND high` bind like the comparison
+/// operators, the bounds can't be comparisons so that the `AND` belongs to the `BETWEEN`.
+fn expr_in(input: &str) -> IResult<&str, Expr> {
+    enum Suffix {
+        In(Vec<Expr>),
+        Between(Expr, Expr),
+    }
+
+    let list = map(
+        preceded(
+            pair(keyword("in"), sp),
+            cut(delimited(
+                char('('),
+                separated_list1(char(','), delimited(sp, expr, sp)),
+                char(')'),
+            )),
+        ),
+        Suffix::In,
+    );
+    let range = map(
+        preceded(
+            keyword("between"),
+            cut(separated_pair(expr_d, keyword("and"), expr_d)),
+        ),
+        |(low, high)| Suffix::Between(low, high),
+    );
+    let suffix = tuple((
+        sp,
+        opt(terminated(keyword("not"), sp)),
+        alt((list, range)),
+        sp,
+    ));
+    map(pair(expr_d, opt(suffix)), |(lhs, suffix)| match suffix {
+        Some((_, not, Suffix::In(list), _)) => lhs.in_list(list, not.is_some()),
+        Some((_, not, Suffix::Between(low, high), _)) => lhs.between(low, high, not.is_some()),
+        None => lhs,
+    })(input)
+}
+
 fn expr_d(input: &str) -> IResult<&str, Expr> {
     let (input, lhs) = expr_e(input)?;
     let (input, exprs) = many0(tuple((
@@ -281,15 +555,29 @@ fn parse_expr(expr: Expr, rem: Vec<(BinaryOperator, Expr)>) -> Expr {
 }
 
 fn projection_field(input: &str) -> IResult<&str, Expr> {
-    context(
-        "projection_field",
-        alt((
-            map(
-                tuple((expr, sp, tag_no_case("as"), sp, name)),
-                |(expr, _, _, _, alias)| expr.alias(alias),
-            ),
+    let expr = map(
+        tuple((
             expr,
+            opt(preceded(tuple((sp, tag_no_case("over"), sp)), window)),
         )),
+        |(expr, window)| match window {
+            Some(window) => expr.over(window),
+            None => expr,
+        },
+    );
+
+    context(
+        "projection_field",
+        map(
+            tuple((
+                expr,
+                opt(preceded(tuple((sp, tag_no_case("as"), sp)), name)),
+            )),
+            |(expr, alias)| match alias {
+                Some(alias) => expr.alias(alias),
+                None => expr,
+            },
+        ),
     )(input)
 }
 
@@ -298,7 +586,7 @@ fn source_from(input: &str) -> IResult<&str, SourceFrom> {
         "source_from",
         alt((
             map(
-                tuple((char('('), sp, select, sp, char(')'))),
+                tuple((char('('), sp, select, sp, cut(char(')')))),
                 |(_, _, sub_query, _, _)| SourceFrom::SubQuery(Box::new(sub_query)),
             ),
             map(name, SourceFrom::Named),
@@ -306,12 +594,98 @@ fn source_from(input: &str) -> IResult<&str, SourceFrom> {
     )(input)
 }
 
+/// A join or an unnest following a source.
+enum SourceSuffix {
+    Join(JoinType, Source, Expr, Option<i64>),
+    Unnest(Expr, Option<String>),
+}
+
 fn source(input: &str) -> IResult<&str, Source> {
+    let refresh = preceded(tuple((keyword("refresh"), sp)), cut(duration));
+    let join_type = alt((
+        value(JoinType::Inner, keyword("inner")),
+        value(JoinType::Left, keyword("left")),
+        value(JoinType::Right, keyword("right")),
+        value(JoinType::Full, keyword("full")),
+    ));
+    let join_type = map(
+        opt(terminated(
+            terminated(join_type, opt(pair(sp, keyword("outer")))),
+            sp,
+        )),
+        Option::unwrap_or_default,
+    );
+    let join = map(
+        pair(
+            delimited(sp, join_type, pair(keyword("join"), sp)),
+            cut(tuple((
+                single_source,
+                sp,
+                keyword("on"),
+                sp,
+                expr,
+                opt(terminated(refresh, sp)),
+            ))),
+        ),
+        |(join_type, (right, _, _, _, on, refresh))| {
+            SourceSuffix::Join(join_type, right, on, refresh)
+        },
+    );
+    let unnest = map(
+        preceded(
+            tuple((
+                sp,
+                keyword("cross"),
+                sp,
+                keyword("join"),
+                sp,
+                keyword("unnest"),
+            )),
+            cut(tuple((
+                delimited(pair(sp, char('(')), delimited(sp, expr, sp), char(')')),
+                opt(preceded(tuple((sp, keyword("as"), sp)), name)),
+            ))),
+        ),
+        |(expr, alias)| SourceSuffix::Unnest(expr, alias),
+    );
     context(
         "source",
+        map(
+            pair(single_source, many0(alt((join, unnest)))),
+            |(source, suffixes)| {
+                suffixes
+                    .into_iter()
+                    .fold(source, |source, suffix| match suffix {
+                        SourceSuffix::Join(join_type, right, on, refresh) => Source {
+                            from: SourceFrom::Join(Box::new(Join {
+                                join_type,
+                                left: source,
+                                right,
+                                on,
+                                refresh,
+                            })),
+                            alias: None,
+                        },
+                        SourceSuffix::Unnest(expr, alias) => Source {
+                            from: SourceFrom::Unnest(Box::new(Unnest {
+                                source,
+                                expr,
+                                alias,
+                            })),
+                            alias: None,
+                        },
+                    })
+            },
+        ),
+    )(input)
+}
+
+fn single_source(input: &str) -> IResult<&str, Source> {
+    context(
+        "single_source",
         alt((
             map(
-                tuple((source_from, sp, tag_no_case("as"), sp, name)),
+                tuple((source_from, sp, tag_no_case("as"), sp, cut(name))),
                 |(from, _, _, _, alias)| Source {
                     from,
                     alias: Some(alias),
@@ -322,7 +696,72 @@ fn source(input: &str) -> IResult<&str, Source> {
     )(input)
 }
 
-fn group_by(input: &str) -> IResult<&str, GroupBy> {
+/// Fails with the reason why the parsed window is invalid, see `Window::validate`.
+fn valid_window<'a>(
+    mut parser: impl FnMut(&'a str) -> IResult<&'a str, Window>,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Window> {
+    move |input| {
+        let (rest, window) = parser(input)?;
+        match window.invalid_reason() {
+            Some((reason, _)) => Err(nom::Err::Failure(VerboseError {
+                errors: vec![(input, VerboseErrorKind::Context(reason))],
+            })),
+            None => Ok((rest, window)),
+        }
+    }
+}
+
+/// Parses `TUMBLE(@time, length)` and `HOP(@time, interval, length)` in the group by clause.
+fn group_window(input: &str) -> IResult<&str, Window> {
+    let time_column = || {
+        verify(
+            column,
+            |expr| matches!(expr, Expr::Column { qualifier: None, name } if name == FIELD_TIME),
+        )
+    };
+    let tumble = preceded(
+        tuple((tag_no_case("tumble"), sp, char('('))),
+        cut(valid_window(map(
+            tuple((
+                sp,
+                time_column(),
+                sp,
+                char(','),
+                sp,
+                duration,
+                sp,
+                char(')'),
+            )),
+            |(_, _, _, _, _, length, _, _)| Window::Fixed { length },
+        ))),
+    );
+    let hop = preceded(
+        tuple((tag_no_case("hop"), sp, char('('))),
+        cut(valid_window(map(
+            tuple((
+                sp,
+                time_column(),
+                sp,
+                char(','),
+                sp,
+                duration,
+                sp,
+                char(','),
+                sp,
+                duration,
+                sp,
+                char(')'),
+            )),
+            |(_, _, _, _, _, interval, _, _, _, length, _, _)| Window::Sliding { length, interval },
+        ))),
+    );
+
+    context("group_window", alt((tumble, hop)))(input)
+}
+
+fn group_by(input: &str) -> IResult<&str, (Option<GroupBy>, Option<Window>)> {
+    let item = alt((map(group_window, Either::Right), map(expr, Either::Left)));
+
     context(
         "group_by",
         map(
@@ -331,18 +770,73 @@ fn group_by(input: &str) -> IResult<&str, GroupBy> {
                 sp,
                 tag_no_case("by"),
                 sp,
-                separated_list1(char(','), delimited(sp, expr, sp)),
+                cut(verify(
+                    separated_list1(char(','), delimited(sp, item, sp)),
+                    |items: &Vec<Either<Expr, Window>>| {
+                        items.iter().filter(|item| item.is_right()).count() <= 1
+                    },
+                )),
+            )),
+            |(_, _, _, _, items)| {
+                let (exprs, windows): (Vec<_>, Vec<_>) =
+                    items.into_iter().partition_map(|item| item);
+                let group_by = if !exprs.is_empty() {
+                    Some(GroupBy { exprs })
+                } else {
+                    None
+                };
+                (group_by, windows.into_iter().next())
+            },
+        ),
+    )(input)
+}
+
+fn pivot(input: &str) -> IResult<&str, Pivot> {
+    context(
+        "pivot",
+        map(
+            tuple((
+                tag_no_case("pivot"),
+                sp,
+                cut(tuple((
+                    char('('),
+                    sp,
+                    expr,
+                    sp,
+                    tag_no_case("for"),
+                    sp,
+                    // `IN` belongs to the pivot, so the expression can't be a comparison
+                    expr_d,
+                    sp,
+                    tag_no_case("in"),
+                    sp,
+                    char('('),
+                    separated_list1(char(','), delimited(sp, literal, sp)),
+                    char(')'),
+                    sp,
+                    char(')'),
+                ))),
             )),
-            |(_, _, _, _, exprs)| GroupBy { exprs },
+            |(_, _, (_, _, aggr_expr, _, _, _, pivot_expr, _, _, _, _, values, _, _, _))| Pivot {
+                aggr_expr,
+                pivot_expr,
+                values,
+            },
         ),
     )(input)
 }
 
+/// Parses a duration in milliseconds, e.g. `30 seconds` or `30s`.
 fn duration(input: &str) -> IResult<&str, i64> {
+    // the units are keywords, so that e.g. `5 mins` is an error instead of 5 minutes followed by
+    // `ins`
     let timeunit = alt((
-        value(1000i64, tag_no_case("seconds")),
-        value(1, tag_no_case("milliseconds")),
-        value(1000 * 60, tag_no_case("minutes")),
+        value(1000i64, keyword("seconds")),
+        value(1, keyword("milliseconds")),
+        value(1000 * 60, keyword("minutes")),
+        value(1, keyword("ms")),
+        value(1000, keyword("s")),
+        value(1000 * 60, keyword("m")),
     ));
     context(
         "duration",
@@ -350,10 +844,40 @@ fn duration(input: &str) -> IResult<&str, i64> {
     )(input)
 }
 
+/// Parses `BOUNDED_OUT_OF_ORDER(time, delay)`, the watermark of rows that arrive at most `delay`
+/// late, which is the same as `timestamp_sub(time, <delay in milliseconds>)`.
+fn bounded_out_of_order(input: &str) -> IResult<&str, Expr> {
+    context(
+        "bounded_out_of_order",
+        map(
+            tuple((
+                keyword("bounded_out_of_order"),
+                sp,
+                char('('),
+                cut(tuple((
+                    sp,
+                    expr,
+                    sp,
+                    char(','),
+                    sp,
+                    duration,
+                    sp,
+                    char(')'),
+                ))),
+            )),
+            |(_, _, _, (_, time, _, _, _, delay, _, _))| Expr::Call {
+                namespace: None,
+                name: "timestamp_sub".to_string(),
+                args: vec![time, Expr::Literal(Literal::Int(delay))],
+            },
+        ),
+    )(input)
+}
+
 fn window(input: &str) -> IResult<&str, Window> {
     let fixed_window = map(
         tuple((
-            tag_no_case("fixed"),
+            alt((tag_no_case("fixed"), tag_no_case("tumbling"))),
             sp,
             char('('),
             sp,
@@ -379,6 +903,18 @@ fn window(input: &str) -> IResult<&str, Window> {
         )),
         |(_, _, _, _, length, _, _, _, interval, _, _)| Window::Sliding { length, interval },
     );
+    let session_window = map(
+        tuple((
+            tag_no_case("session"),
+            sp,
+            char('('),
+            sp,
+            duration,
+            sp,
+            char(')'),
+        )),
+        |(_, _, _, _, gap, _, _)| Window::Session { gap },
+    );
     let period_window = map(
         alt((
             value(Period::Day, tag_no_case("day")),
@@ -395,21 +931,98 @@ fn window(input: &str) -> IResult<&str, Window> {
             tuple((
                 tag_no_case("window"),
                 sp,
-                alt((fixed_window, sliding_window, period_window)),
+                cut(valid_window(alt((
+                    fixed_window,
+                    sliding_window,
+                    session_window,
+                    period_window,
+                )))),
             )),
             |(_, _, window)| window,
         ),
     )(input)
 }
 
+fn emit(input: &str) -> IResult<&str, Emit> {
+    context(
+        "emit",
+        map(
+            tuple((
+                tag_no_case("emit"),
+                sp,
+                cut(alt((
+                    value(Emit::Changes, tag_no_case("changes")),
+                    value(Emit::Final, tag_no_case("final")),
+                ))),
+            )),
+            |(_, _, emit)| emit,
+        ),
+    )(input)
+}
+
+/// Parses `LIMIT n` with an optional `OFFSET m`.
+fn limit(input: &str) -> IResult<&str, Limit> {
+    let count = || map_opt(digit1, |s| usize::from_str(s).ok());
+    context(
+        "limit",
+        map(
+            tuple((
+                tag_no_case("limit"),
+                sp,
+                cut(count()),
+                opt(preceded(
+                    tuple((sp, tag_no_case("offset"), sp)),
+                    cut(count()),
+                )),
+            )),
+            |(_, _, limit, offset)| Limit {
+                limit,
+                offset: offset.unwrap_or_default(),
+            },
+        ),
+    )(input)
+}
+
+fn order_by(input: &str) -> IResult<&str, Vec<(Expr, bool)>> {
+    let direction = alt((value(true, keyword("asc")), value(false, keyword("desc"))));
+    let item = map(
+        tuple((expr, opt(preceded(sp, direction)))),
+        |(expr, asc)| (expr, asc.unwrap_or(true)),
+    );
+
+    context(
+        "order_by",
+        map(
+            tuple((
+                tag_no_case("order"),
+                sp,
+                tag_no_case("by"),
+                sp,
+                cut(separated_list1(char(','), delimited(sp, item, sp))),
+            )),
+            |(_, _, _, _, items)| items,
+        ),
+    )(input)
+}
+
 pub fn select(input: &str) -> IResult<&str, Select> {
     let projection = separated_list1(char(','), delimited(sp, projection_field, sp));
-    let where_clause = map(tuple((tag_no_case("where"), sp, expr)), |(_, _, expr)| expr);
-    let having_clause = map(tuple((tag_no_case("having"), sp, expr)), |(_, _, expr)| {
-        expr
-    });
+    let where_clause = map(
+        tuple((tag_no_case("where"), sp, cut(expr))),
+        |(_, _, expr)| expr,
+    );
+    let having_clause = map(
+        tuple((tag_no_case("having"), sp, cut(expr))),
+        |(_, _, expr)| expr,
+    );
     let watermark_by = map(
-        tuple((tag_no_case("watermark"), sp, tag_no_case("by"), sp, expr)),
+        tuple((
+            tag_no_case("watermark"),
+            sp,
+            tag_no_case("by"),
+            sp,
+            cut(alt((bounded_out_of_order, expr))),
+        )),
         |(_, _, _, _, expr)| expr,
     );
     let window = map(
@@ -419,38 +1032,59 @@ pub fn select(input: &str) -> IResult<&str, Select> {
 
     context(
         "select",
-        map(
+        map_opt(
             tuple((
                 tag_no_case("select"),
-                delimited(sp, projection, sp),
-                tag_no_case("from"),
-                delimited(sp, source, sp),
+                cut(delimited(sp, projection, sp)),
+                cut(tag_no_case("from")),
+                cut(delimited(sp, source, sp)),
                 opt(delimited(sp, where_clause, sp)),
                 opt(delimited(sp, group_by, sp)),
+                opt(delimited(sp, pivot, sp)),
                 opt(delimited(sp, having_clause, sp)),
                 opt(delimited(sp, window, sp)),
+                opt(delimited(sp, emit, sp)),
+                opt(delimited(sp, order_by, sp)),
+                opt(delimited(sp, limit, sp)),
             )),
-            |(_, projection, _, source, where_clause, group_by, having_clause, window)| {
+            |(
+                _,
+                projection,
+                _,
+                source,
+                where_clause,
+                group_by,
+                pivot,
+                having_clause,
+                window,
+                emit,
+                order_by,
+                limit,
+            )| {
+                let (group_clause, group_window) = group_by.unwrap_or_default();
                 let mut select = Select {
                     projection,
                     source,
                     where_clause,
                     having_clause,
-                    group_clause: group_by,
+                    group_clause,
+                    pivot,
                     window: None,
                     watermark: None,
+                    emit: emit.unwrap_or_default(),
+                    order_by: order_by.unwrap_or_default(),
+                    limit,
                 };
-                match window {
-                    Some((window, Some(watermark))) => {
-                        select.window = Some(window);
-                        select.watermark = Some(watermark);
-                    }
-                    Some((window, None)) => {
+                match (window, group_window) {
+                    // the window can't be specified in both the group by and window clause
+                    (Some(_), Some(_)) => return None,
+                    (Some((window, watermark)), None) => {
                         select.window = Some(window);
+                        select.watermark = watermark;
                     }
-                    None => {}
+                    (None, window) => select.window = window,
                 }
-                select
+                Some(select)
             },
         ),
     )(input)
@@ -459,6 +1093,7 @@ pub fn select(input: &str) -> IResult<&str, Select> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::dsl::*;
 
     #[test]
     fn test_sp() {
@@ -474,6 +1109,7 @@ mod tests {
         assert_eq!(ident("A1"), Ok(("", "A1")));
         assert_eq!(ident("a_b"), Ok(("", "a_b")));
         assert_eq!(ident("_ab"), Ok(("", "_ab")));
+        assert_eq!(ident("@time"), Ok(("", "@time")));
     }
 
     #[test]
@@ -506,6 +1142,7 @@ mod tests {
         assert_eq!(string(r#""abc""#), Ok(("", "abc".to_string())));
         assert_eq!(string(r#"'abc'"#), Ok(("", "abc".to_string())));
         assert_eq!(string(r#"'\nab\rc'"#), Ok(("", "\nab\rc".to_string())));
+        assert_eq!(string(r#"'a\%b\_c'"#), Ok(("", "a\\%b\\_c".to_string())));
     }
 
     #[test]
@@ -518,22 +1155,73 @@ mod tests {
             literal(r#""abc""#),
             Ok(("", Literal::String("abc".to_string())))
         );
+        assert_eq!(
+            literal(r#"TIMESTAMP '2021-06-01 01:49:00'"#),
+            Ok(("", Literal::Timestamp(1622512140000)))
+        );
+        assert_eq!(
+            literal(r#"timestamp '2021-06-01T09:49:00.5+08:00'"#),
+            Ok(("", Literal::Timestamp(1622512140500)))
+        );
+        assert!(timestamp(r#"timestamp 'abc'"#).is_err());
     }
 
     #[test]
-    fn test_name() {
-        assert_eq!(name(r#""abc""#), Ok(("", "abc".to_string())));
-        assert_eq!(name(r#"abc"#), Ok(("", "abc".to_string())));
+    fn test_time_zone() {
+        assert_eq!(timezone(r#""UTC""#), Ok(("", chrono_tz::UTC)));
+        assert_eq!(
+            timezone(r#""Asia/Shanghai""#),
+            Ok(("", chrono_tz::Asia::Shanghai))
+        );
     }
 
     #[test]
-    fn test_column() {
+    fn test_data_type() {
+        assert_eq!(data_type("int8"), Ok(("", DataType::Int8)));
+        assert_eq!(data_type("int16"), Ok(("", DataType::Int16)));
+        assert_eq!(data_type("int32"), Ok(("", DataType::Int32)));
+        assert_eq!(data_type("int64"), Ok(("", DataType::Int64)));
+        assert_eq!(data_type("uint8"), Ok(("", DataType::UInt8)));
+        assert_eq!(data_type("uint16"), Ok(("", DataType::UInt16)));
+        assert_eq!(data_type("uint32"), Ok(("", DataType::UInt32)));
+        assert_eq!(data_type("uint64"), Ok(("", DataType::UInt64)));
+        assert_eq!(data_type("float32"), Ok(("", DataType::Float32)));
+        assert_eq!(data_type("float64"), Ok(("", DataType::Float64)));
+        assert_eq!(data_type("boolean"), Ok(("", DataType::Boolean)));
+        assert_eq!(data_type("timestamp"), Ok(("", DataType::Timestamp(None))));
         assert_eq!(
-            column(r#""abc".a"#),
+            data_type("decimal128(10, 2)"),
             Ok((
                 "",
-                Expr::Column {
-                    qualifier: Some("abc".to_string()),
+                DataType::Decimal128 {
+                    precision: 10,
+                    scale: 2
+                }
+            ))
+        );
+        assert!(data_type("decimal128(39, 2)").is_err());
+        assert!(data_type("decimal128(2, 3)").is_err());
+
+        assert_eq!(
+            data_type("timestamp timezone \"UTC\""),
+            Ok(("", DataType::Timestamp(Some(chrono_tz::UTC))))
+        );
+    }
+
+    #[test]
+    fn test_name() {
+        assert_eq!(name(r#""abc""#), Ok(("", "abc".to_string())));
+        assert_eq!(name(r#"abc"#), Ok(("", "abc".to_string())));
+    }
+
+    #[test]
+    fn test_column() {
+        assert_eq!(
+            column(r#""abc".a"#),
+            Ok((
+                "",
+                Expr::Column {
+                    qualifier: Some("abc".to_string()),
                     name: "a".to_string()
                 }
             ))
@@ -594,6 +1282,48 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_expr_case() {
+        assert_eq!(
+            expr("case when a > 10 then 'hi' else 'lo' end"),
+            Ok((
+                "",
+                Expr::Case {
+                    operand: None,
+                    when_then: vec![(col("a").gt(crate::dsl::value(10)), crate::dsl::value("hi"))],
+                    else_expr: Some(Box::new(crate::dsl::value("lo"))),
+                }
+            ))
+        );
+        assert_eq!(
+            expr("CASE a WHEN 1 THEN b WHEN 2 THEN b + 1 END + 1"),
+            Ok((
+                "",
+                Expr::Case {
+                    operand: Some(Box::new(col("a"))),
+                    when_then: vec![
+                        (crate::dsl::value(1), col("b")),
+                        (crate::dsl::value(2), col("b") + crate::dsl::value(1))
+                    ],
+                    else_expr: None,
+                } + crate::dsl::value(1)
+            ))
+        );
+        assert_eq!(
+            expr("case_a"),
+            Ok((
+                "",
+                Expr::Column {
+                    qualifier: None,
+                    name: "case_a".to_string()
+                }
+            ))
+        );
+
+        assert!(expr("case a end").is_err());
+        assert!(expr("case when a then b").is_err());
+    }
+
     #[test]
     fn test_expr_call() {
         assert_eq!(
@@ -648,6 +1378,108 @@ mod tests {
                 }
             ))
         );
+
+        assert_eq!(
+            expr_call(r#"LAST(v ORDER BY ts)"#),
+            Ok((
+                "",
+                Expr::Call {
+                    namespace: None,
+                    name: "last_by".to_string(),
+                    args: vec![
+                        Expr::Column {
+                            qualifier: None,
+                            name: "v".to_string()
+                        },
+                        Expr::Column {
+                            qualifier: None,
+                            name: "ts".to_string()
+                        },
+                    ]
+                }
+            ))
+        );
+        assert!(expr_call(r#"sum(a order by b)"#).is_err());
+        // `order` is not parsed as the operator `or`
+        assert_eq!(
+            expr(r#"a ordinal"#),
+            Ok((
+                "ordinal",
+                Expr::Column {
+                    qualifier: None,
+                    name: "a".to_string()
+                }
+            ))
+        );
+        assert!(expr_call(r#"first(a, b order by c)"#).is_err());
+    }
+
+    #[test]
+    fn test_expr_call_named_arguments() {
+        let named = |name: &str, expr: Expr| Expr::NamedArgument {
+            name: name.to_string(),
+            expr: Box::new(expr),
+        };
+
+        assert_eq!(
+            expr_call(r#"encode(x, from => 'utf8', to=>'hex')"#),
+            Ok((
+                "",
+                call(
+                    "encode",
+                    vec![
+                        col("x"),
+                        named("from", crate::dsl::value("utf8")),
+                        named("to", crate::dsl::value("hex"))
+                    ]
+                )
+            ))
+        );
+        assert_eq!(
+            expr_call(r#"round(digits => 1 + 1, value => a)"#),
+            Ok((
+                "",
+                call(
+                    "round",
+                    vec![
+                        named("digits", crate::dsl::value(1i64) + crate::dsl::value(1i64)),
+                        named("value", col("a"))
+                    ]
+                )
+            ))
+        );
+        // `=` is still a comparison
+        assert_eq!(
+            expr_call(r#"f(a = 1)"#),
+            Ok(("", call("f", vec![col("a").eq(crate::dsl::value(1i64))])))
+        );
+    }
+
+    #[test]
+    fn test_expr_call_distinct() {
+        assert_eq!(
+            expr_call(r#"count(distinct user_id)"#),
+            Ok((
+                "",
+                call("count", vec![Expr::Distinct(Box::new(col("user_id")))])
+            ))
+        );
+        assert_eq!(
+            expr_call(r#"SUM(DISTINCT a + 1)"#),
+            Ok((
+                "",
+                call(
+                    "SUM",
+                    vec![Expr::Distinct(Box::new(col("a") + crate::dsl::value(1i64)))]
+                )
+            ))
+        );
+        // a column whose name starts with `distinct`
+        assert_eq!(
+            expr_call(r#"count(distinct_id)"#),
+            Ok(("", call("count", vec![col("distinct_id")])))
+        );
+        assert!(expr_call(r#"count(distinct)"#).is_err());
     }
 
     #[test]
@@ -664,6 +1496,149 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_source_join() {
+        let named = |name: &str, alias: Option<&str>| Source {
+            from: SourceFrom::Named(name.to_string()),
+            alias: alias.map(ToString::to_string),
+        };
+        let join = |left: Source, right: Source, on: Expr| Source {
+            from: SourceFrom::Join(Box::new(Join {
+                join_type: JoinType::Inner,
+                left,
+                right,
+                on,
+                refresh: None,
+            })),
+            alias: None,
+        };
+
+        assert_eq!(
+            source("a join b on a.k = b.k"),
+            Ok((
+                "",
+                join(
+                    named("a", None),
+                    named("b", None),
+                    qualified_col("a", "k").eq(qualified_col("b", "k"))
+                )
+            ))
+        );
+        assert_eq!(
+            source("a as x INNER JOIN b ON x.k = b.k where x.v > 1"),
+            Ok((
+                "where x.v > 1",
+                join(
+                    named("a", Some("x")),
+                    named("b", None),
+                    qualified_col("x", "k").eq(qualified_col("b", "k"))
+                )
+            ))
+        );
+        assert_eq!(
+            source("a join b on a.k = b.k join c as z on a.j = z.j"),
+            Ok((
+                "",
+                join(
+                    join(
+                        named("a", None),
+                        named("b", None),
+                        qualified_col("a", "k").eq(qualified_col("b", "k"))
+                    ),
+                    named("c", Some("z")),
+                    qualified_col("a", "j").eq(qualified_col("z", "j"))
+                )
+            ))
+        );
+        assert_eq!(
+            source("a join b on a.k = b.k refresh 10m where"),
+            Ok((
+                "where",
+                Source {
+                    from: SourceFrom::Join(Box::new(Join {
+                        join_type: JoinType::Inner,
+                        left: named("a", None),
+                        right: named("b", None),
+                        on: qualified_col("a", "k").eq(qualified_col("b", "k")),
+                        refresh: Some(1000 * 10 * 60),
+                    })),
+                    alias: None,
+                }
+            ))
+        );
+        for (sql, join_type) in [
+            ("a inner join b on a.k = b.k", JoinType::Inner),
+            ("a left join b on a.k = b.k", JoinType::Left),
+            ("a LEFT OUTER JOIN b on a.k = b.k", JoinType::Left),
+            ("a right join b on a.k = b.k", JoinType::Right),
+            ("a full outer join b on a.k = b.k", JoinType::Full),
+        ] {
+            assert_eq!(
+                source(sql),
+                Ok((
+                    "",
+                    Source {
+                        from: SourceFrom::Join(Box::new(Join {
+                            join_type,
+                            left: named("a", None),
+                            right: named("b", None),
+                            on: qualified_col("a", "k").eq(qualified_col("b", "k")),
+                            refresh: None,
+                        })),
+                        alias: None,
+                    }
+                ))
+            );
+        }
+        assert!(source("a join b").is_err());
+        assert!(source("a join b on a.k = b.k refresh").is_err());
+    }
+
+    #[test]
+    fn test_source_unnest() {
+        let unnest = |expr: Expr, alias: Option<&str>| Source {
+            from: SourceFrom::Unnest(Box::new(Unnest {
+                source: Source {
+                    from: SourceFrom::Named("a".to_string()),
+                    alias: None,
+                },
+                expr,
+                alias: alias.map(ToString::to_string),
+            })),
+            alias: None,
+        };
+
+        assert_eq!(
+            source("a cross join unnest(tags) where"),
+            Ok((" where", unnest(col("tags"), None)))
+        );
+        assert_eq!(
+            source("a CROSS JOIN UNNEST( string_to_array(tags, ';') ) AS tag"),
+            Ok((
+                "",
+                unnest(
+                    call("string_to_array", vec![col("tags"), crate::dsl::value(";")]),
+                    Some("tag")
+                )
+            ))
+        );
+        assert!(source("a cross join unnest").is_err());
+        assert!(source("a cross join unnest(tags").is_err());
+    }
+
+    #[test]
+    fn test_duration() {
+        assert_eq!(duration("30s"), Ok(("", 30000)));
+        assert_eq!(duration("30 seconds"), Ok(("", 30000)));
+        assert_eq!(duration("5m)"), Ok((")", 1000 * 60 * 5)));
+        assert_eq!(duration("5 MINUTES"), Ok(("", 1000 * 60 * 5)));
+        assert_eq!(duration("10ms"), Ok(("", 10)));
+        assert_eq!(duration("10 milliseconds"), Ok(("", 10)));
+        assert!(duration("5 mins").is_err());
+        assert!(duration("5sec").is_err());
+        assert!(duration("5").is_err());
+    }
+
     #[test]
     fn test_window() {
         assert_eq!(
@@ -687,6 +1662,16 @@ mod tests {
             ))
         );
 
+        assert_eq!(
+            window(r#"window session(30s)"#),
+            Ok(("", Window::Session { gap: 1000 * 30 }))
+        );
+
+        assert_eq!(
+            window(r#"window session(1 minutes)"#),
+            Ok(("", Window::Session { gap: 1000 * 60 }))
+        );
+
         assert_eq!(
             window(r#"window day"#),
             Ok((
@@ -767,8 +1752,12 @@ mod tests {
                     where_clause: None,
                     having_clause: None,
                     group_clause: None,
+                    pivot: None,
                     window: None,
-                    watermark: None
+                    watermark: None,
+                    emit: Emit::Final,
+                    order_by: vec![],
+                    limit: None,
                 },
             )),
         );
@@ -801,8 +1790,12 @@ mod tests {
                     ),
                     having_clause: None,
                     group_clause: None,
+                    pivot: None,
                     window: None,
-                    watermark: None
+                    watermark: None,
+                    emit: Emit::Final,
+                    order_by: vec![],
+                    limit: None,
                 },
             )),
         );
@@ -840,10 +1833,14 @@ mod tests {
                             name: "b".to_string()
                         }]
                     }),
+                    pivot: None,
                     window: Some(Window::Fixed {
                         length: 5 * 1000 * 60
                     }),
                     watermark: None,
+                    emit: Emit::Final,
+                    order_by: vec![],
+                    limit: None,
                 },
             )),
         );
@@ -875,6 +1872,7 @@ mod tests {
                             name: "b".to_string()
                         }]
                     }),
+                    pivot: None,
                     window: Some(Window::Fixed {
                         length: 5 * 1000 * 60
                     }),
@@ -882,8 +1880,550 @@ mod tests {
                         qualifier: None,
                         name: "time".to_string()
                     }),
+                    emit: Emit::Final,
+                    order_by: vec![],
+                    limit: None,
                 },
             )),
         );
     }
+
+    #[test]
+    fn test_bounded_out_of_order() {
+        let (_, sugar) = select(
+            r#"select a from t window fixed(5m) watermark by bounded_out_of_order(time, 5 seconds)"#,
+        )
+        .unwrap();
+        let (_, manual) =
+            select(r#"select a from t window fixed(5m) watermark by timestamp_sub(time, 5000)"#)
+                .unwrap();
+        assert_eq!(sugar, manual);
+        assert_eq!(
+            sugar.watermark.unwrap().to_string(),
+            "timestamp_sub(time,5000)"
+        );
+
+        assert!(select(
+            r#"select a from t window fixed(5m) watermark by bounded_out_of_order(time, 5)"#
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_window_tumbling() {
+        assert_eq!(
+            window(r#"window tumbling(5 minutes)"#),
+            Ok((
+                "",
+                Window::Fixed {
+                    length: 1000 * 5 * 60
+                },
+            ))
+        );
+        assert_eq!(
+            window(r#"window TUMBLING(5 minutes)"#),
+            window(r#"window fixed(5 minutes)"#)
+        );
+    }
+
+    #[test]
+    fn test_invalid_window() {
+        assert!(window(r#"window sliding(5 minutes, 1 minutes)"#).is_ok());
+        assert!(window(r#"window sliding(5 minutes, 5 minutes)"#).is_ok());
+        assert!(matches!(
+            window(r#"window sliding(1 minutes, 5 minutes)"#),
+            Err(nom::Err::Failure(_))
+        ));
+        assert!(matches!(
+            window(r#"window fixed(0 minutes)"#),
+            Err(nom::Err::Failure(_))
+        ));
+        assert!(
+            select(r#"select a from t group by a window sliding(1 minutes, 5 minutes)"#).is_err()
+        );
+
+        // the reason is reported at the start of the window
+        let (offset, contexts) =
+            parse_error("select a from t group by a window sliding(1 minutes, 5 minutes)");
+        assert_eq!(offset, 34);
+        assert!(
+            contexts
+                .contains(&"the interval of a sliding window must not be greater than its length"),
+            "{:?}",
+            contexts
+        );
+        let (offset, contexts) = parse_error("select a from t group by tumble(@time, 0s)");
+        assert_eq!(offset, 32);
+        assert!(
+            contexts.contains(&"the length of a fixed window must be positive"),
+            "{:?}",
+            contexts
+        );
+    }
+
+    #[test]
+    fn test_group_window() {
+        assert_eq!(
+            select("select a, b from t group by b, tumble(@time, 5 minutes)"),
+            select("select a, b from t group by b window fixed(5 minutes)"),
+        );
+        assert_eq!(
+            select("select count(a) from t group by TUMBLE(@time, 5 minutes)"),
+            select("select count(a) from t window fixed(5 minutes)"),
+        );
+        assert_eq!(
+            select("select a, b from t group by hop(@time, 1 minutes, 5 minutes), b"),
+            select("select a, b from t group by b window sliding(5 minutes, 1 minutes)"),
+        );
+
+        assert!(select("select a from t group by tumble(time, 5 minutes)").is_err());
+        assert!(select("select a from t group by hop(@time, 5 minutes, 1 minutes)").is_err());
+        assert!(select(
+            "select a from t group by tumble(@time, 5 minutes), tumble(@time, 1 minutes)"
+        )
+        .is_err());
+        assert!(select(
+            "select a from t group by tumble(@time, 5 minutes) window fixed(5 minutes)"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_projection_over() {
+        let sum_a = Expr::Call {
+            namespace: None,
+            name: "sum".to_string(),
+            args: vec![Expr::Column {
+                qualifier: None,
+                name: "a".to_string(),
+            }],
+        };
+
+        assert_eq!(
+            projection_field(r#"sum(a) over window fixed(5 minutes) as s"#),
+            Ok((
+                "",
+                sum_a
+                    .clone()
+                    .over(Window::Fixed {
+                        length: 5 * 1000 * 60
+                    })
+                    .alias("s")
+            ))
+        );
+        assert_eq!(
+            projection_field(r#"sum(a) over window day"#),
+            Ok((
+                "",
+                sum_a.over(Window::Period {
+                    period: Period::Day
+                })
+            ))
+        );
+        assert_eq!(
+            projection_field(r#"a as b"#),
+            Ok((
+                "",
+                Expr::Column {
+                    qualifier: None,
+                    name: "a".to_string(),
+                }
+                .alias("b")
+            ))
+        );
+    }
+
+    #[test]
+    fn test_not_precedence() {
+        let col = |name: &str| Expr::Column {
+            qualifier: None,
+            name: name.to_string(),
+        };
+
+        assert_eq!(expr("not a = b"), Ok(("", !col("a").eq(col("b")))));
+        assert_eq!(expr("not a and b"), Ok(("", (!col("a")).and(col("b")))));
+        assert_eq!(expr("not (a or b)"), Ok(("", !col("a").or(col("b")))));
+        assert_eq!(expr("not not a"), Ok(("", !!col("a"))));
+        assert_eq!(
+            expr("a or not b and c"),
+            Ok(("", col("a").or((!col("b")).and(col("c")))))
+        );
+        assert_eq!(expr("nota"), Ok(("", col("nota"))));
+        assert_eq!(expr("-a + b"), Ok(("", -col("a") + col("b"))));
+    }
+
+    #[test]
+    fn test_expr_logical() {
+        let col = |name: &str| Expr::Column {
+            qualifier: None,
+            name: name.to_string(),
+        };
+
+        assert_eq!(expr("a and b"), Ok(("", col("a").and(col("b")))));
+        assert_eq!(expr("a or b"), Ok(("", col("a").or(col("b")))));
+        assert_eq!(
+            expr("a and b or c"),
+            Ok(("", col("a").and(col("b")).or(col("c"))))
+        );
+        assert_eq!(
+            expr("a or b and c"),
+            Ok(("", col("a").or(col("b").and(col("c")))))
+        );
+    }
+
+    #[test]
+    fn test_expr_like() {
+        let col = |name: &str| Expr::Column {
+            qualifier: None,
+            name: name.to_string(),
+        };
+        let pattern = |pattern: &str| Expr::Literal(Literal::String(pattern.to_string()));
+
+        assert_eq!(
+            expr("name like 'error_%'"),
+            Ok(("", col("name").like(pattern("error_%"))))
+        );
+        assert_eq!(
+            expr("name NOT LIKE '%\\%'"),
+            Ok(("", col("name").not_like(pattern("%\\%"))))
+        );
+        assert_eq!(
+            expr("not a like 'x' and b"),
+            Ok(("", (!col("a").like(pattern("x"))).and(col("b"))))
+        );
+        assert_eq!(expr("a likely"), Ok(("likely", col("a"))));
+    }
+
+    #[test]
+    fn test_expr_in_list() {
+        let col = |name: &str| Expr::Column {
+            qualifier: None,
+            name: name.to_string(),
+        };
+        let int = |n: i64| Expr::Literal(Literal::Int(n));
+        let string = |s: &str| Expr::Literal(Literal::String(s.to_string()));
+
+        assert_eq!(
+            expr("status in ('open', 'pending')"),
+            Ok((
+                "",
+                col("status").in_list(vec![string("open"), string("pending")], false)
+            ))
+        );
+        assert_eq!(
+            expr("a + 1 not in (1,b) and c"),
+            Ok((
+                "",
+                (col("a") + int(1))
+                    .in_list(vec![int(1), col("b")], true)
+                    .and(col("c"))
+            ))
+        );
+        assert_eq!(
+            expr("a in (1) = b"),
+            Ok(("", col("a").in_list(vec![int(1)], false).eq(col("b"))))
+        );
+        assert!(expr("a in ()").is_err());
+    }
+
+    #[test]
+    fn test_expr_between() {
+        let col = |name: &str| Expr::Column {
+            qualifier: None,
+            name: name.to_string(),
+        };
+        let int = |n: i64| Expr::Literal(Literal::Int(n));
+
+        assert_eq!(
+            expr("a between 1 and x+1"),
+            Ok(("", col("a").between(int(1), col("x") + int(1), false)))
+        );
+        assert_eq!(
+            expr("a not between 1 and 2 and b"),
+            Ok(("", col("a").between(int(1), int(2), true).and(col("b"))))
+        );
+        assert_eq!(
+            expr("a between 1 and 2").unwrap().1.to_string(),
+            "a between 1 and 2"
+        );
+        assert_eq!(
+            expr("a not between b and 2 * c").unwrap().1.to_string(),
+            "a not between b and (2 * c)"
+        );
+        assert!(expr("a between 1").is_err());
+    }
+
+    #[test]
+    fn test_expr_comparison() {
+        let col = |name: &str| Expr::Column {
+            qualifier: None,
+            name: name.to_string(),
+        };
+
+        assert_eq!(expr("a <= b"), Ok(("", col("a").lt_eq(col("b")))));
+        assert_eq!(expr("a < b"), Ok(("", col("a").lt(col("b")))));
+        assert_eq!(expr("a >= b"), Ok(("", col("a").gt_eq(col("b")))));
+        assert_eq!(expr("a > b"), Ok(("", col("a").gt(col("b")))));
+        assert_eq!(expr("a <> b"), Ok(("", col("a").not_eq(col("b")))));
+        assert_eq!(expr("a<=b"), Ok(("", col("a").lt_eq(col("b")))));
+        assert_eq!(expr("a>=b"), Ok(("", col("a").gt_eq(col("b")))));
+        assert_eq!(
+            expr("a >= b and a <= c"),
+            Ok(("", col("a").gt_eq(col("b")).and(col("a").lt_eq(col("c")))))
+        );
+    }
+
+    #[test]
+    fn test_expr_sub_query() {
+        let (_, sub_query) = select("select label from dim where dim.c = c").unwrap();
+        assert_eq!(
+            expr("(select label from dim where dim.c = c)"),
+            Ok(("", Expr::SubQuery(Box::new(sub_query))))
+        );
+        assert_eq!(
+            expr("(selection + 1)"),
+            Ok(("", col("selection") + Expr::Literal(Literal::Int(1))))
+        );
+    }
+
+    #[test]
+    fn test_expr_cast() {
+        let col = |name: &str| Expr::Column {
+            qualifier: None,
+            name: name.to_string(),
+        };
+
+        assert_eq!(
+            expr("a::float64"),
+            Ok(("", col("a").cast(DataType::Float64)))
+        );
+        assert_eq!(expr("a::float64"), expr("CAST(a AS float64)"));
+        assert_eq!(
+            expr("(a+b)::int32"),
+            Ok(("", (col("a") + col("b")).cast(DataType::Int32)))
+        );
+        assert_eq!(expr("(a+b)::int32"), expr("cast(a + b as int32)"));
+
+        // the shorthand binds tighter than the operators, and can be chained
+        assert_eq!(
+            expr("a + b :: int32"),
+            Ok(("", col("a") + col("b").cast(DataType::Int32)))
+        );
+        assert_eq!(expr("-a::int8"), Ok(("", -col("a").cast(DataType::Int8))));
+        assert_eq!(
+            expr("a::decimal128(10, 2)::string"),
+            Ok((
+                "",
+                col("a")
+                    .cast(DataType::Decimal128 {
+                        precision: 10,
+                        scale: 2
+                    })
+                    .cast(DataType::String)
+            ))
+        );
+        assert!(expr("a::int128").is_err());
+        assert!(expr("cast(a int32)").is_err());
+    }
+
+    #[test]
+    fn test_time_column() {
+        assert_eq!(
+            expr("@time > timestamp '2021-06-01 01:49:00'"),
+            Ok((
+                "",
+                Expr::Column {
+                    qualifier: None,
+                    name: "@time".to_string(),
+                }
+                .gt(Expr::Literal(Literal::Timestamp(1622512140000)))
+            ))
+        );
+        assert_eq!(
+            expr("timestamp"),
+            Ok((
+                "",
+                Expr::Column {
+                    qualifier: None,
+                    name: "timestamp".to_string(),
+                }
+            ))
+        );
+    }
+
+    #[test]
+    fn test_order_by() {
+        assert_eq!(
+            order_by("order by a desc, b"),
+            Ok(("", vec![(col("a"), false), (col("b"), true)]))
+        );
+        assert_eq!(
+            order_by("ORDER BY count(a) ASC"),
+            Ok(("", vec![(call("count", vec![col("a")]), true)]))
+        );
+        assert!(order_by("order by").is_err());
+
+        assert_eq!(
+            select("select c, count(a) as cnt from t group by c window fixed(5 minutes) order by cnt desc limit 3")
+                .unwrap()
+                .1
+                .order_by,
+            vec![(col("cnt"), false)]
+        );
+    }
+
+    #[test]
+    fn test_limit() {
+        assert_eq!(
+            limit("limit 10"),
+            Ok((
+                "",
+                Limit {
+                    limit: 10,
+                    offset: 0
+                }
+            ))
+        );
+        assert_eq!(
+            limit("LIMIT 10 OFFSET 5"),
+            Ok((
+                "",
+                Limit {
+                    limit: 10,
+                    offset: 5
+                }
+            ))
+        );
+        assert!(limit("limit -1").is_err());
+        assert!(limit("limit 10 offset").is_err());
+
+        assert_eq!(select("select a from t").unwrap().1.limit, None);
+        assert_eq!(
+            select("select a from t group by a window fixed(5 minutes) emit changes limit 3")
+                .unwrap()
+                .1
+                .limit,
+            Some(Limit {
+                limit: 3,
+                offset: 0
+            })
+        );
+    }
+
+    #[test]
+    fn test_emit() {
+        assert_eq!(emit("emit changes"), Ok(("", Emit::Changes)));
+        assert_eq!(emit("EMIT FINAL"), Ok(("", Emit::Final)));
+
+        assert_eq!(
+            select(r#"select a from t group by a window fixed(5 minutes)"#)
+                .unwrap()
+                .1
+                .emit,
+            Emit::Final
+        );
+        assert_eq!(
+            select(r#"select a from t group by a window fixed(5 minutes) emit changes"#)
+                .unwrap()
+                .1
+                .emit,
+            Emit::Changes
+        );
+        assert_eq!(
+            select(
+                r#"select a from t group by a window fixed(5 minutes) watermark by time emit changes"#
+            )
+            .unwrap()
+            .1
+            .emit,
+            Emit::Changes
+        );
+    }
+
+    #[test]
+    fn test_pivot() {
+        assert_eq!(
+            pivot("pivot (sum(v) for cat in ('a', 'b',\"c\"))"),
+            Ok((
+                "",
+                Pivot {
+                    aggr_expr: call("sum", vec![col("v")]),
+                    pivot_expr: col("cat"),
+                    values: vec!["a".into(), "b".into(), "c".into()],
+                }
+            ))
+        );
+        assert_eq!(
+            pivot("PIVOT(avg(v) FOR x IN (1,2))"),
+            Ok((
+                "",
+                Pivot {
+                    aggr_expr: call("avg", vec![col("v")]),
+                    pivot_expr: col("x"),
+                    values: vec![1.into(), 2.into()],
+                }
+            ))
+        );
+        assert!(pivot("pivot (sum(v) for cat in ())").is_err());
+
+        let select = select(
+            "select region from t group by region pivot (sum(v) for cat in ('a')) window fixed(5 minutes)",
+        )
+        .unwrap()
+        .1;
+        assert_eq!(
+            select.pivot,
+            Some(Pivot {
+                aggr_expr: call("sum", vec![col("v")]),
+                pivot_expr: col("cat"),
+                values: vec!["a".into()],
+            })
+        );
+        assert_eq!(
+            select.window,
+            Some(Window::Fixed {
+                length: 5 * 1000 * 60
+            })
+        );
+    }
+
+    fn parse_error(sql: &str) -> (usize, Vec<&'static str>) {
+        match select(sql) {
+            Err(nom::Err::Failure(err)) => {
+                let offset = sql.len() - err.errors[0].0.len();
+                let contexts = err
+                    .errors
+                    .iter()
+                    .filter_map(|(_, kind)| match kind {
+                        nom::error::VerboseErrorKind::Context(ctx) => Some(*ctx),
+                        _ => None,
+                    })
+                    .collect();
+                (offset, contexts)
+            }
+            res => panic!("expected a parse failure, but got {:?}", res),
+        }
+    }
+
+    #[test]
+    fn test_error_position() {
+        let (offset, contexts) = parse_error("select a from");
+        assert_eq!(offset, 13);
+        assert!(contexts.contains(&"source"), "{:?}", contexts);
+
+        let (offset, contexts) = parse_error("select sum(a from t");
+        assert_eq!(offset, 13);
+        assert!(contexts.contains(&"expr_call"), "{:?}", contexts);
+
+        let (offset, contexts) = parse_error("select a from t where");
+        assert_eq!(offset, 21);
+        assert!(contexts.contains(&"expr"), "{:?}", contexts);
+
+        let (offset, contexts) = parse_error("select a from t emit now");
+        assert_eq!(offset, 21);
+        assert!(contexts.contains(&"emit"), "{:?}", contexts);
+
+        let err = select("select a from").unwrap_err();
+        assert!(format_error("select a from", err).contains("line 1"));
+    }
 }
@@ -2,6 +2,7 @@ use anyhow::Result;
 use nom::combinator::{eof, map};
 use nom::sequence::tuple;
 
+use crate::expr::{BinaryOperator, Expr};
 use crate::sql::ast::{Select, Source, SourceFrom};
 use crate::sql::parser::sp;
 use crate::sql::SqlContext;
@@ -12,7 +13,7 @@ pub fn create_data_frame_with_sql(ctx: &dyn SqlContext, sql: &str) -> Result<Dat
         tuple((sp, crate::sql::parser::select, sp, eof)),
         |(_, select, _, _)| select,
     )(sql)
-    .map_err(|err| anyhow::anyhow!("{}", err))?;
+    .map_err(|err| anyhow::anyhow!("{}", crate::sql::parser::format_error(sql, err)))?;
     create_data_frame(ctx, select)
 }
 
@@ -22,28 +23,128 @@ pub fn create_data_frame(ctx: &dyn SqlContext, select: Select) -> Result<DataFra
         df = df.filter(condition);
     }
 
-    match (select.group_clause, select.window, select.watermark) {
-        (Some(group_by), Some(window), watermark) => {
-            df = df.aggregate(group_by.exprs, select.projection, window, watermark);
-        }
-        (None, Some(window), watermark) => {
-            df = df.aggregate(vec![], select.projection, window, watermark);
+    // the scalar subqueries are looked up before the projection, and replaced by the columns of
+    // the results
+    let mut projection = Vec::with_capacity(select.projection.len());
+    for (idx, expr) in select.projection.into_iter().enumerate() {
+        let (new_df, expr) = plan_sub_query(ctx, df, expr, idx)?;
+        df = new_df;
+        projection.push(expr);
+    }
+
+    // the window can also be specified on the aggregate expressions
+    let window = select
+        .window
+        .or_else(|| projection.iter().find_map(|expr| expr.window()));
+
+    match (select.group_clause, window, select.watermark) {
+        (group_by, Some(window), watermark) => {
+            let group_exprs = group_by.map(|group_by| group_by.exprs).unwrap_or_default();
+            df = match select.pivot {
+                Some(pivot) => df.pivot(
+                    group_exprs,
+                    projection,
+                    pivot,
+                    window,
+                    watermark,
+                    select.emit,
+                ),
+                None => df.aggregate(group_exprs, projection, window, watermark, select.emit),
+            };
         }
         (Some(_), None, _) => {
             anyhow::bail!("the window clause is missing.");
         }
         (None, None, _) => {
-            df = df.select(select.projection);
+            anyhow::ensure!(select.pivot.is_none(), "the window clause is missing.");
+            df = df.select(projection);
         }
     }
 
     if let Some(condition) = select.having_clause {
-        df = df.filter(condition);
+        df = df.having(condition)?;
+    }
+
+    if !select.order_by.is_empty() {
+        df = df.order_by(select.order_by)?;
+    }
+
+    if let Some(limit) = select.limit {
+        df = df.limit(limit.limit, limit.offset);
     }
 
     Ok(df)
 }
 
+/// Plans a scalar subquery of the projection as a lookup, the other fields are returned unchanged.
+fn plan_sub_query(
+    ctx: &dyn SqlContext,
+    df: DataFrame,
+    expr: Expr,
+    idx: usize,
+) -> Result<(DataFrame, Expr)> {
+    let (select, alias) = match expr {
+        Expr::SubQuery(select) => (select, None),
+        Expr::Alias(expr, alias) => match *expr {
+            Expr::SubQuery(select) => (select, Some(alias)),
+            expr => return Ok((df, expr.alias(alias))),
+        },
+        _ => return Ok((df, expr)),
+    };
+    let Select {
+        mut projection,
+        source,
+        where_clause,
+        having_clause,
+        group_clause,
+        pivot,
+        window,
+        order_by,
+        limit,
+        ..
+    } = *select;
+    anyhow::ensure!(
+        having_clause.is_none()
+            && group_clause.is_none()
+            && pivot.is_none()
+            && window.is_none()
+            && order_by.is_empty()
+            && limit.is_none(),
+        "a subquery only supports the select, from and where clauses."
+    );
+    anyhow::ensure!(
+        projection.len() == 1,
+        "a subquery must select exactly one field."
+    );
+    let on = match where_clause {
+        Some(Expr::Binary {
+            op: BinaryOperator::Eq,
+            lhs,
+            rhs,
+        }) => (*lhs, *rhs),
+        _ => anyhow::bail!(
+            "the where clause of a subquery must be an equality between the subquery and the outer query."
+        ),
+    };
+
+    let value = projection.remove(0);
+    let alias = alias.unwrap_or_else(|| value.create_name());
+    let value = match value {
+        Expr::Alias(expr, _) => *expr,
+        expr => expr,
+    };
+    let name = format!("@subquery_{}", idx);
+    let right = create_source(ctx, qualify_by_name(source))?;
+    Ok((
+        df.lookup(right, on, value.alias(name.clone())),
+        Expr::Column {
+            qualifier: None,
+            name,
+        }
+        .alias(alias),
+    ))
+}
+
 fn create_source(ctx: &dyn SqlContext, source: Source) -> Result<DataFrame> {
     match source.from {
         SourceFrom::Named(name) => {
@@ -57,5 +158,36 @@ fn create_source(ctx: &dyn SqlContext, source: Source) -> Result<DataFrame> {
             ))
         }
         SourceFrom::SubQuery(select) => create_data_frame(ctx, *select),
+        SourceFrom::Join(join) => {
+            let on = match join.on {
+                Expr::Binary {
+                    op: BinaryOperator::Eq,
+                    lhs,
+                    rhs,
+                } => (*lhs, *rhs),
+                on => anyhow::bail!("the join condition must be an equality, but got '{}'.", on),
+            };
+            let left = create_source(ctx, qualify_by_name(join.left))?;
+            let right = create_source(ctx, qualify_by_name(join.right))?;
+            Ok(left.join(right, join.join_type, on, join.refresh))
+        }
+        SourceFrom::Unnest(unnest) => {
+            let alias = unnest.alias.unwrap_or_else(|| "unnest".to_string());
+            Ok(
+                create_source(ctx, qualify_by_name(unnest.source))?
+                    .unnest(unnest.expr.alias(alias)),
+            )
+        }
+    }
+}
+
+/// The columns of a joined source without an alias are qualified by the name of the source.
+fn qualify_by_name(source: Source) -> Source {
+    match (source.from, source.alias) {
+        (SourceFrom::Named(name), None) => Source {
+            alias: Some(name.clone()),
+            from: SourceFrom::Named(name),
+        },
+        (from, alias) => Source { from, alias },
     }
 }
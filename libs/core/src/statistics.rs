@@ -0,0 +1,157 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use futures_util::StreamExt;
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+use crate::array::{compute, Array, ArrayExt, ArrayRef, Scalar};
+use crate::dataset::{DataSet, Field, Schema, SchemaRef};
+use crate::expr::HyperLogLog;
+use crate::SourceProvider;
+
+/// The statistics of a column of a bounded source.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ColumnStatistics {
+    pub name: String,
+    /// The minimum of the non-null values, or `Null` if there are none.
+    pub min: Scalar,
+    /// The maximum of the non-null values, or `Null` if there are none.
+    pub max: Scalar,
+    pub null_count: usize,
+    /// An estimate of the number of distinct non-null values, see `approx_count_distinct`.
+    pub distinct_count: usize,
+}
+
+struct ColumnCollector {
+    /// A schema with only this column, the minimum and the maximum of the datasets are reduced
+    /// again as a dataset with it.
+    schema: SchemaRef,
+    min: Scalar,
+    max: Scalar,
+    null_count: usize,
+    distinct: HyperLogLog,
+}
+
+impl ColumnCollector {
+    fn update(&mut self, array: &dyn Array) -> Result<()> {
+        self.min = self.reduce(self.min.clone(), compute::min(array), compute::min)?;
+        self.max = self.reduce(self.max.clone(), compute::max(array), compute::max)?;
+        for index in 0..array.len() {
+            if array.is_valid(index) {
+                self.distinct
+                    .add(&bincode::serialize(&array.scalar_value(index))?);
+            } else {
+                self.null_count += 1;
+            }
+        }
+        Ok(())
+    }
+
+    fn reduce(&self, a: Scalar, b: Scalar, f: fn(&dyn Array) -> Scalar) -> Result<Scalar> {
+        if a.is_null() {
+            return Ok(b);
+        }
+        if b.is_null() {
+            return Ok(a);
+        }
+        let dataset = DataSet::try_from_rows(self.schema.clone(), vec![vec![a], vec![b]])?;
+        let array: ArrayRef = dataset.column(0).expect("a single column");
+        Ok(f(&*array))
+    }
+}
+
+/// Reads the bounded source to the end, and computes the statistics of each of its columns.
+pub async fn collect_statistics(source_provider: &SourceProvider) -> Result<Vec<ColumnStatistics>> {
+    anyhow::ensure!(
+        source_provider.is_bounded(),
+        "the statistics can only be collected from a bounded source."
+    );
+
+    let schema = source_provider.schema()?;
+    let mut collectors = schema
+        .fields()
+        .iter()
+        .map(|field| {
+            Ok(ColumnCollector {
+                schema: Arc::new(Schema::try_new(vec![Field::new(
+                    &field.name,
+                    field.data_type.clone(),
+                )])?),
+                min: Scalar::Null,
+                max: Scalar::Null,
+                null_count: 0,
+                distinct: HyperLogLog::default(),
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut stream = source_provider.create_stream(None)?;
+    while let Some(source_dataset) = stream.next().await.transpose()? {
+        for (collector, column) in collectors.iter_mut().zip(source_dataset.dataset.columns()) {
+            collector.update(&**column)?;
+        }
+    }
+
+    Ok(schema
+        .fields()
+        .iter()
+        .zip(collectors)
+        .map(|(field, collector)| ColumnStatistics {
+            name: field.name.clone(),
+            min: collector.min,
+            max: collector.max,
+            null_count: collector.null_count,
+            distinct_count: collector.distinct.estimate() as usize,
+        })
+        .collect_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::array::DataType;
+    use crate::sources::json::{Options, Provider};
+    use crate::SourceProviderWrapper;
+
+    #[tokio::test]
+    async fn test_collect_statistics() {
+        let schema = Arc::new(
+            Schema::try_new(vec![
+                Field::new("a", DataType::Int64),
+                Field::new("b", DataType::String),
+            ])
+            .unwrap(),
+        );
+        let provider: SourceProvider = Arc::new(SourceProviderWrapper(Provider::new_from_memory(
+            Options { batch_size: 2 },
+            schema,
+            r#"{"a": 3, "b": "x"}
+{"b": "y"}
+{"a": -2, "b": "x"}
+{"a": 7}
+{"b": "z"}
+"#,
+        )));
+
+        assert_eq!(
+            collect_statistics(&provider).await.unwrap(),
+            vec![
+                ColumnStatistics {
+                    name: "a".to_string(),
+                    min: Scalar::Int64(-2),
+                    max: Scalar::Int64(7),
+                    null_count: 2,
+                    distinct_count: 3,
+                },
+                ColumnStatistics {
+                    name: "b".to_string(),
+                    min: Scalar::String("x".into()),
+                    max: Scalar::String("z".into()),
+                    null_count: 1,
+                    distinct_count: 3,
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,112 @@
+//! Helpers to run the streams over in-memory sources in tests.
+
+use std::sync::Arc;
+
+use anyhow::Result;
+use futures_util::TryStreamExt;
+
+use crate::dataset::{DataSet, SchemaRef};
+use crate::expr::Expr;
+use crate::sources::csv::{Options, Provider};
+use crate::sql::{SqlContext, SqlSourceProvider};
+use crate::{DataFrame, DataStream, SourceProvider, SourceProviderWrapper};
+
+/// Creates a source that reads `data` as CSV without a header, `batch_size` rows per dataset.
+pub fn memory_source(schema: SchemaRef, data: &str, batch_size: usize) -> SourceProvider {
+    Arc::new(SourceProviderWrapper(Provider::new_from_memory(
+        Options {
+            delimiter: b',',
+            has_header: false,
+            batch_size,
+            tail: false,
+            poll_interval: 1000,
+        },
+        schema,
+        data,
+    )))
+}
+
+/// A `SqlContext` in which every source name refers to the same source.
+pub struct MemoryContext {
+    pub source_provider: SourceProvider,
+    pub time_expr: Option<Expr>,
+}
+
+impl SqlContext for MemoryContext {
+    fn create_source_provider(&self, _name: &str) -> Result<Option<SqlSourceProvider>> {
+        Ok(Some(SqlSourceProvider {
+            source_provider: self.source_provider.clone(),
+            time_expr: self.time_expr.clone(),
+        }))
+    }
+}
+
+/// Runs the stream to the end, and returns all the datasets that it outputs.
+pub async fn collect_stream_into_vec(stream: DataStream) -> Result<Vec<DataSet>> {
+    stream.try_collect().await
+}
+
+/// Runs the data frame from the beginning to the end, and returns all the datasets that it
+/// outputs.
+pub async fn collect_into_vec(df: DataFrame) -> Result<Vec<DataSet>> {
+    collect_stream_into_vec(df.into_stream(None)?).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::array::DataType;
+    use crate::dataset::{CsvOptions, Field, Schema};
+    use crate::dsl::*;
+
+    #[tokio::test]
+    async fn test_filter_aggregate() {
+        let schema = Arc::new(
+            Schema::try_new(vec![
+                Field::new("time", DataType::Timestamp(None)),
+                Field::new("user", DataType::String),
+                Field::new("amount", DataType::Int64),
+            ])
+            .unwrap(),
+        );
+        let ctx = MemoryContext {
+            source_provider: memory_source(
+                schema,
+                r#"
+1622509200000,a,10
+1622509260000,b,20
+1622509320000,a,-5
+1622509380000,a,30
+1622509440000,b,40
+1622509500000,c,-1
+1622509560000,c,5
+"#,
+                3,
+            ),
+            time_expr: Some(col("time")),
+        };
+
+        let df = DataFrame::from_sql(
+            &ctx,
+            "select user, sum(amount) as total from t where amount > 0 group by user window fixed(60 minutes) order by user",
+        )
+        .unwrap();
+        let output = collect_into_vec(df).await.unwrap();
+        assert_eq!(output.len(), 1);
+        output[0].assert_eq(
+            &DataSet::from_csv_slice(
+                Arc::new(
+                    Schema::try_new(vec![
+                        Field::new("user", DataType::String),
+                        Field::new("total", DataType::Float64),
+                        Field::new("@time", DataType::Timestamp(None)),
+                    ])
+                    .unwrap(),
+                ),
+                CsvOptions::default(),
+                b"a,40,1622509200000\nb,60,1622509200000\nc,5,1622509200000\n",
+            )
+            .unwrap(),
+        );
+    }
+}
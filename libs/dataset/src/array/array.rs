@@ -2,11 +2,15 @@ use std::any::Any;
 use std::fmt::Debug;
 use std::sync::Arc;
 
-use serde::{Serialize, Serializer};
+use serde::de::DeserializeSeed;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
+use crate::array::list_array::DeListArray;
+use crate::array::struct_array::DeStructArray;
 use crate::array::{
-    ArrayExt, BooleanType, DataType, Float32Type, Float64Type, Int16Type, Int32Type, Int64Type,
-    Int8Type, NullArray, PrimitiveArray, StringArray, TimestampType,
+    ArrayExt, BooleanType, DataType, DeDecimal128Array, Decimal128Array, Float32Type, Float64Type,
+    Int16Type, Int32Type, Int64Type, Int8Type, ListArray, NullArray, PrimitiveArray, StringArray,
+    StructArray, TimestampType, UInt16Type, UInt32Type, UInt64Type, UInt8Type,
 };
 
 /// Trait for dealing with different types of array at runtime when the type of the array is not known in advance.
@@ -88,14 +92,30 @@ impl PartialEq for dyn Array {
             DataType::Int16 => eq_primitive_array!(Int16Type, self, other),
             DataType::Int32 => eq_primitive_array!(Int32Type, self, other),
             DataType::Int64 => eq_primitive_array!(Int64Type, self, other),
+            DataType::UInt8 => eq_primitive_array!(UInt8Type, self, other),
+            DataType::UInt16 => eq_primitive_array!(UInt16Type, self, other),
+            DataType::UInt32 => eq_primitive_array!(UInt32Type, self, other),
+            DataType::UInt64 => eq_primitive_array!(UInt64Type, self, other),
             DataType::Float32 => eq_primitive_array!(Float32Type, self, other),
             DataType::Float64 => eq_primitive_array!(Float64Type, self, other),
             DataType::Boolean => eq_primitive_array!(BooleanType, self, other),
             DataType::Timestamp(_) => eq_primitive_array!(TimestampType, self, other),
+            DataType::Decimal128 { .. } => {
+                self.as_any().downcast_ref::<Decimal128Array>()
+                    == other.as_any().downcast_ref::<Decimal128Array>()
+            }
             DataType::String => {
                 self.as_any().downcast_ref::<StringArray>()
                     == other.as_any().downcast_ref::<StringArray>()
             }
+            DataType::List(_) => {
+                self.as_any().downcast_ref::<ListArray>()
+                    == other.as_any().downcast_ref::<ListArray>()
+            }
+            DataType::Struct(_) => {
+                self.as_any().downcast_ref::<StructArray>()
+                    == other.as_any().downcast_ref::<StructArray>()
+            }
         }
     }
 }
@@ -119,6 +139,18 @@ impl Serialize for dyn Array {
             DataType::Int64 => self
                 .downcast_ref::<PrimitiveArray<Int64Type>>()
                 .serialize(serializer),
+            DataType::UInt8 => self
+                .downcast_ref::<PrimitiveArray<UInt8Type>>()
+                .serialize(serializer),
+            DataType::UInt16 => self
+                .downcast_ref::<PrimitiveArray<UInt16Type>>()
+                .serialize(serializer),
+            DataType::UInt32 => self
+                .downcast_ref::<PrimitiveArray<UInt32Type>>()
+                .serialize(serializer),
+            DataType::UInt64 => self
+                .downcast_ref::<PrimitiveArray<UInt64Type>>()
+                .serialize(serializer),
             DataType::Float32 => self
                 .downcast_ref::<PrimitiveArray<Float32Type>>()
                 .serialize(serializer),
@@ -131,7 +163,56 @@ impl Serialize for dyn Array {
             DataType::Timestamp(_) => self
                 .downcast_ref::<PrimitiveArray<TimestampType>>()
                 .serialize(serializer),
+            DataType::Decimal128 { .. } => {
+                self.downcast_ref::<Decimal128Array>().serialize(serializer)
+            }
             DataType::String => self.downcast_ref::<StringArray>().serialize(serializer),
+            DataType::List(_) => self.downcast_ref::<ListArray>().serialize(serializer),
+            DataType::Struct(_) => self.downcast_ref::<StructArray>().serialize(serializer),
         }
     }
 }
+
+/// Deserializes an array of the specified datatype.
+pub(crate) struct DeArray(pub(crate) DataType);
+
+impl<'de> DeserializeSeed<'de> for DeArray {
+    type Value = ArrayRef;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Ok(match self.0 {
+            DataType::Null => Arc::new(NullArray::deserialize(deserializer)?),
+            DataType::Int8 => Arc::new(PrimitiveArray::<Int8Type>::deserialize(deserializer)?),
+            DataType::Int16 => Arc::new(PrimitiveArray::<Int16Type>::deserialize(deserializer)?),
+            DataType::Int32 => Arc::new(PrimitiveArray::<Int32Type>::deserialize(deserializer)?),
+            DataType::Int64 => Arc::new(PrimitiveArray::<Int64Type>::deserialize(deserializer)?),
+            DataType::UInt8 => Arc::new(PrimitiveArray::<UInt8Type>::deserialize(deserializer)?),
+            DataType::UInt16 => Arc::new(PrimitiveArray::<UInt16Type>::deserialize(deserializer)?),
+            DataType::UInt32 => Arc::new(PrimitiveArray::<UInt32Type>::deserialize(deserializer)?),
+            DataType::UInt64 => Arc::new(PrimitiveArray::<UInt64Type>::deserialize(deserializer)?),
+            DataType::Float32 => {
+                Arc::new(PrimitiveArray::<Float32Type>::deserialize(deserializer)?)
+            }
+            DataType::Float64 => {
+                Arc::new(PrimitiveArray::<Float64Type>::deserialize(deserializer)?)
+            }
+            DataType::Boolean => {
+                Arc::new(PrimitiveArray::<BooleanType>::deserialize(deserializer)?)
+            }
+            DataType::Timestamp(_) => {
+                Arc::new(PrimitiveArray::<TimestampType>::deserialize(deserializer)?)
+            }
+            DataType::Decimal128 { precision, scale } => {
+                Arc::new(DeDecimal128Array { precision, scale }.deserialize(deserializer)?)
+            }
+            DataType::String => Arc::new(StringArray::deserialize(deserializer)?),
+            DataType::List(item_type) => {
+                Arc::new(DeListArray(*item_type).deserialize(deserializer)?)
+            }
+            DataType::Struct(fields) => Arc::new(DeStructArray(fields).deserialize(deserializer)?),
+        })
+    }
+}
@@ -1,8 +1,9 @@
 use std::any::Any;
 
 use crate::array::{
-    Array, BooleanType, DataType, Float32Type, Float64Type, Int16Type, Int32Type, Int64Type,
-    Int8Type, PrimitiveArray, Scalar, StringArray, TimestampType,
+    Array, BooleanType, DataType, Decimal128Array, Float32Type, Float64Type, Int16Type, Int32Type,
+    Int64Type, Int8Type, ListArray, PrimitiveArray, Scalar, StringArray, StructArray,
+    TimestampType, UInt16Type, UInt32Type, UInt64Type, UInt8Type,
 };
 
 macro_rules! get_scalar_value {
@@ -39,15 +40,50 @@ pub trait ArrayExt: Array {
             DataType::Int16 => get_scalar_value!(self, index, Int16Type, Int16),
             DataType::Int32 => get_scalar_value!(self, index, Int32Type, Int32),
             DataType::Int64 => get_scalar_value!(self, index, Int64Type, Int64),
+            DataType::UInt8 => get_scalar_value!(self, index, UInt8Type, UInt8),
+            DataType::UInt16 => get_scalar_value!(self, index, UInt16Type, UInt16),
+            DataType::UInt32 => get_scalar_value!(self, index, UInt32Type, UInt32),
+            DataType::UInt64 => get_scalar_value!(self, index, UInt64Type, UInt64),
             DataType::Float32 => get_scalar_value!(self, index, Float32Type, Float32),
             DataType::Float64 => get_scalar_value!(self, index, Float64Type, Float64),
             DataType::Boolean => get_scalar_value!(self, index, BooleanType, Boolean),
             DataType::Timestamp(_) => get_scalar_value!(self, index, TimestampType, Timestamp),
+            DataType::Decimal128 { precision, scale } => self
+                .downcast_ref::<Decimal128Array>()
+                .value_opt(index)
+                .map(|value| Scalar::Decimal128 {
+                    value,
+                    precision,
+                    scale,
+                })
+                .unwrap_or_default(),
             DataType::String => self
                 .downcast_ref::<StringArray>()
                 .value_opt(index)
                 .map(|s| Scalar::String(s.into()))
                 .unwrap_or_default(),
+            DataType::List(_) => self
+                .downcast_ref::<ListArray>()
+                .value_opt(index)
+                .map(|values| {
+                    Scalar::List((0..values.len()).map(|i| values.scalar_value(i)).collect())
+                })
+                .unwrap_or_default(),
+            DataType::Struct(_) => {
+                let array = self.downcast_ref::<StructArray>();
+                if array.is_valid(index) {
+                    Scalar::Struct(
+                        array
+                            .fields()
+                            .iter()
+                            .zip(array.columns())
+                            .map(|(field, column)| (field.name.clone(), column.scalar_value(index)))
+                            .collect(),
+                    )
+                } else {
+                    Scalar::Null
+                }
+            }
         }
     }
 }
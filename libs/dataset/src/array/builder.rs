@@ -1,5 +1,7 @@
 use std::any::Any;
 
+use crate::array::ArrayRef;
+
 /// Trait for dealing with different array builders at runtime.
 pub trait ArrayBuilder {
     fn as_any(&self) -> &dyn Any;
@@ -7,4 +9,9 @@ pub trait ArrayBuilder {
     fn len(&self) -> usize;
 
     fn is_empty(&self) -> bool;
+
+    /// Builds the array.
+    fn finish_array(self) -> ArrayRef
+    where
+        Self: Sized;
 }
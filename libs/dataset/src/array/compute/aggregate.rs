@@ -0,0 +1,254 @@
+use std::cmp::Ordering;
+
+use crate::array::{
+    Array, ArrayExt, BooleanType, DataType, Decimal128Array, Float32Type, Float64Type, Int16Type,
+    Int32Type, Int64Type, Int8Type, PrimitiveArray, Scalar, StringArray, TimestampType, UInt16Type,
+    UInt32Type, UInt64Type, UInt8Type, DECIMAL128_MAX_PRECISION,
+};
+
+macro_rules! sum_primitive_array {
+    ($array:expr, $ty:ty, $acc_ty:ty, $scalar_ty:ident) => {{
+        let mut sum: Option<$acc_ty> = None;
+        for value in $array
+            .downcast_ref::<PrimitiveArray<$ty>>()
+            .iter_opt()
+            .flatten()
+        {
+            *sum.get_or_insert_with(Default::default) += value as $acc_ty;
+        }
+        sum.map(Scalar::$scalar_ty).unwrap_or_default()
+    }};
+}
+
+/// Like `sum_primitive_array`, but returns `Null` if the sum of the integers overflows.
+macro_rules! checked_sum_primitive_array {
+    ($array:expr, $ty:ty, $acc_ty:ty, $scalar_ty:ident) => {{
+        let mut sum: Option<$acc_ty> = None;
+        for value in $array
+            .downcast_ref::<PrimitiveArray<$ty>>()
+            .iter_opt()
+            .flatten()
+        {
+            match sum.unwrap_or_default().checked_add(value as $acc_ty) {
+                Some(value) => sum = Some(value),
+                None => return Scalar::Null,
+            }
+        }
+        sum.map(Scalar::$scalar_ty).unwrap_or_default()
+    }};
+}
+
+macro_rules! reduce_primitive_array {
+    ($array:expr, $ty:ty, $scalar_ty:ident, $ordering:expr) => {{
+        let mut res = None;
+        for value in $array
+            .downcast_ref::<PrimitiveArray<$ty>>()
+            .iter_opt()
+            .flatten()
+        {
+            match res {
+                Some(current) if value.partial_cmp(&current) != Some($ordering) => {}
+                _ => res = Some(value),
+            }
+        }
+        res.map(Scalar::$scalar_ty).unwrap_or_default()
+    }};
+}
+
+macro_rules! reduce_array {
+    ($array:expr, $ordering:expr) => {
+        match $array.data_type() {
+            DataType::Null => Scalar::Null,
+            DataType::Int8 => reduce_primitive_array!($array, Int8Type, Int8, $ordering),
+            DataType::Int16 => reduce_primitive_array!($array, Int16Type, Int16, $ordering),
+            DataType::Int32 => reduce_primitive_array!($array, Int32Type, Int32, $ordering),
+            DataType::Int64 => reduce_primitive_array!($array, Int64Type, Int64, $ordering),
+            DataType::UInt8 => reduce_primitive_array!($array, UInt8Type, UInt8, $ordering),
+            DataType::UInt16 => reduce_primitive_array!($array, UInt16Type, UInt16, $ordering),
+            DataType::UInt32 => reduce_primitive_array!($array, UInt32Type, UInt32, $ordering),
+            DataType::UInt64 => reduce_primitive_array!($array, UInt64Type, UInt64, $ordering),
+            DataType::Float32 => reduce_primitive_array!($array, Float32Type, Float32, $ordering),
+            DataType::Float64 => reduce_primitive_array!($array, Float64Type, Float64, $ordering),
+            DataType::Boolean => reduce_primitive_array!($array, BooleanType, Boolean, $ordering),
+            DataType::Timestamp(_) => {
+                reduce_primitive_array!($array, TimestampType, Timestamp, $ordering)
+            }
+            DataType::Decimal128 { precision, scale } => {
+                let mut res: Option<i128> = None;
+                for value in $array
+                    .downcast_ref::<Decimal128Array>()
+                    .iter_opt()
+                    .flatten()
+                {
+                    match res {
+                        Some(current) if value.cmp(&current) != $ordering => {}
+                        _ => res = Some(value),
+                    }
+                }
+                res.map(|value| Scalar::Decimal128 {
+                    value,
+                    precision,
+                    scale,
+                })
+                .unwrap_or_default()
+            }
+            DataType::String => {
+                let mut res = None;
+                for value in $array.downcast_ref::<StringArray>().iter_opt().flatten() {
+                    match res {
+                        Some(current) if value.partial_cmp(current) != Some($ordering) => {}
+                        _ => res = Some(value),
+                    }
+                }
+                res.map(|s| Scalar::String(s.into())).unwrap_or_default()
+            }
+            DataType::List(_) | DataType::Struct(_) => Scalar::Null,
+        }
+    };
+}
+
+/// Returns the sum of the non-null values in the array.
+///
+/// Integers and booleans are summed as `Int64`, unsigned integers as `UInt64`, floats as
+/// `Float64`, decimals as decimals with the maximum precision and the same scale. Returns `Null`
+/// if the array has no non-null values, its datatype can't be summed or the sum of the integers or
+/// the decimals overflows.
+pub fn sum(array: &dyn Array) -> Scalar {
+    match array.data_type() {
+        DataType::Int8 => checked_sum_primitive_array!(array, Int8Type, i64, Int64),
+        DataType::Int16 => checked_sum_primitive_array!(array, Int16Type, i64, Int64),
+        DataType::Int32 => checked_sum_primitive_array!(array, Int32Type, i64, Int64),
+        DataType::Int64 => checked_sum_primitive_array!(array, Int64Type, i64, Int64),
+        DataType::UInt8 => checked_sum_primitive_array!(array, UInt8Type, u64, UInt64),
+        DataType::UInt16 => checked_sum_primitive_array!(array, UInt16Type, u64, UInt64),
+        DataType::UInt32 => checked_sum_primitive_array!(array, UInt32Type, u64, UInt64),
+        DataType::UInt64 => checked_sum_primitive_array!(array, UInt64Type, u64, UInt64),
+        DataType::Float32 => sum_primitive_array!(array, Float32Type, f64, Float64),
+        DataType::Float64 => sum_primitive_array!(array, Float64Type, f64, Float64),
+        DataType::Boolean => checked_sum_primitive_array!(array, BooleanType, i64, Int64),
+        DataType::Decimal128 { scale, .. } => {
+            let mut sum: Option<i128> = None;
+            for value in array.downcast_ref::<Decimal128Array>().iter_opt().flatten() {
+                match sum.unwrap_or_default().checked_add(value) {
+                    Some(value) => sum = Some(value),
+                    None => return Scalar::Null,
+                }
+            }
+            sum.map(|value| Scalar::Decimal128 {
+                value,
+                precision: DECIMAL128_MAX_PRECISION,
+                scale,
+            })
+            .unwrap_or_default()
+        }
+        DataType::Null
+        | DataType::Timestamp(_)
+        | DataType::String
+        | DataType::List(_)
+        | DataType::Struct(_) => Scalar::Null,
+    }
+}
+
+/// Returns the minimum of the non-null values in the array, or `Null` if there are none or the
+/// values can't be ordered.
+pub fn min(array: &dyn Array) -> Scalar {
+    reduce_array!(array, Ordering::Less)
+}
+
+/// Returns the maximum of the non-null values in the array, or `Null` if there are none or the
+/// values can't be ordered.
+pub fn max(array: &dyn Array) -> Scalar {
+    reduce_array!(array, Ordering::Greater)
+}
+
+/// Returns the number of non-null values in the array.
+pub fn count(array: &dyn Array) -> Scalar {
+    Scalar::Int64((0..array.len()).filter(|idx| array.is_valid(*idx)).count() as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::array::{
+        BooleanArray, Float64Array, Int32Array, Int64Array, NullArray, TimestampArray, UInt64Array,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_sum() {
+        assert_eq!(
+            sum(&Int32Array::from_opt_vec(vec![Some(1), None, Some(3)])),
+            Scalar::Int64(4)
+        );
+        assert_eq!(
+            sum(&Float64Array::from_opt_vec(vec![
+                Some(1.5),
+                None,
+                Some(3.0)
+            ])),
+            Scalar::Float64(4.5)
+        );
+        assert_eq!(
+            sum(&BooleanArray::from_vec(vec![true, false, true])),
+            Scalar::Int64(2)
+        );
+        assert_eq!(sum(&Int32Array::new_scalar(3, Some(2))), Scalar::Int64(6));
+        assert_eq!(
+            sum(&Int32Array::from_opt_vec(vec![None, None])),
+            Scalar::Null
+        );
+        assert_eq!(sum(&Int32Array::empty()), Scalar::Null);
+        assert_eq!(sum(&StringArray::from_vec(vec!["a"])), Scalar::Null);
+
+        // the sum of the integers overflows
+        assert_eq!(sum(&Int64Array::from_vec(vec![i64::MAX, 1])), Scalar::Null);
+        assert_eq!(sum(&Int64Array::from_vec(vec![i64::MIN, -1])), Scalar::Null);
+        assert_eq!(sum(&UInt64Array::from_vec(vec![u64::MAX, 1])), Scalar::Null);
+        assert_eq!(
+            sum(&Int64Array::from_vec(vec![i64::MAX, -1, 1])),
+            Scalar::Int64(i64::MAX)
+        );
+    }
+
+    #[test]
+    fn test_min_max() {
+        let array = Int32Array::from_opt_vec(vec![Some(3), None, Some(1), Some(2)]);
+        assert_eq!(min(&array), Scalar::Int32(1));
+        assert_eq!(max(&array), Scalar::Int32(3));
+
+        let array = Float64Array::from_opt_vec(vec![None, Some(-1.5), Some(2.5)]);
+        assert_eq!(min(&array), Scalar::Float64(-1.5));
+        assert_eq!(max(&array), Scalar::Float64(2.5));
+
+        let array = TimestampArray::from_vec(vec![1622512200000, 1622512140000]);
+        assert_eq!(min(&array), Scalar::Timestamp(1622512140000));
+        assert_eq!(max(&array), Scalar::Timestamp(1622512200000));
+
+        let array = BooleanArray::from_opt_vec(vec![Some(true), None, Some(false)]);
+        assert_eq!(min(&array), Scalar::Boolean(false));
+        assert_eq!(max(&array), Scalar::Boolean(true));
+
+        let array = StringArray::from_opt_vec(vec![Some("b"), None, Some("c"), Some("a")]);
+        assert_eq!(min(&array), Scalar::String("a".into()));
+        assert_eq!(max(&array), Scalar::String("c".into()));
+
+        assert_eq!(min(&Int32Array::from_opt_vec(vec![None])), Scalar::Null);
+        assert_eq!(max(&Int32Array::empty()), Scalar::Null);
+        assert_eq!(min(&StringArray::empty()), Scalar::Null);
+        assert_eq!(max(&NullArray::new(3)), Scalar::Null);
+    }
+
+    #[test]
+    fn test_count() {
+        assert_eq!(
+            count(&Int32Array::from_opt_vec(vec![Some(1), None, Some(3)])),
+            Scalar::Int64(2)
+        );
+        assert_eq!(
+            count(&StringArray::from_opt_vec(vec![None, Some("a")])),
+            Scalar::Int64(1)
+        );
+        assert_eq!(count(&NullArray::new(3)), Scalar::Int64(0));
+        assert_eq!(count(&Int32Array::empty()), Scalar::Int64(0));
+    }
+}
@@ -0,0 +1,762 @@
+use std::convert::TryFrom;
+use std::sync::Arc;
+
+use anyhow::Result;
+
+use crate::array::{
+    compute, format_decimal, rescale_decimal, Array, ArrayExt, ArrayRef, BooleanType, DataType,
+    Decimal128Array, Decimal128Builder, Float32Type, Float64Array, Float64Builder, Float64Type,
+    Int16Type, Int32Type, Int64Type, Int8Type, PrimitiveArray, PrimitiveBuilder, PrimitiveType,
+    StringArray, StringBuilder, TimestampType, UInt16Type, UInt32Type, UInt64Type, UInt8Type,
+};
+
+/// How to handle values that don't fit in the target type of a cast.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum CastMode {
+    /// Values that don't fit in the target type become null, and so do floats with a fractional
+    /// part when casting to an integer.
+    Null,
+
+    /// Out-of-range values are clamped to the bounds of the target type, and floats are rounded
+    /// toward zero when casting to an integer.
+    Saturate,
+
+    /// Floats are rounded toward zero when casting to an integer, out-of-range values become
+    /// null.
+    Truncate,
+}
+
+impl Default for CastMode {
+    fn default() -> Self {
+        CastMode::Null
+    }
+}
+
+macro_rules! numeric_array_cast {
+    ($array:expr, $from:ty, $to:ty) => {
+        Ok(primitive_array_cast::<$from, $to, _>(&*$array, |value| {
+            Some(value as <$to as PrimitiveType>::Native)
+        }))
+    };
+}
+
+macro_rules! integer_array_narrow {
+    ($array:expr, $from:ty, $to:ty, $mode:expr) => {{
+        type Native = <$to as PrimitiveType>::Native;
+        Ok(primitive_array_cast::<$from, $to, _>(
+            &*$array,
+            |value| match (Native::try_from(value), $mode) {
+                (Ok(value), _) => Some(value),
+                (Err(_), CastMode::Saturate) if (value as i128) < 0 => Some(Native::MIN),
+                (Err(_), CastMode::Saturate) => Some(Native::MAX),
+                (Err(_), _) => None,
+            },
+        ))
+    }};
+}
+
+macro_rules! float_array_cast_to_integer {
+    ($array:expr, $from:ty, $to:ty, $mode:expr) => {{
+        type Native = <$to as PrimitiveType>::Native;
+        Ok(primitive_array_cast::<$from, $to, _>(&*$array, |value| {
+            let value = value as f64;
+            // the lower bound and the upper bound plus one of the integer types are exactly
+            // representable as floats
+            let in_range =
+                |value: f64| value >= Native::MIN as f64 && value < Native::MAX as f64 + 1.0;
+            match $mode {
+                CastMode::Null if value.fract() == 0.0 && in_range(value) => Some(value as Native),
+                CastMode::Truncate if in_range(value.trunc()) => Some(value as Native),
+                CastMode::Saturate if !value.is_nan() => Some(value as Native),
+                _ => None,
+            }
+        }))
+    }};
+}
+
+macro_rules! boolean_array_cast {
+    ($array:expr, $to:ty) => {
+        Ok(primitive_array_cast::<BooleanType, $to, _>(
+            &*$array,
+            |value| Some(value as u8 as <$to as PrimitiveType>::Native),
+        ))
+    };
+}
+
+macro_rules! array_cast_to_string {
+    ($array:expr, $from:ty) => {{
+        let array = $array.downcast_ref::<PrimitiveArray<$from>>();
+        if let Some(scalar) = array.to_scalar() {
+            return Ok(Arc::new(StringArray::new_scalar(
+                array.len(),
+                scalar.map(|value| format!("{}", value)),
+            )));
+        }
+        let mut builder = StringBuilder::with_capacity($array.len());
+        for value in array.iter_opt() {
+            builder.append_opt(value.map(|value| format!("{}", value)).as_deref());
+        }
+        Ok(Arc::new(builder.finish()))
+    }};
+}
+
+macro_rules! string_array_parse {
+    ($array:expr, $to:ty) => {{
+        type Native = <$to as PrimitiveType>::Native;
+        let array = $array.downcast_ref::<StringArray>();
+        let parse = |value: &str| value.trim().parse::<Native>().ok();
+        if let Some(scalar) = array.to_scalar() {
+            return Ok(Arc::new(PrimitiveArray::<$to>::new_scalar(
+                array.len(),
+                scalar.and_then(parse),
+            )));
+        }
+        let mut builder = PrimitiveBuilder::<$to>::with_capacity(array.len());
+        for value in array.iter_opt() {
+            builder.append_opt(value.and_then(parse));
+        }
+        Ok(Arc::new(builder.finish()))
+    }};
+}
+
+macro_rules! integer_array_cast_to_decimal {
+    ($array:expr, $from:ty, $precision:expr, $scale:expr, $mode:expr) => {{
+        let array = $array.downcast_ref::<PrimitiveArray<$from>>();
+        Ok(decimal_array_cast(
+            array
+                .iter_opt()
+                .map(|value| value.map(|value| value as i128)),
+            0,
+            *$precision,
+            *$scale,
+            $mode,
+        ))
+    }};
+}
+
+/// Changes the scale of decimal values, digits that don't fit in the target scale are handled
+/// like the fractional part of floats casting to integers.
+fn decimal_array_cast(
+    values: impl Iterator<Item = Option<i128>>,
+    from_scale: u8,
+    precision: u8,
+    scale: u8,
+    mode: CastMode,
+) -> ArrayRef {
+    let bound = 10i128.pow(precision as u32) - 1;
+    let mut builder = Decimal128Builder::new(precision, scale);
+    for value in values {
+        builder.append_opt(value.and_then(|value| {
+            let value = match rescale_decimal(value, from_scale, scale) {
+                Some(value) => value,
+                None if from_scale > scale && mode != CastMode::Null => {
+                    value / 10i128.pow((from_scale - scale) as u32)
+                }
+                None if from_scale < scale && mode == CastMode::Saturate => value.signum() * bound,
+                None => return None,
+            };
+            match mode {
+                _ if value.abs() <= bound => Some(value),
+                CastMode::Saturate => Some(value.signum() * bound),
+                _ => None,
+            }
+        }));
+    }
+    Arc::new(builder.finish())
+}
+
+fn primitive_array_cast<A, B, F>(array: &dyn Array, f: F) -> ArrayRef
+where
+    A: PrimitiveType,
+    B: PrimitiveType,
+    F: Fn(A::Native) -> Option<B::Native>,
+{
+    let array = array.downcast_ref::<PrimitiveArray<A>>();
+    if let Some(scalar) = array.to_scalar() {
+        return Arc::new(PrimitiveArray::<B>::new_scalar(
+            array.len(),
+            scalar.and_then(&f),
+        ));
+    }
+    let mut builder = PrimitiveBuilder::<B>::with_capacity(array.len());
+    for value in array.iter_opt() {
+        builder.append_opt(value.and_then(&f));
+    }
+    Arc::new(builder.finish())
+}
+
+/// Returns `true` if the arrays of type `from` can be cast to `to` with [`cast`].
+pub fn can_cast(from: &DataType, to: &DataType) -> bool {
+    use DataType::*;
+
+    match (from, to) {
+        (Boolean, Boolean) | (String, String) | (Timestamp(_), Timestamp(_)) => true,
+        (Null, to) => !matches!(to, List(_) | Struct(_)),
+        (Timestamp(_), Int64) | (Int64, Timestamp(_)) => true,
+        (Decimal128 { .. }, to) => matches!(to, Decimal128 { .. } | Float64 | String),
+        (from, Decimal128 { .. }) => from.is_integer(),
+        (String, to) => to.is_numeric(),
+        (from, to) => {
+            (from.is_numeric() || from.is_boolean()) && (to.is_numeric() || to.is_string())
+        }
+    }
+}
+
+/// Casts the array to the specified datatype, values that don't fit in the target type become
+/// null.
+///
+/// Like most SQL engines, a cast is lenient about the values: strings that can't be parsed as the
+/// target type become null instead of failing the whole array. Timestamps are cast to and from
+/// `Int64` as milliseconds since the unix epoch.
+pub fn cast(array: ArrayRef, data_type: DataType) -> Result<ArrayRef> {
+    cast_with_mode(array, data_type, CastMode::default())
+}
+
+/// Casts the array to the specified datatype, using `mode` to handle values that don't fit in the
+/// target type.
+pub fn cast_with_mode(array: ArrayRef, data_type: DataType, mode: CastMode) -> Result<ArrayRef> {
+    use DataType::*;
+
+    match (array.data_type(), &data_type) {
+        (Int8, Int8) => Ok(array.clone()),
+        (Int8, Int16) => numeric_array_cast!(array, Int8Type, Int16Type),
+        (Int8, Int32) => numeric_array_cast!(array, Int8Type, Int32Type),
+        (Int8, Int64) => numeric_array_cast!(array, Int8Type, Int64Type),
+        (Int8, Float32) => numeric_array_cast!(array, Int8Type, Float32Type),
+        (Int8, Float64) => numeric_array_cast!(array, Int8Type, Float64Type),
+        (Int8, UInt8) => integer_array_narrow!(array, Int8Type, UInt8Type, mode),
+        (Int8, UInt16) => integer_array_narrow!(array, Int8Type, UInt16Type, mode),
+        (Int8, UInt32) => integer_array_narrow!(array, Int8Type, UInt32Type, mode),
+        (Int8, UInt64) => integer_array_narrow!(array, Int8Type, UInt64Type, mode),
+        (Int8, String) => array_cast_to_string!(array, Int8Type),
+
+        (Int16, Int8) => integer_array_narrow!(array, Int16Type, Int8Type, mode),
+        (Int16, Int16) => Ok(array.clone()),
+        (Int16, Int32) => numeric_array_cast!(array, Int16Type, Int32Type),
+        (Int16, Int64) => numeric_array_cast!(array, Int16Type, Int64Type),
+        (Int16, Float32) => numeric_array_cast!(array, Int16Type, Float32Type),
+        (Int16, Float64) => numeric_array_cast!(array, Int16Type, Float64Type),
+        (Int16, UInt8) => integer_array_narrow!(array, Int16Type, UInt8Type, mode),
+        (Int16, UInt16) => integer_array_narrow!(array, Int16Type, UInt16Type, mode),
+        (Int16, UInt32) => integer_array_narrow!(array, Int16Type, UInt32Type, mode),
+        (Int16, UInt64) => integer_array_narrow!(array, Int16Type, UInt64Type, mode),
+        (Int16, String) => array_cast_to_string!(array, Int16Type),
+
+        (Int32, Int8) => integer_array_narrow!(array, Int32Type, Int8Type, mode),
+        (Int32, Int16) => integer_array_narrow!(array, Int32Type, Int16Type, mode),
+        (Int32, Int32) => Ok(array.clone()),
+        (Int32, Int64) => numeric_array_cast!(array, Int32Type, Int64Type),
+        (Int32, Float32) => numeric_array_cast!(array, Int32Type, Float32Type),
+        (Int32, Float64) => numeric_array_cast!(array, Int32Type, Float64Type),
+        (Int32, UInt8) => integer_array_narrow!(array, Int32Type, UInt8Type, mode),
+        (Int32, UInt16) => integer_array_narrow!(array, Int32Type, UInt16Type, mode),
+        (Int32, UInt32) => integer_array_narrow!(array, Int32Type, UInt32Type, mode),
+        (Int32, UInt64) => integer_array_narrow!(array, Int32Type, UInt64Type, mode),
+        (Int32, String) => array_cast_to_string!(array, Int32Type),
+
+        (Int64, Int8) => integer_array_narrow!(array, Int64Type, Int8Type, mode),
+        (Int64, Int16) => integer_array_narrow!(array, Int64Type, Int16Type, mode),
+        (Int64, Int32) => integer_array_narrow!(array, Int64Type, Int32Type, mode),
+        (Int64, Int64) => Ok(array.clone()),
+        (Int64, Float32) => numeric_array_cast!(array, Int64Type, Float32Type),
+        (Int64, Float64) => numeric_array_cast!(array, Int64Type, Float64Type),
+        (Int64, UInt8) => integer_array_narrow!(array, Int64Type, UInt8Type, mode),
+        (Int64, UInt16) => integer_array_narrow!(array, Int64Type, UInt16Type, mode),
+        (Int64, UInt32) => integer_array_narrow!(array, Int64Type, UInt32Type, mode),
+        (Int64, UInt64) => integer_array_narrow!(array, Int64Type, UInt64Type, mode),
+        (Int64, String) => array_cast_to_string!(array, Int64Type),
+
+        (UInt8, Int8) => integer_array_narrow!(array, UInt8Type, Int8Type, mode),
+        (UInt8, Int16) => numeric_array_cast!(array, UInt8Type, Int16Type),
+        (UInt8, Int32) => numeric_array_cast!(array, UInt8Type, Int32Type),
+        (UInt8, Int64) => numeric_array_cast!(array, UInt8Type, Int64Type),
+        (UInt8, UInt8) => Ok(array.clone()),
+        (UInt8, UInt16) => numeric_array_cast!(array, UInt8Type, UInt16Type),
+        (UInt8, UInt32) => numeric_array_cast!(array, UInt8Type, UInt32Type),
+        (UInt8, UInt64) => numeric_array_cast!(array, UInt8Type, UInt64Type),
+        (UInt8, Float32) => numeric_array_cast!(array, UInt8Type, Float32Type),
+        (UInt8, Float64) => numeric_array_cast!(array, UInt8Type, Float64Type),
+        (UInt8, String) => array_cast_to_string!(array, UInt8Type),
+
+        (UInt16, Int8) => integer_array_narrow!(array, UInt16Type, Int8Type, mode),
+        (UInt16, Int16) => integer_array_narrow!(array, UInt16Type, Int16Type, mode),
+        (UInt16, Int32) => numeric_array_cast!(array, UInt16Type, Int32Type),
+        (UInt16, Int64) => numeric_array_cast!(array, UInt16Type, Int64Type),
+        (UInt16, UInt8) => integer_array_narrow!(array, UInt16Type, UInt8Type, mode),
+        (UInt16, UInt16) => Ok(array.clone()),
+        (UInt16, UInt32) => numeric_array_cast!(array, UInt16Type, UInt32Type),
+        (UInt16, UInt64) => numeric_array_cast!(array, UInt16Type, UInt64Type),
+        (UInt16, Float32) => numeric_array_cast!(array, UInt16Type, Float32Type),
+        (UInt16, Float64) => numeric_array_cast!(array, UInt16Type, Float64Type),
+        (UInt16, String) => array_cast_to_string!(array, UInt16Type),
+
+        (UInt32, Int8) => integer_array_narrow!(array, UInt32Type, Int8Type, mode),
+        (UInt32, Int16) => integer_array_narrow!(array, UInt32Type, Int16Type, mode),
+        (UInt32, Int32) => integer_array_narrow!(array, UInt32Type, Int32Type, mode),
+        (UInt32, Int64) => numeric_array_cast!(array, UInt32Type, Int64Type),
+        (UInt32, UInt8) => integer_array_narrow!(array, UInt32Type, UInt8Type, mode),
+        (UInt32, UInt16) => integer_array_narrow!(array, UInt32Type, UInt16Type, mode),
+        (UInt32, UInt32) => Ok(array.clone()),
+        (UInt32, UInt64) => numeric_array_cast!(array, UInt32Type, UInt64Type),
+        (UInt32, Float32) => numeric_array_cast!(array, UInt32Type, Float32Type),
+        (UInt32, Float64) => numeric_array_cast!(array, UInt32Type, Float64Type),
+        (UInt32, String) => array_cast_to_string!(array, UInt32Type),
+
+        (UInt64, Int8) => integer_array_narrow!(array, UInt64Type, Int8Type, mode),
+        (UInt64, Int16) => integer_array_narrow!(array, UInt64Type, Int16Type, mode),
+        (UInt64, Int32) => integer_array_narrow!(array, UInt64Type, Int32Type, mode),
+        (UInt64, Int64) => integer_array_narrow!(array, UInt64Type, Int64Type, mode),
+        (UInt64, UInt8) => integer_array_narrow!(array, UInt64Type, UInt8Type, mode),
+        (UInt64, UInt16) => integer_array_narrow!(array, UInt64Type, UInt16Type, mode),
+        (UInt64, UInt32) => integer_array_narrow!(array, UInt64Type, UInt32Type, mode),
+        (UInt64, UInt64) => Ok(array.clone()),
+        (UInt64, Float32) => numeric_array_cast!(array, UInt64Type, Float32Type),
+        (UInt64, Float64) => numeric_array_cast!(array, UInt64Type, Float64Type),
+        (UInt64, String) => array_cast_to_string!(array, UInt64Type),
+
+        (Float32, Int8) => float_array_cast_to_integer!(array, Float32Type, Int8Type, mode),
+        (Float32, Int16) => float_array_cast_to_integer!(array, Float32Type, Int16Type, mode),
+        (Float32, Int32) => float_array_cast_to_integer!(array, Float32Type, Int32Type, mode),
+        (Float32, Int64) => float_array_cast_to_integer!(array, Float32Type, Int64Type, mode),
+        (Float32, UInt8) => float_array_cast_to_integer!(array, Float32Type, UInt8Type, mode),
+        (Float32, UInt16) => float_array_cast_to_integer!(array, Float32Type, UInt16Type, mode),
+        (Float32, UInt32) => float_array_cast_to_integer!(array, Float32Type, UInt32Type, mode),
+        (Float32, UInt64) => float_array_cast_to_integer!(array, Float32Type, UInt64Type, mode),
+        (Float32, Float32) => Ok(array.clone()),
+        (Float32, Float64) => numeric_array_cast!(array, Float32Type, Float64Type),
+        (Float32, String) => array_cast_to_string!(array, Float32Type),
+
+        (Float64, Int8) => float_array_cast_to_integer!(array, Float64Type, Int8Type, mode),
+        (Float64, Int16) => float_array_cast_to_integer!(array, Float64Type, Int16Type, mode),
+        (Float64, Int32) => float_array_cast_to_integer!(array, Float64Type, Int32Type, mode),
+        (Float64, Int64) => float_array_cast_to_integer!(array, Float64Type, Int64Type, mode),
+        (Float64, UInt8) => float_array_cast_to_integer!(array, Float64Type, UInt8Type, mode),
+        (Float64, UInt16) => float_array_cast_to_integer!(array, Float64Type, UInt16Type, mode),
+        (Float64, UInt32) => float_array_cast_to_integer!(array, Float64Type, UInt32Type, mode),
+        (Float64, UInt64) => float_array_cast_to_integer!(array, Float64Type, UInt64Type, mode),
+        (Float64, Float32) => Ok(primitive_array_cast::<Float64Type, Float32Type, _>(
+            &*array,
+            |value| {
+                if !value.is_finite() || value.abs() <= f32::MAX as f64 {
+                    Some(value as f32)
+                } else if mode == CastMode::Saturate {
+                    Some(value.signum() as f32 * f32::MAX)
+                } else {
+                    None
+                }
+            },
+        )),
+        (Float64, Float64) => Ok(array.clone()),
+        (Float64, String) => array_cast_to_string!(array, Float64Type),
+
+        (Boolean, Boolean) => Ok(array.clone()),
+        (Boolean, Int8) => boolean_array_cast!(array, Int8Type),
+        (Boolean, Int16) => boolean_array_cast!(array, Int16Type),
+        (Boolean, Int32) => boolean_array_cast!(array, Int32Type),
+        (Boolean, Int64) => boolean_array_cast!(array, Int64Type),
+        (Boolean, UInt8) => boolean_array_cast!(array, UInt8Type),
+        (Boolean, UInt16) => boolean_array_cast!(array, UInt16Type),
+        (Boolean, UInt32) => boolean_array_cast!(array, UInt32Type),
+        (Boolean, UInt64) => boolean_array_cast!(array, UInt64Type),
+        (Boolean, Float32) => boolean_array_cast!(array, Float32Type),
+        (Boolean, Float64) => boolean_array_cast!(array, Float64Type),
+        (Boolean, String) => array_cast_to_string!(array, BooleanType),
+
+        (Timestamp(_), Timestamp(_)) => Ok(array.clone()),
+        (Timestamp(_), Int64) => numeric_array_cast!(array, TimestampType, Int64Type),
+        (Int64, Timestamp(_)) => numeric_array_cast!(array, Int64Type, TimestampType),
+
+        (String, Int8) => string_array_parse!(array, Int8Type),
+        (String, Int16) => string_array_parse!(array, Int16Type),
+        (String, Int32) => string_array_parse!(array, Int32Type),
+        (String, Int64) => string_array_parse!(array, Int64Type),
+        (String, UInt8) => string_array_parse!(array, UInt8Type),
+        (String, UInt16) => string_array_parse!(array, UInt16Type),
+        (String, UInt32) => string_array_parse!(array, UInt32Type),
+        (String, UInt64) => string_array_parse!(array, UInt64Type),
+        (String, Float32) => string_array_parse!(array, Float32Type),
+        (String, Float64) => string_array_parse!(array, Float64Type),
+
+        (Int8, Decimal128 { precision, scale }) => {
+            integer_array_cast_to_decimal!(array, Int8Type, precision, scale, mode)
+        }
+        (Int16, Decimal128 { precision, scale }) => {
+            integer_array_cast_to_decimal!(array, Int16Type, precision, scale, mode)
+        }
+        (Int32, Decimal128 { precision, scale }) => {
+            integer_array_cast_to_decimal!(array, Int32Type, precision, scale, mode)
+        }
+        (Int64, Decimal128 { precision, scale }) => {
+            integer_array_cast_to_decimal!(array, Int64Type, precision, scale, mode)
+        }
+        (UInt8, Decimal128 { precision, scale }) => {
+            integer_array_cast_to_decimal!(array, UInt8Type, precision, scale, mode)
+        }
+        (UInt16, Decimal128 { precision, scale }) => {
+            integer_array_cast_to_decimal!(array, UInt16Type, precision, scale, mode)
+        }
+        (UInt32, Decimal128 { precision, scale }) => {
+            integer_array_cast_to_decimal!(array, UInt32Type, precision, scale, mode)
+        }
+        (UInt64, Decimal128 { precision, scale }) => {
+            integer_array_cast_to_decimal!(array, UInt64Type, precision, scale, mode)
+        }
+        (Decimal128 { .. }, Decimal128 { precision, scale }) => {
+            let array = array.downcast_ref::<Decimal128Array>();
+            Ok(decimal_array_cast(
+                array.iter_opt(),
+                array.scale(),
+                *precision,
+                *scale,
+                mode,
+            ))
+        }
+        (Decimal128 { scale, .. }, Float64) => {
+            let factor = 10f64.powi(scale as i32);
+            let array = array.downcast_ref::<Decimal128Array>();
+            if let Some(scalar) = array.to_scalar() {
+                return Ok(Arc::new(Float64Array::new_scalar(
+                    array.len(),
+                    scalar.map(|value| value as f64 / factor),
+                )));
+            }
+            let mut builder = Float64Builder::with_capacity(array.len());
+            for value in array.iter_opt() {
+                builder.append_opt(value.map(|value| value as f64 / factor));
+            }
+            Ok(Arc::new(builder.finish()))
+        }
+        (Decimal128 { scale, .. }, String) => {
+            let array = array.downcast_ref::<Decimal128Array>();
+            if let Some(scalar) = array.to_scalar() {
+                return Ok(Arc::new(StringArray::new_scalar(
+                    array.len(),
+                    scalar.map(|value| format_decimal(value, scale)),
+                )));
+            }
+            let mut builder = StringBuilder::with_capacity(array.len());
+            for value in array.iter_opt() {
+                builder.append_opt(value.map(|value| format_decimal(value, scale)).as_deref());
+            }
+            Ok(Arc::new(builder.finish()))
+        }
+
+        (String, String) => Ok(array.clone()),
+
+        (Null, to) => compute::new_null_array(to, array.len())
+            .ok_or_else(|| anyhow::anyhow!("cannot cast type from '{}' to '{}'", Null, data_type)),
+
+        _ => anyhow::bail!(
+            "cannot cast type from '{}' to '{}'",
+            array.data_type(),
+            data_type
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::array::{BooleanArray, Float32Array, Int32Array, Int64Array, Int8Array, NullArray};
+
+    fn cast_int64_to_int8(values: Vec<Option<i64>>, mode: CastMode) -> Vec<Option<i8>> {
+        cast_with_mode(
+            Arc::new(Int64Array::from_opt_vec(values)),
+            DataType::Int8,
+            mode,
+        )
+        .unwrap()
+        .downcast_ref::<Int8Array>()
+        .iter_opt()
+        .collect()
+    }
+
+    fn cast_float64_to_int32(values: Vec<f64>, mode: CastMode) -> Vec<Option<i32>> {
+        cast_with_mode(
+            Arc::new(Float64Array::from_vec(values)),
+            DataType::Int32,
+            mode,
+        )
+        .unwrap()
+        .downcast_ref::<Int32Array>()
+        .iter_opt()
+        .collect()
+    }
+
+    #[test]
+    fn test_can_cast() {
+        assert!(can_cast(&DataType::Int64, &DataType::Int8));
+        assert!(can_cast(&DataType::Float64, &DataType::Int32));
+        assert!(can_cast(&DataType::Boolean, &DataType::Float32));
+        assert!(can_cast(&DataType::Int16, &DataType::String));
+        assert!(can_cast(
+            &DataType::Int32,
+            &DataType::Decimal128 {
+                precision: 10,
+                scale: 2
+            }
+        ));
+        assert!(can_cast(
+            &DataType::Decimal128 {
+                precision: 10,
+                scale: 2
+            },
+            &DataType::Float64
+        ));
+        assert!(can_cast(
+            &DataType::Timestamp(None),
+            &DataType::Timestamp(Some(chrono_tz::UTC))
+        ));
+
+        assert!(!can_cast(&DataType::Int64, &DataType::Boolean));
+        assert!(can_cast(&DataType::String, &DataType::Int64));
+        assert!(can_cast(&DataType::Int64, &DataType::Timestamp(None)));
+        assert!(can_cast(&DataType::Timestamp(None), &DataType::Int64));
+
+        assert!(!can_cast(&DataType::String, &DataType::Boolean));
+        assert!(!can_cast(&DataType::Int32, &DataType::Timestamp(None)));
+        assert!(!can_cast(
+            &DataType::Float64,
+            &DataType::Decimal128 {
+                precision: 10,
+                scale: 2
+            }
+        ));
+        assert!(can_cast(&DataType::Null, &DataType::Int64));
+        assert!(!can_cast(
+            &DataType::Null,
+            &DataType::List(Box::new(DataType::Int64))
+        ));
+        assert_eq!(
+            cast(Arc::new(NullArray::new(2)), DataType::Int64)
+                .unwrap()
+                .downcast_ref::<Int64Array>(),
+            &Int64Array::from_opt_vec(vec![None, None])
+        );
+    }
+
+    #[test]
+    fn test_cast_boolean_to_int64() {
+        let array = cast(
+            Arc::new(BooleanArray::from_opt_vec(vec![
+                Some(true),
+                Some(false),
+                None,
+                Some(true),
+            ])),
+            DataType::Int64,
+        )
+        .unwrap();
+        assert_eq!(
+            array
+                .downcast_ref::<Int64Array>()
+                .iter_opt()
+                .collect::<Vec<_>>(),
+            vec![Some(1), Some(0), None, Some(1)]
+        );
+
+        let array = cast(
+            Arc::new(BooleanArray::new_scalar(3, Some(true))),
+            DataType::Int64,
+        )
+        .unwrap();
+        assert_eq!(
+            array.downcast_ref::<Int64Array>().to_scalar(),
+            Some(Some(1))
+        );
+    }
+
+    #[test]
+    fn test_cast_integer_overflow() {
+        let values = vec![Some(1), Some(-128), Some(127), Some(128), Some(-1000), None];
+
+        assert_eq!(
+            cast_int64_to_int8(values.clone(), CastMode::Null),
+            vec![Some(1), Some(-128), Some(127), None, None, None]
+        );
+        assert_eq!(
+            cast_int64_to_int8(values.clone(), CastMode::Truncate),
+            vec![Some(1), Some(-128), Some(127), None, None, None]
+        );
+        assert_eq!(
+            cast_int64_to_int8(values, CastMode::Saturate),
+            vec![Some(1), Some(-128), Some(127), Some(127), Some(-128), None]
+        );
+
+        let array = cast(
+            Arc::new(Int64Array::new_scalar(3, Some(300))),
+            DataType::Int8,
+        )
+        .unwrap();
+        assert_eq!(array.downcast_ref::<Int8Array>().to_scalar(), Some(None));
+    }
+
+    #[test]
+    fn test_cast_float_to_integer() {
+        let values = vec![1.0, -2.5, 2.9, 3e10, -3e10, f64::NAN];
+
+        assert_eq!(
+            cast_float64_to_int32(values.clone(), CastMode::Null),
+            vec![Some(1), None, None, None, None, None]
+        );
+        assert_eq!(
+            cast_float64_to_int32(values.clone(), CastMode::Truncate),
+            vec![Some(1), Some(-2), Some(2), None, None, None]
+        );
+        assert_eq!(
+            cast_float64_to_int32(values, CastMode::Saturate),
+            vec![
+                Some(1),
+                Some(-2),
+                Some(2),
+                Some(i32::MAX),
+                Some(i32::MIN),
+                None
+            ]
+        );
+
+        let array = cast(
+            Arc::new(Float64Array::from_vec(vec![9.223372036854776e18])),
+            DataType::Int64,
+        )
+        .unwrap();
+        assert!(array.is_null(0));
+    }
+
+    #[test]
+    fn test_cast_float64_to_float32() {
+        let array = Arc::new(Float64Array::from_vec(vec![1.5, 1e300, -1e300]));
+
+        let res = cast(array.clone(), DataType::Float32).unwrap();
+        assert_eq!(
+            res.downcast_ref::<Float32Array>()
+                .iter_opt()
+                .collect::<Vec<_>>(),
+            vec![Some(1.5), None, None]
+        );
+
+        let res = cast_with_mode(array, DataType::Float32, CastMode::Saturate).unwrap();
+        assert_eq!(
+            res.downcast_ref::<Float32Array>()
+                .iter_opt()
+                .collect::<Vec<_>>(),
+            vec![Some(1.5), Some(f32::MAX), Some(f32::MIN)]
+        );
+    }
+
+    #[test]
+    fn test_cast_decimal() {
+        let cast_decimal = |values: Vec<i128>, precision, scale, mode| {
+            cast_with_mode(
+                Arc::new(Decimal128Array::from_vec(10, 2, values)),
+                DataType::Decimal128 { precision, scale },
+                mode,
+            )
+            .unwrap()
+            .downcast_ref::<Decimal128Array>()
+            .iter_opt()
+            .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            cast_decimal(vec![12345, -12300], 10, 4, CastMode::Null),
+            vec![Some(1234500), Some(-1230000)]
+        );
+        assert_eq!(
+            cast_decimal(vec![12345, -12300], 10, 0, CastMode::Null),
+            vec![None, Some(-123)]
+        );
+        assert_eq!(
+            cast_decimal(vec![12345, -12399], 10, 0, CastMode::Truncate),
+            vec![Some(123), Some(-123)]
+        );
+        assert_eq!(
+            cast_decimal(vec![12345, -99999], 4, 2, CastMode::Null),
+            vec![None, None]
+        );
+        assert_eq!(
+            cast_decimal(vec![12345, -99999], 4, 2, CastMode::Saturate),
+            vec![Some(9999), Some(-9999)]
+        );
+
+        let array = cast(
+            Arc::new(Int32Array::from_opt_vec(vec![Some(7), None, Some(-100)])),
+            DataType::Decimal128 {
+                precision: 4,
+                scale: 2,
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            array
+                .downcast_ref::<Decimal128Array>()
+                .iter_opt()
+                .collect::<Vec<_>>(),
+            vec![Some(700), None, None]
+        );
+
+        let array = Arc::new(Decimal128Array::from_vec(10, 2, vec![12345, -5]));
+        assert_eq!(
+            cast(array.clone(), DataType::Float64)
+                .unwrap()
+                .downcast_ref::<Float64Array>()
+                .iter_opt()
+                .collect::<Vec<_>>(),
+            vec![Some(123.45), Some(-0.05)]
+        );
+        assert_eq!(
+            cast(array, DataType::String)
+                .unwrap()
+                .downcast_ref::<StringArray>()
+                .iter_opt()
+                .collect::<Vec<_>>(),
+            vec![Some("123.45"), Some("-0.05")]
+        );
+    }
+
+    #[test]
+    fn test_cast_string_to_numeric() {
+        let array = Arc::new(StringArray::from_opt_vec(vec![
+            Some("12"),
+            Some(" -3 "),
+            Some("1.5"),
+            Some("abc"),
+            Some("300"),
+            None,
+        ]));
+        assert_eq!(
+            cast(array.clone(), DataType::Int8)
+                .unwrap()
+                .downcast_ref::<Int8Array>()
+                .iter_opt()
+                .collect::<Vec<_>>(),
+            vec![Some(12), Some(-3), None, None, None, None]
+        );
+        assert_eq!(
+            cast(array, DataType::Float64)
+                .unwrap()
+                .downcast_ref::<Float64Array>()
+                .iter_opt()
+                .collect::<Vec<_>>(),
+            vec![Some(12.0), Some(-3.0), Some(1.5), None, Some(300.0), None]
+        );
+    }
+
+    #[test]
+    fn test_cast_timestamp() {
+        let array = cast(
+            Arc::new(Int64Array::from_opt_vec(vec![Some(1622509200000), None])),
+            DataType::Timestamp(None),
+        )
+        .unwrap();
+        assert_eq!(array.data_type(), DataType::Timestamp(None));
+        assert_eq!(
+            cast(array, DataType::Int64)
+                .unwrap()
+                .downcast_ref::<Int64Array>()
+                .iter_opt()
+                .collect::<Vec<_>>(),
+            vec![Some(1622509200000), None]
+        );
+    }
+}
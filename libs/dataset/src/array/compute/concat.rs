@@ -0,0 +1,263 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+
+use crate::array::{
+    Array, ArrayExt, ArrayRef, BooleanType, DataType, Decimal128Array, Decimal128Builder,
+    Float32Type, Float64Type, Int16Type, Int32Type, Int64Type, Int8Type, ListArray, NullArray,
+    PrimitiveArray, PrimitiveBuilder, StringArray, StringBuilder, StructArray, TimestampType,
+    UInt16Type, UInt32Type, UInt64Type, UInt8Type,
+};
+
+macro_rules! concat_primitive_arrays {
+    ($arrays:expr, $len:expr, $ty:ty) => {{
+        let inputs = $arrays
+            .iter()
+            .map(|array| array.downcast_ref::<PrimitiveArray<$ty>>())
+            .collect::<Vec<_>>();
+        if let Some(scalar) = same_scalar(inputs.iter().map(|input| input.to_scalar())) {
+            return Ok(Arc::new(PrimitiveArray::<$ty>::new_scalar($len, scalar)));
+        }
+        let mut builder = PrimitiveBuilder::<$ty>::with_capacity($len);
+        for input in inputs {
+            for value in input.iter_opt() {
+                builder.append_opt(value);
+            }
+        }
+        Arc::new(builder.finish())
+    }};
+}
+
+/// Returns the value of the scalar arrays if all of them are scalar arrays of the same value.
+fn same_scalar<T: PartialEq>(mut scalars: impl Iterator<Item = Option<T>>) -> Option<T> {
+    let first = scalars.next()??;
+    for scalar in scalars {
+        if scalar? != first {
+            return None;
+        }
+    }
+    Some(first)
+}
+
+/// Returns a new array with the values of all the arrays one after another.
+///
+/// Unlike chaining `concat` of the arrays pairwise, the values are copied only once.
+pub fn concat(arrays: &[&dyn Array]) -> Result<ArrayRef> {
+    anyhow::ensure!(!arrays.is_empty(), "at least one array is required");
+    let data_type = arrays[0].data_type();
+    if let Some(array) = arrays.iter().find(|array| array.data_type() != data_type) {
+        anyhow::bail!(
+            "cannot concatenate the arrays of type '{}' and '{}'",
+            data_type,
+            array.data_type()
+        );
+    }
+    let len = arrays.iter().map(|array| array.len()).sum();
+
+    Ok(match data_type {
+        DataType::Null => Arc::new(NullArray::new(len)),
+        DataType::Int8 => concat_primitive_arrays!(arrays, len, Int8Type),
+        DataType::Int16 => concat_primitive_arrays!(arrays, len, Int16Type),
+        DataType::Int32 => concat_primitive_arrays!(arrays, len, Int32Type),
+        DataType::Int64 => concat_primitive_arrays!(arrays, len, Int64Type),
+        DataType::UInt8 => concat_primitive_arrays!(arrays, len, UInt8Type),
+        DataType::UInt16 => concat_primitive_arrays!(arrays, len, UInt16Type),
+        DataType::UInt32 => concat_primitive_arrays!(arrays, len, UInt32Type),
+        DataType::UInt64 => concat_primitive_arrays!(arrays, len, UInt64Type),
+        DataType::Float32 => concat_primitive_arrays!(arrays, len, Float32Type),
+        DataType::Float64 => concat_primitive_arrays!(arrays, len, Float64Type),
+        DataType::Boolean => concat_primitive_arrays!(arrays, len, BooleanType),
+        DataType::Timestamp(_) => concat_primitive_arrays!(arrays, len, TimestampType),
+        DataType::Decimal128 { precision, scale } => {
+            let inputs = arrays
+                .iter()
+                .map(|array| array.downcast_ref::<Decimal128Array>())
+                .collect::<Vec<_>>();
+            if let Some(scalar) = same_scalar(inputs.iter().map(|input| input.to_scalar())) {
+                return Ok(Arc::new(Decimal128Array::new_scalar(
+                    precision, scale, len, scalar,
+                )));
+            }
+            let mut builder = Decimal128Builder::with_capacity(precision, scale, len);
+            for input in inputs {
+                for index in 0..input.len() {
+                    builder.append_opt(input.value_opt(index));
+                }
+            }
+            Arc::new(builder.finish())
+        }
+        DataType::String => {
+            let inputs = arrays
+                .iter()
+                .map(|array| array.downcast_ref::<StringArray>())
+                .collect::<Vec<_>>();
+            if let Some(scalar) = same_scalar(inputs.iter().map(|input| input.to_scalar())) {
+                return Ok(Arc::new(StringArray::new_scalar(len, scalar)));
+            }
+            let mut builder = StringBuilder::with_capacity(len);
+            for input in inputs {
+                for index in 0..input.len() {
+                    builder.append_opt(input.value_opt(index));
+                }
+            }
+            Arc::new(builder.finish())
+        }
+        DataType::List(_) => {
+            let inputs = arrays
+                .iter()
+                .map(|array| array.downcast_ref::<ListArray>())
+                .collect::<Vec<_>>();
+            let mut lengths = Vec::with_capacity(len);
+            // An empty slice of the values keeps the type of them when all the lists are empty.
+            let mut values = vec![inputs[0].values().slice(0, 0)];
+            for input in inputs {
+                for value in input.iter_opt() {
+                    lengths.push(value.as_ref().map(|value| value.len()));
+                    values.extend(value);
+                }
+            }
+            let values = concat(&values.iter().map(|value| &**value).collect::<Vec<_>>())?;
+            Arc::new(ListArray::from_lengths(&lengths, values))
+        }
+        DataType::Struct(_) => {
+            let inputs = arrays
+                .iter()
+                .map(|array| array.downcast_ref::<StructArray>())
+                .collect::<Vec<_>>();
+            let columns = (0..inputs[0].columns().len())
+                .map(|column| {
+                    concat(
+                        &inputs
+                            .iter()
+                            .map(|input| &*input.columns()[column])
+                            .collect::<Vec<_>>(),
+                    )
+                })
+                .collect::<Result<Vec<_>>>()?;
+            let validity = inputs
+                .iter()
+                .flat_map(|input| (0..input.len()).map(move |index| input.is_valid(index)))
+                .collect::<Vec<_>>();
+            Arc::new(StructArray::try_new(
+                inputs[0].fields().to_vec(),
+                columns,
+                Some(&validity),
+            )?)
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::array::{Int32Array, Int32Builder, ListBuilder};
+
+    use super::*;
+
+    #[test]
+    fn test_concat_primitive_arrays() {
+        let arrays = (0..100)
+            .map(|n| Int32Array::from_opt_vec(vec![Some(n), None]))
+            .collect::<Vec<_>>();
+        let array = concat(
+            &arrays
+                .iter()
+                .map(|array| array as &dyn Array)
+                .collect::<Vec<_>>(),
+        )
+        .unwrap();
+        assert_eq!(
+            array.downcast_ref::<Int32Array>(),
+            &Int32Array::from_opt_vec((0..100).flat_map(|n| vec![Some(n), None]).collect())
+        );
+    }
+
+    #[test]
+    fn test_concat_string_arrays() {
+        let arrays = (0..100)
+            .map(|n| StringArray::from_opt_vec(vec![Some(n.to_string()), None]))
+            .collect::<Vec<_>>();
+        let array = concat(
+            &arrays
+                .iter()
+                .map(|array| array as &dyn Array)
+                .collect::<Vec<_>>(),
+        )
+        .unwrap();
+        assert_eq!(
+            array.downcast_ref::<StringArray>(),
+            &StringArray::from_opt_vec(
+                (0..100)
+                    .flat_map(|n| vec![Some(n.to_string()), None])
+                    .collect()
+            )
+        );
+    }
+
+    #[test]
+    fn test_concat_scalar_arrays() {
+        let a = Int32Array::new_scalar(1000, Some(1));
+        let b = Int32Array::new_scalar(500, Some(1));
+        let c = Int32Array::new_scalar(100, Some(2));
+
+        let array = concat(&[&a, &b, &a]).unwrap();
+        assert_eq!(
+            array.downcast_ref::<Int32Array>(),
+            &Int32Array::new_scalar(2500, Some(1))
+        );
+        assert!(array.downcast_ref::<Int32Array>().to_scalar().is_some());
+
+        let array = concat(&[&a, &c, &b]).unwrap();
+        let array = array.downcast_ref::<Int32Array>();
+        assert_eq!(array.len(), 1600);
+        assert!(array.to_scalar().is_none());
+        assert_eq!(array.value(999), 1);
+        assert_eq!(array.value(1000), 2);
+        assert_eq!(array.value(1100), 1);
+
+        let d = Int32Array::from_vec(vec![1]);
+        let array = concat(&[&a, &d]).unwrap();
+        assert_eq!(
+            array.downcast_ref::<Int32Array>(),
+            &Int32Array::new_scalar(1001, Some(1))
+        );
+    }
+
+    #[test]
+    fn test_concat_list_arrays() {
+        // [[1, 2], null]
+        let mut builder = ListBuilder::new(Int32Builder::default());
+        builder.values().append(1);
+        builder.values().append(2);
+        builder.append();
+        builder.append_null();
+        let a = builder.finish();
+
+        // [[3]]
+        let mut builder = ListBuilder::new(Int32Builder::default());
+        builder.values().append(3);
+        builder.append();
+        let b = builder.finish();
+
+        let array = concat(&[&a, &b, &*a.slice(1, 1)]).unwrap();
+        let array = array.downcast_ref::<ListArray>();
+        assert_eq!(array.len(), 4);
+        assert_eq!(
+            array.value(0).downcast_ref::<Int32Array>(),
+            &Int32Array::from_vec(vec![1, 2])
+        );
+        assert!(array.is_null(1));
+        assert_eq!(
+            array.value(2).downcast_ref::<Int32Array>(),
+            &Int32Array::from_vec(vec![3])
+        );
+        assert!(array.is_null(3));
+    }
+
+    #[test]
+    fn test_concat_mismatched_types() {
+        let a = Int32Array::from_vec(vec![1]);
+        let b = StringArray::from_vec(vec!["a"]);
+        assert!(concat(&[&a, &b]).is_err());
+        assert!(concat(&[]).is_err());
+    }
+}
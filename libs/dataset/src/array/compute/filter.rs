@@ -1,9 +1,10 @@
 use std::sync::Arc;
 
 use crate::array::{
-    Array, ArrayExt, ArrayRef, BooleanArray, BooleanType, DataType, Float32Type, Float64Type,
-    Int16Type, Int32Type, Int64Type, Int8Type, NullArray, PrimitiveArray, PrimitiveBuilder,
-    StringArray, StringBuilder, TimestampType,
+    compute, Array, ArrayExt, ArrayRef, BooleanArray, BooleanType, DataType, Float32Type,
+    Float64Type, Int16Type, Int32Type, Int64Array, Int64Type, Int8Type, NullArray, PrimitiveArray,
+    PrimitiveBuilder, StringArray, StringBuilder, TimestampType, UInt16Type, UInt32Type,
+    UInt64Type, UInt8Type,
 };
 
 macro_rules! filter_primitive_array {
@@ -11,13 +12,13 @@ macro_rules! filter_primitive_array {
         let input = $array.downcast_ref::<PrimitiveArray<$ty>>();
         if let Some(scalar) = input.to_scalar() {
             return Arc::new(PrimitiveArray::<$ty>::new_scalar(
-                $flags.iter().filter(|x| *x).count(),
+                $flags.iter().filter(|x| **x).count(),
                 scalar,
             ));
         }
         let mut builder = PrimitiveBuilder::<$ty>::default();
-        for (value, flag) in input.iter_opt().zip($flags.iter()) {
-            if flag {
+        for (value, flag) in input.iter_opt().zip(&$flags) {
+            if *flag {
                 builder.append_opt(value);
             }
         }
@@ -25,15 +26,27 @@ macro_rules! filter_primitive_array {
     }};
 }
 
+/// Returns a new array with the values for which the flag is true, a null flag is treated as
+/// false. A scalar array stays a scalar array.
+///
+/// Panics if the lengths of the array and the flags are different.
 pub fn filter(array: ArrayRef, flags: &BooleanArray) -> ArrayRef {
     assert_eq!(array.len(), flags.len());
+    let flags = flags
+        .iter_opt()
+        .map(|flag| flag == Some(true))
+        .collect::<Vec<_>>();
 
     match array.data_type() {
-        DataType::Null => Arc::new(NullArray::new(flags.iter().filter(|x| *x).count())),
+        DataType::Null => Arc::new(NullArray::new(flags.iter().filter(|x| **x).count())),
         DataType::Int8 => filter_primitive_array!(array, flags, Int8Type),
         DataType::Int16 => filter_primitive_array!(array, flags, Int16Type),
         DataType::Int32 => filter_primitive_array!(array, flags, Int32Type),
         DataType::Int64 => filter_primitive_array!(array, flags, Int64Type),
+        DataType::UInt8 => filter_primitive_array!(array, flags, UInt8Type),
+        DataType::UInt16 => filter_primitive_array!(array, flags, UInt16Type),
+        DataType::UInt32 => filter_primitive_array!(array, flags, UInt32Type),
+        DataType::UInt64 => filter_primitive_array!(array, flags, UInt64Type),
         DataType::Float32 => filter_primitive_array!(array, flags, Float32Type),
         DataType::Float64 => filter_primitive_array!(array, flags, Float64Type),
         DataType::Boolean => filter_primitive_array!(array, flags, BooleanType),
@@ -42,18 +55,28 @@ pub fn filter(array: ArrayRef, flags: &BooleanArray) -> ArrayRef {
             let input = array.downcast_ref::<StringArray>();
             if let Some(scalar) = input.to_scalar() {
                 return Arc::new(StringArray::new_scalar(
-                    flags.iter().filter(|x| *x).count(),
+                    flags.iter().filter(|x| **x).count(),
                     scalar,
                 ));
             }
             let mut builder = StringBuilder::default();
-            for (value, flag) in input.iter_opt().zip(flags.iter()) {
-                if flag {
+            for (value, flag) in input.iter_opt().zip(&flags) {
+                if *flag {
                     builder.append_opt(value);
                 }
             }
             Arc::new(builder.finish())
         }
+        DataType::Decimal128 { .. } | DataType::List(_) | DataType::Struct(_) => {
+            let indices = flags
+                .iter()
+                .enumerate()
+                .filter(|(_, flag)| **flag)
+                .map(|(index, _)| index as i64)
+                .collect::<Vec<_>>();
+            compute::take(&*array, &Int64Array::from_vec(indices))
+                .expect("the indices are in bounds")
+        }
     }
 }
 
@@ -180,6 +203,36 @@ mod tests {
         assert!(scalar_array_string.eq(&scalar_array_string_2));
     }
 
+    #[test]
+    fn test_filter_null_flags() {
+        let flags = BooleanArray::from_opt_vec(vec![Some(true), None, Some(false), Some(true)]);
+        let array = filter(Arc::new(Int32Array::from_vec(vec![1, 2, 3, 4])), &flags);
+        assert!(array
+            .downcast_ref::<Int32Array>()
+            .eq(&Int32Array::from_vec(vec![1, 4])));
+
+        let array = filter(
+            Arc::new(StringArray::new_scalar(4, Some("hello"))),
+            &BooleanArray::new_scalar(4, None),
+        );
+        assert_eq!(array.len(), 0);
+    }
+
+    #[test]
+    fn test_filter_alternating_null_flags() {
+        let flags = BooleanArray::from_opt_vec(
+            (0..1000)
+                .map(|x| if x % 2 == 0 { Some(true) } else { None })
+                .collect(),
+        );
+        let array = filter(create_i32_array(), &flags);
+        assert_eq!(array.len(), 500);
+
+        let mut builder = Int32Builder::default();
+        (0..1000).step_by(2).for_each(|x| builder.append(x));
+        assert!(array.downcast_ref::<Int32Array>().eq(&builder.finish()));
+    }
+
     #[test]
     #[should_panic]
     fn test_filter_mismatch_len_panic() {
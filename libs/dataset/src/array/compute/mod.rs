@@ -1,3 +1,16 @@
+mod aggregate;
+mod cast;
+mod concat;
 mod filter;
+mod null;
+mod sort;
+mod take;
 
+pub use aggregate::{count, max, min, sum};
+pub use cast::{can_cast, cast, cast_with_mode, CastMode};
+pub use concat::concat;
 pub use filter::filter;
+pub use null::{compact_nulls, new_null_array};
+pub use sort::{lexsort_to_indices, lexsort_to_indices_limit, sort, sort_to_indices, SortOptions};
+pub use take::take;
+pub(crate) use take::take_opt;
@@ -0,0 +1,72 @@
+use std::sync::Arc;
+
+use crate::array::{
+    ArrayRef, BooleanArray, DataType, Decimal128Array, Float32Array, Float64Array, Int16Array,
+    Int32Array, Int64Array, Int8Array, NullArray, StringArray, TimestampArray, UInt16Array,
+    UInt32Array, UInt64Array, UInt8Array,
+};
+
+/// Returns an array of `len` nulls of `data_type` that doesn't store a value for each row, or
+/// `None` if the type has no such representation.
+pub fn new_null_array(data_type: &DataType, len: usize) -> Option<ArrayRef> {
+    Some(match data_type {
+        DataType::Null => Arc::new(NullArray::new(len)),
+        DataType::Int8 => Arc::new(Int8Array::new_scalar(len, None)),
+        DataType::Int16 => Arc::new(Int16Array::new_scalar(len, None)),
+        DataType::Int32 => Arc::new(Int32Array::new_scalar(len, None)),
+        DataType::Int64 => Arc::new(Int64Array::new_scalar(len, None)),
+        DataType::UInt8 => Arc::new(UInt8Array::new_scalar(len, None)),
+        DataType::UInt16 => Arc::new(UInt16Array::new_scalar(len, None)),
+        DataType::UInt32 => Arc::new(UInt32Array::new_scalar(len, None)),
+        DataType::UInt64 => Arc::new(UInt64Array::new_scalar(len, None)),
+        DataType::Float32 => Arc::new(Float32Array::new_scalar(len, None)),
+        DataType::Float64 => Arc::new(Float64Array::new_scalar(len, None)),
+        DataType::Boolean => Arc::new(BooleanArray::new_scalar(len, None)),
+        DataType::Timestamp(_) => Arc::new(TimestampArray::new_scalar(len, None)),
+        DataType::Decimal128 { precision, scale } => {
+            Arc::new(Decimal128Array::new_scalar(*precision, *scale, len, None))
+        }
+        DataType::String => Arc::new(StringArray::new_scalar(len, None::<&str>)),
+        _ => return None,
+    })
+}
+
+/// Replaces an array in which every value is null with an array created by `new_null_array`, so
+/// that the nulls don't take any memory.
+pub fn compact_nulls(array: ArrayRef) -> ArrayRef {
+    if array.is_empty() || array.null_count() != array.len() {
+        return array;
+    }
+    new_null_array(&array.data_type(), array.len()).unwrap_or(array)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::array::{ArrayExt, Int64Builder, ListBuilder, Scalar};
+
+    #[test]
+    fn test_compact_nulls() {
+        let mut builder = Int64Builder::with_capacity(3);
+        builder.append_null();
+        builder.append_null();
+        builder.append_null();
+        let array = compact_nulls(Arc::new(builder.finish()));
+        assert_eq!(array.data_type(), DataType::Int64);
+        assert_eq!(array.len(), 3);
+        assert_eq!(array.downcast_ref::<Int64Array>().to_scalar(), Some(None));
+
+        let mut builder = Int64Builder::with_capacity(2);
+        builder.append_null();
+        builder.append(1);
+        let array = compact_nulls(Arc::new(builder.finish()));
+        assert_eq!(array.downcast_ref::<Int64Array>().to_scalar(), None);
+        assert_eq!(array.scalar_value(1), Scalar::Int64(1));
+
+        let mut builder = ListBuilder::new(Int64Builder::with_capacity(0));
+        builder.append_null();
+        let array = compact_nulls(Arc::new(builder.finish()));
+        assert_eq!(array.len(), 1);
+        assert_eq!(array.scalar_value(0), Scalar::Null);
+    }
+}
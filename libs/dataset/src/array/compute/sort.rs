@@ -0,0 +1,381 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+use anyhow::Result;
+
+use crate::array::{
+    compute, Array, ArrayExt, ArrayRef, BooleanType, DataType, Decimal128Array, Float32Type,
+    Float64Type, Int16Type, Int32Type, Int64Array, Int64Type, Int8Type, PrimitiveArray,
+    StringArray, TimestampType, UInt16Type, UInt32Type, UInt64Type, UInt8Type,
+};
+
+/// How to order the values of a sort key.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct SortOptions {
+    /// Whether the values are sorted in descending order.
+    pub descending: bool,
+
+    /// Whether the nulls are placed before the non-null values.
+    pub nulls_first: bool,
+}
+
+impl Default for SortOptions {
+    fn default() -> Self {
+        Self::asc()
+    }
+}
+
+impl SortOptions {
+    /// Ascending order with the nulls last, the default of SQL.
+    #[inline]
+    pub fn asc() -> Self {
+        Self {
+            descending: false,
+            nulls_first: false,
+        }
+    }
+
+    /// Descending order with the nulls first, the default of SQL.
+    #[inline]
+    pub fn desc() -> Self {
+        Self {
+            descending: true,
+            nulls_first: true,
+        }
+    }
+
+    #[inline]
+    pub fn nulls_first(self, nulls_first: bool) -> Self {
+        Self {
+            nulls_first,
+            ..self
+        }
+    }
+}
+
+type ValueComparator<'a> = Box<dyn Fn(usize, usize) -> Ordering + 'a>;
+
+macro_rules! compare_primitive_values {
+    ($array:expr, $ty:ty) => {{
+        let array = $array.downcast_ref::<PrimitiveArray<$ty>>();
+        Box::new(move |a, b| array.value(a).cmp(&array.value(b))) as ValueComparator
+    }};
+}
+
+macro_rules! compare_float_values {
+    ($array:expr, $ty:ty) => {{
+        let array = $array.downcast_ref::<PrimitiveArray<$ty>>();
+        Box::new(move |a, b| {
+            let (a, b) = (array.value(a), array.value(b));
+            // NaN is greater than any other value so that the order is total
+            a.partial_cmp(&b)
+                .unwrap_or_else(|| a.is_nan().cmp(&b.is_nan()))
+        }) as ValueComparator
+    }};
+}
+
+/// Returns a comparator of the non-null values at two positions of the array.
+fn value_comparator(array: &dyn Array) -> Result<ValueComparator<'_>> {
+    Ok(match array.data_type() {
+        DataType::Null => Box::new(|_, _| Ordering::Equal),
+        DataType::Int8 => compare_primitive_values!(array, Int8Type),
+        DataType::Int16 => compare_primitive_values!(array, Int16Type),
+        DataType::Int32 => compare_primitive_values!(array, Int32Type),
+        DataType::Int64 => compare_primitive_values!(array, Int64Type),
+        DataType::UInt8 => compare_primitive_values!(array, UInt8Type),
+        DataType::UInt16 => compare_primitive_values!(array, UInt16Type),
+        DataType::UInt32 => compare_primitive_values!(array, UInt32Type),
+        DataType::UInt64 => compare_primitive_values!(array, UInt64Type),
+        DataType::Float32 => compare_float_values!(array, Float32Type),
+        DataType::Float64 => compare_float_values!(array, Float64Type),
+        DataType::Boolean => compare_primitive_values!(array, BooleanType),
+        DataType::Timestamp(_) => compare_primitive_values!(array, TimestampType),
+        DataType::Decimal128 { .. } => {
+            let array = array.downcast_ref::<Decimal128Array>();
+            Box::new(move |a, b| array.value(a).cmp(&array.value(b)))
+        }
+        DataType::String => {
+            let array = array.downcast_ref::<StringArray>();
+            Box::new(move |a, b| array.value(a).cmp(array.value(b)))
+        }
+        DataType::List(_) | DataType::Struct(_) => {
+            anyhow::bail!("cannot sort the values of type '{}'", array.data_type())
+        }
+    })
+}
+
+/// Returns a comparator of the values at two positions of the array, ordered by `options`.
+fn comparator(array: &dyn Array, options: SortOptions) -> Result<ValueComparator<'_>> {
+    let compare_values = value_comparator(array)?;
+    Ok(Box::new(move |a, b| {
+        match (array.is_valid(a), array.is_valid(b)) {
+            (true, true) if options.descending => compare_values(b, a),
+            (true, true) => compare_values(a, b),
+            (false, false) => Ordering::Equal,
+            (false, true) if options.nulls_first => Ordering::Less,
+            (false, true) => Ordering::Greater,
+            (true, false) if options.nulls_first => Ordering::Greater,
+            (true, false) => Ordering::Less,
+        }
+    }))
+}
+
+/// Returns the indices that sort the array, equal values keep their original order.
+pub fn sort_to_indices(array: &dyn Array, options: SortOptions) -> Result<Vec<usize>> {
+    lexsort_to_indices(&[(array, options)])
+}
+
+/// Returns the indices that sort the rows by multiple keys of the same length.
+///
+/// The keys are compared in turn, a key is only compared if all the previous keys are equal,
+/// and the rows that are equal in all keys keep their original order.
+pub fn lexsort_to_indices(keys: &[(&dyn Array, SortOptions)]) -> Result<Vec<usize>> {
+    let (len, compare) = lexsort_comparator(keys)?;
+    let mut indices = (0..len).collect::<Vec<_>>();
+    indices.sort_by(|a, b| compare(*a, *b));
+    Ok(indices)
+}
+
+/// Like `lexsort_to_indices`, but only returns the indices of the first `limit` rows.
+///
+/// The rows are selected with a binary heap of at most `limit` rows instead of sorting all of
+/// them.
+pub fn lexsort_to_indices_limit(
+    keys: &[(&dyn Array, SortOptions)],
+    limit: usize,
+) -> Result<Vec<usize>> {
+    struct HeapItem<'a> {
+        index: usize,
+        compare: &'a dyn Fn(usize, usize) -> Ordering,
+    }
+
+    impl<'a> PartialEq for HeapItem<'a> {
+        fn eq(&self, other: &Self) -> bool {
+            self.cmp(other) == Ordering::Equal
+        }
+    }
+
+    impl<'a> Eq for HeapItem<'a> {}
+
+    impl<'a> PartialOrd for HeapItem<'a> {
+        fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+            Some(self.cmp(other))
+        }
+    }
+
+    impl<'a> Ord for HeapItem<'a> {
+        fn cmp(&self, other: &Self) -> Ordering {
+            // the rows that are equal in all keys keep their original order
+            (self.compare)(self.index, other.index).then(self.index.cmp(&other.index))
+        }
+    }
+
+    let (len, compare) = lexsort_comparator(keys)?;
+    // the top of the heap is the last one of the selected rows
+    let mut heap = BinaryHeap::with_capacity(limit.min(len));
+    for index in 0..len {
+        let item = HeapItem {
+            index,
+            compare: &compare,
+        };
+        if heap.len() < limit {
+            heap.push(item);
+        } else if let Some(mut top) = heap.peek_mut() {
+            if item < *top {
+                *top = item;
+            }
+        }
+    }
+    Ok(heap
+        .into_sorted_vec()
+        .into_iter()
+        .map(|item| item.index)
+        .collect())
+}
+
+/// Returns the number of rows and a comparator of the rows by the keys.
+fn lexsort_comparator<'a>(
+    keys: &[(&'a dyn Array, SortOptions)],
+) -> Result<(usize, impl Fn(usize, usize) -> Ordering + 'a)> {
+    let len = match keys.first() {
+        Some((array, _)) => array.len(),
+        None => anyhow::bail!("at least one sort key is required"),
+    };
+    anyhow::ensure!(
+        keys.iter().all(|(array, _)| array.len() == len),
+        "all sort keys must have the same length"
+    );
+
+    let comparators = keys
+        .iter()
+        .map(|(array, options)| comparator(*array, *options))
+        .collect::<Result<Vec<_>>>()?;
+    Ok((len, move |a, b| {
+        comparators
+            .iter()
+            .map(|compare| compare(a, b))
+            .find(|ordering| *ordering != Ordering::Equal)
+            .unwrap_or(Ordering::Equal)
+    }))
+}
+
+/// Sorts the array.
+pub fn sort(array: ArrayRef, options: SortOptions) -> Result<ArrayRef> {
+    let indices = sort_to_indices(&*array, options)?;
+    compute::take(
+        &*array,
+        &Int64Array::from_vec(indices.into_iter().map(|index| index as i64).collect()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::array::{Float64Array, Int32Array, Int32Builder, ListBuilder};
+
+    fn sort_i32(values: Vec<Option<i32>>, options: SortOptions) -> Vec<Option<i32>> {
+        sort(Arc::new(Int32Array::from_opt_vec(values)), options)
+            .unwrap()
+            .downcast_ref::<Int32Array>()
+            .iter_opt()
+            .collect()
+    }
+
+    #[test]
+    fn test_sort_nulls() {
+        let values = vec![Some(3), None, Some(1), None, Some(2)];
+
+        assert_eq!(
+            sort_i32(values.clone(), SortOptions::asc()),
+            vec![Some(1), Some(2), Some(3), None, None]
+        );
+        assert_eq!(
+            sort_i32(values.clone(), SortOptions::asc().nulls_first(true)),
+            vec![None, None, Some(1), Some(2), Some(3)]
+        );
+        assert_eq!(
+            sort_i32(values.clone(), SortOptions::desc()),
+            vec![None, None, Some(3), Some(2), Some(1)]
+        );
+        assert_eq!(
+            sort_i32(values, SortOptions::desc().nulls_first(false)),
+            vec![Some(3), Some(2), Some(1), None, None]
+        );
+    }
+
+    #[test]
+    fn test_sort_stable() {
+        let array = StringArray::from_opt_vec(vec![Some("b"), None, Some("a"), Some("b"), None]);
+        assert_eq!(
+            sort_to_indices(&array, SortOptions::asc()).unwrap(),
+            vec![2, 0, 3, 1, 4]
+        );
+        assert_eq!(
+            sort_to_indices(&array, SortOptions::desc()).unwrap(),
+            vec![1, 4, 0, 3, 2]
+        );
+    }
+
+    #[test]
+    fn test_lexsort() {
+        let a = Int32Array::from_vec(vec![2, 1, 2, 1, 2]);
+        let b = StringArray::from_opt_vec(vec![Some("x"), Some("y"), None, Some("z"), Some("y")]);
+
+        // ORDER BY a ASC, b DESC
+        assert_eq!(
+            lexsort_to_indices(&[(&a, SortOptions::asc()), (&b, SortOptions::desc())]).unwrap(),
+            vec![3, 1, 2, 4, 0]
+        );
+
+        // ORDER BY a DESC, b ASC NULLS FIRST
+        assert_eq!(
+            lexsort_to_indices(&[
+                (&a, SortOptions::desc()),
+                (&b, SortOptions::asc().nulls_first(true))
+            ])
+            .unwrap(),
+            vec![2, 0, 4, 1, 3]
+        );
+
+        // ties in the first key are ordered by the second key
+        let c = Int32Array::from_vec(vec![1, 1, 1, 1, 1]);
+        assert_eq!(
+            lexsort_to_indices(&[(&c, SortOptions::asc()), (&a, SortOptions::asc())]).unwrap(),
+            vec![1, 3, 0, 2, 4]
+        );
+
+        assert_eq!(
+            lexsort_to_indices(&[
+                (&a, SortOptions::asc()),
+                (&Int32Array::from_vec(vec![1]), SortOptions::asc())
+            ])
+            .unwrap_err()
+            .to_string(),
+            "all sort keys must have the same length"
+        );
+    }
+
+    #[test]
+    fn test_lexsort_limit() {
+        let a = Int32Array::from_vec(vec![2, 1, 2, 1, 2, 3]);
+        let b = StringArray::from_opt_vec(vec![
+            Some("x"),
+            Some("y"),
+            None,
+            Some("z"),
+            Some("y"),
+            Some("x"),
+        ]);
+        let keys: &[(&dyn Array, SortOptions)] =
+            &[(&a, SortOptions::desc()), (&b, SortOptions::asc())];
+        let indices = lexsort_to_indices(keys).unwrap();
+
+        for limit in 0..8 {
+            assert_eq!(
+                lexsort_to_indices_limit(keys, limit).unwrap(),
+                &indices[..limit.min(indices.len())]
+            );
+        }
+
+        // the rows equal in all keys keep their original order
+        let c = Int32Array::from_vec(vec![1, 0, 1, 0, 1]);
+        assert_eq!(
+            lexsort_to_indices_limit(&[(&c, SortOptions::desc())], 2).unwrap(),
+            vec![0, 2]
+        );
+    }
+
+    #[test]
+    fn test_sort_float() {
+        let array = sort(
+            Arc::new(Float64Array::from_opt_vec(vec![
+                Some(f64::NAN),
+                Some(1.5),
+                None,
+                Some(-2.0),
+            ])),
+            SortOptions::asc(),
+        )
+        .unwrap();
+        let values = array
+            .downcast_ref::<Float64Array>()
+            .iter_opt()
+            .collect::<Vec<_>>();
+        assert_eq!(&values[..2], &[Some(-2.0), Some(1.5)]);
+        assert!(values[2].unwrap().is_nan());
+        assert_eq!(values[3], None);
+    }
+
+    #[test]
+    fn test_sort_unsupported() {
+        let array = ListBuilder::new(Int32Builder::default()).finish();
+        assert_eq!(
+            sort_to_indices(&array, SortOptions::asc())
+                .unwrap_err()
+                .to_string(),
+            "cannot sort the values of type 'list<int32>'"
+        );
+    }
+}
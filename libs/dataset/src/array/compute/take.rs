@@ -0,0 +1,229 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+
+use crate::array::{
+    Array, ArrayExt, ArrayRef, BooleanType, DataType, Decimal128Array, Decimal128Builder,
+    Float32Type, Float64Type, Int16Type, Int32Type, Int64Array, Int64Type, Int8Type, ListArray,
+    NullArray, PrimitiveArray, PrimitiveBuilder, StringArray, StringBuilder, StructArray,
+    TimestampType, UInt16Type, UInt32Type, UInt64Type, UInt8Type,
+};
+
+macro_rules! take_primitive_array {
+    ($array:expr, $indices:expr, $ty:ty) => {{
+        let input = $array.downcast_ref::<PrimitiveArray<$ty>>();
+        if let Some(scalar) = input.to_scalar() {
+            if scalar.is_none() || $indices.iter().all(Option::is_some) {
+                return Arc::new(PrimitiveArray::<$ty>::new_scalar($indices.len(), scalar));
+            }
+        }
+        let mut builder = PrimitiveBuilder::<$ty>::with_capacity($indices.len());
+        for index in $indices {
+            builder.append_opt(index.and_then(|index| input.value_opt(index)));
+        }
+        Arc::new(builder.finish())
+    }};
+}
+
+/// Returns a new array with the values at the given indices, an index can be repeated.
+///
+/// The value at a null index is null, and an error is returned if an index is out of bounds.
+pub fn take(array: &dyn Array, indices: &Int64Array) -> Result<ArrayRef> {
+    let indices = indices
+        .iter_opt()
+        .map(|index| match index {
+            Some(index) if index >= 0 && (index as usize) < array.len() => Ok(Some(index as usize)),
+            Some(index) => anyhow::bail!(
+                "index out of bounds: the len is {} but the index is {}",
+                array.len(),
+                index
+            ),
+            None => Ok(None),
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(take_opt(array, &indices))
+}
+
+/// Like `take`, but the indices are known to be in bounds.
+pub(crate) fn take_opt(array: &dyn Array, indices: &[Option<usize>]) -> ArrayRef {
+    match array.data_type() {
+        DataType::Null => Arc::new(NullArray::new(indices.len())),
+        DataType::Int8 => take_primitive_array!(array, indices, Int8Type),
+        DataType::Int16 => take_primitive_array!(array, indices, Int16Type),
+        DataType::Int32 => take_primitive_array!(array, indices, Int32Type),
+        DataType::Int64 => take_primitive_array!(array, indices, Int64Type),
+        DataType::UInt8 => take_primitive_array!(array, indices, UInt8Type),
+        DataType::UInt16 => take_primitive_array!(array, indices, UInt16Type),
+        DataType::UInt32 => take_primitive_array!(array, indices, UInt32Type),
+        DataType::UInt64 => take_primitive_array!(array, indices, UInt64Type),
+        DataType::Float32 => take_primitive_array!(array, indices, Float32Type),
+        DataType::Float64 => take_primitive_array!(array, indices, Float64Type),
+        DataType::Boolean => take_primitive_array!(array, indices, BooleanType),
+        DataType::Timestamp(_) => take_primitive_array!(array, indices, TimestampType),
+        DataType::Decimal128 { precision, scale } => {
+            let input = array.downcast_ref::<Decimal128Array>();
+            if let Some(scalar) = input.to_scalar() {
+                if scalar.is_none() || indices.iter().all(Option::is_some) {
+                    return Arc::new(Decimal128Array::new_scalar(
+                        precision,
+                        scale,
+                        indices.len(),
+                        scalar,
+                    ));
+                }
+            }
+            let mut builder = Decimal128Builder::with_capacity(precision, scale, indices.len());
+            for index in indices {
+                builder.append_opt(index.and_then(|index| input.value_opt(index)));
+            }
+            Arc::new(builder.finish())
+        }
+        DataType::String => {
+            let input = array.downcast_ref::<StringArray>();
+            if let Some(scalar) = input.to_scalar() {
+                if scalar.is_none() || indices.iter().all(Option::is_some) {
+                    return Arc::new(StringArray::new_scalar(indices.len(), scalar));
+                }
+            }
+            let mut builder = StringBuilder::with_capacity(indices.len());
+            for index in indices {
+                builder.append_opt(index.and_then(|index| input.value_opt(index)));
+            }
+            Arc::new(builder.finish())
+        }
+        DataType::List(_) => Arc::new(array.downcast_ref::<ListArray>().take(indices)),
+        DataType::Struct(_) => {
+            let array = array.downcast_ref::<StructArray>();
+            let columns = array
+                .columns()
+                .iter()
+                .map(|column| take_opt(&**column, indices))
+                .collect();
+            let validity = indices
+                .iter()
+                .map(|index| matches!(index, Some(index) if array.is_valid(*index)))
+                .collect::<Vec<_>>();
+            Arc::new(
+                StructArray::try_new(array.fields().to_vec(), columns, Some(&validity)).unwrap(),
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::array::{BooleanArray, Int32Array, Int32Builder, ListBuilder};
+
+    use super::*;
+
+    fn indices(indices: &[i64]) -> Int64Array {
+        Int64Array::from_vec(indices.to_vec())
+    }
+
+    #[test]
+    fn test_take_primitive_array() {
+        let array = Int32Array::from_opt_vec(vec![Some(1), None, Some(3)]);
+        let array = take(&array, &indices(&[2, 2, 1, 0, 0])).unwrap();
+        assert_eq!(
+            array.downcast_ref::<Int32Array>(),
+            &Int32Array::from_opt_vec(vec![Some(3), Some(3), None, Some(1), Some(1)])
+        );
+
+        let array = Int32Array::new_scalar(3, Some(7));
+        let array = take(&array, &indices(&[0, 0, 0, 0])).unwrap();
+        assert_eq!(
+            array.downcast_ref::<Int32Array>(),
+            &Int32Array::new_scalar(4, Some(7))
+        );
+    }
+
+    #[test]
+    fn test_take_string_array() {
+        let array = StringArray::from_opt_vec(vec![Some("a"), None, Some("c")]);
+        let array = take(&array, &indices(&[1, 0, 2, 0])).unwrap();
+        assert_eq!(
+            array.downcast_ref::<StringArray>(),
+            &StringArray::from_opt_vec(vec![None, Some("a"), Some("c"), Some("a")])
+        );
+
+        let array = StringArray::from_vec(vec!["a"]);
+        assert!(take(&array, &indices(&[])).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_take_reorder() {
+        let array = StringArray::from_vec(vec!["a", "b", "c"]);
+        let array = take(&array, &indices(&[2, 0, 1])).unwrap();
+        assert_eq!(
+            array.downcast_ref::<StringArray>(),
+            &StringArray::from_vec(vec!["c", "a", "b"])
+        );
+
+        let array = BooleanArray::from_opt_vec(vec![Some(true), None]);
+        let array = take(&array, &indices(&[1, 0])).unwrap();
+        assert_eq!(
+            array.downcast_ref::<BooleanArray>(),
+            &BooleanArray::from_opt_vec(vec![None, Some(true)])
+        );
+
+        let array = NullArray::new(2);
+        let array = take(&array, &indices(&[1, 0, 1])).unwrap();
+        assert_eq!(array.data_type(), DataType::Null);
+        assert_eq!(array.len(), 3);
+    }
+
+    #[test]
+    fn test_take_null_indices() {
+        let null_indices = Int64Array::from_opt_vec(vec![Some(1), None, Some(0)]);
+
+        let array = Int32Array::from_vec(vec![1, 2]);
+        assert_eq!(
+            take(&array, &null_indices)
+                .unwrap()
+                .downcast_ref::<Int32Array>(),
+            &Int32Array::from_opt_vec(vec![Some(2), None, Some(1)])
+        );
+
+        // a scalar can't hold the null of a null index
+        let array = StringArray::new_scalar(2, Some("a"));
+        assert_eq!(
+            take(&array, &null_indices)
+                .unwrap()
+                .downcast_ref::<StringArray>(),
+            &StringArray::from_opt_vec(vec![Some("a"), None, Some("a")])
+        );
+    }
+
+    #[test]
+    fn test_take_list_array() {
+        // [[1, 2], null, [3]]
+        let mut builder = ListBuilder::new(Int32Builder::default());
+        builder.values().append(1);
+        builder.values().append(2);
+        builder.append();
+        builder.append_null();
+        builder.values().append(3);
+        builder.append();
+        let array: ArrayRef = Arc::new(builder.finish());
+
+        let array = take(&*array.slice(1, 2), &indices(&[1, 0, 1])).unwrap();
+        let array = array.downcast_ref::<ListArray>();
+        assert_eq!(array.len(), 3);
+        assert!(array.is_null(1));
+        assert_eq!(
+            array.value(0).downcast_ref::<Int32Array>(),
+            &Int32Array::from_vec(vec![3])
+        );
+        assert_eq!(
+            array.value(2).downcast_ref::<Int32Array>(),
+            &Int32Array::from_vec(vec![3])
+        );
+    }
+
+    #[test]
+    fn test_take_out_of_bounds() {
+        let array = Int32Array::from_vec(vec![1, 2, 3]);
+        assert!(take(&array, &indices(&[0, 3])).is_err());
+        assert!(take(&array, &indices(&[-1])).is_err());
+    }
+}
@@ -4,8 +4,10 @@ use chrono_tz::Tz;
 use derive_more::Display;
 use serde::{Deserialize, Serialize};
 
+use crate::dataset::Field;
+
 /// The sets of data types.
-#[derive(Debug, Copy, Clone, Display, Serialize, Deserialize)]
+#[derive(Debug, Clone, Display, Serialize, Deserialize)]
 pub enum DataType {
     /// Null type
     #[display(fmt = "null")]
@@ -27,6 +29,22 @@ pub enum DataType {
     #[display(fmt = "int64")]
     Int64,
 
+    /// An unsigned 8-bit integer.
+    #[display(fmt = "uint8")]
+    UInt8,
+
+    /// An unsigned 16-bit integer.
+    #[display(fmt = "uint16")]
+    UInt16,
+
+    /// An unsigned 32-bit integer.
+    #[display(fmt = "uint32")]
+    UInt32,
+
+    /// An unsigned 64-bit integer.
+    #[display(fmt = "uint64")]
+    UInt64,
+
     /// A 32-bit floating point number.
     #[display(fmt = "float32")]
     Float32,
@@ -39,13 +57,42 @@ pub enum DataType {
     #[display(fmt = "boolean")]
     Boolean,
 
-    /// A timestamp type, it can attach a timezone.
-    #[display(fmt = "timestamp")]
+    /// A timestamp type, it can attach a timezone, which is displayed after it, e.g.
+    /// `timestamp(Asia/Shanghai)`.
+    #[display(fmt = "{}", "display_timestamp(_0)")]
     Timestamp(Option<Tz>),
 
+    /// An exact decimal number with up to `precision` digits, `scale` of which are after the
+    /// decimal point.
+    #[display(fmt = "decimal128({}, {})", precision, scale)]
+    Decimal128 { precision: u8, scale: u8 },
+
     /// A variable-length string in Unicode with UTF-8 encoding.
     #[display(fmt = "string")]
     String,
+
+    /// A variable-length list of values of the item type.
+    #[display(fmt = "list<{}>", _0)]
+    List(Box<DataType>),
+
+    /// A struct with named fields, e.g. a JSON object.
+    #[display(fmt = "struct<{}>", "display_fields(_0)")]
+    Struct(Vec<Field>),
+}
+
+fn display_timestamp(tz: &Option<Tz>) -> String {
+    match tz {
+        Some(tz) => format!("timestamp({})", tz.name()),
+        None => "timestamp".to_string(),
+    }
+}
+
+fn display_fields(fields: &[Field]) -> String {
+    fields
+        .iter()
+        .map(|field| format!("{}: {}", field.name, field.data_type))
+        .collect::<Vec<_>>()
+        .join(", ")
 }
 
 impl Hash for DataType {
@@ -61,6 +108,26 @@ impl Hash for DataType {
             DataType::Boolean => 7i32.hash(state),
             DataType::Timestamp(_) => 8i32.hash(state),
             DataType::String => 9i32.hash(state),
+            DataType::List(item_type) => {
+                10i32.hash(state);
+                item_type.hash(state);
+            }
+            DataType::Struct(fields) => {
+                11i32.hash(state);
+                for field in fields {
+                    field.name.hash(state);
+                    field.data_type.hash(state);
+                }
+            }
+            DataType::Decimal128 { precision, scale } => {
+                12i32.hash(state);
+                precision.hash(state);
+                scale.hash(state);
+            }
+            DataType::UInt8 => 13i32.hash(state),
+            DataType::UInt16 => 14i32.hash(state),
+            DataType::UInt32 => 15i32.hash(state),
+            DataType::UInt64 => 16i32.hash(state),
         }
     }
 }
@@ -70,19 +137,37 @@ impl Eq for DataType {}
 impl PartialEq for DataType {
     fn eq(&self, other: &Self) -> bool {
         use DataType::*;
-        matches!(
-            (self, other),
-            (Null, Null)
-                | (Int8, Int8)
-                | (Int16, Int16)
-                | (Int32, Int32)
-                | (Int64, Int64)
-                | (Float32, Float32)
-                | (Float64, Float64)
-                | (Boolean, Boolean)
-                | (Timestamp(_), Timestamp(_))
-                | (String, String)
-        )
+        match (self, other) {
+            (List(a), List(b)) => a == b,
+            (Struct(a), Struct(b)) => a == b,
+            (
+                Decimal128 {
+                    precision: p1,
+                    scale: s1,
+                },
+                Decimal128 {
+                    precision: p2,
+                    scale: s2,
+                },
+            ) => p1 == p2 && s1 == s2,
+            _ => matches!(
+                (self, other),
+                (Null, Null)
+                    | (Int8, Int8)
+                    | (Int16, Int16)
+                    | (Int32, Int32)
+                    | (Int64, Int64)
+                    | (UInt8, UInt8)
+                    | (UInt16, UInt16)
+                    | (UInt32, UInt32)
+                    | (UInt64, UInt64)
+                    | (Float32, Float32)
+                    | (Float64, Float64)
+                    | (Boolean, Boolean)
+                    | (Timestamp(_), Timestamp(_))
+                    | (String, String)
+            ),
+        }
     }
 }
 
@@ -96,26 +181,51 @@ impl DataType {
                 | DataType::Int16
                 | DataType::Int32
                 | DataType::Int64
+                | DataType::UInt8
+                | DataType::UInt16
+                | DataType::UInt32
+                | DataType::UInt64
                 | DataType::Float32
                 | DataType::Float64
         )
     }
 
-    /// Returns `true` if this type is a integer type.
+    /// Returns `true` if this type is a integer type, signed or unsigned.
     #[inline]
     pub fn is_integer(&self) -> bool {
+        self.is_signed_integer() || self.is_unsigned_integer()
+    }
+
+    /// Returns `true` if this type is a signed integer type.
+    #[inline]
+    pub fn is_signed_integer(&self) -> bool {
         matches!(
             self,
             DataType::Int8 | DataType::Int16 | DataType::Int32 | DataType::Int64
         )
     }
 
+    /// Returns `true` if this type is an unsigned integer type.
+    #[inline]
+    pub fn is_unsigned_integer(&self) -> bool {
+        matches!(
+            self,
+            DataType::UInt8 | DataType::UInt16 | DataType::UInt32 | DataType::UInt64
+        )
+    }
+
     /// Returns `true` if this type is a float type.
     #[inline]
     pub fn is_float(&self) -> bool {
         matches!(self, DataType::Float32 | DataType::Float64)
     }
 
+    /// Returns `true` if this type is a decimal type.
+    #[inline]
+    pub fn is_decimal(&self) -> bool {
+        matches!(self, DataType::Decimal128 { .. })
+    }
+
     /// Returns `true` if this type is a boolean type.
     #[inline]
     pub fn is_boolean(&self) -> bool {
@@ -134,6 +244,37 @@ impl DataType {
         matches!(self, DataType::Timestamp(_))
     }
 
+    /// Returns `true` if this type represents a point in time, which is only the timestamp type
+    /// for now.
+    #[inline]
+    pub fn is_temporal(&self) -> bool {
+        self.is_timestamp()
+    }
+
+    /// Returns `true` if the values of this type can be negative.
+    #[inline]
+    pub fn is_signed(&self) -> bool {
+        self.is_signed_integer() || self.is_float() || self.is_decimal()
+    }
+
+    /// Returns `true` if this type is numeric and its values can't be negative.
+    #[inline]
+    pub fn is_unsigned(&self) -> bool {
+        self.is_unsigned_integer()
+    }
+
+    /// Returns `true` if this type is a list type.
+    #[inline]
+    pub fn is_list(&self) -> bool {
+        matches!(self, DataType::List(_))
+    }
+
+    /// Returns `true` if this type is a struct type.
+    #[inline]
+    pub fn is_struct(&self) -> bool {
+        matches!(self, DataType::Struct(_))
+    }
+
     /// Returns `true` if this type can be cast to `to` type.
     #[inline]
     pub fn can_cast_to(&self, to: Self) -> bool {
@@ -143,17 +284,28 @@ impl DataType {
             return true;
         }
 
-        match to {
+        match &to {
             Null => matches!(self, Null),
-            Int8 => matches!(self, Int8),
-            Int16 => matches!(self, Int8 | Int16),
-            Int32 => matches!(self, Int8 | Int16 | Int32),
-            Int64 => matches!(self, Int8 | Int16 | Int32 | Int64),
-            Float32 => matches!(self, Int8 | Int16 | Int32 | Int64 | Float32),
-            Float64 => matches!(self, Int8 | Int16 | Int32 | Int64 | Float32 | Float64),
+            Int8 => matches!(self, Int8 | Boolean),
+            Int16 => matches!(self, Int8 | Int16 | UInt8 | Boolean),
+            Int32 => matches!(self, Int8 | Int16 | Int32 | UInt8 | UInt16 | Boolean),
+            Int64 => matches!(
+                self,
+                Int8 | Int16 | Int32 | Int64 | UInt8 | UInt16 | UInt32 | Boolean
+            ),
+            UInt8 => matches!(self, UInt8 | Boolean),
+            UInt16 => matches!(self, UInt8 | UInt16 | Boolean),
+            UInt32 => matches!(self, UInt8 | UInt16 | UInt32 | Boolean),
+            UInt64 => matches!(self, UInt8 | UInt16 | UInt32 | UInt64 | Boolean),
+            Float32 => self.is_integer() || matches!(self, Float32 | Boolean),
+            Float64 => {
+                self.is_integer() || matches!(self, Float32 | Float64 | Boolean | Decimal128 { .. })
+            }
             Boolean => matches!(self, Boolean),
             Timestamp(_) => matches!(self, Timestamp(_)),
-            String => true,
+            Decimal128 { .. } => self.is_integer() || self.is_decimal(),
+            String => !self.is_list() && !self.is_struct(),
+            List(_) | Struct(_) => false,
         }
     }
 }
@@ -161,6 +313,43 @@ impl DataType {
 #[cfg(test)]
 mod tests {
     use super::DataType::*;
+    use crate::dataset::Field;
+
+    #[test]
+    fn test_sign_and_temporal() {
+        let types = vec![
+            (Null, false, false, false),
+            (Int8, true, false, false),
+            (Int16, true, false, false),
+            (Int32, true, false, false),
+            (Int64, true, false, false),
+            (UInt8, false, true, false),
+            (UInt16, false, true, false),
+            (UInt32, false, true, false),
+            (UInt64, false, true, false),
+            (Float32, true, false, false),
+            (Float64, true, false, false),
+            (Boolean, false, false, false),
+            (Timestamp(None), false, false, true),
+            (
+                Decimal128 {
+                    precision: 10,
+                    scale: 2,
+                },
+                true,
+                false,
+                false,
+            ),
+            (String, false, false, false),
+            (List(Box::new(Int8)), false, false, false),
+            (Struct(vec![Field::new("a", Int8)]), false, false, false),
+        ];
+        for (data_type, signed, unsigned, temporal) in types {
+            assert_eq!(data_type.is_signed(), signed, "{}", data_type);
+            assert_eq!(data_type.is_unsigned(), unsigned, "{}", data_type);
+            assert_eq!(data_type.is_temporal(), temporal, "{}", data_type);
+        }
+    }
 
     macro_rules! test_cast {
         ($t:expr => $($mt1:tt ), + | $($mt2:tt ), +) => {
@@ -180,6 +369,9 @@ mod tests {
         };
 
         (@check3 $t:expr =>)=>{};
+        (@check3 $t:expr => (List(_))) => {assert!($t.can_cast_to(List(Box::new(Int8))));};
+        (@check3 $t:expr => (Struct(_))) => {assert!($t.can_cast_to(Struct(vec![Field::new("a", Int8)])));};
+        (@check3 $t:expr => (Decimal128 { .. })) => {assert!($t.can_cast_to(Decimal128 { precision: 10, scale: 2 }));};
         (@check3 $t:expr => ($data:tt(_))) => {assert!($t.can_cast_to($data(None)));};
         (@check3 $t:expr => $data:tt) => {assert!($t.can_cast_to($data));};
 
@@ -189,57 +381,138 @@ mod tests {
         };
 
         (@check4 $t:expr =>)=>{};
+        (@check4 $t:expr => (List(_))) => {assert!(!$t.can_cast_to(List(Box::new(Int8))));};
+        (@check4 $t:expr => (Struct(_))) => {assert!(!$t.can_cast_to(Struct(vec![Field::new("a", Int8)])));};
+        (@check4 $t:expr => (Decimal128 { .. })) => {assert!(!$t.can_cast_to(Decimal128 { precision: 10, scale: 2 }));};
         (@check4 $t:expr => ($data:tt(_))) => {assert!(!$t.can_cast_to($data(None)));};
         (@check4 $t:expr => $data:tt) => {assert!(!$t.can_cast_to($data));};
 }
 
     #[test]
     fn test_null_can_cast() {
-        test_cast!(Null => Null, String | Int8, Int16, Int32, Int64, Float32, Float64, Boolean, (Timestamp(_)));
+        test_cast!(Null => Null, String | Int8, Int16, Int32, Int64, Float32, Float64, Boolean, UInt8, UInt16, UInt32, UInt64, (Timestamp(_)), (List(_)), (Struct(_)), (Decimal128 { .. }));
     }
 
     #[test]
     fn test_int8_can_cast() {
-        test_cast!(Int8 => Int8, Int16, Int32, Int64, Float32, Float64, String | Null, Boolean, (Timestamp(_)));
+        test_cast!(Int8 => Int8, Int16, Int32, Int64, Float32, Float64, String, (Decimal128 { .. }) | Null, Boolean, UInt8, UInt16, UInt32, UInt64, (Timestamp(_)), (List(_)), (Struct(_)));
     }
 
     #[test]
     fn test_int16_can_cast() {
-        test_cast!(Int16 => Int16, Int32, Int64, Float32, Float64, String | Null, Int8, Boolean, (Timestamp(_)));
+        test_cast!(Int16 => Int16, Int32, Int64, Float32, Float64, String, (Decimal128 { .. }) | Null, Int8, Boolean, UInt8, UInt16, UInt32, UInt64, (Timestamp(_)), (List(_)), (Struct(_)));
     }
 
     #[test]
     fn test_int32_can_cast() {
-        test_cast!(Int32 => Int32, Int64, Float32, Float64, String | Null, Int8, Int16, Boolean, (Timestamp(_)));
+        test_cast!(Int32 => Int32, Int64, Float32, Float64, String, (Decimal128 { .. }) | Null, Int8, Int16, Boolean, UInt8, UInt16, UInt32, UInt64, (Timestamp(_)), (List(_)), (Struct(_)));
     }
 
     #[test]
     fn test_int64_can_cast() {
-        test_cast!(Int64 => Int64, Float32, Float64, String | Null, Int8, Int16, Int32, Boolean, (Timestamp(_)));
+        test_cast!(Int64 => Int64, Float32, Float64, String, (Decimal128 { .. }) | Null, Int8, Int16, Int32, Boolean, UInt8, UInt16, UInt32, UInt64, (Timestamp(_)), (List(_)), (Struct(_)));
+    }
+
+    #[test]
+    fn test_uint8_can_cast() {
+        test_cast!(UInt8 => UInt8, UInt16, UInt32, UInt64, Int16, Int32, Int64, Float32, Float64, String, (Decimal128 { .. }) | Null, Int8, Boolean, (Timestamp(_)), (List(_)), (Struct(_)));
+    }
+
+    #[test]
+    fn test_uint16_can_cast() {
+        test_cast!(UInt16 => UInt16, UInt32, UInt64, Int32, Int64, Float32, Float64, String, (Decimal128 { .. }) | Null, Int8, Int16, UInt8, Boolean, (Timestamp(_)), (List(_)), (Struct(_)));
+    }
+
+    #[test]
+    fn test_uint32_can_cast() {
+        test_cast!(UInt32 => UInt32, UInt64, Int64, Float32, Float64, String, (Decimal128 { .. }) | Null, Int8, Int16, Int32, UInt8, UInt16, Boolean, (Timestamp(_)), (List(_)), (Struct(_)));
+    }
+
+    #[test]
+    fn test_uint64_can_cast() {
+        test_cast!(UInt64 => UInt64, Float32, Float64, String, (Decimal128 { .. }) | Null, Int8, Int16, Int32, Int64, UInt8, UInt16, UInt32, Boolean, (Timestamp(_)), (List(_)), (Struct(_)));
     }
 
     #[test]
     fn test_float32_can_cast() {
-        test_cast!(Float32 => Float32, Float64, String | Null, Int8, Int16, Int32, Int64, Boolean, (Timestamp(_)));
+        test_cast!(Float32 => Float32, Float64, String | Null, Int8, Int16, Int32, Int64, Boolean, UInt8, UInt16, UInt32, UInt64, (Timestamp(_)), (List(_)), (Struct(_)), (Decimal128 { .. }));
     }
 
     #[test]
     fn test_float64_can_cast() {
-        test_cast!(Float64 => Float64, String | Null, Int8, Int16, Int32, Int64, Float32, Boolean, (Timestamp(_)));
+        test_cast!(Float64 => Float64, String | Null, Int8, Int16, Int32, Int64, Float32, Boolean, UInt8, UInt16, UInt32, UInt64, (Timestamp(_)), (List(_)), (Struct(_)), (Decimal128 { .. }));
     }
 
     #[test]
     fn test_boolean_can_cast() {
-        test_cast!(Boolean => Boolean, String | Null, Int8, Int16, Int32, Int64, Float32, Float64, (Timestamp(_)));
+        test_cast!(Boolean => Boolean, Int8, Int16, Int32, Int64, Float32, Float64, String, UInt8, UInt16, UInt32, UInt64 | Null, (Timestamp(_)), (List(_)), (Struct(_)), (Decimal128 { .. }));
     }
 
     #[test]
     fn test_timestamp_can_cast() {
-        test_cast!(Timestamp(None) => (Timestamp(_)), String | Null, Int8, Int16, Int32, Int64, Float32, Float64, Boolean);
+        test_cast!(Timestamp(None) => (Timestamp(_)), String | Null, Int8, Int16, Int32, Int64, Float32, Float64, Boolean, UInt8, UInt16, UInt32, UInt64, (List(_)), (Struct(_)), (Decimal128 { .. }));
+    }
+
+    #[test]
+    fn test_list_can_cast() {
+        test_cast!(List(Box::new(Int8)) => (List(_)) | Null, Int8, Int16, Int32, Int64, Float32, Float64, Boolean, UInt8, UInt16, UInt32, UInt64, String, (Timestamp(_)), (Struct(_)), (Decimal128 { .. }));
+        assert!(!List(Box::new(Int8)).can_cast_to(List(Box::new(Int16))));
+    }
+
+    #[test]
+    fn test_decimal_can_cast() {
+        test_cast!(Decimal128 { precision: 10, scale: 2 } => (Decimal128 { .. }), Float64, String | Null, Int8, Int16, Int32, Int64, Float32, Boolean, UInt8, UInt16, UInt32, UInt64, (Timestamp(_)), (List(_)), (Struct(_)));
+    }
+
+    #[test]
+    fn test_decimal_eq() {
+        let data_type = Decimal128 {
+            precision: 10,
+            scale: 2,
+        };
+        assert_eq!(data_type, data_type.clone());
+        assert_ne!(
+            data_type,
+            Decimal128 {
+                precision: 10,
+                scale: 3
+            }
+        );
+        assert_eq!(data_type.to_string(), "decimal128(10, 2)");
+    }
+
+    #[test]
+    fn test_list_eq() {
+        assert_eq!(List(Box::new(Int8)), List(Box::new(Int8)));
+        assert_ne!(List(Box::new(Int8)), List(Box::new(Int16)));
+        assert_ne!(List(Box::new(Int8)), Int8);
+        assert_eq!(
+            List(Box::new(List(Box::new(String)))).to_string(),
+            "list<list<string>>"
+        );
+    }
+
+    #[test]
+    fn test_struct_can_cast() {
+        test_cast!(Struct(vec![Field::new("a", Int8)]) => (Struct(_)) | Null, Int8, Int16, Int32, Int64, Float32, Float64, Boolean, UInt8, UInt16, UInt32, UInt64, String, (Timestamp(_)), (List(_)), (Decimal128 { .. }));
+        assert!(
+            !Struct(vec![Field::new("a", Int8)]).can_cast_to(Struct(vec![Field::new("b", Int8)]))
+        );
+    }
+
+    #[test]
+    fn test_struct_eq() {
+        let data_type = Struct(vec![
+            Field::new("a", Int8),
+            Field::new("b", List(Box::new(String))),
+        ]);
+        assert_eq!(data_type, data_type.clone());
+        assert_ne!(data_type, Struct(vec![Field::new("a", Int8)]));
+        assert_eq!(data_type.to_string(), "struct<a: int8, b: list<string>>");
     }
 
     #[test]
     fn test_string_can_cast() {
-        test_cast!(String =>  String | Null, Int8, Int16, Int32, Int64, Float32, Float64, Boolean, (Timestamp(_)));
+        test_cast!(String =>  String | Null, Int8, Int16, Int32, Int64, Float32, Float64, Boolean, UInt8, UInt16, UInt32, UInt64, (Timestamp(_)), (List(_)), (Struct(_)), (Decimal128 { .. }));
     }
 }
@@ -0,0 +1,463 @@
+use std::any::Any;
+use std::fmt::{self, Debug, Formatter};
+use std::sync::Arc;
+
+use anyhow::Result;
+use serde::de::DeserializeSeed;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::array::primitive_array::PrimitiveOptIter;
+use crate::array::{
+    Array, ArrayBuilder, ArrayRef, DataType, Decimal128Type, PrimitiveArray, PrimitiveBuilder,
+};
+
+/// The maximum number of digits of a 128-bit decimal.
+pub const DECIMAL128_MAX_PRECISION: u8 = 38;
+
+fn check_precision_and_scale(precision: u8, scale: u8) {
+    if precision == 0 || precision > DECIMAL128_MAX_PRECISION {
+        panic!(
+            "precision (is {}) should be in 1..={}",
+            precision, DECIMAL128_MAX_PRECISION
+        );
+    }
+    if scale > precision {
+        panic!(
+            "scale (is {}) should be <= precision (is {})",
+            scale, precision
+        );
+    }
+}
+
+#[inline]
+fn pow10(n: u8) -> i128 {
+    10i128.pow(n as u32)
+}
+
+/// Parses a decimal string like `-123.45` to a value scaled by `10^scale`.
+///
+/// Returns an error if the string has more fractional digits than `scale`, or more digits than
+/// `precision`.
+pub fn parse_decimal(s: &str, precision: u8, scale: u8) -> Result<i128> {
+    let err = || anyhow::anyhow!("invalid decimal128({}, {}): '{}'", precision, scale, s);
+
+    let (negative, digits) = match s.trim() {
+        s if s.starts_with('-') => (true, &s[1..]),
+        s if s.starts_with('+') => (false, &s[1..]),
+        s => (false, s),
+    };
+    let (int_part, frac_part) = match digits.find('.') {
+        Some(pos) => (&digits[..pos], &digits[pos + 1..]),
+        None => (digits, ""),
+    };
+    if (int_part.is_empty() && frac_part.is_empty())
+        || !int_part
+            .bytes()
+            .chain(frac_part.bytes())
+            .all(|c| c.is_ascii_digit())
+    {
+        return Err(err());
+    }
+
+    let int_part = int_part.trim_start_matches('0');
+    if frac_part.len() > scale as usize || int_part.len() + scale as usize > precision as usize {
+        return Err(err());
+    }
+
+    let mut value = 0i128;
+    for c in int_part.bytes().chain(frac_part.bytes()) {
+        value = value * 10 + (c - b'0') as i128;
+    }
+    value *= pow10(scale - frac_part.len() as u8);
+    Ok(if negative { -value } else { value })
+}
+
+/// Formats a value scaled by `10^scale` as a decimal string.
+pub fn format_decimal(value: i128, scale: u8) -> String {
+    let digits = value.unsigned_abs().to_string();
+    let sign = if value < 0 { "-" } else { "" };
+    if scale == 0 {
+        return format!("{}{}", sign, digits);
+    }
+    let digits = format!("{:0>width$}", digits, width = scale as usize + 1);
+    let (int_part, frac_part) = digits.split_at(digits.len() - scale as usize);
+    format!("{}{}.{}", sign, int_part, frac_part)
+}
+
+/// Changes the scale of a decimal value, returns `None` if it overflows or loses digits.
+pub fn rescale_decimal(value: i128, from_scale: u8, to_scale: u8) -> Option<i128> {
+    if to_scale >= from_scale {
+        value.checked_mul(10i128.checked_pow((to_scale - from_scale) as u32)?)
+    } else {
+        match 10i128.checked_pow((from_scale - to_scale) as u32) {
+            Some(factor) if value % factor == 0 => Some(value / factor),
+            None if value == 0 => Some(0),
+            _ => None,
+        }
+    }
+}
+
+/// Array builder for decimals.
+pub struct Decimal128Builder {
+    precision: u8,
+    scale: u8,
+    values: PrimitiveBuilder<Decimal128Type>,
+}
+
+impl ArrayBuilder for Decimal128Builder {
+    #[inline]
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    #[inline]
+    fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    #[inline]
+    fn finish_array(self) -> ArrayRef {
+        Arc::new(self.finish())
+    }
+}
+
+impl Decimal128Builder {
+    /// Creates a builder of the decimal type with the specified `precision` and `scale`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if precision is not in 1..=38 or scale > precision.
+    pub fn new(precision: u8, scale: u8) -> Self {
+        Self::with_capacity(precision, scale, 0)
+    }
+
+    pub fn with_capacity(precision: u8, scale: u8, size: usize) -> Self {
+        check_precision_and_scale(precision, scale);
+        Self {
+            precision,
+            scale,
+            values: PrimitiveBuilder::with_capacity(size),
+        }
+    }
+
+    /// Appends a value scaled by `10^scale`.
+    #[inline]
+    pub fn append(&mut self, value: i128) {
+        self.values.append(value);
+    }
+
+    #[inline]
+    pub fn append_null(&mut self) {
+        self.values.append_null();
+    }
+
+    #[inline]
+    pub fn append_opt(&mut self, value: Option<i128>) {
+        self.values.append_opt(value);
+    }
+
+    pub fn finish(self) -> Decimal128Array {
+        Decimal128Array {
+            precision: self.precision,
+            scale: self.scale,
+            values: self.values.finish(),
+        }
+    }
+}
+
+/// Array of decimals, the values are stored as `i128` scaled by `10^scale`.
+pub struct Decimal128Array {
+    precision: u8,
+    scale: u8,
+    values: PrimitiveArray<Decimal128Type>,
+}
+
+impl Debug for Decimal128Array {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut ls = f.debug_list();
+        for value in self.iter_opt() {
+            ls.entry(&value.map(|value| format_decimal(value, self.scale)));
+        }
+        ls.finish()
+    }
+}
+
+impl Array for Decimal128Array {
+    #[inline]
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    #[inline]
+    fn data_type(&self) -> DataType {
+        DataType::Decimal128 {
+            precision: self.precision,
+            scale: self.scale,
+        }
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    fn slice(&self, offset: usize, length: usize) -> ArrayRef {
+        Arc::new(Self {
+            precision: self.precision,
+            scale: self.scale,
+            values: self.values.slice_values(offset, length),
+        })
+    }
+
+    #[inline]
+    fn is_valid(&self, index: usize) -> bool {
+        self.values.is_valid(index)
+    }
+
+    #[inline]
+    fn null_count(&self) -> usize {
+        self.values.null_count()
+    }
+}
+
+impl PartialEq for Decimal128Array {
+    fn eq(&self, other: &Self) -> bool {
+        self.data_type() == other.data_type() && self.values == other.values
+    }
+}
+
+impl Decimal128Array {
+    /// Creates an array of the decimal type with the specified `precision` and `scale` from values
+    /// scaled by `10^scale`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if precision is not in 1..=38 or scale > precision.
+    pub fn new(precision: u8, scale: u8, values: PrimitiveArray<Decimal128Type>) -> Self {
+        check_precision_and_scale(precision, scale);
+        Self {
+            precision,
+            scale,
+            values,
+        }
+    }
+
+    #[inline]
+    pub fn new_scalar(precision: u8, scale: u8, len: usize, value: Option<i128>) -> Self {
+        Self::new(precision, scale, PrimitiveArray::new_scalar(len, value))
+    }
+
+    pub fn from_vec(precision: u8, scale: u8, values: Vec<i128>) -> Self {
+        Self::new(precision, scale, PrimitiveArray::from_vec(values))
+    }
+
+    pub fn from_opt_vec(precision: u8, scale: u8, values: Vec<Option<i128>>) -> Self {
+        Self::new(precision, scale, PrimitiveArray::from_opt_vec(values))
+    }
+
+    /// Creates an array from decimal strings like `123.45`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use yql_dataset::array::Decimal128Array;
+    ///
+    /// let array = Decimal128Array::from_str_vec(10, 2, vec!["123.45", "-0.5", "7"]).unwrap();
+    /// assert_eq!(array.iter_opt().collect::<Vec<_>>(), vec![Some(12345), Some(-50), Some(700)]);
+    ///
+    /// assert!(Decimal128Array::from_str_vec(10, 2, vec!["1.234"]).is_err());
+    /// ```
+    pub fn from_str_vec<A: AsRef<str>>(precision: u8, scale: u8, values: Vec<A>) -> Result<Self> {
+        check_precision_and_scale(precision, scale);
+        let mut builder = Decimal128Builder::with_capacity(precision, scale, values.len());
+        for value in values {
+            builder.append(parse_decimal(value.as_ref(), precision, scale)?);
+        }
+        Ok(builder.finish())
+    }
+
+    #[inline]
+    pub fn precision(&self) -> u8 {
+        self.precision
+    }
+
+    #[inline]
+    pub fn scale(&self) -> u8 {
+        self.scale
+    }
+
+    /// Returns the values scaled by `10^scale`.
+    #[inline]
+    pub fn values(&self) -> &PrimitiveArray<Decimal128Type> {
+        &self.values
+    }
+
+    #[inline]
+    pub fn is_scalar_array(&self) -> bool {
+        self.values.is_scalar_array()
+    }
+
+    #[inline]
+    pub fn to_scalar(&self) -> Option<Option<i128>> {
+        self.values.to_scalar()
+    }
+
+    #[inline]
+    pub fn value(&self, index: usize) -> i128 {
+        self.values.value(index)
+    }
+
+    #[inline]
+    pub fn value_opt(&self, index: usize) -> Option<i128> {
+        self.values.value_opt(index)
+    }
+
+    #[inline]
+    pub fn iter_opt(&self) -> PrimitiveOptIter<'_, Decimal128Type> {
+        self.values.iter_opt()
+    }
+
+    /// Concatenates two arrays, `other` is rescaled to the scale of this array.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the values of `other` can't be represented with the scale of this array.
+    pub fn concat(&self, other: &Self) -> Self {
+        if self.scale == other.scale {
+            return Self::new(
+                self.precision,
+                self.scale,
+                self.values.concat(&other.values),
+            );
+        }
+        let values = other
+            .iter_opt()
+            .map(|value| {
+                value.map(|value| {
+                    rescale_decimal(value, other.scale, self.scale)
+                        .expect("failed to rescale the decimal")
+                })
+            })
+            .collect::<Vec<_>>();
+        Self::new(
+            self.precision,
+            self.scale,
+            self.values.concat(&PrimitiveArray::from_opt_vec(values)),
+        )
+    }
+}
+
+impl Serialize for Decimal128Array {
+    #[inline]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.values.serialize(serializer)
+    }
+}
+
+/// Deserializes a decimal array with the specified precision and scale.
+pub(crate) struct DeDecimal128Array {
+    pub(crate) precision: u8,
+    pub(crate) scale: u8,
+}
+
+impl<'de> DeserializeSeed<'de> for DeDecimal128Array {
+    type Value = Decimal128Array;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let values = PrimitiveArray::<Decimal128Type>::deserialize(deserializer)?;
+        Ok(Decimal128Array::new(self.precision, self.scale, values))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::array::{ArrayExt, Scalar};
+
+    #[test]
+    fn test_parse_decimal() {
+        assert_eq!(parse_decimal("123.45", 5, 2).unwrap(), 12345);
+        assert_eq!(parse_decimal("-123.4", 5, 2).unwrap(), -12340);
+        assert_eq!(parse_decimal("+0.05", 5, 2).unwrap(), 5);
+        assert_eq!(parse_decimal(".5", 5, 2).unwrap(), 50);
+        assert_eq!(parse_decimal("007", 3, 2).unwrap(), 700);
+        assert_eq!(
+            parse_decimal(&"9".repeat(38), 38, 0).unwrap(),
+            10i128.pow(38) - 1
+        );
+
+        assert!(parse_decimal("1.234", 5, 2).is_err());
+        assert!(parse_decimal("1234.5", 5, 2).is_err());
+        assert!(parse_decimal("1e5", 5, 2).is_err());
+        assert!(parse_decimal("-", 5, 2).is_err());
+        assert!(parse_decimal("", 5, 2).is_err());
+    }
+
+    #[test]
+    fn test_format_decimal() {
+        assert_eq!(format_decimal(12345, 2), "123.45");
+        assert_eq!(format_decimal(-5, 2), "-0.05");
+        assert_eq!(format_decimal(0, 2), "0.00");
+        assert_eq!(format_decimal(-42, 0), "-42");
+        assert_eq!(format_decimal(i128::MIN, 0), i128::MIN.to_string());
+    }
+
+    #[test]
+    fn test_rescale_decimal() {
+        assert_eq!(rescale_decimal(12345, 2, 4), Some(1234500));
+        assert_eq!(rescale_decimal(12300, 2, 0), Some(123));
+        assert_eq!(rescale_decimal(12345, 2, 0), None);
+        assert_eq!(rescale_decimal(i128::MAX, 0, 1), None);
+        assert_eq!(rescale_decimal(1, 0, 76), None);
+        assert_eq!(rescale_decimal(0, 76, 0), Some(0));
+    }
+
+    #[test]
+    fn test_from_str_vec() {
+        let array = Decimal128Array::from_str_vec(10, 2, vec!["123.45", "-1", "0.1"]).unwrap();
+        assert_eq!(
+            array.data_type(),
+            DataType::Decimal128 {
+                precision: 10,
+                scale: 2
+            }
+        );
+        assert_eq!(
+            array.iter_opt().collect::<Vec<_>>(),
+            vec![Some(12345), Some(-100), Some(10)]
+        );
+        assert_eq!(
+            array.scalar_value(0),
+            Scalar::Decimal128 {
+                value: 12345,
+                precision: 10,
+                scale: 2
+            }
+        );
+        assert_eq!(array.scalar_value(1).to_string(), "-1.00");
+        assert_eq!(
+            format!("{:?}", array),
+            r#"[Some("123.45"), Some("-1.00"), Some("0.10")]"#
+        );
+    }
+
+    #[test]
+    fn test_slice() {
+        let array = Decimal128Array::from_opt_vec(10, 2, vec![Some(1), None, Some(3), Some(4)]);
+        let array = array.slice(1, 2);
+        let array = array.downcast_ref::<Decimal128Array>();
+        assert_eq!(array.scale(), 2);
+        assert_eq!(array.iter_opt().collect::<Vec<_>>(), vec![None, Some(3)]);
+    }
+}
@@ -0,0 +1,437 @@
+use std::any::Any;
+use std::fmt::{self, Debug, Formatter};
+use std::sync::Arc;
+
+use serde::de::{DeserializeSeed, Error, SeqAccess, Visitor};
+use serde::ser::SerializeTuple;
+use serde::{Deserializer, Serialize, Serializer};
+
+use crate::array::bitmap::{Bitmap, BitmapBuilder};
+use crate::array::{compute, Array, ArrayBuilder, ArrayRef, DataType, DeArray};
+
+/// Array builder for lists, the items are appended to the builder of the values.
+pub struct ListBuilder<B> {
+    values: B,
+    offsets: Vec<usize>,
+    bitmap: BitmapBuilder,
+}
+
+impl<B: ArrayBuilder + 'static> ArrayBuilder for ListBuilder<B> {
+    #[inline]
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    #[inline]
+    fn is_empty(&self) -> bool {
+        self.offsets.len() == 1
+    }
+
+    #[inline]
+    fn finish_array(self) -> ArrayRef {
+        Arc::new(self.finish())
+    }
+}
+
+impl<B: ArrayBuilder> ListBuilder<B> {
+    pub fn new(values: B) -> Self {
+        Self {
+            values,
+            offsets: vec![0],
+            bitmap: BitmapBuilder::default(),
+        }
+    }
+
+    /// Returns the builder of the values, which are the items of the current list.
+    #[inline]
+    pub fn values(&mut self) -> &mut B {
+        &mut self.values
+    }
+
+    /// Finishes the current list with the values appended since the previous list.
+    #[inline]
+    pub fn append(&mut self) {
+        self.offsets.push(self.values.len());
+    }
+
+    /// Appends a null list, it must not have any values.
+    #[inline]
+    pub fn append_null(&mut self) {
+        self.bitmap.set(self.offsets.len() - 1, false);
+        self.append();
+    }
+
+    pub fn finish(self) -> ListArray {
+        ListArray {
+            offset: 0,
+            length: self.offsets.len() - 1,
+            offsets: self.offsets.into(),
+            values: self.values.finish_array(),
+            bitmap: if !self.bitmap.is_empty() {
+                Some(self.bitmap.finish())
+            } else {
+                None
+            },
+        }
+    }
+}
+
+/// An array where each element is a variable-length list of values of the same type.
+pub struct ListArray {
+    offset: usize,
+    length: usize,
+    offsets: Arc<[usize]>,
+    values: ArrayRef,
+    bitmap: Option<Bitmap>,
+}
+
+impl Debug for ListArray {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut ls = f.debug_list();
+        for value in self.iter_opt() {
+            ls.entry(&value);
+        }
+        ls.finish()
+    }
+}
+
+impl Array for ListArray {
+    #[inline]
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    #[inline]
+    fn data_type(&self) -> DataType {
+        DataType::List(Box::new(self.values.data_type()))
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.length
+    }
+
+    fn slice(&self, offset: usize, length: usize) -> ArrayRef {
+        if offset > self.len() {
+            panic!(
+                "offset (is {}) should be <= len (is {})",
+                offset,
+                self.len()
+            );
+        }
+
+        if offset + length > self.len() {
+            panic!(
+                "offset+length (is {}) should be <= len (is {})",
+                offset + length,
+                self.len()
+            );
+        }
+
+        Arc::new(ListArray {
+            offset: self.offset + offset,
+            length,
+            offsets: self.offsets.clone(),
+            values: self.values.clone(),
+            bitmap: self.bitmap.as_ref().map(|bitmap| bitmap.offset(offset)),
+        })
+    }
+
+    fn is_valid(&self, index: usize) -> bool {
+        if index >= self.len() {
+            panic!("index (is {}) should be <= len (is {})", index, self.len());
+        }
+
+        match &self.bitmap {
+            Some(bitmap) => bitmap.is_valid(index),
+            None => true,
+        }
+    }
+
+    fn null_count(&self) -> usize {
+        (0..self.len()).filter(|index| self.is_null(*index)).count()
+    }
+}
+
+impl PartialEq for ListArray {
+    fn eq(&self, other: &Self) -> bool {
+        if self.len() != other.len() || self.data_type() != other.data_type() {
+            return false;
+        }
+        self.iter_opt()
+            .zip(other.iter_opt())
+            .all(|(a, b)| match (a, b) {
+                (Some(a), Some(b)) => *a == *b,
+                (None, None) => true,
+                _ => false,
+            })
+    }
+}
+
+impl ListArray {
+    /// Creates an array from the lengths of the lists (`None` for a null list) and their values.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the sum of the lengths is not the length of the values.
+    pub fn from_lengths(lengths: &[Option<usize>], values: ArrayRef) -> Self {
+        let mut offsets = Vec::with_capacity(lengths.len() + 1);
+        let mut bitmap = BitmapBuilder::default();
+        offsets.push(0);
+        for (index, length) in lengths.iter().enumerate() {
+            if length.is_none() {
+                bitmap.set(index, false);
+            }
+            offsets.push(offsets[index] + length.unwrap_or_default());
+        }
+        assert_eq!(offsets[lengths.len()], values.len());
+
+        ListArray {
+            offset: 0,
+            length: lengths.len(),
+            offsets: offsets.into(),
+            values,
+            bitmap: if !bitmap.is_empty() {
+                Some(bitmap.finish())
+            } else {
+                None
+            },
+        }
+    }
+
+    /// Returns the array of all the values, including the ones outside of this slice.
+    #[inline]
+    pub fn values(&self) -> &ArrayRef {
+        &self.values
+    }
+
+    /// Returns the values of the list at position `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if index > len.
+    #[inline]
+    pub fn value(&self, index: usize) -> ArrayRef {
+        if index >= self.len() {
+            panic!("index (is {}) should be <= len (is {})", index, self.len());
+        }
+
+        let start = self.offsets[self.offset + index];
+        let end = self.offsets[self.offset + index + 1];
+        self.values.slice(start, end - start)
+    }
+
+    #[inline]
+    pub fn value_opt(&self, index: usize) -> Option<ArrayRef> {
+        if self.is_valid(index) {
+            Some(self.value(index))
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    pub fn iter_opt(&self) -> impl Iterator<Item = Option<ArrayRef>> + '_ {
+        (0..self.len()).map(move |index| self.value_opt(index))
+    }
+
+    /// Returns a new array with the lists at the given indices, the values are copied. The list at
+    /// a null index is null.
+    pub(crate) fn take(&self, indices: &[Option<usize>]) -> ListArray {
+        let mut offsets = Vec::with_capacity(indices.len() + 1);
+        let mut value_indices = Vec::new();
+        let mut bitmap = BitmapBuilder::default();
+        offsets.push(0);
+        for (idx, index) in indices.iter().enumerate() {
+            match index {
+                Some(index) if self.is_valid(*index) => {
+                    let start = self.offsets[self.offset + index];
+                    let end = self.offsets[self.offset + index + 1];
+                    value_indices.extend((start..end).map(Some));
+                }
+                _ => bitmap.set(idx, false),
+            }
+            offsets.push(value_indices.len());
+        }
+
+        ListArray {
+            offset: 0,
+            length: indices.len(),
+            offsets: offsets.into(),
+            values: compute::take_opt(&*self.values, &value_indices),
+            bitmap: if !bitmap.is_empty() {
+                Some(bitmap.finish())
+            } else {
+                None
+            },
+        }
+    }
+
+    /// Returns the values covered by this slice.
+    fn sliced_values(&self) -> ArrayRef {
+        let start = self.offsets[self.offset];
+        let end = self.offsets[self.offset + self.length];
+        self.values.slice(start, end - start)
+    }
+}
+
+/// Serialized as the lengths of the lists (`None` for a null list) followed by the values.
+impl Serialize for ListArray {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let lengths = (0..self.len())
+            .map(|index| {
+                let length =
+                    self.offsets[self.offset + index + 1] - self.offsets[self.offset + index];
+                if self.is_valid(index) {
+                    Some(length)
+                } else {
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let mut tuple = serializer.serialize_tuple(2)?;
+        tuple.serialize_element(&lengths)?;
+        tuple.serialize_element(&*self.sliced_values())?;
+        tuple.end()
+    }
+}
+
+/// Deserializes a `ListArray`, the item type is required to deserialize the values.
+pub(crate) struct DeListArray(pub(crate) DataType);
+
+impl<'de> DeserializeSeed<'de> for DeListArray {
+    type Value = ListArray;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct ArrayVisitor(DataType);
+
+        impl<'de> Visitor<'de> for ArrayVisitor {
+            type Value = ListArray;
+
+            fn expecting(&self, f: &mut Formatter) -> fmt::Result {
+                f.write_str("ListArray")
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                let lengths = seq
+                    .next_element::<Vec<Option<usize>>>()?
+                    .ok_or_else(|| Error::custom("expect lengths"))?;
+                let values = seq
+                    .next_element_seed(DeArray(self.0))?
+                    .ok_or_else(|| Error::custom("expect values"))?;
+
+                if lengths.iter().flatten().sum::<usize>() != values.len() {
+                    return Err(Error::custom("the lengths don't match the values"));
+                }
+                Ok(ListArray::from_lengths(&lengths, values))
+            }
+        }
+
+        deserializer.deserialize_tuple(2, ArrayVisitor(self.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::array::{ArrayExt, Int32Array, Int32Builder, Scalar};
+
+    fn create_array() -> ListArray {
+        // [[0, 1, 2], null, [], [3, null]]
+        let mut builder = ListBuilder::new(Int32Builder::default());
+        for value in 0..3 {
+            builder.values().append(value);
+        }
+        builder.append();
+        builder.append_null();
+        builder.append();
+        builder.values().append(3);
+        builder.values().append_null();
+        builder.append();
+        builder.finish()
+    }
+
+    #[test]
+    fn test_array() {
+        let array = create_array();
+        assert_eq!(array.data_type(), DataType::List(Box::new(DataType::Int32)));
+        assert_eq!(array.len(), 4);
+        assert_eq!(array.null_count(), 1);
+        assert!(array.is_valid(0));
+        assert!(array.is_null(1));
+        assert_eq!(
+            array.value(0).downcast_ref::<Int32Array>(),
+            &Int32Array::from_vec(vec![0, 1, 2])
+        );
+        assert!(array.value_opt(1).is_none());
+        assert!(array.value(2).is_empty());
+        assert_eq!(
+            array.value(3).downcast_ref::<Int32Array>(),
+            &Int32Array::from_opt_vec(vec![Some(3), None])
+        );
+        assert_eq!(
+            array.scalar_value(0),
+            Scalar::List(vec![Scalar::Int32(0), Scalar::Int32(1), Scalar::Int32(2)])
+        );
+        assert_eq!(array.scalar_value(1), Scalar::Null);
+    }
+
+    #[test]
+    fn test_array_slice() {
+        let array = create_array();
+        let slice = array.slice(1, 3);
+        let slice = slice.downcast_ref::<ListArray>();
+        assert_eq!(slice.len(), 3);
+        assert!(slice.is_null(0));
+        assert!(slice.value(1).is_empty());
+        assert_eq!(
+            slice.value(2).downcast_ref::<Int32Array>(),
+            &Int32Array::from_opt_vec(vec![Some(3), None])
+        );
+
+        let slice = slice.slice(2, 1);
+        assert_eq!(slice.len(), 1);
+        assert_eq!(slice.null_count(), 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_array_slice_should_panic() {
+        let array = create_array();
+        array.slice(2, 3);
+    }
+
+    #[test]
+    fn test_nested_array() {
+        let mut builder = ListBuilder::new(ListBuilder::new(Int32Builder::default()));
+        builder.values().values().append(1);
+        builder.values().append();
+        builder.values().append_null();
+        builder.append();
+        let array = builder.finish();
+
+        assert_eq!(
+            array.data_type(),
+            DataType::List(Box::new(DataType::List(Box::new(DataType::Int32))))
+        );
+        let inner = array.value(0);
+        let inner = inner.downcast_ref::<ListArray>();
+        assert_eq!(inner.len(), 2);
+        assert!(inner.is_null(1));
+    }
+}
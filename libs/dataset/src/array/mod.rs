@@ -4,24 +4,37 @@ mod array_ext;
 mod bitmap;
 mod builder;
 mod data_type;
+mod decimal_array;
+mod list_array;
 mod null_array;
 mod primitive_array;
 mod scalar;
 mod string_array;
+mod struct_array;
 
 pub mod compute;
 
+pub(crate) use array::DeArray;
 pub use array::{Array, ArrayRef};
 pub use array_ext::ArrayExt;
 pub use builder::ArrayBuilder;
 pub use data_type::DataType;
+pub(crate) use decimal_array::DeDecimal128Array;
+pub use decimal_array::{
+    format_decimal, parse_decimal, rescale_decimal, Decimal128Array, Decimal128Builder,
+    DECIMAL128_MAX_PRECISION,
+};
+pub use list_array::{ListArray, ListBuilder};
 pub use null_array::NullArray;
+pub(crate) use primitive_array::parse_timestamp;
 pub use primitive_array::{
-    BooleanType, Float32Type, Float64Type, Int16Type, Int32Type, Int64Type, Int8Type,
-    PrimitiveArray, PrimitiveBuilder, PrimitiveType, TimestampType,
+    BooleanType, Decimal128Type, Float32Type, Float64Type, Int16Type, Int32Type, Int64Type,
+    Int8Type, PrimitiveArray, PrimitiveBuilder, PrimitiveType, TimestampType, UInt16Type,
+    UInt32Type, UInt64Type, UInt8Type,
 };
 pub use scalar::Scalar;
 pub use string_array::{StringArray, StringBuilder};
+pub use struct_array::StructArray;
 
 macro_rules! impl_primitive_arrays {
     ($(($ty:ident, $native_ty:ty)),*) => {
@@ -36,6 +49,10 @@ impl_primitive_arrays!(
     (Int16Array, Int16Type),
     (Int32Array, Int32Type),
     (Int64Array, Int64Type),
+    (UInt8Array, UInt8Type),
+    (UInt16Array, UInt16Type),
+    (UInt32Array, UInt32Type),
+    (UInt64Array, UInt64Type),
     (Float32Array, Float32Type),
     (Float64Array, Float64Type),
     (BooleanArray, BooleanType),
@@ -55,6 +72,10 @@ impl_primitive_builder!(
     (Int16Builder, Int16Type),
     (Int32Builder, Int32Type),
     (Int64Builder, Int64Type),
+    (UInt8Builder, UInt8Type),
+    (UInt16Builder, UInt16Type),
+    (UInt32Builder, UInt32Type),
+    (UInt64Builder, UInt64Type),
     (Float32Builder, Float32Type),
     (Float64Builder, Float64Type),
     (BooleanBuilder, BooleanType),
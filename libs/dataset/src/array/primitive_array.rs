@@ -5,6 +5,8 @@ use std::marker::PhantomData;
 use std::sync::Arc;
 
 use bytes::{BufMut, Bytes, BytesMut};
+use chrono::{DateTime, NaiveDateTime, TimeZone};
+use chrono_tz::Tz;
 use serde::de::{DeserializeOwned, SeqAccess, Visitor};
 use serde::ser::SerializeSeq;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
@@ -49,17 +51,29 @@ macro_rules! impl_primitive_types {
     };
 }
 
-impl_native_types!(i8, i16, i32, i64, f32, f64, bool);
+impl_native_types!(i8, i16, i32, i64, u8, u16, u32, u64, i128, f32, f64, bool);
 
 impl_primitive_types!(
     (Int8Type, i8, DataType::Int8),
     (Int16Type, i16, DataType::Int16),
     (Int32Type, i32, DataType::Int32),
     (Int64Type, i64, DataType::Int64),
+    (UInt8Type, u8, DataType::UInt8),
+    (UInt16Type, u16, DataType::UInt16),
+    (UInt32Type, u32, DataType::UInt32),
+    (UInt64Type, u64, DataType::UInt64),
     (Float32Type, f32, DataType::Float32),
     (Float64Type, f64, DataType::Float64),
     (BooleanType, bool, DataType::Boolean),
-    (TimestampType, i64, DataType::Timestamp(None))
+    (TimestampType, i64, DataType::Timestamp(None)),
+    (
+        Decimal128Type,
+        i128,
+        DataType::Decimal128 {
+            precision: 38,
+            scale: 0
+        }
+    )
 );
 
 /// Array builder for fixed-width primitive types.
@@ -94,6 +108,11 @@ impl<T: PrimitiveType> ArrayBuilder for PrimitiveBuilder<T> {
     fn is_empty(&self) -> bool {
         self.data.is_empty()
     }
+
+    #[inline]
+    fn finish_array(self) -> ArrayRef {
+        Arc::new(self.finish())
+    }
 }
 
 impl<T: PrimitiveType> PrimitiveBuilder<T> {
@@ -143,6 +162,35 @@ impl<T: PrimitiveType> PrimitiveBuilder<T> {
     }
 }
 
+impl PrimitiveBuilder<TimestampType> {
+    /// Appends a RFC 3339 timestamp string, or a null if it cannot be parsed.
+    ///
+    /// Strings without an offset such as `2021-06-01 01:49:00` are in UTC.
+    #[inline]
+    pub fn append_str(&mut self, s: &str) {
+        self.append_str_with_tz(s, None);
+    }
+
+    /// Like [`append_str`](Self::append_str), but strings without an offset are in the timezone
+    /// `tz`.
+    pub fn append_str_with_tz(&mut self, s: &str, tz: Option<Tz>) {
+        self.append_opt(parse_timestamp(s, tz.unwrap_or(chrono_tz::UTC)));
+    }
+}
+
+/// Parses a timestamp string as milliseconds since the unix epoch.
+pub(crate) fn parse_timestamp(s: &str, tz: Tz) -> Option<i64> {
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(s) {
+        return Some(datetime.timestamp_millis());
+    }
+    let datetime = NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S%.f")
+        .or_else(|_| NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S%.f"))
+        .ok()?;
+    tz.from_local_datetime(&datetime)
+        .single()
+        .map(|datetime| datetime.timestamp_millis())
+}
+
 /// Array whose elements are of primitive types.
 pub enum PrimitiveArray<T: PrimitiveType> {
     Array {
@@ -186,33 +234,7 @@ impl<T: PrimitiveType> Array for PrimitiveArray<T> {
     }
 
     fn slice(&self, offset: usize, length: usize) -> ArrayRef {
-        if offset > self.len() {
-            panic!(
-                "offset (is {}) should be <= len (is {})",
-                offset,
-                self.len()
-            );
-        }
-
-        if offset + length > self.len() {
-            panic!(
-                "offset+length (is {}) should be <= len (is {})",
-                offset + length,
-                self.len()
-            );
-        }
-
-        match self {
-            PrimitiveArray::Array { data, bitmap, .. } => Arc::new(Self::Array {
-                data: data.slice((offset * T::byte_width())..(offset + length) * T::byte_width()),
-                bitmap: bitmap.as_ref().map(|bitmap| bitmap.offset(offset)),
-                _mark: PhantomData,
-            }),
-            PrimitiveArray::Scalar { value, .. } => Arc::new(Self::Scalar {
-                len: length,
-                value: *value,
-            }),
-        }
+        Arc::new(self.slice_values(offset, length))
     }
 
     #[inline]
@@ -269,6 +291,17 @@ impl<A: PrimitiveType> FromIterator<<A as PrimitiveType>::Native> for PrimitiveA
     }
 }
 
+impl<'a> FromIterator<&'a str> for PrimitiveArray<TimestampType> {
+    fn from_iter<I: IntoIterator<Item = &'a str>>(iter: I) -> Self {
+        let iter = iter.into_iter();
+        let mut builder = PrimitiveBuilder::with_capacity(iter.size_hint().0);
+        for s in iter {
+            builder.append_str(s);
+        }
+        builder.finish()
+    }
+}
+
 impl<T: PrimitiveType> PrimitiveArray<T> {
     #[inline]
     pub fn new_scalar(len: usize, value: Option<T::Native>) -> Self {
@@ -304,6 +337,37 @@ impl<T: PrimitiveType> PrimitiveArray<T> {
         }
     }
 
+    /// Returns a zero-copy slice of this array, see [`Array::slice`].
+    pub fn slice_values(&self, offset: usize, length: usize) -> Self {
+        if offset > self.len() {
+            panic!(
+                "offset (is {}) should be <= len (is {})",
+                offset,
+                self.len()
+            );
+        }
+
+        if offset + length > self.len() {
+            panic!(
+                "offset+length (is {}) should be <= len (is {})",
+                offset + length,
+                self.len()
+            );
+        }
+
+        match self {
+            PrimitiveArray::Array { data, bitmap, .. } => Self::Array {
+                data: data.slice((offset * T::byte_width())..(offset + length) * T::byte_width()),
+                bitmap: bitmap.as_ref().map(|bitmap| bitmap.offset(offset)),
+                _mark: PhantomData,
+            },
+            PrimitiveArray::Scalar { value, .. } => Self::Scalar {
+                len: length,
+                value: *value,
+            },
+        }
+    }
+
     /// Create an empty array.
     #[inline]
     pub fn empty() -> Self {
@@ -336,8 +400,9 @@ impl<T: PrimitiveType> PrimitiveArray<T> {
     #[inline]
     fn interval_value(&self, index: usize) -> T::Native {
         match self {
+            // the buffer is not guaranteed to be aligned for 128-bit values
             PrimitiveArray::Array { data, .. } => unsafe {
-                std::slice::from_raw_parts(data.as_ptr() as *const T::Native, self.len())[index]
+                std::ptr::read_unaligned((data.as_ptr() as *const T::Native).add(index))
             },
             PrimitiveArray::Scalar { value, .. } => value.unwrap_or_default(),
         }
@@ -506,7 +571,9 @@ impl<'de, T: PrimitiveType> Deserialize<'de> for PrimitiveArray<T> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::array::{ArrayExt, Int32Array, Int32Builder, Scalar};
+    use crate::array::{
+        ArrayExt, Int32Array, Int32Builder, Scalar, TimestampArray, TimestampBuilder,
+    };
 
     fn create_array() -> ArrayRef {
         let mut builder = Int32Builder::default();
@@ -980,4 +1047,39 @@ mod tests {
             assert_eq!(array.value_opt(x), Some(3));
         }
     }
+
+    #[test]
+    fn test_timestamp_append_str() {
+        let mut builder = TimestampBuilder::default();
+        builder.append_str("2021-06-01T01:49:00Z");
+        builder.append_str("2021-06-01T09:49:00.123+08:00");
+        builder.append_str("2021-06-01 01:49:00");
+        builder.append_str("2021-06-01T01:49:00");
+        builder.append_str("2021-06-01");
+        builder.append_str("abc");
+        builder.append_str_with_tz("2021-06-01 09:49:00", Some(chrono_tz::Asia::Shanghai));
+        builder.append_str_with_tz("2021-06-01T01:49:00Z", Some(chrono_tz::Asia::Shanghai));
+        let array = builder.finish();
+        assert_eq!(
+            array.iter_opt().collect::<Vec<_>>(),
+            vec![
+                Some(1622512140000),
+                Some(1622512140123),
+                Some(1622512140000),
+                Some(1622512140000),
+                None,
+                None,
+                Some(1622512140000),
+                Some(1622512140000),
+            ]
+        );
+
+        let array = vec!["2021-06-01T01:49:00Z", "", "1970-01-01 00:00:01"]
+            .into_iter()
+            .collect::<TimestampArray>();
+        assert_eq!(
+            array.iter_opt().collect::<Vec<_>>(),
+            vec![Some(1622512140000), None, Some(1000)]
+        );
+    }
 }
@@ -4,7 +4,12 @@ use std::sync::Arc;
 use chrono::TimeZone;
 use serde::{Deserialize, Serialize};
 
-use crate::array::DataType;
+use crate::array::{
+    compute, format_decimal, ArrayRef, BooleanArray, DataType, Decimal128Array, Float32Array,
+    Float64Array, Int16Array, Int32Array, Int64Array, Int8Array, NullArray, StringArray,
+    TimestampArray, UInt16Array, UInt32Array, UInt64Array, UInt8Array,
+};
+use crate::dataset::{DataSet, Field, Schema};
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Scalar {
@@ -13,11 +18,23 @@ pub enum Scalar {
     Int16(i16),
     Int32(i32),
     Int64(i64),
+    UInt8(u8),
+    UInt16(u16),
+    UInt32(u32),
+    UInt64(u64),
     Float32(f32),
     Float64(f64),
     Boolean(bool),
     Timestamp(i64),
+    /// A decimal value scaled by `10^scale`.
+    Decimal128 {
+        value: i128,
+        precision: u8,
+        scale: u8,
+    },
     String(Arc<str>),
+    List(Vec<Scalar>),
+    Struct(Vec<(String, Scalar)>),
 }
 
 impl Default for Scalar {
@@ -44,6 +61,10 @@ impl_from_numerics!(
     (i16, Int16),
     (i32, Int32),
     (i64, Int64),
+    (u8, UInt8),
+    (u16, UInt16),
+    (u32, UInt32),
+    (u64, UInt64),
     (f32, Float32),
     (f64, Float64)
 );
@@ -81,11 +102,35 @@ impl Scalar {
             Scalar::Int16(_) => DataType::Int16,
             Scalar::Int32(_) => DataType::Int32,
             Scalar::Int64(_) => DataType::Int64,
+            Scalar::UInt8(_) => DataType::UInt8,
+            Scalar::UInt16(_) => DataType::UInt16,
+            Scalar::UInt32(_) => DataType::UInt32,
+            Scalar::UInt64(_) => DataType::UInt64,
             Scalar::Float32(_) => DataType::Float32,
             Scalar::Float64(_) => DataType::Float64,
             Scalar::Boolean(_) => DataType::Boolean,
             Scalar::Timestamp(_) => DataType::Timestamp(None),
+            Scalar::Decimal128 {
+                precision, scale, ..
+            } => DataType::Decimal128 {
+                precision: *precision,
+                scale: *scale,
+            },
             Scalar::String(_) => DataType::String,
+            // the item type of an empty list or a list of nulls can't be known
+            Scalar::List(values) => DataType::List(Box::new(
+                values
+                    .iter()
+                    .find(|value| !value.is_null())
+                    .map(Scalar::data_type)
+                    .unwrap_or(DataType::Null),
+            )),
+            Scalar::Struct(values) => DataType::Struct(
+                values
+                    .iter()
+                    .map(|(name, value)| Field::new(name, value.data_type()))
+                    .collect(),
+            ),
         }
     }
 
@@ -98,6 +143,51 @@ impl Scalar {
     pub fn is_null(&self) -> bool {
         matches!(self, Scalar::Null)
     }
+
+    /// Returns an array of `len` values equal to this scalar, which is a scalar array if the type
+    /// has one.
+    ///
+    /// Panics if the values of a list don't have the same type.
+    pub fn to_array(&self, len: usize) -> ArrayRef {
+        match self {
+            Scalar::Null => Arc::new(NullArray::new(len)),
+            Scalar::Int8(n) => Arc::new(Int8Array::new_scalar(len, Some(*n))),
+            Scalar::Int16(n) => Arc::new(Int16Array::new_scalar(len, Some(*n))),
+            Scalar::Int32(n) => Arc::new(Int32Array::new_scalar(len, Some(*n))),
+            Scalar::Int64(n) => Arc::new(Int64Array::new_scalar(len, Some(*n))),
+            Scalar::UInt8(n) => Arc::new(UInt8Array::new_scalar(len, Some(*n))),
+            Scalar::UInt16(n) => Arc::new(UInt16Array::new_scalar(len, Some(*n))),
+            Scalar::UInt32(n) => Arc::new(UInt32Array::new_scalar(len, Some(*n))),
+            Scalar::UInt64(n) => Arc::new(UInt64Array::new_scalar(len, Some(*n))),
+            Scalar::Float32(n) => Arc::new(Float32Array::new_scalar(len, Some(*n))),
+            Scalar::Float64(n) => Arc::new(Float64Array::new_scalar(len, Some(*n))),
+            Scalar::Boolean(n) => Arc::new(BooleanArray::new_scalar(len, Some(*n))),
+            Scalar::Timestamp(n) => Arc::new(TimestampArray::new_scalar(len, Some(*n))),
+            Scalar::Decimal128 {
+                value,
+                precision,
+                scale,
+            } => Arc::new(Decimal128Array::new_scalar(
+                *precision,
+                *scale,
+                len,
+                Some(*value),
+            )),
+            Scalar::String(s) => Arc::new(StringArray::new_scalar(len, Some(s.clone()))),
+            Scalar::List(_) | Scalar::Struct(_) => {
+                // nested arrays have no scalar representation, so the value is repeated
+                let schema = Schema::try_new(vec![Field::new("value", self.data_type())])
+                    .expect("a single field");
+                let dataset = DataSet::try_from_rows(Arc::new(schema), vec![vec![self.clone()]])
+                    .expect("the values have the types of the scalar");
+                compute::take(
+                    &*dataset.columns()[0],
+                    &Int64Array::new_scalar(len, Some(0)),
+                )
+                .expect("the index is in bounds")
+            }
+        }
+    }
 }
 
 impl Display for Scalar {
@@ -108,11 +198,86 @@ impl Display for Scalar {
             Scalar::Int16(n) => write!(f, "{}", n),
             Scalar::Int32(n) => write!(f, "{}", n),
             Scalar::Int64(n) => write!(f, "{}", n),
+            Scalar::UInt8(n) => write!(f, "{}", n),
+            Scalar::UInt16(n) => write!(f, "{}", n),
+            Scalar::UInt32(n) => write!(f, "{}", n),
+            Scalar::UInt64(n) => write!(f, "{}", n),
             Scalar::Float32(n) => write!(f, "{}", n),
             Scalar::Float64(n) => write!(f, "{}", n),
             Scalar::Boolean(n) => write!(f, "{}", n),
             Scalar::Timestamp(n) => write!(f, "{}", chrono::Local.timestamp_millis(*n)),
+            Scalar::Decimal128 { value, scale, .. } => f.write_str(&format_decimal(*value, *scale)),
             Scalar::String(n) => f.write_str(n),
+            Scalar::List(values) => {
+                f.write_str("[")?;
+                for (idx, value) in values.iter().enumerate() {
+                    if idx > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{}", value)?;
+                }
+                f.write_str("]")
+            }
+            Scalar::Struct(values) => {
+                f.write_str("{")?;
+                for (idx, (name, value)) in values.iter().enumerate() {
+                    if idx > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{}: {}", name, value)?;
+                }
+                f.write_str("}")
+            }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::array::ArrayExt;
+
+    #[test]
+    fn test_to_array() {
+        let scalars = vec![
+            Scalar::Null,
+            Scalar::Int8(1),
+            Scalar::Int16(2),
+            Scalar::Int32(3),
+            Scalar::Int64(4),
+            Scalar::UInt8(5),
+            Scalar::UInt16(6),
+            Scalar::UInt32(7),
+            Scalar::UInt64(8),
+            Scalar::Float32(1.5),
+            Scalar::Float64(2.5),
+            Scalar::Boolean(true),
+            Scalar::Timestamp(1622512140000),
+            Scalar::Decimal128 {
+                value: 12345,
+                precision: 10,
+                scale: 2,
+            },
+            Scalar::String("abc".into()),
+            Scalar::List(vec![Scalar::Int32(1), Scalar::Null]),
+            Scalar::Struct(vec![("a".to_string(), Scalar::Int64(1))]),
+        ];
+        for scalar in scalars {
+            let array = scalar.to_array(3);
+            assert_eq!(array.data_type(), scalar.data_type(), "{:?}", scalar);
+            assert_eq!(array.len(), 3);
+            for row in 0..3 {
+                assert_eq!(array.scalar_value(row), scalar);
+            }
+        }
+
+        assert_eq!(
+            Scalar::Int64(1)
+                .to_array(2)
+                .downcast_ref::<Int64Array>()
+                .to_scalar(),
+            Some(Some(1))
+        );
+        assert!(Scalar::String("abc".into()).to_array(0).is_empty());
+    }
+}
@@ -34,6 +34,11 @@ impl ArrayBuilder for StringBuilder {
     fn is_empty(&self) -> bool {
         self.index_buf.is_empty()
     }
+
+    #[inline]
+    fn finish_array(self) -> ArrayRef {
+        Arc::new(self.finish())
+    }
 }
 
 impl StringBuilder {
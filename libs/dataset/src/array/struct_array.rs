@@ -0,0 +1,325 @@
+use std::any::Any;
+use std::fmt::{self, Debug, Formatter};
+use std::sync::Arc;
+
+use anyhow::Result;
+use serde::de::{DeserializeSeed, Error, SeqAccess, Visitor};
+use serde::ser::SerializeTuple;
+use serde::{Deserializer, Serialize, Serializer};
+
+use crate::array::bitmap::{Bitmap, BitmapBuilder};
+use crate::array::{Array, ArrayExt, ArrayRef, DataType, DeArray};
+use crate::dataset::Field;
+
+/// An array of structs, each field of the struct is stored in a child array.
+pub struct StructArray {
+    fields: Vec<Field>,
+    columns: Vec<ArrayRef>,
+    length: usize,
+    bitmap: Option<Bitmap>,
+}
+
+impl Debug for StructArray {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut ls = f.debug_list();
+        for index in 0..self.len() {
+            ls.entry(&self.scalar_value(index));
+        }
+        ls.finish()
+    }
+}
+
+impl Array for StructArray {
+    #[inline]
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    #[inline]
+    fn data_type(&self) -> DataType {
+        DataType::Struct(self.fields.clone())
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.length
+    }
+
+    fn slice(&self, offset: usize, length: usize) -> ArrayRef {
+        if offset > self.len() {
+            panic!(
+                "offset (is {}) should be <= len (is {})",
+                offset,
+                self.len()
+            );
+        }
+
+        if offset + length > self.len() {
+            panic!(
+                "offset+length (is {}) should be <= len (is {})",
+                offset + length,
+                self.len()
+            );
+        }
+
+        Arc::new(StructArray {
+            fields: self.fields.clone(),
+            columns: self
+                .columns
+                .iter()
+                .map(|column| column.slice(offset, length))
+                .collect(),
+            length,
+            bitmap: self.bitmap.as_ref().map(|bitmap| bitmap.offset(offset)),
+        })
+    }
+
+    fn is_valid(&self, index: usize) -> bool {
+        if index >= self.len() {
+            panic!("index (is {}) should be <= len (is {})", index, self.len());
+        }
+
+        match &self.bitmap {
+            Some(bitmap) => bitmap.is_valid(index),
+            None => true,
+        }
+    }
+
+    fn null_count(&self) -> usize {
+        (0..self.len()).filter(|index| self.is_null(*index)).count()
+    }
+}
+
+impl PartialEq for StructArray {
+    fn eq(&self, other: &Self) -> bool {
+        if self.len() != other.len() || self.fields != other.fields {
+            return false;
+        }
+        (0..self.len()).all(|index| self.scalar_value(index) == other.scalar_value(index))
+    }
+}
+
+impl StructArray {
+    /// Creates an array from the arrays of the fields, `validity` marks the non-null structs.
+    pub fn try_new(
+        fields: Vec<Field>,
+        columns: Vec<ArrayRef>,
+        validity: Option<&[bool]>,
+    ) -> Result<Self> {
+        anyhow::ensure!(
+            fields.len() == columns.len() && !columns.is_empty(),
+            "invalid struct array: expect {} fields, actual {} fields.",
+            fields.len(),
+            columns.len()
+        );
+
+        let length = columns[0].len();
+        for (field, column) in fields.iter().zip(&columns) {
+            anyhow::ensure!(
+                column.data_type() == field.data_type,
+                "invalid struct array: expect field '{}' datatype is {}, actual datatype is {}.",
+                field.name,
+                field.data_type,
+                column.data_type()
+            );
+            anyhow::ensure!(
+                column.len() == length,
+                "invalid struct array: expect field '{}' length is {}, actual length is {}.",
+                field.name,
+                length,
+                column.len()
+            );
+        }
+
+        let bitmap = match validity {
+            Some(validity) => {
+                anyhow::ensure!(
+                    validity.len() == length,
+                    "invalid struct array: expect {} validity flags, actual {} flags.",
+                    length,
+                    validity.len()
+                );
+                let mut bitmap = BitmapBuilder::default();
+                for (index, is_valid) in validity.iter().enumerate() {
+                    if !is_valid {
+                        bitmap.set(index, false);
+                    }
+                }
+                if !bitmap.is_empty() {
+                    Some(bitmap.finish())
+                } else {
+                    None
+                }
+            }
+            None => None,
+        };
+
+        Ok(Self {
+            fields,
+            columns,
+            length,
+            bitmap,
+        })
+    }
+
+    #[inline]
+    pub fn fields(&self) -> &[Field] {
+        &self.fields
+    }
+
+    /// Returns the array of the field with the specified name.
+    ///
+    /// The values of the field are not null where the struct is null.
+    pub fn column(&self, name: &str) -> Option<&ArrayRef> {
+        self.fields
+            .iter()
+            .position(|field| field.name == name)
+            .map(|index| &self.columns[index])
+    }
+
+    #[inline]
+    pub fn columns(&self) -> &[ArrayRef] {
+        &self.columns
+    }
+}
+
+/// Serialized as the validity of the structs followed by the arrays of the fields.
+impl Serialize for StructArray {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let validity = (0..self.len())
+            .map(|index| self.is_valid(index))
+            .collect::<Vec<_>>();
+
+        let mut tuple = serializer.serialize_tuple(1 + self.columns.len())?;
+        tuple.serialize_element(&validity)?;
+        for column in &self.columns {
+            tuple.serialize_element(&**column)?;
+        }
+        tuple.end()
+    }
+}
+
+/// Deserializes a `StructArray`, the fields are required to deserialize the arrays.
+pub(crate) struct DeStructArray(pub(crate) Vec<Field>);
+
+impl<'de> DeserializeSeed<'de> for DeStructArray {
+    type Value = StructArray;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct ArrayVisitor(Vec<Field>);
+
+        impl<'de> Visitor<'de> for ArrayVisitor {
+            type Value = StructArray;
+
+            fn expecting(&self, f: &mut Formatter) -> fmt::Result {
+                f.write_str("StructArray")
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                let validity = seq
+                    .next_element::<Vec<bool>>()?
+                    .ok_or_else(|| Error::custom("expect validity"))?;
+                let mut columns = Vec::with_capacity(self.0.len());
+                for field in &self.0 {
+                    columns.push(
+                        seq.next_element_seed(DeArray(field.data_type.clone()))?
+                            .ok_or_else(|| Error::custom("expect array"))?,
+                    );
+                }
+                StructArray::try_new(self.0, columns, Some(&validity)).map_err(Error::custom)
+            }
+        }
+
+        let len = self.0.len();
+        deserializer.deserialize_tuple(1 + len, ArrayVisitor(self.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::array::{Int32Array, Scalar, StringArray};
+
+    fn create_array() -> StructArray {
+        // [{a: 1, b: x}, null, {a: null, b: z}]
+        StructArray::try_new(
+            vec![
+                Field::new("a", DataType::Int32),
+                Field::new("b", DataType::String),
+            ],
+            vec![
+                Arc::new(Int32Array::from_opt_vec(vec![Some(1), Some(2), None])),
+                Arc::new(StringArray::from_vec(vec!["x", "y", "z"])),
+            ],
+            Some(&[true, false, true]),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_array() {
+        let array = create_array();
+        assert_eq!(
+            array.data_type(),
+            DataType::Struct(vec![
+                Field::new("a", DataType::Int32),
+                Field::new("b", DataType::String),
+            ])
+        );
+        assert_eq!(array.len(), 3);
+        assert_eq!(array.null_count(), 1);
+        assert_eq!(
+            array.column("a").unwrap().downcast_ref::<Int32Array>(),
+            &Int32Array::from_opt_vec(vec![Some(1), Some(2), None])
+        );
+        assert!(array.column("c").is_none());
+        assert_eq!(
+            array.scalar_value(0),
+            Scalar::Struct(vec![
+                ("a".to_string(), Scalar::Int32(1)),
+                ("b".to_string(), Scalar::String("x".into())),
+            ])
+        );
+        assert_eq!(array.scalar_value(1), Scalar::Null);
+    }
+
+    #[test]
+    fn test_array_slice() {
+        let array = create_array();
+        let slice = array.slice(1, 2);
+        assert_eq!(slice.len(), 2);
+        assert!(slice.is_null(0));
+        assert_eq!(
+            slice.scalar_value(1),
+            Scalar::Struct(vec![
+                ("a".to_string(), Scalar::Null),
+                ("b".to_string(), Scalar::String("z".into())),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_try_new_error() {
+        assert!(StructArray::try_new(
+            vec![Field::new("a", DataType::Int32)],
+            vec![Arc::new(StringArray::from_vec(vec!["x"]))],
+            None,
+        )
+        .is_err());
+        assert!(StructArray::try_new(
+            vec![Field::new("a", DataType::Int32)],
+            vec![Arc::new(Int32Array::from_vec(vec![1]))],
+            Some(&[true, false]),
+        )
+        .is_err());
+    }
+}
@@ -12,16 +12,21 @@ use once_cell::sync::Lazy;
 use regex::{Regex, RegexBuilder};
 
 use crate::array::{
-    ArrayRef, BooleanBuilder, BooleanType, DataType, Float32Builder, Float32Type, Float64Builder,
-    Float64Type, Int16Builder, Int16Type, Int32Builder, Int32Type, Int64Builder, Int64Type,
-    Int8Builder, Int8Type, NullArray, PrimitiveBuilder, PrimitiveType, StringBuilder,
-    TimestampBuilder, TimestampType,
+    parse_decimal, ArrayRef, BooleanBuilder, BooleanType, DataType, Decimal128Builder,
+    Float32Builder, Float32Type, Float64Builder, Float64Type, Int16Builder, Int16Type,
+    Int32Builder, Int32Type, Int64Builder, Int64Type, Int8Builder, Int8Type, NullArray,
+    PrimitiveBuilder, PrimitiveType, StringBuilder, TimestampBuilder, TimestampType, UInt16Builder,
+    UInt16Type, UInt32Builder, UInt32Type, UInt64Builder, UInt64Type, UInt8Builder, UInt8Type,
 };
 use crate::dataset::{DataSet, Field, Schema, SchemaRef};
 
 pub struct CsvOptions {
     pub delimiter: u8,
     pub has_header: bool,
+    /// The maximum number of rows scanned to infer the schema, all the rows if `None`.
+    pub infer_sample_size: Option<usize>,
+    /// The values that are read as null, e.g. `NA` or an empty string.
+    pub null_values: Vec<String>,
 }
 
 impl Default for CsvOptions {
@@ -29,11 +34,27 @@ impl Default for CsvOptions {
         Self {
             delimiter: b',',
             has_header: false,
+            infer_sample_size: None,
+            null_values: Vec::new(),
         }
     }
 }
 
 impl CsvOptions {
+    pub fn with_infer_sample_size(self, infer_sample_size: usize) -> Self {
+        Self {
+            infer_sample_size: Some(infer_sample_size),
+            ..self
+        }
+    }
+
+    pub fn with_null_values(self, null_values: Vec<String>) -> Self {
+        Self {
+            null_values,
+            ..self
+        }
+    }
+
     pub fn open_path(&self, schema: SchemaRef, path: impl AsRef<Path>) -> Result<CsvReader<File>> {
         Ok(self.open(schema, File::open(path)?))
     }
@@ -43,7 +64,12 @@ impl CsvOptions {
             .delimiter(self.delimiter)
             .has_headers(self.has_header)
             .from_reader(rdr);
-        CsvReader { reader, schema }
+        CsvReader {
+            reader,
+            schema,
+            column_indices: None,
+            null_values: self.null_values.clone(),
+        }
     }
 
     pub fn infer_schema_from_path(&self, path: impl AsRef<Path>) -> Result<SchemaRef> {
@@ -68,17 +94,23 @@ impl CsvOptions {
 
         let header_length = headers.len();
         let mut column_types: Vec<HashSet<DataType>> = vec![HashSet::new(); header_length];
+        let mut has_negative = vec![false; header_length];
         let mut fields = Vec::new();
         let mut record = StringRecord::new();
 
-        loop {
+        for _ in 0..self.infer_sample_size.unwrap_or(usize::MAX) {
             if !reader.read_record(&mut record)? {
                 break;
             }
 
             for (i, column_type) in column_types.iter_mut().enumerate().take(header_length) {
-                if let Some(string) = record.get(i) {
-                    column_type.insert(infer_field_schema(string));
+                // the nulls don't tell anything about the type of a column
+                if let Some(string) = get_value(&record, i, &self.null_values) {
+                    let data_type = infer_field_schema(string);
+                    if data_type == DataType::Int64 && string.starts_with('-') {
+                        has_negative[i] = true;
+                    }
+                    column_type.insert(data_type);
                 }
             }
         }
@@ -87,23 +119,28 @@ impl CsvOptions {
             let possibilities = &column_types[i];
             let field_name = &headers[i];
 
-            match possibilities.len() {
-                1 => {
-                    for data_type in possibilities.iter() {
-                        fields.push(Field::new(field_name, *data_type));
-                    }
-                }
-                2 => {
-                    if possibilities.contains(&DataType::Int64)
-                        && possibilities.contains(&DataType::Float64)
-                    {
-                        fields.push(Field::new(field_name, DataType::Float64));
+            let is_numeric = possibilities.iter().all(|data_type| {
+                matches!(
+                    data_type,
+                    DataType::Int64 | DataType::UInt64 | DataType::Float64
+                )
+            });
+            let data_type = match possibilities.len() {
+                // all the sampled values are null
+                0 => DataType::String,
+                1 => possibilities.iter().next().unwrap().clone(),
+                // a column is unsigned only if all its integers are non-negative, otherwise the
+                // integers that exceed the range of `Int64` are only representable as floats
+                _ if is_numeric => {
+                    if possibilities.contains(&DataType::Float64) || has_negative[i] {
+                        DataType::Float64
                     } else {
-                        fields.push(Field::new(field_name, DataType::String));
+                        DataType::UInt64
                     }
                 }
-                _ => fields.push(Field::new(field_name, DataType::String)),
-            }
+                _ => DataType::String,
+            };
+            fields.push(Field::new(field_name, data_type));
         }
 
         Ok(Arc::new(Schema::try_new(fields)?))
@@ -113,6 +150,8 @@ impl CsvOptions {
 pub struct CsvReader<R> {
     reader: csv::Reader<R>,
     schema: SchemaRef,
+    column_indices: Option<Vec<usize>>,
+    null_values: Vec<String>,
 }
 
 impl<R: Read> CsvReader<R> {
@@ -120,6 +159,7 @@ impl<R: Read> CsvReader<R> {
         let mut total_count = batch_size.unwrap_or(usize::MAX);
         let mut batch_records = vec![StringRecord::new(); 100];
         let mut builders = create_builders(&self.schema);
+        let column_indices = self.column_indices()?;
 
         while total_count > 0 {
             let read_count = batch_records.len().min(total_count);
@@ -128,12 +168,46 @@ impl<R: Read> CsvReader<R> {
                 break;
             }
             total_count -= count;
-            append_data(&self.schema, &mut builders, &batch_records[..count])?;
+            append_data(
+                &self.schema,
+                &column_indices,
+                &self.null_values,
+                &mut builders,
+                &batch_records[..count],
+            )?;
         }
 
         create_dataset(self.schema.clone(), builders)
     }
 
+    /// Returns the index of the record column for each field, the columns are mapped by the header
+    /// name if the csv has a header, otherwise by position.
+    fn column_indices(&mut self) -> Result<Vec<usize>> {
+        if let Some(column_indices) = &self.column_indices {
+            return Ok(column_indices.clone());
+        }
+
+        let column_indices = if self.reader.has_headers() {
+            let headers = self.reader.headers()?;
+            self.schema
+                .fields()
+                .iter()
+                .map(|field| {
+                    headers
+                        .iter()
+                        .position(|name| name == field.name)
+                        .ok_or_else(|| {
+                            anyhow::anyhow!("column '{}' is missing in the csv header", field.name)
+                        })
+                })
+                .collect::<Result<Vec<_>>>()?
+        } else {
+            (0..self.schema.fields().len()).collect()
+        };
+        self.column_indices = Some(column_indices.clone());
+        Ok(column_indices)
+    }
+
     fn read_batch_records(&mut self, records: &mut [StringRecord]) -> Result<usize> {
         let mut num_records = 0;
 
@@ -154,6 +228,14 @@ impl<R: Read> CsvReader<R> {
         }
         Ok(())
     }
+
+    /// Returns the byte offset of the next record, the header is skipped if present.
+    pub fn byte_position(&mut self) -> Result<u64> {
+        if self.reader.has_headers() {
+            self.reader.byte_headers()?;
+        }
+        Ok(self.reader.position().byte())
+    }
 }
 
 fn infer_field_schema(string: &str) -> DataType {
@@ -174,7 +256,11 @@ fn infer_field_schema(string: &str) -> DataType {
     } else if DECIMAL_RE.is_match(string) {
         DataType::Float64
     } else if INTEGER_RE.is_match(string) {
-        DataType::Int64
+        if i64::from_str(string).is_err() && u64::from_str(string).is_ok() {
+            DataType::UInt64
+        } else {
+            DataType::Int64
+        }
     } else {
         DataType::String
     }
@@ -190,20 +276,28 @@ fn create_builders(schema: &Schema) -> Vec<Box<dyn Any>> {
             DataType::Int16 => Box::new(Int16Builder::default()) as Box<dyn Any>,
             DataType::Int32 => Box::new(Int32Builder::default()) as Box<dyn Any>,
             DataType::Int64 => Box::new(Int64Builder::default()) as Box<dyn Any>,
+            DataType::UInt8 => Box::new(UInt8Builder::default()) as Box<dyn Any>,
+            DataType::UInt16 => Box::new(UInt16Builder::default()) as Box<dyn Any>,
+            DataType::UInt32 => Box::new(UInt32Builder::default()) as Box<dyn Any>,
+            DataType::UInt64 => Box::new(UInt64Builder::default()) as Box<dyn Any>,
             DataType::Float32 => Box::new(Float32Builder::default()) as Box<dyn Any>,
             DataType::Float64 => Box::new(Float64Builder::default()) as Box<dyn Any>,
             DataType::Boolean => Box::new(BooleanBuilder::default()) as Box<dyn Any>,
             DataType::Timestamp(_) => Box::new(TimestampBuilder::default()) as Box<dyn Any>,
+            DataType::Decimal128 { precision, scale } => {
+                Box::new(Decimal128Builder::new(precision, scale)) as Box<dyn Any>
+            }
             DataType::String => Box::new(StringBuilder::default()) as Box<dyn Any>,
+            DataType::List(_) | DataType::Struct(_) => Box::new(()) as Box<dyn Any>,
         })
         .collect::<Vec<_>>()
 }
 
 macro_rules! append_value {
-    ($builder:expr, $records:expr, $idx:expr, $ty:ty) => {{
+    ($builder:expr, $records:expr, $idx:expr, $null_values:expr, $ty:ty) => {{
         let builder = $builder.downcast_mut::<PrimitiveBuilder<$ty>>().unwrap();
         for record in $records {
-            match record.get($idx) {
+            match get_value(record, $idx, $null_values) {
                 Some(value) => {
                     let value =
                         <$ty as PrimitiveType>::Native::from_str(value).with_context(|| {
@@ -222,34 +316,95 @@ macro_rules! append_value {
     }};
 }
 
+/// Returns the value of a column, or `None` if it is missing or one of the null values.
+fn get_value<'a>(record: &'a StringRecord, idx: usize, null_values: &[String]) -> Option<&'a str> {
+    record
+        .get(idx)
+        .filter(|value| !null_values.iter().any(|null_value| null_value == value))
+}
+
 fn append_data(
     schema: &Schema,
+    column_indices: &[usize],
+    null_values: &[String],
     builders: &mut Vec<Box<dyn Any>>,
     records: &[StringRecord],
 ) -> Result<()> {
-    for (idx, field) in schema.fields().iter().enumerate() {
+    for (idx, (field, column)) in schema.fields().iter().zip(column_indices).enumerate() {
+        let column = *column;
         match field.data_type {
             DataType::Null => *builders[idx].downcast_mut::<usize>().unwrap() += records.len(),
-            DataType::Int8 => append_value!(builders[idx], records, idx, Int8Type),
-            DataType::Int16 => append_value!(builders[idx], records, idx, Int16Type),
-            DataType::Int32 => append_value!(builders[idx], records, idx, Int32Type),
-            DataType::Int64 => append_value!(builders[idx], records, idx, Int64Type),
-            DataType::Float32 => append_value!(builders[idx], records, idx, Float32Type),
-            DataType::Float64 => append_value!(builders[idx], records, idx, Float64Type),
-            DataType::Boolean => append_value!(builders[idx], records, idx, BooleanType),
-            DataType::Timestamp(_) => append_value!(builders[idx], records, idx, TimestampType),
+            DataType::Int8 => append_value!(builders[idx], records, column, null_values, Int8Type),
+            DataType::Int16 => {
+                append_value!(builders[idx], records, column, null_values, Int16Type)
+            }
+            DataType::Int32 => {
+                append_value!(builders[idx], records, column, null_values, Int32Type)
+            }
+            DataType::Int64 => {
+                append_value!(builders[idx], records, column, null_values, Int64Type)
+            }
+            DataType::UInt8 => {
+                append_value!(builders[idx], records, column, null_values, UInt8Type)
+            }
+            DataType::UInt16 => {
+                append_value!(builders[idx], records, column, null_values, UInt16Type)
+            }
+            DataType::UInt32 => {
+                append_value!(builders[idx], records, column, null_values, UInt32Type)
+            }
+            DataType::UInt64 => {
+                append_value!(builders[idx], records, column, null_values, UInt64Type)
+            }
+            DataType::Float32 => {
+                append_value!(builders[idx], records, column, null_values, Float32Type)
+            }
+            DataType::Float64 => {
+                append_value!(builders[idx], records, column, null_values, Float64Type)
+            }
+            DataType::Boolean => {
+                append_value!(builders[idx], records, column, null_values, BooleanType)
+            }
+            DataType::Timestamp(_) => {
+                append_value!(builders[idx], records, column, null_values, TimestampType)
+            }
+            DataType::Decimal128 { precision, scale } => {
+                let builder = builders[idx].downcast_mut::<Decimal128Builder>().unwrap();
+                for record in records {
+                    match get_value(record, column, null_values) {
+                        Some(value) => builder.append(
+                            parse_decimal(value, precision, scale).with_context(|| {
+                                format!(
+                                    "failed to parse csv record as {} at index {}: {}",
+                                    field.data_type, column, value
+                                )
+                            })?,
+                        ),
+                        None => builder.append_null(),
+                    }
+                }
+            }
             DataType::String => {
                 let builder = builders[idx].downcast_mut::<StringBuilder>().unwrap();
                 for record in records {
-                    builder.append_opt(record.get(idx));
+                    builder.append_opt(get_value(record, column, null_values));
                 }
             }
+            DataType::List(_) | DataType::Struct(_) => unsupported_datatype(field)?,
         }
     }
 
     Ok(())
 }
 
+fn unsupported_datatype<T>(field: &Field) -> Result<T> {
+    anyhow::bail!(
+        "csv doesn't support the datatype of column '{}': {}",
+        field.name,
+        field.data_type
+    )
+}
+
 macro_rules! create_array {
     ($builder:expr, $ty:ty) => {{
         let builder = *$builder.downcast::<PrimitiveBuilder<$ty>>().unwrap();
@@ -268,15 +423,215 @@ fn create_dataset(schema: SchemaRef, builders: Vec<Box<dyn Any>>) -> Result<Data
             DataType::Int16 => create_array!(builder, Int16Type),
             DataType::Int32 => create_array!(builder, Int32Type),
             DataType::Int64 => create_array!(builder, Int64Type),
+            DataType::UInt8 => create_array!(builder, UInt8Type),
+            DataType::UInt16 => create_array!(builder, UInt16Type),
+            DataType::UInt32 => create_array!(builder, UInt32Type),
+            DataType::UInt64 => create_array!(builder, UInt64Type),
             DataType::Float32 => create_array!(builder, Float32Type),
             DataType::Float64 => create_array!(builder, Float64Type),
             DataType::Boolean => create_array!(builder, BooleanType),
             DataType::Timestamp(_) => create_array!(builder, TimestampType),
+            DataType::Decimal128 { .. } => {
+                let builder = *builder.downcast::<Decimal128Builder>().unwrap();
+                Arc::new(builder.finish())
+            }
             DataType::String => {
                 let builder = *builder.downcast::<StringBuilder>().unwrap();
                 Arc::new(builder.finish())
             }
+            DataType::List(_) | DataType::Struct(_) => unsupported_datatype(field)?,
         });
     }
     DataSet::try_new(schema, columns)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::array::{ArrayExt, Decimal128Array, Int64Array, Scalar, StringArray, UInt64Array};
+
+    fn create_schema() -> SchemaRef {
+        Arc::new(
+            Schema::try_new(vec![
+                Field::new("a", DataType::Int64),
+                Field::new("b", DataType::String),
+            ])
+            .unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_map_columns_by_header() {
+        let options = CsvOptions {
+            delimiter: b',',
+            has_header: true,
+            ..CsvOptions::default()
+        };
+        let mut reader = options.open(create_schema(), &b"c,b,a\ntrue,x,1\nfalse,y,2\n"[..]);
+        assert_eq!(
+            reader.read_batch(None).unwrap(),
+            DataSet::from_csv_slice(create_schema(), CsvOptions::default(), b"1,x\n2,y\n").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_missing_column_in_header() {
+        let options = CsvOptions {
+            delimiter: b',',
+            has_header: true,
+            ..CsvOptions::default()
+        };
+        let mut reader = options.open(create_schema(), &b"c,b\ntrue,x\n"[..]);
+        assert_eq!(
+            reader.read_batch(None).unwrap_err().to_string(),
+            "column 'a' is missing in the csv header"
+        );
+    }
+
+    #[test]
+    fn test_decimal() {
+        let data_type = DataType::Decimal128 {
+            precision: 10,
+            scale: 2,
+        };
+        let schema = Arc::new(Schema::try_new(vec![Field::new("a", data_type.clone())]).unwrap());
+        let dataset = DataSet::from_csv_slice(
+            schema.clone(),
+            CsvOptions::default(),
+            b"123.45
+-0.1
+",
+        )
+        .unwrap();
+        assert_eq!(dataset.columns()[0].data_type(), data_type);
+        assert_eq!(
+            dataset.columns()[0]
+                .downcast_ref::<Decimal128Array>()
+                .iter_opt()
+                .collect::<Vec<_>>(),
+            vec![Some(12345), Some(-10)]
+        );
+
+        assert_eq!(
+            DataSet::from_csv_slice(
+                schema,
+                CsvOptions::default(),
+                b"1.234
+"
+            )
+            .unwrap_err()
+            .to_string(),
+            "failed to parse csv record as decimal128(10, 2) at index 0: 1.234"
+        );
+    }
+
+    #[test]
+    fn test_infer_unsigned() {
+        let data = b"id,n,m\n18446744073709551615,1,-1\n9223372036854775808,2,9223372036854775808\n1,3,1\n";
+        let options = CsvOptions {
+            delimiter: b',',
+            has_header: true,
+            ..CsvOptions::default()
+        };
+        let schema = options.infer_schema(&data[..]).unwrap();
+        assert_eq!(
+            schema
+                .fields()
+                .iter()
+                .map(|field| field.data_type.clone())
+                .collect::<Vec<_>>(),
+            vec![DataType::UInt64, DataType::Int64, DataType::Float64]
+        );
+
+        let dataset = options.open(schema, &data[..]).read_batch(None).unwrap();
+        assert_eq!(
+            dataset.columns()[0]
+                .downcast_ref::<UInt64Array>()
+                .iter_opt()
+                .collect::<Vec<_>>(),
+            vec![Some(u64::MAX), Some(1 << 63), Some(1)]
+        );
+        assert_eq!(
+            dataset.columns()[0].scalar_value(0),
+            Scalar::UInt64(u64::MAX)
+        );
+        assert_eq!(
+            bincode::deserialize::<Scalar>(&bincode::serialize(&Scalar::UInt64(u64::MAX)).unwrap())
+                .unwrap(),
+            Scalar::UInt64(u64::MAX)
+        );
+    }
+
+    #[test]
+    fn test_infer_with_null_values() {
+        let data = b"a,b,c\nNA,x,\n2,,\n3,z,null\n";
+        let options = CsvOptions {
+            delimiter: b',',
+            has_header: true,
+            ..CsvOptions::default()
+        }
+        .with_null_values(vec!["".to_string(), "NA".to_string(), "null".to_string()]);
+        let schema = options.infer_schema(&data[..]).unwrap();
+        assert_eq!(
+            schema
+                .fields()
+                .iter()
+                .map(|field| field.data_type.clone())
+                .collect::<Vec<_>>(),
+            vec![DataType::Int64, DataType::String, DataType::String]
+        );
+
+        let dataset = options.open(schema, &data[..]).read_batch(None).unwrap();
+        assert_eq!(
+            dataset.columns()[0]
+                .downcast_ref::<Int64Array>()
+                .iter_opt()
+                .collect::<Vec<_>>(),
+            vec![None, Some(2), Some(3)]
+        );
+        assert_eq!(
+            dataset.columns()[1]
+                .downcast_ref::<StringArray>()
+                .iter_opt()
+                .collect::<Vec<_>>(),
+            vec![Some("x"), None, Some("z")]
+        );
+        assert_eq!(dataset.columns()[2].null_count(), 3);
+
+        // without the null values, `NA` is a string
+        let options = CsvOptions {
+            delimiter: b',',
+            has_header: true,
+            ..CsvOptions::default()
+        };
+        assert_eq!(
+            options.infer_schema(&data[..]).unwrap().fields()[0].data_type,
+            DataType::String
+        );
+    }
+
+    #[test]
+    fn test_infer_sample_size() {
+        let data = b"1,1\n2,-2\n3,x\n";
+        for (sample_size, expected) in [
+            (1, vec![DataType::Int64, DataType::Int64]),
+            (2, vec![DataType::Int64, DataType::Int64]),
+            (3, vec![DataType::Int64, DataType::String]),
+        ] {
+            let schema = CsvOptions::default()
+                .with_infer_sample_size(sample_size)
+                .infer_schema(&data[..])
+                .unwrap();
+            assert_eq!(
+                schema
+                    .fields()
+                    .iter()
+                    .map(|field| field.data_type.clone())
+                    .collect::<Vec<_>>(),
+                expected,
+                "{}",
+                sample_size
+            );
+        }
+    }
+}
@@ -1,11 +1,38 @@
 use std::fs::File;
 use std::io::{Cursor, Read};
 use std::path::Path;
+use std::sync::Arc;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 
-use crate::array::{compute, ArrayRef, BooleanArray};
-use crate::dataset::{CsvOptions, SchemaRef};
+use crate::array::compute::SortOptions;
+use crate::array::{
+    compute, rescale_decimal, ArrayRef, BooleanArray, BooleanType, DataType, Decimal128Builder,
+    Float32Type, Float64Type, Int16Type, Int32Type, Int64Array, Int64Type, Int8Type, ListArray,
+    NullArray, PrimitiveBuilder, Scalar, StringBuilder, StructArray, TimestampType, UInt16Type,
+    UInt32Type, UInt64Type, UInt8Type,
+};
+use crate::dataset::{CsvOptions, Field, Schema, SchemaRef};
+
+macro_rules! column_from_rows {
+    ($rows:expr, $idx:expr, $field:expr, $builder:ty, $scalar:ident, |$value:ident| $append:expr) => {{
+        let mut builder = <$builder>::with_capacity($rows.len());
+        for (row_idx, row) in $rows.iter().enumerate() {
+            match &row[$idx] {
+                Scalar::Null => builder.append_null(),
+                Scalar::$scalar($value) => builder.append($append),
+                value => anyhow::bail!(
+                    "invalid row {}: expect column '{}' datatype is {}, actual datatype is {}.",
+                    row_idx,
+                    $field.name,
+                    $field.data_type,
+                    value.data_type()
+                ),
+            }
+        }
+        Arc::new(builder.finish()) as ArrayRef
+    }};
+}
 
 #[derive(Debug, Clone)]
 pub struct DataSet {
@@ -46,6 +73,194 @@ impl DataSet {
         Ok(Self { schema, columns })
     }
 
+    /// Creates a dataset from rows of scalars, each row must match the schema.
+    pub fn try_from_rows(schema: SchemaRef, rows: Vec<Vec<Scalar>>) -> Result<Self> {
+        for (row_idx, row) in rows.iter().enumerate() {
+            anyhow::ensure!(
+                row.len() == schema.fields().len(),
+                "invalid row {}: expect {} values, actual {} values.",
+                row_idx,
+                schema.fields().len(),
+                row.len()
+            );
+        }
+
+        let mut columns = Vec::with_capacity(schema.fields().len());
+        for (idx, field) in schema.fields().iter().enumerate() {
+            columns.push(match &field.data_type {
+                DataType::Null => {
+                    for (row_idx, row) in rows.iter().enumerate() {
+                        anyhow::ensure!(
+                            row[idx].is_null(),
+                            "invalid row {}: expect column '{}' datatype is {}, actual datatype is {}.",
+                            row_idx,
+                            field.name,
+                            field.data_type,
+                            row[idx].data_type()
+                        );
+                    }
+                    Arc::new(NullArray::new(rows.len())) as ArrayRef
+                }
+                DataType::Int8 => {
+                    column_from_rows!(rows, idx, field, PrimitiveBuilder<Int8Type>, Int8, |x| *x)
+                }
+                DataType::Int16 => {
+                    column_from_rows!(rows, idx, field, PrimitiveBuilder<Int16Type>, Int16, |x| *x)
+                }
+                DataType::Int32 => {
+                    column_from_rows!(rows, idx, field, PrimitiveBuilder<Int32Type>, Int32, |x| *x)
+                }
+                DataType::Int64 => {
+                    column_from_rows!(rows, idx, field, PrimitiveBuilder<Int64Type>, Int64, |x| *x)
+                }
+                DataType::UInt8 => {
+                    column_from_rows!(rows, idx, field, PrimitiveBuilder<UInt8Type>, UInt8, |x| *x)
+                }
+                DataType::UInt16 => {
+                    column_from_rows!(rows, idx, field, PrimitiveBuilder<UInt16Type>, UInt16, |x| *x)
+                }
+                DataType::UInt32 => {
+                    column_from_rows!(rows, idx, field, PrimitiveBuilder<UInt32Type>, UInt32, |x| *x)
+                }
+                DataType::UInt64 => {
+                    column_from_rows!(rows, idx, field, PrimitiveBuilder<UInt64Type>, UInt64, |x| *x)
+                }
+                DataType::Float32 => column_from_rows!(
+                    rows,
+                    idx,
+                    field,
+                    PrimitiveBuilder<Float32Type>,
+                    Float32,
+                    |x| *x
+                ),
+                DataType::Float64 => column_from_rows!(
+                    rows,
+                    idx,
+                    field,
+                    PrimitiveBuilder<Float64Type>,
+                    Float64,
+                    |x| *x
+                ),
+                DataType::Boolean => column_from_rows!(
+                    rows,
+                    idx,
+                    field,
+                    PrimitiveBuilder<BooleanType>,
+                    Boolean,
+                    |x| *x
+                ),
+                DataType::Timestamp(_) => column_from_rows!(
+                    rows,
+                    idx,
+                    field,
+                    PrimitiveBuilder<TimestampType>,
+                    Timestamp,
+                    |x| *x
+                ),
+                DataType::Decimal128 { precision, scale } => {
+                    let mut builder = Decimal128Builder::with_capacity(*precision, *scale, rows.len());
+                    for (row_idx, row) in rows.iter().enumerate() {
+                        match &row[idx] {
+                            Scalar::Null => builder.append_null(),
+                            Scalar::Decimal128 {
+                                value,
+                                scale: value_scale,
+                                ..
+                            } => builder.append(
+                                rescale_decimal(*value, *value_scale, *scale).with_context(|| {
+                                    format!(
+                                        "invalid row {}: the value of column '{}' doesn't fit in {}.",
+                                        row_idx, field.name, field.data_type
+                                    )
+                                })?,
+                            ),
+                            value => anyhow::bail!(
+                                "invalid row {}: expect column '{}' datatype is {}, actual datatype is {}.",
+                                row_idx,
+                                field.name,
+                                field.data_type,
+                                value.data_type()
+                            ),
+                        }
+                    }
+                    Arc::new(builder.finish()) as ArrayRef
+                }
+                DataType::String => {
+                    column_from_rows!(rows, idx, field, StringBuilder, String, |x| x)
+                }
+                DataType::List(item_type) => {
+                    let mut lengths = Vec::with_capacity(rows.len());
+                    let mut items = Vec::new();
+                    for (row_idx, row) in rows.iter().enumerate() {
+                        match &row[idx] {
+                            Scalar::Null => lengths.push(None),
+                            Scalar::List(values) => {
+                                lengths.push(Some(values.len()));
+                                items.extend(values.iter().map(|value| vec![value.clone()]));
+                            }
+                            value => anyhow::bail!(
+                                "invalid row {}: expect column '{}' datatype is {}, actual datatype is {}.",
+                                row_idx,
+                                field.name,
+                                field.data_type,
+                                value.data_type()
+                            ),
+                        }
+                    }
+                    let item_schema = Arc::new(Schema::try_new(vec![Field::new(
+                        &field.name,
+                        item_type.as_ref().clone(),
+                    )])?);
+                    let values = Self::try_from_rows(item_schema, items)
+                        .with_context(|| format!("invalid list items of column '{}'", field.name))?
+                        .columns[0]
+                        .clone();
+                    Arc::new(ListArray::from_lengths(&lengths, values)) as ArrayRef
+                }
+                DataType::Struct(fields) => {
+                    let mut validity = Vec::with_capacity(rows.len());
+                    let mut items = Vec::with_capacity(rows.len());
+                    for (row_idx, row) in rows.iter().enumerate() {
+                        match &row[idx] {
+                            Scalar::Null => {
+                                validity.push(false);
+                                items.push(vec![Scalar::Null; fields.len()]);
+                            }
+                            Scalar::Struct(values) => {
+                                validity.push(true);
+                                items.push(
+                                    fields
+                                        .iter()
+                                        .map(|field| {
+                                            values
+                                                .iter()
+                                                .find(|(name, _)| name == &field.name)
+                                                .map(|(_, value)| value.clone())
+                                                .unwrap_or_default()
+                                        })
+                                        .collect(),
+                                );
+                            }
+                            value => anyhow::bail!(
+                                "invalid row {}: expect column '{}' datatype is {}, actual datatype is {}.",
+                                row_idx,
+                                field.name,
+                                field.data_type,
+                                value.data_type()
+                            ),
+                        }
+                    }
+                    let columns = Self::try_from_rows(Arc::new(Schema::try_new(fields.clone())?), items)
+                        .with_context(|| format!("invalid struct fields of column '{}'", field.name))?
+                        .columns;
+                    Arc::new(StructArray::try_new(fields.clone(), columns, Some(&validity))?) as ArrayRef
+                }
+            });
+        }
+
+        Self::try_new(schema, columns)
+    }
+
     pub fn from_csv<R: Read>(schema: SchemaRef, options: CsvOptions, rdr: R) -> Result<DataSet> {
         let mut reader = options.open(schema, rdr);
         reader.read_batch(None)
@@ -109,6 +324,50 @@ impl DataSet {
                 .collect(),
         )
     }
+
+    /// Returns a new dataset with the rows at the given indices, a row can be repeated.
+    ///
+    /// Returns an error if an index is out of bounds.
+    pub fn take(&self, indices: &[usize]) -> Result<DataSet> {
+        let indices = Int64Array::from_vec(indices.iter().map(|index| *index as i64).collect());
+        DataSet::try_new(
+            self.schema.clone(),
+            self.columns
+                .iter()
+                .map(|array| compute::take(&**array, &indices))
+                .collect::<Result<_>>()?,
+        )
+    }
+
+    /// Returns a new dataset sorted by the columns at the given indices, the rows that are
+    /// equal in all sort keys keep their original order.
+    pub fn sort(&self, keys: &[(usize, SortOptions)]) -> Result<DataSet> {
+        let keys = keys
+            .iter()
+            .map(|(index, options)| match self.columns.get(*index) {
+                Some(array) => Ok((&**array, *options)),
+                None => anyhow::bail!("sort key index out of range: {}", index),
+            })
+            .collect::<Result<Vec<_>>>()?;
+        self.take(&compute::lexsort_to_indices(&keys)?)
+    }
+
+    /// Like `sort`, but each key is the index of a column and whether it is descending, the nulls
+    /// are placed as the default of SQL.
+    pub fn sort_by(&self, keys: &[(usize, bool)]) -> Result<DataSet> {
+        let keys = keys
+            .iter()
+            .map(|(index, descending)| {
+                let options = if *descending {
+                    SortOptions::desc()
+                } else {
+                    SortOptions::asc()
+                };
+                (*index, options)
+            })
+            .collect::<Vec<_>>();
+        self.sort(&keys)
+    }
 }
 
 impl PartialEq for DataSet {
@@ -127,3 +386,290 @@ impl PartialEq for DataSet {
         true
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::array::{
+        Array, ArrayExt, Decimal128Array, Int64Array, Int64Builder, ListBuilder, StringArray,
+        TimestampArray,
+    };
+
+    fn create_schema() -> SchemaRef {
+        Arc::new(
+            Schema::try_new(vec![
+                Field::new("a", DataType::Int64),
+                Field::new("b", DataType::String),
+                Field::new("c", DataType::Boolean),
+                Field::new("d", DataType::Timestamp(None)),
+            ])
+            .unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_take() {
+        let schema = create_schema();
+        let rows = vec![
+            vec![
+                1i64.into(),
+                "x".into(),
+                true.into(),
+                Scalar::Timestamp(1622512140000),
+            ],
+            vec![
+                2i64.into(),
+                Scalar::Null,
+                false.into(),
+                Scalar::Timestamp(0),
+            ],
+        ];
+        let dataset = DataSet::try_from_rows(schema.clone(), rows.clone()).unwrap();
+
+        assert_eq!(
+            dataset.take(&[1, 0, 0]).unwrap(),
+            DataSet::try_from_rows(
+                schema.clone(),
+                vec![rows[1].clone(), rows[0].clone(), rows[0].clone()]
+            )
+            .unwrap()
+        );
+        assert!(dataset.take(&[]).unwrap().is_empty());
+        assert!(dataset.take(&[0, 5]).is_err());
+    }
+
+    #[test]
+    fn test_sort() {
+        let schema = create_schema();
+        let row = |a: i64, b: Option<&str>, c: bool| {
+            vec![
+                a.into(),
+                b.map(Into::into).unwrap_or(Scalar::Null),
+                c.into(),
+                Scalar::Timestamp(a),
+            ]
+        };
+        let rows = vec![
+            row(2, Some("x"), true),
+            row(1, Some("y"), false),
+            row(2, None, false),
+            row(1, Some("z"), true),
+            row(2, Some("x"), false),
+        ];
+        let dataset = DataSet::try_from_rows(schema.clone(), rows.clone()).unwrap();
+
+        // ORDER BY a ASC, b DESC
+        assert_eq!(
+            dataset
+                .sort(&[(0, SortOptions::asc()), (1, SortOptions::desc())])
+                .unwrap(),
+            DataSet::try_from_rows(
+                schema.clone(),
+                vec![
+                    rows[3].clone(),
+                    rows[1].clone(),
+                    rows[2].clone(),
+                    rows[0].clone(),
+                    rows[4].clone(),
+                ]
+            )
+            .unwrap()
+        );
+
+        assert_eq!(
+            dataset
+                .sort(&[(4, SortOptions::asc())])
+                .unwrap_err()
+                .to_string(),
+            "sort key index out of range: 4"
+        );
+    }
+
+    #[test]
+    fn test_sort_by() {
+        let schema = create_schema();
+        let row = |a: i64, b: Option<&str>, c: bool, d: i64| {
+            vec![
+                a.into(),
+                b.map(Into::into).unwrap_or(Scalar::Null),
+                c.into(),
+                Scalar::Timestamp(d),
+            ]
+        };
+        let rows = vec![
+            row(1, Some("x"), true, 10),
+            row(2, None, false, 20),
+            row(1, Some("z"), false, 30),
+            row(2, Some("y"), true, 40),
+            row(1, None, true, 50),
+        ];
+        let dataset = DataSet::try_from_rows(schema.clone(), rows.clone()).unwrap();
+        let sorted = |order: &[usize]| {
+            DataSet::try_from_rows(
+                schema.clone(),
+                order.iter().map(|index| rows[*index].clone()).collect(),
+            )
+            .unwrap()
+        };
+
+        // ORDER BY a DESC, b ASC, the nulls are last in ascending order
+        assert_eq!(
+            dataset.sort_by(&[(0, true), (1, false)]).unwrap(),
+            sorted(&[3, 1, 0, 2, 4])
+        );
+        // ORDER BY b DESC, c ASC, the nulls are first in descending order
+        assert_eq!(
+            dataset.sort_by(&[(1, true), (2, false)]).unwrap(),
+            sorted(&[1, 4, 2, 3, 0])
+        );
+
+        assert_eq!(
+            dataset
+                .sort_by(&[(0, false), (9, true)])
+                .unwrap_err()
+                .to_string(),
+            "sort key index out of range: 9"
+        );
+    }
+
+    #[test]
+    fn test_try_from_rows() {
+        let schema = create_schema();
+        let dataset = DataSet::try_from_rows(
+            schema.clone(),
+            vec![
+                vec![
+                    1i64.into(),
+                    "x".into(),
+                    true.into(),
+                    Scalar::Timestamp(1622512140000),
+                ],
+                vec![Scalar::Null, Scalar::Null, false.into(), Scalar::Null],
+                vec![3i64.into(), "z".into(), Scalar::Null, Scalar::Timestamp(0)],
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(
+            dataset,
+            DataSet::try_new(
+                schema,
+                vec![
+                    Arc::new(Int64Array::from_opt_vec(vec![Some(1), None, Some(3)])),
+                    Arc::new(StringArray::from_opt_vec(vec![Some("x"), None, Some("z")])),
+                    Arc::new(BooleanArray::from_opt_vec(vec![
+                        Some(true),
+                        Some(false),
+                        None
+                    ])),
+                    Arc::new(TimestampArray::from_opt_vec(vec![
+                        Some(1622512140000),
+                        None,
+                        Some(0)
+                    ])),
+                ]
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_try_from_rows_list() {
+        let schema = Arc::new(
+            Schema::try_new(vec![Field::new(
+                "a",
+                DataType::List(Box::new(DataType::Int64)),
+            )])
+            .unwrap(),
+        );
+        let dataset = DataSet::try_from_rows(
+            schema.clone(),
+            vec![
+                vec![Scalar::List(vec![1i64.into(), Scalar::Null])],
+                vec![Scalar::Null],
+                vec![Scalar::List(vec![])],
+            ],
+        )
+        .unwrap();
+
+        let mut builder = ListBuilder::new(Int64Builder::default());
+        builder.values().append(1);
+        builder.values().append_null();
+        builder.append();
+        builder.append_null();
+        builder.append();
+        assert_eq!(
+            dataset,
+            DataSet::try_new(schema.clone(), vec![Arc::new(builder.finish())]).unwrap()
+        );
+
+        assert_eq!(
+            DataSet::try_from_rows(schema, vec![vec![Scalar::List(vec!["x".into()])]])
+                .unwrap_err()
+                .to_string(),
+            "invalid list items of column 'a'"
+        );
+    }
+
+    #[test]
+    fn test_try_from_rows_decimal() {
+        let schema = Arc::new(
+            Schema::try_new(vec![Field::new(
+                "a",
+                DataType::Decimal128 {
+                    precision: 10,
+                    scale: 2,
+                },
+            )])
+            .unwrap(),
+        );
+        let array = Decimal128Array::from_opt_vec(10, 2, vec![Some(12345), None]);
+        let rows = (0..array.len())
+            .map(|idx| vec![array.scalar_value(idx)])
+            .collect();
+        assert_eq!(
+            DataSet::try_from_rows(schema.clone(), rows).unwrap(),
+            DataSet::try_new(schema.clone(), vec![Arc::new(array)]).unwrap()
+        );
+
+        assert_eq!(
+            DataSet::try_from_rows(
+                schema,
+                vec![vec![Scalar::Decimal128 {
+                    value: 1,
+                    precision: 10,
+                    scale: 3
+                }]]
+            )
+            .unwrap_err()
+            .to_string(),
+            "invalid row 0: the value of column 'a' doesn't fit in decimal128(10, 2)."
+        );
+    }
+
+    #[test]
+    fn test_try_from_rows_error() {
+        assert_eq!(
+            DataSet::try_from_rows(
+                create_schema(),
+                vec![vec![1i64.into(), "x".into(), true.into()]]
+            )
+            .unwrap_err()
+            .to_string(),
+            "invalid row 0: expect 4 values, actual 3 values."
+        );
+
+        assert_eq!(
+            DataSet::try_from_rows(
+                create_schema(),
+                vec![
+                    vec![1i64.into(), "x".into(), true.into(), Scalar::Null],
+                    vec![1i64.into(), 2i64.into(), true.into(), Scalar::Null],
+                ]
+            )
+            .unwrap_err()
+            .to_string(),
+            "invalid row 1: expect column 'b' datatype is string, actual datatype is int64."
+        );
+    }
+}
@@ -0,0 +1,210 @@
+use std::fmt::{self, Display, Formatter};
+
+use crate::array::{ArrayExt, Scalar};
+use crate::dataset::{DataSet, Field};
+
+/// The first difference between two datasets, see [`DataSet::diff`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum DataSetDiff {
+    FieldCount {
+        expected: usize,
+        actual: usize,
+    },
+    Field {
+        index: usize,
+        expected: Field,
+        actual: Field,
+    },
+    Length {
+        expected: usize,
+        actual: usize,
+    },
+    Value {
+        row: usize,
+        column: String,
+        expected: Scalar,
+        actual: Scalar,
+    },
+}
+
+impl Display for DataSetDiff {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            DataSetDiff::FieldCount { expected, actual } => {
+                write!(f, "expect {} fields, actual {} fields.", expected, actual)
+            }
+            DataSetDiff::Field {
+                index,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "expect field {} is '{}' {}, actual is '{}' {}.",
+                index,
+                expected.qualified_name(),
+                expected.data_type,
+                actual.qualified_name(),
+                actual.data_type
+            ),
+            DataSetDiff::Length { expected, actual } => {
+                write!(f, "expect {} rows, actual {} rows.", expected, actual)
+            }
+            DataSetDiff::Value {
+                row,
+                column,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "row {} column '{}': expect {}, actual {}.",
+                row, column, expected, actual
+            ),
+        }
+    }
+}
+
+impl DataSet {
+    /// Compares the dataset with `expected`, and returns the first difference.
+    ///
+    /// The schemas are compared first, then the cells row by row.
+    pub fn diff(&self, expected: &DataSet) -> Option<DataSetDiff> {
+        let expected_fields = expected.schema().fields().to_vec();
+        let actual_fields = self.schema().fields().to_vec();
+        if expected_fields.len() != actual_fields.len() {
+            return Some(DataSetDiff::FieldCount {
+                expected: expected_fields.len(),
+                actual: actual_fields.len(),
+            });
+        }
+        for (index, (expected, actual)) in
+            expected_fields.into_iter().zip(actual_fields).enumerate()
+        {
+            if expected != actual {
+                return Some(DataSetDiff::Field {
+                    index,
+                    expected,
+                    actual,
+                });
+            }
+        }
+
+        if expected.len() != self.len() {
+            return Some(DataSetDiff::Length {
+                expected: expected.len(),
+                actual: self.len(),
+            });
+        }
+        for row in 0..self.len() {
+            for (index, (expected_column, actual_column)) in
+                expected.columns().iter().zip(self.columns()).enumerate()
+            {
+                let expected_value = expected_column.scalar_value(row);
+                let actual_value = actual_column.scalar_value(row);
+                if expected_value != actual_value {
+                    return Some(DataSetDiff::Value {
+                        row,
+                        column: expected.schema().fields()[index].qualified_name(),
+                        expected: expected_value,
+                        actual: actual_value,
+                    });
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Panics with the first difference if the dataset is not equal to `expected`.
+    #[track_caller]
+    pub fn assert_eq(&self, expected: &DataSet) {
+        if let Some(diff) = self.diff(expected) {
+            panic!("the datasets are not equal: {}", diff);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::array::DataType;
+    use crate::dataset::{CsvOptions, Schema, SchemaRef};
+
+    fn create_schema() -> SchemaRef {
+        Arc::new(
+            Schema::try_new(vec![
+                Field::new("a", DataType::Int64),
+                Field::new("b", DataType::String),
+            ])
+            .unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_diff() {
+        let schema = create_schema();
+        let expected =
+            DataSet::from_csv_slice(schema.clone(), CsvOptions::default(), b"1,x\n2,y\n3,z\n")
+                .unwrap();
+
+        let actual =
+            DataSet::from_csv_slice(schema.clone(), CsvOptions::default(), b"1,x\n2,y\n3,z\n")
+                .unwrap();
+        assert_eq!(actual.diff(&expected), None);
+        actual.assert_eq(&expected);
+
+        let actual =
+            DataSet::from_csv_slice(schema.clone(), CsvOptions::default(), b"1,x\n2,w\n3,z\n")
+                .unwrap();
+        let diff = actual.diff(&expected).unwrap();
+        assert_eq!(
+            diff,
+            DataSetDiff::Value {
+                row: 1,
+                column: "b".to_string(),
+                expected: "y".into(),
+                actual: "w".into(),
+            }
+        );
+        assert_eq!(diff.to_string(), "row 1 column 'b': expect y, actual w.");
+
+        let actual = DataSet::from_csv_slice(schema, CsvOptions::default(), b"1,x\n2,y\n").unwrap();
+        assert_eq!(
+            actual.diff(&expected),
+            Some(DataSetDiff::Length {
+                expected: 3,
+                actual: 2
+            })
+        );
+
+        let actual = DataSet::from_csv_slice(
+            Arc::new(
+                Schema::try_new(vec![
+                    Field::new("a", DataType::Int64),
+                    Field::new("b", DataType::Int64),
+                ])
+                .unwrap(),
+            ),
+            CsvOptions::default(),
+            b"1,1\n2,2\n3,3\n",
+        )
+        .unwrap();
+        assert_eq!(
+            actual.diff(&expected).unwrap().to_string(),
+            "expect field 1 is 'b' string, actual is 'b' int64."
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "the datasets are not equal: row 2 column 'a': expect 3, actual 4.")]
+    fn test_assert_eq() {
+        let schema = create_schema();
+        let expected =
+            DataSet::from_csv_slice(schema.clone(), CsvOptions::default(), b"1,x\n2,y\n3,z\n")
+                .unwrap();
+        let actual =
+            DataSet::from_csv_slice(schema, CsvOptions::default(), b"1,x\n2,y\n4,z\n").unwrap();
+        actual.assert_eq(&expected);
+    }
+}
@@ -1,14 +1,29 @@
 use std::fmt::{self, Display, Formatter};
 
+use anyhow::Result;
 use chrono::TimeZone;
+use chrono_tz::Tz;
 use comfy_table::presets::UTF8_HORIZONTAL_BORDERS_ONLY;
 use comfy_table::{Cell, ContentArrangement, Row, Table, TableComponent};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 
 use crate::array::{
     ArrayExt, BooleanArray, DataType, Float32Array, Float64Array, Int16Array, Int32Array,
-    Int64Array, Int8Array, StringArray, TimestampArray,
+    Int64Array, Int8Array, Scalar, StringArray, TimestampArray, UInt16Array, UInt32Array,
+    UInt64Array, UInt8Array,
 };
-use crate::dataset::DataSet;
+use crate::dataset::{DataSet, Schema};
+
+macro_rules! json_value {
+    ($dataset:expr, $row:expr, $column:expr, $ty:ty) => {
+        $dataset.columns()[$column]
+            .downcast_ref::<$ty>()
+            .value_opt($row)
+            .map(Value::from)
+            .unwrap_or(Value::Null)
+    };
+}
 
 macro_rules! add_table_cell {
     ($table_row:expr, $dataset:expr, $row:expr, $column:expr, $ty:ty) => {
@@ -20,9 +35,39 @@ macro_rules! add_table_cell {
     };
 }
 
+/// How the timestamps are rendered.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default, Serialize, Deserialize)]
+pub enum TimestampFormat {
+    /// RFC 3339 strings in the timezone of the column, e.g. `2021-06-02T00:00:00+08:00`.
+    #[default]
+    Rfc3339,
+    /// Milliseconds since the unix epoch.
+    EpochMillis,
+}
+
+impl TimestampFormat {
+    fn json_value(self, tz: Tz, value: i64) -> Value {
+        match self {
+            TimestampFormat::Rfc3339 => Value::String(tz.timestamp_millis(value).to_rfc3339()),
+            TimestampFormat::EpochMillis => Value::from(value),
+        }
+    }
+}
+
 pub struct DataSetDisplay<'a> {
     dataset: &'a DataSet,
     no_header: bool,
+    timestamp_format: Option<TimestampFormat>,
+}
+
+impl<'a> DataSetDisplay<'a> {
+    /// Renders the timestamps in `format` instead of the local time of the column's timezone.
+    pub fn timestamp_format(self, format: TimestampFormat) -> Self {
+        Self {
+            timestamp_format: Some(format),
+            ..self
+        }
+    }
 }
 
 impl DataSet {
@@ -30,6 +75,7 @@ impl DataSet {
         DataSetDisplay {
             dataset: self,
             no_header: false,
+            timestamp_format: None,
         }
     }
 
@@ -37,10 +83,161 @@ impl DataSet {
         DataSetDisplay {
             dataset: self,
             no_header: true,
+            timestamp_format: None,
+        }
+    }
+
+    /// Renders the dataset as CSV with a header row.
+    ///
+    /// Nulls are written as empty fields and timestamps as RFC 3339 strings.
+    pub fn display_csv(&self) -> Result<String> {
+        let mut output = csv_header(&self.schema(), b',')?;
+        output.extend(self.csv_rows(b',', TimestampFormat::Rfc3339)?);
+        Ok(String::from_utf8(output)?)
+    }
+
+    /// Renders the rows as CSV without a header row, the fields are separated by `delimiter`
+    /// and quoted if they contain it, a quote or a newline.
+    pub(crate) fn csv_rows(
+        &self,
+        delimiter: u8,
+        timestamp_format: TimestampFormat,
+    ) -> Result<Vec<u8>> {
+        let mut writer = csv::WriterBuilder::new()
+            .delimiter(delimiter)
+            .from_writer(Vec::new());
+
+        for row in 0..self.len() {
+            writer.write_record((0..self.schema().fields().len()).map(|column| {
+                match self.json_value(row, column, timestamp_format) {
+                    Value::Null => String::new(),
+                    Value::String(s) => s,
+                    value => value.to_string(),
+                }
+            }))?;
+        }
+
+        writer
+            .into_inner()
+            .map_err(|err| anyhow::anyhow!("failed to write csv: {}", err))
+    }
+
+    /// Renders the dataset as a JSON array of objects keyed by field name.
+    ///
+    /// Nulls are written as `null` and timestamps as RFC 3339 strings.
+    pub fn display_json(&self) -> String {
+        let rows = (0..self.len())
+            .map(|row| self.json_row(row, TimestampFormat::Rfc3339))
+            .collect();
+        Value::Array(rows).to_string()
+    }
+
+    /// Renders each row as a JSON object on its own line, the values are rendered like in
+    /// `display_json`.
+    pub fn display_json_lines(&self) -> String {
+        self.display_json_lines_with(TimestampFormat::Rfc3339)
+    }
+
+    /// Like `display_json_lines`, but the timestamps are rendered in `timestamp_format`.
+    pub fn display_json_lines_with(&self, timestamp_format: TimestampFormat) -> String {
+        let mut output = String::new();
+        for row in 0..self.len() {
+            output.push_str(&self.json_row(row, timestamp_format).to_string());
+            output.push('\n');
+        }
+        output
+    }
+
+    fn json_row(&self, row: usize, timestamp_format: TimestampFormat) -> Value {
+        Value::Object(
+            self.schema()
+                .fields()
+                .iter()
+                .enumerate()
+                .map(|(column, field)| {
+                    (
+                        field.name.clone(),
+                        self.json_value(row, column, timestamp_format),
+                    )
+                })
+                .collect::<Map<_, _>>(),
+        )
+    }
+
+    fn json_value(&self, row: usize, column: usize, timestamp_format: TimestampFormat) -> Value {
+        match self.schema().fields()[column].data_type {
+            DataType::Null => Value::Null,
+            DataType::Int8 => json_value!(self, row, column, Int8Array),
+            DataType::Int16 => json_value!(self, row, column, Int16Array),
+            DataType::Int32 => json_value!(self, row, column, Int32Array),
+            DataType::Int64 => json_value!(self, row, column, Int64Array),
+            DataType::UInt8 => json_value!(self, row, column, UInt8Array),
+            DataType::UInt16 => json_value!(self, row, column, UInt16Array),
+            DataType::UInt32 => json_value!(self, row, column, UInt32Array),
+            DataType::UInt64 => json_value!(self, row, column, UInt64Array),
+            DataType::Float32 => json_value!(self, row, column, Float32Array),
+            DataType::Float64 => json_value!(self, row, column, Float64Array),
+            DataType::Boolean => json_value!(self, row, column, BooleanArray),
+            DataType::String => json_value!(self, row, column, StringArray),
+            DataType::Timestamp(tz) => {
+                let tz = tz.unwrap_or(chrono_tz::UTC);
+                self.columns()[column]
+                    .downcast_ref::<TimestampArray>()
+                    .value_opt(row)
+                    .map(|value| timestamp_format.json_value(tz, value))
+                    .unwrap_or(Value::Null)
+            }
+            DataType::Decimal128 { .. } | DataType::List(_) | DataType::Struct(_) => {
+                scalar_json_value(self.columns()[column].scalar_value(row), timestamp_format)
+            }
         }
     }
 }
 
+/// Renders the names of the fields as a CSV row.
+pub(crate) fn csv_header(schema: &Schema, delimiter: u8) -> Result<Vec<u8>> {
+    let mut writer = csv::WriterBuilder::new()
+        .delimiter(delimiter)
+        .from_writer(Vec::new());
+    writer.write_record(schema.fields().iter().map(|field| &field.name))?;
+    writer
+        .into_inner()
+        .map_err(|err| anyhow::anyhow!("failed to write csv: {}", err))
+}
+
+fn scalar_json_value(scalar: Scalar, timestamp_format: TimestampFormat) -> Value {
+    match scalar {
+        Scalar::Null => Value::Null,
+        Scalar::Int8(value) => value.into(),
+        Scalar::Int16(value) => value.into(),
+        Scalar::Int32(value) => value.into(),
+        Scalar::Int64(value) => value.into(),
+        Scalar::UInt8(value) => value.into(),
+        Scalar::UInt16(value) => value.into(),
+        Scalar::UInt32(value) => value.into(),
+        Scalar::UInt64(value) => value.into(),
+        Scalar::Float32(value) => value.into(),
+        Scalar::Float64(value) => value.into(),
+        Scalar::Boolean(value) => value.into(),
+        Scalar::Timestamp(value) => timestamp_format.json_value(chrono_tz::UTC, value),
+        // decimals are written as strings so that they don't lose precision
+        Scalar::Decimal128 { .. } => Value::String(scalar.to_string()),
+        Scalar::String(value) => Value::String(value.to_string()),
+        Scalar::List(values) => Value::Array(
+            values
+                .into_iter()
+                .map(|value| scalar_json_value(value, timestamp_format))
+                .collect(),
+        ),
+        Scalar::Struct(values) => Value::Object(
+            values
+                .into_iter()
+                .map(|(name, value)| (name, scalar_json_value(value, timestamp_format)))
+                .collect(),
+        ),
+    }
+}
+
 impl<'a> Display for DataSetDisplay<'a> {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         let mut table = Table::new();
@@ -81,6 +278,18 @@ impl<'a> Display for DataSetDisplay<'a> {
                         DataType::Int64 => {
                             add_table_cell!(table_row, self.dataset, row, column, Int64Array)
                         }
+                        DataType::UInt8 => {
+                            add_table_cell!(table_row, self.dataset, row, column, UInt8Array)
+                        }
+                        DataType::UInt16 => {
+                            add_table_cell!(table_row, self.dataset, row, column, UInt16Array)
+                        }
+                        DataType::UInt32 => {
+                            add_table_cell!(table_row, self.dataset, row, column, UInt32Array)
+                        }
+                        DataType::UInt64 => {
+                            add_table_cell!(table_row, self.dataset, row, column, UInt64Array)
+                        }
                         DataType::Float32 => {
                             add_table_cell!(table_row, self.dataset, row, column, Float32Array)
                         }
@@ -95,14 +304,23 @@ impl<'a> Display for DataSetDisplay<'a> {
                         }
                         DataType::Timestamp(tz) => {
                             let tz = tz.unwrap_or(chrono_tz::UTC);
+                            let value = self.dataset.columns()[column]
+                                .as_any()
+                                .downcast_ref::<TimestampArray>()
+                                .unwrap()
+                                .value(row);
+                            match self.timestamp_format {
+                                Some(TimestampFormat::Rfc3339) => table_row
+                                    .add_cell(Cell::new(tz.timestamp_millis(value).to_rfc3339())),
+                                Some(TimestampFormat::EpochMillis) => {
+                                    table_row.add_cell(Cell::new(value))
+                                }
+                                None => table_row.add_cell(Cell::new(tz.timestamp_millis(value))),
+                            }
+                        }
+                        DataType::Decimal128 { .. } | DataType::List(_) | DataType::Struct(_) => {
                             table_row.add_cell(Cell::new(
-                                tz.timestamp_millis(
-                                    self.dataset.columns()[column]
-                                        .as_any()
-                                        .downcast_ref::<TimestampArray>()
-                                        .unwrap()
-                                        .value(row),
-                                ),
+                                self.dataset.columns()[column].scalar_value(row),
                             ))
                         }
                     };
@@ -115,3 +333,175 @@ impl<'a> Display for DataSetDisplay<'a> {
         table.fmt(f)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::array::{
+        ArrayRef, BooleanBuilder, Float64Builder, Int32Array, StringBuilder, TimestampBuilder,
+    };
+    use crate::dataset::{DataFormat, Field, Schema};
+
+    fn create_dataset() -> DataSet {
+        let fields = vec![
+            Field::new("a", DataType::Int32),
+            Field::new("b", DataType::Float64),
+            Field::new("c", DataType::Boolean),
+            Field::new("d", DataType::String),
+            Field::new("e", DataType::Timestamp(None)),
+            Field::new("f", DataType::Timestamp(Some(chrono_tz::Asia::Shanghai))),
+        ];
+        let schema = Arc::new(Schema::try_new(fields).unwrap());
+
+        let mut b = Float64Builder::default();
+        b.append(1.5);
+        b.append_null();
+        let mut c = BooleanBuilder::default();
+        c.append_null();
+        c.append(true);
+        let mut d = StringBuilder::default();
+        d.append("x,\"y\"");
+        d.append_null();
+        let mut e = TimestampBuilder::default();
+        e.append(1622563200000);
+        e.append_null();
+        let mut f = TimestampBuilder::default();
+        f.append(1622563200000);
+        f.append(1622563201500);
+
+        let columns = vec![
+            Arc::new(Int32Array::from_vec(vec![1, 2])) as ArrayRef,
+            Arc::new(b.finish()),
+            Arc::new(c.finish()),
+            Arc::new(d.finish()),
+            Arc::new(e.finish()),
+            Arc::new(f.finish()),
+        ];
+        DataSet::try_new(schema, columns).unwrap()
+    }
+
+    #[test]
+    fn test_display_csv() {
+        assert_eq!(
+            create_dataset().display_csv().unwrap(),
+            "a,b,c,d,e,f\n\
+             1,1.5,,\"x,\"\"y\"\"\",2021-06-01T16:00:00+00:00,2021-06-02T00:00:00+08:00\n\
+             2,,true,,,2021-06-02T00:00:01.500+08:00\n"
+        );
+    }
+
+    #[test]
+    fn test_display_json() {
+        assert_eq!(
+            create_dataset().display_json(),
+            r#"[{"a":1,"b":1.5,"c":null,"d":"x,\"y\"","e":"2021-06-01T16:00:00+00:00","f":"2021-06-02T00:00:00+08:00"},{"a":2,"b":null,"c":true,"d":null,"e":null,"f":"2021-06-02T00:00:01.500+08:00"}]"#
+        );
+    }
+
+    #[test]
+    fn test_display_json_lines() {
+        assert_eq!(
+            create_dataset().display_json_lines(),
+            concat!(
+                r#"{"a":1,"b":1.5,"c":null,"d":"x,\"y\"","e":"2021-06-01T16:00:00+00:00","f":"2021-06-02T00:00:00+08:00"}"#,
+                "\n",
+                r#"{"a":2,"b":null,"c":true,"d":null,"e":null,"f":"2021-06-02T00:00:01.500+08:00"}"#,
+                "\n"
+            )
+        );
+        assert_eq!(create_dataset().slice(0, 0).display_json_lines(), "");
+    }
+
+    #[test]
+    fn test_timestamp_format() {
+        let dataset = create_dataset();
+        assert_eq!(
+            dataset.display_json_lines_with(TimestampFormat::EpochMillis),
+            concat!(
+                r#"{"a":1,"b":1.5,"c":null,"d":"x,\"y\"","e":1622563200000,"f":1622563200000}"#,
+                "\n",
+                r#"{"a":2,"b":null,"c":true,"d":null,"e":null,"f":1622563201500}"#,
+                "\n"
+            )
+        );
+
+        let table = dataset
+            .display()
+            .timestamp_format(TimestampFormat::EpochMillis)
+            .to_string();
+        assert!(table.contains("1622563201500"));
+        let table = dataset
+            .display()
+            .timestamp_format(TimestampFormat::Rfc3339)
+            .to_string();
+        assert!(table.contains("2021-06-02T00:00:01.500+08:00"));
+
+        // both of the formats are parsed back to the same timestamps
+        for timestamp_format in &[TimestampFormat::Rfc3339, TimestampFormat::EpochMillis] {
+            let data = DataFormat::Json
+                .serialize_lines(&dataset, *timestamp_format)
+                .unwrap();
+            let lines = std::str::from_utf8(&data)
+                .unwrap()
+                .lines()
+                .collect::<Vec<_>>();
+            assert_eq!(
+                DataFormat::Json
+                    .parse_lines(dataset.schema(), &lines, 1)
+                    .unwrap(),
+                dataset
+            );
+        }
+    }
+
+    #[test]
+    fn test_serialize_csv() {
+        let dataset = create_dataset();
+        let format = DataFormat::Csv {
+            delimiter: b';',
+            header: true,
+        };
+        assert_eq!(
+            format.serialize_header(&dataset.schema()).unwrap(),
+            b"a;b;c;d;e;f\n"
+        );
+        assert_eq!(
+            std::str::from_utf8(
+                &format
+                    .serialize_lines(&dataset, TimestampFormat::Rfc3339)
+                    .unwrap()
+            )
+            .unwrap(),
+            "1;1.5;;\"x,\"\"y\"\"\";2021-06-01T16:00:00+00:00;2021-06-02T00:00:00+08:00\n\
+             2;;true;;;2021-06-02T00:00:01.500+08:00\n"
+        );
+        assert!(DataFormat::Json
+            .serialize_header(&dataset.schema())
+            .unwrap()
+            .is_empty());
+
+        let mut d = StringBuilder::default();
+        d.append("x;y");
+        d.append("x\ny");
+        let dataset = DataSet::try_new(
+            Arc::new(Schema::try_new(vec![Field::new("d", DataType::String)]).unwrap()),
+            vec![Arc::new(d.finish())],
+        )
+        .unwrap();
+        let data = format
+            .serialize_lines(&dataset, TimestampFormat::Rfc3339)
+            .unwrap();
+        assert_eq!(std::str::from_utf8(&data).unwrap(), "\"x;y\"\n\"x\ny\"\n");
+        assert_eq!(
+            DataFormat::Csv {
+                delimiter: b';',
+                header: false,
+            }
+            .parse(dataset.schema(), &data)
+            .unwrap(),
+            dataset
+        );
+    }
+}
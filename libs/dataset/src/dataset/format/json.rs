@@ -1,19 +1,22 @@
+use std::io::BufRead;
 use std::sync::Arc;
 
 use anyhow::Result;
 use serde_json::{Map, Value};
 
 use crate::array::{
-    ArrayRef, BooleanType, DataType, Float32Type, Float64Type, Int16Type, Int32Type, Int64Type,
-    Int8Type, NullArray, PrimitiveBuilder, PrimitiveType, StringBuilder, TimestampType,
+    parse_decimal, parse_timestamp, ArrayRef, BooleanType, DataType, Decimal128Builder,
+    Float32Type, Float64Type, Int16Type, Int32Type, Int64Type, Int8Type, NullArray,
+    PrimitiveBuilder, PrimitiveType, StringBuilder, StructArray, TimestampType, UInt16Type,
+    UInt32Type, UInt64Type, UInt8Type,
 };
-use crate::dataset::{DataSet, SchemaRef};
+use crate::dataset::{DataSet, Field, Schema, SchemaRef};
 
 macro_rules! parse_integer {
     ($field:expr, $rows:expr, $columns:expr, $ty:ty) => {{
         let mut builder = PrimitiveBuilder::<$ty>::with_capacity($rows.len());
-        for row in &$rows {
-            if let Some(value) = row.get(&$field.name) {
+        for row in $rows.iter() {
+            if let Some(value) = row.get(&$field.name).filter(|value| !value.is_null()) {
                 match value.as_i64() {
                     Some(n) => {
                         if n >= <<$ty as PrimitiveType>::Native>::MIN as i64
@@ -34,6 +37,39 @@ macro_rules! parse_integer {
                         );
                     }
                 }
+            } else {
+                builder.append_null();
+            }
+        }
+        $columns.push(Arc::new(builder.finish()));
+    }}
+}
+
+macro_rules! parse_unsigned_integer {
+    ($field:expr, $rows:expr, $columns:expr, $ty:ty) => {{
+        let mut builder = PrimitiveBuilder::<$ty>::with_capacity($rows.len());
+        for row in $rows.iter() {
+            if let Some(value) = row.get(&$field.name).filter(|value| !value.is_null()) {
+                match value.as_u64() {
+                    Some(n) => {
+                        if n <= <<$ty as PrimitiveType>::Native>::MAX as u64 {
+                            builder.append(n as <$ty as PrimitiveType>::Native);
+                        } else {
+                            anyhow::bail!(
+                                "value of field '{}' has overflowed: expect datatype is {}, actual value is {}",
+                                    $field.name, <$ty>::DATA_TYPE, value.to_string(),
+                            );
+                        }
+                    }
+                    None => {
+                        anyhow::bail!(
+                            "failed to parse field '{}': expect datatype is {}, actual value is '{}'",
+                            $field.name, <$ty>::DATA_TYPE, value.to_string(),
+                        );
+                    }
+                }
+            } else {
+                builder.append_null();
             }
         }
         $columns.push(Arc::new(builder.finish()));
@@ -43,8 +79,8 @@ macro_rules! parse_integer {
 macro_rules! parse_float {
     ($field:expr, $rows:expr, $columns:expr, $ty:ty) => {{
         let mut builder = PrimitiveBuilder::<$ty>::with_capacity($rows.len());
-        for row in &$rows {
-            if let Some(value) = row.get(&$field.name) {
+        for row in $rows.iter() {
+            if let Some(value) = row.get(&$field.name).filter(|value| !value.is_null()) {
                 match value.as_f64() {
                     Some(n) => {
                         if n >= <<$ty as PrimitiveType>::Native>::MIN as f64
@@ -65,6 +101,8 @@ macro_rules! parse_float {
                         );
                     }
                 }
+            } else {
+                builder.append_null();
             }
         }
         $columns.push(Arc::new(builder.finish()));
@@ -73,21 +111,224 @@ macro_rules! parse_float {
 
 pub fn parse_json(schema: SchemaRef, data: &[u8]) -> Result<DataSet> {
     let rows = serde_json::from_slice::<Vec<Map<String, Value>>>(data)?;
-    let mut columns = Vec::with_capacity(schema.fields().len());
+    let columns = parse_columns(schema.fields(), &rows)?;
+    DataSet::try_new(schema, columns)
+}
+
+/// Parses newline-delimited JSON objects, the blank lines are skipped.
+///
+/// `first_line` is the line number of the first line, it is used to report the malformed line.
+pub fn parse_json_lines<S: AsRef<str>>(
+    schema: SchemaRef,
+    lines: &[S],
+    first_line: usize,
+) -> Result<DataSet> {
+    let mut rows = Vec::with_capacity(lines.len());
+    let mut line_numbers = Vec::with_capacity(lines.len());
+    for (idx, line) in lines.iter().enumerate() {
+        let line = line.as_ref().trim();
+        if line.is_empty() {
+            continue;
+        }
+        let row = serde_json::from_str::<Map<String, Value>>(line)
+            .map_err(|err| anyhow::anyhow!("invalid json at line {}: {}", first_line + idx, err))?;
+        rows.push(row);
+        line_numbers.push(first_line + idx);
+    }
+
+    match parse_columns(schema.fields(), &rows) {
+        Ok(columns) => DataSet::try_new(schema, columns),
+        Err(err) => {
+            // parses the rows one by one to find the line of the error
+            for (row, line) in rows.iter().zip(line_numbers) {
+                if let Err(err) = parse_columns(schema.fields(), std::slice::from_ref(row)) {
+                    anyhow::bail!("invalid row at line {}: {}", line, err);
+                }
+            }
+            Err(err)
+        }
+    }
+}
+
+/// Infers the schema of newline-delimited JSON objects from the first `sample_size` lines, all the
+/// lines if `None`, the blank lines are skipped.
+///
+/// The fields are in the order they are first seen. The integers are `Int64`, or `UInt64` if some
+/// of them exceed the range of `Int64` and none is negative, and the numbers are `Float64` if any
+/// of them is not an integer. The objects are structs, and the fields that are always null are
+/// strings.
+pub fn infer_json_lines_schema(rdr: impl BufRead, sample_size: Option<usize>) -> Result<SchemaRef> {
+    let mut fields = Vec::new();
+    for (idx, line) in rdr
+        .lines()
+        .take(sample_size.unwrap_or(usize::MAX))
+        .enumerate()
+    {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let row = serde_json::from_str::<Map<String, Value>>(line)
+            .map_err(|err| anyhow::anyhow!("invalid json at line {}: {}", idx + 1, err))?;
+        merge_object_fields(&mut fields, &row)
+            .map_err(|err| anyhow::anyhow!("invalid row at line {}: {}", idx + 1, err))?;
+    }
+    Ok(Arc::new(Schema::try_new(inferred_fields(&fields))?))
+}
+
+/// The type of a field inferred from the values of the sampled rows.
+#[derive(Clone)]
+enum InferredType {
+    Boolean,
+    /// Integers within the range of `Int64`, `negative` if any of them is negative.
+    Int {
+        negative: bool,
+    },
+    /// Integers of which some are greater than `i64::MAX`.
+    UInt,
+    Float,
+    String,
+    /// The fields of the objects, a field is `None` if all its values are null.
+    Struct(Vec<(String, Option<InferredType>)>),
+}
+
+impl InferredType {
+    fn from_value(name: &str, value: &Value) -> Result<Option<InferredType>> {
+        Ok(Some(match value {
+            Value::Null => return Ok(None),
+            Value::Bool(_) => InferredType::Boolean,
+            Value::Number(n) if n.is_i64() => InferredType::Int {
+                negative: n.as_i64().unwrap_or_default() < 0,
+            },
+            Value::Number(n) if n.is_u64() => InferredType::UInt,
+            Value::Number(_) => InferredType::Float,
+            Value::String(_) => InferredType::String,
+            Value::Object(object) => {
+                let mut fields = Vec::new();
+                merge_object_fields(&mut fields, object)?;
+                InferredType::Struct(fields)
+            }
+            Value::Array(_) => anyhow::bail!(
+                "failed to infer the type of field '{}': lists are not supported",
+                name
+            ),
+        }))
+    }
+
+    fn is_numeric(&self) -> bool {
+        matches!(
+            self,
+            InferredType::Int { .. } | InferredType::UInt | InferredType::Float
+        )
+    }
+
+    fn merge(self, name: &str, other: InferredType) -> Result<InferredType> {
+        Ok(match (self, other) {
+            (InferredType::Int { negative: a }, InferredType::Int { negative: b }) => {
+                InferredType::Int { negative: a || b }
+            }
+            (InferredType::UInt, InferredType::UInt)
+            | (InferredType::Int { negative: false }, InferredType::UInt)
+            | (InferredType::UInt, InferredType::Int { negative: false }) => InferredType::UInt,
+            // the integers that exceed the range of `Int64` and the negative ones are only
+            // representable as floats together
+            (a, b) if a.is_numeric() && b.is_numeric() => InferredType::Float,
+            (InferredType::Boolean, InferredType::Boolean) => InferredType::Boolean,
+            (InferredType::String, InferredType::String) => InferredType::String,
+            (InferredType::Struct(mut fields), InferredType::Struct(other)) => {
+                for (name, ty) in other {
+                    merge_field(&mut fields, name, ty)?;
+                }
+                InferredType::Struct(fields)
+            }
+            (a, b) => anyhow::bail!(
+                "failed to infer the type of field '{}': found values of {} and {}",
+                name,
+                a.data_type(),
+                b.data_type()
+            ),
+        })
+    }
+
+    fn data_type(&self) -> DataType {
+        match self {
+            InferredType::Boolean => DataType::Boolean,
+            InferredType::Int { .. } => DataType::Int64,
+            InferredType::UInt => DataType::UInt64,
+            InferredType::Float => DataType::Float64,
+            InferredType::String => DataType::String,
+            InferredType::Struct(fields) => DataType::Struct(inferred_fields(fields)),
+        }
+    }
+}
+
+fn merge_field(
+    fields: &mut Vec<(String, Option<InferredType>)>,
+    name: String,
+    ty: Option<InferredType>,
+) -> Result<()> {
+    match fields
+        .iter_mut()
+        .find(|(field_name, _)| *field_name == name)
+    {
+        Some((_, current)) => {
+            *current = match (current.take(), ty) {
+                (Some(a), Some(b)) => Some(a.merge(&name, b)?),
+                (a, b) => a.or(b),
+            };
+        }
+        None => fields.push((name, ty)),
+    }
+    Ok(())
+}
+
+fn merge_object_fields(
+    fields: &mut Vec<(String, Option<InferredType>)>,
+    object: &Map<String, Value>,
+) -> Result<()> {
+    for (name, value) in object {
+        let ty = InferredType::from_value(name, value)?;
+        merge_field(fields, name.clone(), ty)?;
+    }
+    Ok(())
+}
+
+fn inferred_fields(fields: &[(String, Option<InferredType>)]) -> Vec<Field> {
+    fields
+        .iter()
+        .map(|(name, ty)| {
+            Field::new(
+                name,
+                ty.as_ref()
+                    .map(InferredType::data_type)
+                    .unwrap_or(DataType::String),
+            )
+        })
+        .collect()
+}
+
+/// Parses a column for each field, a missing or `null` value is parsed as null.
+fn parse_columns(fields: &[Field], rows: &[Map<String, Value>]) -> Result<Vec<ArrayRef>> {
+    let mut columns = Vec::with_capacity(fields.len());
 
-    for field in schema.fields() {
-        match field.data_type {
+    for field in fields {
+        match &field.data_type {
             DataType::Null => columns.push(Arc::new(NullArray::new(rows.len())) as ArrayRef),
             DataType::Int8 => parse_integer!(field, rows, columns, Int8Type),
             DataType::Int16 => parse_integer!(field, rows, columns, Int16Type),
             DataType::Int32 => parse_integer!(field, rows, columns, Int32Type),
             DataType::Int64 => parse_integer!(field, rows, columns, Int64Type),
+            DataType::UInt8 => parse_unsigned_integer!(field, rows, columns, UInt8Type),
+            DataType::UInt16 => parse_unsigned_integer!(field, rows, columns, UInt16Type),
+            DataType::UInt32 => parse_unsigned_integer!(field, rows, columns, UInt32Type),
+            DataType::UInt64 => parse_unsigned_integer!(field, rows, columns, UInt64Type),
             DataType::Float32 => parse_float!(field, rows, columns, Float32Type),
             DataType::Float64 => parse_float!(field, rows, columns, Float64Type),
             DataType::Boolean => {
                 let mut builder = PrimitiveBuilder::<BooleanType>::with_capacity(rows.len());
-                for row in &rows {
-                    if let Some(value) = row.get(&field.name) {
+                for row in rows {
+                    if let Some(value) = row.get(&field.name).filter(|value| !value.is_null()) {
                         match value.as_bool() {
                             Some(n) => builder.append(n),
                             None => {
@@ -97,15 +338,67 @@ pub fn parse_json(schema: SchemaRef, data: &[u8]) -> Result<DataSet> {
                                 );
                             }
                         }
+                    } else {
+                        builder.append_null();
+                    }
+                }
+                columns.push(Arc::new(builder.finish()));
+            }
+            DataType::Timestamp(tz) => {
+                // timestamps can be milliseconds since the unix epoch or RFC 3339 strings
+                let mut builder = PrimitiveBuilder::<TimestampType>::with_capacity(rows.len());
+                for row in rows {
+                    if let Some(value) = row.get(&field.name).filter(|value| !value.is_null()) {
+                        let n = match value {
+                            Value::String(s) => parse_timestamp(s, tz.unwrap_or(chrono_tz::UTC)),
+                            _ => value.as_i64(),
+                        };
+                        match n {
+                            Some(n) => builder.append(n),
+                            None => {
+                                anyhow::bail!(
+                                    "failed to parse field '{}': expect datatype is {}, actual value is '{}'",
+                                    field.name, field.data_type, value.to_string(),
+                                );
+                            }
+                        }
+                    } else {
+                        builder.append_null();
+                    }
+                }
+                columns.push(Arc::new(builder.finish()));
+            }
+            DataType::Decimal128 { precision, scale } => {
+                let mut builder = Decimal128Builder::with_capacity(*precision, *scale, rows.len());
+                for row in rows {
+                    if let Some(value) = row.get(&field.name).filter(|value| !value.is_null()) {
+                        // decimals can be written as strings to keep their precision
+                        let n = match value {
+                            Value::String(s) => parse_decimal(s, *precision, *scale).ok(),
+                            Value::Number(n) => {
+                                parse_decimal(&n.to_string(), *precision, *scale).ok()
+                            }
+                            _ => None,
+                        };
+                        match n {
+                            Some(n) => builder.append(n),
+                            None => {
+                                anyhow::bail!(
+                                    "failed to parse field '{}': expect datatype is {}, actual value is '{}'",
+                                    field.name, field.data_type, value.to_string(),
+                                );
+                            }
+                        }
+                    } else {
+                        builder.append_null();
                     }
                 }
                 columns.push(Arc::new(builder.finish()));
             }
-            DataType::Timestamp(_) => parse_integer!(field, rows, columns, TimestampType),
             DataType::String => {
                 let mut builder = StringBuilder::with_capacity(rows.len());
-                for row in &rows {
-                    if let Some(value) = row.get(&field.name) {
+                for row in rows {
+                    if let Some(value) = row.get(&field.name).filter(|value| !value.is_null()) {
                         match value.as_str() {
                             Some(n) => builder.append(n),
                             None => {
@@ -115,12 +408,213 @@ pub fn parse_json(schema: SchemaRef, data: &[u8]) -> Result<DataSet> {
                                 );
                             }
                         }
+                    } else {
+                        builder.append_null();
                     }
                 }
                 columns.push(Arc::new(builder.finish()));
             }
+            DataType::List(_) => anyhow::bail!(
+                "failed to parse field '{}': datatype {} is not supported",
+                field.name,
+                field.data_type
+            ),
+            DataType::Struct(struct_fields) => {
+                let mut validity = Vec::with_capacity(rows.len());
+                let mut objects = Vec::with_capacity(rows.len());
+                for row in rows {
+                    match row.get(&field.name) {
+                        Some(Value::Object(object)) => {
+                            validity.push(true);
+                            objects.push(object.clone());
+                        }
+                        None | Some(Value::Null) => {
+                            validity.push(false);
+                            objects.push(Map::new());
+                        }
+                        Some(value) => {
+                            anyhow::bail!(
+                                "failed to parse field '{}': expect datatype is {}, actual value is '{}'",
+                                field.name, field.data_type, value.to_string(),
+                            );
+                        }
+                    }
+                }
+                let struct_columns = parse_columns(struct_fields, &objects)?;
+                columns.push(Arc::new(StructArray::try_new(
+                    struct_fields.clone(),
+                    struct_columns,
+                    Some(&validity),
+                )?));
+            }
         }
     }
 
-    DataSet::try_new(schema, columns)
+    Ok(columns)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::array::{Array, ArrayExt, Int64Array, Scalar, StringArray};
+    use crate::dataset::Schema;
+
+    #[test]
+    fn test_infer_json_lines_schema() {
+        let data = br#"{"a": 1, "b": "x", "c": null, "d": {"x": 1}}
+
+{"a": -2, "b": null, "d": {"y": true}, "e": 18446744073709551615}
+{"a": 1.5, "e": 1, "f": false}
+"#;
+        assert_eq!(
+            infer_json_lines_schema(&data[..], None).unwrap(),
+            Arc::new(
+                Schema::try_new(vec![
+                    Field::new("a", DataType::Float64),
+                    Field::new("b", DataType::String),
+                    Field::new("c", DataType::String),
+                    Field::new(
+                        "d",
+                        DataType::Struct(vec![
+                            Field::new("x", DataType::Int64),
+                            Field::new("y", DataType::Boolean),
+                        ])
+                    ),
+                    Field::new("e", DataType::UInt64),
+                    Field::new("f", DataType::Boolean),
+                ])
+                .unwrap()
+            )
+        );
+
+        // only the first lines are sampled
+        assert_eq!(
+            infer_json_lines_schema(&data[..], Some(1))
+                .unwrap()
+                .fields()
+                .iter()
+                .map(|field| field.data_type.clone())
+                .collect::<Vec<_>>(),
+            vec![
+                DataType::Int64,
+                DataType::String,
+                DataType::String,
+                DataType::Struct(vec![Field::new("x", DataType::Int64)])
+            ]
+        );
+
+        assert_eq!(
+            infer_json_lines_schema(&b"{\"a\": 1}\n{\"a\": \"x\"}\n"[..], None)
+                .err()
+                .unwrap()
+                .to_string(),
+            "invalid row at line 2: failed to infer the type of field 'a': found values of int64 and string"
+        );
+        assert_eq!(
+            infer_json_lines_schema(&b"{\"a\": [1]}\n"[..], None)
+                .err()
+                .unwrap()
+                .to_string(),
+            "invalid row at line 1: failed to infer the type of field 'a': lists are not supported"
+        );
+    }
+
+    #[test]
+    fn test_parse_struct() {
+        let schema = Arc::new(
+            Schema::try_new(vec![
+                Field::new("a", DataType::Int64),
+                Field::new(
+                    "b",
+                    DataType::Struct(vec![
+                        Field::new("x", DataType::String),
+                        Field::new("y", DataType::Int64),
+                    ]),
+                ),
+            ])
+            .unwrap(),
+        );
+        let dataset = parse_json(
+            schema,
+            br#"[
+                {"a": 1, "b": {"x": "hello", "y": 10}},
+                {"a": 2, "b": null},
+                {"a": null, "b": {"x": "world"}}
+            ]"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            dataset.columns()[0].downcast_ref::<Int64Array>(),
+            &Int64Array::from_opt_vec(vec![Some(1), Some(2), None])
+        );
+        let array = dataset.columns()[1].downcast_ref::<StructArray>();
+        assert!(array.is_null(1));
+        assert_eq!(
+            array.column("x").unwrap().downcast_ref::<StringArray>(),
+            &StringArray::from_opt_vec(vec![Some("hello"), None, Some("world")])
+        );
+        assert_eq!(
+            array.scalar_value(2),
+            Scalar::Struct(vec![
+                ("x".to_string(), "world".into()),
+                ("y".to_string(), Scalar::Null),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_parse_json_lines() {
+        let schema = Arc::new(
+            Schema::try_new(vec![
+                Field::new("a", DataType::Int64),
+                Field::new("b", DataType::String),
+            ])
+            .unwrap(),
+        );
+        let dataset = parse_json_lines(
+            schema.clone(),
+            &[r#"{"a": 1, "b": "x"}"#, "", r#"{"a": 2}"#],
+            1,
+        )
+        .unwrap();
+        assert_eq!(
+            dataset.columns()[0].downcast_ref::<Int64Array>(),
+            &Int64Array::from_vec(vec![1, 2])
+        );
+        assert_eq!(
+            dataset.columns()[1].downcast_ref::<StringArray>(),
+            &StringArray::from_opt_vec(vec![Some("x"), None])
+        );
+
+        assert_eq!(
+            parse_json_lines(schema.clone(), &[r#"{"a": 1}"#, r#"{"a": 2"#], 10)
+                .unwrap_err()
+                .to_string(),
+            "invalid json at line 11: EOF while parsing an object at line 1 column 7"
+        );
+        assert_eq!(
+            parse_json_lines(schema, &[r#"{"a": 1}"#, "", r#"{"a": "x"}"#], 1)
+                .unwrap_err()
+                .to_string(),
+            "invalid row at line 3: failed to parse field 'a': expect datatype is int64, actual value is '\"x\"'"
+        );
+    }
+
+    #[test]
+    fn test_parse_struct_error() {
+        let schema = Arc::new(
+            Schema::try_new(vec![Field::new(
+                "b",
+                DataType::Struct(vec![Field::new("x", DataType::String)]),
+            )])
+            .unwrap(),
+        );
+        assert_eq!(
+            parse_json(schema, br#"[{"b": 1}]"#)
+                .unwrap_err()
+                .to_string(),
+            "failed to parse field 'b': expect datatype is struct<x: string>, actual value is '1'"
+        );
+    }
 }
@@ -1,15 +1,24 @@
 mod json;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 
-use crate::dataset::{DataSet, SchemaRef};
+use crate::dataset::display::csv_header;
+use crate::dataset::{CsvOptions, DataSet, Schema, SchemaRef, TimestampFormat};
 
-use json::parse_json;
+use json::{parse_json, parse_json_lines};
 
-#[derive(Copy, Clone, Serialize, Deserialize)]
+pub use json::infer_json_lines_schema;
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
 pub enum DataFormat {
     Json,
+    /// The fields are separated by `delimiter`, the first line is the names of the fields if
+    /// `header` is set.
+    Csv {
+        delimiter: u8,
+        header: bool,
+    },
 }
 
 impl Default for DataFormat {
@@ -22,6 +31,147 @@ impl DataFormat {
     pub fn parse(&self, schema: SchemaRef, data: &[u8]) -> Result<DataSet> {
         match self {
             DataFormat::Json => parse_json(schema, data),
+            DataFormat::Csv { delimiter, header } => DataSet::from_csv_slice(
+                schema,
+                CsvOptions {
+                    delimiter: *delimiter,
+                    has_header: *header,
+                    ..CsvOptions::default()
+                },
+                data,
+            ),
+        }
+    }
+
+    /// Serializes the dataset so that it is parsed back by `parse` with the same schema, i.e. a
+    /// JSON array of objects keyed by the names of the fields, or the CSV rows after the header.
+    ///
+    /// Nulls are written as `null` or empty fields, and timestamps as RFC 3339 strings in the
+    /// timezone of the column.
+    pub fn serialize(&self, dataset: &DataSet) -> Result<Vec<u8>> {
+        match self {
+            DataFormat::Json => Ok(dataset.display_json().into_bytes()),
+            DataFormat::Csv { .. } => {
+                let mut data = self.serialize_header(&dataset.schema())?;
+                data.extend(self.serialize_lines(dataset, TimestampFormat::Rfc3339)?);
+                Ok(data)
+            }
+        }
+    }
+
+    /// Serializes the rows of the dataset with one row per line, each line ends with a newline.
+    ///
+    /// The timestamps are rendered in `timestamp_format` in the timezone of the column, either of
+    /// them can be parsed back from JSON. The header of CSV is not included, see
+    /// `serialize_header`.
+    pub fn serialize_lines(
+        &self,
+        dataset: &DataSet,
+        timestamp_format: TimestampFormat,
+    ) -> Result<Vec<u8>> {
+        match self {
+            DataFormat::Json => Ok(dataset
+                .display_json_lines_with(timestamp_format)
+                .into_bytes()),
+            DataFormat::Csv { delimiter, .. } => dataset.csv_rows(*delimiter, timestamp_format),
         }
     }
+
+    /// Serializes the line written once before the lines of `serialize_lines`, i.e. the names of
+    /// the fields for CSV with a header, or nothing.
+    pub fn serialize_header(&self, schema: &Schema) -> Result<Vec<u8>> {
+        match self {
+            DataFormat::Csv {
+                delimiter,
+                header: true,
+            } => csv_header(schema, *delimiter),
+            _ => Ok(Vec::new()),
+        }
+    }
+
+    /// Parses a batch of rows with one row per line, `first_line` is the line number of the
+    /// first line in the errors.
+    pub fn parse_lines<S: AsRef<str>>(
+        &self,
+        schema: SchemaRef,
+        lines: &[S],
+        first_line: usize,
+    ) -> Result<DataSet> {
+        match self {
+            DataFormat::Json => parse_json_lines(schema, lines, first_line),
+            DataFormat::Csv { delimiter, .. } => {
+                let data = lines
+                    .iter()
+                    .map(AsRef::as_ref)
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                DataSet::from_csv_slice(
+                    schema,
+                    CsvOptions {
+                        delimiter: *delimiter,
+                        has_header: false,
+                        ..CsvOptions::default()
+                    },
+                    data.as_bytes(),
+                )
+                .with_context(|| {
+                    format!(
+                        "failed to parse the lines {}-{}",
+                        first_line,
+                        first_line + lines.len() - 1
+                    )
+                })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::array::{DataType, Scalar};
+    use crate::dataset::Field;
+
+    #[test]
+    fn test_serialize() {
+        let schema = Arc::new(
+            Schema::try_new(vec![
+                Field::new("a", DataType::Int64),
+                Field::new("b", DataType::Float64),
+                Field::new("c", DataType::Boolean),
+                Field::new("d", DataType::String),
+                Field::new("e", DataType::Timestamp(Some(chrono_tz::Asia::Shanghai))),
+            ])
+            .unwrap(),
+        );
+        let dataset = DataSet::try_from_rows(
+            schema.clone(),
+            vec![
+                vec![
+                    Scalar::Int64(1),
+                    Scalar::Float64(1.5),
+                    Scalar::Boolean(true),
+                    Scalar::String("x,\"y\"".into()),
+                    Scalar::Timestamp(1622563200000),
+                ],
+                vec![
+                    Scalar::Null,
+                    Scalar::Float64(-2.0),
+                    Scalar::Null,
+                    Scalar::Null,
+                    Scalar::Timestamp(1622563201500),
+                ],
+            ],
+        )
+        .unwrap();
+
+        let data = DataFormat::Json.serialize(&dataset).unwrap();
+        assert_eq!(
+            std::str::from_utf8(&data).unwrap(),
+            r#"[{"a":1,"b":1.5,"c":true,"d":"x,\"y\"","e":"2021-06-02T00:00:00+08:00"},{"a":null,"b":-2.0,"c":null,"d":null,"e":"2021-06-02T00:00:01.500+08:00"}]"#
+        );
+        assert_eq!(DataFormat::Json.parse(schema, &data).unwrap(), dataset);
+    }
 }
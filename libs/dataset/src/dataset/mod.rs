@@ -1,6 +1,7 @@
 mod csv_reader;
 #[allow(clippy::module_inception)]
 mod dataset;
+mod diff;
 mod display;
 mod format;
 mod schema;
@@ -8,6 +9,7 @@ mod serde;
 
 pub use csv_reader::{CsvOptions, CsvReader};
 pub use dataset::DataSet;
-pub use display::DataSetDisplay;
-pub use format::DataFormat;
+pub use diff::DataSetDiff;
+pub use display::{DataSetDisplay, TimestampFormat};
+pub use format::{infer_json_lines_schema, DataFormat};
 pub use schema::{Field, Schema, SchemaRef};
@@ -22,6 +22,13 @@ impl Field {
         }
     }
 
+    pub fn with_qualifier(self, qualifier: impl Into<String>) -> Self {
+        Self {
+            qualifier: Some(qualifier.into()),
+            ..self
+        }
+    }
+
     pub fn qualified_name(&self) -> String {
         match &self.qualifier {
             Some(qualified_name) => format!("{}.{}", qualified_name, self.name),
@@ -107,4 +114,57 @@ impl Schema {
     pub fn fields(&self) -> &[Field] {
         &self.fields
     }
+
+    /// Returns a copy of this schema with the qualifier of all fields replaced by `qualifier`.
+    pub fn qualify(&self, qualifier: &str) -> Result<SchemaRef> {
+        Ok(Arc::new(Schema::try_new(
+            self.fields
+                .iter()
+                .cloned()
+                .map(|field| field.with_qualifier(qualifier))
+                .collect(),
+        )?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_qualify() {
+        let schema = Schema::try_new(vec![
+            Field::new("a", DataType::Int64),
+            Field::new("b", DataType::String).with_qualifier("t"),
+        ])
+        .unwrap()
+        .qualify("x")
+        .unwrap();
+
+        assert!(schema
+            .fields()
+            .iter()
+            .all(|field| field.qualifier.as_deref() == Some("x")));
+        assert_eq!(
+            schema.field(Some("x"), "a"),
+            Some((0, &Field::new("a", DataType::Int64).with_qualifier("x")))
+        );
+        assert_eq!(schema.field(Some("X"), "B").map(|(idx, _)| idx), Some(1));
+        assert_eq!(schema.field(None, "b").map(|(idx, _)| idx), Some(1));
+        assert!(schema.field(Some("t"), "b").is_none());
+    }
+
+    #[test]
+    fn test_qualify_duplicate() {
+        let schema = Schema::try_new(vec![
+            Field::new("a", DataType::Int64).with_qualifier("t1"),
+            Field::new("a", DataType::Int64).with_qualifier("t2"),
+        ])
+        .unwrap();
+
+        assert_eq!(
+            schema.qualify("x").unwrap_err().to_string(),
+            "schema contains duplicate qualified field name: 'x.a'"
+        );
+    }
 }
@@ -6,8 +6,9 @@ use serde::ser::SerializeTuple;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 use crate::array::{
-    ArrayRef, BooleanArray, DataType, Float32Array, Float64Array, Int16Array, Int32Array,
-    Int64Array, Int8Array, NullArray, StringArray, TimestampArray,
+    ArrayRef, BooleanArray, DataType, DeArray, Float32Array, Float64Array, Int16Array, Int32Array,
+    Int64Array, Int8Array, NullArray, StringArray, TimestampArray, UInt16Array, UInt32Array,
+    UInt64Array, UInt8Array,
 };
 use crate::dataset::{DataSet, Field, SchemaRef};
 
@@ -111,6 +112,22 @@ impl<'de, 'a> Visitor<'de> for ColumnsVisitor<'a> {
                     seq.next_element::<Int64Array>()?
                         .ok_or_else(|| Error::custom("expect array"))?,
                 ) as ArrayRef),
+                DataType::UInt8 => columns.push(Arc::new(
+                    seq.next_element::<UInt8Array>()?
+                        .ok_or_else(|| Error::custom("expect array"))?,
+                ) as ArrayRef),
+                DataType::UInt16 => columns.push(Arc::new(
+                    seq.next_element::<UInt16Array>()?
+                        .ok_or_else(|| Error::custom("expect array"))?,
+                ) as ArrayRef),
+                DataType::UInt32 => columns.push(Arc::new(
+                    seq.next_element::<UInt32Array>()?
+                        .ok_or_else(|| Error::custom("expect array"))?,
+                ) as ArrayRef),
+                DataType::UInt64 => columns.push(Arc::new(
+                    seq.next_element::<UInt64Array>()?
+                        .ok_or_else(|| Error::custom("expect array"))?,
+                ) as ArrayRef),
                 DataType::Float32 => columns.push(Arc::new(
                     seq.next_element::<Float32Array>()?
                         .ok_or_else(|| Error::custom("expect array"))?,
@@ -131,6 +148,11 @@ impl<'de, 'a> Visitor<'de> for ColumnsVisitor<'a> {
                     seq.next_element::<StringArray>()?
                         .ok_or_else(|| Error::custom("expect array"))?,
                 ) as ArrayRef),
+                DataType::Decimal128 { .. } | DataType::List(_) | DataType::Struct(_) => columns
+                    .push(
+                        seq.next_element_seed(DeArray(field.data_type.clone()))?
+                            .ok_or_else(|| Error::custom("expect array"))?,
+                    ),
             }
 
             self.0 = tail;
@@ -143,6 +165,7 @@ impl<'de, 'a> Visitor<'de> for ColumnsVisitor<'a> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::array::{Decimal128Array, Int32Builder, ListBuilder};
     use crate::dataset::Schema;
 
     #[test]
@@ -151,13 +174,48 @@ mod tests {
             Field::new("a", DataType::Int32),
             Field::new("b", DataType::String),
             Field::new("c", DataType::Timestamp(None)),
+            Field::new("d", DataType::List(Box::new(DataType::Int32))),
+            Field::new(
+                "e",
+                DataType::Decimal128 {
+                    precision: 38,
+                    scale: 4,
+                },
+            ),
+            Field::new("f", DataType::UInt64),
         ];
         let schema = Arc::new(Schema::try_new(fields).unwrap());
 
+        // [[1, 2], null, [], [3], [null]]
+        let mut builder = ListBuilder::new(Int32Builder::default());
+        builder.values().append(1);
+        builder.values().append(2);
+        builder.append();
+        builder.append_null();
+        builder.append();
+        builder.values().append(3);
+        builder.append();
+        builder.values().append_null();
+        builder.append();
+        let list_array = builder.finish();
+
         let columns = vec![
             Arc::new(Int32Array::from_vec(vec![1, 3, 5, 7, 9])) as ArrayRef,
             Arc::new(StringArray::from_vec(vec!["a", "b", "c", "d", "e"])),
             Arc::new(TimestampArray::from_vec(vec![111, 333, 555, 777, 999])),
+            Arc::new(list_array),
+            Arc::new(Decimal128Array::from_opt_vec(
+                38,
+                4,
+                vec![Some(i128::MAX / 2), None, Some(-12345), Some(0), Some(1)],
+            )),
+            Arc::new(UInt64Array::from_opt_vec(vec![
+                Some(u64::MAX),
+                Some(0),
+                None,
+                Some(1 << 63),
+                Some(42),
+            ])),
         ];
         let dataset = DataSet::try_new(schema, columns).unwrap();
 
@@ -1,3 +1,4 @@
+use std::cmp::Ordering;
 use std::path::Path;
 use std::sync::Arc;
 use std::time::Duration;
@@ -7,17 +8,26 @@ use futures_util::stream::{BoxStream, StreamExt};
 use itertools::Itertools;
 use once_cell::sync::Lazy;
 use tokio::sync::Mutex;
-use yql_core::array::{ArrayRef, BooleanBuilder, DataType, StringArray, StringBuilder};
+use tokio::time::Instant;
+use yql_core::array::{
+    ArrayRef, BooleanBuilder, DataType, Int64Array, Scalar, StringArray, StringBuilder,
+};
 use yql_core::dataset::{DataSet, Field, Schema, SchemaRef};
+use yql_core::expr::{function_names, like};
+use yql_core::sql::ast::Select;
 use yql_core::sql::SqlSourceProvider;
-use yql_core::{DataFrame, ExecutionMetrics, SinkProvider};
+use yql_core::{
+    collect_statistics, BoxSink, DataFrame, DataStream, ExecutionMetrics, SinkProvider,
+    SourceProvider,
+};
 
 use crate::registry::Registry;
 use crate::sink_provider::create_sink_provider;
-use crate::source_provider::create_source_provider;
+use crate::source_provider::{create_source_provider, source_schema};
 use crate::sql::{
-    ShowType, Stmt, StmtCreateSink, StmtCreateSource, StmtCreateStream, StmtDeleteSink,
-    StmtDeleteSource, StmtDeleteStream, StmtSelect, StmtShow, StmtStartStream, StmtStopStream,
+    ShowOrderBy, ShowType, Stmt, StmtAnalyzeSource, StmtCreateSink, StmtCreateSource,
+    StmtCreateStream, StmtDeleteSink, StmtDeleteSource, StmtDeleteStream, StmtDescribe,
+    StmtInsertInto, StmtSelect, StmtShow, StmtStartStream, StmtStopStream,
 };
 use crate::storage::{Definition, SourceDefinition, Storage};
 use crate::task::start_task;
@@ -48,6 +58,17 @@ static SHOW_STREAMS_SCHEMA: Lazy<SchemaRef> = Lazy::new(|| {
     Arc::new(Schema::try_new(fields).unwrap())
 });
 
+static ANALYZE_SOURCE_SCHEMA: Lazy<SchemaRef> = Lazy::new(|| {
+    let fields = vec![
+        Field::new("name", DataType::String),
+        Field::new("min", DataType::String),
+        Field::new("max", DataType::String),
+        Field::new("null_count", DataType::Int64),
+        Field::new("distinct_count", DataType::Int64),
+    ];
+    Arc::new(Schema::try_new(fields).unwrap())
+});
+
 static SHOW_SINKS_SCHEMA: Lazy<SchemaRef> = Lazy::new(|| {
     let fields = vec![
         Field::new("name", DataType::String),
@@ -56,6 +77,19 @@ static SHOW_SINKS_SCHEMA: Lazy<SchemaRef> = Lazy::new(|| {
     Arc::new(Schema::try_new(fields).unwrap())
 });
 
+static SHOW_FUNCTIONS_SCHEMA: Lazy<SchemaRef> = Lazy::new(|| {
+    let fields = vec![Field::new("name", DataType::String)];
+    Arc::new(Schema::try_new(fields).unwrap())
+});
+
+static DESCRIBE_SCHEMA: Lazy<SchemaRef> = Lazy::new(|| {
+    let fields = vec![
+        Field::new("name", DataType::String),
+        Field::new("type", DataType::String),
+    ];
+    Arc::new(Schema::try_new(fields).unwrap())
+});
+
 fn create_action_result_dataset(action: &str, success: bool) -> Result<DataSet> {
     let columns = vec![
         {
@@ -72,6 +106,35 @@ fn create_action_result_dataset(action: &str, success: bool) -> Result<DataSet>
     DataSet::try_new(ACTION_RESULT_SCHEMA.clone(), columns)
 }
 
+fn filter_show_items<T>(
+    items: impl IntoIterator<Item = T>,
+    stmt: &StmtShow,
+    name: impl Fn(&T) -> &str,
+    cmp_status: impl Fn(&T, &T) -> Ordering,
+) -> Vec<T> {
+    let mut items = items
+        .into_iter()
+        .filter(|item| match &stmt.like {
+            Some(pattern) => like(name(item), pattern),
+            None => true,
+        })
+        .collect_vec();
+    items.sort_by(|a, b| match stmt.order_by {
+        ShowOrderBy::Name => name(a).cmp(name(b)),
+        ShowOrderBy::Status => cmp_status(a, b).then_with(|| name(a).cmp(name(b))),
+    });
+    items.truncate(stmt.limit.unwrap_or(usize::MAX));
+    items
+}
+
+fn validate_show(stmt: &StmtShow) -> Result<()> {
+    anyhow::ensure!(
+        stmt.order_by != ShowOrderBy::Status || stmt.show_type == ShowType::Streams,
+        "only streams can be ordered by status"
+    );
+    Ok(())
+}
+
 struct SqlContext<'a>(&'a ServiceInner);
 
 impl<'a> yql_core::sql::SqlContext for SqlContext<'a> {
@@ -91,8 +154,17 @@ impl<'a> yql_core::sql::SqlContext for SqlContext<'a> {
     }
 }
 
+/// The number of datasets of a select between two progress reports.
+const PROGRESS_DATASETS: usize = 100;
+
+/// The longest interval between two progress reports of a select.
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(2);
+
 pub enum ExecuteStreamItem {
     DataSet(DataSet),
+    /// The metrics of a select that is still running, reported periodically.
+    Progress(ExecutionMetrics),
+    /// The metrics of a select that has finished, it is the last item.
     Metrics(ExecutionMetrics),
 }
 
@@ -117,7 +189,177 @@ impl ServiceInner {
                 });
         match definition {
             Some(definition) => create_sink_provider(&definition),
-            None => anyhow::bail!("sink '{}' not defined"),
+            None => anyhow::bail!("sink '{}' not defined", name),
+        }
+    }
+
+    fn create_stream(
+        &self,
+        select: Select,
+        to: &str,
+        state: Option<Vec<u8>>,
+    ) -> Result<(DataStream, BoxSink)> {
+        let sink = self.create_sink_provider(to)?.create()?;
+        let df = DataFrame::from_sql_select(&SqlContext(self), select)?;
+        Ok((df.into_stream(state)?, sink))
+    }
+
+    fn get_source_definition(&self, name: &str) -> Result<Box<SourceDefinition>> {
+        match self.storage.get_definition(name)? {
+            Some(Definition::Source(source_definition)) => Ok(source_definition),
+            _ => anyhow::bail!("not exists"),
+        }
+    }
+
+    /// Returns the columns of a source, or the output columns of the select of a stream.
+    fn describe_definition(&self, name: &str) -> Result<SchemaRef> {
+        match self.storage.get_definition(name)? {
+            Some(Definition::Source(source_definition)) => Ok(source_definition.schema),
+            Some(Definition::Stream(stream_definition)) => {
+                DataFrame::from_sql_select(&SqlContext(self), stream_definition.select)?.schema()
+            }
+            Some(Definition::Sink(_)) => anyhow::bail!("a sink has no columns"),
+            None => anyhow::bail!("not exists"),
+        }
+    }
+
+    fn ensure_definition_not_exists(&self, name: &str) -> Result<()> {
+        anyhow::ensure!(!self.storage.definition_exists(name)?, "already exists");
+        Ok(())
+    }
+
+    fn ensure_definition_exists(
+        &self,
+        name: &str,
+        is_type: impl FnOnce(&Definition) -> bool,
+    ) -> Result<()> {
+        anyhow::ensure!(
+            self.storage
+                .get_definition(name)?
+                .map(|definition| is_type(&definition))
+                .unwrap_or_default(),
+            "not exists"
+        );
+        Ok(())
+    }
+
+    /// Returns the definition of a stream that can be started.
+    fn stream_definition_to_start(&self, name: &str) -> Result<Box<StreamDefinition>> {
+        anyhow::ensure!(!self.registry.is_running(name), "already running");
+        let definition = self
+            .storage
+            .get_definition(name)?
+            .ok_or_else(|| anyhow::anyhow!("not found"))?;
+        match definition {
+            Definition::Stream(stream_definition) => Ok(stream_definition),
+            _ => anyhow::bail!("not stream"),
+        }
+    }
+
+    fn ensure_stream_running(&self, name: &str) -> Result<()> {
+        anyhow::ensure!(self.registry.is_running(name), "not running");
+        Ok(())
+    }
+
+    /// Returns the name of the stream created by an `INSERT INTO`, a name is generated from the
+    /// sink if it is not specified.
+    fn insert_into_stream_name(&self, stmt: &StmtInsertInto) -> Result<String> {
+        match &stmt.name {
+            Some(name) => {
+                self.ensure_definition_not_exists(name)?;
+                Ok(name.clone())
+            }
+            None => {
+                let mut id = 1;
+                loop {
+                    let name = format!("insert_{}_{}", stmt.sink, id);
+                    if !self.storage.definition_exists(&name)? {
+                        break Ok(name);
+                    }
+                    id += 1;
+                }
+            }
+        }
+    }
+
+    /// Returns the provider of a source that the statistics can be collected from.
+    fn source_provider_to_analyze(&self, name: &str) -> Result<SourceProvider> {
+        let source_definition = self.get_source_definition(name)?;
+        let source_provider = create_source_provider(&source_definition)?.source_provider;
+        anyhow::ensure!(
+            source_provider.is_bounded(),
+            "the statistics can only be collected from a bounded source."
+        );
+        Ok(source_provider)
+    }
+
+    /// Checks the statement against the current definitions, and plans the queries in it without
+    /// creating any definition or running any stream.
+    fn validate_stmt(&self, stmt: Stmt) -> Result<()> {
+        match stmt {
+            Stmt::CreateSource(stmt) => {
+                self.ensure_definition_not_exists(&stmt.name)?;
+                let source_provider = create_source_provider(&SourceDefinition {
+                    name: stmt.name,
+                    schema: source_schema(&stmt.uri, stmt.fields)?,
+                    uri: stmt.uri,
+                    time_expr: stmt.time,
+                    statistics: None,
+                })?;
+                DataFrame::new(
+                    source_provider.source_provider,
+                    None,
+                    source_provider.time_expr,
+                )
+                .validate()
+            }
+            Stmt::CreateStream(stmt) => {
+                self.ensure_definition_not_exists(&stmt.name)?;
+                self.create_sink_provider(&stmt.to)?;
+                DataFrame::from_sql_select(&SqlContext(self), stmt.select)?.validate()
+            }
+            Stmt::CreateSink(stmt) => {
+                self.ensure_definition_not_exists(&stmt.name)?;
+                create_sink_provider(&SinkDefinition {
+                    name: stmt.name,
+                    uri: stmt.uri,
+                    format: stmt.format.map(Into::into),
+                })?;
+                Ok(())
+            }
+            Stmt::DeleteSource(stmt) => self.ensure_definition_exists(&stmt.name, |definition| {
+                matches!(definition, Definition::Source(_))
+            }),
+            Stmt::DeleteStream(stmt) => self.ensure_definition_exists(&stmt.name, |definition| {
+                matches!(definition, Definition::Stream(_))
+            }),
+            Stmt::DeleteSink(stmt) => self.ensure_definition_exists(&stmt.name, |definition| {
+                matches!(definition, Definition::Sink(_))
+            }),
+            Stmt::StartStream(stmt) => {
+                let stream_definition = self.stream_definition_to_start(&stmt.name)?;
+                self.create_sink_provider(&stream_definition.to)?;
+                DataFrame::from_sql_select(&SqlContext(self), stream_definition.select)?.validate()
+            }
+            Stmt::StopStream(stmt) => self.ensure_stream_running(&stmt.name),
+            Stmt::Show(stmt) => validate_show(&stmt),
+            Stmt::Select(stmt) => {
+                DataFrame::from_sql_select(&SqlContext(self), stmt.select)?.validate()
+            }
+            Stmt::InsertInto(stmt) => {
+                self.insert_into_stream_name(&stmt)?;
+                self.create_sink_provider(&stmt.sink)?;
+                DataFrame::from_sql_select(&SqlContext(self), stmt.select)?.validate()
+            }
+            Stmt::AnalyzeSource(stmt) => {
+                self.source_provider_to_analyze(&stmt.name)?;
+                Ok(())
+            }
+            Stmt::Describe(stmt) => {
+                self.describe_definition(&stmt.name)?;
+                Ok(())
+            }
+            Stmt::Validate(stmt) => self.validate_stmt(*stmt),
         }
     }
 }
@@ -138,9 +380,53 @@ impl Service {
         })
     }
 
-    pub async fn execute(&self, sql: &str) -> Result<ExecuteResult> {
-        let (_, stmt) = crate::sql::stmt(sql).map_err(|err| anyhow::anyhow!("{}", err))?;
+    /// Executes the statements separated by semicolons in order, and returns their results.
+    ///
+    /// The execution stops at the first statement that fails. Only the last statement can be a
+    /// select, because the stream of a select is returned instead of being run.
+    pub async fn execute(&self, sql: &str) -> Result<Vec<ExecuteResult>> {
+        let (_, stmts) = crate::sql::stmts(sql)
+            .map_err(|err| anyhow::anyhow!("{}", yql_core::sql::parser::format_error(sql, err)))?;
+        anyhow::ensure!(
+            !stmts[..stmts.len() - 1]
+                .iter()
+                .any(|stmt| matches!(stmt, Stmt::Select(_))),
+            "only the last statement can be a select."
+        );
+
+        let num_stmts = stmts.len();
+        let mut results = Vec::with_capacity(num_stmts);
+        for (index, stmt) in stmts.into_iter().enumerate() {
+            match self.execute_stmt(stmt).await {
+                Ok(result) => results.push(result),
+                Err(err) if num_stmts == 1 => return Err(err),
+                Err(err) => anyhow::bail!("statement {} failed: {}", index + 1, err),
+            }
+        }
+        Ok(results)
+    }
+
+    /// Checks the statements separated by semicolons without executing them, e.g. the names of the
+    /// definitions, the fields of the sources and the types of the expressions in the queries.
+    ///
+    /// Nothing is changed, so each statement is checked against the current definitions, and
+    /// can't refer to a definition created by a previous statement.
+    pub async fn validate(&self, sql: &str) -> Result<()> {
+        let (_, stmts) = crate::sql::stmts(sql)
+            .map_err(|err| anyhow::anyhow!("{}", yql_core::sql::parser::format_error(sql, err)))?;
+        let inner = self.inner.lock().await;
+        let num_stmts = stmts.len();
+        for (index, stmt) in stmts.into_iter().enumerate() {
+            match inner.validate_stmt(stmt) {
+                Ok(()) => {}
+                Err(err) if num_stmts == 1 => return Err(err),
+                Err(err) => anyhow::bail!("statement {} failed: {}", index + 1, err),
+            }
+        }
+        Ok(())
+    }
 
+    async fn execute_stmt(&self, stmt: Stmt) -> Result<ExecuteResult> {
         match stmt {
             Stmt::CreateSource(stmt) => Ok(ExecuteResult::DataSet(
                 self.execute_create_source(*stmt).await?,
@@ -168,23 +454,39 @@ impl Service {
             )),
             Stmt::Show(stmt) => Ok(ExecuteResult::DataSet(self.execute_show(*stmt).await?)),
             Stmt::Select(stmt) => Ok(ExecuteResult::ExecStream(self.execute_select(*stmt).await?)),
+            Stmt::InsertInto(stmt) => Ok(ExecuteResult::DataSet(
+                self.execute_insert_into(*stmt).await?,
+            )),
+            Stmt::AnalyzeSource(stmt) => Ok(ExecuteResult::DataSet(
+                self.execute_analyze_source(*stmt).await?,
+            )),
+            Stmt::Describe(stmt) => Ok(ExecuteResult::DataSet(self.execute_describe(*stmt).await?)),
+            Stmt::Validate(stmt) => {
+                self.inner.lock().await.validate_stmt(*stmt)?;
+                Ok(ExecuteResult::DataSet(create_action_result_dataset(
+                    "Validate", true,
+                )?))
+            }
         }
     }
 
+    fn spawn_stream(&self, name: String, stream: DataStream, sink: BoxSink) {
+        let interval = tokio::time::interval(Duration::from_secs(5 * 60));
+        tokio::spawn(start_task(self.inner.clone(), name, interval, stream, sink));
+    }
+
     async fn execute_create_source(&self, stmt: StmtCreateSource) -> Result<DataSet> {
         let inner = self.inner.lock().await;
-        anyhow::ensure!(
-            !inner.storage.definition_exists(&stmt.name)?,
-            "already exists"
-        );
+        inner.ensure_definition_not_exists(&stmt.name)?;
 
         inner
             .storage
             .create_definition(Definition::Source(Box::new(SourceDefinition {
                 name: stmt.name,
-                schema: Arc::new(Schema::try_new(stmt.fields)?),
+                schema: source_schema(&stmt.uri, stmt.fields)?,
                 uri: stmt.uri,
                 time_expr: stmt.time,
+                statistics: None,
             })))?;
 
         create_action_result_dataset("Create Source", true)
@@ -192,10 +494,7 @@ impl Service {
 
     async fn execute_create_stream(&self, stmt: StmtCreateStream) -> Result<DataSet> {
         let inner = self.inner.lock().await;
-        anyhow::ensure!(
-            !inner.storage.definition_exists(&stmt.name)?,
-            "already exists"
-        );
+        inner.ensure_definition_not_exists(&stmt.name)?;
 
         inner
             .storage
@@ -210,16 +509,14 @@ impl Service {
 
     async fn execute_create_sink(&self, stmt: StmtCreateSink) -> Result<DataSet> {
         let inner = self.inner.lock().await;
-        anyhow::ensure!(
-            !inner.storage.definition_exists(&stmt.name)?,
-            "already exists"
-        );
+        inner.ensure_definition_not_exists(&stmt.name)?;
 
         inner
             .storage
             .create_definition(Definition::Sink(Box::new(SinkDefinition {
                 name: stmt.name,
                 uri: stmt.uri,
+                format: stmt.format.map(Into::into),
             })))?;
 
         create_action_result_dataset("Create Sink", true)
@@ -227,28 +524,18 @@ impl Service {
 
     async fn execute_delete_source(&self, stmt: StmtDeleteSource) -> Result<DataSet> {
         let inner = self.inner.lock().await;
-        anyhow::ensure!(
-            inner
-                .storage
-                .get_definition(&stmt.name)?
-                .map(|definition| matches!(definition, Definition::Source(_)))
-                .unwrap_or_default(),
-            "not exists"
-        );
+        inner.ensure_definition_exists(&stmt.name, |definition| {
+            matches!(definition, Definition::Source(_))
+        })?;
         inner.storage.delete_definition(&stmt.name)?;
         create_action_result_dataset("Delete Source", true)
     }
 
     async fn execute_delete_stream(&self, stmt: StmtDeleteStream) -> Result<DataSet> {
         let mut inner = self.inner.lock().await;
-        anyhow::ensure!(
-            inner
-                .storage
-                .get_definition(&stmt.name)?
-                .map(|definition| matches!(definition, Definition::Stream(_)))
-                .unwrap_or_default(),
-            "not exists"
-        );
+        inner.ensure_definition_exists(&stmt.name, |definition| {
+            matches!(definition, Definition::Stream(_))
+        })?;
         inner.storage.delete_definition(&stmt.name)?;
         inner.storage.delete_stream_state(&stmt.name)?;
         inner.storage.delete_stream_state_data(&stmt.name)?;
@@ -258,77 +545,129 @@ impl Service {
 
     async fn execute_delete_sink(&self, stmt: StmtDeleteSink) -> Result<DataSet> {
         let inner = self.inner.lock().await;
-        anyhow::ensure!(
-            inner
-                .storage
-                .get_definition(&stmt.name)?
-                .map(|definition| matches!(definition, Definition::Sink(_)))
-                .unwrap_or_default(),
-            "not exists"
-        );
+        inner.ensure_definition_exists(&stmt.name, |definition| {
+            matches!(definition, Definition::Sink(_))
+        })?;
         inner.storage.delete_definition(&stmt.name)?;
         create_action_result_dataset("Delete Sink", true)
     }
 
     async fn execute_start_stream(&self, stmt: StmtStartStream) -> Result<DataSet> {
-        let service_inner = self.inner.clone();
+        let inner = self.inner.lock().await;
+        let stream_definition = inner.stream_definition_to_start(&stmt.name)?;
+        let state = if stmt.restart {
+            inner.storage.delete_stream_state_data(&stmt.name)?;
+            None
+        } else {
+            inner.storage.get_stream_state_data(&stmt.name)?
+        };
+        let (stream, sink) =
+            inner.create_stream(stream_definition.select, &stream_definition.to, state)?;
+        self.spawn_stream(stmt.name, stream, sink);
 
+        create_action_result_dataset("Start Stream", true)
+    }
+
+    async fn execute_insert_into(&self, stmt: StmtInsertInto) -> Result<DataSet> {
         let inner = self.inner.lock().await;
-        anyhow::ensure!(!inner.registry.is_running(&stmt.name), "already running");
+        let name = inner.insert_into_stream_name(&stmt)?;
 
-        let definition = inner
+        let (stream, sink) = inner.create_stream(stmt.select.clone(), &stmt.sink, None)?;
+        inner
             .storage
-            .get_definition(&stmt.name)?
-            .ok_or_else(|| anyhow::anyhow!("not found"))?;
-        let stream_definition = match definition {
-            Definition::Stream(stream_definition) => stream_definition,
-            _ => anyhow::bail!("not stream"),
-        };
-        let sink = inner
-            .create_sink_provider(&stream_definition.to)?
-            .create()?;
-        let df = DataFrame::from_sql_select(&SqlContext(&*inner), stream_definition.select)?;
+            .create_definition(Definition::Stream(Box::new(StreamDefinition {
+                name: name.clone(),
+                select: stmt.select,
+                to: stmt.sink,
+            })))?;
+        self.spawn_stream(name, stream, sink);
 
-        let stream = if stmt.restart {
-            inner.storage.delete_stream_state_data(&stmt.name)?;
-            df.into_stream(None)?
-        } else {
-            df.into_stream(inner.storage.get_stream_state_data(&stmt.name)?)?
-        };
-        let interval = tokio::time::interval(Duration::from_secs(5 * 60));
+        create_action_result_dataset("Insert Into", true)
+    }
 
-        tokio::spawn(start_task(
-            service_inner,
-            stmt.name.clone(),
-            interval,
-            stream,
-            sink,
-        ));
+    async fn execute_analyze_source(&self, stmt: StmtAnalyzeSource) -> Result<DataSet> {
+        // the source is read without holding the lock, so other statements are not blocked
+        let source_provider = self
+            .inner
+            .lock()
+            .await
+            .source_provider_to_analyze(&stmt.name)?;
+        let statistics = collect_statistics(&source_provider).await?;
 
-        create_action_result_dataset("Start Stream", true)
+        let inner = self.inner.lock().await;
+        let mut source_definition = inner.get_source_definition(&stmt.name)?;
+        source_definition.statistics = Some(statistics.clone());
+        inner
+            .storage
+            .update_definition(Definition::Source(source_definition))?;
+
+        let scalar_string = |scalar: &Scalar| match scalar {
+            Scalar::Null => None,
+            scalar => Some(scalar.to_string()),
+        };
+        DataSet::try_new(
+            ANALYZE_SOURCE_SCHEMA.clone(),
+            vec![
+                Arc::new(
+                    statistics
+                        .iter()
+                        .map(|column| &column.name)
+                        .collect::<StringArray>(),
+                ),
+                Arc::new(StringArray::from_opt_vec(
+                    statistics
+                        .iter()
+                        .map(|column| scalar_string(&column.min))
+                        .collect(),
+                )),
+                Arc::new(StringArray::from_opt_vec(
+                    statistics
+                        .iter()
+                        .map(|column| scalar_string(&column.max))
+                        .collect(),
+                )),
+                Arc::new(Int64Array::from_vec(
+                    statistics
+                        .iter()
+                        .map(|column| column.null_count as i64)
+                        .collect(),
+                )),
+                Arc::new(Int64Array::from_vec(
+                    statistics
+                        .iter()
+                        .map(|column| column.distinct_count as i64)
+                        .collect(),
+                )),
+            ],
+        )
     }
 
     async fn execute_stop_stream(&self, stmt: StmtStopStream) -> Result<DataSet> {
         let mut inner = self.inner.lock().await;
-        anyhow::ensure!(inner.registry.is_running(&stmt.name), "not running");
+        inner.ensure_stream_running(&stmt.name)?;
         inner.registry.stop(&stmt.name);
         create_action_result_dataset("Stop Stream", true)
     }
 
     async fn execute_show(&self, stmt: StmtShow) -> Result<DataSet> {
         let inner = self.inner.lock().await;
+        validate_show(&stmt)?;
 
         match stmt.show_type {
             ShowType::Sources => {
-                let sources = inner
-                    .storage
-                    .definition_list()?
-                    .into_iter()
-                    .filter_map(|definition| match definition {
-                        Definition::Source(source_definition) => Some(source_definition),
-                        _ => None,
-                    })
-                    .collect_vec();
+                let sources = filter_show_items(
+                    inner
+                        .storage
+                        .definition_list()?
+                        .into_iter()
+                        .filter_map(|definition| match definition {
+                            Definition::Source(source_definition) => Some(source_definition),
+                            _ => None,
+                        }),
+                    &stmt,
+                    |source| &source.name,
+                    |_, _| Ordering::Equal,
+                );
                 DataSet::try_new(
                     SHOW_SOURCES_SCHEMA.clone(),
                     vec![
@@ -361,14 +700,18 @@ impl Service {
                     .iter()
                     .map(|stream| inner.storage.get_stream_state(&stream.name))
                     .collect_vec();
-                let streams = streams
-                    .into_iter()
-                    .zip(status)
-                    .filter_map(|(stream_definition, status)| match status {
-                        Ok(Some(status)) => Some((stream_definition, status)),
-                        _ => None,
-                    })
-                    .collect_vec();
+                let streams = filter_show_items(
+                    streams
+                        .into_iter()
+                        .zip(status)
+                        .filter_map(|(stream_definition, status)| match status {
+                            Ok(Some(status)) => Some((stream_definition, status)),
+                            _ => None,
+                        }),
+                    &stmt,
+                    |(stream, _)| &stream.name,
+                    |(_, a), (_, b)| a.cmp(b),
+                );
                 DataSet::try_new(
                     SHOW_STREAMS_SCHEMA.clone(),
                     vec![
@@ -394,15 +737,19 @@ impl Service {
                 )
             }
             ShowType::Sinks => {
-                let sinks = inner
-                    .storage
-                    .definition_list()?
-                    .into_iter()
-                    .filter_map(|definition| match definition {
-                        Definition::Sink(sink_definition) => Some(sink_definition),
-                        _ => None,
-                    })
-                    .collect_vec();
+                let sinks = filter_show_items(
+                    inner
+                        .storage
+                        .definition_list()?
+                        .into_iter()
+                        .filter_map(|definition| match definition {
+                            Definition::Sink(sink_definition) => Some(sink_definition),
+                            _ => None,
+                        }),
+                    &stmt,
+                    |sink| &sink.name,
+                    |_, _| Ordering::Equal,
+                );
                 DataSet::try_new(
                     SHOW_SINKS_SCHEMA.clone(),
                     vec![
@@ -411,9 +758,40 @@ impl Service {
                     ],
                 )
             }
+            ShowType::Functions => {
+                let names =
+                    filter_show_items(function_names(), &stmt, |name| name, |_, _| Ordering::Equal);
+                DataSet::try_new(
+                    SHOW_FUNCTIONS_SCHEMA.clone(),
+                    vec![Arc::new(names.iter().collect::<StringArray>())],
+                )
+            }
         }
     }
 
+    async fn execute_describe(&self, stmt: StmtDescribe) -> Result<DataSet> {
+        let schema = self.inner.lock().await.describe_definition(&stmt.name)?;
+        DataSet::try_new(
+            DESCRIBE_SCHEMA.clone(),
+            vec![
+                Arc::new(
+                    schema
+                        .fields()
+                        .iter()
+                        .map(|field| &field.name)
+                        .collect::<StringArray>(),
+                ),
+                Arc::new(
+                    schema
+                        .fields()
+                        .iter()
+                        .map(|field| field.data_type.to_string())
+                        .collect::<StringArray>(),
+                ),
+            ],
+        )
+    }
+
     async fn execute_select(
         &self,
         stmt: StmtSelect,
@@ -423,7 +801,38 @@ impl Service {
         let mut input = df.into_stream(None)?;
 
         Ok(Box::pin(async_stream::try_stream! {
-            while let Some(dataset) = input.next().await.transpose()? {
+            let mut has_output = false;
+            let mut num_datasets = 0;
+            let progress_interval = || {
+                tokio::time::interval_at(Instant::now() + PROGRESS_INTERVAL, PROGRESS_INTERVAL)
+            };
+            let mut progress = progress_interval();
+
+            loop {
+                let item = tokio::select! {
+                    dataset = input.next() => Some(dataset),
+                    _ = progress.tick() => None,
+                };
+                match item {
+                    Some(dataset) => {
+                        let dataset = match dataset {
+                            Some(dataset) => dataset?,
+                            None => break,
+                        };
+                        has_output = true;
+                        num_datasets += 1;
+                        yield ExecuteStreamItem::DataSet(dataset);
+                        if num_datasets % PROGRESS_DATASETS == 0 {
+                            progress = progress_interval();
+                            yield ExecuteStreamItem::Progress(input.metrics());
+                        }
+                    }
+                    None => yield ExecuteStreamItem::Progress(input.metrics()),
+                }
+            }
+            if !has_output {
+                // an empty result still tells the client the names of the columns
+                let dataset = DataSet::try_from_rows(input.schema(), vec![])?;
                 yield ExecuteStreamItem::DataSet(dataset);
             }
             let metrics = ExecuteStreamItem::Metrics(input.metrics());
@@ -431,3 +840,555 @@ impl Service {
         }))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use yql_core::array::ArrayExt;
+
+    use crate::storage::StreamState;
+
+    async fn show(service: &Service, sql: &str) -> Vec<String> {
+        match service.execute(sql).await.unwrap().remove(0) {
+            ExecuteResult::DataSet(dataset) => dataset
+                .column(0)
+                .unwrap()
+                .downcast_ref::<StringArray>()
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            ExecuteResult::ExecStream(_) => unreachable!(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_show_like_limit() {
+        let dir = tempfile::tempdir().unwrap();
+        let service = Service::open(dir.path()).unwrap();
+
+        for name in &["btc_1m", "btc_5m", "eth_1m", "btc1m"] {
+            service
+                .execute(&format!("create stream {} with select a from s to k", name))
+                .await
+                .unwrap();
+            service
+                .inner
+                .lock()
+                .await
+                .storage
+                .set_stream_state(name, StreamState::Created)
+                .unwrap();
+        }
+
+        assert_eq!(
+            show(&service, "show streams like 'btc\\\\_%'").await,
+            vec!["btc_1m", "btc_5m"]
+        );
+        assert_eq!(
+            show(&service, "show streams like '%1m'").await,
+            vec!["btc1m", "btc_1m", "eth_1m"]
+        );
+        assert_eq!(
+            show(&service, "show streams limit 2").await,
+            vec!["btc1m", "btc_1m"]
+        );
+        assert_eq!(
+            show(&service, "show streams like 'btc%' limit 1").await,
+            vec!["btc1m"]
+        );
+        assert!(show(&service, "show streams like 'xrp%'").await.is_empty());
+        assert!(show(&service, "show sources like 'btc%'").await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_show_order_by() {
+        let dir = tempfile::tempdir().unwrap();
+        let service = Service::open(dir.path()).unwrap();
+
+        for (name, state) in [
+            ("eth_1m", StreamState::Stop),
+            ("btc_1m", StreamState::Started),
+            ("btc_5m", StreamState::Created),
+            ("btc1m", StreamState::Started),
+        ]
+        .iter()
+        .cloned()
+        {
+            service
+                .execute(&format!("create stream {} with select a from s to k", name))
+                .await
+                .unwrap();
+            service
+                .inner
+                .lock()
+                .await
+                .storage
+                .set_stream_state(name, state)
+                .unwrap();
+        }
+
+        assert_eq!(
+            show(&service, "show streams").await,
+            vec!["btc1m", "btc_1m", "btc_5m", "eth_1m"]
+        );
+        assert_eq!(
+            show(&service, "show streams order by name").await,
+            vec!["btc1m", "btc_1m", "btc_5m", "eth_1m"]
+        );
+        assert_eq!(
+            show(&service, "show streams order by status").await,
+            vec!["btc_5m", "btc1m", "btc_1m", "eth_1m"]
+        );
+        assert_eq!(
+            show(&service, "show streams like 'btc%' order by status limit 2").await,
+            vec!["btc_5m", "btc1m"]
+        );
+        assert!(service.execute("show sinks order by status").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_show_functions() {
+        let dir = tempfile::tempdir().unwrap();
+        let service = Service::open(dir.path()).unwrap();
+
+        assert_eq!(
+            show(&service, "show functions like 'regexp%'").await,
+            vec!["regexp_match", "regexp_replace"]
+        );
+        assert_eq!(
+            show(&service, "show functions like 'f.ma'").await,
+            vec!["f.ma"]
+        );
+        assert!(show(&service, "show functions").await.len() > 10);
+    }
+
+    #[tokio::test]
+    async fn test_describe() {
+        let dir = tempfile::tempdir().unwrap();
+        let service = Service::open(dir.path()).unwrap();
+
+        service
+            .execute("create source s (a int64, b string) with 'file:///tmp/s.csv'")
+            .await
+            .unwrap();
+        service
+            .execute("create stream t with select a + 1 as c, b from s to k")
+            .await
+            .unwrap();
+        service
+            .execute("create sink k with 'file:///tmp/k.csv'")
+            .await
+            .unwrap();
+
+        let describe = |sql: &'static str| {
+            let service = service.clone();
+            async move {
+                match service.execute(sql).await.unwrap().remove(0) {
+                    ExecuteResult::DataSet(dataset) => dataset.display_csv().unwrap(),
+                    ExecuteResult::ExecStream(_) => unreachable!(),
+                }
+            }
+        };
+        assert_eq!(
+            describe("describe s").await,
+            "name,type\na,int64\nb,string\n"
+        );
+        assert_eq!(
+            describe("describe t").await,
+            "name,type\nc,int64\nb,string\n"
+        );
+
+        assert!(service.execute("describe k").await.is_err());
+        assert!(service.execute("describe x").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_infer_json_source_schema() {
+        let dir = tempfile::tempdir().unwrap();
+        let data_dir = tempfile::tempdir().unwrap();
+        let service = Service::open(dir.path()).unwrap();
+
+        let path = data_dir.path().join("data.json");
+        std::fs::write(
+            &path,
+            "{\"a\": 1, \"b\": \"x\"}\n{\"a\": 2.5, \"c\": true}\n",
+        )
+        .unwrap();
+        service
+            .execute(&format!(
+                "create source s () with 'json://{}'",
+                path.display()
+            ))
+            .await
+            .unwrap();
+
+        match service.execute("describe s").await.unwrap().remove(0) {
+            ExecuteResult::DataSet(dataset) => assert_eq!(
+                dataset.display_csv().unwrap(),
+                "name,type\na,float64\nb,string\nc,boolean\n"
+            ),
+            ExecuteResult::ExecStream(_) => unreachable!(),
+        }
+
+        assert!(service
+            .validate(&format!(
+                "create source t () with 'json://{}'",
+                data_dir.path().join("missing.json").display()
+            ))
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_insert_into() {
+        let dir = tempfile::tempdir().unwrap();
+        let data_dir = tempfile::tempdir().unwrap();
+        let service = Service::open(dir.path()).unwrap();
+
+        let path = data_dir.path().join("data.csv");
+        std::fs::write(&path, "1\n2\n3\n").unwrap();
+        let out_dir = data_dir.path().join("out");
+        service
+            .execute(&format!(
+                "create source s (a int64) with 'file://{}'",
+                path.display()
+            ))
+            .await
+            .unwrap();
+        service
+            .execute(&format!(
+                "create sink k with 'file://{}'",
+                out_dir.display()
+            ))
+            .await
+            .unwrap();
+
+        service
+            .execute("insert into k select a from s")
+            .await
+            .unwrap();
+        service
+            .execute("insert into k as t1 select a from s where a > 1")
+            .await
+            .unwrap();
+        assert!(service
+            .execute("insert into k as t1 select a from s")
+            .await
+            .is_err());
+        assert!(service
+            .execute("insert into x select a from s")
+            .await
+            .is_err());
+        assert!(!service
+            .inner
+            .lock()
+            .await
+            .storage
+            .definition_exists("insert_x_1")
+            .unwrap());
+
+        for name in &["insert_k_1", "t1"] {
+            tokio::time::timeout(Duration::from_secs(10), async {
+                loop {
+                    let state = service
+                        .inner
+                        .lock()
+                        .await
+                        .storage
+                        .get_stream_state(name)
+                        .unwrap();
+                    if state == Some(StreamState::Stop) {
+                        break;
+                    }
+                    tokio::time::sleep(Duration::from_millis(10)).await;
+                }
+            })
+            .await
+            .unwrap();
+        }
+        assert_eq!(
+            show(&service, "show streams").await,
+            vec!["insert_k_1", "t1"]
+        );
+
+        // both streams write to the files of the sink
+        let mut lines = std::fs::read_dir(&out_dir)
+            .unwrap()
+            .flat_map(|entry| {
+                std::fs::read_to_string(entry.unwrap().path())
+                    .unwrap()
+                    .lines()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        lines.sort();
+        assert_eq!(
+            lines,
+            vec![
+                "{\"a\":1}",
+                "{\"a\":2}",
+                "{\"a\":2}",
+                "{\"a\":3}",
+                "{\"a\":3}"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_multiple_statements() {
+        let dir = tempfile::tempdir().unwrap();
+        let service = Service::open(dir.path()).unwrap();
+
+        let results = service
+            .execute(
+                "create source s (a int64) with 'file:///tmp/data.csv';
+                create sink k with 'console://stdout';
+                create stream t with select a from s to k;",
+            )
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 3);
+        assert_eq!(show(&service, "show sources").await, vec!["s"]);
+
+        assert_eq!(
+            service
+                .execute(
+                    "create sink k2 with 'console://stdout'; create sink k with 'console://stdout'"
+                )
+                .await
+                .err()
+                .unwrap()
+                .to_string(),
+            "statement 2 failed: already exists"
+        );
+        assert_eq!(show(&service, "show sinks").await, vec!["k", "k2"]);
+
+        assert_eq!(
+            service
+                .execute("select a from s; delete sink k2")
+                .await
+                .err()
+                .unwrap()
+                .to_string(),
+            "only the last statement can be a select."
+        );
+    }
+
+    #[tokio::test]
+    async fn test_validate() {
+        let dir = tempfile::tempdir().unwrap();
+        let service = Service::open(dir.path()).unwrap();
+
+        service
+            .validate("create source s (a int64) with 'file:///tmp/data.csv'")
+            .await
+            .unwrap();
+        assert!(service
+            .validate("create source s (a int64, a string) with 'file:///tmp/data.csv'")
+            .await
+            .is_err());
+        assert!(show(&service, "show sources").await.is_empty());
+
+        service
+            .execute(
+                "create source s (a int64, b string) with 'file:///tmp/data.csv';
+                create sink k with 'console://stdout'",
+            )
+            .await
+            .unwrap();
+        service.validate("select a + 1 from s").await.unwrap();
+        service
+            .validate("create stream t with select a from s to k; select b from s")
+            .await
+            .unwrap();
+        assert!(service.validate("select a + b from s").await.is_err());
+        assert!(service.validate("select c from s").await.is_err());
+        assert_eq!(
+            service
+                .validate(
+                    "create sink k2 with 'console://stdout'; create sink k with 'console://stdout'"
+                )
+                .await
+                .err()
+                .unwrap()
+                .to_string(),
+            "statement 2 failed: already exists"
+        );
+        assert!(service
+            .validate("insert into k select a + b from s")
+            .await
+            .is_err());
+        assert!(!service
+            .inner
+            .lock()
+            .await
+            .storage
+            .definition_exists("insert_k_1")
+            .unwrap());
+
+        assert!(matches!(
+            service
+                .execute("validate create stream t with select a from s to k")
+                .await
+                .unwrap()
+                .remove(0),
+            ExecuteResult::DataSet(_)
+        ));
+        assert!(service
+            .execute("validate select a + b from s")
+            .await
+            .is_err());
+        assert_eq!(show(&service, "show sources").await, vec!["s"]);
+        assert_eq!(show(&service, "show sinks").await, vec!["k"]);
+    }
+
+    #[tokio::test]
+    async fn test_analyze_source() {
+        let dir = tempfile::tempdir().unwrap();
+        let data_dir = tempfile::tempdir().unwrap();
+        let service = Service::open(dir.path()).unwrap();
+
+        let path = data_dir.path().join("data.csv");
+        std::fs::write(&path, "3,x\n5,y\n-2,x\n7,x\n3,z\n").unwrap();
+        service
+            .execute(&format!(
+                "create source s (a int64, b string) with 'file://{}'",
+                path.display()
+            ))
+            .await
+            .unwrap();
+
+        let dataset = match service.execute("analyze s").await.unwrap().remove(0) {
+            ExecuteResult::DataSet(dataset) => dataset,
+            ExecuteResult::ExecStream(_) => unreachable!(),
+        };
+        assert_eq!(
+            dataset,
+            DataSet::try_from_rows(
+                ANALYZE_SOURCE_SCHEMA.clone(),
+                vec![
+                    vec![
+                        Scalar::String("a".into()),
+                        Scalar::String("-2".into()),
+                        Scalar::String("7".into()),
+                        Scalar::Int64(0),
+                        Scalar::Int64(4),
+                    ],
+                    vec![
+                        Scalar::String("b".into()),
+                        Scalar::String("x".into()),
+                        Scalar::String("z".into()),
+                        Scalar::Int64(0),
+                        Scalar::Int64(3),
+                    ],
+                ]
+            )
+            .unwrap()
+        );
+
+        let source_definition = service
+            .inner
+            .lock()
+            .await
+            .get_source_definition("s")
+            .unwrap();
+        let statistics = source_definition.statistics.unwrap();
+        assert_eq!(statistics[0].min, Scalar::Int64(-2));
+        assert_eq!(statistics[0].max, Scalar::Int64(7));
+        assert_eq!(statistics[0].null_count, 0);
+
+        assert!(service.execute("analyze t").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_select_empty_result() {
+        let dir = tempfile::tempdir().unwrap();
+        let data_dir = tempfile::tempdir().unwrap();
+        let service = Service::open(dir.path()).unwrap();
+
+        let path = data_dir.path().join("data.csv");
+        std::fs::write(&path, "1,x\n2,y\n").unwrap();
+        service
+            .execute(&format!(
+                "create source s (a int64, b string) with 'file://{}'",
+                path.display()
+            ))
+            .await
+            .unwrap();
+
+        let mut stream = match service
+            .execute("select a, b from s where a > 10")
+            .await
+            .unwrap()
+            .remove(0)
+        {
+            ExecuteResult::ExecStream(stream) => stream,
+            ExecuteResult::DataSet(_) => unreachable!(),
+        };
+        match stream.next().await.unwrap().unwrap() {
+            ExecuteStreamItem::DataSet(dataset) => {
+                assert!(dataset.is_empty());
+                let names = dataset
+                    .schema()
+                    .fields()
+                    .iter()
+                    .map(|field| field.name.clone())
+                    .collect::<Vec<_>>();
+                assert_eq!(names, vec!["a", "b"]);
+            }
+            _ => panic!("expected an empty dataset"),
+        }
+        assert!(matches!(
+            stream.next().await.unwrap().unwrap(),
+            ExecuteStreamItem::Metrics(_)
+        ));
+        assert!(stream.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_select_progress() {
+        let dir = tempfile::tempdir().unwrap();
+        let data_dir = tempfile::tempdir().unwrap();
+        let service = Service::open(dir.path()).unwrap();
+
+        let path = data_dir.path().join("data.csv");
+        std::fs::write(
+            &path,
+            (0..150).map(|n| format!("{}\n", n)).collect::<String>(),
+        )
+        .unwrap();
+        service
+            .execute(&format!(
+                "create source s (a int64) with 'file://{}?batch_size=1'",
+                path.display()
+            ))
+            .await
+            .unwrap();
+
+        let mut stream = match service.execute("select a from s").await.unwrap().remove(0) {
+            ExecuteResult::ExecStream(stream) => stream,
+            ExecuteResult::DataSet(_) => unreachable!(),
+        };
+        let mut num_rows = 0;
+        let mut progress = Vec::new();
+        let metrics = loop {
+            match stream.next().await.unwrap().unwrap() {
+                ExecuteStreamItem::DataSet(dataset) => num_rows += dataset.len(),
+                ExecuteStreamItem::Progress(metrics) => {
+                    assert!(metrics.start_time.is_some());
+                    assert!(metrics.end_time.is_none());
+                    assert_eq!(metrics.num_output_rows, num_rows);
+                    progress.push(metrics.num_output_rows);
+                }
+                ExecuteStreamItem::Metrics(metrics) => break metrics,
+            }
+        };
+        assert!(progress.contains(&100));
+        assert_eq!(num_rows, 150);
+        assert_eq!(metrics.num_input_rows, 150);
+        assert_eq!(metrics.num_output_rows, 150);
+        assert!(metrics.end_time.is_some());
+        assert!(stream.next().await.is_none());
+    }
+}
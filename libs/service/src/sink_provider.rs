@@ -1,4 +1,4 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use url::Url;
 use yql_core::{sinks, SinkProvider};
 
@@ -8,8 +8,81 @@ pub fn create_sink_provider(definition: &SinkDefinition) -> Result<Box<dyn SinkP
     let url: Url = definition.uri.parse()?;
 
     if url.scheme().eq_ignore_ascii_case("console") {
-        return Ok(Box::new(sinks::Console));
+        return Ok(Box::new(sinks::Console {
+            format: definition.format,
+        }));
+    }
+
+    // file:///tmp/out?rows=1000
+    if url.scheme().eq_ignore_ascii_case("file") {
+        let path = url
+            .to_file_path()
+            .map_err(|_| anyhow::anyhow!("invalid file sink path: '{}'", definition.uri))?;
+        let mut options =
+            serde_qs::from_str::<sinks::file::Options>(url.query().unwrap_or_default())
+                .with_context(|| "failed to parse file sink options")?;
+        if let Some(format) = definition.format {
+            options.format = format;
+        }
+        return Ok(Box::new(sinks::file::Provider::new(options, path)));
     }
 
     anyhow::bail!("unsupported sink: '{}'", definition.uri)
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use yql_core::array::DataType;
+    use yql_core::dataset::{CsvOptions, DataFormat, DataSet, Field, Schema};
+
+    use super::*;
+
+    fn create_dataset(data: &[u8]) -> DataSet {
+        let schema = Arc::new(Schema::try_new(vec![Field::new("a", DataType::Int64)]).unwrap());
+        DataSet::from_csv_slice(schema, CsvOptions::default(), data).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_file_sink() {
+        let dir = tempfile::tempdir().unwrap();
+        let definition = |query: &str, format| SinkDefinition {
+            name: "out".to_string(),
+            uri: format!("file://{}?{}", dir.path().display(), query),
+            format,
+        };
+
+        let provider = create_sink_provider(&definition("rows=1", None)).unwrap();
+        assert_eq!(provider.provider_name(), "file");
+        let mut sink = provider.create().unwrap();
+        sink.send(create_dataset(b"1\n")).await.unwrap();
+        sink.send(create_dataset(b"2\n")).await.unwrap();
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("out-0.json")).unwrap(),
+            "{\"a\":1}\n"
+        );
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("out-1.json")).unwrap(),
+            "{\"a\":2}\n"
+        );
+
+        // the format of the definition takes precedence over the one of the uri
+        let provider = create_sink_provider(&definition(
+            "format=Json&filename=part-{seq}.csv",
+            Some(DataFormat::Csv {
+                delimiter: b',',
+                header: true,
+            }),
+        ))
+        .unwrap();
+        let mut sink = provider.create().unwrap();
+        sink.send(create_dataset(b"3\n")).await.unwrap();
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("part-0.csv")).unwrap(),
+            "a\n3\n"
+        );
+
+        assert!(create_sink_provider(&definition("rows=x", None)).is_err());
+    }
+}
@@ -2,11 +2,26 @@ use std::sync::Arc;
 
 use anyhow::{Context, Result};
 use url::Url;
+use yql_core::dataset::{Field, Schema, SchemaRef};
 use yql_core::sql::SqlSourceProvider;
 use yql_core::{sources, SourceProviderWrapper};
 
 use crate::storage::SourceDefinition;
 
+/// The schema of a new source, a json source declared without fields infers it from the first
+/// lines of its file.
+pub fn source_schema(uri: &str, fields: Vec<Field>) -> Result<SchemaRef> {
+    if fields.is_empty() {
+        if let Ok(url) = uri.parse::<Url>() {
+            if url.scheme() == "json" {
+                return sources::json::Provider::infer_schema(url.path())
+                    .with_context(|| format!("failed to infer the schema of source: {}", uri));
+            }
+        }
+    }
+    Ok(Arc::new(Schema::try_new(fields)?))
+}
+
 pub fn create_source_provider(definition: &SourceDefinition) -> Result<SqlSourceProvider> {
     let url: Url = definition
         .uri
@@ -27,5 +42,114 @@ pub fn create_source_provider(definition: &SourceDefinition) -> Result<SqlSource
         }
     }
 
+    if url.scheme() == "json" {
+        let options = serde_qs::from_str::<sources::json::Options>(url.query().unwrap_or_default())
+            .with_context(|| "failed to parse json options")?;
+        let source_provider =
+            sources::json::Provider::new(options, definition.schema.clone(), url.path());
+        return Ok(SqlSourceProvider {
+            source_provider: Arc::new(SourceProviderWrapper(source_provider)),
+            time_expr: definition.time_expr.clone(),
+        });
+    }
+
+    // stdin://?format=Json&batch_size=100
+    if url.scheme() == "stdin" {
+        let options =
+            serde_qs::from_str::<sources::stdin::Options>(url.query().unwrap_or_default())
+                .with_context(|| "failed to parse stdin options")?;
+        let source_provider = sources::stdin::Provider::new(options, definition.schema.clone());
+        return Ok(SqlSourceProvider {
+            source_provider: Arc::new(SourceProviderWrapper(source_provider)),
+            time_expr: definition.time_expr.clone(),
+        });
+    }
+
+    if url.scheme() == "kafka" {
+        let source_provider =
+            sources::kafka::Provider::new(kafka_options(&url)?, definition.schema.clone());
+        return Ok(SqlSourceProvider {
+            source_provider: Arc::new(SourceProviderWrapper(source_provider)),
+            time_expr: definition.time_expr.clone(),
+        });
+    }
+
     anyhow::bail!("unsupported source: '{}'", definition.uri)
 }
+
+/// Parses `kafka://host:port/topic1,topic2?group_id=yql`, the brokers and topics in the query
+/// take precedence over the ones in the uri.
+fn kafka_options(url: &Url) -> Result<sources::kafka::Options> {
+    let mut options =
+        serde_qs::from_str::<sources::kafka::Options>(url.query().unwrap_or_default())
+            .with_context(|| "failed to parse kafka options")?;
+    if options.servers.is_empty() {
+        options.servers = match (url.host_str(), url.port()) {
+            (Some(host), Some(port)) => format!("{}:{}", host, port),
+            (Some(host), None) => host.to_string(),
+            (None, _) => anyhow::bail!("the brokers of the kafka source are required"),
+        };
+    }
+    if options.topics.is_empty() {
+        options.topics = url
+            .path()
+            .trim_start_matches('/')
+            .split(',')
+            .filter(|topic| !topic.is_empty())
+            .map(ToString::to_string)
+            .collect();
+    }
+    Ok(options)
+}
+
+#[cfg(test)]
+mod tests {
+    use yql_core::dataset::DataFormat;
+
+    use super::*;
+
+    fn parse(uri: &str) -> Result<sources::kafka::Options> {
+        kafka_options(&uri.parse()?)
+    }
+
+    #[test]
+    fn test_kafka_options() {
+        let options = parse("kafka://localhost:9092/a,b?group_id=yql").unwrap();
+        assert_eq!(options.servers, "localhost:9092");
+        assert_eq!(options.topics, vec!["a".to_string(), "b".to_string()]);
+        assert_eq!(options.group_id, "yql");
+        assert_eq!(options.format, DataFormat::Json);
+
+        let options = parse("kafka://localhost/a?group_id=yql").unwrap();
+        assert_eq!(options.servers, "localhost");
+        assert_eq!(options.topics, vec!["a".to_string()]);
+
+        // the query takes precedence over the uri
+        let options = parse(
+            "kafka://localhost:9092/a?group_id=yql&servers=k1:9092,k2:9092&topics[0]=c&topics[1]=d",
+        )
+        .unwrap();
+        assert_eq!(options.servers, "k1:9092,k2:9092");
+        assert_eq!(options.topics, vec!["c".to_string(), "d".to_string()]);
+
+        let options =
+            parse("kafka://localhost:9092/a?group_id=yql&format[Csv][delimiter]=59&format[Csv][header]=false")
+                .unwrap();
+        assert_eq!(
+            options.format,
+            DataFormat::Csv {
+                delimiter: b';',
+                header: false
+            }
+        );
+
+        assert_eq!(
+            parse("kafka:///a?group_id=yql").err().unwrap().to_string(),
+            "the brokers of the kafka source are required"
+        );
+        assert_eq!(
+            parse("kafka://localhost:9092/a").err().unwrap().to_string(),
+            "failed to parse kafka options"
+        );
+    }
+}
@@ -1,17 +1,14 @@
-use chrono_tz::Tz;
 use nom::branch::alt;
 use nom::bytes::complete::tag_no_case;
-use nom::character::complete::char;
-use nom::combinator::{cut, eof, map, map_res, opt, value};
+use nom::character::complete::{char, digit1};
+use nom::combinator::{eof, map, map_res, opt, value};
 use nom::error::context;
-use nom::multi::separated_list0;
-use nom::sequence::{delimited, terminated, tuple};
-use nom::IResult;
-use yql_core::array::DataType;
-use yql_core::dataset::Field;
+use nom::multi::{separated_list0, separated_list1};
+use nom::sequence::{delimited, preceded, terminated, tuple};
+use yql_core::dataset::{DataFormat, Field};
 use yql_core::expr::Expr;
 use yql_core::sql::ast::Select;
-use yql_core::sql::parser::{expr, name, select, sp, string};
+use yql_core::sql::parser::{data_type, expr, name, select, sp, string, IResult};
 
 #[derive(Debug, PartialEq)]
 pub struct StmtCreateStream {
@@ -20,6 +17,13 @@ pub struct StmtCreateStream {
     pub to: String,
 }
 
+#[derive(Debug, PartialEq)]
+pub struct StmtInsertInto {
+    pub sink: String,
+    pub name: Option<String>,
+    pub select: Select,
+}
+
 #[derive(Debug, PartialEq)]
 pub struct StmtCreateSource {
     pub name: String,
@@ -31,11 +35,15 @@ pub struct StmtCreateSource {
 #[derive(Debug, PartialEq, Copy, Clone)]
 pub enum OutputFormat {
     Json,
+    Csv { delimiter: u8, header: bool },
 }
 
-impl Default for OutputFormat {
-    fn default() -> Self {
-        OutputFormat::Json
+impl From<OutputFormat> for DataFormat {
+    fn from(format: OutputFormat) -> Self {
+        match format {
+            OutputFormat::Json => DataFormat::Json,
+            OutputFormat::Csv { delimiter, header } => DataFormat::Csv { delimiter, header },
+        }
     }
 }
 
@@ -43,7 +51,8 @@ impl Default for OutputFormat {
 pub struct StmtCreateSink {
     pub name: String,
     pub uri: String,
-    pub format: OutputFormat,
+    /// The format of the rows, the sink decides it if not specified.
+    pub format: Option<OutputFormat>,
 }
 
 #[derive(Debug, PartialEq)]
@@ -51,6 +60,16 @@ pub struct StmtDeleteSource {
     pub name: String,
 }
 
+#[derive(Debug, PartialEq)]
+pub struct StmtAnalyzeSource {
+    pub name: String,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct StmtDescribe {
+    pub name: String,
+}
+
 #[derive(Debug, PartialEq)]
 pub struct StmtDeleteStream {
     pub name: String,
@@ -77,11 +96,27 @@ pub enum ShowType {
     Sources,
     Streams,
     Sinks,
+    Functions,
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum ShowOrderBy {
+    Name,
+    Status,
+}
+
+impl Default for ShowOrderBy {
+    fn default() -> Self {
+        ShowOrderBy::Name
+    }
 }
 
 #[derive(Debug, PartialEq)]
 pub struct StmtShow {
     pub show_type: ShowType,
+    pub like: Option<String>,
+    pub order_by: ShowOrderBy,
+    pub limit: Option<usize>,
 }
 
 #[derive(Debug, PartialEq)]
@@ -101,39 +136,11 @@ pub enum Stmt {
     StopStream(Box<StmtStopStream>),
     Show(Box<StmtShow>),
     Select(Box<StmtSelect>),
-}
-
-fn timezone(input: &str) -> IResult<&str, Tz> {
-    cut(map_res(string, |name| name.parse::<Tz>()))(input)
-}
-
-fn data_type(input: &str) -> IResult<&str, DataType> {
-    let timezone = map(
-        tuple((tag_no_case("timezone"), sp, timezone)),
-        |(_, _, tz)| tz,
-    );
-
-    context(
-        "data_type",
-        alt((
-            value(DataType::Int8, tag_no_case("int8")),
-            value(DataType::Int16, tag_no_case("int16")),
-            value(DataType::Int32, tag_no_case("int32")),
-            value(DataType::Int64, tag_no_case("int64")),
-            value(DataType::Float32, tag_no_case("float32")),
-            value(DataType::Float64, tag_no_case("float64")),
-            value(DataType::Boolean, tag_no_case("boolean")),
-            value(DataType::String, tag_no_case("string")),
-            map(
-                tuple((
-                    tag_no_case("timestamp"),
-                    sp,
-                    opt(delimited(sp, timezone, sp)),
-                )),
-                |(_, _, tz)| DataType::Timestamp(tz),
-            ),
-        )),
-    )(input)
+    InsertInto(Box<StmtInsertInto>),
+    AnalyzeSource(Box<StmtAnalyzeSource>),
+    Describe(Box<StmtDescribe>),
+    /// Checks the statement without executing it.
+    Validate(Box<Stmt>),
 }
 
 fn stmt_create_source(input: &str) -> IResult<&str, StmtCreateSource> {
@@ -206,10 +213,53 @@ fn stmt_create_stream(input: &str) -> IResult<&str, StmtCreateStream> {
     )(input)
 }
 
+fn stmt_insert_into(input: &str) -> IResult<&str, StmtInsertInto> {
+    let stream_name = map(
+        tuple((tag_no_case("as"), sp, name, sp)),
+        |(_, _, name, _)| name,
+    );
+
+    context(
+        "stmt_insert_into",
+        map(
+            tuple((
+                tag_no_case("insert"),
+                sp,
+                tag_no_case("into"),
+                sp,
+                name,
+                sp,
+                opt(stream_name),
+                select,
+            )),
+            |(_, _, _, _, sink, _, name, select)| StmtInsertInto { sink, name, select },
+        ),
+    )(input)
+}
+
 fn output_format(input: &str) -> IResult<&str, OutputFormat> {
+    let delimiter = map_res(
+        tuple((tag_no_case("delimiter"), sp, string)),
+        |(_, _, delimiter)| match delimiter.as_bytes() {
+            [delimiter] => Ok(*delimiter),
+            _ => Err("the delimiter must be a single byte"),
+        },
+    );
+    let csv = map(
+        tuple((
+            tag_no_case("csv"),
+            opt(preceded(sp, delimiter)),
+            opt(preceded(sp, tag_no_case("header"))),
+        )),
+        |(_, delimiter, header)| OutputFormat::Csv {
+            delimiter: delimiter.unwrap_or(b','),
+            header: header.is_some(),
+        },
+    );
+
     context(
         "output_format",
-        value(OutputFormat::Json, tag_no_case("json")),
+        alt((value(OutputFormat::Json, tag_no_case("json")), csv)),
     )(input)
 }
 
@@ -235,11 +285,7 @@ fn stmt_create_sink(input: &str) -> IResult<&str, StmtCreateSink> {
                 sp,
                 opt(format),
             )),
-            |(_, _, _, _, name, _, _, _, uri, _, format)| StmtCreateSink {
-                name,
-                uri,
-                format: format.unwrap_or_default(),
-            },
+            |(_, _, _, _, name, _, _, _, uri, _, format)| StmtCreateSink { name, uri, format },
         ),
     )(input)
 }
@@ -254,6 +300,25 @@ fn stmt_delete_source(input: &str) -> IResult<&str, StmtDeleteSource> {
     )(input)
 }
 
+fn stmt_analyze_source(input: &str) -> IResult<&str, StmtAnalyzeSource> {
+    context(
+        "stmt_analyze_source",
+        map(tuple((tag_no_case("analyze"), sp, name)), |(_, _, name)| {
+            StmtAnalyzeSource { name }
+        }),
+    )(input)
+}
+
+fn stmt_describe(input: &str) -> IResult<&str, StmtDescribe> {
+    context(
+        "stmt_describe",
+        map(
+            tuple((tag_no_case("describe"), sp, name)),
+            |(_, _, name)| StmtDescribe { name },
+        ),
+    )(input)
+}
+
 fn stmt_delete_stream(input: &str) -> IResult<&str, StmtDeleteStream> {
     context(
         "stmt_delete_stream",
@@ -308,54 +373,119 @@ fn stmt_show_stream(input: &str) -> IResult<&str, StmtShow> {
         value(ShowType::Sources, tag_no_case("sources")),
         value(ShowType::Streams, tag_no_case("streams")),
         value(ShowType::Sinks, tag_no_case("sinks")),
+        value(ShowType::Functions, tag_no_case("functions")),
     ));
 
+    let like = map(
+        tuple((tag_no_case("like"), sp, string)),
+        |(_, _, pattern)| pattern,
+    );
+    let order_by = map(
+        tuple((
+            tag_no_case("order"),
+            sp,
+            tag_no_case("by"),
+            sp,
+            alt((
+                value(ShowOrderBy::Name, tag_no_case("name")),
+                value(ShowOrderBy::Status, tag_no_case("status")),
+            )),
+        )),
+        |(_, _, _, _, order_by)| order_by,
+    );
+    let limit = map(
+        tuple((tag_no_case("limit"), sp, map_res(digit1, str::parse))),
+        |(_, _, limit)| limit,
+    );
+
     context(
         "stmt_show_stream",
         map(
-            tuple((tag_no_case("show"), sp, show_type)),
-            |(_, _, show_type)| StmtShow { show_type },
+            tuple((
+                tag_no_case("show"),
+                sp,
+                show_type,
+                opt(preceded(sp, like)),
+                opt(preceded(sp, order_by)),
+                opt(preceded(sp, limit)),
+            )),
+            |(_, _, show_type, like, order_by, limit)| StmtShow {
+                show_type,
+                like,
+                order_by: order_by.unwrap_or_default(),
+                limit,
+            },
         ),
     )(input)
 }
 
-pub fn stmt(input: &str) -> IResult<&str, Stmt> {
+fn stmt_validate(input: &str) -> IResult<&str, Stmt> {
+    context(
+        "stmt_validate",
+        map(
+            tuple((tag_no_case("validate"), sp, single_stmt)),
+            |(_, _, stmt)| stmt,
+        ),
+    )(input)
+}
+
+fn single_stmt(input: &str) -> IResult<&str, Stmt> {
     context(
         "stmt",
+        alt((
+            map(delimited(sp, stmt_validate, sp), |stmt| {
+                Stmt::Validate(Box::new(stmt))
+            }),
+            map(delimited(sp, stmt_create_source, sp), |stmt| {
+                Stmt::CreateSource(Box::new(stmt))
+            }),
+            map(delimited(sp, stmt_create_stream, sp), |stmt| {
+                Stmt::CreateStream(Box::new(stmt))
+            }),
+            map(delimited(sp, stmt_create_sink, sp), |stmt| {
+                Stmt::CreateSink(Box::new(stmt))
+            }),
+            map(delimited(sp, stmt_delete_source, sp), |stmt| {
+                Stmt::DeleteSource(Box::new(stmt))
+            }),
+            map(delimited(sp, stmt_delete_stream, sp), |stmt| {
+                Stmt::DeleteStream(Box::new(stmt))
+            }),
+            map(delimited(sp, stmt_delete_sink, sp), |stmt| {
+                Stmt::DeleteSink(Box::new(stmt))
+            }),
+            map(delimited(sp, stmt_start_stream, sp), |stmt| {
+                Stmt::StartStream(Box::new(stmt))
+            }),
+            map(delimited(sp, stmt_stop_stream, sp), |stmt| {
+                Stmt::StopStream(Box::new(stmt))
+            }),
+            map(delimited(sp, stmt_show_stream, sp), |stmt| {
+                Stmt::Show(Box::new(stmt))
+            }),
+            map(delimited(sp, stmt_insert_into, sp), |stmt| {
+                Stmt::InsertInto(Box::new(stmt))
+            }),
+            map(delimited(sp, stmt_analyze_source, sp), |stmt| {
+                Stmt::AnalyzeSource(Box::new(stmt))
+            }),
+            map(delimited(sp, stmt_describe, sp), |stmt| {
+                Stmt::Describe(Box::new(stmt))
+            }),
+            map(delimited(sp, select, sp), |select| {
+                Stmt::Select(Box::new(StmtSelect { select }))
+            }),
+        )),
+    )(input)
+}
+
+/// Parses statements separated by semicolons, the last statement can be followed by a semicolon.
+pub fn stmts(input: &str) -> IResult<&str, Vec<Stmt>> {
+    context(
+        "stmts",
         terminated(
-            alt((
-                map(delimited(sp, stmt_create_source, sp), |stmt| {
-                    Stmt::CreateSource(Box::new(stmt))
-                }),
-                map(delimited(sp, stmt_create_stream, sp), |stmt| {
-                    Stmt::CreateStream(Box::new(stmt))
-                }),
-                map(delimited(sp, stmt_create_sink, sp), |stmt| {
-                    Stmt::CreateSink(Box::new(stmt))
-                }),
-                map(delimited(sp, stmt_delete_source, sp), |stmt| {
-                    Stmt::DeleteSource(Box::new(stmt))
-                }),
-                map(delimited(sp, stmt_delete_stream, sp), |stmt| {
-                    Stmt::DeleteStream(Box::new(stmt))
-                }),
-                map(delimited(sp, stmt_delete_sink, sp), |stmt| {
-                    Stmt::DeleteSink(Box::new(stmt))
-                }),
-                map(delimited(sp, stmt_start_stream, sp), |stmt| {
-                    Stmt::StartStream(Box::new(stmt))
-                }),
-                map(delimited(sp, stmt_stop_stream, sp), |stmt| {
-                    Stmt::StopStream(Box::new(stmt))
-                }),
-                map(delimited(sp, stmt_show_stream, sp), |stmt| {
-                    Stmt::Show(Box::new(stmt))
-                }),
-                map(delimited(sp, select, sp), |select| {
-                    Stmt::Select(Box::new(StmtSelect { select }))
-                }),
-            )),
-            eof,
+            separated_list1(char(';'), single_stmt),
+            tuple((opt(char(';')), sp, eof)),
         ),
     )(input)
 }
@@ -363,32 +493,12 @@ pub fn stmt(input: &str) -> IResult<&str, Stmt> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use yql_core::array::DataType;
     use yql_core::sql::ast::{Source, SourceFrom};
+    use yql_core::Emit;
 
-    #[test]
-    fn test_time_zone() {
-        assert_eq!(timezone(r#""UTC""#), Ok(("", chrono_tz::UTC)));
-        assert_eq!(
-            timezone(r#""Asia/Shanghai""#),
-            Ok(("", chrono_tz::Asia::Shanghai))
-        );
-    }
-
-    #[test]
-    fn test_data_type() {
-        assert_eq!(data_type("int8"), Ok(("", DataType::Int8)));
-        assert_eq!(data_type("int16"), Ok(("", DataType::Int16)));
-        assert_eq!(data_type("int32"), Ok(("", DataType::Int32)));
-        assert_eq!(data_type("int64"), Ok(("", DataType::Int64)));
-        assert_eq!(data_type("float32"), Ok(("", DataType::Float32)));
-        assert_eq!(data_type("float64"), Ok(("", DataType::Float64)));
-        assert_eq!(data_type("boolean"), Ok(("", DataType::Boolean)));
-        assert_eq!(data_type("timestamp"), Ok(("", DataType::Timestamp(None))));
-
-        assert_eq!(
-            data_type("timestamp timezone \"UTC\""),
-            Ok(("", DataType::Timestamp(Some(chrono_tz::UTC))))
-        );
+    fn stmt(input: &str) -> IResult<&str, Stmt> {
+        terminated(single_stmt, eof)(input)
     }
 
     #[test]
@@ -471,8 +581,12 @@ mod tests {
                         where_clause: None,
                         having_clause: None,
                         group_clause: None,
+                        pivot: None,
                         window: None,
-                        watermark: None
+                        watermark: None,
+                        emit: Emit::Final,
+                        order_by: vec![],
+                        limit: None,
                     },
                     to: "d".to_string()
                 }
@@ -503,8 +617,12 @@ mod tests {
                         where_clause: None,
                         having_clause: None,
                         group_clause: None,
+                        pivot: None,
                         window: None,
-                        watermark: None
+                        watermark: None,
+                        emit: Emit::Final,
+                        order_by: vec![],
+                        limit: None,
                     },
                     to: "d".to_string()
                 }
@@ -512,6 +630,55 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_insert_into() {
+        let select = Select {
+            projection: vec![Expr::Column {
+                qualifier: None,
+                name: "a".to_string(),
+            }],
+            source: Source {
+                from: SourceFrom::Named("abc".to_string()),
+                alias: None,
+            },
+            where_clause: None,
+            having_clause: None,
+            group_clause: None,
+            pivot: None,
+            window: None,
+            watermark: None,
+            emit: Emit::Final,
+            order_by: vec![],
+            limit: None,
+        };
+
+        assert_eq!(
+            stmt_insert_into(r#"insert into d select a from abc"#),
+            Ok((
+                "",
+                StmtInsertInto {
+                    sink: "d".to_string(),
+                    name: None,
+                    select: select.clone(),
+                }
+            ))
+        );
+
+        assert_eq!(
+            stmt(r#"INSERT INTO d AS s1 select a from abc"#),
+            Ok((
+                "",
+                Stmt::InsertInto(Box::new(StmtInsertInto {
+                    sink: "d".to_string(),
+                    name: Some("s1".to_string()),
+                    select,
+                }))
+            ))
+        );
+
+        assert!(stmt(r#"insert into d"#).is_err());
+    }
+
     #[test]
     fn test_create_sink() {
         assert_eq!(
@@ -521,7 +688,7 @@ mod tests {
                 StmtCreateSink {
                     name: "a".to_string(),
                     uri: "http://test".to_string(),
-                    format: OutputFormat::Json,
+                    format: None,
                 }
             ))
         );
@@ -533,10 +700,46 @@ mod tests {
                 StmtCreateSink {
                     name: "a".to_string(),
                     uri: "http://test".to_string(),
-                    format: OutputFormat::Json,
+                    format: Some(OutputFormat::Json),
+                }
+            ))
+        );
+
+        assert_eq!(
+            stmt_create_sink(r#"create sink a with "file:///out.csv" format csv"#),
+            Ok((
+                "",
+                StmtCreateSink {
+                    name: "a".to_string(),
+                    uri: "file:///out.csv".to_string(),
+                    format: Some(OutputFormat::Csv {
+                        delimiter: b',',
+                        header: false,
+                    }),
+                }
+            ))
+        );
+
+        assert_eq!(
+            stmt_create_sink(
+                r#"create sink a with "console://stdout" format csv delimiter '|' header"#
+            ),
+            Ok((
+                "",
+                StmtCreateSink {
+                    name: "a".to_string(),
+                    uri: "console://stdout".to_string(),
+                    format: Some(OutputFormat::Csv {
+                        delimiter: b'|',
+                        header: true,
+                    }),
                 }
             ))
         );
+
+        assert!(
+            stmt(r#"create sink a with "console://stdout" format csv delimiter '||'"#).is_err()
+        );
     }
 
     #[test]
@@ -552,6 +755,32 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_analyze_source() {
+        assert_eq!(
+            stmt_analyze_source(r#"analyze a"#),
+            Ok((
+                "",
+                StmtAnalyzeSource {
+                    name: "a".to_string(),
+                }
+            ))
+        );
+    }
+
+    #[test]
+    fn test_describe() {
+        assert_eq!(
+            stmt_describe(r#"describe a"#),
+            Ok((
+                "",
+                StmtDescribe {
+                    name: "a".to_string(),
+                }
+            ))
+        );
+    }
+
     #[test]
     fn test_delete_stream() {
         assert_eq!(
@@ -577,4 +806,129 @@ mod tests {
             ))
         );
     }
+
+    #[test]
+    fn test_show() {
+        assert_eq!(
+            stmt_show_stream(r#"show streams"#),
+            Ok((
+                "",
+                StmtShow {
+                    show_type: ShowType::Streams,
+                    like: None,
+                    order_by: ShowOrderBy::Name,
+                    limit: None,
+                }
+            ))
+        );
+
+        assert_eq!(
+            stmt_show_stream(r#"show sources like 'a%'"#),
+            Ok((
+                "",
+                StmtShow {
+                    show_type: ShowType::Sources,
+                    like: Some("a%".to_string()),
+                    order_by: ShowOrderBy::Name,
+                    limit: None,
+                }
+            ))
+        );
+
+        assert_eq!(
+            stmt_show_stream(r#"show sinks limit 10"#),
+            Ok((
+                "",
+                StmtShow {
+                    show_type: ShowType::Sinks,
+                    like: None,
+                    order_by: ShowOrderBy::Name,
+                    limit: Some(10),
+                }
+            ))
+        );
+
+        assert_eq!(
+            stmt_show_stream(r#"show streams like 'a_%' limit 2"#),
+            Ok((
+                "",
+                StmtShow {
+                    show_type: ShowType::Streams,
+                    like: Some("a_%".to_string()),
+                    order_by: ShowOrderBy::Name,
+                    limit: Some(2),
+                }
+            ))
+        );
+
+        assert_eq!(
+            stmt_show_stream(r#"show functions like 'f.%'"#),
+            Ok((
+                "",
+                StmtShow {
+                    show_type: ShowType::Functions,
+                    like: Some("f.%".to_string()),
+                    order_by: ShowOrderBy::Name,
+                    limit: None,
+                }
+            ))
+        );
+
+        assert_eq!(
+            stmt_show_stream(r#"show streams like 'a%' order by status limit 2"#),
+            Ok((
+                "",
+                StmtShow {
+                    show_type: ShowType::Streams,
+                    like: Some("a%".to_string()),
+                    order_by: ShowOrderBy::Status,
+                    limit: Some(2),
+                }
+            ))
+        );
+
+        assert!(stmt(r#"show streams limit -1"#).is_err());
+        assert!(stmt(r#"show streams order by uri"#).is_err());
+    }
+
+    #[test]
+    fn test_stmts() {
+        let (_, res) = stmts("delete sink a; start stream b ;stop stream c").unwrap();
+        assert_eq!(
+            res,
+            vec![
+                Stmt::DeleteSink(Box::new(StmtDeleteSink {
+                    name: "a".to_string()
+                })),
+                Stmt::StartStream(Box::new(StmtStartStream {
+                    name: "b".to_string(),
+                    restart: false,
+                })),
+                Stmt::StopStream(Box::new(StmtStopStream {
+                    name: "c".to_string()
+                })),
+            ]
+        );
+
+        let (_, res) = stmts("delete sink a; ").unwrap();
+        assert_eq!(res.len(), 1);
+
+        assert!(stmts("delete sink a;; delete sink b").is_err());
+        assert!(stmts(";").is_err());
+    }
+
+    #[test]
+    fn test_validate() {
+        let (_, res) = stmt("validate delete sink a").unwrap();
+        assert_eq!(
+            res,
+            Stmt::Validate(Box::new(Stmt::DeleteSink(Box::new(StmtDeleteSink {
+                name: "a".to_string()
+            }))))
+        );
+
+        let (_, res) = stmts("VALIDATE delete sink a; delete sink b").unwrap();
+        assert!(matches!(&res[0], Stmt::Validate(_)));
+        assert!(matches!(&res[1], Stmt::DeleteSink(_)));
+    }
 }
@@ -4,9 +4,10 @@ use anyhow::Result;
 use derive_more::Display;
 use rocksdb::{DBCompressionType, Options, DB};
 use serde::{Deserialize, Serialize};
-use yql_core::dataset::SchemaRef;
+use yql_core::dataset::{DataFormat, SchemaRef};
 use yql_core::expr::Expr;
 use yql_core::sql::ast::Select;
+use yql_core::ColumnStatistics;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SourceDefinition {
@@ -14,12 +15,24 @@ pub struct SourceDefinition {
     pub schema: SchemaRef,
     pub uri: String,
     pub time_expr: Option<Expr>,
+    /// The statistics of the columns collected by the last `ANALYZE`.
+    ///
+    /// They are stored under their own key, so that the definitions saved before they were added
+    /// can still be read.
+    #[serde(skip)]
+    pub statistics: Option<Vec<ColumnStatistics>>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SinkDefinition {
     pub name: String,
     pub uri: String,
+    /// The format of the rows, it takes precedence over the format in the options of the uri.
+    ///
+    /// It is stored under its own key, so that the definitions saved before it was added can
+    /// still be read.
+    #[serde(skip)]
+    pub format: Option<DataFormat>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -36,7 +49,7 @@ pub enum Definition {
     Sink(Box<SinkDefinition>),
 }
 
-#[derive(Debug, Serialize, Deserialize, Display)]
+#[derive(Debug, Serialize, Deserialize, Display, Clone, Eq, PartialEq, Ord, PartialOrd)]
 pub enum StreamState {
     #[display(fmt = "created")]
     Created,
@@ -88,7 +101,19 @@ impl Storage {
             definition.name()
         );
         self.db.put(key, bincode::serialize(&definition)?)?;
-        Ok(())
+        self.save_extra(&definition)
+    }
+
+    /// Replaces the definition with the same name.
+    pub fn update_definition(&self, definition: Definition) -> Result<()> {
+        let key = format!("definition/{}", definition.name());
+        anyhow::ensure!(
+            self.db.get_pinned(&key)?.is_some(),
+            "definition '{}' not exists",
+            definition.name()
+        );
+        self.db.put(key, bincode::serialize(&definition)?)?;
+        self.save_extra(&definition)
     }
 
     pub fn definition_list(&self) -> Result<Vec<Definition>> {
@@ -96,7 +121,9 @@ impl Storage {
 
         for (key, value) in self.db.prefix_iterator("definition/") {
             if key.starts_with(b"definition/") {
-                definitions.push(bincode::deserialize(&value)?);
+                let mut definition = bincode::deserialize(&value)?;
+                self.load_extra(&mut definition)?;
+                definitions.push(definition);
             }
         }
 
@@ -106,17 +133,65 @@ impl Storage {
     pub fn delete_definition(&self, name: &str) -> Result<()> {
         let key = format!("definition/{}", name);
         self.db.delete(key)?;
+        self.db.delete(format!("source_statistics/{}", name))?;
+        self.db.delete(format!("sink_format/{}", name))?;
         Ok(())
     }
 
     pub fn get_definition(&self, name: &str) -> Result<Option<Definition>> {
         let key = format!("definition/{}", name);
         match self.db.get_pinned(key)? {
-            Some(data) => Ok(Some(bincode::deserialize(&data)?)),
+            Some(data) => {
+                let mut definition = bincode::deserialize(&data)?;
+                self.load_extra(&mut definition)?;
+                Ok(Some(definition))
+            }
             None => Ok(None),
         }
     }
 
+    /// Saves the fields of the definition that are stored under their own keys.
+    fn save_extra(&self, definition: &Definition) -> Result<()> {
+        match definition {
+            Definition::Source(source) => {
+                let key = format!("source_statistics/{}", source.name);
+                match &source.statistics {
+                    Some(statistics) => self.db.put(key, bincode::serialize(statistics)?)?,
+                    None => self.db.delete(key)?,
+                }
+            }
+            Definition::Sink(sink) => {
+                let key = format!("sink_format/{}", sink.name);
+                match &sink.format {
+                    Some(format) => self.db.put(key, bincode::serialize(format)?)?,
+                    None => self.db.delete(key)?,
+                }
+            }
+            Definition::Stream(_) => {}
+        }
+        Ok(())
+    }
+
+    /// Loads the fields of the definition that are stored under their own keys.
+    fn load_extra(&self, definition: &mut Definition) -> Result<()> {
+        match definition {
+            Definition::Source(source) => {
+                let key = format!("source_statistics/{}", source.name);
+                if let Some(data) = self.db.get_pinned(key)? {
+                    source.statistics = Some(bincode::deserialize(&data)?);
+                }
+            }
+            Definition::Sink(sink) => {
+                let key = format!("sink_format/{}", sink.name);
+                if let Some(data) = self.db.get_pinned(key)? {
+                    sink.format = Some(bincode::deserialize(&data)?);
+                }
+            }
+            Definition::Stream(_) => {}
+        }
+        Ok(())
+    }
+
     pub fn definition_exists(&self, name: &str) -> Result<bool> {
         let key = format!("definition/{}", name);
         Ok(self.db.get_pinned(key)?.is_some())
@@ -158,3 +233,122 @@ impl Storage {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use yql_core::array::{DataType, Scalar};
+    use yql_core::dataset::{Field, Schema};
+
+    use super::*;
+
+    #[test]
+    fn test_source_statistics() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = Storage::open(dir.path()).unwrap();
+        let schema = Arc::new(Schema::try_new(vec![Field::new("a", DataType::Int64)]).unwrap());
+
+        // a source saved before the statistics were added to the definition
+        #[derive(Serialize)]
+        struct OldSourceDefinition {
+            name: String,
+            schema: SchemaRef,
+            uri: String,
+            time_expr: Option<Expr>,
+        }
+        #[derive(Serialize)]
+        enum OldDefinition {
+            Source(Box<OldSourceDefinition>),
+        }
+        let old_definition = OldDefinition::Source(Box::new(OldSourceDefinition {
+            name: "s".to_string(),
+            schema: schema.clone(),
+            uri: "file:///tmp/a.csv".to_string(),
+            time_expr: None,
+        }));
+        storage
+            .db
+            .put("definition/s", bincode::serialize(&old_definition).unwrap())
+            .unwrap();
+        let mut source = match storage.get_definition("s").unwrap() {
+            Some(Definition::Source(source)) => source,
+            _ => panic!("expected a source"),
+        };
+        assert_eq!(source.uri, "file:///tmp/a.csv");
+        assert!(source.statistics.is_none());
+
+        source.statistics = Some(vec![ColumnStatistics {
+            name: "a".to_string(),
+            min: Scalar::Int64(1),
+            max: Scalar::Int64(5),
+            null_count: 0,
+            distinct_count: 3,
+        }]);
+        storage
+            .update_definition(Definition::Source(source))
+            .unwrap();
+        match storage.definition_list().unwrap().as_slice() {
+            [Definition::Source(source)] => {
+                assert_eq!(source.statistics.as_ref().unwrap()[0].max, Scalar::Int64(5))
+            }
+            _ => panic!("expected a source"),
+        }
+
+        storage.delete_definition("s").unwrap();
+        assert!(storage
+            .db
+            .get_pinned("source_statistics/s")
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_sink_format() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = Storage::open(dir.path()).unwrap();
+
+        // a sink saved before the format was added to the definition
+        #[derive(Serialize)]
+        struct OldSinkDefinition {
+            name: String,
+            uri: String,
+        }
+        #[derive(Serialize)]
+        enum OldDefinition {
+            #[allow(dead_code)]
+            Source,
+            #[allow(dead_code)]
+            Stream,
+            Sink(Box<OldSinkDefinition>),
+        }
+        let old_definition = OldDefinition::Sink(Box::new(OldSinkDefinition {
+            name: "k".to_string(),
+            uri: "console://stdout".to_string(),
+        }));
+        storage
+            .db
+            .put("definition/k", bincode::serialize(&old_definition).unwrap())
+            .unwrap();
+        match storage.get_definition("k").unwrap() {
+            Some(Definition::Sink(sink)) => {
+                assert_eq!(sink.uri, "console://stdout");
+                assert!(sink.format.is_none());
+            }
+            _ => panic!("expected a sink"),
+        }
+
+        storage.delete_definition("k").unwrap();
+        storage
+            .create_definition(Definition::Sink(Box::new(SinkDefinition {
+                name: "k".to_string(),
+                uri: "console://stdout".to_string(),
+                format: Some(DataFormat::Json),
+            })))
+            .unwrap();
+        match storage.get_definition("k").unwrap() {
+            Some(Definition::Sink(sink)) => assert_eq!(sink.format, Some(DataFormat::Json)),
+            _ => panic!("expected a sink"),
+        }
+    }
+}
@@ -17,11 +17,13 @@ async fn internal_start_task(
     mut sink: BoxSink,
 ) -> Result<()> {
     let (tx_shutdown, mut rx_shutdown) = oneshot::channel::<()>();
-    let mut inner = service.lock().await;
-    inner
-        .storage
-        .set_stream_state(&name, StreamState::Started)?;
-    inner.registry.add(&name, tx_shutdown);
+    {
+        let mut inner = service.lock().await;
+        inner
+            .storage
+            .set_stream_state(&name, StreamState::Started)?;
+        inner.registry.add(&name, tx_shutdown);
+    }
 
     loop {
         tokio::select! {